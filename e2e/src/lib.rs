@@ -50,6 +50,14 @@ macro_rules! define_spec_tests {
     };
 }
 
+define_tests! {
+    let folder = "src/local/multi-memory";
+    let runner = run::run_wasm_spec_test;
+
+    fn local_multi_memory_memories("memories");
+    fn local_multi_memory_bulk("bulk");
+}
+
 define_spec_tests! {
     let runner = run::run_wasm_spec_test;
 
@@ -67,6 +75,42 @@ define_spec_tests! {
     fn wasm_extended_const_data("proposals/extended-const/data");
     fn wasm_extended_const_elem("proposals/extended-const/elem");
     fn wasm_extended_const_global("proposals/extended-const/global");
+    fn wasm_multi_memory_address0("proposals/multi-memory/address0");
+    fn wasm_multi_memory_address1("proposals/multi-memory/address1");
+    fn wasm_multi_memory_align0("proposals/multi-memory/align0");
+    fn wasm_multi_memory_binary0("proposals/multi-memory/binary0");
+    fn wasm_multi_memory_data0("proposals/multi-memory/data0");
+    fn wasm_multi_memory_data1("proposals/multi-memory/data1");
+    fn wasm_multi_memory_data_drop0("proposals/multi-memory/data_drop0");
+    fn wasm_multi_memory_exports0("proposals/multi-memory/exports0");
+    fn wasm_multi_memory_float_exprs0("proposals/multi-memory/float_exprs0");
+    fn wasm_multi_memory_float_exprs1("proposals/multi-memory/float_exprs1");
+    fn wasm_multi_memory_float_memory0("proposals/multi-memory/float_memory0");
+    // imports functions and memories of a module registered by the script, rWasm links imports
+    // with the host import linker only
+    // fn wasm_multi_memory_imports0("proposals/multi-memory/imports0"); // UnknownImport
+    fn wasm_multi_memory_load("proposals/multi-memory/load");
+    fn wasm_multi_memory_load0("proposals/multi-memory/load0");
+    fn wasm_multi_memory_load1("proposals/multi-memory/load1");
+    fn wasm_multi_memory_load2("proposals/multi-memory/load2");
+    fn wasm_multi_memory_memory_multi("proposals/multi-memory/memory-multi");
+    fn wasm_multi_memory_memory_copy0("proposals/multi-memory/memory_copy0");
+    fn wasm_multi_memory_memory_copy1("proposals/multi-memory/memory_copy1");
+    fn wasm_multi_memory_memory_fill0("proposals/multi-memory/memory_fill0");
+    fn wasm_multi_memory_memory_grow("proposals/multi-memory/memory_grow");
+    fn wasm_multi_memory_memory_init0("proposals/multi-memory/memory_init0");
+    fn wasm_multi_memory_memory_size("proposals/multi-memory/memory_size");
+    fn wasm_multi_memory_memory_size0("proposals/multi-memory/memory_size0");
+    fn wasm_multi_memory_memory_size1("proposals/multi-memory/memory_size1");
+    fn wasm_multi_memory_memory_size2("proposals/multi-memory/memory_size2");
+    fn wasm_multi_memory_memory_size3("proposals/multi-memory/memory_size3");
+    fn wasm_multi_memory_memory_trap0("proposals/multi-memory/memory_trap0");
+    fn wasm_multi_memory_memory_trap1("proposals/multi-memory/memory_trap1");
+    fn wasm_multi_memory_start0("proposals/multi-memory/start0");
+    fn wasm_multi_memory_store("proposals/multi-memory/store");
+    fn wasm_multi_memory_store0("proposals/multi-memory/store0");
+    fn wasm_multi_memory_store1("proposals/multi-memory/store1");
+    fn wasm_multi_memory_traps0("proposals/multi-memory/traps0");
     fn wasm_return_call("proposals/tail-call/return_call");
     fn wasm_return_call_indirect("proposals/tail-call/return_call_indirect");
     fn wasm_comments("comments");
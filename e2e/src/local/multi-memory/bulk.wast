@@ -0,0 +1,47 @@
+;; Bulk memory operations between memories.
+
+(module
+  (memory $m0 1)
+  (memory $m1 1)
+  (data $d "\aa\bb\cc\dd")
+  (data (memory $m0) (i32.const 0) "\01\02\03\04\05\06\07\08")
+
+  (func (export "load0") (param i32) (result i32) (i32.load8_u $m0 (local.get 0)))
+  (func (export "load1") (param i32) (result i32) (i32.load8_u $m1 (local.get 0)))
+  (func (export "copy0to1") (param i32 i32 i32)
+    (memory.copy $m1 $m0 (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "copy1to1") (param i32 i32 i32)
+    (memory.copy $m1 $m1 (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "fill1") (param i32 i32 i32)
+    (memory.fill $m1 (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "init1") (param i32 i32 i32)
+    (memory.init $m1 $d (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "drop") (data.drop $d))
+)
+
+(invoke "copy0to1" (i32.const 100) (i32.const 2) (i32.const 4))
+(assert_return (invoke "load1" (i32.const 99)) (i32.const 0))
+(assert_return (invoke "load1" (i32.const 100)) (i32.const 3))
+(assert_return (invoke "load1" (i32.const 103)) (i32.const 6))
+(assert_return (invoke "load1" (i32.const 104)) (i32.const 0))
+(assert_return (invoke "load0" (i32.const 100)) (i32.const 0))
+
+(invoke "copy1to1" (i32.const 101) (i32.const 100) (i32.const 4))
+(assert_return (invoke "load1" (i32.const 101)) (i32.const 3))
+(assert_return (invoke "load1" (i32.const 104)) (i32.const 6))
+
+(invoke "fill1" (i32.const 200) (i32.const 0x77) (i32.const 3))
+(assert_return (invoke "load1" (i32.const 202)) (i32.const 0x77))
+(assert_return (invoke "load1" (i32.const 203)) (i32.const 0))
+(assert_return (invoke "load0" (i32.const 200)) (i32.const 0))
+
+(invoke "init1" (i32.const 300) (i32.const 1) (i32.const 3))
+(assert_return (invoke "load1" (i32.const 300)) (i32.const 0xbb))
+(assert_return (invoke "load1" (i32.const 302)) (i32.const 0xdd))
+(assert_return (invoke "load0" (i32.const 300)) (i32.const 0))
+
+(assert_trap (invoke "copy0to1" (i32.const 65535) (i32.const 0) (i32.const 2)) "out of bounds memory access")
+(assert_trap (invoke "fill1" (i32.const 65535) (i32.const 0) (i32.const 2)) "out of bounds memory access")
+(assert_trap (invoke "init1" (i32.const 0) (i32.const 3) (i32.const 2)) "out of bounds memory access")
+(invoke "drop")
+(assert_trap (invoke "init1" (i32.const 0) (i32.const 0) (i32.const 1)) "out of bounds memory access")
@@ -0,0 +1,53 @@
+;; Loads, stores, size and grow of non-default memories, every memory keeps its own data and limits.
+
+(module
+  (memory $m0 1)
+  (memory $m1 1 3)
+  (memory $m2 0)
+  (data (memory $m0) (i32.const 0) "\01\02\03\04")
+  (data (memory $m1) (i32.const 0) "\11\12\13\14")
+
+  (func (export "load0") (param i32) (result i32) (i32.load8_u $m0 (local.get 0)))
+  (func (export "load1") (param i32) (result i32) (i32.load8_u $m1 (local.get 0)))
+  (func (export "load2") (param i32) (result i32) (i32.load8_u $m2 (local.get 0)))
+  (func (export "load1_i64") (param i32) (result i64) (i64.load $m1 offset=8 (local.get 0)))
+  (func (export "store1") (param i32 i32) (i32.store $m1 (local.get 0) (local.get 1)))
+  (func (export "store1_i64") (param i32 i64) (i64.store $m1 offset=8 (local.get 0) (local.get 1)))
+  (func (export "store2") (param i32 i32) (i32.store8 $m2 (local.get 0) (local.get 1)))
+  (func (export "size0") (result i32) (memory.size $m0))
+  (func (export "size1") (result i32) (memory.size $m1))
+  (func (export "size2") (result i32) (memory.size $m2))
+  (func (export "grow1") (param i32) (result i32) (memory.grow $m1 (local.get 0)))
+  (func (export "grow2") (param i32) (result i32) (memory.grow $m2 (local.get 0)))
+)
+
+(assert_return (invoke "load0" (i32.const 0)) (i32.const 1))
+(assert_return (invoke "load1" (i32.const 0)) (i32.const 0x11))
+(assert_return (invoke "load1" (i32.const 3)) (i32.const 0x14))
+(assert_return (invoke "size0") (i32.const 1))
+(assert_return (invoke "size1") (i32.const 1))
+(assert_return (invoke "size2") (i32.const 0))
+(assert_trap (invoke "load2" (i32.const 0)) "out of bounds memory access")
+(assert_trap (invoke "store2" (i32.const 0) (i32.const 1)) "out of bounds memory access")
+(assert_trap (invoke "load1" (i32.const 65536)) "out of bounds memory access")
+
+(invoke "store1" (i32.const 0) (i32.const 0x44434241))
+(assert_return (invoke "load1" (i32.const 2)) (i32.const 0x43))
+(assert_return (invoke "load0" (i32.const 2)) (i32.const 3))
+(invoke "store1_i64" (i32.const 16) (i64.const 0x0102030405060708))
+(assert_return (invoke "load1_i64" (i32.const 16)) (i64.const 0x0102030405060708))
+(assert_return (invoke "load1" (i32.const 24)) (i32.const 0x08))
+
+(assert_return (invoke "grow1" (i32.const 1)) (i32.const 1))
+(assert_return (invoke "size1") (i32.const 2))
+(assert_return (invoke "grow1" (i32.const 2)) (i32.const -1))
+(assert_return (invoke "grow1" (i32.const 1)) (i32.const 2))
+(assert_return (invoke "grow1" (i32.const 0)) (i32.const 3))
+(assert_return (invoke "load1" (i32.const 196607)) (i32.const 0))
+(assert_trap (invoke "load1" (i32.const 196608)) "out of bounds memory access")
+(assert_return (invoke "size0") (i32.const 1))
+
+(assert_return (invoke "grow2" (i32.const 1)) (i32.const 0))
+(invoke "store2" (i32.const 65535) (i32.const 0x55))
+(assert_return (invoke "load2" (i32.const 65535)) (i32.const 0x55))
+(assert_return (invoke "load0" (i32.const 65535)) (i32.const 0))
@@ -0,0 +1,242 @@
+//! Lowering of the atomic instructions of the threads proposal for single-threaded execution.
+//!
+//! Without other threads an atomic access can't be observed half done, so loads, stores,
+//! read-modify-write and compare-exchange instructions become regular memory accesses after a
+//! check that traps with [`TrapCode::UnalignedAtomic`] if the effective address isn't aligned to
+//! the access width. Nothing can wake a waiter or change the memory while it waits, so
+//! `memory.atomic.wait*` never blocks and `memory.atomic.notify` never wakes anybody.
+use crate::{
+    compiler::lowering::Lowering, AddressOffset, InstructionSet as IS, MaxStackHeight, TrapCode,
+};
+use wasmparser::ValType;
+
+/// The type and the width of an atomic memory access, narrow accesses are zero-extended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Access {
+    I32,
+    I32U8,
+    I32U16,
+    I64,
+    I64U8,
+    I64U16,
+    I64U32,
+}
+
+impl Access {
+    pub(crate) fn value_type(self) -> ValType {
+        match self {
+            Access::I32 | Access::I32U8 | Access::I32U16 => ValType::I32,
+            Access::I64 | Access::I64U8 | Access::I64U16 | Access::I64U32 => ValType::I64,
+        }
+    }
+
+    /// The width of the access in bytes.
+    fn size(self) -> u32 {
+        match self {
+            Access::I32U8 | Access::I64U8 => 1,
+            Access::I32U16 | Access::I64U16 => 2,
+            Access::I32 | Access::I64U32 => 4,
+            Access::I64 => 8,
+        }
+    }
+
+    /// The number of stack slots taken by the value.
+    fn words(self) -> u32 {
+        match self.value_type() {
+            ValType::I64 => 2,
+            _ => 1,
+        }
+    }
+
+    /// Loads the value from the address on top of the stack.
+    fn load(self, l: &mut Lowering, offset: AddressOffset) {
+        match self {
+            Access::I32 => l.load(IS::op_i32_load, offset),
+            Access::I32U8 => l.load(IS::op_i32_load8_u, offset),
+            Access::I32U16 => l.load(IS::op_i32_load16_u, offset),
+            Access::I64 => l.memory(IS::op_i64_load, offset, IS::MSH_I64_LOAD, 1, 2),
+            Access::I64U8 => l.memory(IS::op_i64_load8_u, offset, IS::MSH_I64_LOAD8_U, 1, 2),
+            Access::I64U16 => l.memory(IS::op_i64_load16_u, offset, IS::MSH_I64_LOAD16_U, 1, 2),
+            Access::I64U32 => l.memory(IS::op_i64_load32_u, offset, IS::MSH_I64_LOAD32_U, 1, 2),
+        }
+    }
+
+    /// Stores the value on top of the stack to the address below it.
+    fn store(self, l: &mut Lowering, offset: AddressOffset) {
+        match self {
+            Access::I32 => l.store(IS::op_i32_store, offset),
+            Access::I32U8 => l.store(IS::op_i32_store8, offset),
+            Access::I32U16 => l.store(IS::op_i32_store16, offset),
+            Access::I64 => l.memory(IS::op_i64_store, offset, IS::MSH_I64_STORE, 3, 0),
+            Access::I64U8 => l.memory(IS::op_i64_store8, offset, IS::MSH_I64_STORE8, 3, 0),
+            Access::I64U16 => l.memory(IS::op_i64_store16, offset, IS::MSH_I64_STORE16, 3, 0),
+            Access::I64U32 => l.memory(IS::op_i64_store32, offset, IS::MSH_I64_STORE32, 3, 0),
+        }
+    }
+}
+
+/// The operation of an atomic read-modify-write instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RmwOp {
+    Add,
+    Sub,
+    And,
+    Or,
+    Xor,
+    Xchg,
+}
+
+/// Traps with [`TrapCode::UnalignedAtomic`] if the effective address of the access isn't aligned
+/// to its width, the address is the first operand.
+fn check_alignment(l: &mut Lowering, offset: AddressOffset, size: u32) {
+    if size == 1 {
+        return;
+    }
+    l.get(0);
+    // the width divides 2^32, so only the remainder of the offset matters
+    let remainder = offset % size;
+    if remainder != 0 {
+        l.i32_const(remainder as i32);
+        l.binary(IS::op_i32_add);
+    }
+    l.i32_const(size as i32 - 1);
+    l.binary(IS::op_i32_and);
+    l.trap_if(TrapCode::UnalignedAtomic);
+}
+
+pub(crate) fn atomic_load(code: &mut IS, offset: AddressOffset, access: Access) -> MaxStackHeight {
+    let mut l = Lowering::new(code, 1);
+    check_alignment(&mut l, offset, access.size());
+    access.load(&mut l, offset);
+    l.finish(access.words())
+}
+
+pub(crate) fn atomic_store(code: &mut IS, offset: AddressOffset, access: Access) -> MaxStackHeight {
+    let mut l = Lowering::new(code, 1 + access.words());
+    check_alignment(&mut l, offset, access.size());
+    access.store(&mut l, offset);
+    l.finish(0)
+}
+
+/// Stores the result of `op` applied to the loaded and the given values, the loaded value is the
+/// result.
+pub(crate) fn atomic_rmw(
+    code: &mut IS,
+    offset: AddressOffset,
+    access: Access,
+    op: RmwOp,
+) -> MaxStackHeight {
+    let words = access.words();
+    let mut l = Lowering::new(code, 1 + words);
+    check_alignment(&mut l, offset, access.size());
+    let old = l.height;
+    l.get(0);
+    access.load(&mut l, offset);
+    l.get(0);
+    if op != RmwOp::Xchg {
+        for word in 0..words {
+            l.get(old + word);
+        }
+    }
+    for word in 0..words {
+        l.get(1 + word);
+    }
+    // the narrow results are truncated by the store
+    match (op, words) {
+        (RmwOp::Xchg, _) => {}
+        (RmwOp::Add, 1) => l.binary(IS::op_i32_add),
+        (RmwOp::Sub, 1) => l.binary(IS::op_i32_sub),
+        (RmwOp::And, 1) => l.binary(IS::op_i32_and),
+        (RmwOp::Or, 1) => l.binary(IS::op_i32_or),
+        (RmwOp::Xor, 1) => l.binary(IS::op_i32_xor),
+        (RmwOp::Add, _) => l.emit(IS::op_i64_add, IS::MSH_I64_ADD, 4, 2),
+        (RmwOp::Sub, _) => l.emit(IS::op_i64_sub, IS::MSH_I64_SUB, 4, 2),
+        (RmwOp::And, _) => l.emit(IS::op_i64_and, IS::MSH_I64_AND, 4, 2),
+        (RmwOp::Or, _) => l.emit(IS::op_i64_or, IS::MSH_I64_OR, 4, 2),
+        (RmwOp::Xor, _) => l.emit(IS::op_i64_xor, IS::MSH_I64_XOR, 4, 2),
+    }
+    access.store(&mut l, offset);
+    l.finish(words)
+}
+
+/// Stores the replacement if the loaded value equals the expected one wrapped to the access
+/// width, the loaded value is the result.
+pub(crate) fn atomic_cmpxchg(
+    code: &mut IS,
+    offset: AddressOffset,
+    access: Access,
+) -> MaxStackHeight {
+    let words = access.words();
+    let (expected, replacement) = (1, 1 + words);
+    let mut l = Lowering::new(code, 1 + 2 * words);
+    check_alignment(&mut l, offset, access.size());
+    let old = l.height;
+    l.get(0);
+    access.load(&mut l, offset);
+    l.get(old);
+    l.get(expected);
+    if access.size() < 4 {
+        l.i32_const((1 << (8 * access.size())) - 1);
+        l.binary(IS::op_i32_and);
+    }
+    l.binary(IS::op_i32_eq);
+    if access == Access::I64 {
+        l.get(old + 1);
+        l.get(expected + 1);
+        l.binary(IS::op_i32_eq);
+        l.binary(IS::op_i32_and);
+    }
+    // the old value is stored back if the values differ
+    let equal = l.height - 1;
+    l.get(0);
+    for word in 0..words {
+        l.get(replacement + word);
+        l.get(old + word);
+        l.get(equal);
+        l.select();
+    }
+    access.store(&mut l, offset);
+    l.drop(1);
+    l.finish(words)
+}
+
+/// Returns "not-equal" (1) if the loaded value differs from the expected one and "timed-out" (2)
+/// otherwise, a wait without a timeout would block forever and traps instead.
+pub(crate) fn atomic_wait(code: &mut IS, offset: AddressOffset, access: Access) -> MaxStackHeight {
+    let words = access.words();
+    let (expected, timeout_hi) = (1, 2 + words);
+    let mut l = Lowering::new(code, 3 + words);
+    check_alignment(&mut l, offset, access.size());
+    for word in 0..words {
+        l.get(0);
+        l.load(IS::op_i32_load, offset.saturating_add(4 * word));
+        l.get(expected + word);
+        l.binary(IS::op_i32_ne);
+        if word > 0 {
+            l.binary(IS::op_i32_or);
+        }
+    }
+    let not_equal = l.height - 1;
+    l.get(timeout_hi);
+    l.i32_const(0);
+    l.binary(IS::op_i32_lt_s);
+    l.get(not_equal);
+    l.unary(IS::op_i32_eqz);
+    l.binary(IS::op_i32_and);
+    l.trap_if(TrapCode::UnreachableCodeReached);
+    l.i32_const(2);
+    l.get(not_equal);
+    l.binary(IS::op_i32_sub);
+    l.finish(1)
+}
+
+/// Checks the address like an atomic load and returns the number of woken waiters (0).
+pub(crate) fn atomic_notify(code: &mut IS, offset: AddressOffset) -> MaxStackHeight {
+    let mut l = Lowering::new(code, 2);
+    check_alignment(&mut l, offset, 4);
+    l.get(0);
+    l.load(IS::op_i32_load, offset);
+    l.drop(1);
+    l.i32_const(0);
+    l.finish(1)
+}
@@ -1,15 +1,20 @@
 use crate::{
-    instruction_set_internal, BranchOffset, InstructionSet, LocalDepth, TrapCode, UntypedValue,
+    instruction_set_internal, BranchOffset, InstructionSet, LocalDepth, MaxStackHeight, TrapCode,
+    UntypedValue,
 };
 use rwasm_fuel_policy::{SyscallFuelParams, FUEL_MAX_LINEAR_X, FUEL_MAX_QUADRATIC_X};
 
+/// Emits the fuel charging for a syscall and returns the max stack height it requires.
 pub(crate) fn compile_block_params(
     isa: &mut InstructionSet,
     syscall_fuel_param: SyscallFuelParams,
-) {
+) -> MaxStackHeight {
     match syscall_fuel_param {
-        SyscallFuelParams::None => {}
-        SyscallFuelParams::Const(base) => isa.op_consume_fuel(base as u32),
+        SyscallFuelParams::None => 0,
+        SyscallFuelParams::Const(base) => {
+            isa.op_consume_fuel(base as u32);
+            0
+        }
         SyscallFuelParams::LinearFuel(fuel_params) => {
             isa.op_local_get(LocalDepth::from(fuel_params.param_index));
             isa.op_i32_const(UntypedValue::from(FUEL_MAX_LINEAR_X));
@@ -27,7 +32,8 @@ pub(crate) fn compile_block_params(
                 isa.op_i32_const(UntypedValue::from(fuel_params.base_fuel));
                 isa.op_i32_add();
             }
-            isa.op_consume_fuel_stack()
+            isa.op_consume_fuel_stack();
+            2
         }
         SyscallFuelParams::QuadraticFuel(fuel_params) => {
             instruction_set_internal! {
@@ -67,6 +73,7 @@ pub(crate) fn compile_block_params(
                 I32Mul
                 ConsumeFuelStack
             }
+            4
         }
     }
 }
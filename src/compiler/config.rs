@@ -1,5 +1,8 @@
-use crate::{ImportLinker, Opcode, N_DEFAULT_MAX_MEMORY_PAGES};
+use crate::{
+    DefaultFuelCostModel, FuelCostModel, ImportLinker, Opcode, N_DEFAULT_MAX_MEMORY_PAGES,
+};
 use alloc::{boxed::Box, sync::Arc};
+use bincode::{Decode, Encode};
 use wasmparser::WasmFeatures;
 
 #[derive(Debug, Clone)]
@@ -14,6 +17,40 @@ pub struct StateRouterConfig {
     pub opcode: Option<Opcode>,
 }
 
+#[derive(Default, Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+/// Optimizations applied to the compiled code section.
+///
+/// Optimizations never change the charged fuel, the `ConsumeFuel` instructions are kept as is.
+pub enum OptimizationLevel {
+    /// The code is emitted as translated, one Wasm operator at a time.
+    #[default]
+    Disabled,
+    /// Peephole rewrites and constant folding: redundant `LocalSet`+`LocalGet` pairs, `I32Const`+
+    /// `Drop` pairs, branches to the next instruction, dead code after unconditional branches and
+    /// constant `i32` arithmetic.
+    Peephole,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Default, Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd, Encode, Decode)]
+/// How the compiled code charges the fuel of the translated Wasm operators.
+///
+/// Both modes charge the same amount unless the execution leaves a straight-line segment of code
+/// in the middle, that is on a trap or on an exception thrown by a callee.
+pub enum FuelMeteringMode {
+    /// Every segment charges its whole cost with a `ConsumeFuel` at its start, so the execution
+    /// stops before the fuel limit is exceeded, but a trap, or an exception thrown by a callee,
+    /// in the middle of a segment is charged for the rest of the segment too.
+    #[default]
+    Eager,
+    /// Every segment charges the fuel of its operators with a `ConsumeFuel` right before it's
+    /// left by a branch, a call, a return or a throw, before a branch target and before an
+    /// operator that can trap, so only the completed operators are charged (the function
+    /// prologue is still charged on entry). The fuel limit is checked once the operators are
+    /// done, and the code has more `ConsumeFuel` instructions.
+    Lazy,
+}
+
 #[derive(Clone, Debug)]
 /// Controls how a Wasm module is lowered into rwasm bytecode.
 /// Options affect entry routing, import linking, fuel metering, and validation relaxations for tests.
@@ -38,8 +75,11 @@ pub struct CompilationConfig {
     /// We don't support imported global, but you can set a default value for these values instead.
     /// Thus is required by testing suite.
     pub default_imported_global_value: Option<i64>,
-    /// Enable fuel metering (always eager mode)
+    /// Enable fuel metering (see [`Self::fuel_metering_mode`])
     pub consume_fuel: bool,
+    /// Chooses whether the fuel of a straight-line segment of code is charged when the segment
+    /// is entered or when it's left.
+    pub fuel_metering_mode: FuelMeteringMode,
     /// Enable replacement with optimized code snippets
     pub code_snippets: bool,
     /// Enable extra dynamic fuel checks for bulk memory/table instructions.
@@ -63,6 +103,41 @@ pub struct CompilationConfig {
     pub allow_start_section: bool,
     /// The maximum number of memory pages that can be allocated by the module.
     pub max_allowed_memory_pages: u32,
+    /// Emit an export section with all exported functions, so they can be called by name.
+    ///
+    /// Note: modules with an export section are encoded using the V2 binary format.
+    pub emit_export_section: bool,
+    /// Emit a debug section that maps compiled code back to Wasm functions and operator offsets,
+    /// including function names from the `name` custom section.
+    ///
+    /// Note: modules with a debug section are encoded using the V5 binary format.
+    pub emit_debug_section: bool,
+    /// Emit a metadata section that records the compilation settings (fuel flags, code snippets,
+    /// memory limit, fpu usage and the import linker fingerprint).
+    ///
+    /// Note: modules with a metadata section are encoded using the V6 binary format.
+    pub emit_metadata_section: bool,
+    /// Optimizations applied to the code section after translation.
+    pub optimize: OptimizationLevel,
+    /// Remove functions that can't be reached from the entrypoint, the state router, the start
+    /// function, the element segments or the exports (only with the export section).
+    ///
+    /// Note: removed functions shift the offsets of all following functions.
+    pub eliminate_dead_functions: bool,
+    /// Replace the hottest instruction pairs with fused opcodes (e.g. `LocalGet`+`LocalGet` with
+    /// `LocalGetLocalGet`).
+    ///
+    /// Note: fused opcodes aren't supported by rWasm VMs released before they were added.
+    pub fused_opcodes: bool,
+    /// Accept the threads proposal and lower its atomic instructions to plain memory accesses
+    /// for single-threaded execution. Atomic accesses still trap with
+    /// [`crate::TrapCode::UnalignedAtomic`] on unaligned addresses, `memory.atomic.wait*` returns
+    /// "not-equal" (1) or "timed-out" (2) and traps if it would wait forever,
+    /// `memory.atomic.notify` wakes no waiters (0) and `atomic.fence` is a no-op.
+    pub lower_atomics: bool,
+    /// Prices the operators charged by the emitted `ConsumeFuel` instructions and the dynamic
+    /// fuel checks of bulk operations.
+    pub fuel_cost_model: Arc<dyn FuelCostModel>,
 }
 
 impl Default for CompilationConfig {
@@ -75,12 +150,21 @@ impl Default for CompilationConfig {
             builtins_consume_fuel: false,
             default_imported_global_value: None,
             consume_fuel: true,
+            fuel_metering_mode: FuelMeteringMode::Eager,
             consume_fuel_for_bulk_ops: true,
             consume_fuel_for_params_and_locals: true,
             code_snippets: true,
             allow_func_ref_function_types: false,
             allow_start_section: false,
             max_allowed_memory_pages: N_DEFAULT_MAX_MEMORY_PAGES,
+            emit_export_section: false,
+            emit_debug_section: false,
+            emit_metadata_section: false,
+            optimize: OptimizationLevel::Disabled,
+            eliminate_dead_functions: false,
+            fused_opcodes: false,
+            lower_atomics: false,
+            fuel_cost_model: Arc::new(DefaultFuelCostModel),
         }
     }
 }
@@ -93,10 +177,14 @@ impl CompilationConfig {
             saturating_float_to_int: true,
             sign_extension: true,
             multi_value: true,
+            multi_memory: true,
             bulk_memory: true,
             reference_types: true,
             tail_call: true,
+            exceptions: true,
             extended_const: true,
+            simd: true,
+            threads: self.lower_atomics,
             ..Default::default()
         }
     }
@@ -147,6 +235,11 @@ impl CompilationConfig {
         self
     }
 
+    pub fn with_fuel_metering_mode(mut self, fuel_metering_mode: FuelMeteringMode) -> Self {
+        self.fuel_metering_mode = fuel_metering_mode;
+        self
+    }
+
     pub fn with_consume_fuel_for_bulk_ops(mut self, v: bool) -> Self {
         self.consume_fuel_for_bulk_ops = self.consume_fuel && v;
         self
@@ -179,4 +272,44 @@ impl CompilationConfig {
         self.max_allowed_memory_pages = max_allowed_memory_pages;
         self
     }
+
+    pub fn with_emit_export_section(mut self, emit_export_section: bool) -> Self {
+        self.emit_export_section = emit_export_section;
+        self
+    }
+
+    pub fn with_emit_debug_section(mut self, emit_debug_section: bool) -> Self {
+        self.emit_debug_section = emit_debug_section;
+        self
+    }
+
+    pub fn with_emit_metadata_section(mut self, emit_metadata_section: bool) -> Self {
+        self.emit_metadata_section = emit_metadata_section;
+        self
+    }
+
+    pub fn with_optimize(mut self, optimize: OptimizationLevel) -> Self {
+        self.optimize = optimize;
+        self
+    }
+
+    pub fn with_eliminate_dead_functions(mut self, eliminate_dead_functions: bool) -> Self {
+        self.eliminate_dead_functions = eliminate_dead_functions;
+        self
+    }
+
+    pub fn with_fused_opcodes(mut self, fused_opcodes: bool) -> Self {
+        self.fused_opcodes = fused_opcodes;
+        self
+    }
+
+    pub fn with_lower_atomics(mut self, lower_atomics: bool) -> Self {
+        self.lower_atomics = lower_atomics;
+        self
+    }
+
+    pub fn with_fuel_cost_model(mut self, fuel_cost_model: Arc<dyn FuelCostModel>) -> Self {
+        self.fuel_cost_model = fuel_cost_model;
+        self
+    }
 }
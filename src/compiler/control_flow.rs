@@ -76,6 +76,18 @@ impl ControlFlowStack {
             })
     }
 
+    /// Returns the number of `try` control flow frames among the `depth` last frames.
+    ///
+    /// Each of them owns an entry on the handler stack of the VM.
+    pub fn count_try_frames(&self, depth: u32) -> u32 {
+        self.frames
+            .iter()
+            .rev()
+            .take(depth as usize)
+            .filter(|frame| matches!(frame.kind(), ControlFrameKind::Try))
+            .count() as u32
+    }
+
     pub fn nth_back_mut(&mut self, depth: u32) -> &mut ControlFrame {
         let len = self.len();
         self.frames
@@ -433,6 +445,146 @@ impl IfControlFrame {
     }
 }
 
+/// The part of a Wasm `try` control flow frame that is currently translated.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TryStage {
+    /// The protected body of the `try` block.
+    Body,
+    /// A `catch` clause, the thrown exception is active.
+    Catch,
+    /// The `catch_all` clause, the thrown exception is active.
+    CatchAll,
+    /// The `try` block ended with `delegate`, its landing pad forwards the exception.
+    Delegate,
+}
+
+/// A Wasm `try` control flow frame.
+#[derive(Debug, Copy, Clone)]
+pub struct TryControlFrame {
+    /// The type of the [`TryControlFrame`].
+    block_type: BlockType,
+    /// The value stack height upon entering the
+    /// [`TryControlFrame`].
+    stack_height: u32,
+    /// Label representing the end of the
+    /// [`TryControlFrame`].
+    end_label: LabelRef,
+    /// Label of the next handler code.
+    ///
+    /// # Note
+    ///
+    /// Inside the body this is the landing pad `TryBegin` jumps to, inside a `catch` clause
+    /// this is the tag test of the next clause (or the rethrow after the last one).
+    next_label: LabelRef,
+    /// The part of the [`TryControlFrame`] that is currently translated.
+    stage: TryStage,
+    /// Instruction to consume fuel upon entering the basic block if fuel metering is enabled.
+    ///
+    /// This is updated for every `catch` clause, the same way as for the `else` block of an
+    /// [`IfControlFrame`].
+    consume_fuel: Option<InstrLoc>,
+
+    len_branches: usize,
+}
+
+impl TryControlFrame {
+    /// Creates a new [`TryControlFrame`].
+    pub fn new(
+        block_type: BlockType,
+        end_label: LabelRef,
+        next_label: LabelRef,
+        stack_height: u32,
+        consume_fuel: Option<InstrLoc>,
+    ) -> Self {
+        assert_ne!(
+            end_label, next_label,
+            "end and handler labels must be different"
+        );
+        Self {
+            block_type,
+            stack_height,
+            end_label,
+            next_label,
+            stage: TryStage::Body,
+            consume_fuel,
+            len_branches: 0,
+        }
+    }
+
+    /// Returns the label for the branch destination of the
+    /// [`TryControlFrame`].
+    ///
+    /// # Note
+    ///
+    /// Branches to [`TryControlFrame`] jump to the end of the frame.
+    pub fn branch_destination(&self) -> LabelRef {
+        self.end_label
+    }
+
+    /// Returns the label to the end of the
+    /// [`TryControlFrame`].
+    pub fn end_label(&self) -> LabelRef {
+        self.end_label
+    }
+
+    /// Returns the label of the next handler code of the
+    /// [`TryControlFrame`].
+    pub fn next_label(&self) -> LabelRef {
+        self.next_label
+    }
+
+    /// Enters a new `catch` or `catch_all` clause with the label of the next handler code.
+    pub fn enter_stage(&mut self, stage: TryStage, next_label: LabelRef) {
+        debug_assert_ne!(stage, TryStage::Body, "can't re-enter the body of a try");
+        self.stage = stage;
+        self.next_label = next_label;
+    }
+
+    /// Returns the [`TryStage`] of the
+    /// [`TryControlFrame`].
+    pub fn stage(&self) -> TryStage {
+        self.stage
+    }
+
+    /// Returns the value stack height upon entering the
+    /// [`TryControlFrame`].
+    pub fn stack_height(&self) -> u32 {
+        self.stack_height
+    }
+
+    /// Returns the [`BlockType`] of the
+    /// [`TryControlFrame`].
+    pub fn block_type(&self) -> BlockType {
+        self.block_type
+    }
+
+    /// Returns a reference to the [`ConsumeFuel`] instruction of the
+    /// [`TryControlFrame`] if any.
+    ///
+    /// Returns `None` if fuel metering is disabled.
+    ///
+    /// [`ConsumeFuel`]: enum.Instruction.html#variant.ConsumeFuel
+    pub fn consume_fuel_instr(&self) -> Option<InstrLoc> {
+        self.consume_fuel
+    }
+
+    pub fn update_consume_fuel_instr(&mut self, instr: InstrLoc) {
+        assert!(
+            self.consume_fuel.is_some(),
+            "can only update the consumption fuel instruction if it existed before"
+        );
+        self.consume_fuel = Some(instr);
+    }
+
+    pub(crate) fn bump_branches(&mut self) {
+        self.len_branches += 1;
+    }
+
+    pub fn is_branched_to(&self) -> bool {
+        self.len_branches > 0
+    }
+}
+
 /// An unreachable control flow frame of any kind.
 #[derive(Debug, Copy, Clone)]
 pub struct UnreachableControlFrame {
@@ -451,6 +603,8 @@ pub enum ControlFrameKind {
     Loop,
     /// An `if` and `else` block control flow frame.
     If,
+    /// A `try` block with its `catch` clauses.
+    Try,
 }
 
 impl UnreachableControlFrame {
@@ -482,6 +636,8 @@ pub enum ControlFrame {
     Loop(LoopControlFrame),
     /// If and else control frame.
     If(IfControlFrame),
+    /// Try and catch control frame.
+    Try(TryControlFrame),
     /// An unreachable control frame.
     Unreachable(UnreachableControlFrame),
 }
@@ -504,6 +660,12 @@ impl From<IfControlFrame> for ControlFrame {
     }
 }
 
+impl From<TryControlFrame> for ControlFrame {
+    fn from(frame: TryControlFrame) -> Self {
+        Self::Try(frame)
+    }
+}
+
 impl From<UnreachableControlFrame> for ControlFrame {
     fn from(frame: UnreachableControlFrame) -> Self {
         Self::Unreachable(frame)
@@ -518,6 +680,7 @@ impl ControlFrame {
             ControlFrame::Block(_) => ControlFrameKind::Block,
             ControlFrame::Loop(_) => ControlFrameKind::Loop,
             ControlFrame::If(_) => ControlFrameKind::If,
+            ControlFrame::Try(_) => ControlFrameKind::Try,
             ControlFrame::Unreachable(frame) => frame.kind(),
         }
     }
@@ -529,6 +692,7 @@ impl ControlFrame {
             Self::Block(frame) => frame.branch_destination(),
             Self::Loop(frame) => frame.branch_destination(),
             Self::If(frame) => frame.branch_destination(),
+            Self::Try(frame) => frame.branch_destination(),
             Self::Unreachable(frame) => panic!(
                 "tried to get `branch_destination` for an unreachable control frame: {:?}",
                 frame
@@ -545,6 +709,7 @@ impl ControlFrame {
         match self {
             Self::Block(frame) => frame.end_label(),
             Self::If(frame) => frame.end_label(),
+            Self::Try(frame) => frame.end_label(),
             Self::Loop(frame) => {
                 panic!("tried to get `end_label` for a loop control frame: {frame:?}")
             }
@@ -560,6 +725,7 @@ impl ControlFrame {
             Self::Block(frame) => Some(frame.stack_height()),
             Self::Loop(frame) => Some(frame.stack_height()),
             Self::If(frame) => Some(frame.stack_height()),
+            Self::Try(frame) => Some(frame.stack_height()),
             Self::Unreachable(_frame) => None,
         }
     }
@@ -570,6 +736,7 @@ impl ControlFrame {
             Self::Block(frame) => frame.block_type(),
             Self::Loop(frame) => frame.block_type(),
             Self::If(frame) => frame.block_type(),
+            Self::Try(frame) => frame.block_type(),
             Self::Unreachable(frame) => frame.block_type(),
         }
     }
@@ -590,6 +757,7 @@ impl ControlFrame {
             ControlFrame::Block(frame) => frame.consume_fuel_instr(),
             ControlFrame::Loop(frame) => frame.consume_fuel_instr(),
             ControlFrame::If(frame) => frame.consume_fuel_instr(),
+            ControlFrame::Try(frame) => frame.consume_fuel_instr(),
             ControlFrame::Unreachable(_) => None,
         }
     }
@@ -599,6 +767,7 @@ impl ControlFrame {
             ControlFrame::Block(frame) => frame.bump_branches(),
             ControlFrame::Loop(frame) => frame.bump_branches(),
             ControlFrame::If(frame) => frame.bump_branches(),
+            ControlFrame::Try(frame) => frame.bump_branches(),
             Self::Unreachable(_) => {
                 // No branch bookkeeping is needed once control flow is unreachable.
             }
@@ -610,6 +779,7 @@ impl ControlFrame {
             Self::Block(frame) => frame.is_branched_to(),
             Self::Loop(frame) => frame.is_branched_to(),
             Self::If(frame) => frame.is_branched_to(),
+            Self::Try(frame) => frame.is_branched_to(),
             Self::Unreachable(frame) => false,
         }
     }
@@ -619,6 +789,7 @@ impl ControlFrame {
             Self::Block(frame) => frame.update_consume_fuel_instr(instr),
             Self::Loop(frame) => frame.update_consume_fuel_instr(instr),
             Self::If(frame) => frame.update_consume_fuel_instr(instr),
+            Self::Try(frame) => frame.update_consume_fuel_instr(instr),
             Self::Unreachable(_) => {
                 // Unreachable frames do not patch fuel instructions.
             }
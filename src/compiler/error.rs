@@ -1,3 +1,4 @@
+use alloc::boxed::Box;
 use core::fmt::Formatter;
 use wasmparser::BinaryReaderError;
 
@@ -13,19 +14,92 @@ pub enum CompilationError {
     NotSupportedFuncType,
     UnresolvedImportFunction,
     MalformedImportFunctionType,
-    NonDefaultMemoryIndex,
+    UnresolvedImportGlobal,
+    MalformedImportGlobalType,
+    UnresolvedImportMemory,
+    MalformedImportMemoryType,
+    UnresolvedImportTable,
+    MalformedImportTableType,
     ConstEvaluationFailed,
     NotSupportedLocalType,
     NotSupportedGlobalType,
     MaxReadonlyDataReached,
+    MaxMemoryPagesReached,
     MissingEntrypoint,
     MalformedFuncType,
     MemoryOutOfBounds,
     TableOutOfBounds,
     StartSectionsAreNotAllowed,
+    /// The hint section of an rWasm module doesn't contain the original Wasm binary.
+    MissingWasmHint,
+    /// Wasmtime charges [`crate::DefaultFuelCostModel`] costs only, so a custom
+    /// [`crate::FuelCostModel`] can't be used with it.
+    NotSupportedFuelCostModel,
+    /// Wasmtime failed to compile the Wasm binary, it stores the wasmtime error message.
+    WasmtimeCompilationFailed(Box<str>),
+    /// An error in the body of a Wasm function, see [`CompilationErrorContext`].
+    InFunction(Box<CompilationErrorContext>),
 }
 
-impl core::error::Error for CompilationError {}
+/// The location of a [`CompilationError`] inside a Wasm function.
+#[derive(Debug)]
+pub struct CompilationErrorContext {
+    /// The error without the context.
+    pub error: CompilationError,
+    /// The index of the function, imported functions included.
+    pub func_idx: u32,
+    /// The name of the function from the `name` custom section, if any.
+    pub func_name: Option<Box<str>>,
+    /// The byte offset of the operator inside the Wasm binary, if the error is caused by one.
+    pub offset: Option<usize>,
+    /// The name of the operator (e.g. `MemoryInit`), if the error is caused by one.
+    pub operator: Option<&'static str>,
+}
+
+impl CompilationError {
+    /// Returns the context of an error in the body of a Wasm function.
+    pub fn context(&self) -> Option<&CompilationErrorContext> {
+        match self {
+            CompilationError::InFunction(context) => Some(context),
+            _ => None,
+        }
+    }
+
+    /// Returns the error without its context.
+    pub fn without_context(&self) -> &CompilationError {
+        match self {
+            CompilationError::InFunction(context) => &context.error,
+            _ => self,
+        }
+    }
+
+    /// Wraps the error into the context of the function, an error that already has a context
+    /// is kept as is.
+    pub(crate) fn in_function(
+        self,
+        func_idx: u32,
+        offset: Option<usize>,
+        operator: Option<&'static str>,
+    ) -> Self {
+        match self {
+            CompilationError::InFunction(_) => self,
+            error => CompilationError::InFunction(Box::new(CompilationErrorContext {
+                error,
+                func_idx,
+                func_name: None,
+                offset,
+                operator,
+            })),
+        }
+    }
+}
+
+impl core::error::Error for CompilationError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        self.context()
+            .map(|context| &context.error as &(dyn core::error::Error + 'static))
+    }
+}
 
 impl From<BinaryReaderError> for CompilationError {
     fn from(err: BinaryReaderError) -> Self {
@@ -52,11 +126,23 @@ impl core::fmt::Display for CompilationError {
             CompilationError::MalformedImportFunctionType => {
                 write!(f, "malformed import function type")
             }
-            CompilationError::NonDefaultMemoryIndex => write!(f, "non default memory index"),
+            CompilationError::UnresolvedImportGlobal => write!(f, "unresolved import global"),
+            CompilationError::MalformedImportGlobalType => {
+                write!(f, "malformed import global type")
+            }
+            CompilationError::UnresolvedImportMemory => write!(f, "unresolved import memory"),
+            CompilationError::MalformedImportMemoryType => {
+                write!(f, "malformed import memory type")
+            }
+            CompilationError::UnresolvedImportTable => write!(f, "unresolved import table"),
+            CompilationError::MalformedImportTableType => {
+                write!(f, "malformed import table type")
+            }
             CompilationError::ConstEvaluationFailed => write!(f, "const evaluation failed"),
             CompilationError::NotSupportedLocalType => write!(f, "not supported local type"),
             CompilationError::NotSupportedGlobalType => write!(f, "not supported global type"),
             CompilationError::MaxReadonlyDataReached => write!(f, "memory segments overflow"),
+            CompilationError::MaxMemoryPagesReached => write!(f, "memory pages limit reached"),
             CompilationError::MissingEntrypoint => write!(f, "missing entrypoint"),
             CompilationError::MalformedFuncType => write!(f, "malformed func type"),
             CompilationError::MemoryOutOfBounds => write!(f, "out of bounds memory access"),
@@ -64,6 +150,32 @@ impl core::fmt::Display for CompilationError {
             CompilationError::StartSectionsAreNotAllowed => {
                 write!(f, "start sections are not allowed")
             }
+            CompilationError::MissingWasmHint => {
+                write!(f, "missing wasm binary in the hint section")
+            }
+            CompilationError::NotSupportedFuelCostModel => {
+                write!(f, "not supported fuel cost model")
+            }
+            CompilationError::WasmtimeCompilationFailed(message) => {
+                write!(f, "wasmtime compilation failed ({})", message)
+            }
+            CompilationError::InFunction(context) => write!(f, "{}", context),
+        }
+    }
+}
+
+impl core::fmt::Display for CompilationErrorContext {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{} (in function #{}", self.error, self.func_idx)?;
+        if let Some(func_name) = self.func_name.as_ref() {
+            write!(f, " `{}`", func_name)?;
+        }
+        if let Some(operator) = self.operator {
+            write!(f, ", {}", operator)?;
+        }
+        if let Some(offset) = self.offset {
+            write!(f, " at offset {:#x}", offset)?;
         }
+        write!(f, ")")
     }
 }
@@ -0,0 +1,126 @@
+use core::fmt::Debug;
+use rwasm_fuel_policy::{FuelCosts, MEMORY_BYTES_PER_FUEL_LOG2, TABLE_ELEMS_PER_FUEL_LOG2};
+use tiny_keccak::{Hasher, Keccak};
+
+/// A class of Wasm operators that are charged the same amount of fuel.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum FuelCostClass {
+    /// Control flow, locals, constants and `select`.
+    Base,
+    /// Numeric and vector operators, including the ones lowered to code snippets.
+    Arithmetic,
+    /// Globals, tables, `memory.size`, `memory.grow` and bulk operations (their size is charged
+    /// separately, see [`FuelCostModel::memory_bytes_per_fuel_log2`]).
+    Entity,
+    /// Memory loads.
+    Load,
+    /// Memory stores.
+    Store,
+}
+
+impl FuelCostClass {
+    /// All the classes.
+    pub const ALL: [FuelCostClass; 5] = [
+        FuelCostClass::Base,
+        FuelCostClass::Arithmetic,
+        FuelCostClass::Entity,
+        FuelCostClass::Load,
+        FuelCostClass::Store,
+    ];
+}
+
+/// Prices Wasm operators for the `ConsumeFuel` instructions emitted by the compiler.
+///
+/// Every method has a default that matches [`FuelCosts`], so a model only overrides the costs
+/// that differ. The fuel is charged at compile time, so a model must be deterministic, and
+/// modules compiled with different models look the same on the wire, the metadata section
+/// records [`Self::fingerprint`] to tell them apart.
+///
+/// Note: custom models aren't supported by wasmtime, it fails with
+/// [`crate::CompilationError::NotSupportedFuelCostModel`].
+pub trait FuelCostModel: Debug + Send + Sync {
+    /// Returns the fuel charged for an operator of the given class.
+    fn opcode_cost(&self, class: FuelCostClass) -> u32 {
+        match class {
+            FuelCostClass::Base | FuelCostClass::Arithmetic => FuelCosts::BASE,
+            FuelCostClass::Entity => FuelCosts::ENTITY,
+            FuelCostClass::Load => FuelCosts::LOAD,
+            FuelCostClass::Store => FuelCosts::STORE,
+        }
+    }
+
+    /// Returns the fuel charged for a direct, indirect or tail call.
+    fn call_cost(&self) -> u32 {
+        FuelCosts::CALL
+    }
+
+    /// Returns the fuel charged on a function entry for its params or locals.
+    fn locals_cost(&self, locals: u32) -> u32 {
+        FuelCosts::fuel_for_locals(locals)
+    }
+
+    /// Returns the fuel charged for a `br_table` target that drops `drop` values and keeps
+    /// `keep` values on the stack.
+    fn drop_keep_cost(&self, drop: u16, keep: u16) -> u32 {
+        FuelCosts::fuel_for_drop_keep(drop, keep)
+    }
+
+    /// Bulk memory operations charge one fuel per `2^log2` bytes (rounded up), the value must be
+    /// less than 32. The verifier takes it from the metadata section, so modules that override it
+    /// pass [`crate::FuelPolicy::Required`] only with
+    /// [`crate::CompilationConfig::emit_metadata_section`].
+    fn memory_bytes_per_fuel_log2(&self) -> u32 {
+        MEMORY_BYTES_PER_FUEL_LOG2
+    }
+
+    /// Bulk table operations charge one fuel per `2^log2` elements (rounded up), the value must
+    /// be less than 32. Like [`Self::memory_bytes_per_fuel_log2`], it's taken from the metadata
+    /// section by the verifier.
+    fn table_elems_per_fuel_log2(&self) -> u32 {
+        TABLE_ELEMS_PER_FUEL_LOG2
+    }
+
+    /// Returns a keccak256 hash of the costs charged by the model.
+    ///
+    /// It covers the cost of every class, the call cost, the bulk shifts and the locals and
+    /// `br_table` costs sampled at zero and at every power of two and its neighbours up to
+    /// `u16::MAX` (a Wasm function has fewer locals). A model whose costs differ only between the
+    /// samples must override it.
+    fn fingerprint(&self) -> [u8; 32] {
+        let mut hasher = Keccak::v256();
+        let mut update = |value: u32| hasher.update(&value.to_le_bytes());
+        for class in FuelCostClass::ALL {
+            update(self.opcode_cost(class));
+        }
+        update(self.call_cost());
+        update(self.memory_bytes_per_fuel_log2());
+        update(self.table_elems_per_fuel_log2());
+        for locals in fingerprint_samples() {
+            update(self.locals_cost(locals.into()));
+        }
+        for drop in [0, 1, u16::MAX] {
+            for keep in fingerprint_samples() {
+                update(self.drop_keep_cost(drop, keep));
+            }
+        }
+        let mut hash = [0u8; 32];
+        hasher.finalize(&mut hash);
+        hash
+    }
+}
+
+/// Returns zero and every power of two with its neighbours up to `u16::MAX`.
+fn fingerprint_samples() -> impl Iterator<Item = u16> {
+    (0..16)
+        .flat_map(|log2| {
+            let power = 1u16 << log2;
+            [power - 1, power, power + 1]
+        })
+        .chain([u16::MAX])
+}
+
+/// The fuel cost model used by default, it charges [`FuelCosts`].
+#[derive(Debug, Default, Copy, Clone)]
+pub struct DefaultFuelCostModel;
+
+impl FuelCostModel for DefaultFuelCostModel {}
@@ -1,8 +1,8 @@
 use crate::{
     compiler::translator::{FuncTranslatorAllocations, InstructionTranslator, ReusableAllocations},
-    CompilationError, FuncIdx,
+    CompilationError, FuelCostClass, FuelCostModel, FuelMeteringMode, FuncIdx,
 };
-use rwasm_fuel_policy::FuelCosts;
+use alloc::sync::Arc;
 use wasmparser::{
     BinaryReaderError, FuncValidator, FunctionBody, ValType, ValidatorResources, VisitOperator,
 };
@@ -13,6 +13,9 @@ pub struct FuncBuilder<'a> {
     pub(crate) func_idx: FuncIdx,
     pub(crate) translator: InstructionTranslator,
     pub(crate) pos: usize,
+    /// The name of the Wasm operator being translated.
+    pub(crate) operator: Option<&'static str>,
+    pub(crate) emit_debug_section: bool,
 }
 
 impl<'a> FuncBuilder<'a> {
@@ -27,6 +30,9 @@ impl<'a> FuncBuilder<'a> {
         consume_fuel_for_bulk_ops: bool,
         consume_fuel_for_params_and_locals: bool,
         max_allowed_memory_pages: u32,
+        fuel_cost_model: Arc<dyn FuelCostModel>,
+        fuel_metering_mode: FuelMeteringMode,
+        emit_debug_section: bool,
     ) -> Self {
         Self {
             func_body,
@@ -39,24 +45,47 @@ impl<'a> FuncBuilder<'a> {
                 consume_fuel_for_bulk_ops,
                 consume_fuel_for_params_and_locals,
                 max_allowed_memory_pages,
+                fuel_cost_model,
+                fuel_metering_mode,
             ),
             pos: 0,
+            operator: None,
+            emit_debug_section,
         }
     }
 
     pub fn translate(mut self) -> Result<ReusableAllocations, CompilationError> {
+        if let Err(err) = self.translate_function() {
+            let offset = self.operator.map(|_| self.pos);
+            return Err(err.in_function(self.func_idx, offset, self.operator));
+        }
+        Ok(ReusableAllocations {
+            translation: self.translator.alloc,
+            validation: self.validator.into_allocations(),
+        })
+    }
+
+    fn translate_function(&mut self) -> Result<(), CompilationError> {
         self.translator.prepare(self.func_idx)?;
-        self.translator.bump_fuel_consumption(|| FuelCosts::BASE)?;
+        if self.emit_debug_section {
+            // the function prologue is mapped to the beginning of the function body
+            self.translator
+                .alloc
+                .begin_source_location(self.func_idx, self.func_body.range().start);
+        }
+        self.translator
+            .bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Base))?;
         // emit special opcodes before the beginning of the function
         self.translate_stack_alloc();
         self.translate_locals()?;
+        self.translator.begin_lazy_fuel_metering();
         let offset = self.translate_operators()?;
+        if self.emit_debug_section {
+            self.translator.alloc.end_source_location();
+        }
         self.validator.finish(offset)?;
         self.translator.finish()?;
-        Ok(ReusableAllocations {
-            translation: self.translator.alloc,
-            validation: self.validator.into_allocations(),
-        })
+        Ok(())
     }
 
     fn translate_locals(&mut self) -> Result<(), CompilationError> {
@@ -67,9 +96,12 @@ impl<'a> FuncBuilder<'a> {
                 let (amount, _) = locals_reader.read()?;
                 total_body_locals = total_body_locals.saturating_add(amount);
             }
-            let cost = FuelCosts::fuel_for_locals(total_body_locals);
+            let cost = self
+                .translator
+                .fuel_cost_model
+                .locals_cost(total_body_locals);
             if cost > 0 {
-                self.translator.bump_fuel_consumption(|| cost)?;
+                self.translator.bump_fuel_consumption(|_| cost)?;
             }
         }
         // translate locals
@@ -82,9 +114,10 @@ impl<'a> FuncBuilder<'a> {
             self.validator.define_locals(offset, amount, value_type)?;
             match value_type {
                 ValType::I32 | ValType::I64 => {}
-                // TODO(dmitry123): "make sure this type is not allowed with floats disabled"
+                // without the `fpu` feature floats are lowered to the software float snippets
                 ValType::F32 | ValType::F64 => {}
-                ValType::V128 => return Err(CompilationError::NotSupportedLocalType),
+                // `v128` is lowered to four 32-bit words
+                ValType::V128 => {}
                 ValType::FuncRef | ValType::ExternRef => {}
                 #[allow(unreachable_patterns)]
                 _ => return Err(CompilationError::NotSupportedLocalType),
@@ -92,11 +125,11 @@ impl<'a> FuncBuilder<'a> {
 
             let mut total_locals_required = 0u32;
             for _ in 0..amount as usize {
-                // for i64 type, we need to push 2 values on the stack
-                if value_type == ValType::I64 || value_type == ValType::F64 {
-                    total_locals_required += 2;
-                } else {
-                    total_locals_required += 1;
+                // for i64 type, we need to push 2 values on the stack (4 values for v128)
+                match value_type {
+                    ValType::I64 | ValType::F64 => total_locals_required += 2,
+                    ValType::V128 => total_locals_required += 4,
+                    _ => total_locals_required += 1,
                 }
                 self.translator.alloc.stack_types.push(value_type);
             }
@@ -105,10 +138,7 @@ impl<'a> FuncBuilder<'a> {
                 .instruction_set
                 .op_bulk_const(total_locals_required);
 
-            self.translator.stack_height.push_n(amount);
-            if value_type == ValType::I64 || value_type == ValType::F64 {
-                self.translator.stack_height.push_n(amount);
-            }
+            self.translator.stack_height.push_n(total_locals_required);
         }
 
         Ok(())
@@ -135,8 +165,15 @@ impl<'a> FuncBuilder<'a> {
             //     println!("{:?}", operator);
             // }
             self.pos = reader.original_position();
+            if self.emit_debug_section {
+                self.translator
+                    .alloc
+                    .begin_source_location(self.func_idx, self.pos);
+            }
+            self.operator = None;
             reader.visit_operator(self)??;
         }
+        self.operator = None;
         reader.ensure_end()?;
         Ok(reader.original_position())
     }
@@ -164,6 +201,7 @@ macro_rules! impl_visit_operator {
         // the other impls make use of.
         fn $visit(&mut self, $arg: $argty) -> Self::Output {
             let offset = self.pos;
+            self.operator = Some("BrTable");
             let arg_cloned = $arg.clone();
             self.validate_then_translate(
                 |validator| validator.visitor(offset).$visit(arg_cloned),
@@ -190,9 +228,19 @@ macro_rules! impl_visit_operator {
     ( @tail_call $($rest:tt)* ) => {
         impl_visit_operator!(@@supported $($rest)*);
     };
+    ( @exceptions $($rest:tt)* ) => {
+        impl_visit_operator!(@@supported $($rest)*);
+    };
+    ( @simd $($rest:tt)* ) => {
+        impl_visit_operator!(@@supported $($rest)*);
+    };
+    ( @threads $($rest:tt)* ) => {
+        impl_visit_operator!(@@supported $($rest)*);
+    };
     ( @@supported $op:ident $({ $($arg:ident: $argty:ty),* })? => $visit:ident $($rest:tt)* ) => {
         fn $visit(&mut self $($(,$arg: $argty)*)?) -> Self::Output {
             let offset = self.pos;
+            self.operator = Some(stringify!($op));
             self.validate_then_translate(
                 |v| v.visitor(offset).$visit($($($arg),*)?),
                 |t| t.$visit($($($arg),*)?),
@@ -204,6 +252,7 @@ macro_rules! impl_visit_operator {
         // Wildcard match arm for all the other (yet) unsupported Wasm proposals.
         fn $visit(&mut self $($(, $arg: $argty)*)?) -> Self::Output {
             let offset = self.pos;
+            self.operator = Some(stringify!($op));
             self.validator.visitor(offset).$visit($($($arg),*)?).map_err(::core::convert::Into::into)
         }
         impl_visit_operator!($($rest)*);
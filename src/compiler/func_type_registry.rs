@@ -38,9 +38,8 @@ impl FuncTypeRegistry {
                     adjusted_params.push(ValType::I32);
                     adjusted_params.push(ValType::I32);
                 }
-                ValType::V128 => {
-                    return Err(CompilationError::NotSupportedFuncType);
-                }
+                // `v128` is lowered to four 32-bit words, the lowest word is pushed first
+                ValType::V128 => adjusted_params.extend([ValType::I32; 4]),
                 _ => adjusted_params.push(*x),
             }
         }
@@ -50,9 +49,7 @@ impl FuncTypeRegistry {
                     adjusted_result.push(ValType::I32);
                     adjusted_result.push(ValType::I32);
                 }
-                ValType::V128 => {
-                    return Err(CompilationError::NotSupportedFuncType);
-                }
+                ValType::V128 => adjusted_result.extend([ValType::I32; 4]),
                 _ => adjusted_result.push(*x),
             }
         }
@@ -84,6 +81,7 @@ impl FuncTypeRegistry {
             BlockType::Type(ty) => {
                 return match ty {
                     ValType::I64 | ValType::F64 => 2,
+                    ValType::V128 => 4,
                     _ => 1,
                 }
             }
@@ -0,0 +1,444 @@
+use crate::{
+    compiler::segment_builder::memory_pages_limit_reached, CompilationConfig, CompilationError,
+    GlobalValueProvider, ImportLinkerEntity, ImportLinkerGlobal, ImportName, N_MAX_TABLE_SIZE,
+};
+use alloc::vec::Vec;
+use core::fmt::Formatter;
+use wasmparser::{
+    FuncType, GlobalType, ImportSectionReader, MemoryType, Parser, Payload, TableType, Type,
+    TypeRef, TypeSectionReader, ValType,
+};
+
+/// An import of a Wasm module that fails to resolve with an [`ImportLinker`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportIssue {
+    /// The name of the import.
+    pub import_name: ImportName,
+    /// The offset of the import entry inside the Wasm binary.
+    pub offset: usize,
+    /// What is wrong with the import.
+    pub kind: ImportIssueKind,
+}
+
+/// The reason why an import fails to resolve, every kind matches the [`CompilationError`] returned
+/// by the compilation (see [`ImportIssueKind::compilation_error`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportIssueKind {
+    /// The linker doesn't declare the function.
+    UnresolvedFunction,
+    /// The linker declares the function with another type.
+    FuncTypeMismatch {
+        expected: FuncType,
+        actual: FuncType,
+    },
+    /// The function type has `funcref` or `externref` params or results, see
+    /// [`crate::CompilationConfig::allow_func_ref_function_types`].
+    RefTypeInFuncType(FuncType),
+    /// The linker doesn't declare the global, see
+    /// [`crate::CompilationConfig::default_imported_global_value`].
+    UnresolvedGlobal,
+    /// The linker declares the global with another type or mutability.
+    GlobalTypeMismatch {
+        expected: GlobalType,
+        actual: GlobalType,
+    },
+    /// Only numeric globals can be imported.
+    UnsupportedGlobalType(GlobalType),
+    /// The linker doesn't declare the memory.
+    UnresolvedMemory,
+    /// The linker declares the memory with another type or with limits the import doesn't accept.
+    MemoryTypeMismatch {
+        expected: MemoryType,
+        actual: MemoryType,
+    },
+    /// The initial pages of the memory exceed
+    /// [`crate::CompilationConfig::max_allowed_memory_pages`].
+    MemoryPagesLimitReached(MemoryType),
+    /// The linker doesn't declare the table.
+    UnresolvedTable,
+    /// The linker declares the table with another element type or with limits the import doesn't
+    /// accept.
+    TableTypeMismatch {
+        expected: TableType,
+        actual: TableType,
+    },
+    /// The initial elements of the table exceed [`crate::N_MAX_TABLE_SIZE`].
+    TableSizeLimitReached(TableType),
+    /// Tags can't be imported.
+    UnsupportedImportType,
+}
+
+impl ImportIssueKind {
+    /// Returns the error the compilation fails with because of the issue.
+    pub fn compilation_error(&self) -> CompilationError {
+        match self {
+            ImportIssueKind::UnresolvedFunction => CompilationError::UnresolvedImportFunction,
+            ImportIssueKind::FuncTypeMismatch { .. } | ImportIssueKind::RefTypeInFuncType(_) => {
+                CompilationError::MalformedImportFunctionType
+            }
+            ImportIssueKind::UnresolvedGlobal => CompilationError::UnresolvedImportGlobal,
+            ImportIssueKind::GlobalTypeMismatch { .. } => {
+                CompilationError::MalformedImportGlobalType
+            }
+            ImportIssueKind::UnsupportedGlobalType(_) => CompilationError::NotSupportedGlobalType,
+            ImportIssueKind::UnresolvedMemory => CompilationError::UnresolvedImportMemory,
+            ImportIssueKind::MemoryTypeMismatch { .. } => {
+                CompilationError::MalformedImportMemoryType
+            }
+            ImportIssueKind::MemoryPagesLimitReached(_) => CompilationError::MaxMemoryPagesReached,
+            ImportIssueKind::UnresolvedTable => CompilationError::UnresolvedImportTable,
+            ImportIssueKind::TableTypeMismatch { .. } => CompilationError::MalformedImportTableType,
+            ImportIssueKind::TableSizeLimitReached(_) => CompilationError::TableOutOfBounds,
+            ImportIssueKind::UnsupportedImportType => CompilationError::NotSupportedImportType,
+        }
+    }
+}
+
+impl core::fmt::Display for ImportIssue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{} (at offset {:#x}): ", self.import_name, self.offset)?;
+        match &self.kind {
+            ImportIssueKind::FuncTypeMismatch { expected, actual } => {
+                write!(f, "expected {:?}, found {:?}", expected, actual)
+            }
+            ImportIssueKind::RefTypeInFuncType(func_type) => {
+                write!(f, "funcref or externref in {:?}", func_type)
+            }
+            ImportIssueKind::GlobalTypeMismatch { expected, actual } => {
+                write!(f, "expected {:?}, found {:?}", expected, actual)
+            }
+            ImportIssueKind::UnsupportedGlobalType(global_type) => {
+                write!(f, "not supported {:?}", global_type)
+            }
+            ImportIssueKind::MemoryTypeMismatch { expected, actual } => {
+                write!(f, "expected {:?}, found {:?}", expected, actual)
+            }
+            ImportIssueKind::TableTypeMismatch { expected, actual } => {
+                write!(f, "expected {:?}, found {:?}", expected, actual)
+            }
+            ImportIssueKind::MemoryPagesLimitReached(memory_type) => {
+                write!(f, "memory pages limit reached by {:?}", memory_type)
+            }
+            ImportIssueKind::TableSizeLimitReached(table_type) => {
+                write!(f, "table size limit reached by {:?}", table_type)
+            }
+            kind => write!(f, "{}", kind.compilation_error()),
+        }
+    }
+}
+
+/// The result of [`crate::ModuleParser::analyze_imports`], lists the imports in the order of the
+/// import section.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ImportReport {
+    /// The number of imports of the module.
+    pub imports: usize,
+    /// The imports that fail to resolve, an import can have several issues.
+    pub issues: Vec<ImportIssue>,
+}
+
+impl ImportReport {
+    /// Returns `true` if every import resolves.
+    pub fn is_resolved(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Checks the imports of a Wasm module against the import linker and the limits of a
+/// [`CompilationConfig`], the compilation fails at the first issue of an import, while
+/// [`analyze_imports`] reports all of them.
+#[derive(Debug, Default)]
+pub(crate) struct ImportChecker {
+    /// The initial pages of the memories imported so far.
+    memory_pages: u32,
+    /// The issues of the last checked import.
+    issues: Vec<ImportIssueKind>,
+}
+
+impl ImportChecker {
+    /// Returns the issues of the last checked import.
+    pub(crate) fn issues(&self) -> &[ImportIssueKind] {
+        &self.issues
+    }
+
+    /// Returns the error of the first issue of the last checked import.
+    pub(crate) fn error(&self) -> CompilationError {
+        self.issues
+            .first()
+            .map(ImportIssueKind::compilation_error)
+            .expect("rwasm: the import has no issues")
+    }
+
+    /// Rejects an import of a kind that can't be imported, e.g., a tag.
+    pub(crate) fn check_unsupported(&mut self) {
+        self.issues.clear();
+        self.issues.push(ImportIssueKind::UnsupportedImportType);
+    }
+
+    /// Resolves an imported function, `None` if it has any issue.
+    pub(crate) fn check_func(
+        &mut self,
+        config: &CompilationConfig,
+        import_name: &ImportName,
+        func_type: &FuncType,
+    ) -> Option<ImportLinkerEntity> {
+        self.issues.clear();
+        let entity = config
+            .import_linker
+            .as_ref()
+            .and_then(|import_linker| import_linker.resolve_by_import_name(import_name));
+        match entity {
+            None => self.issues.push(ImportIssueKind::UnresolvedFunction),
+            Some(entity) if !entity.matches_func_type(func_type) => {
+                self.issues.push(ImportIssueKind::FuncTypeMismatch {
+                    expected: FuncType::new(
+                        entity.params.iter().copied(),
+                        entity.result.iter().copied(),
+                    ),
+                    actual: func_type.clone(),
+                })
+            }
+            Some(_) => {}
+        }
+        // don't allow funcref/externref in imported functions
+        if !config.allow_func_ref_function_types
+            && func_type
+                .params()
+                .iter()
+                .chain(func_type.results())
+                .any(|x| x == &ValType::FuncRef || x == &ValType::ExternRef)
+        {
+            self.issues
+                .push(ImportIssueKind::RefTypeInFuncType(func_type.clone()));
+        }
+        entity.filter(|_| self.issues.is_empty()).cloned()
+    }
+
+    /// Resolves an imported global, a global the linker doesn't declare gets
+    /// [`CompilationConfig::default_imported_global_value`], `None` if it has any issue.
+    pub(crate) fn check_global(
+        &mut self,
+        config: &CompilationConfig,
+        import_name: &ImportName,
+        global_type: GlobalType,
+    ) -> Option<ImportLinkerGlobal> {
+        self.issues.clear();
+        let linker_global = config
+            .import_linker
+            .as_ref()
+            .and_then(|import_linker| import_linker.resolve_global(import_name))
+            .copied();
+        match linker_global {
+            Some(linker_global) if linker_global.global_type != global_type => {
+                self.issues.push(ImportIssueKind::GlobalTypeMismatch {
+                    expected: linker_global.global_type,
+                    actual: global_type,
+                });
+                None
+            }
+            Some(linker_global) => match global_type.content_type {
+                ValType::I32 | ValType::I64 | ValType::F32 | ValType::F64 => Some(linker_global),
+                _ => {
+                    self.issues
+                        .push(ImportIssueKind::UnsupportedGlobalType(global_type));
+                    None
+                }
+            },
+            None => match config.default_imported_global_value {
+                Some(value) => Some(ImportLinkerGlobal {
+                    global_type,
+                    value: GlobalValueProvider::Const(value),
+                }),
+                None => {
+                    self.issues.push(ImportIssueKind::UnresolvedGlobal);
+                    None
+                }
+            },
+        }
+    }
+
+    /// Resolves an imported memory to the memory type declared by the linker and counts its
+    /// initial pages towards [`CompilationConfig::max_allowed_memory_pages`], `None` if it has any
+    /// issue.
+    pub(crate) fn check_memory(
+        &mut self,
+        config: &CompilationConfig,
+        import_name: &ImportName,
+        memory_type: MemoryType,
+    ) -> Option<MemoryType> {
+        self.issues.clear();
+        let linker_memory = config
+            .import_linker
+            .as_ref()
+            .and_then(|import_linker| import_linker.resolve_memory(import_name))
+            .copied();
+        let Some(linker_memory) = linker_memory else {
+            self.issues.push(ImportIssueKind::UnresolvedMemory);
+            return None;
+        };
+        if !memory_type_matches(&linker_memory, &memory_type) {
+            self.issues.push(ImportIssueKind::MemoryTypeMismatch {
+                expected: linker_memory,
+                actual: memory_type,
+            });
+            return None;
+        }
+        let memory_pages = u32::try_from(linker_memory.initial)
+            .ok()
+            .map(|initial| self.memory_pages.saturating_add(initial))
+            .filter(|&pages| !memory_pages_limit_reached(pages, config.max_allowed_memory_pages));
+        let Some(memory_pages) = memory_pages else {
+            self.issues
+                .push(ImportIssueKind::MemoryPagesLimitReached(linker_memory));
+            return None;
+        };
+        self.memory_pages = memory_pages;
+        Some(linker_memory)
+    }
+
+    /// Resolves an imported table to the table type declared by the linker, `None` if it has any
+    /// issue.
+    pub(crate) fn check_table(
+        &mut self,
+        config: &CompilationConfig,
+        import_name: &ImportName,
+        table_type: TableType,
+    ) -> Option<TableType> {
+        self.issues.clear();
+        let linker_table = config
+            .import_linker
+            .as_ref()
+            .and_then(|import_linker| import_linker.resolve_table(import_name))
+            .copied();
+        let Some(linker_table) = linker_table else {
+            self.issues.push(ImportIssueKind::UnresolvedTable);
+            return None;
+        };
+        if !table_type_matches(&linker_table, &table_type) {
+            self.issues.push(ImportIssueKind::TableTypeMismatch {
+                expected: linker_table,
+                actual: table_type,
+            });
+            return None;
+        }
+        if linker_table.initial > N_MAX_TABLE_SIZE {
+            self.issues
+                .push(ImportIssueKind::TableSizeLimitReached(linker_table));
+            return None;
+        }
+        Some(linker_table)
+    }
+}
+
+/// Checks the imports of a Wasm module with the [`ImportChecker`] the compilation uses, but
+/// reports all the issues instead of the first one.
+///
+/// Only the type and the import sections are parsed.
+pub(crate) fn analyze_imports(
+    wasm_binary: &[u8],
+    config: &CompilationConfig,
+) -> Result<ImportReport, CompilationError> {
+    let mut func_types = Vec::new();
+    let mut report = ImportReport::default();
+    for payload in Parser::new(0).parse_all(wasm_binary) {
+        match payload? {
+            Payload::TypeSection(section) => read_func_types(section, &mut func_types)?,
+            Payload::ImportSection(section) => {
+                analyze_import_section(section, &func_types, config, &mut report)?;
+            }
+            // the import section precedes all the other sections that can't be skipped
+            Payload::FunctionSection(_)
+            | Payload::TableSection(_)
+            | Payload::MemorySection(_)
+            | Payload::GlobalSection(_)
+            | Payload::CodeSectionStart { .. } => break,
+            _ => {}
+        }
+    }
+    Ok(report)
+}
+
+fn read_func_types(
+    section: TypeSectionReader,
+    func_types: &mut Vec<FuncType>,
+) -> Result<(), CompilationError> {
+    for func_type in section {
+        let Type::Func(func_type) = func_type?;
+        func_types.push(func_type);
+    }
+    Ok(())
+}
+
+fn analyze_import_section(
+    section: ImportSectionReader,
+    func_types: &[FuncType],
+    config: &CompilationConfig,
+    report: &mut ImportReport,
+) -> Result<(), CompilationError> {
+    let mut checker = ImportChecker::default();
+    for import in section.into_iter_with_offsets() {
+        let (offset, import) = import?;
+        let import_name = ImportName::new(import.module, import.name);
+        report.imports += 1;
+        match import.ty {
+            TypeRef::Func(func_type_index) => {
+                let func_type = func_types
+                    .get(func_type_index as usize)
+                    .ok_or(CompilationError::MalformedFuncType)?;
+                checker.check_func(config, &import_name, func_type);
+            }
+            TypeRef::Global(global_type) => {
+                checker.check_global(config, &import_name, global_type);
+            }
+            TypeRef::Memory(memory_type) => {
+                checker.check_memory(config, &import_name, memory_type);
+            }
+            TypeRef::Table(table_type) => {
+                checker.check_table(config, &import_name, table_type);
+            }
+            _ => checker.check_unsupported(),
+        }
+        report
+            .issues
+            .extend(checker.issues().iter().map(|kind| ImportIssue {
+                import_name: import_name.clone(),
+                offset,
+                kind: kind.clone(),
+            }));
+    }
+    Ok(())
+}
+
+/// Returns `true` if a memory declared by the linker satisfies the imported memory type.
+pub(crate) fn memory_type_matches(linker_memory: &MemoryType, memory_type: &MemoryType) -> bool {
+    linker_memory.memory64 == memory_type.memory64
+        && linker_memory.shared == memory_type.shared
+        && limits_match(
+            (linker_memory.initial, linker_memory.maximum),
+            (memory_type.initial, memory_type.maximum),
+        )
+}
+
+/// Returns `true` if a table declared by the linker satisfies the imported table type.
+pub(crate) fn table_type_matches(linker_table: &TableType, table_type: &TableType) -> bool {
+    linker_table.element_type == table_type.element_type
+        && limits_match(
+            (
+                linker_table.initial as u64,
+                linker_table.maximum.map(u64::from),
+            ),
+            (table_type.initial as u64, table_type.maximum.map(u64::from)),
+        )
+}
+
+/// Checks that the `(initial, maximum)` limits of an imported entity fit the limits the module
+/// imports it with, the same way as Wasm import matching does.
+fn limits_match(actual: (u64, Option<u64>), expected: (u64, Option<u64>)) -> bool {
+    let (initial, maximum) = actual;
+    let (expected_initial, expected_maximum) = expected;
+    initial >= expected_initial
+        && match expected_maximum {
+            None => true,
+            Some(expected_maximum) => maximum.is_some_and(|maximum| maximum <= expected_maximum),
+        }
+}
@@ -0,0 +1,140 @@
+use crate::{AddressOffset, InstructionSet, MaxStackHeight, TrapCode};
+
+/// Emits an instruction lowered to a sequence of 32-bit opcodes and tracks its stack height.
+///
+/// The stack slots are addressed from the first word of the operands (slot 0) and converted to
+/// local depths on emission.
+pub(super) struct Lowering<'a> {
+    code: &'a mut InstructionSet,
+    operands: u32,
+    pub(super) height: u32,
+    max_height: u32,
+}
+
+impl<'a> Lowering<'a> {
+    pub(super) fn new(code: &'a mut InstructionSet, operands: u32) -> Self {
+        Self {
+            code,
+            operands,
+            height: operands,
+            max_height: operands,
+        }
+    }
+
+    fn push(&mut self, count: u32) {
+        self.height += count;
+        self.max_height = self.max_height.max(self.height);
+    }
+
+    pub(super) fn get(&mut self, slot: u32) {
+        self.code.op_local_get(self.height - slot);
+        self.push(1);
+    }
+
+    pub(super) fn set(&mut self, slot: u32) {
+        self.height -= 1;
+        self.code.op_local_set(self.height - slot);
+    }
+
+    pub(super) fn dup(&mut self) {
+        self.get(self.height - 1);
+    }
+
+    pub(super) fn i32_const(&mut self, value: i32) {
+        self.code.op_i32_const(value);
+        self.push(1);
+    }
+
+    pub(super) fn drop(&mut self, count: u32) {
+        match count {
+            0 => {}
+            1 => self.code.op_drop(),
+            _ => self.code.op_bulk_drop(count),
+        }
+        self.height -= count;
+    }
+
+    /// Emits a sequence taking `params` slots and leaving `results` slots on the stack.
+    pub(super) fn emit(
+        &mut self,
+        emitter: fn(&mut InstructionSet),
+        max_stack_height: u32,
+        params: u32,
+        results: u32,
+    ) {
+        self.max_height = self.max_height.max(self.height + max_stack_height);
+        emitter(self.code);
+        self.height = self.height - params + results;
+        self.max_height = self.max_height.max(self.height);
+    }
+
+    pub(super) fn unary(&mut self, emitter: fn(&mut InstructionSet)) {
+        self.emit(emitter, 0, 1, 1);
+    }
+
+    pub(super) fn binary(&mut self, emitter: fn(&mut InstructionSet)) {
+        self.emit(emitter, 0, 2, 1);
+    }
+
+    pub(super) fn select(&mut self) {
+        self.emit(InstructionSet::op_select, 0, 3, 1);
+    }
+
+    /// Emits a memory access taking `params` slots (the address first) and leaving `results`
+    /// slots on the stack.
+    pub(super) fn memory(
+        &mut self,
+        emitter: fn(&mut InstructionSet, AddressOffset),
+        offset: AddressOffset,
+        max_stack_height: u32,
+        params: u32,
+        results: u32,
+    ) {
+        self.max_height = self.max_height.max(self.height + max_stack_height);
+        emitter(self.code, offset);
+        self.height = self.height - params + results;
+        self.max_height = self.max_height.max(self.height);
+    }
+
+    pub(super) fn load(
+        &mut self,
+        emitter: fn(&mut InstructionSet, AddressOffset),
+        offset: AddressOffset,
+    ) {
+        self.memory(emitter, offset, 0, 1, 1);
+    }
+
+    pub(super) fn store(
+        &mut self,
+        emitter: fn(&mut InstructionSet, AddressOffset),
+        offset: AddressOffset,
+    ) {
+        self.memory(emitter, offset, 0, 2, 0);
+    }
+
+    /// Pops a condition and traps with `trap_code` if it's not zero.
+    pub(super) fn trap_if(&mut self, trap_code: TrapCode) {
+        self.code.op_br_if_eqz(2);
+        self.code.op_trap(trap_code);
+        self.height -= 1;
+    }
+
+    /// Replaces the operands with the `results` words on top of the stack and returns the max
+    /// stack height above the operands.
+    pub(super) fn finish(mut self, results: u32) -> MaxStackHeight {
+        let first = self.height - results;
+        if first >= results {
+            for slot in (0..results).rev() {
+                self.set(slot);
+            }
+        } else if first > 0 {
+            // the results overlap their slots, so they're moved starting from the lowest word
+            for slot in 0..results {
+                self.get(first + slot);
+                self.set(slot);
+            }
+        }
+        self.drop(self.height - results);
+        self.max_height - self.operands
+    }
+}
@@ -1,25 +1,35 @@
+mod atomics;
 mod block_fuel;
 mod compiled_expr;
 mod config;
 mod control_flow;
 mod drop_keep;
 mod error;
+mod fuel_cost_model;
 mod func_builder;
 mod func_type_registry;
+mod import_report;
 pub mod intrinsic;
 mod labels;
 mod locals_registry;
+mod lowering;
+mod optimizer;
 mod parser;
+mod report;
 mod segment_builder;
+mod simd;
 mod snippets;
 mod translator;
 mod utils;
 mod value_stack;
 
 pub use self::{
-    config::{CompilationConfig, StateRouterConfig},
-    error::CompilationError,
+    config::{CompilationConfig, FuelMeteringMode, OptimizationLevel, StateRouterConfig},
+    error::{CompilationError, CompilationErrorContext},
+    fuel_cost_model::{DefaultFuelCostModel, FuelCostClass, FuelCostModel},
+    import_report::{ImportIssue, ImportIssueKind, ImportReport},
     parser::ModuleParser,
+    report::{CompilationReport, CompiledFuncKind, FuncReport, FuncStats},
 };
 use crate::RwasmModule;
 use alloc::vec::Vec;
@@ -0,0 +1,539 @@
+use crate::{
+    CompilationConfig, InstructionSet, Opcode, OptimizationLevel, RwasmModuleInner, UntypedValue,
+};
+use alloc::{vec, vec::Vec};
+
+/// The number of instructions of the dynamic fuel check (ending with `ConsumeFuelStack`) emitted
+/// before bulk memory/table operations.
+const BULK_FUEL_CHECK_LEN: usize = 6;
+
+/// Rewrites the code section according to the compilation config.
+///
+/// With [`OptimizationLevel::Peephole`] peephole rewrites and constant folding are applied until
+/// nothing changes, then, with [`CompilationConfig::fused_opcodes`], hot instruction pairs are
+/// replaced with fused opcodes.
+///
+/// The code section must already be relocated (calls, `RefFunc` and elem entries hold absolute
+/// offsets), all offsets of the module and `func_offsets` (the entries of functions, they are
+/// never removed) are fixed up after every round. The rewrites never touch
+/// `ConsumeFuel` instructions, so the charged fuel stays the same, only the number of executed
+/// instructions goes down.
+pub(crate) fn optimize(
+    module: &mut RwasmModuleInner,
+    config: &CompilationConfig,
+    func_offsets: &mut [u32],
+) {
+    let bounded_loops = InstructionSet::bounded_loop_snippets();
+    let mut passes = Vec::new();
+    if config.optimize >= OptimizationLevel::Peephole {
+        passes.push(Pass::Fold);
+    }
+    if config.fused_opcodes {
+        passes.push(Pass::Fuse);
+    }
+    for pass in passes {
+        loop {
+            let peephole = Peephole::new(module, &bounded_loops);
+            let Some(rewritten) = peephole.rewrite(pass) else {
+                break;
+            };
+            relocate(module, rewritten, func_offsets);
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Pass {
+    /// Peephole rewrites, constant folding and dead code elimination.
+    Fold,
+    /// Replacement of instruction pairs with fused opcodes.
+    Fuse,
+}
+
+struct Peephole<'a> {
+    code: &'a [Opcode],
+    /// Instructions that can be entered not only from the previous instruction.
+    targets: Vec<bool>,
+    /// Instructions that must be kept as is: function entries, branch tables, auxiliary
+    /// instructions, dynamic fuel checks and the snippets recognized by the fuel verifier.
+    pinned: Vec<bool>,
+}
+
+impl<'a> Peephole<'a> {
+    fn new(module: &'a RwasmModuleInner, bounded_loops: &[InstructionSet]) -> Self {
+        let code = &module.code_section[..];
+        let mut targets = vec![false; code.len() + 1];
+        let mut pinned = vec![false; code.len() + 1];
+        let mut pin = |pc: usize| {
+            if let Some(pinned) = pinned.get_mut(pc) {
+                *pinned = true;
+            }
+        };
+        pin(0);
+        pin(module.source_pc as usize);
+        module
+            .elem_section
+            .iter()
+            .for_each(|elem| pin(*elem as usize));
+        module
+            .export_section
+            .iter()
+            .for_each(|export| pin(export.func_offset as usize));
+        for (pc, opcode) in code.iter().enumerate() {
+            match *opcode {
+                Opcode::Br(offset)
+                | Opcode::BrIfEqz(offset)
+                | Opcode::BrIfNez(offset)
+                | Opcode::TryBegin(offset) => {
+                    let target = pc as i64 + offset.to_i32() as i64;
+                    if let Some(target) = usize::try_from(target)
+                        .ok()
+                        .and_then(|target| targets.get_mut(target))
+                    {
+                        *target = true;
+                    }
+                }
+                Opcode::BrTable(len) => {
+                    (pc..=pc + 2 * len as usize).for_each(&mut pin);
+                }
+                Opcode::CallInternal(func)
+                | Opcode::ReturnCallInternal(func)
+                | Opcode::RefFunc(func) => pin(func as usize),
+                Opcode::SignatureCheck(_) => pin(pc),
+                // these instructions are followed by an auxiliary `TableGet`
+                Opcode::CallIndirect(_) | Opcode::ReturnCallIndirect(_) | Opcode::TableInit(_) => {
+                    pin(pc);
+                    pin(pc + 1);
+                }
+                Opcode::ConsumeFuelStack => {
+                    (pc.saturating_sub(BULK_FUEL_CHECK_LEN - 1)..=pc + 1).for_each(&mut pin);
+                }
+                _ => {}
+            }
+        }
+        for body in bounded_loops {
+            let Some(first) = body.first() else {
+                continue;
+            };
+            for start in 0..code.len() {
+                if code[start] == *first && code[start..].starts_with(body) {
+                    (start..start + body.len()).for_each(&mut pin);
+                }
+            }
+        }
+        Self {
+            code,
+            targets,
+            pinned,
+        }
+    }
+
+    /// Checks that the instructions at `pc..pc + len` can be replaced: none of them is pinned and
+    /// only the first one can be a branch target.
+    fn is_replaceable(&self, pc: usize, len: usize) -> bool {
+        pc + len <= self.code.len()
+            && (pc..pc + len).all(|pc| !self.pinned[pc])
+            && (pc + 1..pc + len).all(|pc| !self.targets[pc])
+    }
+
+    /// Returns the rewritten code, where removed instructions are `None`, or `None` if there is
+    /// nothing to rewrite.
+    fn rewrite(&self, pass: Pass) -> Option<Vec<Option<Opcode>>> {
+        let mut rewritten = self.code.iter().copied().map(Some).collect::<Vec<_>>();
+        let mut changed = false;
+        let mut pc = 0;
+        while pc < self.code.len() {
+            let replacement = match pass {
+                Pass::Fold => self.fold(pc),
+                Pass::Fuse => self.fuse(pc),
+            };
+            if let Some((replacement, len)) = replacement {
+                rewritten[pc] = replacement;
+                rewritten[pc + 1..pc + len].fill(None);
+                changed = true;
+                pc += len;
+                continue;
+            }
+            if pass != Pass::Fold {
+                pc += 1;
+                continue;
+            }
+            let Some(mut dead_pc) = self.unreachable_after(pc) else {
+                pc += 1;
+                continue;
+            };
+            while dead_pc < self.code.len() && !self.targets[dead_pc] && !self.pinned[dead_pc] {
+                rewritten[dead_pc] = None;
+                changed = true;
+                dead_pc += 1;
+            }
+            pc = dead_pc.max(pc + 1);
+        }
+        changed.then_some(rewritten)
+    }
+
+    /// Returns a replacement of the instructions starting at `pc` and the number of replaced
+    /// instructions.
+    fn fold(&self, pc: usize) -> Option<(Option<Opcode>, usize)> {
+        let code = self.code;
+        let window = |len: usize| self.is_replaceable(pc, len).then(|| &code[pc..pc + len]);
+        if let Some([opcode]) = window(1) {
+            match *opcode {
+                // a branch to the next instruction
+                Opcode::Br(offset) if offset.to_i32() == 1 => return Some((None, 1)),
+                Opcode::BrIfEqz(offset) | Opcode::BrIfNez(offset) if offset.to_i32() == 1 => {
+                    return Some((Some(Opcode::Drop), 1));
+                }
+                _ => {}
+            }
+        }
+        if let Some([lhs, rhs]) = window(2) {
+            match (*lhs, *rhs) {
+                (Opcode::LocalSet(depth), Opcode::LocalGet(get_depth)) if depth == get_depth => {
+                    if let Some(depth) = depth.checked_add(1) {
+                        return Some((Some(Opcode::LocalTee(depth)), 2));
+                    }
+                }
+                (Opcode::I32Const(_), Opcode::Drop) => return Some((None, 2)),
+                (Opcode::I32Const(value), opcode) => {
+                    if let Some(value) = fold_unary(opcode, value) {
+                        return Some((Some(Opcode::I32Const(value)), 2));
+                    }
+                }
+                _ => {}
+            }
+        }
+        if let Some([Opcode::I32Const(lhs), Opcode::I32Const(rhs), opcode]) = window(3) {
+            if let Some(value) = fold_binary(*opcode, *lhs, *rhs) {
+                return Some((Some(Opcode::I32Const(value)), 3));
+            }
+        }
+        None
+    }
+
+    /// Returns a fused opcode replacing the two instructions starting at `pc`.
+    ///
+    /// The fused opcode takes the place of the first instruction, so the offset of a branch is one
+    /// more than before.
+    fn fuse(&self, pc: usize) -> Option<(Option<Opcode>, usize)> {
+        if !self.is_replaceable(pc, 2) {
+            return None;
+        }
+        let short = |depth: u32| u16::try_from(depth).ok();
+        let fused = match (self.code[pc], self.code[pc + 1]) {
+            (Opcode::LocalGet(first), Opcode::LocalGet(second)) => {
+                Opcode::LocalGetLocalGet(short(first)?, short(second)?)
+            }
+            (Opcode::LocalGet(depth), Opcode::I32Const(value)) => {
+                Opcode::LocalGetI32Const(short(depth)?, value)
+            }
+            (Opcode::LocalGet(depth), Opcode::I32Load(offset)) => {
+                Opcode::LocalGetI32Load(short(depth)?, offset)
+            }
+            (Opcode::LocalSet(first), Opcode::LocalGet(second)) => {
+                Opcode::LocalSetLocalGet(short(first)?, short(second)?)
+            }
+            (Opcode::LocalSet(first), Opcode::LocalSet(second)) => {
+                Opcode::LocalSetLocalSet(short(first)?, short(second)?)
+            }
+            (Opcode::I32Const(value), Opcode::I32Add) => Opcode::I32ConstI32Add(value),
+            (Opcode::I32Eqz, Opcode::BrIfNez(offset)) => {
+                Opcode::BrIfEqz(offset.to_i32().checked_add(1)?.into())
+            }
+            (Opcode::Drop, Opcode::Drop) => Opcode::BulkDrop(2),
+            (Opcode::BulkDrop(count), Opcode::Drop) => Opcode::BulkDrop(count.checked_add(1)?),
+            _ => return None,
+        };
+        Some((Some(fused), 2))
+    }
+
+    /// Returns the first instruction after `pc` if `pc` never falls through to it.
+    fn unreachable_after(&self, pc: usize) -> Option<usize> {
+        match self.code[pc] {
+            Opcode::Br(_)
+            | Opcode::Return
+            | Opcode::ReturnCall(_)
+            | Opcode::ReturnCallInternal(_)
+            | Opcode::Unreachable
+            | Opcode::Trap(_)
+            | Opcode::Throw(_)
+            | Opcode::Rethrow(_)
+            | Opcode::Delegate(_) => Some(pc + 1),
+            Opcode::ReturnCallIndirect(_) => Some(pc + 2),
+            Opcode::BrTable(len) => Some(pc + 1 + 2 * len as usize),
+            _ => None,
+        }
+    }
+}
+
+fn fold_unary(opcode: Opcode, value: UntypedValue) -> Option<UntypedValue> {
+    Some(match opcode {
+        Opcode::I32Eqz => value.i32_eqz(),
+        Opcode::I32Clz => value.i32_clz(),
+        Opcode::I32Ctz => value.i32_ctz(),
+        Opcode::I32Popcnt => value.i32_popcnt(),
+        Opcode::I32Extend8S => value.i32_extend8_s(),
+        Opcode::I32Extend16S => value.i32_extend16_s(),
+        _ => return None,
+    })
+}
+
+fn fold_binary(opcode: Opcode, lhs: UntypedValue, rhs: UntypedValue) -> Option<UntypedValue> {
+    Some(match opcode {
+        Opcode::I32Eq => lhs.i32_eq(rhs),
+        Opcode::I32Ne => lhs.i32_ne(rhs),
+        Opcode::I32LtS => lhs.i32_lt_s(rhs),
+        Opcode::I32LtU => lhs.i32_lt_u(rhs),
+        Opcode::I32GtS => lhs.i32_gt_s(rhs),
+        Opcode::I32GtU => lhs.i32_gt_u(rhs),
+        Opcode::I32LeS => lhs.i32_le_s(rhs),
+        Opcode::I32LeU => lhs.i32_le_u(rhs),
+        Opcode::I32GeS => lhs.i32_ge_s(rhs),
+        Opcode::I32GeU => lhs.i32_ge_u(rhs),
+        Opcode::I32Add => lhs.i32_add(rhs),
+        Opcode::I32Sub => lhs.i32_sub(rhs),
+        Opcode::I32Mul => lhs.i32_mul(rhs),
+        // a trapping division is kept as is
+        Opcode::I32DivS => lhs.i32_div_s(rhs).ok()?,
+        Opcode::I32DivU => lhs.i32_div_u(rhs).ok()?,
+        Opcode::I32RemS => lhs.i32_rem_s(rhs).ok()?,
+        Opcode::I32RemU => lhs.i32_rem_u(rhs).ok()?,
+        Opcode::I32And => lhs.i32_and(rhs),
+        Opcode::I32Or => lhs.i32_or(rhs),
+        Opcode::I32Xor => lhs.i32_xor(rhs),
+        Opcode::I32Shl => lhs.i32_shl(rhs),
+        Opcode::I32ShrS => lhs.i32_shr_s(rhs),
+        Opcode::I32ShrU => lhs.i32_shr_u(rhs),
+        Opcode::I32Rotl => lhs.i32_rotl(rhs),
+        Opcode::I32Rotr => lhs.i32_rotr(rhs),
+        _ => return None,
+    })
+}
+
+/// Replaces the code section with the rewritten code and fixes up all offsets of the module.
+///
+/// An offset of a removed instruction is moved to the next kept one, it's only possible for
+/// instructions that have no effect when entered by a branch (e.g. `I32Const`+`Drop`).
+fn relocate(
+    module: &mut RwasmModuleInner,
+    rewritten: Vec<Option<Opcode>>,
+    func_offsets: &mut [u32],
+) {
+    // maps an old pc to the new pc of the first kept instruction at or after it
+    let mut new_pcs = Vec::with_capacity(rewritten.len() + 1);
+    let mut new_pc = 0u32;
+    for opcode in rewritten.iter() {
+        new_pcs.push(new_pc);
+        new_pc += opcode.is_some() as u32;
+    }
+    new_pcs.push(new_pc);
+    let relocate_pc = |pc: u32| new_pcs.get(pc as usize).copied().unwrap_or(pc);
+
+    let mut code_section = InstructionSet::new();
+    for (pc, opcode) in rewritten.into_iter().enumerate() {
+        let Some(mut opcode) = opcode else {
+            continue;
+        };
+        match &mut opcode {
+            Opcode::Br(offset)
+            | Opcode::BrIfEqz(offset)
+            | Opcode::BrIfNez(offset)
+            | Opcode::TryBegin(offset) => {
+                let target = (pc as i64 + offset.to_i32() as i64) as u32;
+                let new_offset = relocate_pc(target) as i64 - new_pcs[pc] as i64;
+                *offset = (new_offset as i32).into();
+            }
+            Opcode::CallInternal(func)
+            | Opcode::ReturnCallInternal(func)
+            | Opcode::RefFunc(func) => *func = relocate_pc(*func),
+            _ => {}
+        }
+        code_section.push(opcode);
+    }
+    module.code_section = code_section;
+    for elem in module.elem_section.iter_mut() {
+        *elem = relocate_pc(*elem);
+    }
+    module.source_pc = relocate_pc(module.source_pc);
+    for func_offset in func_offsets.iter_mut() {
+        *func_offset = relocate_pc(*func_offset);
+    }
+    for export in module.export_section.iter_mut() {
+        export.func_offset = relocate_pc(export.func_offset);
+    }
+    for entry in module.debug_section.source_map.iter_mut() {
+        entry.start_pc = relocate_pc(entry.start_pc);
+        entry.end_pc = relocate_pc(entry.end_pc);
+    }
+    module
+        .debug_section
+        .source_map
+        .retain(|entry| entry.start_pc < entry.end_pc);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TrapCode;
+
+    fn optimized(code_section: InstructionSet, elem_section: Vec<u32>) -> RwasmModuleInner {
+        let config = CompilationConfig::default().with_optimize(OptimizationLevel::Peephole);
+        optimized_with_config(code_section, elem_section, &config)
+    }
+
+    fn optimized_with_config(
+        code_section: InstructionSet,
+        elem_section: Vec<u32>,
+        config: &CompilationConfig,
+    ) -> RwasmModuleInner {
+        let mut module = RwasmModuleInner {
+            code_section,
+            elem_section,
+            ..Default::default()
+        };
+        optimize(&mut module, config, &mut []);
+        module
+    }
+
+    #[test]
+    fn test_peephole_rewrites() {
+        let mut code = InstructionSet::new();
+        code.op_stack_check(2);
+        code.op_local_set(2);
+        code.op_local_get(2);
+        code.op_i32_const(7);
+        code.op_drop();
+        code.op_br(1);
+        code.op_br_if_eqz(1);
+        code.op_i32_const(2);
+        code.op_i32_const(3);
+        code.op_i32_add();
+        code.op_i32_const(4);
+        code.op_i32_mul();
+        code.op_i32_eqz();
+        code.op_return();
+        let module = optimized(code, vec![]);
+        let mut expected = InstructionSet::new();
+        expected.op_stack_check(2);
+        expected.op_local_tee(3);
+        expected.op_drop();
+        expected.op_i32_const(0);
+        expected.op_return();
+        assert_eq!(module.code_section, expected);
+    }
+
+    #[test]
+    fn test_dead_code_and_relocation() {
+        let mut code = InstructionSet::new();
+        // 0: entrypoint
+        code.op_call_internal(6);
+        code.op_br_if_nez(4);
+        code.op_br(3);
+        code.op_i32_const(1);
+        code.op_drop();
+        code.op_return();
+        // 6: function
+        code.op_i32_const(1);
+        code.op_i32_const(2);
+        code.op_i32_add();
+        code.op_br_if_eqz(-2);
+        code.op_return();
+        code.op_trap(TrapCode::UnreachableCodeReached);
+        code.op_return();
+        let module = optimized(code, vec![0, 6]);
+        let mut expected = InstructionSet::new();
+        expected.op_call_internal(3);
+        expected.op_drop();
+        expected.op_return();
+        // the branch target inside the folded chain is kept
+        expected.op_i32_const(1);
+        expected.op_i32_const(2);
+        expected.op_i32_add();
+        expected.op_br_if_eqz(-2);
+        expected.op_return();
+        assert_eq!(module.code_section, expected);
+        assert_eq!(module.elem_section, vec![0, 3]);
+    }
+
+    #[test]
+    fn test_pinned_code_is_kept() {
+        let mut code = InstructionSet::new();
+        code.op_br_table(2);
+        code.op_br(4);
+        code.op_return();
+        code.op_br(2);
+        code.op_return();
+        code.op_return();
+        code.op_i32_const(0);
+        code.op_i32_const(1);
+        code.op_call_indirect(0);
+        code.op_table_get(0);
+        code.op_return();
+        let expected = code.clone();
+        let module = optimized(code, vec![6]);
+        assert_eq!(module.code_section, expected);
+    }
+
+    #[test]
+    fn test_fused_opcodes() {
+        let mut code = InstructionSet::new();
+        code.op_stack_check(4);
+        code.op_local_get(1);
+        code.op_local_get(2);
+        code.op_local_get(3);
+        code.op_i32_const(7);
+        code.op_local_set(4);
+        code.op_local_get(2);
+        code.op_local_get(1);
+        code.op_i32_load(8);
+        code.op_i32_const(1);
+        code.op_i32_add();
+        code.op_local_set(5);
+        code.op_local_set(5);
+        code.op_i32_eqz();
+        code.op_br_if_nez(4);
+        code.op_drop();
+        code.op_drop();
+        code.op_drop();
+        code.op_return();
+        let config = CompilationConfig::default().with_fused_opcodes(true);
+        let module = optimized_with_config(code, vec![], &config);
+        let mut expected = InstructionSet::new();
+        expected.op_stack_check(4);
+        expected.op_local_get_local_get(1, 2);
+        expected.op_local_get_i32_const(3, 7);
+        expected.op_local_set_local_get(4, 2);
+        expected.op_local_get_i32_load(1, 8);
+        expected.op_i32_const_i32_add(1);
+        expected.op_local_set_local_set(5, 5);
+        expected.op_br_if_eqz(2);
+        expected.op_bulk_drop(3);
+        expected.op_return();
+        assert_eq!(module.code_section, expected);
+    }
+
+    #[test]
+    fn test_fusion_keeps_branch_targets() {
+        let mut code = InstructionSet::new();
+        code.op_stack_check(2);
+        code.op_local_get(1);
+        // a loop header can't be merged into the previous instruction
+        code.op_local_get(2);
+        code.op_i32_const(5);
+        code.op_local_get(70000);
+        code.op_local_get(1);
+        code.op_br_if_nez(-4);
+        code.op_return();
+        let config = CompilationConfig::default().with_fused_opcodes(true);
+        let module = optimized_with_config(code, vec![], &config);
+        let mut expected = InstructionSet::new();
+        expected.op_stack_check(2);
+        expected.op_local_get(1);
+        expected.op_local_get_i32_const(2, 5);
+        expected.op_local_get(70000);
+        expected.op_local_get(1);
+        expected.op_br_if_nez(-3);
+        expected.op_return();
+        assert_eq!(module.code_section, expected);
+    }
+}
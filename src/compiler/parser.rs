@@ -3,14 +3,18 @@ use crate::{
         block_fuel::compile_block_params,
         compiled_expr::CompiledExpr,
         func_builder::FuncBuilder,
+        import_report::{analyze_imports, ImportChecker, ImportReport},
+        optimizer::optimize,
+        report::{CompilationReport, CompiledFunc, CompiledFuncKind},
         snippets::Snippet,
         translator::{InstructionTranslator, ReusableAllocations},
     },
     CompilationConfig, CompilationError, ConstructorParams, DataSegmentIdx, ElementSegmentIdx,
-    FuncIdx, FuncRef, GlobalIdx, GlobalVariable, ImportName, Opcode, RwasmModule, RwasmModuleInner,
-    TableIdx, DEFAULT_MEMORY_INDEX, SNIPPET_FUNC_IDX_UNRESOLVED,
+    FuncIdx, FuncRef, GlobalIdx, GlobalValueProvider, GlobalVariable, ImportName, InstructionSet,
+    MemoryIdx, Opcode, OptimizationLevel, RwasmDebugSection, RwasmExport, RwasmFuncName,
+    RwasmMetadata, RwasmModule, RwasmModuleInner, RwasmTag, TableIdx, SNIPPET_FUNC_IDX_UNRESOLVED,
 };
-use alloc::{boxed::Box, vec::Vec};
+use alloc::{boxed::Box, vec, vec::Vec};
 use core::{
     mem::{replace, take},
     ops::Range,
@@ -19,8 +23,9 @@ use hashbrown::HashMap;
 use wasmparser::{
     CustomSectionReader, DataKind, DataSectionReader, ElementItems, ElementKind,
     ElementSectionReader, Encoding, ExportSectionReader, ExternalKind, FuncType, FunctionBody,
-    FunctionSectionReader, GlobalSectionReader, ImportSectionReader, MemorySectionReader, Parser,
-    Payload, TableSectionReader, Type, TypeRef, TypeSectionReader, ValType, Validator,
+    FunctionSectionReader, GlobalSectionReader, GlobalType, ImportSectionReader,
+    MemorySectionReader, MemoryType, Name, NameSectionReader, Parser, Payload, TableSectionReader,
+    TableType, Type, TypeRef, TypeSectionReader, Validator,
 };
 
 /// Single-pass Wasm front-end that validates, translates, and assembles rwasm bytecode.
@@ -31,10 +36,15 @@ pub struct ModuleParser {
     validator: Validator,
     /// The number of compiled or processed functions.
     compiled_funcs: u32,
+    /// The number of imported functions, they go first in the function index space.
+    imported_funcs: u32,
     /// Reusable allocations for validating and translation functions.
     allocations: ReusableAllocations,
     /// A compilation config
     config: CompilationConfig,
+    /// Function names from the `name` custom section, used by the debug section and the
+    /// compilation errors.
+    func_names: Vec<RwasmFuncName>,
 }
 
 impl ModuleParser {
@@ -42,8 +52,10 @@ impl ModuleParser {
         Self {
             validator: Validator::new_with_features(config.wasm_features()),
             compiled_funcs: 0,
+            imported_funcs: 0,
             allocations: ReusableAllocations::default(),
             config,
+            func_names: Vec::new(),
         }
     }
 
@@ -93,10 +105,46 @@ impl ModuleParser {
         Ok(result)
     }
 
+    /// Checks the imports of the Wasm binary against the import linker and the limits of the
+    /// config without translating it and reports every import that fails to resolve, while the
+    /// compilation fails at the first one.
+    ///
+    /// # Errors
+    ///
+    /// If the type or the import section is malformed.
+    pub fn analyze_imports(
+        wasm_binary: &[u8],
+        config: &CompilationConfig,
+    ) -> Result<ImportReport, CompilationError> {
+        analyze_imports(wasm_binary, config)
+    }
+
     pub fn finalize(
-        mut self,
+        self,
         wasm_binary: &[u8],
     ) -> Result<(RwasmModule, ConstructorParams), CompilationError> {
+        let (module, constructor_params, _) = self.finalize_module(wasm_binary)?;
+        Ok((module, constructor_params))
+    }
+
+    /// Finalizes the module like [`Self::finalize`] and collects the [`CompilationReport`].
+    pub fn finalize_with_report(
+        self,
+        wasm_binary: &[u8],
+    ) -> Result<(RwasmModule, ConstructorParams, CompilationReport), CompilationError> {
+        let (module, constructor_params, compiled_funcs) = self.finalize_module(wasm_binary)?;
+        let report = CompilationReport::new(&module, &compiled_funcs);
+        Ok((module, constructor_params, report))
+    }
+
+    /// Assembles the module, also returns the functions of the code section sorted by their
+    /// offsets.
+    fn finalize_module(
+        mut self,
+        wasm_binary: &[u8],
+    ) -> Result<(RwasmModule, ConstructorParams, Vec<CompiledFunc>), CompilationError> {
+        // the functions emitted after the Wasm functions are snippets
+        let wasm_funcs = self.compiled_funcs;
         if let Some(start_func) = self.allocations.translation.start_func {
             if !self.config.allow_start_section {
                 return Err(CompilationError::StartSectionsAreNotAllowed);
@@ -144,6 +192,26 @@ impl ModuleParser {
             .segment_builder
             .entrypoint_bytecode
             .finalize(true);
+        if self.config.eliminate_dead_functions {
+            self.eliminate_dead_functions();
+        }
+        let entrypoint_length = self
+            .allocations
+            .translation
+            .segment_builder
+            .entrypoint_bytecode
+            .len() as u32;
+
+        let export_section = if self.config.emit_export_section {
+            self.collect_exports(entrypoint_length)
+        } else {
+            Vec::new()
+        };
+        let debug_section = if self.config.emit_debug_section {
+            self.collect_debug_section(entrypoint_length)
+        } else {
+            RwasmDebugSection::default()
+        };
 
         // merge the entrypoint with our code section
         let mut code_section = self
@@ -151,7 +219,6 @@ impl ModuleParser {
             .translation
             .segment_builder
             .entrypoint_bytecode;
-        let entrypoint_length = code_section.len() as u32;
         code_section.extend(self.allocations.translation.instruction_set.iter());
 
         // TODO(dmitry123): "optimize it"
@@ -170,6 +237,11 @@ impl ModuleParser {
             }
         }
 
+        let metadata_section = self
+            .config
+            .emit_metadata_section
+            .then(|| RwasmMetadata::new(&self.config, &code_section));
+
         let mut element_section = self
             .allocations
             .translation
@@ -182,7 +254,30 @@ impl ModuleParser {
             }
         }
 
-        let module = RwasmModuleInner {
+        let mut compiled_funcs = self
+            .allocations
+            .translation
+            .func_offsets
+            .iter()
+            .enumerate()
+            .map(|(func_idx, func_offset)| {
+                let func_idx = func_idx as FuncIdx;
+                let kind = if func_idx < self.imported_funcs {
+                    CompiledFuncKind::ImportTrampoline
+                } else if func_idx < wasm_funcs {
+                    CompiledFuncKind::Wasm
+                } else {
+                    CompiledFuncKind::Snippet
+                };
+                CompiledFunc {
+                    func_idx,
+                    kind,
+                    offset: *func_offset + entrypoint_length,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let mut module = RwasmModuleInner {
             code_section,
             data_section: self
                 .allocations
@@ -192,10 +287,158 @@ impl ModuleParser {
             elem_section: element_section,
             hint_section: wasm_binary.to_vec(),
             source_pc,
+            export_section,
+            debug_section,
+            metadata_section,
+            tag_section: take(&mut self.allocations.translation.tags),
         };
+        if self.config.optimize >= OptimizationLevel::Peephole || self.config.fused_opcodes {
+            let mut func_offsets = compiled_funcs
+                .iter()
+                .map(|compiled_func| compiled_func.offset)
+                .collect::<Vec<_>>();
+            optimize(&mut module, &self.config, &mut func_offsets);
+            for (compiled_func, func_offset) in compiled_funcs.iter_mut().zip(func_offsets) {
+                compiled_func.offset = func_offset;
+            }
+        }
         let constructor_params = self.allocations.translation.constructor_params;
 
-        Ok((RwasmModule::from(module), constructor_params))
+        Ok((
+            RwasmModule::from(module),
+            constructor_params,
+            compiled_funcs,
+        ))
+    }
+
+    /// Removes functions (including import trampolines and snippets) that can't be reached from
+    /// the entrypoint, the element section or the exports.
+    ///
+    /// Functions are moved as a whole, so branch offsets inside them stay valid, only
+    /// `func_offsets` and the source map are updated, calls are relocated by `finalize` later.
+    fn eliminate_dead_functions(&mut self) {
+        let translation = &mut self.allocations.translation;
+        let func_offsets = &translation.func_offsets;
+        let func_code = |func_idx: usize| {
+            let end = func_offsets
+                .get(func_idx + 1)
+                .map(|offset| *offset as usize)
+                .unwrap_or(translation.instruction_set.len());
+            func_offsets[func_idx] as usize..end
+        };
+        let mut reachable = vec![false; func_offsets.len()];
+        let mut queue = Vec::new();
+        let mut visit = |compiled_func: u32, queue: &mut Vec<usize>| {
+            // compiled functions are indexed from 1, 0 is a null reference
+            let Some(func_idx) = (compiled_func as usize).checked_sub(1) else {
+                return;
+            };
+            if let Some(reachable) = reachable.get_mut(func_idx) {
+                if !*reachable {
+                    *reachable = true;
+                    queue.push(func_idx);
+                }
+            }
+        };
+        let roots = translation
+            .segment_builder
+            .entrypoint_bytecode
+            .iter()
+            .filter_map(|opcode| match opcode {
+                Opcode::CallInternal(compiled_func)
+                | Opcode::ReturnCallInternal(compiled_func)
+                | Opcode::RefFunc(compiled_func) => Some(*compiled_func),
+                _ => None,
+            })
+            .chain(
+                translation
+                    .segment_builder
+                    .global_element_section
+                    .iter()
+                    .copied(),
+            )
+            .chain(
+                translation
+                    .exported_funcs
+                    .values()
+                    .filter(|_| self.config.emit_export_section)
+                    .map(|func_idx| *func_idx + 1),
+            );
+        for compiled_func in roots {
+            visit(compiled_func, &mut queue);
+        }
+        while let Some(func_idx) = queue.pop() {
+            for opcode in &translation.instruction_set[func_code(func_idx)] {
+                match opcode {
+                    Opcode::CallInternal(compiled_func)
+                    | Opcode::ReturnCallInternal(compiled_func)
+                    | Opcode::RefFunc(compiled_func) => visit(*compiled_func, &mut queue),
+                    _ => {}
+                }
+            }
+        }
+        if reachable.iter().all(|reachable| *reachable) {
+            return;
+        }
+
+        let mut instruction_set = InstructionSet::new();
+        let mut new_func_offsets = Vec::with_capacity(func_offsets.len());
+        for (func_idx, reachable) in reachable.iter().enumerate() {
+            // offsets of removed functions are never referenced
+            new_func_offsets.push(instruction_set.len() as u32);
+            if *reachable {
+                instruction_set.extend(translation.instruction_set[func_code(func_idx)].iter());
+            }
+        }
+        let func_at = |pc: u32| func_offsets.partition_point(|offset| *offset <= pc) - 1;
+        translation.source_map.retain_mut(|entry| {
+            let func_idx = func_at(entry.start_pc);
+            let shift = func_offsets[func_idx] - new_func_offsets[func_idx];
+            entry.start_pc -= shift;
+            entry.end_pc -= shift;
+            reachable[func_idx]
+        });
+        self.func_names
+            .retain(|func_name| reachable.get(func_name.func_idx as usize) == Some(&true));
+        translation.instruction_set = instruction_set;
+        translation.func_offsets = new_func_offsets;
+    }
+
+    /// Collects all exported functions with their final offsets inside the code section.
+    ///
+    /// Exports are sorted by name to keep the output deterministic.
+    fn collect_exports(&self, entrypoint_length: u32) -> Vec<RwasmExport> {
+        let translation = &self.allocations.translation;
+        let mut exports = translation
+            .exported_funcs
+            .iter()
+            .map(|(name, func_idx)| {
+                let func_offset = translation.func_offsets[*func_idx as usize] + entrypoint_length;
+                let func_type_idx = translation.resolve_func_type_index(*func_idx);
+                let func_type = translation
+                    .func_type_registry
+                    .resolve_original_func_type(func_type_idx);
+                RwasmExport::new(name.clone(), func_offset, func_type)
+            })
+            .collect::<Vec<_>>();
+        exports.sort_by(|a, b| a.name.cmp(&b.name));
+        exports
+    }
+
+    /// Collects the source map with final PCs inside the code section and function names.
+    fn collect_debug_section(&mut self, entrypoint_length: u32) -> RwasmDebugSection {
+        let mut source_map = take(&mut self.allocations.translation.source_map);
+        for entry in source_map.iter_mut() {
+            entry.start_pc += entrypoint_length;
+            entry.end_pc += entrypoint_length;
+        }
+        let mut func_names = take(&mut self.func_names);
+        func_names.sort_by_key(|func_name| func_name.func_idx);
+        func_names.dedup_by_key(|func_name| func_name.func_idx);
+        RwasmDebugSection {
+            source_map,
+            func_names,
+        }
     }
 
     pub fn emit_state_router(&mut self) -> Result<(), CompilationError> {
@@ -204,6 +447,12 @@ impl ModuleParser {
         let Some(state_router) = &self.config.state_router else {
             return Ok(());
         };
+        // reserve the stack for the state and its comparison with a state value
+        self.allocations
+            .translation
+            .segment_builder
+            .entrypoint_bytecode
+            .op_stack_check(3);
         // push state on the stack
         if let Some(opcode) = &state_router.opcode {
             self.allocations
@@ -405,6 +654,100 @@ impl ModuleParser {
         Ok(())
     }
 
+    /// Resolves an imported global with the import linker, a global the linker doesn't declare
+    /// is initialized with [`CompilationConfig::default_imported_global_value`].
+    ///
+    /// # Errors
+    ///
+    /// - If the global is declared with another type or mutability.
+    /// - If the global is neither declared nor has a default value.
+    fn process_imported_global(
+        &mut self,
+        checker: &mut ImportChecker,
+        import_name: &ImportName,
+        global_type: GlobalType,
+    ) -> Result<(), CompilationError> {
+        let global_index = self.allocations.translation.globals.len() as u32;
+        let linker_global = checker
+            .check_global(&self.config, import_name, global_type)
+            .ok_or_else(|| checker.error())?;
+        let GlobalValueProvider::Const(default_value) = linker_global.value else {
+            // the value is set by the host, so the entrypoint doesn't initialize it
+            let global_word = linker_global.global_word().unwrap();
+            self.allocations
+                .translation
+                .host_globals
+                .insert(global_index, global_word);
+            self.allocations
+                .translation
+                .globals
+                .push(GlobalVariable::new(global_type, 0));
+            return Ok(());
+        };
+        let global_variable = GlobalVariable::new(global_type, default_value);
+        self.allocations
+            .translation
+            .segment_builder
+            .add_global_variable(global_index, &global_variable)?;
+        self.allocations.translation.globals.push(global_variable);
+        Ok(())
+    }
+
+    /// Resolves an imported linear memory with the import linker, the memory gets the limits
+    /// declared by the linker and its pages are allocated by the host.
+    ///
+    /// # Errors
+    ///
+    /// - If the linker doesn't declare the memory.
+    /// - If the declared limits don't match the imported ones.
+    /// - If the initial pages exceed [`CompilationConfig::max_allowed_memory_pages`].
+    fn process_imported_memory(
+        &mut self,
+        checker: &mut ImportChecker,
+        import_name: &ImportName,
+        memory_type: MemoryType,
+    ) -> Result<(), CompilationError> {
+        let linker_memory = checker
+            .check_memory(&self.config, import_name, memory_type)
+            .ok_or_else(|| checker.error())?;
+        // the checker has already limited the initial pages
+        self.allocations
+            .translation
+            .segment_builder
+            .add_imported_memory_pages(
+                linker_memory.initial as u32,
+                self.config.max_allowed_memory_pages,
+            )?;
+        self.allocations.translation.memories.push(linker_memory);
+        Ok(())
+    }
+
+    /// Resolves an imported table with the import linker, the table gets the limits declared by
+    /// the linker and its elements are allocated by the host.
+    ///
+    /// # Errors
+    ///
+    /// - If the linker doesn't declare the table.
+    /// - If the declared element type or limits don't match the imported ones.
+    /// - If the initial elements exceed [`crate::N_MAX_TABLE_SIZE`].
+    fn process_imported_table(
+        &mut self,
+        checker: &mut ImportChecker,
+        import_name: &ImportName,
+        table_type: TableType,
+    ) -> Result<(), CompilationError> {
+        let linker_table = checker
+            .check_table(&self.config, import_name, table_type)
+            .ok_or_else(|| checker.error())?;
+        let table_index = TableIdx::try_from(self.allocations.translation.tables.len()).unwrap();
+        self.allocations
+            .translation
+            .segment_builder
+            .add_imported_table(table_index, &linker_table);
+        self.allocations.translation.tables.push(linker_table);
+        Ok(())
+    }
+
     /// Processes the Wasm import section.
     ///
     /// # Note
@@ -417,53 +760,41 @@ impl ModuleParser {
     /// - If an unsupported import declaration is encountered.
     fn process_imports(&mut self, section: ImportSectionReader) -> Result<(), CompilationError> {
         self.validator.import_section(&section)?;
-        for import in section.into_iter() {
-            let import = import?;
+        let mut checker = ImportChecker::default();
+        for import in section.into_iter_with_offsets() {
+            let (import_offset, import) = import?;
+            let import_name = ImportName::new(import.module, import.name);
             let func_type_index = match import.ty {
                 TypeRef::Func(func_type_index) => func_type_index,
                 TypeRef::Global(global_type) => {
-                    let Some(default_value) = self.config.default_imported_global_value else {
-                        return Err(CompilationError::NotSupportedImportType);
-                    };
-                    let global_index = self.allocations.translation.globals.len() as u32;
-                    let global_variable = GlobalVariable::new(global_type, default_value);
-                    self.allocations
-                        .translation
-                        .segment_builder
-                        .add_global_variable(global_index, &global_variable)?;
-                    self.allocations.translation.globals.push(global_variable);
+                    self.process_imported_global(&mut checker, &import_name, global_type)?;
                     continue;
                 }
-                _ => return Err(CompilationError::NotSupportedImportType),
-            };
-            let import_name = ImportName::new(import.module, import.name);
-            let Some(import_linker) = self.config.import_linker.as_ref() else {
-                // Do we need to process imports if there is no import linker?
-                return Err(CompilationError::UnresolvedImportFunction);
+                TypeRef::Memory(memory_type) => {
+                    self.process_imported_memory(&mut checker, &import_name, memory_type)?;
+                    continue;
+                }
+                TypeRef::Table(table_type) => {
+                    self.process_imported_table(&mut checker, &import_name, table_type)?;
+                    continue;
+                }
+                _ => {
+                    checker.check_unsupported();
+                    return Err(checker.error());
+                }
             };
-            let import_linker_entity = import_linker
-                .resolve_by_import_name(&import_name)
-                .cloned()
-                .ok_or(CompilationError::UnresolvedImportFunction)?;
             // verify an imported function type
             let func_type = self
                 .allocations
                 .translation
                 .func_type_registry
                 .resolve_original_func_type(func_type_index);
-            if !import_linker_entity.matches_func_type(func_type) {
-                return Err(CompilationError::MalformedImportFunctionType);
-            }
-            // don't allow funcref/externref in imported functions
-            if !self.config.allow_func_ref_function_types {
-                for x in func_type.params().iter().chain(func_type.results()) {
-                    if x == &ValType::FuncRef || x == &ValType::ExternRef {
-                        return Err(CompilationError::MalformedImportFunctionType);
-                    }
-                }
-            }
+            let import_linker_entity = checker
+                .check_func(&self.config, &import_name, func_type)
+                .ok_or_else(|| checker.error())?;
             // inject an import function trampoline to support reffunc
             let func_idx = self.next_func();
+            self.imported_funcs += 1;
             self.allocations
                 .translation
                 .compiled_funcs
@@ -485,8 +816,16 @@ impl ModuleParser {
                 self.config.consume_fuel_for_bulk_ops,
                 self.config.consume_fuel_for_params_and_locals,
                 self.config.max_allowed_memory_pages,
+                self.config.fuel_cost_model.clone(),
+                self.config.fuel_metering_mode,
             );
             translator.prepare(func_idx)?;
+            if self.config.emit_debug_section {
+                // an import trampoline is mapped to the import entry
+                translator
+                    .alloc
+                    .begin_source_location(func_idx, import_offset);
+            }
             let signature_index = translator
                 .alloc
                 .func_type_registry
@@ -494,10 +833,13 @@ impl ModuleParser {
             translator.alloc.instruction_set.op_stack_check(u32::MAX);
 
             if self.config.builtins_consume_fuel {
-                compile_block_params(
+                let max_stack_height = compile_block_params(
                     &mut translator.alloc.instruction_set,
                     import_linker_entity.syscall_fuel_param,
-                )
+                );
+                // the fuel is calculated on top of the syscall params
+                translator.stack_height.push_n(max_stack_height);
+                translator.stack_height.pop_n(max_stack_height);
             }
 
             translator
@@ -505,6 +847,9 @@ impl ModuleParser {
                 .instruction_set
                 .op_call(import_linker_entity.sys_func_idx);
             translator.alloc.instruction_set.op_return();
+            if self.config.emit_debug_section {
+                translator.alloc.end_source_location();
+            }
             translator.finish()?;
             let _ = replace(
                 &mut self.allocations,
@@ -567,9 +912,10 @@ impl ModuleParser {
     /// If a table declaration fails to validate.
     fn process_tables(&mut self, section: TableSectionReader) -> Result<(), CompilationError> {
         self.validator.table_section(&section)?;
-        for (table_idx, table_type) in section.into_iter().enumerate() {
+        for table_type in section.into_iter() {
             let table_type = table_type?;
-            let table_idx = TableIdx::try_from(table_idx).unwrap();
+            // imported tables come first
+            let table_idx = TableIdx::try_from(self.allocations.translation.tables.len()).unwrap();
             self.allocations
                 .translation
                 .segment_builder
@@ -607,13 +953,30 @@ impl ModuleParser {
     ///
     /// # Note
     ///
-    /// This is part of the module linking a Wasm proposal and not yet supported
-    /// by `rwasm`.
+    /// This extracts all exception tags (the exception handling proposal) into the [`Module`]
+    /// under construction, the tag signatures are stored in the tag section.
+    ///
+    /// # Errors
+    ///
+    /// If a tag declaration fails to validate.
     fn process_tags(
         &mut self,
         section: wasmparser::TagSectionReader,
     ) -> Result<(), CompilationError> {
-        self.validator.tag_section(&section).map_err(Into::into)
+        self.validator.tag_section(&section)?;
+        for tag in section.into_iter() {
+            let tag = tag?;
+            let func_type = self
+                .allocations
+                .translation
+                .func_type_registry
+                .resolve_original_func_type(tag.func_type_idx);
+            self.allocations
+                .translation
+                .tags
+                .push(RwasmTag::new(func_type));
+        }
+        Ok(())
     }
 
     /// Process module global variable declarations.
@@ -786,9 +1149,6 @@ impl ModuleParser {
                     memory_index,
                     offset_expr,
                 } => {
-                    if memory_index != DEFAULT_MEMORY_INDEX {
-                        return Err(CompilationError::NonDefaultMemoryIndex);
-                    }
                     let compiled_expr = CompiledExpr::new(offset_expr);
                     // We can fail-fast here because we already that know that there an overflow
                     let data_offset = u32::try_from(self.eval_const(compiled_expr)?)
@@ -796,7 +1156,12 @@ impl ModuleParser {
                     self.allocations
                         .translation
                         .segment_builder
-                        .add_active_memory(data_segment_idx, data_offset, data.data);
+                        .add_active_memory(
+                            MemoryIdx::try_from(memory_index).unwrap(),
+                            data_segment_idx,
+                            data_offset,
+                            data.data,
+                        );
                 }
                 DataKind::Passive => self
                     .allocations
@@ -812,6 +1177,15 @@ impl ModuleParser {
         compiled_expr
             .eval_with_context(
                 |global_index| {
+                    // the values of host globals are unknown at compile time
+                    if self
+                        .allocations
+                        .translation
+                        .host_globals
+                        .contains_key(&global_index)
+                    {
+                        return None;
+                    }
                     self.allocations
                         .translation
                         .globals
@@ -827,6 +1201,9 @@ impl ModuleParser {
         &mut self,
         reader: CustomSectionReader,
     ) -> Result<(), CompilationError> {
+        if reader.name() == "name" {
+            self.parse_func_names(&reader);
+        }
         self.allocations
             .translation
             .constructor_params
@@ -834,6 +1211,29 @@ impl ModuleParser {
         Ok(())
     }
 
+    /// Parses function names from the `name` custom section.
+    ///
+    /// The name section is optional, so it's not validated, and a malformed one is ignored
+    /// starting from the first error.
+    fn parse_func_names(&mut self, reader: &CustomSectionReader) {
+        for name in NameSectionReader::new(reader.data(), reader.data_offset()) {
+            let name_map = match name {
+                Ok(Name::Function(name_map)) => name_map,
+                Ok(_) => continue,
+                Err(_) => break,
+            };
+            for naming in name_map {
+                let Ok(naming) = naming else {
+                    break;
+                };
+                self.func_names.push(RwasmFuncName {
+                    func_idx: naming.index,
+                    name: naming.name.into(),
+                });
+            }
+        }
+    }
+
     /// Process module code section start.
     ///
     /// # Note
@@ -871,9 +1271,29 @@ impl ModuleParser {
     ///
     /// # Errors
     ///
-    /// If the function body fails to validate.
+    /// If the function body fails to validate, the error carries the function context (see
+    /// [`CompilationError::InFunction`]).
     fn process_code_entry(&mut self, func_body: FunctionBody) -> Result<(), CompilationError> {
         let func_idx = self.next_func();
+        self.translate_code_entry(func_idx, func_body)
+            .map_err(|err| {
+                let mut err = err.in_function(func_idx, None, None);
+                if let CompilationError::InFunction(context) = &mut err {
+                    context.func_name = self
+                        .func_names
+                        .iter()
+                        .find(|func_name| func_name.func_idx == func_idx)
+                        .map(|func_name| func_name.name.clone());
+                }
+                err
+            })
+    }
+
+    fn translate_code_entry(
+        &mut self,
+        func_idx: FuncIdx,
+        func_body: FunctionBody,
+    ) -> Result<(), CompilationError> {
         // #[cfg(feature = "debug-print")]
         // println!("\nfunc_idx={}", func_idx);
         let allocations = take(&mut self.allocations);
@@ -889,6 +1309,9 @@ impl ModuleParser {
             self.config.consume_fuel_for_bulk_ops,
             self.config.consume_fuel_for_params_and_locals,
             self.config.max_allowed_memory_pages,
+            self.config.fuel_cost_model.clone(),
+            self.config.fuel_metering_mode,
+            self.config.emit_debug_section,
         )
         .translate()?;
         let _ = replace(&mut self.allocations, allocations);
@@ -0,0 +1,149 @@
+use crate::{FuncIdx, MaxStackHeight, Opcode, RwasmModule};
+use alloc::{boxed::Box, collections::BTreeMap, format, vec::Vec};
+
+/// The origin of a function of the code section.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum CompiledFuncKind {
+    /// A trampoline that calls an imported function.
+    ImportTrampoline,
+    /// A function of the Wasm module.
+    Wasm,
+    /// A code snippet shared by the lowered operators (see
+    /// [`crate::CompilationConfig::code_snippets`]).
+    Snippet,
+}
+
+/// The location of a function inside the code section of a compiled module.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct CompiledFunc {
+    pub(crate) func_idx: FuncIdx,
+    pub(crate) kind: CompiledFuncKind,
+    pub(crate) offset: u32,
+}
+
+/// The statistics of a function of the code section.
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct FuncStats {
+    /// The number of instructions.
+    pub len: u32,
+    /// The number of instructions of every opcode, the keys are the opcode names (e.g. `I32Add`).
+    pub opcode_counts: BTreeMap<Box<str>, u32>,
+    /// The number of calls of code snippets.
+    pub snippet_calls: u32,
+    /// The sum of all `ConsumeFuel` instructions, the fuel charged dynamically for bulk
+    /// operations isn't included.
+    pub consume_fuel: u64,
+    /// The maximal height of all `StackCheck` instructions.
+    pub max_stack_check: MaxStackHeight,
+}
+
+impl FuncStats {
+    fn new(code: &[Opcode], snippets: &[u32]) -> Self {
+        let mut stats = FuncStats {
+            len: code.len() as u32,
+            ..Default::default()
+        };
+        let mut opcode_counts = BTreeMap::<u32, (Opcode, u32)>::new();
+        for opcode in code {
+            opcode_counts.entry(opcode.code()).or_insert((*opcode, 0)).1 += 1;
+            match *opcode {
+                Opcode::ConsumeFuel(fuel) => stats.consume_fuel += fuel as u64,
+                Opcode::StackCheck(height) => {
+                    stats.max_stack_check = stats.max_stack_check.max(height)
+                }
+                Opcode::CallInternal(target) | Opcode::ReturnCallInternal(target)
+                    if snippets.binary_search(&target).is_ok() =>
+                {
+                    stats.snippet_calls += 1
+                }
+                _ => {}
+            }
+        }
+        stats.opcode_counts = opcode_counts
+            .into_values()
+            .map(|(opcode, count)| (format!("{:#}", opcode).into(), count))
+            .collect();
+        stats
+    }
+}
+
+/// The statistics of a function of the code section, see [`CompilationReport::funcs`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct FuncReport {
+    /// The index of the function, snippets go after all the Wasm functions.
+    pub func_idx: FuncIdx,
+    /// The origin of the function.
+    pub kind: CompiledFuncKind,
+    /// The offset of the function inside the code section.
+    pub offset: u32,
+    /// The statistics of the function code.
+    pub stats: FuncStats,
+}
+
+/// The statistics of a compiled module returned by [`RwasmModule::compile_with_report`].
+///
+/// All the numbers describe the final module, after the dead function elimination and the
+/// optimizations, so reports of two builds can be compared to find out why the module got larger
+/// or more expensive.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct CompilationReport {
+    /// The entrypoint generated by the segment builder (memory, table and global initialization,
+    /// the state router and the entrypoint call).
+    pub entrypoint: FuncStats,
+    /// The functions in the order of the code section, eliminated functions aren't included.
+    pub funcs: Vec<FuncReport>,
+    /// The statistics of the whole code section.
+    pub total: FuncStats,
+    /// The length of the data section in bytes.
+    pub data_section_len: usize,
+    /// The number of entries of the element section.
+    pub elem_section_len: usize,
+}
+
+impl CompilationReport {
+    /// Collects the statistics of the module, the functions must be sorted by their offsets.
+    pub(crate) fn new(module: &RwasmModule, funcs: &[CompiledFunc]) -> Self {
+        let code = &module.code_section[..];
+        let entrypoint_len = funcs
+            .first()
+            .map_or(code.len(), |func| func.offset as usize);
+        // the offset of an eliminated function is the offset of the next one
+        let funcs = funcs
+            .iter()
+            .enumerate()
+            .map(|(index, func)| {
+                let end = funcs
+                    .get(index + 1)
+                    .map_or(code.len(), |next| next.offset as usize);
+                (func, &code[func.offset as usize..end])
+            })
+            .filter(|(_, func_code)| !func_code.is_empty())
+            .collect::<Vec<_>>();
+        let snippets = funcs
+            .iter()
+            .filter(|(func, _)| func.kind == CompiledFuncKind::Snippet)
+            .map(|(func, _)| func.offset)
+            .collect::<Vec<_>>();
+        let funcs = funcs
+            .into_iter()
+            .map(|(func, func_code)| FuncReport {
+                func_idx: func.func_idx,
+                kind: func.kind,
+                offset: func.offset,
+                stats: FuncStats::new(func_code, &snippets),
+            })
+            .collect();
+        Self {
+            entrypoint: FuncStats::new(&code[..entrypoint_len], &snippets),
+            funcs,
+            total: FuncStats::new(code, &snippets),
+            data_section_len: module.data_section.len(),
+            elem_section_len: module.elem_section.len(),
+        }
+    }
+
+    /// Returns the statistics of the function with the given index.
+    pub fn func(&self, func_idx: FuncIdx) -> Option<&FuncReport> {
+        self.funcs.iter().find(|func| func.func_idx == func_idx)
+    }
+}
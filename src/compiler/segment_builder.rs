@@ -1,6 +1,6 @@
 use crate::{
     instruction_set, CompilationError, DataSegmentIdx, ElementSegmentIdx, GlobalIdx,
-    GlobalVariable, I64ValueSplit, InstructionSet, TableIdx, DEFAULT_MEMORY_INDEX, NULL_FUNC_IDX,
+    GlobalVariable, I64ValueSplit, InstructionSet, MemoryIdx, TableIdx, TrapCode, NULL_FUNC_IDX,
     N_BYTES_PER_MEMORY_PAGE,
 };
 use alloc::{vec, vec::Vec};
@@ -14,6 +14,8 @@ pub struct SegmentBuilder {
     pub(crate) global_element_section: Vec<u32>,
     pub(crate) element_sections: HashMap<ElementSegmentIdx, (u32, u32)>,
     pub(crate) total_allocated_pages: u32,
+    /// The initial number of pages of each memory.
+    pub(crate) memory_pages: Vec<u32>,
     pub(crate) entrypoint_bytecode: InstructionSet,
 }
 
@@ -35,6 +37,7 @@ impl Default for SegmentBuilder {
             global_element_section: vec![],
             element_sections: Default::default(),
             total_allocated_pages: 0,
+            memory_pages: vec![],
             entrypoint_bytecode,
         }
     }
@@ -68,32 +71,88 @@ impl SegmentBuilder {
         Ok(())
     }
 
+    /// Adds the next memory with the given number of initial pages.
+    ///
     /// Max stack height: 3
     pub fn add_memory_pages(
         &mut self,
         initial_pages: u32,
         max_allowed_memory_pages: u32,
     ) -> Result<(), CompilationError> {
-        // there is a hard limit of max possible memory used (~64 mB)
-        let next_pages = self
-            .total_allocated_pages.saturating_add(initial_pages);
-        if next_pages >= max_allowed_memory_pages {
-            return Err(CompilationError::MaxReadonlyDataReached);
-        }
+        let memory_index = MemoryIdx::try_from(self.memory_pages.len()).unwrap();
+        self.push_memory_pages(initial_pages, max_allowed_memory_pages)?;
         // it makes no sense to grow memory with 0 pages
         if initial_pages > 0 {
             // TODO(dmitry123): "add stack height check?"
             self.entrypoint_bytecode.op_i32_const(initial_pages);
+            let start = self.entrypoint_bytecode.loc();
             self.entrypoint_bytecode.op_memory_grow_checked(None, false);
+            self.entrypoint_bytecode.retarget_memory(start, memory_index);
             // there is no need to verify for a potential trap because it can't overflow,
             // we have this check upper during the compilation time
             self.entrypoint_bytecode.op_drop();
         }
+        Ok(())
+    }
+
+    /// Adds the next memory imported with the given number of initial pages, the pages are
+    /// allocated by the host, so they're only counted towards the limit, and the entrypoint traps
+    /// with [`TrapCode::MemoryOutOfBounds`] if the host hasn't allocated them.
+    ///
+    /// Max stack height: 2
+    pub fn add_imported_memory_pages(
+        &mut self,
+        initial_pages: u32,
+        max_allowed_memory_pages: u32,
+    ) -> Result<(), CompilationError> {
+        let memory_index = MemoryIdx::try_from(self.memory_pages.len()).unwrap();
+        self.push_memory_pages(initial_pages, max_allowed_memory_pages)?;
+        if initial_pages > 0 {
+            self.entrypoint_bytecode.op_stack_check(2);
+            let start = self.entrypoint_bytecode.loc();
+            self.entrypoint_bytecode.op_memory_size();
+            self.entrypoint_bytecode.retarget_memory(start, memory_index);
+            self.emit_initial_size_check(initial_pages, TrapCode::MemoryOutOfBounds);
+        }
+        Ok(())
+    }
+
+    /// Counts the initial pages of the next memory towards the limit of all memories.
+    fn push_memory_pages(
+        &mut self,
+        initial_pages: u32,
+        max_allowed_memory_pages: u32,
+    ) -> Result<(), CompilationError> {
+        let next_pages = self.total_allocated_pages.saturating_add(initial_pages);
+        if memory_pages_limit_reached(next_pages, max_allowed_memory_pages) {
+            return Err(CompilationError::MaxMemoryPagesReached);
+        }
         // increase the total number of pages allocated
         self.total_allocated_pages = next_pages;
+        self.memory_pages.push(initial_pages);
         Ok(())
     }
 
+    /// Makes the entrypoint trap with [`TrapCode::TableOutOfBounds`] if the host hasn't grown the
+    /// imported table to its initial elements.
+    ///
+    /// Max stack height: 2
+    pub fn add_imported_table(&mut self, table_index: TableIdx, table_type: &TableType) {
+        if table_type.initial > 0 {
+            self.entrypoint_bytecode.op_stack_check(2);
+            self.entrypoint_bytecode.op_table_size(table_index);
+            self.emit_initial_size_check(table_type.initial, TrapCode::TableOutOfBounds);
+        }
+    }
+
+    /// Pops the size of an imported entity and traps if it's less than the initial size.
+    fn emit_initial_size_check(&mut self, initial_size: u32, trap_code: TrapCode) {
+        self.entrypoint_bytecode.op_i32_const(initial_size);
+        self.entrypoint_bytecode.op_i32_lt_u();
+        self.entrypoint_bytecode.op_br_if_eqz(2);
+        self.entrypoint_bytecode.op_trap(trap_code);
+    }
+
     pub fn emit_table_segment(
         &mut self,
         table_index: TableIdx,
@@ -108,14 +167,25 @@ impl SegmentBuilder {
         Ok(())
     }
 
-    pub fn add_active_memory(&mut self, segment_idx: DataSegmentIdx, offset: u32, bytes: &[u8]) {
-        // don't allow growing default memory if there are no enough pages allocated
+    pub fn add_active_memory(
+        &mut self,
+        memory_index: MemoryIdx,
+        segment_idx: DataSegmentIdx,
+        offset: u32,
+        bytes: &[u8],
+    ) {
+        // don't allow growing memory if there are no enough pages allocated
+        let memory_pages = self
+            .memory_pages
+            .get(memory_index as usize)
+            .copied()
+            .unwrap_or_default();
         let has_memory_overflow = || -> Option<bool> {
             let max_affected_page = offset
                 .checked_add(bytes.len() as u32)?
                 .checked_add(N_BYTES_PER_MEMORY_PAGE - 1)?
                 .checked_div(N_BYTES_PER_MEMORY_PAGE)?;
-            Some(max_affected_page > self.total_allocated_pages)
+            Some(max_affected_page > memory_pages)
         };
         // expand default memory
         let data_offset = self.global_memory_section.len();
@@ -130,8 +200,9 @@ impl SegmentBuilder {
             self.entrypoint_bytecode.op_i32_const(data_length);
         }
         // TODO(dmitry123): "add stack height check"
-        self.entrypoint_bytecode
-            .op_memory_init(DEFAULT_MEMORY_INDEX);
+        let start = self.entrypoint_bytecode.loc();
+        self.entrypoint_bytecode.op_memory_init(0);
+        self.entrypoint_bytecode.retarget_memory(start, memory_index);
         self.entrypoint_bytecode.op_data_drop(segment_idx + 1);
         // store passive section info
         self.memory_sections
@@ -186,3 +257,9 @@ impl SegmentBuilder {
             .insert(segment_idx, (segment_offset, segment_length));
     }
 }
+
+/// Returns `true` if the total pages of all memories exceed the limit.
+pub(crate) fn memory_pages_limit_reached(total_pages: u32, max_allowed_memory_pages: u32) -> bool {
+    // there is a hard limit of max possible memory used (~64 mB) for all memories
+    total_pages >= max_allowed_memory_pages
+}
@@ -0,0 +1,1738 @@
+//! Lowering of the fixed-width SIMD proposal to 32-bit opcodes.
+//!
+//! A `v128` value takes four 32-bit stack slots, the lowest word is pushed first (like the low
+//! word of an `i64`) and the lanes are numbered from the lowest byte, so the words have the same
+//! layout as in the linear memory. Lane operations are unrolled into branch-free sequences, the
+//! cheap ones (constants, memory access, lane access, shuffles and bitwise operations) are
+//! emitted inline and the others are called as snippets.
+use crate::{
+    compiler::{lowering::Lowering, snippets::Snippet},
+    AddressOffset, InstructionSet, MaxStackHeight,
+};
+use wasmparser::{ValType, V128};
+
+/// SIMD instructions lowered to snippets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SimdOp {
+    I8x16Swizzle,
+    I8x16Eq,
+    I8x16Ne,
+    I8x16LtS,
+    I8x16LtU,
+    I8x16GtS,
+    I8x16GtU,
+    I8x16LeS,
+    I8x16LeU,
+    I8x16GeS,
+    I8x16GeU,
+    I16x8Eq,
+    I16x8Ne,
+    I16x8LtS,
+    I16x8LtU,
+    I16x8GtS,
+    I16x8GtU,
+    I16x8LeS,
+    I16x8LeU,
+    I16x8GeS,
+    I16x8GeU,
+    I32x4Eq,
+    I32x4Ne,
+    I32x4LtS,
+    I32x4LtU,
+    I32x4GtS,
+    I32x4GtU,
+    I32x4LeS,
+    I32x4LeU,
+    I32x4GeS,
+    I32x4GeU,
+    I64x2Eq,
+    I64x2Ne,
+    I64x2LtS,
+    I64x2GtS,
+    I64x2LeS,
+    I64x2GeS,
+    F32x4Eq,
+    F32x4Ne,
+    F32x4Lt,
+    F32x4Gt,
+    F32x4Le,
+    F32x4Ge,
+    F64x2Eq,
+    F64x2Ne,
+    F64x2Lt,
+    F64x2Gt,
+    F64x2Le,
+    F64x2Ge,
+    I8x16Abs,
+    I8x16Neg,
+    I8x16Popcnt,
+    I8x16AllTrue,
+    I8x16Bitmask,
+    I8x16NarrowI16x8S,
+    I8x16NarrowI16x8U,
+    I8x16Shl,
+    I8x16ShrS,
+    I8x16ShrU,
+    I8x16Add,
+    I8x16AddSatS,
+    I8x16AddSatU,
+    I8x16Sub,
+    I8x16SubSatS,
+    I8x16SubSatU,
+    I8x16MinS,
+    I8x16MinU,
+    I8x16MaxS,
+    I8x16MaxU,
+    I8x16AvgrU,
+    I16x8ExtAddPairwiseI8x16S,
+    I16x8ExtAddPairwiseI8x16U,
+    I16x8Abs,
+    I16x8Neg,
+    I16x8Q15MulrSatS,
+    I16x8AllTrue,
+    I16x8Bitmask,
+    I16x8NarrowI32x4S,
+    I16x8NarrowI32x4U,
+    I16x8ExtendLowI8x16S,
+    I16x8ExtendHighI8x16S,
+    I16x8ExtendLowI8x16U,
+    I16x8ExtendHighI8x16U,
+    I16x8Shl,
+    I16x8ShrS,
+    I16x8ShrU,
+    I16x8Add,
+    I16x8AddSatS,
+    I16x8AddSatU,
+    I16x8Sub,
+    I16x8SubSatS,
+    I16x8SubSatU,
+    I16x8Mul,
+    I16x8MinS,
+    I16x8MinU,
+    I16x8MaxS,
+    I16x8MaxU,
+    I16x8AvgrU,
+    I16x8ExtMulLowI8x16S,
+    I16x8ExtMulHighI8x16S,
+    I16x8ExtMulLowI8x16U,
+    I16x8ExtMulHighI8x16U,
+    I32x4ExtAddPairwiseI16x8S,
+    I32x4ExtAddPairwiseI16x8U,
+    I32x4Abs,
+    I32x4Neg,
+    I32x4AllTrue,
+    I32x4Bitmask,
+    I32x4ExtendLowI16x8S,
+    I32x4ExtendHighI16x8S,
+    I32x4ExtendLowI16x8U,
+    I32x4ExtendHighI16x8U,
+    I32x4Shl,
+    I32x4ShrS,
+    I32x4ShrU,
+    I32x4Add,
+    I32x4Sub,
+    I32x4Mul,
+    I32x4MinS,
+    I32x4MinU,
+    I32x4MaxS,
+    I32x4MaxU,
+    I32x4DotI16x8S,
+    I32x4ExtMulLowI16x8S,
+    I32x4ExtMulHighI16x8S,
+    I32x4ExtMulLowI16x8U,
+    I32x4ExtMulHighI16x8U,
+    I64x2Abs,
+    I64x2Neg,
+    I64x2AllTrue,
+    I64x2Bitmask,
+    I64x2ExtendLowI32x4S,
+    I64x2ExtendHighI32x4S,
+    I64x2ExtendLowI32x4U,
+    I64x2ExtendHighI32x4U,
+    I64x2Shl,
+    I64x2ShrS,
+    I64x2ShrU,
+    I64x2Add,
+    I64x2Sub,
+    I64x2Mul,
+    I64x2ExtMulLowI32x4S,
+    I64x2ExtMulHighI32x4S,
+    I64x2ExtMulLowI32x4U,
+    I64x2ExtMulHighI32x4U,
+    F32x4Ceil,
+    F32x4Floor,
+    F32x4Trunc,
+    F32x4Nearest,
+    F32x4Sqrt,
+    F32x4Add,
+    F32x4Sub,
+    F32x4Mul,
+    F32x4Div,
+    F32x4Min,
+    F32x4Max,
+    F32x4PMin,
+    F32x4PMax,
+    F64x2Ceil,
+    F64x2Floor,
+    F64x2Trunc,
+    F64x2Nearest,
+    F64x2Sqrt,
+    F64x2Add,
+    F64x2Sub,
+    F64x2Mul,
+    F64x2Div,
+    F64x2Min,
+    F64x2Max,
+    F64x2PMin,
+    F64x2PMax,
+    I32x4TruncSatF32x4S,
+    I32x4TruncSatF32x4U,
+    F32x4ConvertI32x4S,
+    F32x4ConvertI32x4U,
+    I32x4TruncSatF64x2SZero,
+    I32x4TruncSatF64x2UZero,
+    F64x2ConvertLowI32x4S,
+    F64x2ConvertLowI32x4U,
+    F32x4DemoteF64x2Zero,
+    F64x2PromoteLowF32x4,
+}
+
+impl SimdOp {
+    /// Returns the original parameter types of the snippet.
+    pub fn params(&self) -> &'static [ValType] {
+        use SimdOp::*;
+        match self {
+            I8x16Shl | I8x16ShrS | I8x16ShrU | I16x8Shl | I16x8ShrS | I16x8ShrU | I32x4Shl
+            | I32x4ShrS | I32x4ShrU | I64x2Shl | I64x2ShrS | I64x2ShrU => {
+                &[ValType::V128, ValType::I32]
+            }
+            I8x16Abs
+            | I8x16Neg
+            | I8x16Popcnt
+            | I8x16AllTrue
+            | I8x16Bitmask
+            | I16x8ExtAddPairwiseI8x16S
+            | I16x8ExtAddPairwiseI8x16U
+            | I16x8Abs
+            | I16x8Neg
+            | I16x8AllTrue
+            | I16x8Bitmask
+            | I16x8ExtendLowI8x16S
+            | I16x8ExtendHighI8x16S
+            | I16x8ExtendLowI8x16U
+            | I16x8ExtendHighI8x16U
+            | I32x4ExtAddPairwiseI16x8S
+            | I32x4ExtAddPairwiseI16x8U
+            | I32x4Abs
+            | I32x4Neg
+            | I32x4AllTrue
+            | I32x4Bitmask
+            | I32x4ExtendLowI16x8S
+            | I32x4ExtendHighI16x8S
+            | I32x4ExtendLowI16x8U
+            | I32x4ExtendHighI16x8U
+            | I64x2Abs
+            | I64x2Neg
+            | I64x2AllTrue
+            | I64x2Bitmask
+            | I64x2ExtendLowI32x4S
+            | I64x2ExtendHighI32x4S
+            | I64x2ExtendLowI32x4U
+            | I64x2ExtendHighI32x4U
+            | F32x4Ceil
+            | F32x4Floor
+            | F32x4Trunc
+            | F32x4Nearest
+            | F32x4Sqrt
+            | F64x2Ceil
+            | F64x2Floor
+            | F64x2Trunc
+            | F64x2Nearest
+            | F64x2Sqrt
+            | I32x4TruncSatF32x4S
+            | I32x4TruncSatF32x4U
+            | F32x4ConvertI32x4S
+            | F32x4ConvertI32x4U
+            | I32x4TruncSatF64x2SZero
+            | I32x4TruncSatF64x2UZero
+            | F64x2ConvertLowI32x4S
+            | F64x2ConvertLowI32x4U
+            | F32x4DemoteF64x2Zero
+            | F64x2PromoteLowF32x4 => &[ValType::V128],
+            _ => &[ValType::V128, ValType::V128],
+        }
+    }
+
+    /// Returns the original result types of the snippet.
+    pub fn results(&self) -> &'static [ValType] {
+        use SimdOp::*;
+        match self {
+            I8x16AllTrue | I8x16Bitmask | I16x8AllTrue | I16x8Bitmask | I32x4AllTrue
+            | I32x4Bitmask | I64x2AllTrue | I64x2Bitmask => &[ValType::I32],
+            _ => &[ValType::V128],
+        }
+    }
+
+    /// Returns the max stack height of the snippet.
+    pub fn max_stack_height(&self) -> MaxStackHeight {
+        self.emit(&mut InstructionSet::new())
+    }
+
+    /// Emits the snippet body and returns its max stack height.
+    pub fn emit(&self, code: &mut InstructionSet) -> MaxStackHeight {
+        use Ext::*;
+        use InstructionSet as IS;
+        use Shape::*;
+        use SimdOp::*;
+        match *self {
+            I8x16Swizzle => swizzle(code),
+            I8x16Eq => compare(code, I8, Unsigned, IS::op_i32_eq),
+            I8x16Ne => compare(code, I8, Unsigned, IS::op_i32_ne),
+            I8x16LtS => compare(code, I8, Signed, IS::op_i32_lt_s),
+            I8x16LtU => compare(code, I8, Unsigned, IS::op_i32_lt_u),
+            I8x16GtS => compare(code, I8, Signed, IS::op_i32_gt_s),
+            I8x16GtU => compare(code, I8, Unsigned, IS::op_i32_gt_u),
+            I8x16LeS => compare(code, I8, Signed, IS::op_i32_le_s),
+            I8x16LeU => compare(code, I8, Unsigned, IS::op_i32_le_u),
+            I8x16GeS => compare(code, I8, Signed, IS::op_i32_ge_s),
+            I8x16GeU => compare(code, I8, Unsigned, IS::op_i32_ge_u),
+            I16x8Eq => compare(code, I16, Unsigned, IS::op_i32_eq),
+            I16x8Ne => compare(code, I16, Unsigned, IS::op_i32_ne),
+            I16x8LtS => compare(code, I16, Signed, IS::op_i32_lt_s),
+            I16x8LtU => compare(code, I16, Unsigned, IS::op_i32_lt_u),
+            I16x8GtS => compare(code, I16, Signed, IS::op_i32_gt_s),
+            I16x8GtU => compare(code, I16, Unsigned, IS::op_i32_gt_u),
+            I16x8LeS => compare(code, I16, Signed, IS::op_i32_le_s),
+            I16x8LeU => compare(code, I16, Unsigned, IS::op_i32_le_u),
+            I16x8GeS => compare(code, I16, Signed, IS::op_i32_ge_s),
+            I16x8GeU => compare(code, I16, Unsigned, IS::op_i32_ge_u),
+            I32x4Eq => compare(code, I32, Wrap, IS::op_i32_eq),
+            I32x4Ne => compare(code, I32, Wrap, IS::op_i32_ne),
+            I32x4LtS => compare(code, I32, Wrap, IS::op_i32_lt_s),
+            I32x4LtU => compare(code, I32, Wrap, IS::op_i32_lt_u),
+            I32x4GtS => compare(code, I32, Wrap, IS::op_i32_gt_s),
+            I32x4GtU => compare(code, I32, Wrap, IS::op_i32_gt_u),
+            I32x4LeS => compare(code, I32, Wrap, IS::op_i32_le_s),
+            I32x4LeU => compare(code, I32, Wrap, IS::op_i32_le_u),
+            I32x4GeS => compare(code, I32, Wrap, IS::op_i32_ge_s),
+            I32x4GeU => compare(code, I32, Wrap, IS::op_i32_ge_u),
+            I64x2Eq => compare_i64(code, IS::op_i64_eq, IS::MSH_I64_EQ),
+            I64x2Ne => compare_i64(code, IS::op_i64_ne, IS::MSH_I64_NE),
+            I64x2LtS => compare_i64(code, IS::op_i64_lt_s, IS::MSH_I64_LT_S),
+            I64x2GtS => compare_i64(code, IS::op_i64_gt_s, IS::MSH_I64_GT_S),
+            I64x2LeS => compare_i64(code, IS::op_i64_le_s, IS::MSH_I64_LE_S),
+            I64x2GeS => compare_i64(code, IS::op_i64_ge_s, IS::MSH_I64_GE_S),
+            F32x4Eq => compare_float(code, I32, IS::op_f32_eq, Snippet::COMPARE_EQ),
+            F32x4Ne => compare_float(code, I32, IS::op_f32_ne, Snippet::COMPARE_NE),
+            F32x4Lt => compare_float(code, I32, IS::op_f32_lt, Snippet::COMPARE_LT),
+            F32x4Gt => compare_float(code, I32, IS::op_f32_gt, Snippet::COMPARE_GT),
+            F32x4Le => compare_float(code, I32, IS::op_f32_le, Snippet::COMPARE_LE),
+            F32x4Ge => compare_float(code, I32, IS::op_f32_ge, Snippet::COMPARE_GE),
+            F64x2Eq => compare_float(code, I64, IS::op_f64_eq, Snippet::COMPARE_EQ),
+            F64x2Ne => compare_float(code, I64, IS::op_f64_ne, Snippet::COMPARE_NE),
+            F64x2Lt => compare_float(code, I64, IS::op_f64_lt, Snippet::COMPARE_LT),
+            F64x2Gt => compare_float(code, I64, IS::op_f64_gt, Snippet::COMPARE_GT),
+            F64x2Le => compare_float(code, I64, IS::op_f64_le, Snippet::COMPARE_LE),
+            F64x2Ge => compare_float(code, I64, IS::op_f64_ge, Snippet::COMPARE_GE),
+            I8x16Abs => abs(code, I8),
+            I8x16Neg => neg(code, I8),
+            I8x16Popcnt => unary_lanes(code, I8, Unsigned, |l| l.unary(IS::op_i32_popcnt)),
+            I8x16AllTrue => all_true(code, I8),
+            I8x16Bitmask => bitmask(code, I8),
+            I8x16NarrowI16x8S => narrow(code, I8, true),
+            I8x16NarrowI16x8U => narrow(code, I8, false),
+            I8x16Shl => shift(code, I8, Wrap, IS::op_i32_shl),
+            I8x16ShrS => shift(code, I8, Signed, IS::op_i32_shr_s),
+            I8x16ShrU => shift(code, I8, Unsigned, IS::op_i32_shr_u),
+            I8x16Add => binary_lanes(code, I8, Wrap, |l| l.binary(IS::op_i32_add)),
+            I8x16AddSatS => add_sat(code, I8, true),
+            I8x16AddSatU => add_sat(code, I8, false),
+            I8x16Sub => binary_lanes(code, I8, Wrap, |l| l.binary(IS::op_i32_sub)),
+            I8x16SubSatS => sub_sat(code, I8, true),
+            I8x16SubSatU => sub_sat(code, I8, false),
+            I8x16MinS => min_max(code, I8, Signed, IS::op_i32_lt_s),
+            I8x16MinU => min_max(code, I8, Unsigned, IS::op_i32_lt_u),
+            I8x16MaxS => min_max(code, I8, Signed, IS::op_i32_gt_s),
+            I8x16MaxU => min_max(code, I8, Unsigned, IS::op_i32_gt_u),
+            I8x16AvgrU => avgr_u(code, I8),
+            I16x8ExtAddPairwiseI8x16S => extadd_pairwise(code, I16, Signed),
+            I16x8ExtAddPairwiseI8x16U => extadd_pairwise(code, I16, Unsigned),
+            I16x8Abs => abs(code, I16),
+            I16x8Neg => neg(code, I16),
+            I16x8Q15MulrSatS => binary_lanes(code, I16, Signed, |l| {
+                l.binary(IS::op_i32_mul);
+                l.i32_const(0x4000);
+                l.binary(IS::op_i32_add);
+                l.i32_const(15);
+                l.binary(IS::op_i32_shr_s);
+                // only `-32768 * -32768` overflows
+                l.clamp_max(i16::MAX as i32);
+            }),
+            I16x8AllTrue => all_true(code, I16),
+            I16x8Bitmask => bitmask(code, I16),
+            I16x8NarrowI32x4S => narrow(code, I16, true),
+            I16x8NarrowI32x4U => narrow(code, I16, false),
+            I16x8ExtendLowI8x16S => extend(code, I16, false, Signed),
+            I16x8ExtendHighI8x16S => extend(code, I16, true, Signed),
+            I16x8ExtendLowI8x16U => extend(code, I16, false, Unsigned),
+            I16x8ExtendHighI8x16U => extend(code, I16, true, Unsigned),
+            I16x8Shl => shift(code, I16, Wrap, IS::op_i32_shl),
+            I16x8ShrS => shift(code, I16, Signed, IS::op_i32_shr_s),
+            I16x8ShrU => shift(code, I16, Unsigned, IS::op_i32_shr_u),
+            I16x8Add => binary_lanes(code, I16, Wrap, |l| l.binary(IS::op_i32_add)),
+            I16x8AddSatS => add_sat(code, I16, true),
+            I16x8AddSatU => add_sat(code, I16, false),
+            I16x8Sub => binary_lanes(code, I16, Wrap, |l| l.binary(IS::op_i32_sub)),
+            I16x8SubSatS => sub_sat(code, I16, true),
+            I16x8SubSatU => sub_sat(code, I16, false),
+            I16x8Mul => binary_lanes(code, I16, Wrap, |l| l.binary(IS::op_i32_mul)),
+            I16x8MinS => min_max(code, I16, Signed, IS::op_i32_lt_s),
+            I16x8MinU => min_max(code, I16, Unsigned, IS::op_i32_lt_u),
+            I16x8MaxS => min_max(code, I16, Signed, IS::op_i32_gt_s),
+            I16x8MaxU => min_max(code, I16, Unsigned, IS::op_i32_gt_u),
+            I16x8AvgrU => avgr_u(code, I16),
+            I16x8ExtMulLowI8x16S => extmul(code, I16, false, Signed),
+            I16x8ExtMulHighI8x16S => extmul(code, I16, true, Signed),
+            I16x8ExtMulLowI8x16U => extmul(code, I16, false, Unsigned),
+            I16x8ExtMulHighI8x16U => extmul(code, I16, true, Unsigned),
+            I32x4ExtAddPairwiseI16x8S => extadd_pairwise(code, I32, Signed),
+            I32x4ExtAddPairwiseI16x8U => extadd_pairwise(code, I32, Unsigned),
+            I32x4Abs => abs(code, I32),
+            I32x4Neg => neg(code, I32),
+            I32x4AllTrue => all_true(code, I32),
+            I32x4Bitmask => bitmask(code, I32),
+            I32x4ExtendLowI16x8S => extend(code, I32, false, Signed),
+            I32x4ExtendHighI16x8S => extend(code, I32, true, Signed),
+            I32x4ExtendLowI16x8U => extend(code, I32, false, Unsigned),
+            I32x4ExtendHighI16x8U => extend(code, I32, true, Unsigned),
+            I32x4Shl => shift(code, I32, Wrap, IS::op_i32_shl),
+            I32x4ShrS => shift(code, I32, Wrap, IS::op_i32_shr_s),
+            I32x4ShrU => shift(code, I32, Wrap, IS::op_i32_shr_u),
+            I32x4Add => binary_lanes(code, I32, Wrap, |l| l.binary(IS::op_i32_add)),
+            I32x4Sub => binary_lanes(code, I32, Wrap, |l| l.binary(IS::op_i32_sub)),
+            I32x4Mul => binary_lanes(code, I32, Wrap, |l| l.binary(IS::op_i32_mul)),
+            I32x4MinS => min_max(code, I32, Wrap, IS::op_i32_lt_s),
+            I32x4MinU => min_max(code, I32, Wrap, IS::op_i32_lt_u),
+            I32x4MaxS => min_max(code, I32, Wrap, IS::op_i32_gt_s),
+            I32x4MaxU => min_max(code, I32, Wrap, IS::op_i32_gt_u),
+            I32x4DotI16x8S => map(code, 8, I32, |l, lane| {
+                for lane in [2 * lane, 2 * lane + 1] {
+                    l.lane(0, I16, lane, Signed);
+                    l.lane(4, I16, lane, Signed);
+                    l.binary(IS::op_i32_mul);
+                }
+                l.binary(IS::op_i32_add);
+            }),
+            I32x4ExtMulLowI16x8S => extmul(code, I32, false, Signed),
+            I32x4ExtMulHighI16x8S => extmul(code, I32, true, Signed),
+            I32x4ExtMulLowI16x8U => extmul(code, I32, false, Unsigned),
+            I32x4ExtMulHighI16x8U => extmul(code, I32, true, Unsigned),
+            I64x2Abs => abs(code, I64),
+            I64x2Neg => neg(code, I64),
+            I64x2AllTrue => all_true(code, I64),
+            I64x2Bitmask => bitmask(code, I64),
+            I64x2ExtendLowI32x4S => extend(code, I64, false, Signed),
+            I64x2ExtendHighI32x4S => extend(code, I64, true, Signed),
+            I64x2ExtendLowI32x4U => extend(code, I64, false, Unsigned),
+            I64x2ExtendHighI32x4U => extend(code, I64, true, Unsigned),
+            I64x2Shl => shift_i64(code, IS::op_i64_shl, IS::MSH_I64_SHL),
+            I64x2ShrS => shift_i64(code, IS::op_i64_shr_s, IS::MSH_I64_SHR_S),
+            I64x2ShrU => shift_i64(code, IS::op_i64_shr_u, IS::MSH_I64_SHR_U),
+            I64x2Add => binary_lanes(code, I64, Wrap, |l| {
+                l.emit(IS::op_i64_add, IS::MSH_I64_ADD, 4, 2)
+            }),
+            I64x2Sub => binary_lanes(code, I64, Wrap, |l| {
+                l.emit(IS::op_i64_sub, IS::MSH_I64_SUB, 4, 2)
+            }),
+            I64x2Mul => binary_lanes(code, I64, Wrap, |l| {
+                l.emit(IS::op_i64_mul, IS::MSH_I64_MUL, 4, 2)
+            }),
+            I64x2ExtMulLowI32x4S => extmul(code, I64, false, Signed),
+            I64x2ExtMulHighI32x4S => extmul(code, I64, true, Signed),
+            I64x2ExtMulLowI32x4U => extmul(code, I64, false, Unsigned),
+            I64x2ExtMulHighI32x4U => extmul(code, I64, true, Unsigned),
+            F32x4Ceil => round(code, I32, IS::op_f32_ceil, Snippet::ROUND_CEIL),
+            F32x4Floor => round(code, I32, IS::op_f32_floor, Snippet::ROUND_FLOOR),
+            F32x4Trunc => round(code, I32, IS::op_f32_trunc, Snippet::ROUND_TRUNC),
+            F32x4Nearest => round(code, I32, IS::op_f32_nearest, Snippet::ROUND_NEAREST),
+            F32x4Sqrt => unary_lanes(code, I32, Wrap, |l| {
+                l.float(
+                    IS::op_f32_sqrt,
+                    IS::op_soft_f32_sqrt,
+                    IS::MSH_SOFT_F32_SQRT,
+                    None,
+                    1,
+                    1,
+                )
+            }),
+            F32x4Add => binary_lanes(code, I32, Wrap, |l| l.f32_add()),
+            F32x4Sub => binary_lanes(code, I32, Wrap, |l| {
+                #[cfg(not(feature = "fpu"))]
+                l.emit(IS::op_soft_f32_neg, IS::MSH_SOFT_F32_NEG, 1, 1);
+                #[cfg(feature = "fpu")]
+                l.binary(IS::op_f32_sub);
+                #[cfg(not(feature = "fpu"))]
+                l.f32_add();
+            }),
+            F32x4Mul => binary_lanes(code, I32, Wrap, |l| {
+                l.float(
+                    IS::op_f32_mul,
+                    IS::op_soft_f32_mul,
+                    IS::MSH_SOFT_F32_MUL,
+                    None,
+                    2,
+                    1,
+                )
+            }),
+            F32x4Div => binary_lanes(code, I32, Wrap, |l| {
+                l.float(
+                    IS::op_f32_div,
+                    IS::op_soft_f32_div,
+                    IS::MSH_SOFT_F32_DIV,
+                    None,
+                    2,
+                    1,
+                )
+            }),
+            F32x4Min => binary_lanes(code, I32, Wrap, |l| {
+                l.float(
+                    IS::op_f32_min,
+                    IS::op_soft_f32_min_max,
+                    IS::MSH_SOFT_F32_MIN_MAX,
+                    Some(0),
+                    2,
+                    1,
+                )
+            }),
+            F32x4Max => binary_lanes(code, I32, Wrap, |l| {
+                l.float(
+                    IS::op_f32_max,
+                    IS::op_soft_f32_min_max,
+                    IS::MSH_SOFT_F32_MIN_MAX,
+                    Some(1),
+                    2,
+                    1,
+                )
+            }),
+            F32x4PMin => pseudo_min_max(code, I32, true),
+            F32x4PMax => pseudo_min_max(code, I32, false),
+            F64x2Ceil => round(code, I64, IS::op_f64_ceil, Snippet::ROUND_CEIL),
+            F64x2Floor => round(code, I64, IS::op_f64_floor, Snippet::ROUND_FLOOR),
+            F64x2Trunc => round(code, I64, IS::op_f64_trunc, Snippet::ROUND_TRUNC),
+            F64x2Nearest => round(code, I64, IS::op_f64_nearest, Snippet::ROUND_NEAREST),
+            F64x2Sqrt => unary_lanes(code, I64, Wrap, |l| {
+                l.float(
+                    IS::op_f64_sqrt,
+                    IS::op_soft_f64_sqrt,
+                    IS::MSH_SOFT_F64_SQRT,
+                    None,
+                    2,
+                    2,
+                )
+            }),
+            F64x2Add => binary_lanes(code, I64, Wrap, |l| l.f64_add()),
+            F64x2Sub => binary_lanes(code, I64, Wrap, |l| {
+                #[cfg(not(feature = "fpu"))]
+                l.emit(IS::op_soft_f64_neg, IS::MSH_SOFT_F64_NEG, 2, 2);
+                #[cfg(feature = "fpu")]
+                l.emit(IS::op_f64_sub, 0, 4, 2);
+                #[cfg(not(feature = "fpu"))]
+                l.f64_add();
+            }),
+            F64x2Mul => binary_lanes(code, I64, Wrap, |l| {
+                l.float(
+                    IS::op_f64_mul,
+                    IS::op_soft_f64_mul,
+                    IS::MSH_SOFT_F64_MUL,
+                    None,
+                    4,
+                    2,
+                )
+            }),
+            F64x2Div => binary_lanes(code, I64, Wrap, |l| {
+                l.float(
+                    IS::op_f64_div,
+                    IS::op_soft_f64_div,
+                    IS::MSH_SOFT_F64_DIV,
+                    None,
+                    4,
+                    2,
+                )
+            }),
+            F64x2Min => binary_lanes(code, I64, Wrap, |l| {
+                l.float(
+                    IS::op_f64_min,
+                    IS::op_soft_f64_min_max,
+                    IS::MSH_SOFT_F64_MIN_MAX,
+                    Some(0),
+                    4,
+                    2,
+                )
+            }),
+            F64x2Max => binary_lanes(code, I64, Wrap, |l| {
+                l.float(
+                    IS::op_f64_max,
+                    IS::op_soft_f64_min_max,
+                    IS::MSH_SOFT_F64_MIN_MAX,
+                    Some(1),
+                    4,
+                    2,
+                )
+            }),
+            F64x2PMin => pseudo_min_max(code, I64, true),
+            F64x2PMax => pseudo_min_max(code, I64, false),
+            I32x4TruncSatF32x4S => unary_lanes(code, I32, Wrap, |l| l.trunc_sat(I32, true)),
+            I32x4TruncSatF32x4U => unary_lanes(code, I32, Wrap, |l| l.trunc_sat(I32, false)),
+            F32x4ConvertI32x4S => unary_lanes(code, I32, Wrap, |l| l.convert(I32, true)),
+            F32x4ConvertI32x4U => unary_lanes(code, I32, Wrap, |l| l.convert(I32, false)),
+            I32x4TruncSatF64x2SZero => map(code, 4, I32, |l, lane| {
+                if lane < 2 {
+                    l.lane(0, I64, lane, Wrap);
+                    l.trunc_sat(I64, true);
+                } else {
+                    l.i32_const(0);
+                }
+            }),
+            I32x4TruncSatF64x2UZero => map(code, 4, I32, |l, lane| {
+                if lane < 2 {
+                    l.lane(0, I64, lane, Wrap);
+                    l.trunc_sat(I64, false);
+                } else {
+                    l.i32_const(0);
+                }
+            }),
+            F64x2ConvertLowI32x4S => map(code, 4, I64, |l, lane| {
+                l.get(lane);
+                l.convert(I64, true);
+            }),
+            F64x2ConvertLowI32x4U => map(code, 4, I64, |l, lane| {
+                l.get(lane);
+                l.convert(I64, false);
+            }),
+            F32x4DemoteF64x2Zero => map(code, 4, I32, |l, lane| {
+                if lane < 2 {
+                    l.lane(0, I64, lane, Wrap);
+                    l.float(
+                        IS::op_f32_demote_f64,
+                        IS::op_soft_f32_demote_f64,
+                        IS::MSH_SOFT_F32_DEMOTE_F64,
+                        None,
+                        2,
+                        1,
+                    );
+                } else {
+                    l.i32_const(0);
+                }
+            }),
+            F64x2PromoteLowF32x4 => map(code, 4, I64, |l, lane| {
+                l.get(lane);
+                l.float(
+                    IS::op_f64_promote_f32,
+                    IS::op_soft_f64_promote_f32,
+                    IS::MSH_SOFT_F64_PROMOTE_F32,
+                    None,
+                    1,
+                    2,
+                );
+            }),
+        }
+    }
+}
+
+/// The lane shape of a vector, float lanes use the integer shape of the same width.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Shape {
+    I8,
+    I16,
+    I32,
+    I64,
+}
+
+impl Shape {
+    fn bits(self) -> u32 {
+        match self {
+            Shape::I8 => 8,
+            Shape::I16 => 16,
+            Shape::I32 => 32,
+            Shape::I64 => 64,
+        }
+    }
+
+    fn lanes(self) -> u32 {
+        128 / self.bits()
+    }
+
+    /// The number of lanes in a word (`i8` and `i16` lanes only).
+    fn lanes_per_word(self) -> u32 {
+        32 / self.bits()
+    }
+
+    /// The mask of the lane bits (`i8` and `i16` lanes only).
+    fn mask(self) -> i32 {
+        (1 << self.bits()) - 1
+    }
+
+    /// The shape of the lanes twice wider.
+    fn wide(self) -> Shape {
+        match self {
+            Shape::I8 => Shape::I16,
+            Shape::I16 => Shape::I32,
+            Shape::I32 => Shape::I64,
+            Shape::I64 => unreachable!("there are no lanes wider than i64"),
+        }
+    }
+
+    /// The shape of the lanes twice narrower.
+    fn half(self) -> Shape {
+        match self {
+            Shape::I16 => Shape::I8,
+            Shape::I32 => Shape::I16,
+            Shape::I64 => Shape::I32,
+            Shape::I8 => unreachable!("there are no lanes narrower than i8"),
+        }
+    }
+}
+
+/// How a lane narrower than a word is extended to 32 bits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Ext {
+    /// The bits above the lane are undefined, for operations that don't depend on them.
+    Wrap,
+    Signed,
+    Unsigned,
+}
+
+impl Lowering<'_> {
+    /// Emits a float operation, the native opcode with the `fpu` feature or the software float
+    /// snippet otherwise, `operand` is pushed as the last `i32` operand of the snippet.
+    fn float(
+        &mut self,
+        native: fn(&mut InstructionSet),
+        soft: fn(&mut InstructionSet),
+        max_stack_height: u32,
+        operand: Option<i32>,
+        params: u32,
+        results: u32,
+    ) {
+        #[cfg(feature = "fpu")]
+        {
+            let _ = (soft, max_stack_height, operand);
+            self.emit(native, 0, params, results);
+        }
+        #[cfg(not(feature = "fpu"))]
+        {
+            let _ = native;
+            let mut params = params;
+            if let Some(operand) = operand {
+                self.i32_const(operand);
+                params += 1;
+            }
+            self.emit(soft, max_stack_height, params, results);
+        }
+    }
+
+    fn f32_add(&mut self) {
+        self.float(
+            InstructionSet::op_f32_add,
+            InstructionSet::op_soft_f32_add,
+            InstructionSet::MSH_SOFT_F32_ADD,
+            None,
+            2,
+            1,
+        );
+    }
+
+    fn f64_add(&mut self) {
+        self.float(
+            InstructionSet::op_f64_add,
+            InstructionSet::op_soft_f64_add,
+            InstructionSet::MSH_SOFT_F64_ADD,
+            None,
+            4,
+            2,
+        );
+    }
+
+    /// Truncates the float on top of the stack to an `i32` with saturation.
+    fn trunc_sat(&mut self, shape: Shape, signed: bool) {
+        #[cfg(feature = "fpu")]
+        match (shape, signed) {
+            (Shape::I64, true) => self.emit(InstructionSet::op_i32_trunc_sat_f64_s, 0, 2, 1),
+            (Shape::I64, false) => self.emit(InstructionSet::op_i32_trunc_sat_f64_u, 0, 2, 1),
+            (_, true) => self.unary(InstructionSet::op_i32_trunc_sat_f32_s),
+            (_, false) => self.unary(InstructionSet::op_i32_trunc_sat_f32_u),
+        }
+        #[cfg(not(feature = "fpu"))]
+        {
+            let flags = if signed {
+                Snippet::TO_INT_SIGNED | Snippet::TO_INT_SATURATING
+            } else {
+                Snippet::TO_INT_SATURATING
+            };
+            self.i32_const(flags);
+            if shape == Shape::I64 {
+                self.emit(
+                    InstructionSet::op_soft_f64_to_int,
+                    InstructionSet::MSH_SOFT_F64_TO_INT,
+                    3,
+                    2,
+                );
+            } else {
+                self.emit(
+                    InstructionSet::op_soft_f32_to_int,
+                    InstructionSet::MSH_SOFT_F32_TO_INT,
+                    2,
+                    2,
+                );
+            }
+            // the high word of the result
+            self.drop(1);
+        }
+    }
+
+    /// Converts the `i32` on top of the stack to a float.
+    fn convert(&mut self, shape: Shape, signed: bool) {
+        #[cfg(feature = "fpu")]
+        match (shape, signed) {
+            (Shape::I64, true) => self.emit(InstructionSet::op_f64_convert_i32_s, 0, 1, 2),
+            (Shape::I64, false) => self.emit(InstructionSet::op_f64_convert_i32_u, 0, 1, 2),
+            (_, true) => self.unary(InstructionSet::op_f32_convert_i32_s),
+            (_, false) => self.unary(InstructionSet::op_f32_convert_i32_u),
+        }
+        #[cfg(not(feature = "fpu"))]
+        {
+            // the snippets convert `i64` values
+            if signed {
+                self.dup();
+                self.i32_const(31);
+                self.binary(InstructionSet::op_i32_shr_s);
+            } else {
+                self.i32_const(0);
+            }
+            self.i32_const(signed as i32);
+            if shape == Shape::I64 {
+                self.emit(
+                    InstructionSet::op_soft_f64_from_int,
+                    InstructionSet::MSH_SOFT_F64_FROM_INT,
+                    3,
+                    2,
+                );
+            } else {
+                self.emit(
+                    InstructionSet::op_soft_f32_from_int,
+                    InstructionSet::MSH_SOFT_F32_FROM_INT,
+                    3,
+                    1,
+                );
+            }
+        }
+    }
+
+    /// Replaces the value on top of the stack with `max` if it's greater (signed).
+    fn clamp_max(&mut self, max: i32) {
+        let value = self.height - 1;
+        self.i32_const(max);
+        self.get(value);
+        self.get(value);
+        self.i32_const(max);
+        self.binary(InstructionSet::op_i32_gt_s);
+        self.select();
+        self.set(value);
+    }
+
+    /// Replaces the value on top of the stack with `min` if it's less (signed).
+    fn clamp_min(&mut self, min: i32) {
+        let value = self.height - 1;
+        self.i32_const(min);
+        self.get(value);
+        self.get(value);
+        self.i32_const(min);
+        self.binary(InstructionSet::op_i32_lt_s);
+        self.select();
+        self.set(value);
+    }
+
+    /// Pushes a lane of the vector starting at the slot `vector`, `i64` lanes take two words.
+    fn lane(&mut self, vector: u32, shape: Shape, lane: u32, ext: Ext) {
+        match shape {
+            Shape::I64 => {
+                self.get(vector + 2 * lane);
+                self.get(vector + 2 * lane + 1);
+            }
+            Shape::I32 => self.get(vector + lane),
+            Shape::I8 | Shape::I16 => {
+                let shift = (lane % shape.lanes_per_word()) * shape.bits();
+                self.get(vector + lane / shape.lanes_per_word());
+                if ext == Ext::Signed && shift + shape.bits() == 32 {
+                    self.i32_const(shift as i32);
+                    self.binary(InstructionSet::op_i32_shr_s);
+                    return;
+                }
+                if shift > 0 {
+                    self.i32_const(shift as i32);
+                    self.binary(InstructionSet::op_i32_shr_u);
+                }
+                match ext {
+                    Ext::Signed if shape == Shape::I8 => {
+                        self.unary(InstructionSet::op_i32_extend8_s)
+                    }
+                    Ext::Signed => self.unary(InstructionSet::op_i32_extend16_s),
+                    Ext::Unsigned if shift + shape.bits() < 32 => {
+                        self.i32_const(shape.mask());
+                        self.binary(InstructionSet::op_i32_and);
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    /// Pushes the four words of a vector, `lane` pushes the value of every lane (two words for
+    /// `i64` lanes) and the values are truncated to the lane width.
+    fn build(&mut self, shape: Shape, mut lane: impl FnMut(&mut Self, u32)) {
+        for i in 0..shape.lanes() {
+            lane(self, i);
+            if shape == Shape::I32 || shape == Shape::I64 {
+                continue;
+            }
+            let shift = (i % shape.lanes_per_word()) * shape.bits();
+            if shift + shape.bits() < 32 {
+                self.i32_const(shape.mask());
+                self.binary(InstructionSet::op_i32_and);
+            }
+            if shift > 0 {
+                self.i32_const(shift as i32);
+                self.binary(InstructionSet::op_i32_shl);
+                self.binary(InstructionSet::op_i32_or);
+            }
+        }
+    }
+}
+
+/// Emits a vector operation with `operands` slots, `lane` pushes the value of every result lane.
+fn map(
+    code: &mut InstructionSet,
+    operands: u32,
+    shape: Shape,
+    lane: impl FnMut(&mut Lowering, u32),
+) -> MaxStackHeight {
+    let mut lowering = Lowering::new(code, operands);
+    lowering.build(shape, lane);
+    lowering.finish(4)
+}
+
+fn unary_lanes(
+    code: &mut InstructionSet,
+    shape: Shape,
+    ext: Ext,
+    op: impl Fn(&mut Lowering),
+) -> MaxStackHeight {
+    map(code, 4, shape, |l, lane| {
+        l.lane(0, shape, lane, ext);
+        op(l);
+    })
+}
+
+fn binary_lanes(
+    code: &mut InstructionSet,
+    shape: Shape,
+    ext: Ext,
+    op: impl Fn(&mut Lowering),
+) -> MaxStackHeight {
+    map(code, 8, shape, |l, lane| {
+        l.lane(0, shape, lane, ext);
+        l.lane(4, shape, lane, ext);
+        op(l);
+    })
+}
+
+/// The lanes are set to all ones if the comparison holds and to zero otherwise.
+fn compare(
+    code: &mut InstructionSet,
+    shape: Shape,
+    ext: Ext,
+    cmp: fn(&mut InstructionSet),
+) -> MaxStackHeight {
+    map(code, 8, shape, |l, lane| {
+        l.i32_const(0);
+        l.lane(0, shape, lane, ext);
+        l.lane(4, shape, lane, ext);
+        l.binary(cmp);
+        l.binary(InstructionSet::op_i32_sub);
+    })
+}
+
+fn compare_i64(
+    code: &mut InstructionSet,
+    cmp: fn(&mut InstructionSet),
+    max_stack_height: u32,
+) -> MaxStackHeight {
+    map(code, 8, Shape::I64, |l, lane| {
+        l.i32_const(0);
+        l.lane(0, Shape::I64, lane, Ext::Wrap);
+        l.lane(4, Shape::I64, lane, Ext::Wrap);
+        l.emit(cmp, max_stack_height, 4, 1);
+        l.binary(InstructionSet::op_i32_sub);
+        l.dup();
+    })
+}
+
+fn compare_float(
+    code: &mut InstructionSet,
+    shape: Shape,
+    native: fn(&mut InstructionSet),
+    mask: i32,
+) -> MaxStackHeight {
+    map(code, 8, shape, |l, lane| {
+        l.i32_const(0);
+        l.lane(0, shape, lane, Ext::Wrap);
+        l.lane(4, shape, lane, Ext::Wrap);
+        if shape == Shape::I64 {
+            l.float(
+                native,
+                InstructionSet::op_soft_f64_compare,
+                InstructionSet::MSH_SOFT_F64_COMPARE,
+                Some(mask),
+                4,
+                1,
+            );
+        } else {
+            l.float(
+                native,
+                InstructionSet::op_soft_f32_compare,
+                InstructionSet::MSH_SOFT_F32_COMPARE,
+                Some(mask),
+                2,
+                1,
+            );
+        }
+        l.binary(InstructionSet::op_i32_sub);
+        if shape == Shape::I64 {
+            l.dup();
+        }
+    })
+}
+
+fn abs(code: &mut InstructionSet, shape: Shape) -> MaxStackHeight {
+    if shape == Shape::I64 {
+        return map(code, 4, shape, |l, lane| {
+            // `(x ^ sign) - sign` where the sign fills both words
+            let sign = |l: &mut Lowering| {
+                l.get(2 * lane + 1);
+                l.i32_const(31);
+                l.binary(InstructionSet::op_i32_shr_s);
+            };
+            for word in [2 * lane, 2 * lane + 1] {
+                l.get(word);
+                sign(l);
+                l.binary(InstructionSet::op_i32_xor);
+            }
+            sign(l);
+            sign(l);
+            l.emit(
+                InstructionSet::op_i64_sub,
+                InstructionSet::MSH_I64_SUB,
+                4,
+                2,
+            );
+        });
+    }
+    map(code, 4, shape, |l, lane| {
+        l.i32_const(0);
+        l.lane(0, shape, lane, Ext::Signed);
+        l.binary(InstructionSet::op_i32_sub);
+        l.lane(0, shape, lane, Ext::Signed);
+        l.lane(0, shape, lane, Ext::Signed);
+        l.i32_const(0);
+        l.binary(InstructionSet::op_i32_lt_s);
+        l.select();
+    })
+}
+
+fn neg(code: &mut InstructionSet, shape: Shape) -> MaxStackHeight {
+    map(code, 4, shape, |l, lane| {
+        if shape == Shape::I64 {
+            l.i32_const(0);
+            l.i32_const(0);
+            l.lane(0, shape, lane, Ext::Wrap);
+            l.emit(
+                InstructionSet::op_i64_sub,
+                InstructionSet::MSH_I64_SUB,
+                4,
+                2,
+            );
+        } else {
+            l.i32_const(0);
+            l.lane(0, shape, lane, Ext::Wrap);
+            l.binary(InstructionSet::op_i32_sub);
+        }
+    })
+}
+
+fn all_true(code: &mut InstructionSet, shape: Shape) -> MaxStackHeight {
+    let mut l = Lowering::new(code, 4);
+    // counts the zero lanes
+    for lane in 0..shape.lanes() {
+        l.lane(0, shape, lane, Ext::Unsigned);
+        if shape == Shape::I64 {
+            l.binary(InstructionSet::op_i32_or);
+        }
+        l.unary(InstructionSet::op_i32_eqz);
+        if lane > 0 {
+            l.binary(InstructionSet::op_i32_or);
+        }
+    }
+    l.unary(InstructionSet::op_i32_eqz);
+    l.finish(1)
+}
+
+fn bitmask(code: &mut InstructionSet, shape: Shape) -> MaxStackHeight {
+    let mut l = Lowering::new(code, 4);
+    for lane in 0..shape.lanes() {
+        // the sign bit of the lane is moved to the bit `lane`
+        let (word, bit) = match shape {
+            Shape::I64 => (2 * lane + 1, 31),
+            Shape::I32 => (lane, 31),
+            _ => {
+                let index = lane % shape.lanes_per_word();
+                (
+                    lane / shape.lanes_per_word(),
+                    (index + 1) * shape.bits() - 1,
+                )
+            }
+        };
+        l.get(word);
+        if bit > lane {
+            l.i32_const((bit - lane) as i32);
+            l.binary(InstructionSet::op_i32_shr_u);
+        } else if bit < lane {
+            l.i32_const((lane - bit) as i32);
+            l.binary(InstructionSet::op_i32_shl);
+        }
+        l.i32_const(1 << lane);
+        l.binary(InstructionSet::op_i32_and);
+        if lane > 0 {
+            l.binary(InstructionSet::op_i32_or);
+        }
+    }
+    l.finish(1)
+}
+
+/// Narrows the lanes of two vectors with signed or unsigned saturation (the inputs are signed).
+fn narrow(code: &mut InstructionSet, shape: Shape, signed: bool) -> MaxStackHeight {
+    let half = shape.lanes() / 2;
+    let (min, max) = if signed {
+        (-(1 << (shape.bits() - 1)), (1 << (shape.bits() - 1)) - 1)
+    } else {
+        (0, shape.mask())
+    };
+    map(code, 8, shape, |l, lane| {
+        let (vector, lane) = if lane < half {
+            (0, lane)
+        } else {
+            (4, lane - half)
+        };
+        l.lane(vector, shape.wide(), lane, Ext::Signed);
+        l.clamp_max(max);
+        l.clamp_min(min);
+    })
+}
+
+fn shift(
+    code: &mut InstructionSet,
+    shape: Shape,
+    ext: Ext,
+    op: fn(&mut InstructionSet),
+) -> MaxStackHeight {
+    map(code, 5, shape, |l, lane| {
+        l.lane(0, shape, lane, ext);
+        l.get(4);
+        if shape != Shape::I32 {
+            l.i32_const(shape.bits() as i32 - 1);
+            l.binary(InstructionSet::op_i32_and);
+        }
+        l.binary(op);
+    })
+}
+
+fn shift_i64(
+    code: &mut InstructionSet,
+    op: fn(&mut InstructionSet),
+    max_stack_height: u32,
+) -> MaxStackHeight {
+    map(code, 5, Shape::I64, |l, lane| {
+        l.lane(0, Shape::I64, lane, Ext::Wrap);
+        l.get(4);
+        l.i32_const(0);
+        l.emit(op, max_stack_height, 4, 2);
+    })
+}
+
+fn add_sat(code: &mut InstructionSet, shape: Shape, signed: bool) -> MaxStackHeight {
+    if signed {
+        binary_lanes(code, shape, Ext::Signed, |l| {
+            l.binary(InstructionSet::op_i32_add);
+            l.clamp_max((1 << (shape.bits() - 1)) - 1);
+            l.clamp_min(-(1 << (shape.bits() - 1)));
+        })
+    } else {
+        binary_lanes(code, shape, Ext::Unsigned, |l| {
+            l.binary(InstructionSet::op_i32_add);
+            l.clamp_max(shape.mask());
+        })
+    }
+}
+
+fn sub_sat(code: &mut InstructionSet, shape: Shape, signed: bool) -> MaxStackHeight {
+    if signed {
+        binary_lanes(code, shape, Ext::Signed, |l| {
+            l.binary(InstructionSet::op_i32_sub);
+            l.clamp_max((1 << (shape.bits() - 1)) - 1);
+            l.clamp_min(-(1 << (shape.bits() - 1)));
+        })
+    } else {
+        binary_lanes(code, shape, Ext::Unsigned, |l| {
+            l.binary(InstructionSet::op_i32_sub);
+            l.clamp_min(0);
+        })
+    }
+}
+
+/// Selects the lane of the first vector if `cmp(a, b)` holds and of the second one otherwise.
+fn min_max(
+    code: &mut InstructionSet,
+    shape: Shape,
+    ext: Ext,
+    cmp: fn(&mut InstructionSet),
+) -> MaxStackHeight {
+    map(code, 8, shape, |l, lane| {
+        l.lane(0, shape, lane, ext);
+        l.lane(4, shape, lane, ext);
+        l.lane(0, shape, lane, ext);
+        l.lane(4, shape, lane, ext);
+        l.binary(cmp);
+        l.select();
+    })
+}
+
+fn avgr_u(code: &mut InstructionSet, shape: Shape) -> MaxStackHeight {
+    binary_lanes(code, shape, Ext::Unsigned, |l| {
+        l.binary(InstructionSet::op_i32_add);
+        l.i32_const(1);
+        l.binary(InstructionSet::op_i32_add);
+        l.i32_const(1);
+        l.binary(InstructionSet::op_i32_shr_u);
+    })
+}
+
+fn extadd_pairwise(code: &mut InstructionSet, shape: Shape, ext: Ext) -> MaxStackHeight {
+    map(code, 4, shape, |l, lane| {
+        l.lane(0, shape.half(), 2 * lane, ext);
+        l.lane(0, shape.half(), 2 * lane + 1, ext);
+        l.binary(InstructionSet::op_i32_add);
+    })
+}
+
+fn extend(code: &mut InstructionSet, shape: Shape, high: bool, ext: Ext) -> MaxStackHeight {
+    let first = if high { shape.lanes() } else { 0 };
+    map(code, 4, shape, |l, lane| {
+        l.lane(0, shape.half(), first + lane, ext);
+        if shape == Shape::I64 {
+            if ext == Ext::Signed {
+                l.dup();
+                l.i32_const(31);
+                l.binary(InstructionSet::op_i32_shr_s);
+            } else {
+                l.i32_const(0);
+            }
+        }
+    })
+}
+
+fn extmul(code: &mut InstructionSet, shape: Shape, high: bool, ext: Ext) -> MaxStackHeight {
+    let first = if high { shape.lanes() } else { 0 };
+    map(code, 8, shape, |l, lane| {
+        let lane = first + lane;
+        if shape != Shape::I64 {
+            l.lane(0, shape.half(), lane, ext);
+            l.lane(4, shape.half(), lane, ext);
+            l.binary(InstructionSet::op_i32_mul);
+            return;
+        }
+        l.get(lane);
+        l.get(4 + lane);
+        l.emit(InstructionSet::op_i32_mul64, 0, 2, 2);
+        if ext == Ext::Signed {
+            // the unsigned product is corrected by subtracting the other operand from the high
+            // word for every negative operand
+            for (sign, other) in [(lane, 4 + lane), (4 + lane, lane)] {
+                l.get(sign);
+                l.i32_const(31);
+                l.binary(InstructionSet::op_i32_shr_s);
+                l.get(other);
+                l.binary(InstructionSet::op_i32_and);
+                l.binary(InstructionSet::op_i32_sub);
+            }
+        }
+    })
+}
+
+fn round(
+    code: &mut InstructionSet,
+    shape: Shape,
+    native: fn(&mut InstructionSet),
+    mode: i32,
+) -> MaxStackHeight {
+    unary_lanes(code, shape, Ext::Wrap, |l| {
+        if shape == Shape::I64 {
+            l.float(
+                native,
+                InstructionSet::op_soft_f64_round,
+                InstructionSet::MSH_SOFT_F64_ROUND,
+                Some(mode),
+                2,
+                2,
+            );
+        } else {
+            l.float(
+                native,
+                InstructionSet::op_soft_f32_round,
+                InstructionSet::MSH_SOFT_F32_ROUND,
+                Some(mode),
+                1,
+                1,
+            );
+        }
+    })
+}
+
+/// `pmin` selects `b` if `b < a` and `pmax` selects `b` if `a < b`, `a` otherwise.
+fn pseudo_min_max(code: &mut InstructionSet, shape: Shape, is_min: bool) -> MaxStackHeight {
+    map(code, 8, shape, |l, lane| {
+        let (a, b) = if shape == Shape::I64 {
+            (2 * lane, 4 + 2 * lane)
+        } else {
+            (lane, 4 + lane)
+        };
+        let condition = l.height;
+        let (lhs, rhs) = if is_min { (4, 0) } else { (0, 4) };
+        l.lane(lhs, shape, lane, Ext::Wrap);
+        l.lane(rhs, shape, lane, Ext::Wrap);
+        if shape == Shape::I64 {
+            l.float(
+                InstructionSet::op_f64_lt,
+                InstructionSet::op_soft_f64_compare,
+                InstructionSet::MSH_SOFT_F64_COMPARE,
+                Some(Snippet::COMPARE_LT),
+                4,
+                1,
+            );
+            // the high word first, the low word replaces the condition
+            for word in [1, 0] {
+                l.get(b + word);
+                l.get(a + word);
+                l.get(condition);
+                l.select();
+            }
+            l.set(condition);
+        } else {
+            l.float(
+                InstructionSet::op_f32_lt,
+                InstructionSet::op_soft_f32_compare,
+                InstructionSet::MSH_SOFT_F32_COMPARE,
+                Some(Snippet::COMPARE_LT),
+                2,
+                1,
+            );
+            l.get(b);
+            l.get(a);
+            l.get(condition);
+            l.select();
+            l.set(condition);
+        }
+    })
+}
+
+fn swizzle(code: &mut InstructionSet) -> MaxStackHeight {
+    use InstructionSet as IS;
+    map(code, 8, Shape::I8, |l, lane| {
+        let index = l.height;
+        l.lane(4, Shape::I8, lane, Ext::Unsigned);
+        // the word is selected by the bits 2 and 3 of the index
+        for (high, low) in [(3, 2), (1, 0)] {
+            l.get(high);
+            l.get(low);
+            l.get(index);
+            l.i32_const(4);
+            l.binary(IS::op_i32_and);
+            l.select();
+        }
+        l.get(index);
+        l.i32_const(8);
+        l.binary(IS::op_i32_and);
+        l.select();
+        l.get(index);
+        l.i32_const(3);
+        l.binary(IS::op_i32_and);
+        l.i32_const(3);
+        l.binary(IS::op_i32_shl);
+        l.binary(IS::op_i32_shr_u);
+        // the indices out of range select zero
+        l.i32_const(0);
+        l.get(index);
+        l.i32_const(16);
+        l.binary(IS::op_i32_lt_u);
+        l.select();
+        l.set(index);
+    })
+}
+
+/// Pushes the words of a `v128` constant.
+pub(crate) fn v128_const(code: &mut InstructionSet, value: V128) -> MaxStackHeight {
+    for word in value.bytes().chunks_exact(4) {
+        code.op_i32_const(i32::from_le_bytes([word[0], word[1], word[2], word[3]]));
+    }
+    0
+}
+
+pub(crate) fn v128_load(code: &mut InstructionSet, offset: AddressOffset) -> MaxStackHeight {
+    let mut l = Lowering::new(code, 1);
+    for word in 1..4 {
+        l.get(0);
+        l.load(InstructionSet::op_i32_load, offset.saturating_add(4 * word));
+    }
+    // the lowest word replaces the address
+    l.get(0);
+    l.load(InstructionSet::op_i32_load, offset);
+    l.set(0);
+    l.finish(4)
+}
+
+/// Loads the lanes of the half width and extends them to the given shape.
+pub(crate) fn v128_load_extend(
+    code: &mut InstructionSet,
+    offset: AddressOffset,
+    shape: Shape,
+    signed: bool,
+) -> MaxStackHeight {
+    let mut l = Lowering::new(code, 1);
+    l.build(shape, |l, lane| {
+        l.get(0);
+        match (shape, signed) {
+            (Shape::I16, true) => {
+                l.load(InstructionSet::op_i32_load8_s, offset.saturating_add(lane))
+            }
+            (Shape::I16, false) => {
+                l.load(InstructionSet::op_i32_load8_u, offset.saturating_add(lane))
+            }
+            (Shape::I32, true) => l.load(
+                InstructionSet::op_i32_load16_s,
+                offset.saturating_add(2 * lane),
+            ),
+            (Shape::I32, false) => l.load(
+                InstructionSet::op_i32_load16_u,
+                offset.saturating_add(2 * lane),
+            ),
+            _ => {
+                l.load(InstructionSet::op_i32_load, offset.saturating_add(4 * lane));
+                if signed {
+                    l.dup();
+                    l.i32_const(31);
+                    l.binary(InstructionSet::op_i32_shr_s);
+                } else {
+                    l.i32_const(0);
+                }
+            }
+        }
+    });
+    l.finish(4)
+}
+
+pub(crate) fn v128_load_splat(
+    code: &mut InstructionSet,
+    offset: AddressOffset,
+    shape: Shape,
+) -> MaxStackHeight {
+    let mut l = Lowering::new(code, 1);
+    match shape {
+        Shape::I8 => l.load(InstructionSet::op_i32_load8_u, offset),
+        Shape::I16 => l.load(InstructionSet::op_i32_load16_u, offset),
+        Shape::I32 => l.load(InstructionSet::op_i32_load, offset),
+        Shape::I64 => l.load_i64(offset),
+    }
+    l.splat(shape);
+    l.finish(4)
+}
+
+pub(crate) fn v128_load_zero(
+    code: &mut InstructionSet,
+    offset: AddressOffset,
+    shape: Shape,
+) -> MaxStackHeight {
+    let mut l = Lowering::new(code, 1);
+    if shape == Shape::I64 {
+        l.load_i64(offset);
+    } else {
+        l.load(InstructionSet::op_i32_load, offset);
+    }
+    while l.height < 4 {
+        l.i32_const(0);
+    }
+    l.finish(4)
+}
+
+/// Loads a lane of the vector (operands: the address and the vector).
+pub(crate) fn v128_load_lane(
+    code: &mut InstructionSet,
+    offset: AddressOffset,
+    shape: Shape,
+    lane: u32,
+) -> MaxStackHeight {
+    let mut l = Lowering::new(code, 5);
+    match shape {
+        Shape::I64 => {
+            l.get(0);
+            l.load(InstructionSet::op_i32_load, offset);
+            l.set(1 + 2 * lane);
+            l.get(0);
+            l.load(InstructionSet::op_i32_load, offset.saturating_add(4));
+            l.set(2 + 2 * lane);
+        }
+        Shape::I32 => {
+            l.get(0);
+            l.load(InstructionSet::op_i32_load, offset);
+            l.set(1 + lane);
+        }
+        Shape::I8 | Shape::I16 => {
+            let load = if shape == Shape::I8 {
+                InstructionSet::op_i32_load8_u
+            } else {
+                InstructionSet::op_i32_load16_u
+            };
+            l.insert_lane(1, shape, lane, |l| {
+                l.get(0);
+                l.load(load, offset);
+            });
+        }
+    }
+    l.finish(4)
+}
+
+pub(crate) fn v128_store(code: &mut InstructionSet, offset: AddressOffset) -> MaxStackHeight {
+    let mut l = Lowering::new(code, 5);
+    // the highest word first, so nothing is written if the store is out of bounds
+    for word in (0..4).rev() {
+        l.get(0);
+        l.get(1 + word);
+        l.store(
+            InstructionSet::op_i32_store,
+            offset.saturating_add(4 * word),
+        );
+    }
+    l.drop(5);
+    l.finish(0)
+}
+
+/// Stores a lane of the vector (operands: the address and the vector).
+pub(crate) fn v128_store_lane(
+    code: &mut InstructionSet,
+    offset: AddressOffset,
+    shape: Shape,
+    lane: u32,
+) -> MaxStackHeight {
+    let mut l = Lowering::new(code, 5);
+    match shape {
+        Shape::I64 => {
+            l.get(0);
+            l.get(2 + 2 * lane);
+            l.store(InstructionSet::op_i32_store, offset.saturating_add(4));
+            l.get(0);
+            l.get(1 + 2 * lane);
+            l.store(InstructionSet::op_i32_store, offset);
+        }
+        Shape::I32 => {
+            l.get(0);
+            l.get(1 + lane);
+            l.store(InstructionSet::op_i32_store, offset);
+        }
+        Shape::I8 | Shape::I16 => {
+            l.get(0);
+            l.lane(1, shape, lane, Ext::Wrap);
+            let store = if shape == Shape::I8 {
+                InstructionSet::op_i32_store8
+            } else {
+                InstructionSet::op_i32_store16
+            };
+            l.store(store, offset);
+        }
+    }
+    l.drop(5);
+    l.finish(0)
+}
+
+/// Fills all lanes with the scalar operand.
+pub(crate) fn splat(code: &mut InstructionSet, shape: Shape) -> MaxStackHeight {
+    let mut l = Lowering::new(code, if shape == Shape::I64 { 2 } else { 1 });
+    l.splat(shape);
+    l.finish(4)
+}
+
+pub(crate) fn extract_lane(
+    code: &mut InstructionSet,
+    shape: Shape,
+    lane: u32,
+    ext: Ext,
+) -> MaxStackHeight {
+    let mut l = Lowering::new(code, 4);
+    l.lane(0, shape, lane, ext);
+    l.finish(if shape == Shape::I64 { 2 } else { 1 })
+}
+
+pub(crate) fn replace_lane(code: &mut InstructionSet, shape: Shape, lane: u32) -> MaxStackHeight {
+    let mut l = Lowering::new(code, if shape == Shape::I64 { 6 } else { 5 });
+    match shape {
+        Shape::I64 => {
+            l.set(2 * lane + 1);
+            l.set(2 * lane);
+        }
+        Shape::I32 => l.set(lane),
+        Shape::I8 | Shape::I16 => {
+            l.insert_lane(0, shape, lane, |l| {
+                l.get(4);
+                l.i32_const(shape.mask());
+                l.binary(InstructionSet::op_i32_and);
+            });
+            l.drop(1);
+        }
+    }
+    l.finish(4)
+}
+
+pub(crate) fn shuffle(code: &mut InstructionSet, lanes: [u8; 16]) -> MaxStackHeight {
+    map(code, 8, Shape::I8, |l, lane| {
+        let source = lanes[lane as usize] as u32;
+        let vector = if source < 16 { 0 } else { 4 };
+        l.lane(vector, Shape::I8, source % 16, Ext::Wrap);
+    })
+}
+
+pub(crate) fn v128_not(code: &mut InstructionSet) -> MaxStackHeight {
+    map(code, 4, Shape::I32, |l, word| {
+        l.get(word);
+        l.i32_const(-1);
+        l.binary(InstructionSet::op_i32_xor);
+    })
+}
+
+/// Applies a bitwise operation to the words of two vectors.
+pub(crate) fn v128_bitwise(
+    code: &mut InstructionSet,
+    op: fn(&mut InstructionSet),
+) -> MaxStackHeight {
+    map(code, 8, Shape::I32, |l, word| {
+        l.get(word);
+        l.get(4 + word);
+        l.binary(op);
+    })
+}
+
+pub(crate) fn v128_andnot(code: &mut InstructionSet) -> MaxStackHeight {
+    map(code, 8, Shape::I32, |l, word| {
+        l.get(word);
+        l.get(4 + word);
+        l.i32_const(-1);
+        l.binary(InstructionSet::op_i32_xor);
+        l.binary(InstructionSet::op_i32_and);
+    })
+}
+
+pub(crate) fn v128_bitselect(code: &mut InstructionSet) -> MaxStackHeight {
+    // `v2 ^ ((v1 ^ v2) & c)`
+    map(code, 12, Shape::I32, |l, word| {
+        l.get(word);
+        l.get(4 + word);
+        l.binary(InstructionSet::op_i32_xor);
+        l.get(8 + word);
+        l.binary(InstructionSet::op_i32_and);
+        l.get(4 + word);
+        l.binary(InstructionSet::op_i32_xor);
+    })
+}
+
+pub(crate) fn v128_any_true(code: &mut InstructionSet) -> MaxStackHeight {
+    let mut l = Lowering::new(code, 4);
+    l.get(0);
+    for word in 1..4 {
+        l.get(word);
+        l.binary(InstructionSet::op_i32_or);
+    }
+    l.i32_const(0);
+    l.binary(InstructionSet::op_i32_ne);
+    l.finish(1)
+}
+
+/// Applies a bitwise operation with `mask` to the sign words of float lanes (`abs` and `neg`).
+pub(crate) fn float_sign(
+    code: &mut InstructionSet,
+    shape: Shape,
+    op: fn(&mut InstructionSet),
+    mask: i32,
+) -> MaxStackHeight {
+    map(code, 4, Shape::I32, |l, word| {
+        l.get(word);
+        if shape == Shape::I32 || word % 2 == 1 {
+            l.i32_const(mask);
+            l.binary(op);
+        }
+    })
+}
+
+impl Lowering<'_> {
+    /// Replaces the address on top of the stack with the `i64` value it points to.
+    fn load_i64(&mut self, offset: AddressOffset) {
+        self.get(0);
+        self.load(InstructionSet::op_i32_load, offset.saturating_add(4));
+        self.get(0);
+        self.load(InstructionSet::op_i32_load, offset);
+        self.set(0);
+    }
+
+    /// Pushes the four words of a vector with all lanes set to the value in the first slots.
+    fn splat(&mut self, shape: Shape) {
+        match shape {
+            Shape::I64 => {
+                self.get(0);
+                self.get(1);
+            }
+            Shape::I32 => (0..3).for_each(|_| self.dup()),
+            Shape::I8 | Shape::I16 => {
+                self.i32_const(shape.mask());
+                self.binary(InstructionSet::op_i32_and);
+                self.i32_const(if shape == Shape::I8 {
+                    0x01010101
+                } else {
+                    0x00010001
+                });
+                self.binary(InstructionSet::op_i32_mul);
+                (0..3).for_each(|_| self.dup());
+            }
+        }
+    }
+
+    /// Replaces a lane of the vector at slot `vector` with the value pushed by `value`, the
+    /// bits above the lane must be zero.
+    fn insert_lane(&mut self, vector: u32, shape: Shape, lane: u32, value: impl FnOnce(&mut Self)) {
+        let word = vector + lane / shape.lanes_per_word();
+        let shift = (lane % shape.lanes_per_word()) * shape.bits();
+        self.get(word);
+        self.i32_const(!(shape.mask() << shift));
+        self.binary(InstructionSet::op_i32_and);
+        value(self);
+        if shift > 0 {
+            self.i32_const(shift as i32);
+            self.binary(InstructionSet::op_i32_shl);
+        }
+        self.binary(InstructionSet::op_i32_or);
+        self.set(word);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::InstructionSet;
+use crate::{compiler::simd::SimdOp, InstructionSet};
 use alloc::vec::Vec;
 use wasmparser::{FuncType, ValType};
 
@@ -26,6 +26,28 @@ pub enum Snippet {
     I64ShrU,
     I64RotL,
     I64RotR,
+    F32Add,
+    F32Mul,
+    F32Div,
+    F32Sqrt,
+    F32MinMax,
+    F32Compare,
+    F32Round,
+    F32ToInt,
+    F32FromInt,
+    F32DemoteF64,
+    F64PromoteF32,
+    F64Add,
+    F64Mul,
+    F64Div,
+    F64Sqrt,
+    F64MinMax,
+    F64Compare,
+    F64Round,
+    F64ToInt,
+    F64FromInt,
+    /// A SIMD instruction lowered to 32-bit opcodes.
+    Simd(SimdOp),
 }
 
 #[derive(Debug)]
@@ -49,6 +71,26 @@ macro_rules! define_snippet {
 }
 
 impl Snippet {
+    /// The last operand of [`Snippet::F32Compare`] and [`Snippet::F64Compare`], bit `n` is the
+    /// result for less (0), equal (1), greater (2) and unordered (3) operands.
+    pub const COMPARE_EQ: i32 = 0b0010;
+    pub const COMPARE_NE: i32 = 0b1101;
+    pub const COMPARE_LT: i32 = 0b0001;
+    pub const COMPARE_GT: i32 = 0b0100;
+    pub const COMPARE_LE: i32 = 0b0011;
+    pub const COMPARE_GE: i32 = 0b0110;
+
+    /// The last operand of [`Snippet::F32Round`] and [`Snippet::F64Round`].
+    pub const ROUND_CEIL: i32 = 0;
+    pub const ROUND_FLOOR: i32 = 1;
+    pub const ROUND_TRUNC: i32 = 2;
+    pub const ROUND_NEAREST: i32 = 3;
+
+    /// Flags of the last operand of [`Snippet::F32ToInt`] and [`Snippet::F64ToInt`].
+    pub const TO_INT_SIGNED: i32 = 1;
+    pub const TO_INT_I64: i32 = 2;
+    pub const TO_INT_SATURATING: i32 = 4;
+
     fn definition(&self) -> &'static SnippetDefinition {
         use wasmparser::ValType::*;
         use Snippet::*;
@@ -75,31 +117,109 @@ impl Snippet {
             I64ShrU => define_snippet!(op_i64_shr_u, MSH_I64_SHR_U, &[I64, I64], &[I64]),
             I64RotL => define_snippet!(op_i64_rotl, MSH_I64_ROTL, &[I64, I64], &[I64]),
             I64RotR => define_snippet!(op_i64_rotr, MSH_I64_ROTR, &[I64, I64], &[I64]),
+            F32Add => define_snippet!(op_soft_f32_add, MSH_SOFT_F32_ADD, &[F32, F32], &[F32]),
+            F32Mul => define_snippet!(op_soft_f32_mul, MSH_SOFT_F32_MUL, &[F32, F32], &[F32]),
+            F32Div => define_snippet!(op_soft_f32_div, MSH_SOFT_F32_DIV, &[F32, F32], &[F32]),
+            F32Sqrt => define_snippet!(op_soft_f32_sqrt, MSH_SOFT_F32_SQRT, &[F32], &[F32]),
+            F32MinMax => define_snippet!(
+                op_soft_f32_min_max,
+                MSH_SOFT_F32_MIN_MAX,
+                &[F32, F32, I32],
+                &[F32]
+            ),
+            F32Compare => define_snippet!(
+                op_soft_f32_compare,
+                MSH_SOFT_F32_COMPARE,
+                &[F32, F32, I32],
+                &[I32]
+            ),
+            F32Round => {
+                define_snippet!(op_soft_f32_round, MSH_SOFT_F32_ROUND, &[F32, I32], &[F32])
+            }
+            F32ToInt => {
+                define_snippet!(op_soft_f32_to_int, MSH_SOFT_F32_TO_INT, &[F32, I32], &[I64])
+            }
+            F32FromInt => define_snippet!(
+                op_soft_f32_from_int,
+                MSH_SOFT_F32_FROM_INT,
+                &[I64, I32],
+                &[F32]
+            ),
+            F32DemoteF64 => define_snippet!(
+                op_soft_f32_demote_f64,
+                MSH_SOFT_F32_DEMOTE_F64,
+                &[F64],
+                &[F32]
+            ),
+            F64PromoteF32 => define_snippet!(
+                op_soft_f64_promote_f32,
+                MSH_SOFT_F64_PROMOTE_F32,
+                &[F32],
+                &[F64]
+            ),
+            F64Add => define_snippet!(op_soft_f64_add, MSH_SOFT_F64_ADD, &[F64, F64], &[F64]),
+            F64Mul => define_snippet!(op_soft_f64_mul, MSH_SOFT_F64_MUL, &[F64, F64], &[F64]),
+            F64Div => define_snippet!(op_soft_f64_div, MSH_SOFT_F64_DIV, &[F64, F64], &[F64]),
+            F64Sqrt => define_snippet!(op_soft_f64_sqrt, MSH_SOFT_F64_SQRT, &[F64], &[F64]),
+            F64MinMax => define_snippet!(
+                op_soft_f64_min_max,
+                MSH_SOFT_F64_MIN_MAX,
+                &[F64, F64, I32],
+                &[F64]
+            ),
+            F64Compare => define_snippet!(
+                op_soft_f64_compare,
+                MSH_SOFT_F64_COMPARE,
+                &[F64, F64, I32],
+                &[I32]
+            ),
+            F64Round => {
+                define_snippet!(op_soft_f64_round, MSH_SOFT_F64_ROUND, &[F64, I32], &[F64])
+            }
+            F64ToInt => {
+                define_snippet!(op_soft_f64_to_int, MSH_SOFT_F64_TO_INT, &[F64, I32], &[I64])
+            }
+            F64FromInt => define_snippet!(
+                op_soft_f64_from_int,
+                MSH_SOFT_F64_FROM_INT,
+                &[I64, I32],
+                &[F64]
+            ),
+            Simd(_) => unreachable!("rwasm: SIMD snippets are emitted by `SimdOp`"),
         }
     }
 
-    pub fn emitter(&self) -> fn(&mut InstructionSet) {
-        self.definition().emitter
-    }
-
     pub fn emit(&self, instruction_set: &mut InstructionSet) {
+        if let Snippet::Simd(op) = self {
+            op.emit(instruction_set);
+            return;
+        }
         (self.definition().emitter)(instruction_set);
     }
 
     pub fn max_stack_height(&self) -> u32 {
+        if let Snippet::Simd(op) = self {
+            return op.max_stack_height();
+        }
         self.definition().max_stack_height
     }
 
+    fn orig_types(&self) -> (&'static [ValType], &'static [ValType]) {
+        if let Snippet::Simd(op) = self {
+            return (op.params(), op.results());
+        }
+        let definition = self.definition();
+        (definition.orig_params, definition.orig_results)
+    }
+
     pub fn orig_func_type(&self) -> FuncType {
-        let params = self.definition().orig_params.to_vec();
-        let result = self.definition().orig_results.to_vec();
-        FuncType::new(params, result)
+        let (params, results) = self.orig_types();
+        FuncType::new(params.to_vec(), results.to_vec())
     }
 
     pub fn func_type(&self) -> FuncType {
-        let params = expand_i64_to_i32(self.definition().orig_params);
-        let result = expand_i64_to_i32(self.definition().orig_results);
-        FuncType::new(params, result)
+        let (params, results) = self.orig_types();
+        FuncType::new(expand_i64_to_i32(params), expand_i64_to_i32(results))
     }
 }
 
@@ -111,6 +231,7 @@ fn expand_i64_to_i32(params: &[ValType]) -> Vec<ValType> {
                 expanded.push(ValType::I32);
                 expanded.push(ValType::I32);
             }
+            ValType::V128 => expanded.extend([ValType::I32; 4]),
             _ => expanded.push(t),
         }
     }
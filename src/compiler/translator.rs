@@ -1,8 +1,9 @@
 use crate::{
     compiler::{
+        atomics::{self, Access, RmwOp},
         control_flow::{
             BlockControlFrame, ControlFlowStack, ControlFrame, ControlFrameKind, IfControlFrame,
-            LoopControlFrame, UnreachableControlFrame,
+            LoopControlFrame, TryControlFrame, TryStage, UnreachableControlFrame,
         },
         drop_keep::DropKeep,
         error::CompilationError,
@@ -11,18 +12,19 @@ use crate::{
         labels::LabelRegistry,
         locals_registry::LocalsRegistry,
         segment_builder::SegmentBuilder,
+        simd::{self, Ext, Shape, SimdOp},
         snippets::{Snippet, SnippetCall},
         utils::RelativeDepth,
         value_stack::ValueStackHeight,
     },
     AddressOffset, BranchOffset, BranchTableTargets, ConstructorParams, DataSegmentIdx,
-    ElementSegmentIdx, FuncIdx, FuncTypeIdx, GlobalVariable, InstrLoc, InstructionSet, LabelRef,
-    Opcode, TableIdx, DEFAULT_MEMORY_INDEX, N_MAX_TABLE_SIZE, SNIPPET_FUNC_IDX_UNRESOLVED,
+    ElementSegmentIdx, FuelCostClass, FuelCostModel, FuelMeteringMode, FuncIdx, FuncTypeIdx,
+    GlobalIdx, GlobalVariable, InstrLoc, InstructionSet, LabelRef, MaxStackHeight, MemoryIdx,
+    Opcode, RwasmSourceMapEntry, RwasmTag, TableIdx, N_MAX_TABLE_SIZE, SNIPPET_FUNC_IDX_UNRESOLVED,
 };
-use alloc::{boxed::Box, vec::Vec};
+use alloc::{boxed::Box, sync::Arc, vec::Vec};
 use bitvec::macros::internal::funty::Fundamental;
 use hashbrown::HashMap;
-use rwasm_fuel_policy::FuelCosts;
 use wasmparser::{
     BlockType, BrTable, FuncType, FuncValidatorAllocations, GlobalType, Ieee32, Ieee64, MemArg,
     MemoryType, TableType, ValType, VisitOperator, V128,
@@ -63,12 +65,18 @@ pub struct FuncTranslatorAllocations {
     pub(crate) tables: Vec<TableType>,
     pub(crate) memories: Vec<MemoryType>,
     pub(crate) globals: Vec<GlobalVariable>,
+    /// Imported globals living in the store, mapped to their first global word.
+    pub(crate) host_globals: HashMap<u32, GlobalIdx>,
+    /// Exception tags of the module, indexed by the tag index.
+    pub(crate) tags: Vec<RwasmTag>,
     pub(crate) exported_funcs: HashMap<Box<str>, FuncIdx>,
     pub(crate) start_func: Option<FuncIdx>,
     pub(crate) func_offsets: Vec<u32>,
     pub(crate) constructor_params: ConstructorParams,
     pub(crate) snippet_calls: Vec<SnippetCall>,
     pub(crate) intrinsic_handler: IntrinsicHandler,
+    /// Source map entries with PCs relative to the instruction set (without the entrypoint).
+    pub(crate) source_map: Vec<RwasmSourceMapEntry>,
 }
 
 impl FuncTranslatorAllocations {
@@ -85,6 +93,33 @@ impl FuncTranslatorAllocations {
         self.stack_types.clear();
     }
 
+    /// Starts a new source map entry at the current PC, the previous entry (if it's still open)
+    /// ends here.
+    pub(crate) fn begin_source_location(&mut self, func_idx: FuncIdx, wasm_offset: usize) {
+        self.end_source_location();
+        let pc = self.instruction_set.len() as u32;
+        self.source_map.push(RwasmSourceMapEntry {
+            start_pc: pc,
+            end_pc: pc,
+            func_idx,
+            wasm_offset: wasm_offset as u32,
+        });
+    }
+
+    /// Ends the open source map entry at the current PC, entries w/o code are removed.
+    ///
+    /// An entry is open while its range is empty.
+    pub(crate) fn end_source_location(&mut self) {
+        let pc = self.instruction_set.len() as u32;
+        match self.source_map.last_mut() {
+            Some(entry) if entry.start_pc == entry.end_pc && entry.start_pc == pc => {
+                self.source_map.pop();
+            }
+            Some(entry) if entry.start_pc == entry.end_pc => entry.end_pc = pc,
+            _ => {}
+        }
+    }
+
     pub(crate) fn resolve_func_type_index<I: Into<FuncIdx>>(&self, func_idx: I) -> FuncTypeIdx {
         let func_idx: FuncIdx = func_idx.into();
         self.compiled_funcs.get(func_idx as usize).copied().unwrap()
@@ -169,9 +204,19 @@ pub struct InstructionTranslator {
     pub(crate) consume_fuel_for_bulk_ops: bool,
     /// Max allowed memory pages
     pub(crate) max_allowed_memory_pages: u32,
+    /// Prices the translated operators.
+    pub(crate) fuel_cost_model: Arc<dyn FuelCostModel>,
+    /// Charges the fuel of a code segment when it's entered or when it's left.
+    pub(crate) fuel_metering_mode: FuelMeteringMode,
+    /// The fuel of the lazily metered operators that isn't charged yet.
+    ///
+    /// It's `None` while the fuel is charged by the `ConsumeFuel` instructions of the control
+    /// frames, that is in the eager mode and in the function prologue.
+    pub(crate) pending_fuel: Option<u32>,
 }
 
 impl InstructionTranslator {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         alloc: FuncTranslatorAllocations,
         with_consume_fuel: bool,
@@ -179,6 +224,8 @@ impl InstructionTranslator {
         consume_fuel_for_bulk_ops: bool,
         consume_fuel_for_params_and_locals: bool,
         max_allowed_memory_pages: u32,
+        fuel_cost_model: Arc<dyn FuelCostModel>,
+        fuel_metering_mode: FuelMeteringMode,
     ) -> Self {
         Self {
             reachable: true,
@@ -190,6 +237,9 @@ impl InstructionTranslator {
             consume_fuel_for_bulk_ops,
             consume_fuel_for_params_and_locals,
             max_allowed_memory_pages,
+            fuel_cost_model,
+            fuel_metering_mode,
+            pending_fuel: None,
         }
     }
 
@@ -240,7 +290,7 @@ impl InstructionTranslator {
         self.locals.register_locals(func_params_len as u32);
         if self.consume_fuel_for_params_and_locals {
             let locals_count = self.locals.len_registered();
-            self.bump_fuel_consumption(|| FuelCosts::fuel_for_locals(locals_count))
+            self.bump_fuel_consumption(|model| model.locals_cost(locals_count))
                 .unwrap_or_else(|_| panic!("failed to add fuel charging for locals"));
         }
     }
@@ -283,15 +333,21 @@ impl InstructionTranslator {
     /// Does nothing if gas metering is disabled.
     ///
     /// [`ConsumeFuel`]: enum.Instruction.html#variant.ConsumeFuel
-    pub(crate) fn bump_fuel_consumption<F: FnOnce() -> u32>(
+    pub(crate) fn bump_fuel_consumption<F: FnOnce(&dyn FuelCostModel) -> u32>(
         &mut self,
         delta: F,
     ) -> Result<(), CompilationError> {
-        if let Some(instr) = self.consume_fuel_instr() {
+        if let Some(pending_fuel) = self.pending_fuel {
+            let delta = delta(self.fuel_cost_model.as_ref());
+            let pending_fuel = pending_fuel
+                .checked_add(delta)
+                .ok_or(CompilationError::BlockFuelOutOfBounds)?;
+            self.pending_fuel = Some(pending_fuel);
+        } else if let Some(instr) = self.consume_fuel_instr() {
             if !self.with_consume_fuel {
                 return Ok(());
             };
-            let delta = delta();
+            let delta = delta(self.fuel_cost_model.as_ref());
             self.alloc
                 .instruction_set
                 .bump_fuel_consumption(instr, delta)?;
@@ -299,6 +355,46 @@ impl InstructionTranslator {
         Ok(())
     }
 
+    /// Starts charging the fuel of the translated operators lazily if the [`FuelMeteringMode`]
+    /// requires it, the fuel of the function prologue is charged on entry.
+    pub(crate) fn begin_lazy_fuel_metering(&mut self) {
+        if self.with_consume_fuel && self.fuel_metering_mode == FuelMeteringMode::Lazy {
+            self.pending_fuel = Some(0);
+        }
+    }
+
+    /// Charges the pending fuel of the lazily metered operators.
+    ///
+    /// Must be called before the translated code leaves the current straight-line segment and
+    /// before a label is pinned, so every operator is charged on the path that executes it. It's
+    /// also called before an operator that can trap, so the completed operators are charged.
+    fn charge_pending_fuel(&mut self) {
+        match self.pending_fuel {
+            Some(pending_fuel) if pending_fuel > 0 => {
+                self.alloc.instruction_set.op_consume_fuel(pending_fuel);
+                self.pending_fuel = Some(0);
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns `true` if every code segment charges its fuel with a `ConsumeFuel` at its start.
+    fn is_eager_fuel_metering(&self) -> bool {
+        self.is_fuel_metering_enabled() && self.pending_fuel.is_none()
+    }
+
+    /// Returns the fuel cost model of the dynamic fuel check of bulk operations, or `None` if the
+    /// check isn't emitted.
+    fn bulk_fuel_cost_model(&self) -> Option<Arc<dyn FuelCostModel>> {
+        (self.consume_fuel_for_bulk_ops && self.is_fuel_metering_enabled())
+            .then(|| self.fuel_cost_model.clone())
+    }
+
+    /// Returns the fuel charged for an operator of the given class.
+    fn fuel_cost(&self, class: FuelCostClass) -> u32 {
+        self.fuel_cost_model.opcode_cost(class)
+    }
+
     /// Calculates the stack height upon entering a control flow frame.
     ///
     /// # Note
@@ -398,6 +494,15 @@ impl InstructionTranslator {
         Ok(())
     }
 
+    /// Returns the first global word of the global, `i64` and `f64` globals take two words.
+    pub(crate) fn resolve_global_word(&self, global_index: u32) -> GlobalIdx {
+        self.alloc
+            .host_globals
+            .get(&global_index)
+            .copied()
+            .unwrap_or(global_index * 2)
+    }
+
     pub(crate) fn resolve_global_type(&self, global_index: u32) -> &GlobalType {
         self.alloc
             .globals
@@ -419,6 +524,7 @@ impl InstructionTranslator {
             ControlFrame::Block(frame) => frame.bump_branches(),
             ControlFrame::Loop(frame) => frame.bump_branches(),
             ControlFrame::If(frame) => frame.bump_branches(),
+            ControlFrame::Try(frame) => frame.bump_branches(),
             ControlFrame::Unreachable(frame) => {
                 panic!("tried to `bump_branches` on an unreachable control frame: {frame:?}")
             }
@@ -457,7 +563,7 @@ impl InstructionTranslator {
         let frame = self.alloc.control_frames.nth_back(depth);
         // Find out how many values we need to keep (copy to the new stack location after the drop).
         let keep = match frame.kind() {
-            ControlFrameKind::Block | ControlFrameKind::If => self
+            ControlFrameKind::Block | ControlFrameKind::If | ControlFrameKind::Try => self
                 .alloc
                 .func_type_registry
                 .resolve_func_results_len_type_by_block(frame.block_type()),
@@ -549,6 +655,84 @@ impl InstructionTranslator {
             .expect("the control flow frame stack must not be empty") as u32
     }
 
+    /// Returns the number of `try` frames a branch to the given `relative_depth` leaves.
+    ///
+    /// Every of them needs a `TryEnd` to pop its handler entry before the branch.
+    fn try_frames_to_leave(&self, relative_depth: u32) -> u32 {
+        self.alloc
+            .control_frames
+            .count_try_frames(relative_depth + 1)
+    }
+
+    /// Shrinks the emulated value stack together with the stack types to the given height.
+    fn shrink_stack_to(&mut self, new_height: u32) {
+        let mut old_stack_height = self.stack_height.height();
+        self.stack_height.shrink_to(new_height);
+        while old_stack_height > new_height {
+            match self.alloc.stack_types.pop() {
+                Some(value_type) => old_stack_height -= value_slots(value_type),
+                None => panic!("type stack corrupted"),
+            }
+        }
+    }
+
+    /// Ends the current part of the `try` frame.
+    ///
+    /// If the end of the part is reachable, its handler entry is popped and (optionally) the
+    /// execution continues at the end of the frame.
+    fn end_try_stage(
+        &mut self,
+        try_frame: &TryControlFrame,
+        jump_to_end: bool,
+    ) -> Result<(), CompilationError> {
+        if self.is_reachable() {
+            self.charge_pending_fuel();
+            self.alloc.instruction_set.op_try_end();
+            if jump_to_end {
+                let offset = self.branch_offset(try_frame.end_label())?;
+                self.alloc.instruction_set.op_br(offset);
+            }
+        }
+        Ok(())
+    }
+
+    /// Starts the handler code for a `catch` or `catch_all` clause of the `try` frame.
+    ///
+    /// # Note
+    ///
+    /// Upon a caught exception the VM restores the stack height of `TryBegin` that still includes
+    /// the parameters of the `try` block, so the landing pad drops them first.
+    fn begin_try_handler(
+        &mut self,
+        try_frame: &mut TryControlFrame,
+        stage: TryStage,
+    ) -> Result<(), CompilationError> {
+        let is_landing_pad = try_frame.stage() == TryStage::Body;
+        self.pin_label(try_frame.next_label());
+        // In the `catch_all` clause there is no next handler code.
+        let next_label = match stage {
+            TryStage::Catch => self.alloc.labels.new_label(),
+            _ => try_frame.next_label(),
+        };
+        try_frame.enter_stage(stage, next_label);
+        self.is_eager_fuel_metering().then(|| {
+            let consume_fuel = self.push_consume_fuel_empty();
+            try_frame.update_consume_fuel_instr(consume_fuel);
+        });
+        self.shrink_stack_to(try_frame.stack_height());
+        if is_landing_pad {
+            let len_params = self
+                .alloc
+                .func_type_registry
+                .resolve_func_params_len_type_by_block(try_frame.block_type());
+            DropKeep::new(len_params, 0)?
+                .translate_drop_keep(&mut self.alloc.instruction_set, &mut self.stack_height);
+        }
+        // The handler code is reachable since the `try` frame was reachable.
+        self.reachable = true;
+        Ok(())
+    }
+
     /// Translates into `rwasm` bytecode if the current code path is reachable.
     ///
     /// # Note
@@ -579,13 +763,7 @@ impl InstructionTranslator {
             .iter()
             .rev()
             .take(local_depth as usize)
-            .map(|t| {
-                if t == &ValType::I64 || t == &ValType::F64 {
-                    2
-                } else {
-                    1
-                }
-            })
+            .map(|t| value_slots(*t))
             .sum()
     }
 
@@ -611,6 +789,15 @@ impl InstructionTranslator {
     }
 }
 
+/// Returns the number of 32-bit stack slots a value of the given type takes.
+fn value_slots(value_type: ValType) -> u32 {
+    match value_type {
+        ValType::I64 | ValType::F64 => 2,
+        ValType::V128 => 4,
+        _ => 1,
+    }
+}
+
 /// An acquired target.
 ///
 /// Returned by [`FuncTranslatorI32::acquire_target`].
@@ -633,6 +820,7 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
 
     fn visit_unreachable(&mut self) -> Self::Output {
         self.translate_if_reachable(|builder| {
+            builder.charge_pending_fuel();
             builder.alloc.instruction_set.op_unreachable();
             builder.reachable = false;
             Ok(())
@@ -672,9 +860,10 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
         if self.is_reachable() {
             let stack_height = self.frame_stack_height(block_type);
             let header = self.alloc.labels.new_label();
+            self.charge_pending_fuel();
             self.pin_label(header);
             let consume_fuel = self
-                .is_fuel_metering_enabled()
+                .is_eager_fuel_metering()
                 .then(|| self.push_consume_fuel_empty());
             self.alloc.control_frames.push_frame(LoopControlFrame::new(
                 block_type,
@@ -700,12 +889,13 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
             let stack_height = self.frame_stack_height(block_type);
             let else_label = self.alloc.labels.new_label();
             let end_label = self.alloc.labels.new_label();
-            self.bump_fuel_consumption(|| FuelCosts::BASE)?;
+            self.bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Base))?;
+            self.charge_pending_fuel();
 
             let branch_offset = self.branch_offset(else_label)?;
             self.alloc.instruction_set.op_br_if_eqz(branch_offset);
             let consume_fuel = self
-                .is_fuel_metering_enabled()
+                .is_eager_fuel_metering()
                 .then(|| self.push_consume_fuel_empty());
             self.alloc.control_frames.push_frame(IfControlFrame::new(
                 block_type,
@@ -751,6 +941,7 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
         // Create the jump from the end of the `then` block to the `if`
         // block's end label in case the end of `then` is reachable.
         if reachable {
+            self.charge_pending_fuel();
             let offset = self.branch_offset(if_frame.end_label())?;
             self.alloc.instruction_set.op_br(offset);
         }
@@ -760,7 +951,7 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
         // created for the `else` part of the `if` block. This can be done
         // since the `ConsumeFuel` instruction for the `then` block is no longer
         // used from this point on.
-        self.is_fuel_metering_enabled().then(|| {
+        self.is_eager_fuel_metering().then(|| {
             let consume_fuel = self.push_consume_fuel_empty();
             if_frame.update_consume_fuel_instr(consume_fuel);
         });
@@ -772,9 +963,7 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
         // Adjust the stack height for the `else` block.
         while old_stack_height > self.stack_height.height() {
             match self.alloc.stack_types.pop() {
-                Some(ValType::I64) => old_stack_height -= 2,
-                Some(ValType::F64) => old_stack_height -= 2,
-                Some(_) => old_stack_height -= 1,
+                Some(value_type) => old_stack_height -= value_slots(value_type),
                 None => panic!("stack corrupted in else block"),
             }
         }
@@ -784,11 +973,7 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
                 .func_type_registry
                 .resolve_original_func_type(func_type_idx);
             func_type.params().iter().for_each(|param| {
-                if *param == ValType::I64 || *param == ValType::F64 {
-                    self.stack_height.push_n(2);
-                } else {
-                    self.stack_height.push1();
-                }
+                self.stack_height.push_type(*param);
                 self.alloc.stack_types.push(*param);
             });
         }
@@ -798,23 +983,128 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
         Ok(())
     }
 
-    fn visit_try(&mut self, _block_type: BlockType) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_try(&mut self, block_type: BlockType) -> Self::Output {
+        if self.is_reachable() {
+            // The body unconditionally executes after `TryBegin`, so it inherits the
+            // `ConsumeFuel` instruction of the parent control frame like a `block` does.
+            let consume_fuel = self.alloc.control_frames.last().consume_fuel_instr();
+            let stack_height = self.frame_stack_height(block_type);
+            let end_label = self.alloc.labels.new_label();
+            let landing_pad = self.alloc.labels.new_label();
+            self.bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Base))?;
+            let offset = self.branch_offset(landing_pad)?;
+            self.alloc.instruction_set.op_try_begin(offset);
+            self.alloc.control_frames.push_frame(TryControlFrame::new(
+                block_type,
+                end_label,
+                landing_pad,
+                stack_height,
+                consume_fuel,
+            ));
+        } else {
+            self.alloc
+                .control_frames
+                .push_frame(UnreachableControlFrame::new(
+                    ControlFrameKind::Try,
+                    block_type,
+                ));
+        }
+        Ok(())
     }
 
-    fn visit_catch(&mut self, _tag_index: u32) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_catch(&mut self, tag_index: u32) -> Self::Output {
+        let mut try_frame = match self.alloc.control_frames.pop_frame() {
+            ControlFrame::Try(try_frame) => try_frame,
+            ControlFrame::Unreachable(frame) if matches!(frame.kind(), ControlFrameKind::Try) => {
+                // The `catch` clause of an unreachable `try` block is unreachable too.
+                self.alloc.control_frames.push_frame(frame);
+                return Ok(());
+            }
+            unexpected => panic!(
+                "expected `try` control flow frame on top \
+                for `catch` but found: {unexpected:?}",
+            ),
+        };
+        self.end_try_stage(&try_frame, true)?;
+        self.begin_try_handler(&mut try_frame, TryStage::Catch)?;
+        let next_label = try_frame.next_label();
+        self.alloc.control_frames.push_frame(try_frame);
+        // Test the tag of the active exception, the next clause handles a mismatch.
+        self.bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Base))?;
+        self.stack_height.push_n(2);
+        self.stack_height.pop_n(2);
+        self.alloc.instruction_set.op_exception_tag();
+        self.alloc.instruction_set.op_i32_const(tag_index);
+        self.alloc.instruction_set.op_i32_eq();
+        self.charge_pending_fuel();
+        let offset = self.branch_offset(next_label)?;
+        self.alloc.instruction_set.op_br_if_eqz(offset);
+        if self.is_eager_fuel_metering() {
+            let fuel_ix = self.push_consume_fuel_empty();
+            let mut frame = self.alloc.control_frames.pop_frame();
+            frame.update_consume_fuel_instr(fuel_ix);
+            self.alloc.control_frames.push_frame(frame);
+        }
+        self.bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Base))?;
+        self.alloc.instruction_set.op_exception_payload(tag_index);
+        let tag = &self.alloc.tags[tag_index as usize];
+        for param in tag.params.iter() {
+            let param = ValType::from(*param);
+            self.stack_height.push_type(param);
+            self.alloc.stack_types.push(param);
+        }
+        Ok(())
     }
 
-    fn visit_throw(&mut self, _tag_index: u32) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_throw(&mut self, tag_index: u32) -> Self::Output {
+        self.translate_if_reachable(|builder| {
+            builder.bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Base))?;
+            let tag = &builder.alloc.tags[tag_index as usize];
+            for param in tag.params.iter().rev() {
+                let param = ValType::from(*param);
+                builder.stack_height.pop_type(param);
+                let popped_type = builder.alloc.stack_types.pop().unwrap();
+                debug_assert_eq!(param, popped_type);
+            }
+            builder.charge_pending_fuel();
+            builder.alloc.instruction_set.op_throw(tag_index);
+            builder.reachable = false;
+            Ok(())
+        })
     }
 
-    fn visit_rethrow(&mut self, _relative_depth: u32) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_rethrow(&mut self, relative_depth: u32) -> Self::Output {
+        self.translate_if_reachable(|builder| {
+            builder.bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Base))?;
+            // The handler entries of the inner `try` frames are on top of the caught exception.
+            let depth = builder
+                .alloc
+                .control_frames
+                .count_try_frames(relative_depth);
+            builder.charge_pending_fuel();
+            builder.alloc.instruction_set.op_rethrow(depth);
+            builder.reachable = false;
+            Ok(())
+        })
     }
 
     fn visit_end(&mut self) -> Self::Output {
+        // The end of the frame can be a branch target.
+        self.charge_pending_fuel();
+        if let ControlFrame::Try(try_frame) = self.alloc.control_frames.last() {
+            let try_frame = *try_frame;
+            match try_frame.stage() {
+                TryStage::Body | TryStage::Catch => {
+                    // An exception that no clause handles is thrown further.
+                    self.end_try_stage(&try_frame, true)?;
+                    self.pin_label(try_frame.next_label());
+                    self.alloc.instruction_set.op_rethrow(0);
+                    self.reachable = false;
+                }
+                TryStage::CatchAll => self.end_try_stage(&try_frame, false)?,
+                TryStage::Delegate => {}
+            }
+        }
         let frame = self.alloc.control_frames.last();
         if let ControlFrame::If(if_frame) = frame {
             // At this point, we can resolve the `Else` label.
@@ -856,9 +1146,7 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
             self.stack_height.shrink_to(frame_stack_height);
             while old_stack_height > self.stack_height.height() {
                 match self.alloc.stack_types.pop() {
-                    Some(ValType::I64) => old_stack_height -= 2,
-                    Some(ValType::F64) => old_stack_height -= 2,
-                    Some(_) => old_stack_height -= 1,
+                    Some(value_type) => old_stack_height -= value_slots(value_type),
                     None => panic!("type stack corrupted"),
                 }
             }
@@ -871,25 +1159,17 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
                     .func_type_registry
                     .resolve_original_func_type(func_type_idx);
                 func_type.results().iter().for_each(|param| {
-                    if *param == ValType::I64 || *param == ValType::F64 {
-                        self.stack_height.push_n(2);
-                    } else {
-                        self.stack_height.push1();
-                    }
+                    self.stack_height.push_type(*param);
                     self.alloc.stack_types.push(*param);
                 });
             }
             BlockType::Type(val_type) => {
-                if val_type == ValType::I64 || val_type == ValType::F64 {
-                    self.stack_height.push_n(2);
-                } else {
-                    self.stack_height.push1();
-                }
+                self.stack_height.push_type(val_type);
                 self.alloc.stack_types.push(val_type);
             }
             _ => {}
         }
-        if self.is_fuel_metering_enabled() && !self.alloc.control_frames.is_empty() {
+        if self.is_eager_fuel_metering() && !self.alloc.control_frames.is_empty() {
             let fuel_ix = self.push_consume_fuel_empty();
             let mut frame = self.alloc.control_frames.pop_frame();
             frame.update_consume_fuel_instr(fuel_ix);
@@ -905,7 +1185,12 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
 
             match builder.acquire_target(relative_depth)? {
                 AcquiredTarget::Branch(end_label, drop_keep) => {
-                    builder.bump_fuel_consumption(|| FuelCosts::BASE)?;
+                    builder
+                        .bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Base))?;
+                    builder.charge_pending_fuel();
+                    for _ in 0..builder.try_frames_to_leave(relative_depth) {
+                        builder.alloc.instruction_set.op_try_end();
+                    }
                     drop_keep.translate_drop_keep(
                         &mut builder.alloc.instruction_set,
                         &mut builder.stack_height,
@@ -916,7 +1201,8 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
                 }
                 AcquiredTarget::Return(_) => {
                     // In this case, the `br` can be directly translated as `return`.
-                    builder.bump_fuel_consumption(|| FuelCosts::BASE)?;
+                    builder
+                        .bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Base))?;
                     builder.visit_return()?;
                 }
             }
@@ -930,10 +1216,13 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
             builder.stack_height.pop1();
             builder.alloc.stack_types.pop().unwrap();
             builder.add_branch(relative_depth);
+            let try_ends = builder.try_frames_to_leave(relative_depth) as usize;
             match builder.acquire_target(relative_depth)? {
                 AcquiredTarget::Branch(end_label, drop_keep) => {
-                    builder.bump_fuel_consumption(|| FuelCosts::BASE)?;
-                    if drop_keep.is_noop() {
+                    builder
+                        .bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Base))?;
+                    builder.charge_pending_fuel();
+                    if drop_keep.is_noop() && try_ends == 0 {
                         let offset = builder.branch_offset(end_label)?;
                         builder.alloc.instruction_set.op_br_if_nez(offset);
                     } else {
@@ -941,10 +1230,14 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
                             .alloc
                             .instruction_set
                             .op_br_if_eqz(BranchOffset::uninit());
-                        let drop_keep_length = drop_keep.translate_drop_keep(
-                            &mut builder.alloc.instruction_set,
-                            &mut builder.stack_height,
-                        );
+                        for _ in 0..try_ends {
+                            builder.alloc.instruction_set.op_try_end();
+                        }
+                        let drop_keep_length = try_ends
+                            + drop_keep.translate_drop_keep(
+                                &mut builder.alloc.instruction_set,
+                                &mut builder.stack_height,
+                            );
                         builder
                             .alloc
                             .instruction_set
@@ -956,15 +1249,21 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
                     }
                 }
                 AcquiredTarget::Return(drop_keep) => {
-                    builder.bump_fuel_consumption(|| FuelCosts::BASE)?;
+                    builder
+                        .bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Base))?;
+                    builder.charge_pending_fuel();
                     builder
                         .alloc
                         .instruction_set
                         .op_br_if_eqz(BranchOffset::uninit());
-                    let drop_keep_length = drop_keep.translate_drop_keep(
-                        &mut builder.alloc.instruction_set,
-                        &mut builder.stack_height,
-                    );
+                    for _ in 0..try_ends {
+                        builder.alloc.instruction_set.op_try_end();
+                    }
+                    let drop_keep_length = try_ends
+                        + drop_keep.translate_drop_keep(
+                            &mut builder.alloc.instruction_set,
+                            &mut builder.stack_height,
+                        );
                     builder
                         .alloc
                         .instruction_set
@@ -975,7 +1274,7 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
                 }
             }
 
-            if builder.is_fuel_metering_enabled() {
+            if builder.is_eager_fuel_metering() {
                 let fuel_ix = builder.push_consume_fuel_empty();
                 let mut frame = builder.alloc.control_frames.pop_frame();
                 frame.update_consume_fuel_instr(fuel_ix);
@@ -989,8 +1288,8 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
     fn visit_br_table(&mut self, targets: BrTable<'a>) -> Self::Output {
         #[derive(Debug, Copy, Clone)]
         enum BrTableTarget {
-            Return(DropKeep),
-            Label(LabelRef, DropKeep),
+            Return(DropKeep, u32),
+            Label(LabelRef, DropKeep, u32),
         }
 
         self.translate_if_reachable(|builder| {
@@ -1000,7 +1299,9 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
 
             fn fuel_for_drop_keep(builder: &mut InstructionTranslator, drop_keep: DropKeep) -> u32 {
                 if builder.with_consume_fuel {
-                    FuelCosts::fuel_for_drop_keep(drop_keep.drop, drop_keep.keep)
+                    builder
+                        .fuel_cost_model
+                        .drop_keep_cost(drop_keep.drop, drop_keep.keep)
                 } else {
                     0
                 }
@@ -1011,11 +1312,14 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
                 n: usize,
                 depth: RelativeDepth,
             ) -> Result<BrTableTarget, CompilationError> {
+                let try_ends = builder.try_frames_to_leave(depth.into_u32());
                 match builder.acquire_target(depth.into_u32())? {
                     AcquiredTarget::Branch(label, drop_keep) => {
-                        Ok(BrTableTarget::Label(label, drop_keep))
+                        Ok(BrTableTarget::Label(label, drop_keep, try_ends))
+                    }
+                    AcquiredTarget::Return(drop_keep) => {
+                        Ok(BrTableTarget::Return(drop_keep, try_ends))
                     }
-                    AcquiredTarget::Return(drop_keep) => Ok(BrTableTarget::Return(drop_keep)),
                 }
             }
 
@@ -1027,11 +1331,11 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
                 final_len: usize,
             ) -> Result<(), CompilationError> {
                 match target {
-                    BrTableTarget::Return(drop_keep) => {
+                    BrTableTarget::Return(drop_keep, try_ends) => {
                         // Case: We push `Return` two times to make all branch targets use 2
                         // instruction words.       This is important to
                         // make `br_table` dispatch efficient.
-                        if drop_keep.is_noop() {
+                        if drop_keep.is_noop() && try_ends == 0 {
                             builder.alloc.br_table_branches.op_return();
                             builder.alloc.br_table_branches.op_return();
                         } else {
@@ -1040,14 +1344,17 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
                                     + trampoline_ixs.len()) as i32,
                             );
                             builder.alloc.br_table_branches.op_return();
+                            for _ in 0..try_ends {
+                                trampoline_ixs.op_try_end();
+                            }
                             drop_keep
                                 .translate_drop_keep(trampoline_ixs, &mut builder.stack_height);
                             trampoline_ixs.op_return();
                         }
                     }
-                    BrTableTarget::Label(label, drop_keep) => {
+                    BrTableTarget::Label(label, drop_keep, try_ends) => {
                         let base = builder.current_pc();
-                        if drop_keep.is_noop() {
+                        if drop_keep.is_noop() && try_ends == 0 {
                             builder.alloc.br_table_branches.op_return();
 
                             let instr = offset_instr(base, builder.alloc.br_table_branches.len());
@@ -1066,6 +1373,9 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
                                 .op_br(BranchOffset::from(br_offset));
                             builder.alloc.br_table_branches.op_return();
 
+                            for _ in 0..try_ends {
+                                trampoline_ixs.op_try_end();
+                            }
                             drop_keep
                                 .translate_drop_keep(trampoline_ixs, &mut builder.stack_height);
                             trampoline_ixs.op_return();
@@ -1094,7 +1404,8 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
                 })
                 .map(RelativeDepth::from_u32);
 
-            builder.bump_fuel_consumption(|| FuelCosts::BASE)?;
+            builder.bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Base))?;
+            builder.charge_pending_fuel();
             builder.stack_height.pop1();
             builder.alloc.stack_types.pop().unwrap();
 
@@ -1130,6 +1441,10 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
 
     fn visit_return(&mut self) -> Self::Output {
         self.translate_if_reachable(|builder| {
+            builder.charge_pending_fuel();
+            for _ in 0..builder.try_frames_to_leave(builder.max_depth()) {
+                builder.alloc.instruction_set.op_try_end();
+            }
             let drop_keep = builder.drop_keep_return()?;
             drop_keep.translate_drop_keep(
                 &mut builder.alloc.instruction_set,
@@ -1143,7 +1458,8 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
 
     fn visit_call(&mut self, function_index: u32) -> Self::Output {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(|| FuelCosts::CALL)?;
+            builder.bump_fuel_consumption(|model| model.call_cost())?;
+            builder.charge_pending_fuel();
             let func_type_idx = builder.alloc.resolve_func_type_index(function_index);
             builder.adjust_value_stack_for_call(func_type_idx);
             builder
@@ -1160,7 +1476,8 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
         _table_byte: u8,
     ) -> Self::Output {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(|| FuelCosts::CALL)?;
+            builder.bump_fuel_consumption(|model| model.call_cost())?;
+            builder.charge_pending_fuel();
             builder.stack_height.pop1();
             builder.alloc.stack_types.pop().unwrap();
             builder.adjust_value_stack_for_call(func_type_index as FuncTypeIdx);
@@ -1185,7 +1502,11 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
                 .func_type_registry
                 .resolve_func_type(func_type_idx);
             let drop_keep = builder.drop_keep_return_call(func_type)?;
-            builder.bump_fuel_consumption(|| FuelCosts::CALL)?;
+            builder.bump_fuel_consumption(|model| model.call_cost())?;
+            builder.charge_pending_fuel();
+            for _ in 0..builder.try_frames_to_leave(builder.max_depth()) {
+                builder.alloc.instruction_set.op_try_end();
+            }
             drop_keep.translate_drop_keep(
                 &mut builder.alloc.instruction_set,
                 &mut builder.stack_height,
@@ -1213,7 +1534,11 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
             let mut drop_keep = builder.drop_keep_return_call(func_type)?;
             // TODO(dmitry123): "why? is there a bug in [drop_keep_return_call]?"
             drop_keep.keep += 1;
-            builder.bump_fuel_consumption(|| FuelCosts::CALL)?;
+            builder.bump_fuel_consumption(|model| model.call_cost())?;
+            builder.charge_pending_fuel();
+            for _ in 0..builder.try_frames_to_leave(builder.max_depth()) {
+                builder.alloc.instruction_set.op_try_end();
+            }
             drop_keep.translate_drop_keep(
                 &mut builder.alloc.instruction_set,
                 &mut builder.stack_height,
@@ -1232,20 +1557,53 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
         })
     }
 
-    fn visit_delegate(&mut self, _relative_depth: u32) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_delegate(&mut self, relative_depth: u32) -> Self::Output {
+        if let ControlFrame::Try(try_frame) = self.alloc.control_frames.last() {
+            let mut try_frame = *try_frame;
+            // The depth is relative to the parent of the `try` frame, so the handler entries of
+            // the frames in between are dropped together with the own one.
+            let depth = self.try_frames_to_leave(relative_depth) - 1;
+            self.end_try_stage(&try_frame, true)?;
+            self.pin_label(try_frame.next_label());
+            try_frame.enter_stage(TryStage::Delegate, try_frame.next_label());
+            if self.is_eager_fuel_metering() {
+                let fuel_ix = self.push_consume_fuel_empty();
+                try_frame.update_consume_fuel_instr(fuel_ix);
+            }
+            self.alloc.control_frames.pop_frame();
+            self.alloc.control_frames.push_frame(try_frame);
+            self.bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Base))?;
+            self.charge_pending_fuel();
+            self.alloc.instruction_set.op_delegate(depth);
+            self.reachable = false;
+        }
+        // `delegate` also ends the `try` block.
+        self.visit_end()
     }
 
     fn visit_catch_all(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        let mut try_frame = match self.alloc.control_frames.pop_frame() {
+            ControlFrame::Try(try_frame) => try_frame,
+            ControlFrame::Unreachable(frame) if matches!(frame.kind(), ControlFrameKind::Try) => {
+                // The `catch_all` clause of an unreachable `try` block is unreachable too.
+                self.alloc.control_frames.push_frame(frame);
+                return Ok(());
+            }
+            unexpected => panic!(
+                "expected `try` control flow frame on top \
+                for `catch_all` but found: {unexpected:?}",
+            ),
+        };
+        self.end_try_stage(&try_frame, true)?;
+        self.begin_try_handler(&mut try_frame, TryStage::CatchAll)?;
+        self.alloc.control_frames.push_frame(try_frame);
+        Ok(())
     }
 
     fn visit_drop(&mut self) -> Self::Output {
         self.translate_if_reachable(|builder| {
-            builder.stack_height.pop1();
             let item_type = builder.alloc.stack_types.pop().unwrap();
-            builder.alloc.instruction_set.op_drop();
-            if item_type == ValType::I64 || item_type == ValType::F64 {
+            for _ in 0..value_slots(item_type) {
                 builder.stack_height.pop1();
                 builder.alloc.instruction_set.op_drop();
             }
@@ -1255,19 +1613,23 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
 
     fn visit_select(&mut self) -> Self::Output {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(|| FuelCosts::BASE)?;
+            builder.bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Base))?;
             builder.stack_height.pop3();
             builder.stack_height.push1();
             builder.alloc.stack_types.pop().unwrap();
             let item = builder.alloc.stack_types.pop().unwrap();
-            if item == ValType::I64 || item == ValType::F64 {
-                builder.stack_height.pop1();
-                builder.alloc.instruction_set.op_br_if_eqz(4);
-                builder.alloc.instruction_set.op_drop();
-                builder.alloc.instruction_set.op_drop();
-                builder.alloc.instruction_set.op_br(3);
-                builder.alloc.instruction_set.op_local_set(2);
-                builder.alloc.instruction_set.op_local_set(2);
+            let slots = value_slots(item);
+            if slots > 1 {
+                // the words of the second operand are either dropped or moved over the first one
+                builder.stack_height.pop_n(slots - 1);
+                builder.alloc.instruction_set.op_br_if_eqz(slots as i32 + 2);
+                for _ in 0..slots {
+                    builder.alloc.instruction_set.op_drop();
+                }
+                builder.alloc.instruction_set.op_br(slots as i32 + 1);
+                for _ in 0..slots {
+                    builder.alloc.instruction_set.op_local_set(slots);
+                }
             } else {
                 builder.alloc.instruction_set.op_select();
             }
@@ -1283,14 +1645,12 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
 
     fn visit_local_get(&mut self, local_index: u32) -> Self::Output {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(|| FuelCosts::BASE)?;
+            builder.bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Base))?;
             let local_depth = builder.relative_local_depth(local_index);
             let value =
                 builder.alloc.stack_types[builder.alloc.stack_types.len() - local_depth as usize];
             let expressed_depth = builder.get_expressed_depth(local_depth);
-            builder.alloc.instruction_set.op_local_get(expressed_depth);
-            builder.stack_height.push1();
-            if value == ValType::I64 || value == ValType::F64 {
+            for _ in 0..value_slots(value) {
                 builder.alloc.instruction_set.op_local_get(expressed_depth);
                 builder.stack_height.push1();
             }
@@ -1301,13 +1661,11 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
 
     fn visit_local_set(&mut self, local_index: u32) -> Self::Output {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(|| FuelCosts::BASE)?;
-            builder.stack_height.pop1();
+            builder.bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Base))?;
             let value_type = builder.alloc.stack_types.pop().unwrap();
             let local_depth = builder.relative_local_depth(local_index);
             let expressed_depth = builder.get_expressed_depth(local_depth);
-            builder.alloc.instruction_set.op_local_set(expressed_depth);
-            if value_type == ValType::I64 || value_type == ValType::F64 {
+            for _ in 0..value_slots(value_type) {
                 builder.alloc.instruction_set.op_local_set(expressed_depth);
                 builder.stack_height.pop1();
             }
@@ -1317,19 +1675,25 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
 
     fn visit_local_tee(&mut self, local_index: u32) -> Self::Output {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(|| FuelCosts::BASE)?;
+            builder.bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Base))?;
             let local_depth = builder.relative_local_depth(local_index);
             let expressed_depth = builder.get_expressed_depth(local_depth);
-            let value_type = builder.alloc.stack_types.last().unwrap();
-            if *value_type == ValType::I64 || *value_type == ValType::F64 {
+            let slots = value_slots(*builder.alloc.stack_types.last().unwrap());
+            if slots > 1 {
                 builder.stack_height.push1();
                 builder.stack_height.pop1();
+                // the top word is written in place, the lower words are copied one by one
                 builder
                     .alloc
                     .instruction_set
-                    .op_local_tee(expressed_depth - 1);
-                builder.alloc.instruction_set.op_local_get(2);
-                builder.alloc.instruction_set.op_local_set(expressed_depth);
+                    .op_local_tee(expressed_depth - (slots - 1));
+                for word in 0..slots - 1 {
+                    builder.alloc.instruction_set.op_local_get(slots - word);
+                    builder
+                        .alloc
+                        .instruction_set
+                        .op_local_set(expressed_depth - word);
+                }
             } else {
                 builder.alloc.instruction_set.op_local_tee(expressed_depth);
             }
@@ -1339,25 +1703,17 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
 
     fn visit_global_get(&mut self, global_index: u32) -> Self::Output {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(|| FuelCosts::ENTITY)?;
+            builder.bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Entity))?;
             let global_type = *builder.resolve_global_type(global_index);
+            let global_word = builder.resolve_global_word(global_index);
             builder.alloc.stack_types.push(global_type.content_type);
             if global_type.content_type == ValType::I64 || global_type.content_type == ValType::F64
             {
-                builder
-                    .alloc
-                    .instruction_set
-                    .op_global_get(global_index * 2 + 1);
-                builder
-                    .alloc
-                    .instruction_set
-                    .op_global_get(global_index * 2);
+                builder.alloc.instruction_set.op_global_get(global_word + 1);
+                builder.alloc.instruction_set.op_global_get(global_word);
                 builder.stack_height.push_n(2);
             } else {
-                builder
-                    .alloc
-                    .instruction_set
-                    .op_global_get(global_index * 2);
+                builder.alloc.instruction_set.op_global_get(global_word);
                 builder.stack_height.push1();
             }
             Ok(())
@@ -1366,21 +1722,16 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
 
     fn visit_global_set(&mut self, global_index: u32) -> Self::Output {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(|| FuelCosts::ENTITY)?;
+            builder.bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Entity))?;
             let global_type = *builder.resolve_global_type(global_index);
+            let global_word = builder.resolve_global_word(global_index);
             debug_assert!(global_type.mutable);
             builder.alloc.stack_types.pop().unwrap();
-            builder
-                .alloc
-                .instruction_set
-                .op_global_set(global_index * 2);
+            builder.alloc.instruction_set.op_global_set(global_word);
             builder.stack_height.pop1();
             if global_type.content_type == ValType::I64 || global_type.content_type == ValType::F64
             {
-                builder
-                    .alloc
-                    .instruction_set
-                    .op_global_set(global_index * 2 + 1);
+                builder.alloc.instruction_set.op_global_set(global_word + 1);
                 builder.stack_height.pop1();
             }
             Ok(())
@@ -1401,11 +1752,25 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
     }
 
     fn visit_f32_load(&mut self, memarg: MemArg) -> Self::Output {
-        self.translate_load(memarg, ValType::F32, InstructionSet::op_f32_load, 0)
+        // fpu loads exist for the default memory only, integer loads give the same bits
+        #[cfg(feature = "fpu")]
+        if memarg.memory == crate::DEFAULT_MEMORY_INDEX {
+            return self.translate_load(memarg, ValType::F32, InstructionSet::op_f32_load, 0);
+        }
+        self.translate_load(memarg, ValType::F32, InstructionSet::op_i32_load, 0)
     }
 
     fn visit_f64_load(&mut self, memarg: MemArg) -> Self::Output {
-        self.translate_load(memarg, ValType::F64, InstructionSet::op_f64_load, 0)
+        #[cfg(feature = "fpu")]
+        if memarg.memory == crate::DEFAULT_MEMORY_INDEX {
+            return self.translate_load(memarg, ValType::F64, InstructionSet::op_f64_load, 0);
+        }
+        self.translate_load(
+            memarg,
+            ValType::F64,
+            InstructionSet::op_i64_load,
+            InstructionSet::MSH_I64_LOAD,
+        )
     }
 
     fn visit_i32_load8_s(&mut self, memarg: MemArg) -> Self::Output {
@@ -1492,11 +1857,24 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
     }
 
     fn visit_f32_store(&mut self, memarg: MemArg) -> Self::Output {
-        self.translate_store(memarg, ValType::F32, InstructionSet::op_f32_store, 0)
+        #[cfg(feature = "fpu")]
+        if memarg.memory == crate::DEFAULT_MEMORY_INDEX {
+            return self.translate_store(memarg, ValType::F32, InstructionSet::op_f32_store, 0);
+        }
+        self.translate_store(memarg, ValType::F32, InstructionSet::op_i32_store, 0)
     }
 
     fn visit_f64_store(&mut self, memarg: MemArg) -> Self::Output {
-        self.translate_store(memarg, ValType::F64, InstructionSet::op_f64_store, 0)
+        #[cfg(feature = "fpu")]
+        if memarg.memory == crate::DEFAULT_MEMORY_INDEX {
+            return self.translate_store(memarg, ValType::F64, InstructionSet::op_f64_store, 0);
+        }
+        self.translate_store(
+            memarg,
+            ValType::F64,
+            InstructionSet::op_i64_store,
+            InstructionSet::MSH_I64_STORE,
+        )
     }
 
     fn visit_i32_store8(&mut self, memarg: MemArg) -> Self::Output {
@@ -1521,19 +1899,22 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
 
     fn visit_memory_size(&mut self, memory_index: u32, _mem_byte: u8) -> Self::Output {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(|| FuelCosts::ENTITY)?;
-            debug_assert_eq!(memory_index, DEFAULT_MEMORY_INDEX);
+            builder.bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Entity))?;
             builder.alloc.stack_types.push(ValType::I32);
             builder.stack_height.push1();
+            let start = builder.alloc.instruction_set.loc();
             builder.alloc.instruction_set.op_memory_size();
+            builder
+                .alloc
+                .instruction_set
+                .retarget_memory(start, MemoryIdx::try_from(memory_index).unwrap());
             Ok(())
         })
     }
 
     fn visit_memory_grow(&mut self, memory_index: u32, _mem_byte: u8) -> Self::Output {
         self.translate_if_reachable(|builder| {
-            debug_assert_eq!(memory_index, DEFAULT_MEMORY_INDEX);
-            builder.bump_fuel_consumption(|| FuelCosts::ENTITY)?;
+            builder.bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Entity))?;
             // for rWASM, we inject memory limit error check, if we exceed the number of allowed
             // pages, then we push `u32::MAX` value on the stack that is equal to memory grow
             // overflow error
@@ -1543,12 +1924,16 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
                 .and_then(|v| u32::try_from(v).ok())
                 .filter(|v| *v <= builder.max_allowed_memory_pages)
                 .unwrap_or(builder.max_allowed_memory_pages);
-            let inject_fuel_check =
-                builder.consume_fuel_for_bulk_ops && builder.is_fuel_metering_enabled();
+            let fuel_cost_model = builder.bulk_fuel_cost_model();
+            let start = builder.alloc.instruction_set.loc();
+            builder
+                .alloc
+                .instruction_set
+                .op_memory_grow_checked_with_model(Some(max_pages), fuel_cost_model.as_deref());
             builder
                 .alloc
                 .instruction_set
-                .op_memory_grow_checked(Some(max_pages), inject_fuel_check);
+                .retarget_memory(start, MemoryIdx::try_from(memory_index).unwrap());
             // make sure types are correct
             let popped_type = builder.alloc.stack_types.pop().unwrap();
             debug_assert_eq!(popped_type, ValType::I32);
@@ -1564,7 +1949,7 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
 
     fn visit_i32_const(&mut self, value: i32) -> Self::Output {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(|| FuelCosts::BASE)?;
+            builder.bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Base))?;
             builder.alloc.stack_types.push(ValType::I32);
             builder.stack_height.push1();
             builder.alloc.instruction_set.op_i32_const(value);
@@ -1574,7 +1959,7 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
 
     fn visit_i64_const(&mut self, value: i64) -> Self::Output {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(|| FuelCosts::BASE)?;
+            builder.bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Base))?;
             builder.alloc.stack_types.push(ValType::I64);
             builder.stack_height.push2();
             builder.alloc.instruction_set.op_i64_const(value);
@@ -1584,7 +1969,7 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
 
     fn visit_f32_const(&mut self, value: Ieee32) -> Self::Output {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(|| FuelCosts::BASE)?;
+            builder.bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Base))?;
             builder.alloc.stack_types.push(ValType::F32);
             builder.stack_height.push1();
             use crate::F32;
@@ -1596,7 +1981,7 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
 
     fn visit_f64_const(&mut self, value: Ieee64) -> Self::Output {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(|| FuelCosts::BASE)?;
+            builder.bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Base))?;
             builder.alloc.stack_types.push(ValType::F64);
             builder.stack_height.push2();
             let value = value.bits() as i64;
@@ -1607,7 +1992,7 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
 
     fn visit_ref_null(&mut self, ty: ValType) -> Self::Output {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(|| FuelCosts::BASE)?;
+            builder.bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Base))?;
             // Since `rwasm` bytecode is untyped, we have no special `null` instructions
             // but simply reuse the `constant` instruction with an immediate value of 0.
             // IMPORTANT: We still must track the correct Wasm type on the emulated type stack,
@@ -1633,7 +2018,7 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
 
     fn visit_ref_func(&mut self, function_index: u32) -> Self::Output {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(|| FuelCosts::BASE)?;
+            builder.bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Base))?;
             builder.alloc.stack_types.push(ValType::FuncRef);
             builder.stack_height.push1();
             // We do +1 here because 0 offset is reserved for `null` value and an entrypoint
@@ -1734,51 +2119,87 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
     }
 
     fn visit_f32_eq(&mut self) -> Self::Output {
-        self.translate_binary_compare(InstructionSet::op_f32_eq, 0)
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_call(Snippet::F32Compare, Snippet::COMPARE_EQ);
+        #[cfg(feature = "fpu")]
+        return self.translate_binary_compare(InstructionSet::op_f32_eq, 0);
     }
 
     fn visit_f32_ne(&mut self) -> Self::Output {
-        self.translate_binary_compare(InstructionSet::op_f32_ne, 0)
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_call(Snippet::F32Compare, Snippet::COMPARE_NE);
+        #[cfg(feature = "fpu")]
+        return self.translate_binary_compare(InstructionSet::op_f32_ne, 0);
     }
 
     fn visit_f32_lt(&mut self) -> Self::Output {
-        self.translate_binary_compare(InstructionSet::op_f32_lt, 0)
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_call(Snippet::F32Compare, Snippet::COMPARE_LT);
+        #[cfg(feature = "fpu")]
+        return self.translate_binary_compare(InstructionSet::op_f32_lt, 0);
     }
 
     fn visit_f32_gt(&mut self) -> Self::Output {
-        self.translate_binary_compare(InstructionSet::op_f32_gt, 0)
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_call(Snippet::F32Compare, Snippet::COMPARE_GT);
+        #[cfg(feature = "fpu")]
+        return self.translate_binary_compare(InstructionSet::op_f32_gt, 0);
     }
 
     fn visit_f32_le(&mut self) -> Self::Output {
-        self.translate_binary_compare(InstructionSet::op_f32_le, 0)
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_call(Snippet::F32Compare, Snippet::COMPARE_LE);
+        #[cfg(feature = "fpu")]
+        return self.translate_binary_compare(InstructionSet::op_f32_le, 0);
     }
 
     fn visit_f32_ge(&mut self) -> Self::Output {
-        self.translate_binary_compare(InstructionSet::op_f32_ge, 0)
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_call(Snippet::F32Compare, Snippet::COMPARE_GE);
+        #[cfg(feature = "fpu")]
+        return self.translate_binary_compare(InstructionSet::op_f32_ge, 0);
     }
 
     fn visit_f64_eq(&mut self) -> Self::Output {
-        self.translate_binary_compare(InstructionSet::op_f64_eq, 0)
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_call(Snippet::F64Compare, Snippet::COMPARE_EQ);
+        #[cfg(feature = "fpu")]
+        return self.translate_binary_compare(InstructionSet::op_f64_eq, 0);
     }
 
     fn visit_f64_ne(&mut self) -> Self::Output {
-        self.translate_binary_compare(InstructionSet::op_f64_ne, 0)
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_call(Snippet::F64Compare, Snippet::COMPARE_NE);
+        #[cfg(feature = "fpu")]
+        return self.translate_binary_compare(InstructionSet::op_f64_ne, 0);
     }
 
     fn visit_f64_lt(&mut self) -> Self::Output {
-        self.translate_binary_compare(InstructionSet::op_f64_lt, 0)
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_call(Snippet::F64Compare, Snippet::COMPARE_LT);
+        #[cfg(feature = "fpu")]
+        return self.translate_binary_compare(InstructionSet::op_f64_lt, 0);
     }
 
     fn visit_f64_gt(&mut self) -> Self::Output {
-        self.translate_binary_compare(InstructionSet::op_f64_gt, 0)
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_call(Snippet::F64Compare, Snippet::COMPARE_GT);
+        #[cfg(feature = "fpu")]
+        return self.translate_binary_compare(InstructionSet::op_f64_gt, 0);
     }
 
     fn visit_f64_le(&mut self) -> Self::Output {
-        self.translate_binary_compare(InstructionSet::op_f64_le, 0)
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_call(Snippet::F64Compare, Snippet::COMPARE_LE);
+        #[cfg(feature = "fpu")]
+        return self.translate_binary_compare(InstructionSet::op_f64_le, 0);
     }
 
     fn visit_f64_ge(&mut self) -> Self::Output {
-        self.translate_binary_compare(InstructionSet::op_f64_ge, 0)
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_call(Snippet::F64Compare, Snippet::COMPARE_GE);
+        #[cfg(feature = "fpu")]
+        return self.translate_binary_compare(InstructionSet::op_f64_ge, 0);
     }
 
     fn visit_i32_clz(&mut self) -> Self::Output {
@@ -1806,18 +2227,22 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
     }
 
     fn visit_i32_div_s(&mut self) -> Self::Output {
+        self.charge_pending_fuel();
         self.translate_binary(InstructionSet::op_i32_div_s, 0)
     }
 
     fn visit_i32_div_u(&mut self) -> Self::Output {
+        self.charge_pending_fuel();
         self.translate_binary(InstructionSet::op_i32_div_u, 0)
     }
 
     fn visit_i32_rem_s(&mut self) -> Self::Output {
+        self.charge_pending_fuel();
         self.translate_binary(InstructionSet::op_i32_rem_s, 0)
     }
 
     fn visit_i32_rem_u(&mut self) -> Self::Output {
+        self.charge_pending_fuel();
         self.translate_binary(InstructionSet::op_i32_rem_u, 0)
     }
 
@@ -1881,18 +2306,22 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
     }
 
     fn visit_i64_div_s(&mut self) -> Self::Output {
+        self.charge_pending_fuel();
         self.translate_to_snippet_call(Snippet::I64DivS)
     }
 
     fn visit_i64_div_u(&mut self) -> Self::Output {
+        self.charge_pending_fuel();
         self.translate_to_snippet_call(Snippet::I64DivU)
     }
 
     fn visit_i64_rem_s(&mut self) -> Self::Output {
+        self.charge_pending_fuel();
         self.translate_to_snippet_call(Snippet::I64RemS)
     }
 
     fn visit_i64_rem_u(&mut self) -> Self::Output {
+        self.charge_pending_fuel();
         self.translate_to_snippet_call(Snippet::I64RemU)
     }
 
@@ -1929,120 +2358,232 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
     }
 
     fn visit_f32_abs(&mut self) -> Self::Output {
-        self.translate_unary(InstructionSet::op_f32_abs, 0)
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_unary(
+            InstructionSet::op_soft_f32_abs,
+            InstructionSet::MSH_SOFT_F32_ABS,
+        );
+        #[cfg(feature = "fpu")]
+        return self.translate_unary(InstructionSet::op_f32_abs, 0);
     }
 
     fn visit_f32_neg(&mut self) -> Self::Output {
-        self.translate_unary(InstructionSet::op_f32_neg, 0)
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_unary(
+            InstructionSet::op_soft_f32_neg,
+            InstructionSet::MSH_SOFT_F32_NEG,
+        );
+        #[cfg(feature = "fpu")]
+        return self.translate_unary(InstructionSet::op_f32_neg, 0);
     }
 
     fn visit_f32_ceil(&mut self) -> Self::Output {
-        self.translate_unary(InstructionSet::op_f32_ceil, 0)
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_call(Snippet::F32Round, Snippet::ROUND_CEIL);
+        #[cfg(feature = "fpu")]
+        return self.translate_unary(InstructionSet::op_f32_ceil, 0);
     }
 
     fn visit_f32_floor(&mut self) -> Self::Output {
-        self.translate_unary(InstructionSet::op_f32_floor, 0)
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_call(Snippet::F32Round, Snippet::ROUND_FLOOR);
+        #[cfg(feature = "fpu")]
+        return self.translate_unary(InstructionSet::op_f32_floor, 0);
     }
 
     fn visit_f32_trunc(&mut self) -> Self::Output {
-        self.translate_unary(InstructionSet::op_f32_trunc, 0)
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_call(Snippet::F32Round, Snippet::ROUND_TRUNC);
+        #[cfg(feature = "fpu")]
+        return self.translate_unary(InstructionSet::op_f32_trunc, 0);
     }
 
     fn visit_f32_nearest(&mut self) -> Self::Output {
-        self.translate_unary(InstructionSet::op_f32_nearest, 0)
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_call(Snippet::F32Round, Snippet::ROUND_NEAREST);
+        #[cfg(feature = "fpu")]
+        return self.translate_unary(InstructionSet::op_f32_nearest, 0);
     }
 
     fn visit_f32_sqrt(&mut self) -> Self::Output {
-        self.translate_unary(InstructionSet::op_f32_sqrt, 0)
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_to_snippet_call(Snippet::F32Sqrt);
+        #[cfg(feature = "fpu")]
+        return self.translate_unary(InstructionSet::op_f32_sqrt, 0);
     }
 
     fn visit_f32_add(&mut self) -> Self::Output {
-        self.translate_binary(InstructionSet::op_f32_add, 0)
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_to_snippet_call(Snippet::F32Add);
+        #[cfg(feature = "fpu")]
+        return self.translate_binary(InstructionSet::op_f32_add, 0);
     }
 
     fn visit_f32_sub(&mut self) -> Self::Output {
-        self.translate_binary(InstructionSet::op_f32_sub, 0)
+        #[cfg(not(feature = "fpu"))]
+        return self
+            .translate_unary(
+                InstructionSet::op_soft_f32_neg,
+                InstructionSet::MSH_SOFT_F32_NEG,
+            )
+            .and_then(|_| self.translate_to_snippet_call(Snippet::F32Add));
+        #[cfg(feature = "fpu")]
+        return self.translate_binary(InstructionSet::op_f32_sub, 0);
     }
 
     fn visit_f32_mul(&mut self) -> Self::Output {
-        self.translate_binary(InstructionSet::op_f32_mul, 0)
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_to_snippet_call(Snippet::F32Mul);
+        #[cfg(feature = "fpu")]
+        return self.translate_binary(InstructionSet::op_f32_mul, 0);
     }
 
     fn visit_f32_div(&mut self) -> Self::Output {
-        self.translate_binary(InstructionSet::op_f32_div, 0)
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_to_snippet_call(Snippet::F32Div);
+        #[cfg(feature = "fpu")]
+        return self.translate_binary(InstructionSet::op_f32_div, 0);
     }
 
     fn visit_f32_min(&mut self) -> Self::Output {
-        self.translate_binary(InstructionSet::op_f32_min, 0)
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_call(Snippet::F32MinMax, 0);
+        #[cfg(feature = "fpu")]
+        return self.translate_binary(InstructionSet::op_f32_min, 0);
     }
 
     fn visit_f32_max(&mut self) -> Self::Output {
-        self.translate_binary(InstructionSet::op_f32_max, 0)
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_call(Snippet::F32MinMax, 1);
+        #[cfg(feature = "fpu")]
+        return self.translate_binary(InstructionSet::op_f32_max, 0);
     }
 
     fn visit_f32_copysign(&mut self) -> Self::Output {
-        self.translate_binary(InstructionSet::op_f32_copysign, 0)
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_binary(
+            InstructionSet::op_soft_f32_copysign,
+            InstructionSet::MSH_SOFT_F32_COPYSIGN,
+        );
+        #[cfg(feature = "fpu")]
+        return self.translate_binary(InstructionSet::op_f32_copysign, 0);
     }
 
     fn visit_f64_abs(&mut self) -> Self::Output {
-        self.translate_unary(InstructionSet::op_f64_abs, 0)
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_unary(
+            InstructionSet::op_soft_f64_abs,
+            InstructionSet::MSH_SOFT_F64_ABS,
+        );
+        #[cfg(feature = "fpu")]
+        return self.translate_unary(InstructionSet::op_f64_abs, 0);
     }
 
     fn visit_f64_neg(&mut self) -> Self::Output {
-        self.translate_unary(InstructionSet::op_f64_neg, 0)
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_unary(
+            InstructionSet::op_soft_f64_neg,
+            InstructionSet::MSH_SOFT_F64_NEG,
+        );
+        #[cfg(feature = "fpu")]
+        return self.translate_unary(InstructionSet::op_f64_neg, 0);
     }
 
     fn visit_f64_ceil(&mut self) -> Self::Output {
-        self.translate_unary(InstructionSet::op_f64_ceil, 0)
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_call(Snippet::F64Round, Snippet::ROUND_CEIL);
+        #[cfg(feature = "fpu")]
+        return self.translate_unary(InstructionSet::op_f64_ceil, 0);
     }
 
     fn visit_f64_floor(&mut self) -> Self::Output {
-        self.translate_unary(InstructionSet::op_f64_floor, 0)
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_call(Snippet::F64Round, Snippet::ROUND_FLOOR);
+        #[cfg(feature = "fpu")]
+        return self.translate_unary(InstructionSet::op_f64_floor, 0);
     }
 
     fn visit_f64_trunc(&mut self) -> Self::Output {
-        self.translate_unary(InstructionSet::op_f64_trunc, 0)
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_call(Snippet::F64Round, Snippet::ROUND_TRUNC);
+        #[cfg(feature = "fpu")]
+        return self.translate_unary(InstructionSet::op_f64_trunc, 0);
     }
 
     fn visit_f64_nearest(&mut self) -> Self::Output {
-        self.translate_unary(InstructionSet::op_f64_nearest, 0)
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_call(Snippet::F64Round, Snippet::ROUND_NEAREST);
+        #[cfg(feature = "fpu")]
+        return self.translate_unary(InstructionSet::op_f64_nearest, 0);
     }
 
     fn visit_f64_sqrt(&mut self) -> Self::Output {
-        self.translate_unary(InstructionSet::op_f64_sqrt, 0)
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_to_snippet_call(Snippet::F64Sqrt);
+        #[cfg(feature = "fpu")]
+        return self.translate_unary(InstructionSet::op_f64_sqrt, 0);
     }
 
     fn visit_f64_add(&mut self) -> Self::Output {
-        self.translate_binary(InstructionSet::op_f64_add, 0)
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_to_snippet_call(Snippet::F64Add);
+        #[cfg(feature = "fpu")]
+        return self.translate_binary(InstructionSet::op_f64_add, 0);
     }
 
     fn visit_f64_sub(&mut self) -> Self::Output {
-        self.translate_binary(InstructionSet::op_f64_sub, 0)
+        #[cfg(not(feature = "fpu"))]
+        return self
+            .translate_unary(
+                InstructionSet::op_soft_f64_neg,
+                InstructionSet::MSH_SOFT_F64_NEG,
+            )
+            .and_then(|_| self.translate_to_snippet_call(Snippet::F64Add));
+        #[cfg(feature = "fpu")]
+        return self.translate_binary(InstructionSet::op_f64_sub, 0);
     }
 
     fn visit_f64_mul(&mut self) -> Self::Output {
-        self.translate_binary(InstructionSet::op_f64_mul, 0)
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_to_snippet_call(Snippet::F64Mul);
+        #[cfg(feature = "fpu")]
+        return self.translate_binary(InstructionSet::op_f64_mul, 0);
     }
 
     fn visit_f64_div(&mut self) -> Self::Output {
-        self.translate_binary(InstructionSet::op_f64_div, 0)
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_to_snippet_call(Snippet::F64Div);
+        #[cfg(feature = "fpu")]
+        return self.translate_binary(InstructionSet::op_f64_div, 0);
     }
 
     fn visit_f64_min(&mut self) -> Self::Output {
-        self.translate_binary(InstructionSet::op_f64_min, 0)
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_call(Snippet::F64MinMax, 0);
+        #[cfg(feature = "fpu")]
+        return self.translate_binary(InstructionSet::op_f64_min, 0);
     }
 
     fn visit_f64_max(&mut self) -> Self::Output {
-        self.translate_binary(InstructionSet::op_f64_max, 0)
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_call(Snippet::F64MinMax, 1);
+        #[cfg(feature = "fpu")]
+        return self.translate_binary(InstructionSet::op_f64_max, 0);
     }
 
     fn visit_f64_copysign(&mut self) -> Self::Output {
-        self.translate_binary(InstructionSet::op_f64_copysign, 0)
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_binary(
+            InstructionSet::op_soft_f64_copysign,
+            InstructionSet::MSH_SOFT_F64_COPYSIGN,
+        );
+        #[cfg(feature = "fpu")]
+        return self.translate_binary(InstructionSet::op_f64_copysign, 0);
     }
 
     fn visit_i32_wrap_i64(&mut self) -> Self::Output {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(|| FuelCosts::BASE)?;
+            builder.bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Arithmetic))?;
             let popped_value = builder.alloc.stack_types.pop().unwrap();
             debug_assert_eq!(popped_value, ValType::I64);
             builder.alloc.stack_types.push(ValType::I32);
@@ -2053,39 +2594,55 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
     }
 
     fn visit_i32_trunc_f32_s(&mut self) -> Self::Output {
-        self.translate_conversion(
+        self.charge_pending_fuel();
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_truncation(Snippet::F32ToInt, Snippet::TO_INT_SIGNED);
+        #[cfg(feature = "fpu")]
+        return self.translate_conversion(
             ValType::F32,
             ValType::I32,
             InstructionSet::op_i32_trunc_f32_s,
             0,
-        )
+        );
     }
 
     fn visit_i32_trunc_f32_u(&mut self) -> Self::Output {
-        self.translate_conversion(
+        self.charge_pending_fuel();
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_truncation(Snippet::F32ToInt, 0);
+        #[cfg(feature = "fpu")]
+        return self.translate_conversion(
             ValType::F32,
             ValType::I32,
             InstructionSet::op_i32_trunc_f32_u,
             0,
-        )
+        );
     }
 
     fn visit_i32_trunc_f64_s(&mut self) -> Self::Output {
-        self.translate_conversion(
+        self.charge_pending_fuel();
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_truncation(Snippet::F64ToInt, Snippet::TO_INT_SIGNED);
+        #[cfg(feature = "fpu")]
+        return self.translate_conversion(
             ValType::F64,
             ValType::I32,
             InstructionSet::op_i32_trunc_f64_s,
             0,
-        )
+        );
     }
 
     fn visit_i32_trunc_f64_u(&mut self) -> Self::Output {
-        self.translate_conversion(
+        self.charge_pending_fuel();
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_truncation(Snippet::F64ToInt, 0);
+        #[cfg(feature = "fpu")]
+        return self.translate_conversion(
             ValType::F64,
             ValType::I32,
             InstructionSet::op_i32_trunc_f64_u,
             0,
-        )
+        );
     }
 
     fn visit_i64_extend_i32_s(&mut self) -> Self::Output {
@@ -2107,129 +2664,181 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
     }
 
     fn visit_i64_trunc_f32_s(&mut self) -> Self::Output {
-        self.translate_conversion(
+        self.charge_pending_fuel();
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_truncation(
+            Snippet::F32ToInt,
+            Snippet::TO_INT_SIGNED | Snippet::TO_INT_I64,
+        );
+        #[cfg(feature = "fpu")]
+        return self.translate_conversion(
             ValType::F32,
             ValType::I64,
             InstructionSet::op_i64_trunc_f32_s,
             0,
-        )
+        );
     }
 
     fn visit_i64_trunc_f32_u(&mut self) -> Self::Output {
-        self.translate_conversion(
+        self.charge_pending_fuel();
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_truncation(Snippet::F32ToInt, Snippet::TO_INT_I64);
+        #[cfg(feature = "fpu")]
+        return self.translate_conversion(
             ValType::F32,
             ValType::I64,
             InstructionSet::op_i64_trunc_f32_u,
             0,
-        )
+        );
     }
 
     fn visit_i64_trunc_f64_s(&mut self) -> Self::Output {
-        self.translate_conversion(
+        self.charge_pending_fuel();
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_truncation(
+            Snippet::F64ToInt,
+            Snippet::TO_INT_SIGNED | Snippet::TO_INT_I64,
+        );
+        #[cfg(feature = "fpu")]
+        return self.translate_conversion(
             ValType::F64,
             ValType::I64,
             InstructionSet::op_i64_trunc_f64_s,
             0,
-        )
+        );
     }
 
     fn visit_i64_trunc_f64_u(&mut self) -> Self::Output {
-        self.translate_conversion(
+        self.charge_pending_fuel();
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_truncation(Snippet::F64ToInt, Snippet::TO_INT_I64);
+        #[cfg(feature = "fpu")]
+        return self.translate_conversion(
             ValType::F64,
             ValType::I64,
             InstructionSet::op_i64_trunc_f64_u,
             0,
-        )
+        );
     }
 
     fn visit_f32_convert_i32_s(&mut self) -> Self::Output {
-        self.translate_conversion(
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_conversion(Snippet::F32FromInt, ValType::I32, true);
+        #[cfg(feature = "fpu")]
+        return self.translate_conversion(
             ValType::I32,
             ValType::F32,
             InstructionSet::op_f32_convert_i32_s,
             0,
-        )
+        );
     }
 
     fn visit_f32_convert_i32_u(&mut self) -> Self::Output {
-        self.translate_conversion(
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_conversion(Snippet::F32FromInt, ValType::I32, false);
+        #[cfg(feature = "fpu")]
+        return self.translate_conversion(
             ValType::I32,
             ValType::F32,
             InstructionSet::op_f32_convert_i32_u,
             0,
-        )
+        );
     }
 
     fn visit_f32_convert_i64_s(&mut self) -> Self::Output {
-        self.translate_conversion(
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_conversion(Snippet::F32FromInt, ValType::I64, true);
+        #[cfg(feature = "fpu")]
+        return self.translate_conversion(
             ValType::I64,
             ValType::F32,
             InstructionSet::op_f32_convert_i64_s,
             0,
-        )
+        );
     }
 
     fn visit_f32_convert_i64_u(&mut self) -> Self::Output {
-        self.translate_conversion(
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_conversion(Snippet::F32FromInt, ValType::I64, false);
+        #[cfg(feature = "fpu")]
+        return self.translate_conversion(
             ValType::I64,
             ValType::F32,
             InstructionSet::op_f32_convert_i64_u,
             0,
-        )
+        );
     }
 
     fn visit_f32_demote_f64(&mut self) -> Self::Output {
-        self.translate_conversion(
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_to_snippet_call(Snippet::F32DemoteF64);
+        #[cfg(feature = "fpu")]
+        return self.translate_conversion(
             ValType::F64,
             ValType::F32,
             InstructionSet::op_f32_demote_f64,
             0,
-        )
+        );
     }
 
     fn visit_f64_convert_i32_s(&mut self) -> Self::Output {
-        self.translate_conversion(
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_conversion(Snippet::F64FromInt, ValType::I32, true);
+        #[cfg(feature = "fpu")]
+        return self.translate_conversion(
             ValType::I32,
             ValType::F64,
             InstructionSet::op_f64_convert_i32_s,
             0,
-        )
+        );
     }
 
     fn visit_f64_convert_i32_u(&mut self) -> Self::Output {
-        self.translate_conversion(
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_conversion(Snippet::F64FromInt, ValType::I32, false);
+        #[cfg(feature = "fpu")]
+        return self.translate_conversion(
             ValType::I32,
             ValType::F64,
             InstructionSet::op_f64_convert_i32_u,
             0,
-        )
+        );
     }
 
     fn visit_f64_convert_i64_s(&mut self) -> Self::Output {
-        self.translate_conversion(
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_conversion(Snippet::F64FromInt, ValType::I64, true);
+        #[cfg(feature = "fpu")]
+        return self.translate_conversion(
             ValType::I64,
             ValType::F64,
             InstructionSet::op_f64_convert_i64_s,
             0,
-        )
+        );
     }
 
     fn visit_f64_convert_i64_u(&mut self) -> Self::Output {
-        self.translate_conversion(
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_conversion(Snippet::F64FromInt, ValType::I64, false);
+        #[cfg(feature = "fpu")]
+        return self.translate_conversion(
             ValType::I64,
             ValType::F64,
             InstructionSet::op_f64_convert_i64_u,
             0,
-        )
+        );
     }
 
     fn visit_f64_promote_f32(&mut self) -> Self::Output {
-        self.translate_conversion(
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_to_snippet_call(Snippet::F64PromoteF32);
+        #[cfg(feature = "fpu")]
+        return self.translate_conversion(
             ValType::F32,
             ValType::F64,
             InstructionSet::op_f64_promote_f32,
             0,
-        )
+        );
     }
 
     fn visit_i32_reinterpret_f32(&mut self) -> Self::Output {
@@ -2278,87 +2887,128 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
     }
 
     fn visit_i32_trunc_sat_f32_s(&mut self) -> Self::Output {
-        self.translate_conversion(
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_truncation(
+            Snippet::F32ToInt,
+            Snippet::TO_INT_SIGNED | Snippet::TO_INT_SATURATING,
+        );
+        #[cfg(feature = "fpu")]
+        return self.translate_conversion(
             ValType::F32,
             ValType::I32,
             InstructionSet::op_i32_trunc_sat_f32_s,
             0,
-        )
+        );
     }
 
     fn visit_i32_trunc_sat_f32_u(&mut self) -> Self::Output {
-        self.translate_conversion(
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_truncation(Snippet::F32ToInt, Snippet::TO_INT_SATURATING);
+        #[cfg(feature = "fpu")]
+        return self.translate_conversion(
             ValType::F32,
             ValType::I32,
             InstructionSet::op_i32_trunc_sat_f32_u,
             0,
-        )
+        );
     }
 
     fn visit_i32_trunc_sat_f64_s(&mut self) -> Self::Output {
-        self.translate_conversion(
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_truncation(
+            Snippet::F64ToInt,
+            Snippet::TO_INT_SIGNED | Snippet::TO_INT_SATURATING,
+        );
+        #[cfg(feature = "fpu")]
+        return self.translate_conversion(
             ValType::F64,
             ValType::I32,
             InstructionSet::op_i32_trunc_sat_f64_s,
             0,
-        )
+        );
     }
 
     fn visit_i32_trunc_sat_f64_u(&mut self) -> Self::Output {
-        self.translate_conversion(
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_truncation(Snippet::F64ToInt, Snippet::TO_INT_SATURATING);
+        #[cfg(feature = "fpu")]
+        return self.translate_conversion(
             ValType::F64,
             ValType::I32,
             InstructionSet::op_i32_trunc_sat_f64_u,
             0,
-        )
+        );
     }
 
     fn visit_i64_trunc_sat_f32_s(&mut self) -> Self::Output {
-        self.translate_conversion(
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_truncation(
+            Snippet::F32ToInt,
+            Snippet::TO_INT_SIGNED | Snippet::TO_INT_I64 | Snippet::TO_INT_SATURATING,
+        );
+        #[cfg(feature = "fpu")]
+        return self.translate_conversion(
             ValType::F32,
             ValType::I64,
             InstructionSet::op_i64_trunc_sat_f32_s,
             0,
-        )
+        );
     }
 
     fn visit_i64_trunc_sat_f32_u(&mut self) -> Self::Output {
-        self.translate_conversion(
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_truncation(
+            Snippet::F32ToInt,
+            Snippet::TO_INT_I64 | Snippet::TO_INT_SATURATING,
+        );
+        #[cfg(feature = "fpu")]
+        return self.translate_conversion(
             ValType::F32,
             ValType::I64,
             InstructionSet::op_i64_trunc_sat_f32_u,
             0,
-        )
+        );
     }
 
     fn visit_i64_trunc_sat_f64_s(&mut self) -> Self::Output {
-        self.translate_conversion(
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_truncation(
+            Snippet::F64ToInt,
+            Snippet::TO_INT_SIGNED | Snippet::TO_INT_I64 | Snippet::TO_INT_SATURATING,
+        );
+        #[cfg(feature = "fpu")]
+        return self.translate_conversion(
             ValType::F64,
             ValType::I64,
             InstructionSet::op_i64_trunc_sat_f64_s,
             0,
-        )
+        );
     }
 
     fn visit_i64_trunc_sat_f64_u(&mut self) -> Self::Output {
-        self.translate_conversion(
+        #[cfg(not(feature = "fpu"))]
+        return self.translate_soft_float_truncation(
+            Snippet::F64ToInt,
+            Snippet::TO_INT_I64 | Snippet::TO_INT_SATURATING,
+        );
+        #[cfg(feature = "fpu")]
+        return self.translate_conversion(
             ValType::F64,
             ValType::I64,
             InstructionSet::op_i64_trunc_sat_f64_u,
             0,
-        )
+        );
     }
 
     fn visit_memory_init(&mut self, data_segment_index: u32, memory_index: u32) -> Self::Output {
+        self.charge_pending_fuel();
         self.translate_if_reachable(|builder| {
-            debug_assert_eq!(memory_index, DEFAULT_MEMORY_INDEX);
-            builder.bump_fuel_consumption(|| FuelCosts::ENTITY)?;
+            builder.bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Entity))?;
             builder.alloc.stack_types.pop().unwrap();
             builder.alloc.stack_types.pop().unwrap();
             builder.alloc.stack_types.pop().unwrap();
             let data_segment_index: DataSegmentIdx = data_segment_index;
-            let inject_fuel_check =
-                builder.consume_fuel_for_bulk_ops && builder.is_fuel_metering_enabled();
+            let fuel_cost_model = builder.bulk_fuel_cost_model();
             let (ib, rb) = (
                 &mut builder.alloc.instruction_set,
                 &mut builder.alloc.segment_builder,
@@ -2372,19 +3022,21 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
             builder.stack_height.pop2();
             builder.stack_height.pop3();
             // since we store all data sections in the one segment, then the index is always 0
-            ib.op_memory_init_checked(
+            let start = ib.loc();
+            ib.op_memory_init_checked_with_model(
                 Some(offset),
                 Some(length),
                 data_segment_index + 1,
-                inject_fuel_check,
+                fuel_cost_model.as_deref(),
             );
+            ib.retarget_memory(start, MemoryIdx::try_from(memory_index).unwrap());
             Ok(())
         })
     }
 
     fn visit_data_drop(&mut self, data_index: u32) -> Self::Output {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(|| FuelCosts::ENTITY)?;
+            builder.bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Entity))?;
             // We do +1 here because we store all data sections in the one segment,
             // and we use 0 for a default memory segment.
             builder.alloc.instruction_set.op_data_drop(data_index + 1);
@@ -2393,51 +3045,59 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
     }
 
     fn visit_memory_copy(&mut self, dst_memory_index: u32, src_memory_index: u32) -> Self::Output {
+        self.charge_pending_fuel();
         self.translate_if_reachable(|builder| {
-            debug_assert_eq!(dst_memory_index, DEFAULT_MEMORY_INDEX);
-            debug_assert_eq!(src_memory_index, DEFAULT_MEMORY_INDEX);
-            builder.bump_fuel_consumption(|| FuelCosts::ENTITY)?;
+            builder.bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Entity))?;
             builder.stack_height.push2();
             builder.stack_height.pop2();
             builder.stack_height.pop3();
             builder.alloc.stack_types.pop().unwrap();
             builder.alloc.stack_types.pop().unwrap();
             builder.alloc.stack_types.pop().unwrap();
-            let inject_fuel_check =
-                builder.consume_fuel_for_bulk_ops && builder.is_fuel_metering_enabled();
+            let fuel_cost_model = builder.bulk_fuel_cost_model();
             builder
                 .alloc
                 .instruction_set
-                .op_memory_copy_checked(inject_fuel_check);
+                .op_memory_copy_checked_with_model(fuel_cost_model.as_deref());
+            let dst_memory = MemoryIdx::try_from(dst_memory_index).unwrap();
+            let src_memory = MemoryIdx::try_from(src_memory_index).unwrap();
+            if dst_memory != 0 || src_memory != 0 {
+                let opcode = builder.alloc.instruction_set.last_nth_mut(0).unwrap();
+                *opcode = Opcode::MemoryCopyAt(dst_memory, src_memory);
+            }
             Ok(())
         })
     }
 
     fn visit_memory_fill(&mut self, memory_index: u32) -> Self::Output {
+        self.charge_pending_fuel();
         self.translate_if_reachable(|builder| {
-            debug_assert_eq!(memory_index, DEFAULT_MEMORY_INDEX);
-            builder.bump_fuel_consumption(|| FuelCosts::ENTITY)?;
+            builder.bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Entity))?;
             builder.stack_height.push2();
             builder.stack_height.pop2();
             builder.stack_height.pop3();
             builder.alloc.stack_types.pop().unwrap();
             builder.alloc.stack_types.pop().unwrap();
             builder.alloc.stack_types.pop().unwrap();
-            let inject_fuel_check =
-                builder.consume_fuel_for_bulk_ops && builder.is_fuel_metering_enabled();
+            let fuel_cost_model = builder.bulk_fuel_cost_model();
+            let start = builder.alloc.instruction_set.loc();
+            builder
+                .alloc
+                .instruction_set
+                .op_memory_fill_checked_with_model(fuel_cost_model.as_deref());
             builder
                 .alloc
                 .instruction_set
-                .op_memory_fill_checked(inject_fuel_check);
+                .retarget_memory(start, MemoryIdx::try_from(memory_index).unwrap());
             Ok(())
         })
     }
 
     fn visit_table_init(&mut self, segment_index: u32, table_index: u32) -> Self::Output {
+        self.charge_pending_fuel();
         self.translate_if_reachable(|builder| {
-            let inject_fuel_check =
-                builder.consume_fuel_for_bulk_ops && builder.is_fuel_metering_enabled();
-            builder.bump_fuel_consumption(|| FuelCosts::ENTITY)?;
+            let fuel_cost_model = builder.bulk_fuel_cost_model();
+            builder.bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Entity))?;
             builder.alloc.stack_types.pop().unwrap();
             builder.alloc.stack_types.pop().unwrap();
             builder.alloc.stack_types.pop().unwrap();
@@ -2451,12 +3111,12 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
                 .get(&elem_segment_index)
                 .copied()
                 .ok_or(CompilationError::TableOutOfBounds)?;
-            ib.op_table_init_checked(
+            ib.op_table_init_checked_with_model(
                 segment_index + 1,
                 TableIdx::try_from(table_index).unwrap(),
                 length,
                 offset,
-                inject_fuel_check,
+                fuel_cost_model.as_deref(),
             );
             builder
                 .stack_height
@@ -2471,7 +3131,7 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
 
     fn visit_elem_drop(&mut self, segment_index: u32) -> Self::Output {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(|| FuelCosts::ENTITY)?;
+            builder.bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Entity))?;
             builder
                 .alloc
                 .instruction_set
@@ -2481,10 +3141,10 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
     }
 
     fn visit_table_copy(&mut self, dst_table: u32, src_table: u32) -> Self::Output {
+        self.charge_pending_fuel();
         self.translate_if_reachable(|builder| {
-            let inject_fuel_check =
-                builder.consume_fuel_for_bulk_ops && builder.is_fuel_metering_enabled();
-            builder.bump_fuel_consumption(|| FuelCosts::ENTITY)?;
+            let fuel_cost_model = builder.bulk_fuel_cost_model();
+            builder.bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Entity))?;
             builder
                 .stack_height
                 .push_n(InstructionSet::MSH_TABLE_COPY_CHECKED);
@@ -2495,20 +3155,23 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
             builder.alloc.stack_types.pop().unwrap();
             builder.alloc.stack_types.pop().unwrap();
             builder.alloc.stack_types.pop().unwrap();
-            builder.alloc.instruction_set.op_table_copy_checked(
-                TableIdx::try_from(dst_table).unwrap(),
-                TableIdx::try_from(src_table).unwrap(),
-                inject_fuel_check,
-            );
+            builder
+                .alloc
+                .instruction_set
+                .op_table_copy_checked_with_model(
+                    TableIdx::try_from(dst_table).unwrap(),
+                    TableIdx::try_from(src_table).unwrap(),
+                    fuel_cost_model.as_deref(),
+                );
             Ok(())
         })
     }
 
     fn visit_table_fill(&mut self, table_index: u32) -> Self::Output {
+        self.charge_pending_fuel();
         self.translate_if_reachable(|builder| {
-            let inject_fuel_check =
-                builder.consume_fuel_for_bulk_ops && builder.is_fuel_metering_enabled();
-            builder.bump_fuel_consumption(|| FuelCosts::ENTITY)?;
+            let fuel_cost_model = builder.bulk_fuel_cost_model();
+            builder.bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Entity))?;
             builder
                 .stack_height
                 .push_n(InstructionSet::MSH_TABLE_FILL_CHECKED);
@@ -2522,14 +3185,18 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
             builder
                 .alloc
                 .instruction_set
-                .op_table_fill_checked(TableIdx::try_from(table_index).unwrap(), inject_fuel_check);
+                .op_table_fill_checked_with_model(
+                    TableIdx::try_from(table_index).unwrap(),
+                    fuel_cost_model.as_deref(),
+                );
             Ok(())
         })
     }
 
     fn visit_table_get(&mut self, table_index: u32) -> Self::Output {
+        self.charge_pending_fuel();
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(|| FuelCosts::ENTITY)?;
+            builder.bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Entity))?;
             let popped_type = builder.alloc.stack_types.pop().unwrap();
             debug_assert_eq!(popped_type, ValType::I32);
             builder.alloc.instruction_set.op_table_get(table_index);
@@ -2540,8 +3207,9 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
     }
 
     fn visit_table_set(&mut self, table_index: u32) -> Self::Output {
+        self.charge_pending_fuel();
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(|| FuelCosts::ENTITY)?;
+            builder.bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Entity))?;
             builder.stack_height.pop2();
             //TODO: Do set and get for i32 x2 as i64
             builder.alloc.stack_types.pop().unwrap();
@@ -2553,9 +3221,8 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
 
     fn visit_table_grow(&mut self, table_index: u32) -> Self::Output {
         self.translate_if_reachable(|builder| {
-            let inject_fuel_check =
-                builder.consume_fuel_for_bulk_ops && builder.is_fuel_metering_enabled();
-            builder.bump_fuel_consumption(|| FuelCosts::ENTITY)?;
+            let fuel_cost_model = builder.bulk_fuel_cost_model();
+            builder.bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Entity))?;
             builder.alloc.stack_types.pop().unwrap();
             builder.alloc.stack_types.pop().unwrap();
             builder.alloc.stack_types.push(ValType::I32);
@@ -2566,10 +3233,10 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
             // TODO(dmitry123): "is this construction correct?"
             let max_table_elements = table_type.maximum.unwrap_or(N_MAX_TABLE_SIZE);
             let ib = &mut builder.alloc.instruction_set;
-            ib.op_table_grow_checked(
+            ib.op_table_grow_checked_with_model(
                 TableIdx::try_from(table_index).unwrap(),
                 Some(max_table_elements),
-                inject_fuel_check,
+                fuel_cost_model.as_deref(),
             );
             builder
                 .stack_height
@@ -2585,7 +3252,7 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
 
     fn visit_table_size(&mut self, table_index: u32) -> Self::Output {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(|| FuelCosts::ENTITY)?;
+            builder.bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Entity))?;
             builder.stack_height.push1();
             builder.alloc.stack_types.push(ValType::I32);
             builder.alloc.instruction_set.op_table_size(table_index);
@@ -2597,1216 +3264,1419 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
         Err(CompilationError::NotSupportedExtension)
     }
 
-    fn visit_memory_atomic_notify(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_memory_atomic_notify(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_lowered_memory(
+            memarg,
+            self.fuel_cost(FuelCostClass::Load),
+            &[ValType::I32, ValType::I32],
+            &[ValType::I32],
+            atomics::atomic_notify,
+        )
     }
 
-    fn visit_memory_atomic_wait32(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_memory_atomic_wait32(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_wait(memarg, Access::I32)
     }
 
-    fn visit_memory_atomic_wait64(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_memory_atomic_wait64(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_wait(memarg, Access::I64)
     }
 
     fn visit_atomic_fence(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        // there are no other threads to order the memory accesses with
+        Ok(())
     }
 
-    fn visit_i32_atomic_load(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i32_atomic_load(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_load(memarg, Access::I32)
     }
 
-    fn visit_i64_atomic_load(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i64_atomic_load(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_load(memarg, Access::I64)
     }
 
-    fn visit_i32_atomic_load8_u(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i32_atomic_load8_u(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_load(memarg, Access::I32U8)
     }
 
-    fn visit_i32_atomic_load16_u(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i32_atomic_load16_u(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_load(memarg, Access::I32U16)
     }
 
-    fn visit_i64_atomic_load8_u(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i64_atomic_load8_u(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_load(memarg, Access::I64U8)
     }
 
-    fn visit_i64_atomic_load16_u(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i64_atomic_load16_u(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_load(memarg, Access::I64U16)
     }
 
-    fn visit_i64_atomic_load32_u(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i64_atomic_load32_u(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_load(memarg, Access::I64U32)
     }
 
-    fn visit_i32_atomic_store(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i32_atomic_store(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_store(memarg, Access::I32)
     }
 
-    fn visit_i64_atomic_store(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i64_atomic_store(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_store(memarg, Access::I64)
     }
 
-    fn visit_i32_atomic_store8(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i32_atomic_store8(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_store(memarg, Access::I32U8)
     }
 
-    fn visit_i32_atomic_store16(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i32_atomic_store16(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_store(memarg, Access::I32U16)
     }
 
-    fn visit_i64_atomic_store8(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i64_atomic_store8(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_store(memarg, Access::I64U8)
     }
 
-    fn visit_i64_atomic_store16(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i64_atomic_store16(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_store(memarg, Access::I64U16)
     }
 
-    fn visit_i64_atomic_store32(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i64_atomic_store32(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_store(memarg, Access::I64U32)
     }
 
-    fn visit_i32_atomic_rmw_add(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i32_atomic_rmw_add(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_rmw(memarg, Access::I32, RmwOp::Add)
     }
 
-    fn visit_i64_atomic_rmw_add(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i64_atomic_rmw_add(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_rmw(memarg, Access::I64, RmwOp::Add)
     }
 
-    fn visit_i32_atomic_rmw8_add_u(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i32_atomic_rmw8_add_u(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_rmw(memarg, Access::I32U8, RmwOp::Add)
     }
 
-    fn visit_i32_atomic_rmw16_add_u(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i32_atomic_rmw16_add_u(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_rmw(memarg, Access::I32U16, RmwOp::Add)
     }
 
-    fn visit_i64_atomic_rmw8_add_u(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i64_atomic_rmw8_add_u(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_rmw(memarg, Access::I64U8, RmwOp::Add)
     }
 
-    fn visit_i64_atomic_rmw16_add_u(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i64_atomic_rmw16_add_u(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_rmw(memarg, Access::I64U16, RmwOp::Add)
     }
 
-    fn visit_i64_atomic_rmw32_add_u(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i64_atomic_rmw32_add_u(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_rmw(memarg, Access::I64U32, RmwOp::Add)
     }
 
-    fn visit_i32_atomic_rmw_sub(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i32_atomic_rmw_sub(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_rmw(memarg, Access::I32, RmwOp::Sub)
     }
 
-    fn visit_i64_atomic_rmw_sub(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i64_atomic_rmw_sub(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_rmw(memarg, Access::I64, RmwOp::Sub)
     }
 
-    fn visit_i32_atomic_rmw8_sub_u(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i32_atomic_rmw8_sub_u(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_rmw(memarg, Access::I32U8, RmwOp::Sub)
     }
 
-    fn visit_i32_atomic_rmw16_sub_u(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i32_atomic_rmw16_sub_u(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_rmw(memarg, Access::I32U16, RmwOp::Sub)
     }
 
-    fn visit_i64_atomic_rmw8_sub_u(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i64_atomic_rmw8_sub_u(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_rmw(memarg, Access::I64U8, RmwOp::Sub)
     }
 
-    fn visit_i64_atomic_rmw16_sub_u(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i64_atomic_rmw16_sub_u(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_rmw(memarg, Access::I64U16, RmwOp::Sub)
     }
 
-    fn visit_i64_atomic_rmw32_sub_u(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i64_atomic_rmw32_sub_u(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_rmw(memarg, Access::I64U32, RmwOp::Sub)
     }
 
-    fn visit_i32_atomic_rmw_and(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i32_atomic_rmw_and(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_rmw(memarg, Access::I32, RmwOp::And)
     }
 
-    fn visit_i64_atomic_rmw_and(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i64_atomic_rmw_and(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_rmw(memarg, Access::I64, RmwOp::And)
     }
 
-    fn visit_i32_atomic_rmw8_and_u(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i32_atomic_rmw8_and_u(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_rmw(memarg, Access::I32U8, RmwOp::And)
     }
 
-    fn visit_i32_atomic_rmw16_and_u(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i32_atomic_rmw16_and_u(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_rmw(memarg, Access::I32U16, RmwOp::And)
     }
 
-    fn visit_i64_atomic_rmw8_and_u(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i64_atomic_rmw8_and_u(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_rmw(memarg, Access::I64U8, RmwOp::And)
     }
 
-    fn visit_i64_atomic_rmw16_and_u(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i64_atomic_rmw16_and_u(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_rmw(memarg, Access::I64U16, RmwOp::And)
     }
 
-    fn visit_i64_atomic_rmw32_and_u(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i64_atomic_rmw32_and_u(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_rmw(memarg, Access::I64U32, RmwOp::And)
     }
 
-    fn visit_i32_atomic_rmw_or(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i32_atomic_rmw_or(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_rmw(memarg, Access::I32, RmwOp::Or)
     }
 
-    fn visit_i64_atomic_rmw_or(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i64_atomic_rmw_or(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_rmw(memarg, Access::I64, RmwOp::Or)
     }
 
-    fn visit_i32_atomic_rmw8_or_u(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i32_atomic_rmw8_or_u(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_rmw(memarg, Access::I32U8, RmwOp::Or)
     }
 
-    fn visit_i32_atomic_rmw16_or_u(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i32_atomic_rmw16_or_u(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_rmw(memarg, Access::I32U16, RmwOp::Or)
     }
 
-    fn visit_i64_atomic_rmw8_or_u(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i64_atomic_rmw8_or_u(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_rmw(memarg, Access::I64U8, RmwOp::Or)
     }
 
-    fn visit_i64_atomic_rmw16_or_u(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i64_atomic_rmw16_or_u(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_rmw(memarg, Access::I64U16, RmwOp::Or)
     }
 
-    fn visit_i64_atomic_rmw32_or_u(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i64_atomic_rmw32_or_u(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_rmw(memarg, Access::I64U32, RmwOp::Or)
     }
 
-    fn visit_i32_atomic_rmw_xor(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i32_atomic_rmw_xor(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_rmw(memarg, Access::I32, RmwOp::Xor)
     }
 
-    fn visit_i64_atomic_rmw_xor(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i64_atomic_rmw_xor(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_rmw(memarg, Access::I64, RmwOp::Xor)
     }
 
-    fn visit_i32_atomic_rmw8_xor_u(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i32_atomic_rmw8_xor_u(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_rmw(memarg, Access::I32U8, RmwOp::Xor)
     }
 
-    fn visit_i32_atomic_rmw16_xor_u(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i32_atomic_rmw16_xor_u(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_rmw(memarg, Access::I32U16, RmwOp::Xor)
     }
 
-    fn visit_i64_atomic_rmw8_xor_u(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i64_atomic_rmw8_xor_u(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_rmw(memarg, Access::I64U8, RmwOp::Xor)
     }
 
-    fn visit_i64_atomic_rmw16_xor_u(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i64_atomic_rmw16_xor_u(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_rmw(memarg, Access::I64U16, RmwOp::Xor)
     }
 
-    fn visit_i64_atomic_rmw32_xor_u(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i64_atomic_rmw32_xor_u(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_rmw(memarg, Access::I64U32, RmwOp::Xor)
     }
 
-    fn visit_i32_atomic_rmw_xchg(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i32_atomic_rmw_xchg(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_rmw(memarg, Access::I32, RmwOp::Xchg)
     }
 
-    fn visit_i64_atomic_rmw_xchg(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i64_atomic_rmw_xchg(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_rmw(memarg, Access::I64, RmwOp::Xchg)
     }
 
-    fn visit_i32_atomic_rmw8_xchg_u(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i32_atomic_rmw8_xchg_u(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_rmw(memarg, Access::I32U8, RmwOp::Xchg)
     }
 
-    fn visit_i32_atomic_rmw16_xchg_u(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i32_atomic_rmw16_xchg_u(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_rmw(memarg, Access::I32U16, RmwOp::Xchg)
     }
 
-    fn visit_i64_atomic_rmw8_xchg_u(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i64_atomic_rmw8_xchg_u(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_rmw(memarg, Access::I64U8, RmwOp::Xchg)
     }
 
-    fn visit_i64_atomic_rmw16_xchg_u(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i64_atomic_rmw16_xchg_u(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_rmw(memarg, Access::I64U16, RmwOp::Xchg)
     }
 
-    fn visit_i64_atomic_rmw32_xchg_u(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i64_atomic_rmw32_xchg_u(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_rmw(memarg, Access::I64U32, RmwOp::Xchg)
     }
 
-    fn visit_i32_atomic_rmw_cmpxchg(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i32_atomic_rmw_cmpxchg(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_cmpxchg(memarg, Access::I32)
     }
 
-    fn visit_i64_atomic_rmw_cmpxchg(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i64_atomic_rmw_cmpxchg(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_cmpxchg(memarg, Access::I64)
     }
 
-    fn visit_i32_atomic_rmw8_cmpxchg_u(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i32_atomic_rmw8_cmpxchg_u(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_cmpxchg(memarg, Access::I32U8)
     }
 
-    fn visit_i32_atomic_rmw16_cmpxchg_u(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i32_atomic_rmw16_cmpxchg_u(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_cmpxchg(memarg, Access::I32U16)
     }
 
-    fn visit_i64_atomic_rmw8_cmpxchg_u(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i64_atomic_rmw8_cmpxchg_u(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_cmpxchg(memarg, Access::I64U8)
     }
 
-    fn visit_i64_atomic_rmw16_cmpxchg_u(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i64_atomic_rmw16_cmpxchg_u(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_cmpxchg(memarg, Access::I64U16)
     }
 
-    fn visit_i64_atomic_rmw32_cmpxchg_u(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i64_atomic_rmw32_cmpxchg_u(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_atomic_cmpxchg(memarg, Access::I64U32)
     }
 
-    fn visit_v128_load(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_v128_load(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_lowered_memory(
+            memarg,
+            self.fuel_cost(FuelCostClass::Load),
+            &[ValType::I32],
+            &[ValType::V128],
+            simd::v128_load,
+        )
     }
 
-    fn visit_v128_load8x8_s(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_v128_load8x8_s(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_lowered_memory(
+            memarg,
+            self.fuel_cost(FuelCostClass::Load),
+            &[ValType::I32],
+            &[ValType::V128],
+            |code, offset| simd::v128_load_extend(code, offset, Shape::I16, true),
+        )
     }
 
-    fn visit_v128_load8x8_u(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_v128_load8x8_u(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_lowered_memory(
+            memarg,
+            self.fuel_cost(FuelCostClass::Load),
+            &[ValType::I32],
+            &[ValType::V128],
+            |code, offset| simd::v128_load_extend(code, offset, Shape::I16, false),
+        )
     }
 
-    fn visit_v128_load16x4_s(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_v128_load16x4_s(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_lowered_memory(
+            memarg,
+            self.fuel_cost(FuelCostClass::Load),
+            &[ValType::I32],
+            &[ValType::V128],
+            |code, offset| simd::v128_load_extend(code, offset, Shape::I32, true),
+        )
     }
 
-    fn visit_v128_load16x4_u(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_v128_load16x4_u(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_lowered_memory(
+            memarg,
+            self.fuel_cost(FuelCostClass::Load),
+            &[ValType::I32],
+            &[ValType::V128],
+            |code, offset| simd::v128_load_extend(code, offset, Shape::I32, false),
+        )
     }
 
-    fn visit_v128_load32x2_s(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_v128_load32x2_s(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_lowered_memory(
+            memarg,
+            self.fuel_cost(FuelCostClass::Load),
+            &[ValType::I32],
+            &[ValType::V128],
+            |code, offset| simd::v128_load_extend(code, offset, Shape::I64, true),
+        )
     }
 
-    fn visit_v128_load32x2_u(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_v128_load32x2_u(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_lowered_memory(
+            memarg,
+            self.fuel_cost(FuelCostClass::Load),
+            &[ValType::I32],
+            &[ValType::V128],
+            |code, offset| simd::v128_load_extend(code, offset, Shape::I64, false),
+        )
     }
 
-    fn visit_v128_load8_splat(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_v128_load8_splat(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_lowered_memory(
+            memarg,
+            self.fuel_cost(FuelCostClass::Load),
+            &[ValType::I32],
+            &[ValType::V128],
+            |code, offset| simd::v128_load_splat(code, offset, Shape::I8),
+        )
     }
 
-    fn visit_v128_load16_splat(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_v128_load16_splat(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_lowered_memory(
+            memarg,
+            self.fuel_cost(FuelCostClass::Load),
+            &[ValType::I32],
+            &[ValType::V128],
+            |code, offset| simd::v128_load_splat(code, offset, Shape::I16),
+        )
     }
 
-    fn visit_v128_load32_splat(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_v128_load32_splat(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_lowered_memory(
+            memarg,
+            self.fuel_cost(FuelCostClass::Load),
+            &[ValType::I32],
+            &[ValType::V128],
+            |code, offset| simd::v128_load_splat(code, offset, Shape::I32),
+        )
     }
 
-    fn visit_v128_load64_splat(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_v128_load64_splat(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_lowered_memory(
+            memarg,
+            self.fuel_cost(FuelCostClass::Load),
+            &[ValType::I32],
+            &[ValType::V128],
+            |code, offset| simd::v128_load_splat(code, offset, Shape::I64),
+        )
     }
 
-    fn visit_v128_load32_zero(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_v128_load32_zero(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_lowered_memory(
+            memarg,
+            self.fuel_cost(FuelCostClass::Load),
+            &[ValType::I32],
+            &[ValType::V128],
+            |code, offset| simd::v128_load_zero(code, offset, Shape::I32),
+        )
     }
 
-    fn visit_v128_load64_zero(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_v128_load64_zero(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_lowered_memory(
+            memarg,
+            self.fuel_cost(FuelCostClass::Load),
+            &[ValType::I32],
+            &[ValType::V128],
+            |code, offset| simd::v128_load_zero(code, offset, Shape::I64),
+        )
     }
 
-    fn visit_v128_store(&mut self, _memarg: MemArg) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_v128_store(&mut self, memarg: MemArg) -> Self::Output {
+        self.translate_lowered_memory(
+            memarg,
+            self.fuel_cost(FuelCostClass::Store),
+            &[ValType::I32, ValType::V128],
+            &[],
+            simd::v128_store,
+        )
     }
 
-    fn visit_v128_load8_lane(&mut self, _memarg: MemArg, _lane: u8) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_v128_load8_lane(&mut self, memarg: MemArg, lane: u8) -> Self::Output {
+        self.translate_lowered_memory(
+            memarg,
+            self.fuel_cost(FuelCostClass::Load),
+            &[ValType::I32, ValType::V128],
+            &[ValType::V128],
+            |code, offset| simd::v128_load_lane(code, offset, Shape::I8, lane as u32),
+        )
     }
 
-    fn visit_v128_load16_lane(&mut self, _memarg: MemArg, _lane: u8) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_v128_load16_lane(&mut self, memarg: MemArg, lane: u8) -> Self::Output {
+        self.translate_lowered_memory(
+            memarg,
+            self.fuel_cost(FuelCostClass::Load),
+            &[ValType::I32, ValType::V128],
+            &[ValType::V128],
+            |code, offset| simd::v128_load_lane(code, offset, Shape::I16, lane as u32),
+        )
     }
 
-    fn visit_v128_load32_lane(&mut self, _memarg: MemArg, _lane: u8) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_v128_load32_lane(&mut self, memarg: MemArg, lane: u8) -> Self::Output {
+        self.translate_lowered_memory(
+            memarg,
+            self.fuel_cost(FuelCostClass::Load),
+            &[ValType::I32, ValType::V128],
+            &[ValType::V128],
+            |code, offset| simd::v128_load_lane(code, offset, Shape::I32, lane as u32),
+        )
     }
 
-    fn visit_v128_load64_lane(&mut self, _memarg: MemArg, _lane: u8) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_v128_load64_lane(&mut self, memarg: MemArg, lane: u8) -> Self::Output {
+        self.translate_lowered_memory(
+            memarg,
+            self.fuel_cost(FuelCostClass::Load),
+            &[ValType::I32, ValType::V128],
+            &[ValType::V128],
+            |code, offset| simd::v128_load_lane(code, offset, Shape::I64, lane as u32),
+        )
     }
 
-    fn visit_v128_store8_lane(&mut self, _memarg: MemArg, _lane: u8) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_v128_store8_lane(&mut self, memarg: MemArg, lane: u8) -> Self::Output {
+        self.translate_lowered_memory(
+            memarg,
+            self.fuel_cost(FuelCostClass::Store),
+            &[ValType::I32, ValType::V128],
+            &[],
+            |code, offset| simd::v128_store_lane(code, offset, Shape::I8, lane as u32),
+        )
     }
 
-    fn visit_v128_store16_lane(&mut self, _memarg: MemArg, _lane: u8) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_v128_store16_lane(&mut self, memarg: MemArg, lane: u8) -> Self::Output {
+        self.translate_lowered_memory(
+            memarg,
+            self.fuel_cost(FuelCostClass::Store),
+            &[ValType::I32, ValType::V128],
+            &[],
+            |code, offset| simd::v128_store_lane(code, offset, Shape::I16, lane as u32),
+        )
     }
 
-    fn visit_v128_store32_lane(&mut self, _memarg: MemArg, _lane: u8) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_v128_store32_lane(&mut self, memarg: MemArg, lane: u8) -> Self::Output {
+        self.translate_lowered_memory(
+            memarg,
+            self.fuel_cost(FuelCostClass::Store),
+            &[ValType::I32, ValType::V128],
+            &[],
+            |code, offset| simd::v128_store_lane(code, offset, Shape::I32, lane as u32),
+        )
     }
 
-    fn visit_v128_store64_lane(&mut self, _memarg: MemArg, _lane: u8) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_v128_store64_lane(&mut self, memarg: MemArg, lane: u8) -> Self::Output {
+        self.translate_lowered_memory(
+            memarg,
+            self.fuel_cost(FuelCostClass::Store),
+            &[ValType::I32, ValType::V128],
+            &[],
+            |code, offset| simd::v128_store_lane(code, offset, Shape::I64, lane as u32),
+        )
     }
 
-    fn visit_v128_const(&mut self, _value: V128) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_v128_const(&mut self, value: V128) -> Self::Output {
+        self.translate_simd(&[], &[ValType::V128], |code| simd::v128_const(code, value))
     }
 
-    fn visit_i8x16_shuffle(&mut self, _value: [u8; 16]) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i8x16_shuffle(&mut self, value: [u8; 16]) -> Self::Output {
+        self.translate_simd(&[ValType::V128, ValType::V128], &[ValType::V128], |code| {
+            simd::shuffle(code, value)
+        })
     }
 
-    fn visit_i8x16_extract_lane_s(&mut self, _lane: u8) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i8x16_extract_lane_s(&mut self, lane: u8) -> Self::Output {
+        self.translate_simd(&[ValType::V128], &[ValType::I32], |code| {
+            simd::extract_lane(code, Shape::I8, lane as u32, Ext::Signed)
+        })
     }
 
-    fn visit_i8x16_extract_lane_u(&mut self, _lane: u8) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i8x16_extract_lane_u(&mut self, lane: u8) -> Self::Output {
+        self.translate_simd(&[ValType::V128], &[ValType::I32], |code| {
+            simd::extract_lane(code, Shape::I8, lane as u32, Ext::Unsigned)
+        })
     }
 
-    fn visit_i8x16_replace_lane(&mut self, _lane: u8) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i8x16_replace_lane(&mut self, lane: u8) -> Self::Output {
+        self.translate_simd(&[ValType::V128, ValType::I32], &[ValType::V128], |code| {
+            simd::replace_lane(code, Shape::I8, lane as u32)
+        })
     }
 
-    fn visit_i16x8_extract_lane_s(&mut self, _lane: u8) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i16x8_extract_lane_s(&mut self, lane: u8) -> Self::Output {
+        self.translate_simd(&[ValType::V128], &[ValType::I32], |code| {
+            simd::extract_lane(code, Shape::I16, lane as u32, Ext::Signed)
+        })
     }
 
-    fn visit_i16x8_extract_lane_u(&mut self, _lane: u8) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i16x8_extract_lane_u(&mut self, lane: u8) -> Self::Output {
+        self.translate_simd(&[ValType::V128], &[ValType::I32], |code| {
+            simd::extract_lane(code, Shape::I16, lane as u32, Ext::Unsigned)
+        })
     }
 
-    fn visit_i16x8_replace_lane(&mut self, _lane: u8) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i16x8_replace_lane(&mut self, lane: u8) -> Self::Output {
+        self.translate_simd(&[ValType::V128, ValType::I32], &[ValType::V128], |code| {
+            simd::replace_lane(code, Shape::I16, lane as u32)
+        })
     }
 
-    fn visit_i32x4_extract_lane(&mut self, _lane: u8) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i32x4_extract_lane(&mut self, lane: u8) -> Self::Output {
+        self.translate_simd(&[ValType::V128], &[ValType::I32], |code| {
+            simd::extract_lane(code, Shape::I32, lane as u32, Ext::Wrap)
+        })
     }
 
-    fn visit_i32x4_replace_lane(&mut self, _lane: u8) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i32x4_replace_lane(&mut self, lane: u8) -> Self::Output {
+        self.translate_simd(&[ValType::V128, ValType::I32], &[ValType::V128], |code| {
+            simd::replace_lane(code, Shape::I32, lane as u32)
+        })
     }
 
-    fn visit_i64x2_extract_lane(&mut self, _lane: u8) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i64x2_extract_lane(&mut self, lane: u8) -> Self::Output {
+        self.translate_simd(&[ValType::V128], &[ValType::I64], |code| {
+            simd::extract_lane(code, Shape::I64, lane as u32, Ext::Wrap)
+        })
     }
 
-    fn visit_i64x2_replace_lane(&mut self, _lane: u8) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_i64x2_replace_lane(&mut self, lane: u8) -> Self::Output {
+        self.translate_simd(&[ValType::V128, ValType::I64], &[ValType::V128], |code| {
+            simd::replace_lane(code, Shape::I64, lane as u32)
+        })
     }
 
-    fn visit_f32x4_extract_lane(&mut self, _lane: u8) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_f32x4_extract_lane(&mut self, lane: u8) -> Self::Output {
+        self.translate_simd(&[ValType::V128], &[ValType::F32], |code| {
+            simd::extract_lane(code, Shape::I32, lane as u32, Ext::Wrap)
+        })
     }
 
-    fn visit_f32x4_replace_lane(&mut self, _lane: u8) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_f32x4_replace_lane(&mut self, lane: u8) -> Self::Output {
+        self.translate_simd(&[ValType::V128, ValType::F32], &[ValType::V128], |code| {
+            simd::replace_lane(code, Shape::I32, lane as u32)
+        })
     }
 
-    fn visit_f64x2_extract_lane(&mut self, _lane: u8) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_f64x2_extract_lane(&mut self, lane: u8) -> Self::Output {
+        self.translate_simd(&[ValType::V128], &[ValType::F64], |code| {
+            simd::extract_lane(code, Shape::I64, lane as u32, Ext::Wrap)
+        })
     }
 
-    fn visit_f64x2_replace_lane(&mut self, _lane: u8) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+    fn visit_f64x2_replace_lane(&mut self, lane: u8) -> Self::Output {
+        self.translate_simd(&[ValType::V128, ValType::F64], &[ValType::V128], |code| {
+            simd::replace_lane(code, Shape::I64, lane as u32)
+        })
     }
 
     fn visit_i8x16_swizzle(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I8x16Swizzle))
     }
 
     fn visit_i8x16_splat(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_simd(&[ValType::I32], &[ValType::V128], |code| {
+            simd::splat(code, Shape::I8)
+        })
     }
 
     fn visit_i16x8_splat(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_simd(&[ValType::I32], &[ValType::V128], |code| {
+            simd::splat(code, Shape::I16)
+        })
     }
 
     fn visit_i32x4_splat(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_simd(&[ValType::I32], &[ValType::V128], |code| {
+            simd::splat(code, Shape::I32)
+        })
     }
 
     fn visit_i64x2_splat(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_simd(&[ValType::I64], &[ValType::V128], |code| {
+            simd::splat(code, Shape::I64)
+        })
     }
 
     fn visit_f32x4_splat(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_simd(&[ValType::F32], &[ValType::V128], |code| {
+            simd::splat(code, Shape::I32)
+        })
     }
 
     fn visit_f64x2_splat(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_simd(&[ValType::F64], &[ValType::V128], |code| {
+            simd::splat(code, Shape::I64)
+        })
     }
 
     fn visit_i8x16_eq(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I8x16Eq))
     }
 
     fn visit_i8x16_ne(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I8x16Ne))
     }
 
     fn visit_i8x16_lt_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I8x16LtS))
     }
 
     fn visit_i8x16_lt_u(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I8x16LtU))
     }
 
     fn visit_i8x16_gt_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I8x16GtS))
     }
 
     fn visit_i8x16_gt_u(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I8x16GtU))
     }
 
     fn visit_i8x16_le_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I8x16LeS))
     }
 
     fn visit_i8x16_le_u(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I8x16LeU))
     }
 
     fn visit_i8x16_ge_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I8x16GeS))
     }
 
     fn visit_i8x16_ge_u(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I8x16GeU))
     }
 
     fn visit_i16x8_eq(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I16x8Eq))
     }
 
     fn visit_i16x8_ne(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I16x8Ne))
     }
 
     fn visit_i16x8_lt_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I16x8LtS))
     }
 
     fn visit_i16x8_lt_u(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I16x8LtU))
     }
 
     fn visit_i16x8_gt_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I16x8GtS))
     }
 
     fn visit_i16x8_gt_u(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I16x8GtU))
     }
 
     fn visit_i16x8_le_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I16x8LeS))
     }
 
     fn visit_i16x8_le_u(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I16x8LeU))
     }
 
     fn visit_i16x8_ge_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I16x8GeS))
     }
 
     fn visit_i16x8_ge_u(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I16x8GeU))
     }
 
     fn visit_i32x4_eq(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I32x4Eq))
     }
 
     fn visit_i32x4_ne(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I32x4Ne))
     }
 
     fn visit_i32x4_lt_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I32x4LtS))
     }
 
     fn visit_i32x4_lt_u(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I32x4LtU))
     }
 
     fn visit_i32x4_gt_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I32x4GtS))
     }
 
     fn visit_i32x4_gt_u(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I32x4GtU))
     }
 
     fn visit_i32x4_le_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I32x4LeS))
     }
 
     fn visit_i32x4_le_u(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I32x4LeU))
     }
 
     fn visit_i32x4_ge_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I32x4GeS))
     }
 
     fn visit_i32x4_ge_u(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I32x4GeU))
     }
 
     fn visit_i64x2_eq(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I64x2Eq))
     }
 
     fn visit_i64x2_ne(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I64x2Ne))
     }
 
     fn visit_i64x2_lt_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I64x2LtS))
     }
 
     fn visit_i64x2_gt_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I64x2GtS))
     }
 
     fn visit_i64x2_le_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I64x2LeS))
     }
 
     fn visit_i64x2_ge_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I64x2GeS))
     }
 
     fn visit_f32x4_eq(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::F32x4Eq))
     }
 
     fn visit_f32x4_ne(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::F32x4Ne))
     }
 
     fn visit_f32x4_lt(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::F32x4Lt))
     }
 
     fn visit_f32x4_gt(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::F32x4Gt))
     }
 
     fn visit_f32x4_le(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::F32x4Le))
     }
 
     fn visit_f32x4_ge(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::F32x4Ge))
     }
 
     fn visit_f64x2_eq(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::F64x2Eq))
     }
 
     fn visit_f64x2_ne(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::F64x2Ne))
     }
 
     fn visit_f64x2_lt(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::F64x2Lt))
     }
 
     fn visit_f64x2_gt(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::F64x2Gt))
     }
 
     fn visit_f64x2_le(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::F64x2Le))
     }
 
     fn visit_f64x2_ge(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::F64x2Ge))
     }
 
     fn visit_v128_not(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_simd(&[ValType::V128], &[ValType::V128], simd::v128_not)
     }
 
     fn visit_v128_and(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_simd(&[ValType::V128, ValType::V128], &[ValType::V128], |code| {
+            simd::v128_bitwise(code, InstructionSet::op_i32_and)
+        })
     }
 
     fn visit_v128_andnot(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_simd(
+            &[ValType::V128, ValType::V128],
+            &[ValType::V128],
+            simd::v128_andnot,
+        )
     }
 
     fn visit_v128_or(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_simd(&[ValType::V128, ValType::V128], &[ValType::V128], |code| {
+            simd::v128_bitwise(code, InstructionSet::op_i32_or)
+        })
     }
 
     fn visit_v128_xor(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_simd(&[ValType::V128, ValType::V128], &[ValType::V128], |code| {
+            simd::v128_bitwise(code, InstructionSet::op_i32_xor)
+        })
     }
 
     fn visit_v128_bitselect(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_simd(
+            &[ValType::V128, ValType::V128, ValType::V128],
+            &[ValType::V128],
+            simd::v128_bitselect,
+        )
     }
 
     fn visit_v128_any_true(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_simd(&[ValType::V128], &[ValType::I32], simd::v128_any_true)
     }
 
     fn visit_i8x16_abs(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I8x16Abs))
     }
 
     fn visit_i8x16_neg(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I8x16Neg))
     }
 
     fn visit_i8x16_popcnt(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I8x16Popcnt))
     }
 
     fn visit_i8x16_all_true(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I8x16AllTrue))
     }
 
     fn visit_i8x16_bitmask(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I8x16Bitmask))
     }
 
     fn visit_i8x16_narrow_i16x8_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I8x16NarrowI16x8S))
     }
 
     fn visit_i8x16_narrow_i16x8_u(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I8x16NarrowI16x8U))
     }
 
     fn visit_i8x16_shl(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I8x16Shl))
     }
 
     fn visit_i8x16_shr_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I8x16ShrS))
     }
 
     fn visit_i8x16_shr_u(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I8x16ShrU))
     }
 
     fn visit_i8x16_add(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I8x16Add))
     }
 
     fn visit_i8x16_add_sat_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I8x16AddSatS))
     }
 
     fn visit_i8x16_add_sat_u(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I8x16AddSatU))
     }
 
     fn visit_i8x16_sub(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I8x16Sub))
     }
 
     fn visit_i8x16_sub_sat_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I8x16SubSatS))
     }
 
     fn visit_i8x16_sub_sat_u(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I8x16SubSatU))
     }
 
     fn visit_i8x16_min_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I8x16MinS))
     }
 
     fn visit_i8x16_min_u(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I8x16MinU))
     }
 
     fn visit_i8x16_max_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I8x16MaxS))
     }
 
     fn visit_i8x16_max_u(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I8x16MaxU))
     }
 
     fn visit_i8x16_avgr_u(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I8x16AvgrU))
     }
 
     fn visit_i16x8_extadd_pairwise_i8x16_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I16x8ExtAddPairwiseI8x16S))
     }
 
     fn visit_i16x8_extadd_pairwise_i8x16_u(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I16x8ExtAddPairwiseI8x16U))
     }
 
     fn visit_i16x8_abs(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I16x8Abs))
     }
 
     fn visit_i16x8_neg(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I16x8Neg))
     }
 
     fn visit_i16x8_q15mulr_sat_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I16x8Q15MulrSatS))
     }
 
     fn visit_i16x8_all_true(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I16x8AllTrue))
     }
 
     fn visit_i16x8_bitmask(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I16x8Bitmask))
     }
 
     fn visit_i16x8_narrow_i32x4_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I16x8NarrowI32x4S))
     }
 
     fn visit_i16x8_narrow_i32x4_u(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I16x8NarrowI32x4U))
     }
 
     fn visit_i16x8_extend_low_i8x16_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I16x8ExtendLowI8x16S))
     }
 
     fn visit_i16x8_extend_high_i8x16_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I16x8ExtendHighI8x16S))
     }
 
     fn visit_i16x8_extend_low_i8x16_u(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I16x8ExtendLowI8x16U))
     }
 
     fn visit_i16x8_extend_high_i8x16_u(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I16x8ExtendHighI8x16U))
     }
 
     fn visit_i16x8_shl(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I16x8Shl))
     }
 
     fn visit_i16x8_shr_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I16x8ShrS))
     }
 
     fn visit_i16x8_shr_u(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I16x8ShrU))
     }
 
     fn visit_i16x8_add(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I16x8Add))
     }
 
     fn visit_i16x8_add_sat_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I16x8AddSatS))
     }
 
     fn visit_i16x8_add_sat_u(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I16x8AddSatU))
     }
 
     fn visit_i16x8_sub(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I16x8Sub))
     }
 
     fn visit_i16x8_sub_sat_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I16x8SubSatS))
     }
 
     fn visit_i16x8_sub_sat_u(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I16x8SubSatU))
     }
 
     fn visit_i16x8_mul(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I16x8Mul))
     }
 
     fn visit_i16x8_min_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I16x8MinS))
     }
 
     fn visit_i16x8_min_u(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I16x8MinU))
     }
 
     fn visit_i16x8_max_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I16x8MaxS))
     }
 
     fn visit_i16x8_max_u(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I16x8MaxU))
     }
 
     fn visit_i16x8_avgr_u(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I16x8AvgrU))
     }
 
     fn visit_i16x8_extmul_low_i8x16_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I16x8ExtMulLowI8x16S))
     }
 
     fn visit_i16x8_extmul_high_i8x16_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I16x8ExtMulHighI8x16S))
     }
 
     fn visit_i16x8_extmul_low_i8x16_u(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I16x8ExtMulLowI8x16U))
     }
 
     fn visit_i16x8_extmul_high_i8x16_u(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I16x8ExtMulHighI8x16U))
     }
 
     fn visit_i32x4_extadd_pairwise_i16x8_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I32x4ExtAddPairwiseI16x8S))
     }
 
     fn visit_i32x4_extadd_pairwise_i16x8_u(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I32x4ExtAddPairwiseI16x8U))
     }
 
     fn visit_i32x4_abs(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I32x4Abs))
     }
 
     fn visit_i32x4_neg(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I32x4Neg))
     }
 
     fn visit_i32x4_all_true(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I32x4AllTrue))
     }
 
     fn visit_i32x4_bitmask(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I32x4Bitmask))
     }
 
     fn visit_i32x4_extend_low_i16x8_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I32x4ExtendLowI16x8S))
     }
 
     fn visit_i32x4_extend_high_i16x8_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I32x4ExtendHighI16x8S))
     }
 
     fn visit_i32x4_extend_low_i16x8_u(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I32x4ExtendLowI16x8U))
     }
 
     fn visit_i32x4_extend_high_i16x8_u(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I32x4ExtendHighI16x8U))
     }
 
     fn visit_i32x4_shl(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I32x4Shl))
     }
 
     fn visit_i32x4_shr_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I32x4ShrS))
     }
 
     fn visit_i32x4_shr_u(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I32x4ShrU))
     }
 
     fn visit_i32x4_add(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I32x4Add))
     }
 
     fn visit_i32x4_sub(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I32x4Sub))
     }
 
     fn visit_i32x4_mul(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I32x4Mul))
     }
 
     fn visit_i32x4_min_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I32x4MinS))
     }
 
     fn visit_i32x4_min_u(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I32x4MinU))
     }
 
     fn visit_i32x4_max_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I32x4MaxS))
     }
 
     fn visit_i32x4_max_u(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I32x4MaxU))
     }
 
     fn visit_i32x4_dot_i16x8_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I32x4DotI16x8S))
     }
 
     fn visit_i32x4_extmul_low_i16x8_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I32x4ExtMulLowI16x8S))
     }
 
     fn visit_i32x4_extmul_high_i16x8_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I32x4ExtMulHighI16x8S))
     }
 
     fn visit_i32x4_extmul_low_i16x8_u(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I32x4ExtMulLowI16x8U))
     }
 
     fn visit_i32x4_extmul_high_i16x8_u(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I32x4ExtMulHighI16x8U))
     }
 
     fn visit_i64x2_abs(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I64x2Abs))
     }
 
     fn visit_i64x2_neg(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I64x2Neg))
     }
 
     fn visit_i64x2_all_true(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I64x2AllTrue))
     }
 
     fn visit_i64x2_bitmask(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I64x2Bitmask))
     }
 
     fn visit_i64x2_extend_low_i32x4_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I64x2ExtendLowI32x4S))
     }
 
     fn visit_i64x2_extend_high_i32x4_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I64x2ExtendHighI32x4S))
     }
 
     fn visit_i64x2_extend_low_i32x4_u(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I64x2ExtendLowI32x4U))
     }
 
     fn visit_i64x2_extend_high_i32x4_u(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I64x2ExtendHighI32x4U))
     }
 
     fn visit_i64x2_shl(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I64x2Shl))
     }
 
     fn visit_i64x2_shr_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I64x2ShrS))
     }
 
     fn visit_i64x2_shr_u(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I64x2ShrU))
     }
 
     fn visit_i64x2_add(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I64x2Add))
     }
 
     fn visit_i64x2_sub(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I64x2Sub))
     }
 
     fn visit_i64x2_mul(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I64x2Mul))
     }
 
     fn visit_i64x2_extmul_low_i32x4_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I64x2ExtMulLowI32x4S))
     }
 
     fn visit_i64x2_extmul_high_i32x4_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I64x2ExtMulHighI32x4S))
     }
 
     fn visit_i64x2_extmul_low_i32x4_u(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I64x2ExtMulLowI32x4U))
     }
 
     fn visit_i64x2_extmul_high_i32x4_u(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I64x2ExtMulHighI32x4U))
     }
 
     fn visit_f32x4_ceil(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::F32x4Ceil))
     }
 
     fn visit_f32x4_floor(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::F32x4Floor))
     }
 
     fn visit_f32x4_trunc(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::F32x4Trunc))
     }
 
     fn visit_f32x4_nearest(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::F32x4Nearest))
     }
 
     fn visit_f32x4_abs(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_simd(&[ValType::V128], &[ValType::V128], |code| {
+            simd::float_sign(code, Shape::I32, InstructionSet::op_i32_and, 0x7fffffff)
+        })
     }
 
     fn visit_f32x4_neg(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_simd(&[ValType::V128], &[ValType::V128], |code| {
+            simd::float_sign(code, Shape::I32, InstructionSet::op_i32_xor, i32::MIN)
+        })
     }
 
     fn visit_f32x4_sqrt(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::F32x4Sqrt))
     }
 
     fn visit_f32x4_add(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::F32x4Add))
     }
 
     fn visit_f32x4_sub(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::F32x4Sub))
     }
 
     fn visit_f32x4_mul(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::F32x4Mul))
     }
 
     fn visit_f32x4_div(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::F32x4Div))
     }
 
     fn visit_f32x4_min(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::F32x4Min))
     }
 
     fn visit_f32x4_max(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::F32x4Max))
     }
 
     fn visit_f32x4_pmin(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::F32x4PMin))
     }
 
     fn visit_f32x4_pmax(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::F32x4PMax))
     }
 
     fn visit_f64x2_ceil(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::F64x2Ceil))
     }
 
     fn visit_f64x2_floor(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::F64x2Floor))
     }
 
     fn visit_f64x2_trunc(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::F64x2Trunc))
     }
 
     fn visit_f64x2_nearest(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::F64x2Nearest))
     }
 
     fn visit_f64x2_abs(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_simd(&[ValType::V128], &[ValType::V128], |code| {
+            simd::float_sign(code, Shape::I64, InstructionSet::op_i32_and, 0x7fffffff)
+        })
     }
 
     fn visit_f64x2_neg(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_simd(&[ValType::V128], &[ValType::V128], |code| {
+            simd::float_sign(code, Shape::I64, InstructionSet::op_i32_xor, i32::MIN)
+        })
     }
 
     fn visit_f64x2_sqrt(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::F64x2Sqrt))
     }
 
     fn visit_f64x2_add(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::F64x2Add))
     }
 
     fn visit_f64x2_sub(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::F64x2Sub))
     }
 
     fn visit_f64x2_mul(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::F64x2Mul))
     }
 
     fn visit_f64x2_div(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::F64x2Div))
     }
 
     fn visit_f64x2_min(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::F64x2Min))
     }
 
     fn visit_f64x2_max(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::F64x2Max))
     }
 
     fn visit_f64x2_pmin(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::F64x2PMin))
     }
 
     fn visit_f64x2_pmax(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::F64x2PMax))
     }
 
     fn visit_i32x4_trunc_sat_f32x4_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I32x4TruncSatF32x4S))
     }
 
     fn visit_i32x4_trunc_sat_f32x4_u(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I32x4TruncSatF32x4U))
     }
 
     fn visit_f32x4_convert_i32x4_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::F32x4ConvertI32x4S))
     }
 
     fn visit_f32x4_convert_i32x4_u(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::F32x4ConvertI32x4U))
     }
 
     fn visit_i32x4_trunc_sat_f64x2_s_zero(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I32x4TruncSatF64x2SZero))
     }
 
     fn visit_i32x4_trunc_sat_f64x2_u_zero(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::I32x4TruncSatF64x2UZero))
     }
 
     fn visit_f64x2_convert_low_i32x4_s(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::F64x2ConvertLowI32x4S))
     }
 
     fn visit_f64x2_convert_low_i32x4_u(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::F64x2ConvertLowI32x4U))
     }
 
     fn visit_f32x4_demote_f64x2_zero(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::F32x4DemoteF64x2Zero))
     }
 
     fn visit_f64x2_promote_low_f32x4(&mut self) -> Self::Output {
-        Err(CompilationError::NotSupportedExtension)
+        self.translate_to_snippet_call(Snippet::Simd(SimdOp::F64x2PromoteLowF32x4))
     }
 
     fn visit_i8x16_relaxed_swizzle(&mut self) -> Self::Output {
@@ -3895,6 +4765,138 @@ impl<'a> VisitOperator<'a> for InstructionTranslator {
 }
 
 impl InstructionTranslator {
+    /// Translates a SIMD instruction lowered inline, the emitter returns the max stack height
+    /// of the emitted sequence above its operands.
+    fn translate_simd(
+        &mut self,
+        params: &[ValType],
+        results: &[ValType],
+        emitter: impl FnOnce(&mut InstructionSet) -> MaxStackHeight,
+    ) -> Result<(), CompilationError> {
+        self.translate_if_reachable(|builder| {
+            builder.bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Arithmetic))?;
+            builder.translate_lowered(params, results, emitter);
+            Ok(())
+        })
+    }
+
+    /// Translates a memory instruction lowered inline (SIMD or atomic), the emitted sequence
+    /// accesses the memory of `memarg`.
+    fn translate_lowered_memory(
+        &mut self,
+        memarg: MemArg,
+        fuel_cost: u32,
+        params: &[ValType],
+        results: &[ValType],
+        emitter: impl FnOnce(&mut InstructionSet, AddressOffset) -> MaxStackHeight,
+    ) -> Result<(), CompilationError> {
+        self.charge_pending_fuel();
+        self.translate_if_reachable(|builder| {
+            builder.bump_fuel_consumption(|_| fuel_cost)?;
+            let offset = AddressOffset::from(memarg.offset as u32);
+            let start = builder.alloc.instruction_set.loc();
+            builder.translate_lowered(params, results, |code| emitter(code, offset));
+            builder
+                .alloc
+                .instruction_set
+                .retarget_memory(start, MemoryIdx::try_from(memarg.memory).unwrap());
+            Ok(())
+        })
+    }
+
+    fn translate_atomic_load(
+        &mut self,
+        memarg: MemArg,
+        access: Access,
+    ) -> Result<(), CompilationError> {
+        self.translate_lowered_memory(
+            memarg,
+            self.fuel_cost(FuelCostClass::Load),
+            &[ValType::I32],
+            &[access.value_type()],
+            |code, offset| atomics::atomic_load(code, offset, access),
+        )
+    }
+
+    fn translate_atomic_store(
+        &mut self,
+        memarg: MemArg,
+        access: Access,
+    ) -> Result<(), CompilationError> {
+        self.translate_lowered_memory(
+            memarg,
+            self.fuel_cost(FuelCostClass::Store),
+            &[ValType::I32, access.value_type()],
+            &[],
+            |code, offset| atomics::atomic_store(code, offset, access),
+        )
+    }
+
+    fn translate_atomic_rmw(
+        &mut self,
+        memarg: MemArg,
+        access: Access,
+        op: RmwOp,
+    ) -> Result<(), CompilationError> {
+        self.translate_lowered_memory(
+            memarg,
+            self.fuel_cost(FuelCostClass::Load) + self.fuel_cost(FuelCostClass::Store),
+            &[ValType::I32, access.value_type()],
+            &[access.value_type()],
+            |code, offset| atomics::atomic_rmw(code, offset, access, op),
+        )
+    }
+
+    fn translate_atomic_cmpxchg(
+        &mut self,
+        memarg: MemArg,
+        access: Access,
+    ) -> Result<(), CompilationError> {
+        self.translate_lowered_memory(
+            memarg,
+            self.fuel_cost(FuelCostClass::Load) + self.fuel_cost(FuelCostClass::Store),
+            &[ValType::I32, access.value_type(), access.value_type()],
+            &[access.value_type()],
+            |code, offset| atomics::atomic_cmpxchg(code, offset, access),
+        )
+    }
+
+    fn translate_atomic_wait(
+        &mut self,
+        memarg: MemArg,
+        access: Access,
+    ) -> Result<(), CompilationError> {
+        self.translate_lowered_memory(
+            memarg,
+            self.fuel_cost(FuelCostClass::Load),
+            &[ValType::I32, access.value_type(), ValType::I64],
+            &[ValType::I32],
+            |code, offset| atomics::atomic_wait(code, offset, access),
+        )
+    }
+
+    fn translate_lowered(
+        &mut self,
+        params: &[ValType],
+        results: &[ValType],
+        emitter: impl FnOnce(&mut InstructionSet) -> MaxStackHeight,
+    ) {
+        for param in params.iter().rev() {
+            let popped_type = self.alloc.stack_types.pop().unwrap();
+            debug_assert_eq!(*param, popped_type);
+        }
+        self.alloc.stack_types.extend(results);
+        let max_stack_height = emitter(&mut self.alloc.instruction_set);
+        self.stack_height.push_n(max_stack_height);
+        for param in params {
+            self.stack_height.pop_type(*param);
+        }
+        for result in results {
+            self.stack_height.push_type(*result);
+        }
+        self.stack_height.pop_n(max_stack_height);
+    }
+
     fn translate_load(
         &mut self,
         memarg: MemArg,
@@ -3902,9 +4904,9 @@ impl InstructionTranslator {
         emitter: fn(&mut InstructionSet, offset: AddressOffset),
         max_stack_height: u32,
     ) -> Result<(), CompilationError> {
+        self.charge_pending_fuel();
         self.translate_if_reachable(|builder| {
-            debug_assert_eq!(memarg.memory, DEFAULT_MEMORY_INDEX);
-            builder.bump_fuel_consumption(|| FuelCosts::LOAD)?;
+            builder.bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Load))?;
             let addr_type = builder.alloc.stack_types.pop().unwrap();
             debug_assert_eq!(addr_type, ValType::I32);
             builder.stack_height.push_n(max_stack_height);
@@ -3913,7 +4915,12 @@ impl InstructionTranslator {
             builder.stack_height.pop_n(max_stack_height);
             builder.alloc.stack_types.push(loaded_type);
             let offset = AddressOffset::from(memarg.offset as u32);
+            let start = builder.alloc.instruction_set.loc();
             emitter(&mut builder.alloc.instruction_set, offset);
+            builder
+                .alloc
+                .instruction_set
+                .retarget_memory(start, MemoryIdx::try_from(memarg.memory).unwrap());
             Ok(())
         })
     }
@@ -3925,9 +4932,9 @@ impl InstructionTranslator {
         emitter: fn(&mut InstructionSet, offset: AddressOffset),
         max_stack_height: u32,
     ) -> Result<(), CompilationError> {
+        self.charge_pending_fuel();
         self.translate_if_reachable(|builder| {
-            debug_assert_eq!(memarg.memory, DEFAULT_MEMORY_INDEX);
-            builder.bump_fuel_consumption(|| FuelCosts::STORE)?;
+            builder.bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Store))?;
             builder.stack_height.push_n(max_stack_height);
             let value_type = builder.alloc.stack_types.pop().unwrap();
             debug_assert_eq!(value_type, stored_value);
@@ -3937,7 +4944,12 @@ impl InstructionTranslator {
             builder.stack_height.pop_type(addr_type);
             builder.stack_height.pop_n(max_stack_height);
             let offset = AddressOffset::from(memarg.offset as u32);
+            let start = builder.alloc.instruction_set.loc();
             emitter(&mut builder.alloc.instruction_set, offset);
+            builder
+                .alloc
+                .instruction_set
+                .retarget_memory(start, MemoryIdx::try_from(memarg.memory).unwrap());
             Ok(())
         })
     }
@@ -3950,7 +4962,7 @@ impl InstructionTranslator {
         max_stack_height: u32,
     ) -> Result<(), CompilationError> {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(|| FuelCosts::BASE)?;
+            builder.bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Arithmetic))?;
             if max_stack_height > 0 {
                 builder.stack_height.push_n(max_stack_height);
             }
@@ -3976,7 +4988,7 @@ impl InstructionTranslator {
         self.translate_if_reachable(|builder| {
             let lhs_type = builder.alloc.stack_types.pop().unwrap();
             debug_assert_eq!(lhs_type, input_type);
-            builder.bump_fuel_consumption(|| FuelCosts::BASE)?;
+            builder.bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Arithmetic))?;
             builder.alloc.stack_types.push(output_type);
             // calc stack height
             builder.stack_height.pop_type(input_type);
@@ -3991,7 +5003,7 @@ impl InstructionTranslator {
         max_stack_height: u32,
     ) -> Result<(), CompilationError> {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(|| FuelCosts::BASE)?;
+            builder.bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Arithmetic))?;
             // calculate the type stack and make sure params are correct
             let lhs_type = builder.alloc.stack_types.pop().unwrap();
             let rhs_type = builder.alloc.stack_types.pop().unwrap();
@@ -4015,11 +5027,11 @@ impl InstructionTranslator {
 
     fn translate_to_snippet_call(&mut self, snippet: Snippet) -> Result<(), CompilationError> {
         if !self.with_code_snippets {
-            return self.translate_binary(snippet.emitter(), snippet.max_stack_height());
+            return self.translate_inline_snippet(snippet);
         }
 
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(|| FuelCosts::BASE)?;
+            builder.bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Arithmetic))?;
             builder
                 .stack_height
                 .pop_n(snippet.func_type().params().len() as u32);
@@ -4046,13 +5058,80 @@ impl InstructionTranslator {
         })
     }
 
+    fn translate_inline_snippet(&mut self, snippet: Snippet) -> Result<(), CompilationError> {
+        self.translate_if_reachable(|builder| {
+            builder.bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Arithmetic))?;
+            let func_type = snippet.orig_func_type();
+            // calculate the type stack and make sure params are correct
+            for param in func_type.params().iter().rev() {
+                let popped_type = builder.alloc.stack_types.pop().unwrap();
+                debug_assert_eq!(*param, popped_type);
+            }
+            builder.alloc.stack_types.extend(func_type.results());
+            // calculate max stack height
+            builder.stack_height.push_n(snippet.max_stack_height());
+            for param in func_type.params() {
+                builder.stack_height.pop_type(*param);
+            }
+            for result in func_type.results() {
+                builder.stack_height.push_type(*result);
+            }
+            builder.stack_height.pop_n(snippet.max_stack_height());
+            // emit the snippet body
+            snippet.emit(&mut builder.alloc.instruction_set);
+            Ok(())
+        })
+    }
+
+    /// Calls a software float snippet, `operand` is pushed as its last `i32` operand (a rounding
+    /// mode, a comparison mask or conversion flags).
+    #[cfg(not(feature = "fpu"))]
+    fn translate_soft_float_call(
+        &mut self,
+        snippet: Snippet,
+        operand: i32,
+    ) -> Result<(), CompilationError> {
+        self.visit_i32_const(operand)?;
+        self.translate_to_snippet_call(snippet)
+    }
+
+    /// Truncates a float to an integer, the high word of an `i32` result is dropped.
+    #[cfg(not(feature = "fpu"))]
+    fn translate_soft_float_truncation(
+        &mut self,
+        snippet: Snippet,
+        flags: i32,
+    ) -> Result<(), CompilationError> {
+        self.translate_soft_float_call(snippet, flags)?;
+        if flags & Snippet::TO_INT_I64 == 0 {
+            self.visit_i32_wrap_i64()?;
+        }
+        Ok(())
+    }
+
+    /// Converts an integer to a float, `i32` operands are extended to `i64` first.
+    #[cfg(not(feature = "fpu"))]
+    fn translate_soft_float_conversion(
+        &mut self,
+        snippet: Snippet,
+        input_type: ValType,
+        is_signed: bool,
+    ) -> Result<(), CompilationError> {
+        match (input_type, is_signed) {
+            (ValType::I32, true) => self.visit_i64_extend_i32_s()?,
+            (ValType::I32, false) => self.visit_i64_extend_i32_u()?,
+            _ => {}
+        }
+        self.translate_soft_float_call(snippet, is_signed as i32)
+    }
+
     fn translate_binary_compare(
         &mut self,
         emitter: fn(&mut InstructionSet),
         max_stack_height: u32,
     ) -> Result<(), CompilationError> {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(|| FuelCosts::BASE)?;
+            builder.bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Arithmetic))?;
             let lhs_type = builder.alloc.stack_types.pop().unwrap();
             let rhs_type = builder.alloc.stack_types.pop().unwrap();
             debug_assert_eq!(lhs_type, rhs_type);
@@ -4079,7 +5158,7 @@ impl InstructionTranslator {
         max_stack_height: u32,
     ) -> Result<(), CompilationError> {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(|| FuelCosts::BASE)?;
+            builder.bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Arithmetic))?;
             // calc the type stack
             let lhs_type = builder.alloc.stack_types.pop().unwrap();
             builder.alloc.stack_types.push(lhs_type);
@@ -4100,7 +5179,7 @@ impl InstructionTranslator {
         max_stack_height: u32,
     ) -> Result<(), CompilationError> {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(|| FuelCosts::BASE)?;
+            builder.bump_fuel_consumption(|model| model.opcode_cost(FuelCostClass::Arithmetic))?;
             // check the type stack
             let lsh_type = builder.alloc.stack_types.pop().unwrap();
             builder.alloc.stack_types.push(ValType::I32);
@@ -1,7 +1,8 @@
+use super::default_fuel_cost_model;
 use crate::{
-    AddressOffset, DataSegmentIdx, I64ValueSplit, InstructionSet, TrapCode, N_BYTES_PER_MEMORY_PAGE,
+    AddressOffset, DataSegmentIdx, FuelCostModel, I64ValueSplit, InstructionSet, TrapCode,
+    N_BYTES_PER_MEMORY_PAGE,
 };
-use rwasm_fuel_policy::{MEMORY_BYTES_PER_FUEL, MEMORY_BYTES_PER_FUEL_LOG2};
 
 impl InstructionSet {
     pub const MSH_I64_LOAD: u32 = 2;
@@ -119,6 +120,22 @@ impl InstructionSet {
 
     /// Max stack height: 2
     pub fn op_memory_grow_checked(&mut self, max_pages: Option<u32>, inject_fuel_check: bool) {
+        self.op_memory_grow_checked_with_model(
+            max_pages,
+            default_fuel_cost_model(inject_fuel_check),
+        );
+    }
+
+    /// Charges one fuel per `2^log2` bytes (rounded up) if the fuel cost model is set, see
+    /// [`FuelCostModel::memory_bytes_per_fuel_log2`].
+    ///
+    /// Max stack height: 2
+    pub fn op_memory_grow_checked_with_model(
+        &mut self,
+        max_pages: Option<u32>,
+        fuel_cost_model: Option<&dyn FuelCostModel>,
+    ) {
+        let bytes_per_fuel_log2 = fuel_cost_model.map(|model| model.memory_bytes_per_fuel_log2());
         // we must do max memory check before an execution
         if let Some(max_pages) = max_pages {
             self.op_local_get(1); // d
@@ -129,17 +146,17 @@ impl InstructionSet {
             self.op_br_if_eqz(4);
             self.op_drop();
             self.op_i32_const(u32::MAX);
-            self.op_br(if inject_fuel_check { 8 } else { 2 });
+            self.op_br(if bytes_per_fuel_log2.is_some() { 8 } else { 2 });
         }
         // now we know that pages can't exceed i32::MAX,
         // so we can safely multiply the num of pages to the page size
         // to calculate fuel required for memory to grow
-        if inject_fuel_check {
+        if let Some(bytes_per_fuel_log2) = bytes_per_fuel_log2 {
             self.op_local_get(1); // n
             self.op_i32_const(N_BYTES_PER_MEMORY_PAGE); // size of each memory page
             self.op_i32_mul(); // overflow is impossible here (we pass max pages in trustless mode)
-            self.op_i32_const(MEMORY_BYTES_PER_FUEL_LOG2); // 2^6=64
-            self.op_i32_shr_u(); // delta/64
+            self.op_i32_const(bytes_per_fuel_log2);
+            self.op_i32_shr_u(); // delta/2^bytes_per_fuel_log2
             self.op_consume_fuel_stack();
         }
         // emit memory grows only if fuel is charged
@@ -148,13 +165,25 @@ impl InstructionSet {
 
     /// Max stack height: 2
     pub fn op_memory_fill_checked(&mut self, inject_fuel_check: bool) {
+        self.op_memory_fill_checked_with_model(default_fuel_cost_model(inject_fuel_check));
+    }
+
+    /// Charges one fuel per `2^log2` bytes (rounded up) if the fuel cost model is set, see
+    /// [`FuelCostModel::memory_bytes_per_fuel_log2`].
+    ///
+    /// Max stack height: 2
+    pub fn op_memory_fill_checked_with_model(
+        &mut self,
+        fuel_cost_model: Option<&dyn FuelCostModel>,
+    ) {
+        let bytes_per_fuel_log2 = fuel_cost_model.map(|model| model.memory_bytes_per_fuel_log2());
         // [d, val, n]
-        if inject_fuel_check {
+        if let Some(bytes_per_fuel_log2) = bytes_per_fuel_log2 {
             self.op_local_get(1); // n
-            self.op_i32_const(MEMORY_BYTES_PER_FUEL - 1); // upper round
+            self.op_i32_const((1u32 << bytes_per_fuel_log2) - 1); // upper round
             self.op_i32_add();
-            self.op_i32_const(MEMORY_BYTES_PER_FUEL_LOG2); // 2^6=64
-            self.op_i32_shr_u(); // delta/64
+            self.op_i32_const(bytes_per_fuel_log2);
+            self.op_i32_shr_u(); // delta/2^bytes_per_fuel_log2
             self.op_consume_fuel_stack();
         }
         // emit memory fill
@@ -163,13 +192,25 @@ impl InstructionSet {
 
     /// Max stack height: 2
     pub fn op_memory_copy_checked(&mut self, inject_fuel_check: bool) {
+        self.op_memory_copy_checked_with_model(default_fuel_cost_model(inject_fuel_check));
+    }
+
+    /// Charges one fuel per `2^log2` bytes (rounded up) if the fuel cost model is set, see
+    /// [`FuelCostModel::memory_bytes_per_fuel_log2`].
+    ///
+    /// Max stack height: 2
+    pub fn op_memory_copy_checked_with_model(
+        &mut self,
+        fuel_cost_model: Option<&dyn FuelCostModel>,
+    ) {
+        let bytes_per_fuel_log2 = fuel_cost_model.map(|model| model.memory_bytes_per_fuel_log2());
         // [d, s, n]
-        if inject_fuel_check {
+        if let Some(bytes_per_fuel_log2) = bytes_per_fuel_log2 {
             self.op_local_get(1); // n
-            self.op_i32_const(MEMORY_BYTES_PER_FUEL - 1); // upper round
+            self.op_i32_const((1u32 << bytes_per_fuel_log2) - 1); // upper round
             self.op_i32_add();
-            self.op_i32_const(MEMORY_BYTES_PER_FUEL_LOG2); // 2^6=64
-            self.op_i32_shr_u(); // delta/64
+            self.op_i32_const(bytes_per_fuel_log2);
+            self.op_i32_shr_u(); // delta/2^bytes_per_fuel_log2
             self.op_consume_fuel_stack();
         }
         // emit memory copy
@@ -218,6 +259,27 @@ impl InstructionSet {
         data_segment_index: DataSegmentIdx,
         inject_fuel_check: bool,
     ) {
+        self.op_memory_init_checked_with_model(
+            rewrite_offset,
+            rewrite_length,
+            data_segment_index,
+            default_fuel_cost_model(inject_fuel_check),
+        );
+    }
+
+    /// The same as [`Self::op_memory_init_checked`], but charges one fuel per `2^log2` bytes
+    /// (rounded up) if the fuel cost model is set, see
+    /// [`FuelCostModel::memory_bytes_per_fuel_log2`].
+    ///
+    /// Max stack height: 2
+    pub fn op_memory_init_checked_with_model(
+        &mut self,
+        rewrite_offset: Option<u32>,
+        rewrite_length: Option<u32>,
+        data_segment_index: DataSegmentIdx,
+        fuel_cost_model: Option<&dyn FuelCostModel>,
+    ) {
+        let bytes_per_fuel_log2 = fuel_cost_model.map(|model| model.memory_bytes_per_fuel_log2());
         // do an overflow check
         if let Some(length) = rewrite_length.filter(|v| *v > 0) {
             self.op_local_get(1); // n
@@ -236,12 +298,12 @@ impl InstructionSet {
             self.op_local_set(2);
         }
         // [d, s, n]
-        if inject_fuel_check {
+        if let Some(bytes_per_fuel_log2) = bytes_per_fuel_log2 {
             self.op_local_get(1); // n
-            self.op_i32_const(MEMORY_BYTES_PER_FUEL - 1); // upper round
+            self.op_i32_const((1u32 << bytes_per_fuel_log2) - 1); // upper round
             self.op_i32_add();
-            self.op_i32_const(MEMORY_BYTES_PER_FUEL_LOG2); // 2^6=64
-            self.op_i32_shr_u(); // delta/64
+            self.op_i32_const(bytes_per_fuel_log2);
+            self.op_i32_shr_u(); // delta/2^bytes_per_fuel_log2
             self.op_consume_fuel_stack();
         }
         // emit memory init
@@ -8,15 +8,17 @@ mod memory;
 mod mul;
 mod rem_s;
 mod rem_u;
+mod soft_f32;
+mod soft_f64;
 mod table;
 
 use crate::{
     types::{
         AddressOffset, BlockFuel, BranchOffset, BranchTableTargets, CompiledFunc, DataSegmentIdx,
-        ElementSegmentIdx, GlobalIdx, LocalDepth, MaxStackHeight, Opcode, SignatureIdx, TableIdx,
-        UntypedValue,
+        ElementSegmentIdx, GlobalIdx, LocalDepth, MaxStackHeight, MemoryIdx, Opcode,
+        ShortLocalDepth, SignatureIdx, TableIdx, TagIdx, UntypedValue,
     },
-    CompilationError, NumLocals, SysFuncIdx, TrapCode,
+    CompilationError, DefaultFuelCostModel, FuelCostModel, NumLocals, SysFuncIdx, TrapCode,
 };
 use alloc::{vec, vec::Vec};
 use bincode::{
@@ -27,6 +29,12 @@ use bincode::{
 };
 use core::ops::{Deref, DerefMut};
 
+/// Returns the fuel cost model of the bulk operations that charge the default fuel costs, see
+/// [`InstructionSet::op_memory_fill_checked`].
+fn default_fuel_cost_model(inject_fuel_check: bool) -> Option<&'static dyn FuelCostModel> {
+    inject_fuel_check.then_some(&DefaultFuelCostModel)
+}
+
 /// Compact, linear sequence of rwasm opcodes produced by the compiler.
 /// Acts as the executable bytecode for the interpreter and supports simple editing during lowering.
 /// The layout is stable and indexable to allow inexpensive jumps and metadata lookups.
@@ -145,6 +153,14 @@ impl InstructionSet {
         self.instr.get_mut(n)
     }
 
+    /// Makes the memory instructions emitted since `start` access the memory with the given
+    /// index, so the memory emitters can be reused for all memories.
+    pub fn retarget_memory(&mut self, start: u32, memory: MemoryIdx) {
+        for opcode in self.instr[start as usize..].iter_mut() {
+            *opcode = opcode.with_memory_index(memory);
+        }
+    }
+
     pub fn op_dup(&mut self) {
         self.op_local_get(1);
     }
@@ -249,6 +265,38 @@ impl InstructionSet {
     impl_basic_opcode!(BulkConst(NumLocals));
     impl_basic_opcode!(BulkDrop(NumLocals));
 
+    // fused opcodes
+    impl_basic_opcode!(LocalGetLocalGet(ShortLocalDepth, ShortLocalDepth));
+    impl_basic_opcode!(LocalGetI32Const(ShortLocalDepth, UntypedValue));
+    impl_basic_opcode!(LocalSetLocalGet(ShortLocalDepth, ShortLocalDepth));
+    impl_basic_opcode!(LocalGetI32Load(ShortLocalDepth, AddressOffset));
+    impl_basic_opcode!(LocalSetLocalSet(ShortLocalDepth, ShortLocalDepth));
+    impl_basic_opcode!(I32ConstI32Add(UntypedValue));
+
+    // multi-memory opcodes
+    impl_basic_opcode!(I32LoadAt(MemoryIdx, AddressOffset));
+    impl_basic_opcode!(I32Load8SAt(MemoryIdx, AddressOffset));
+    impl_basic_opcode!(I32Load8UAt(MemoryIdx, AddressOffset));
+    impl_basic_opcode!(I32Load16SAt(MemoryIdx, AddressOffset));
+    impl_basic_opcode!(I32Load16UAt(MemoryIdx, AddressOffset));
+    impl_basic_opcode!(I32StoreAt(MemoryIdx, AddressOffset));
+    impl_basic_opcode!(I32Store8At(MemoryIdx, AddressOffset));
+    impl_basic_opcode!(I32Store16At(MemoryIdx, AddressOffset));
+    impl_basic_opcode!(MemorySizeAt(MemoryIdx));
+    impl_basic_opcode!(MemoryGrowAt(MemoryIdx));
+    impl_basic_opcode!(MemoryFillAt(MemoryIdx));
+    impl_basic_opcode!(MemoryCopyAt(MemoryIdx, MemoryIdx));
+    impl_basic_opcode!(MemoryInitAt(MemoryIdx, DataSegmentIdx));
+
+    // exception opcodes
+    impl_basic_opcode!(TryBegin(BranchOffset));
+    impl_basic_opcode!(TryEnd);
+    impl_basic_opcode!(Throw(TagIdx));
+    impl_basic_opcode!(Rethrow(u32));
+    impl_basic_opcode!(Delegate(u32));
+    impl_basic_opcode!(ExceptionTag);
+    impl_basic_opcode!(ExceptionPayload(TagIdx));
+
     // fpu opcodes (emits trap for disable fpu feature flag)
     impl_fpu_opcode!(F32Load(AddressOffset));
     impl_fpu_opcode!(F64Load(AddressOffset));
@@ -343,6 +391,29 @@ impl InstructionSet {
             .ok_or(CompilationError::BlockFuelOutOfBounds)?;
         Ok(())
     }
+
+    /// Returns the code of the snippets with loops that don't consume fuel (the i64 division and
+    /// remainder, the software float division and square root).
+    ///
+    /// The fuel verifier recognizes these loops by their exact code, so the code must never be
+    /// rewritten after emission.
+    pub(crate) fn bounded_loop_snippets() -> [InstructionSet; 8] {
+        let emitters: [fn(&mut InstructionSet); 8] = [
+            InstructionSet::op_i64_div_s,
+            InstructionSet::op_i64_div_u,
+            InstructionSet::op_i64_rem_s,
+            InstructionSet::op_i64_rem_u,
+            InstructionSet::op_soft_f32_div,
+            InstructionSet::op_soft_f32_sqrt,
+            InstructionSet::op_soft_f64_div,
+            InstructionSet::op_soft_f64_sqrt,
+        ];
+        emitters.map(|emitter| {
+            let mut instruction_set = InstructionSet::new();
+            emitter(&mut instruction_set);
+            instruction_set
+        })
+    }
 }
 
 #[macro_export]
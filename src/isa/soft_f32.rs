@@ -0,0 +1,1639 @@
+use crate::{InstructionSet, TrapCode};
+
+impl InstructionSet {
+    pub const MSH_SOFT_F32_ABS: u32 = 1;
+    pub const MSH_SOFT_F32_NEG: u32 = 1;
+    pub const MSH_SOFT_F32_COPYSIGN: u32 = 2;
+    pub const MSH_SOFT_F32_ADD: u32 = 14;
+    pub const MSH_SOFT_F32_MUL: u32 = 15;
+    pub const MSH_SOFT_F32_DIV: u32 = 16;
+    pub const MSH_SOFT_F32_SQRT: u32 = 11;
+    pub const MSH_SOFT_F32_MIN_MAX: u32 = 7;
+    pub const MSH_SOFT_F32_COMPARE: u32 = 6;
+    pub const MSH_SOFT_F32_ROUND: u32 = 11;
+    pub const MSH_SOFT_F32_TO_INT: u32 = 11;
+    pub const MSH_SOFT_F32_FROM_INT: u32 = 8;
+    pub const MSH_SOFT_F32_DEMOTE_F64: u32 = 11;
+
+    /// Max stack height: 1
+    pub fn op_soft_f32_abs(&mut self) {
+        self.op_i32_const(2147483647);
+        self.op_i32_and();
+    }
+
+    /// Max stack height: 1
+    pub fn op_soft_f32_neg(&mut self) {
+        self.op_i32_const(-2147483648);
+        self.op_i32_xor();
+    }
+
+    /// Max stack height: 2
+    pub fn op_soft_f32_copysign(&mut self) {
+        self.op_i32_const(-2147483648);
+        self.op_i32_and(); // the sign of rhs
+        self.op_local_get(2);
+        self.op_i32_const(2147483647);
+        self.op_i32_and(); // the magnitude of lhs
+        self.op_i32_or();
+        self.op_local_set(1);
+    }
+
+    /// Adds two `f32` values (`lhs`, `rhs`), `f32.sub` negates `rhs` first.
+    ///
+    /// Max stack height: 14
+    pub fn op_soft_f32_add(&mut self) {
+        self.op_bulk_const(10);
+        self.op_local_get(12);
+        self.op_i32_const(2147483647);
+        self.op_i32_and();
+        self.op_local_set(10);
+        self.op_local_get(11);
+        self.op_i32_const(2147483647);
+        self.op_i32_and();
+        self.op_local_set(9);
+        self.op_local_get(10);
+        self.op_i32_const(1);
+        self.op_i32_sub();
+        self.op_i32_const(2139095039);
+        self.op_i32_ge_u();
+        self.op_local_get(10);
+        self.op_i32_const(1);
+        self.op_i32_sub();
+        self.op_i32_const(2139095039);
+        self.op_i32_ge_u();
+        self.op_i32_or();
+        self.op_br_if_eqz(51);
+        self.op_local_get(10);
+        self.op_i32_const(2139095040);
+        self.op_i32_gt_u();
+        self.op_local_get(10);
+        self.op_i32_const(2139095040);
+        self.op_i32_gt_u();
+        self.op_i32_or();
+        self.op_br_if_eqz(4);
+        self.op_i32_const(2143289344);
+        self.op_local_set(2);
+        self.op_br(269);
+        self.op_local_get(10);
+        self.op_i32_const(2139095040);
+        self.op_i32_eq();
+        self.op_br_if_eqz(11);
+        self.op_i32_const(2143289344);
+        self.op_local_get(13);
+        self.op_local_get(14);
+        self.op_local_get(14);
+        self.op_i32_xor();
+        self.op_i32_const(-2147483648);
+        self.op_i32_eq();
+        self.op_select();
+        self.op_local_set(2);
+        self.op_br(255);
+        self.op_local_get(9);
+        self.op_i32_const(2139095040);
+        self.op_i32_eq();
+        self.op_br_if_eqz(4);
+        self.op_local_get(11);
+        self.op_local_set(2);
+        self.op_br(248);
+        self.op_local_get(10);
+        self.op_i32_eqz();
+        self.op_br_if_eqz(10);
+        self.op_local_get(12);
+        self.op_local_get(12);
+        self.op_i32_and();
+        self.op_local_get(12);
+        self.op_local_get(11);
+        self.op_i32_eqz();
+        self.op_select();
+        self.op_local_set(2);
+        self.op_br(236);
+        self.op_local_get(9);
+        self.op_i32_eqz();
+        self.op_br_if_eqz(4);
+        self.op_local_get(12);
+        self.op_local_set(2);
+        self.op_br(230);
+        self.op_local_get(9);
+        self.op_local_get(11);
+        self.op_i32_gt_u();
+        self.op_br_if_eqz(7);
+        self.op_local_get(12);
+        self.op_local_set(8);
+        self.op_local_get(11);
+        self.op_local_set(12);
+        self.op_local_get(8);
+        self.op_local_set(11);
+        self.op_local_get(12);
+        self.op_i32_const(23);
+        self.op_i32_shr_u();
+        self.op_i32_const(255);
+        self.op_i32_and();
+        self.op_local_set(7);
+        self.op_local_get(11);
+        self.op_i32_const(23);
+        self.op_i32_shr_u();
+        self.op_i32_const(255);
+        self.op_i32_and();
+        self.op_local_set(6);
+        self.op_local_get(12);
+        self.op_i32_const(8388607);
+        self.op_i32_and();
+        self.op_local_set(5);
+        self.op_local_get(11);
+        self.op_i32_const(8388607);
+        self.op_i32_and();
+        self.op_local_set(4);
+        self.op_local_get(7);
+        self.op_i32_eqz();
+        self.op_br_if_eqz(14);
+        self.op_local_get(5);
+        self.op_i32_clz();
+        self.op_i32_const(8);
+        self.op_i32_sub();
+        self.op_local_set(3);
+        self.op_local_get(5);
+        self.op_local_get(4);
+        self.op_i32_shl();
+        self.op_local_set(5);
+        self.op_i32_const(1);
+        self.op_local_get(4);
+        self.op_i32_sub();
+        self.op_local_set(7);
+        self.op_local_get(6);
+        self.op_i32_eqz();
+        self.op_br_if_eqz(14);
+        self.op_local_get(4);
+        self.op_i32_clz();
+        self.op_i32_const(8);
+        self.op_i32_sub();
+        self.op_local_set(3);
+        self.op_local_get(4);
+        self.op_local_get(4);
+        self.op_i32_shl();
+        self.op_local_set(4);
+        self.op_i32_const(1);
+        self.op_local_get(4);
+        self.op_i32_sub();
+        self.op_local_set(6);
+        self.op_local_get(5);
+        self.op_i32_const(8388608);
+        self.op_i32_or();
+        self.op_i32_const(3);
+        self.op_i32_shl();
+        self.op_local_set(5);
+        self.op_local_get(4);
+        self.op_i32_const(8388608);
+        self.op_i32_or();
+        self.op_i32_const(3);
+        self.op_i32_shl();
+        self.op_local_set(4);
+        self.op_local_get(7);
+        self.op_local_get(7);
+        self.op_i32_sub();
+        self.op_local_set(3);
+        self.op_local_get(3);
+        self.op_br_if_eqz(20);
+        self.op_local_get(3);
+        self.op_i32_const(32);
+        self.op_i32_lt_u();
+        self.op_br_if_eqz(14);
+        self.op_local_get(4);
+        self.op_local_get(4);
+        self.op_i32_shr_u();
+        self.op_local_get(5);
+        self.op_i32_const(32);
+        self.op_local_get(6);
+        self.op_i32_sub();
+        self.op_i32_shl();
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_i32_or();
+        self.op_local_set(4);
+        self.op_br(3);
+        self.op_i32_const(1);
+        self.op_local_set(4);
+        self.op_local_get(12);
+        self.op_local_get(12);
+        self.op_i32_xor();
+        self.op_i32_const(0);
+        self.op_i32_lt_s();
+        self.op_br_if_eqz(29);
+        self.op_local_get(5);
+        self.op_local_get(5);
+        self.op_i32_sub();
+        self.op_local_set(5);
+        self.op_local_get(5);
+        self.op_i32_eqz();
+        self.op_br_if_eqz(4);
+        self.op_i32_const(0);
+        self.op_local_set(2);
+        self.op_br(115);
+        self.op_local_get(5);
+        self.op_i32_const(67108864);
+        self.op_i32_lt_u();
+        self.op_br_if_eqz(14);
+        self.op_local_get(5);
+        self.op_i32_clz();
+        self.op_i32_const(5);
+        self.op_i32_sub();
+        self.op_local_set(3);
+        self.op_local_get(5);
+        self.op_local_get(4);
+        self.op_i32_shl();
+        self.op_local_set(5);
+        self.op_local_get(7);
+        self.op_local_get(4);
+        self.op_i32_sub();
+        self.op_local_set(7);
+        self.op_br(21);
+        self.op_local_get(5);
+        self.op_local_get(5);
+        self.op_i32_add();
+        self.op_local_set(5);
+        self.op_local_get(5);
+        self.op_i32_const(134217728);
+        self.op_i32_and();
+        self.op_br_if_eqz(13);
+        self.op_local_get(5);
+        self.op_i32_const(1);
+        self.op_i32_shr_u();
+        self.op_local_get(6);
+        self.op_i32_const(1);
+        self.op_i32_and();
+        self.op_i32_or();
+        self.op_local_set(5);
+        self.op_local_get(7);
+        self.op_i32_const(1);
+        self.op_i32_add();
+        self.op_local_set(7);
+        self.op_local_get(12);
+        self.op_i32_const(-2147483648);
+        self.op_i32_and();
+        self.op_local_set(12);
+        self.op_local_get(7);
+        self.op_i32_const(255);
+        self.op_i32_ge_s();
+        self.op_br_if_eqz(6);
+        self.op_local_get(12);
+        self.op_i32_const(2139095040);
+        self.op_i32_or();
+        self.op_local_set(2);
+        self.op_br(64);
+        self.op_local_get(7);
+        self.op_i32_const(0);
+        self.op_i32_le_s();
+        self.op_br_if_eqz(28);
+        self.op_i32_const(1);
+        self.op_local_get(8);
+        self.op_i32_sub();
+        self.op_local_set(3);
+        self.op_local_get(3);
+        self.op_i32_const(32);
+        self.op_i32_lt_u();
+        self.op_br_if_eqz(14);
+        self.op_local_get(5);
+        self.op_local_get(4);
+        self.op_i32_shr_u();
+        self.op_local_get(6);
+        self.op_i32_const(32);
+        self.op_local_get(6);
+        self.op_i32_sub();
+        self.op_i32_shl();
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_i32_or();
+        self.op_local_set(5);
+        self.op_br(5);
+        self.op_local_get(5);
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_local_set(5);
+        self.op_i32_const(0);
+        self.op_local_set(7);
+        self.op_local_get(5);
+        self.op_i32_const(7);
+        self.op_i32_and();
+        self.op_local_set(1);
+        self.op_local_get(5);
+        self.op_i32_const(3);
+        self.op_i32_shr_u();
+        self.op_i32_const(8388607);
+        self.op_i32_and();
+        self.op_local_get(8);
+        self.op_i32_const(23);
+        self.op_i32_shl();
+        self.op_i32_or();
+        self.op_local_get(13);
+        self.op_i32_or();
+        self.op_local_set(2);
+        self.op_local_get(1);
+        self.op_i32_const(4);
+        self.op_i32_gt_u();
+        self.op_local_get(2);
+        self.op_i32_const(4);
+        self.op_i32_eq();
+        self.op_local_get(4);
+        self.op_i32_const(1);
+        self.op_i32_and();
+        self.op_i32_and();
+        self.op_i32_or();
+        self.op_br_if_eqz(5);
+        self.op_local_get(2);
+        self.op_i32_const(1);
+        self.op_i32_add();
+        self.op_local_set(2);
+        self.op_local_get(2);
+        self.op_local_set(12);
+        self.op_bulk_drop(11);
+    }
+
+    /// Multiplies two `f32` values (`lhs`, `rhs`).
+    ///
+    /// Max stack height: 15
+    pub fn op_soft_f32_mul(&mut self) {
+        self.op_bulk_const(11);
+        self.op_local_get(13);
+        self.op_i32_const(2147483647);
+        self.op_i32_and();
+        self.op_local_set(11);
+        self.op_local_get(12);
+        self.op_i32_const(2147483647);
+        self.op_i32_and();
+        self.op_local_set(10);
+        self.op_local_get(13);
+        self.op_local_get(13);
+        self.op_i32_xor();
+        self.op_i32_const(-2147483648);
+        self.op_i32_and();
+        self.op_local_set(5);
+        self.op_local_get(11);
+        self.op_i32_const(2139095040);
+        self.op_i32_ge_u();
+        self.op_local_get(11);
+        self.op_i32_const(2139095040);
+        self.op_i32_ge_u();
+        self.op_i32_or();
+        self.op_br_if_eqz(22);
+        self.op_i32_const(2143289344);
+        self.op_local_set(2);
+        self.op_local_get(11);
+        self.op_i32_const(2139095040);
+        self.op_i32_gt_u();
+        self.op_local_get(11);
+        self.op_i32_const(2139095040);
+        self.op_i32_gt_u();
+        self.op_i32_or();
+        self.op_br_if_nez(185);
+        self.op_local_get(11);
+        self.op_i32_eqz();
+        self.op_local_get(11);
+        self.op_i32_eqz();
+        self.op_i32_or();
+        self.op_br_if_nez(179);
+        self.op_local_get(5);
+        self.op_i32_const(2139095040);
+        self.op_i32_or();
+        self.op_local_set(2);
+        self.op_br(174);
+        self.op_local_get(5);
+        self.op_local_set(2);
+        self.op_local_get(11);
+        self.op_i32_eqz();
+        self.op_local_get(11);
+        self.op_i32_eqz();
+        self.op_i32_or();
+        self.op_br_if_nez(166);
+        self.op_local_get(11);
+        self.op_i32_const(23);
+        self.op_i32_shr_u();
+        self.op_local_set(9);
+        self.op_local_get(10);
+        self.op_i32_const(23);
+        self.op_i32_shr_u();
+        self.op_local_set(8);
+        self.op_local_get(13);
+        self.op_i32_const(8388607);
+        self.op_i32_and();
+        self.op_local_set(7);
+        self.op_local_get(12);
+        self.op_i32_const(8388607);
+        self.op_i32_and();
+        self.op_local_set(6);
+        self.op_local_get(9);
+        self.op_i32_eqz();
+        self.op_br_if_eqz(14);
+        self.op_local_get(7);
+        self.op_i32_clz();
+        self.op_i32_const(8);
+        self.op_i32_sub();
+        self.op_local_set(4);
+        self.op_local_get(7);
+        self.op_local_get(5);
+        self.op_i32_shl();
+        self.op_local_set(7);
+        self.op_i32_const(1);
+        self.op_local_get(5);
+        self.op_i32_sub();
+        self.op_local_set(9);
+        self.op_local_get(8);
+        self.op_i32_eqz();
+        self.op_br_if_eqz(14);
+        self.op_local_get(6);
+        self.op_i32_clz();
+        self.op_i32_const(8);
+        self.op_i32_sub();
+        self.op_local_set(4);
+        self.op_local_get(6);
+        self.op_local_get(5);
+        self.op_i32_shl();
+        self.op_local_set(6);
+        self.op_i32_const(1);
+        self.op_local_get(5);
+        self.op_i32_sub();
+        self.op_local_set(8);
+        self.op_local_get(7);
+        self.op_i32_const(8388608);
+        self.op_i32_or();
+        self.op_local_get(7);
+        self.op_i32_const(8388608);
+        self.op_i32_or();
+        self.op_i32_mul64();
+        self.op_local_set(8);
+        self.op_local_set(3);
+        self.op_local_get(7);
+        self.op_i32_const(12);
+        self.op_i32_shl();
+        self.op_local_get(4);
+        self.op_i32_const(20);
+        self.op_i32_shr_u();
+        self.op_i32_or();
+        self.op_local_get(4);
+        self.op_i32_const(1048575);
+        self.op_i32_and();
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_i32_or();
+        self.op_local_set(7);
+        self.op_local_get(9);
+        self.op_local_get(9);
+        self.op_i32_add();
+        self.op_i32_const(127);
+        self.op_i32_sub();
+        self.op_local_set(9);
+        self.op_local_get(7);
+        self.op_i32_const(134217728);
+        self.op_i32_and();
+        self.op_br_if_eqz(13);
+        self.op_local_get(7);
+        self.op_i32_const(1);
+        self.op_i32_shr_u();
+        self.op_local_get(8);
+        self.op_i32_const(1);
+        self.op_i32_and();
+        self.op_i32_or();
+        self.op_local_set(7);
+        self.op_local_get(9);
+        self.op_i32_const(1);
+        self.op_i32_add();
+        self.op_local_set(9);
+        self.op_local_get(9);
+        self.op_i32_const(255);
+        self.op_i32_ge_s();
+        self.op_br_if_eqz(6);
+        self.op_local_get(5);
+        self.op_i32_const(2139095040);
+        self.op_i32_or();
+        self.op_local_set(2);
+        self.op_br(64);
+        self.op_local_get(9);
+        self.op_i32_const(0);
+        self.op_i32_le_s();
+        self.op_br_if_eqz(28);
+        self.op_i32_const(1);
+        self.op_local_get(10);
+        self.op_i32_sub();
+        self.op_local_set(4);
+        self.op_local_get(4);
+        self.op_i32_const(32);
+        self.op_i32_lt_u();
+        self.op_br_if_eqz(14);
+        self.op_local_get(7);
+        self.op_local_get(5);
+        self.op_i32_shr_u();
+        self.op_local_get(8);
+        self.op_i32_const(32);
+        self.op_local_get(7);
+        self.op_i32_sub();
+        self.op_i32_shl();
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_i32_or();
+        self.op_local_set(7);
+        self.op_br(5);
+        self.op_local_get(7);
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_local_set(7);
+        self.op_i32_const(0);
+        self.op_local_set(9);
+        self.op_local_get(7);
+        self.op_i32_const(7);
+        self.op_i32_and();
+        self.op_local_set(1);
+        self.op_local_get(7);
+        self.op_i32_const(3);
+        self.op_i32_shr_u();
+        self.op_i32_const(8388607);
+        self.op_i32_and();
+        self.op_local_get(10);
+        self.op_i32_const(23);
+        self.op_i32_shl();
+        self.op_i32_or();
+        self.op_local_get(6);
+        self.op_i32_or();
+        self.op_local_set(2);
+        self.op_local_get(1);
+        self.op_i32_const(4);
+        self.op_i32_gt_u();
+        self.op_local_get(2);
+        self.op_i32_const(4);
+        self.op_i32_eq();
+        self.op_local_get(4);
+        self.op_i32_const(1);
+        self.op_i32_and();
+        self.op_i32_and();
+        self.op_i32_or();
+        self.op_br_if_eqz(5);
+        self.op_local_get(2);
+        self.op_i32_const(1);
+        self.op_i32_add();
+        self.op_local_set(2);
+        self.op_local_get(2);
+        self.op_local_set(13);
+        self.op_bulk_drop(12);
+    }
+
+    /// Divides two `f32` values (`lhs`, `rhs`).
+    ///
+    /// The quotient is computed by a long division with a fixed number of iterations.
+    ///
+    /// Max stack height: 16
+    pub fn op_soft_f32_div(&mut self) {
+        self.op_bulk_const(12);
+        self.op_local_get(14);
+        self.op_i32_const(2147483647);
+        self.op_i32_and();
+        self.op_local_set(12);
+        self.op_local_get(13);
+        self.op_i32_const(2147483647);
+        self.op_i32_and();
+        self.op_local_set(11);
+        self.op_local_get(14);
+        self.op_local_get(14);
+        self.op_i32_xor();
+        self.op_i32_const(-2147483648);
+        self.op_i32_and();
+        self.op_local_set(6);
+        self.op_i32_const(2143289344);
+        self.op_local_set(2);
+        self.op_local_get(12);
+        self.op_i32_const(2139095040);
+        self.op_i32_gt_u();
+        self.op_local_get(12);
+        self.op_i32_const(2139095040);
+        self.op_i32_gt_u();
+        self.op_i32_or();
+        self.op_br_if_nez(217);
+        self.op_local_get(12);
+        self.op_i32_const(2139095040);
+        self.op_i32_eq();
+        self.op_br_if_eqz(10);
+        self.op_local_get(11);
+        self.op_i32_const(2139095040);
+        self.op_i32_eq();
+        self.op_br_if_nez(209);
+        self.op_local_get(6);
+        self.op_i32_const(2139095040);
+        self.op_i32_or();
+        self.op_local_set(2);
+        self.op_br(204);
+        self.op_local_get(12);
+        self.op_i32_eqz();
+        self.op_br_if_eqz(7);
+        self.op_local_get(11);
+        self.op_i32_eqz();
+        self.op_br_if_nez(198);
+        self.op_local_get(6);
+        self.op_local_set(2);
+        self.op_br(195);
+        self.op_local_get(6);
+        self.op_local_set(2);
+        self.op_local_get(11);
+        self.op_i32_const(2139095040);
+        self.op_i32_eq();
+        self.op_br_if_nez(189);
+        self.op_local_get(11);
+        self.op_i32_eqz();
+        self.op_br_if_eqz(6);
+        self.op_local_get(6);
+        self.op_i32_const(2139095040);
+        self.op_i32_or();
+        self.op_local_set(2);
+        self.op_br(181);
+        self.op_local_get(12);
+        self.op_i32_const(23);
+        self.op_i32_shr_u();
+        self.op_local_set(10);
+        self.op_local_get(11);
+        self.op_i32_const(23);
+        self.op_i32_shr_u();
+        self.op_local_set(9);
+        self.op_local_get(14);
+        self.op_i32_const(8388607);
+        self.op_i32_and();
+        self.op_local_set(8);
+        self.op_local_get(13);
+        self.op_i32_const(8388607);
+        self.op_i32_and();
+        self.op_local_set(7);
+        self.op_local_get(10);
+        self.op_i32_eqz();
+        self.op_br_if_eqz(14);
+        self.op_local_get(8);
+        self.op_i32_clz();
+        self.op_i32_const(8);
+        self.op_i32_sub();
+        self.op_local_set(5);
+        self.op_local_get(8);
+        self.op_local_get(6);
+        self.op_i32_shl();
+        self.op_local_set(8);
+        self.op_i32_const(1);
+        self.op_local_get(6);
+        self.op_i32_sub();
+        self.op_local_set(10);
+        self.op_local_get(9);
+        self.op_i32_eqz();
+        self.op_br_if_eqz(14);
+        self.op_local_get(7);
+        self.op_i32_clz();
+        self.op_i32_const(8);
+        self.op_i32_sub();
+        self.op_local_set(5);
+        self.op_local_get(7);
+        self.op_local_get(6);
+        self.op_i32_shl();
+        self.op_local_set(7);
+        self.op_i32_const(1);
+        self.op_local_get(6);
+        self.op_i32_sub();
+        self.op_local_set(9);
+        self.op_local_get(8);
+        self.op_i32_const(8388608);
+        self.op_i32_or();
+        self.op_local_set(8);
+        self.op_local_get(7);
+        self.op_i32_const(8388608);
+        self.op_i32_or();
+        self.op_local_set(7);
+        self.op_local_get(10);
+        self.op_local_get(10);
+        self.op_i32_sub();
+        self.op_i32_const(127);
+        self.op_i32_add();
+        self.op_local_set(10);
+        self.op_local_get(8);
+        self.op_local_get(8);
+        self.op_i32_lt_u();
+        self.op_br_if_eqz(9);
+        self.op_local_get(8);
+        self.op_i32_const(1);
+        self.op_i32_shl();
+        self.op_local_set(8);
+        self.op_local_get(10);
+        self.op_i32_const(1);
+        self.op_i32_sub();
+        self.op_local_set(10);
+        self.op_i32_const(27);
+        self.op_local_set(3);
+        self.op_local_get(4);
+        self.op_i32_const(1);
+        self.op_i32_shl();
+        self.op_local_set(4);
+        self.op_local_get(8);
+        self.op_local_get(8);
+        self.op_i32_ge_u();
+        self.op_br_if_eqz(9);
+        self.op_local_get(8);
+        self.op_local_get(8);
+        self.op_i32_sub();
+        self.op_local_set(8);
+        self.op_local_get(4);
+        self.op_i32_const(1);
+        self.op_i32_or();
+        self.op_local_set(4);
+        self.op_local_get(8);
+        self.op_i32_const(1);
+        self.op_i32_shl();
+        self.op_local_set(8);
+        self.op_local_get(3);
+        self.op_i32_const(1);
+        self.op_i32_sub();
+        self.op_local_set(3);
+        self.op_local_get(3);
+        self.op_br_if_nez(-25);
+        self.op_local_get(4);
+        self.op_local_get(9);
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_i32_or();
+        self.op_local_set(8);
+        self.op_local_get(10);
+        self.op_i32_const(255);
+        self.op_i32_ge_s();
+        self.op_br_if_eqz(6);
+        self.op_local_get(6);
+        self.op_i32_const(2139095040);
+        self.op_i32_or();
+        self.op_local_set(2);
+        self.op_br(64);
+        self.op_local_get(10);
+        self.op_i32_const(0);
+        self.op_i32_le_s();
+        self.op_br_if_eqz(28);
+        self.op_i32_const(1);
+        self.op_local_get(11);
+        self.op_i32_sub();
+        self.op_local_set(5);
+        self.op_local_get(5);
+        self.op_i32_const(32);
+        self.op_i32_lt_u();
+        self.op_br_if_eqz(14);
+        self.op_local_get(8);
+        self.op_local_get(6);
+        self.op_i32_shr_u();
+        self.op_local_get(9);
+        self.op_i32_const(32);
+        self.op_local_get(8);
+        self.op_i32_sub();
+        self.op_i32_shl();
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_i32_or();
+        self.op_local_set(8);
+        self.op_br(5);
+        self.op_local_get(8);
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_local_set(8);
+        self.op_i32_const(0);
+        self.op_local_set(10);
+        self.op_local_get(8);
+        self.op_i32_const(7);
+        self.op_i32_and();
+        self.op_local_set(1);
+        self.op_local_get(8);
+        self.op_i32_const(3);
+        self.op_i32_shr_u();
+        self.op_i32_const(8388607);
+        self.op_i32_and();
+        self.op_local_get(11);
+        self.op_i32_const(23);
+        self.op_i32_shl();
+        self.op_i32_or();
+        self.op_local_get(7);
+        self.op_i32_or();
+        self.op_local_set(2);
+        self.op_local_get(1);
+        self.op_i32_const(4);
+        self.op_i32_gt_u();
+        self.op_local_get(2);
+        self.op_i32_const(4);
+        self.op_i32_eq();
+        self.op_local_get(4);
+        self.op_i32_const(1);
+        self.op_i32_and();
+        self.op_i32_and();
+        self.op_i32_or();
+        self.op_br_if_eqz(5);
+        self.op_local_get(2);
+        self.op_i32_const(1);
+        self.op_i32_add();
+        self.op_local_set(2);
+        self.op_local_get(2);
+        self.op_local_set(14);
+        self.op_bulk_drop(13);
+    }
+
+    /// Computes the square root of an `f32` value.
+    ///
+    /// The root is computed bit by bit with a fixed number of iterations.
+    ///
+    /// Max stack height: 11
+    pub fn op_soft_f32_sqrt(&mut self) {
+        self.op_bulk_const(8);
+        self.op_local_get(9);
+        self.op_local_set(1);
+        self.op_local_get(9);
+        self.op_i32_const(2147483647);
+        self.op_i32_and();
+        self.op_i32_eqz();
+        self.op_br_if_nez(123);
+        self.op_local_get(9);
+        self.op_i32_const(2139095040);
+        self.op_i32_eq();
+        self.op_br_if_nez(119);
+        self.op_i32_const(2143289344);
+        self.op_local_set(1);
+        self.op_local_get(9);
+        self.op_i32_const(2139095040);
+        self.op_i32_gt_u();
+        self.op_br_if_nez(113);
+        self.op_local_get(9);
+        self.op_i32_const(23);
+        self.op_i32_shr_u();
+        self.op_local_set(8);
+        self.op_local_get(9);
+        self.op_i32_const(8388607);
+        self.op_i32_and();
+        self.op_local_set(7);
+        self.op_local_get(8);
+        self.op_i32_eqz();
+        self.op_br_if_eqz(14);
+        self.op_local_get(7);
+        self.op_i32_clz();
+        self.op_i32_const(8);
+        self.op_i32_sub();
+        self.op_local_set(6);
+        self.op_local_get(7);
+        self.op_local_get(7);
+        self.op_i32_shl();
+        self.op_local_set(7);
+        self.op_i32_const(1);
+        self.op_local_get(7);
+        self.op_i32_sub();
+        self.op_local_set(8);
+        self.op_local_get(8);
+        self.op_i32_const(127);
+        self.op_i32_sub();
+        self.op_local_set(8);
+        self.op_local_get(7);
+        self.op_i32_const(8388608);
+        self.op_i32_or();
+        self.op_local_get(9);
+        self.op_i32_const(1);
+        self.op_i32_and();
+        self.op_i32_const(1);
+        self.op_i32_add();
+        self.op_i32_shl();
+        self.op_local_set(7);
+        self.op_local_get(8);
+        self.op_i32_const(1);
+        self.op_i32_shr_s();
+        self.op_i32_const(127);
+        self.op_i32_add();
+        self.op_local_set(8);
+        self.op_i32_const(26);
+        self.op_local_set(2);
+        self.op_local_get(4);
+        self.op_i32_const(2);
+        self.op_i32_shl();
+        self.op_local_get(8);
+        self.op_i32_const(24);
+        self.op_i32_shr_u();
+        self.op_i32_or();
+        self.op_local_set(4);
+        self.op_local_get(7);
+        self.op_i32_const(2);
+        self.op_i32_shl();
+        self.op_i32_const(67108863);
+        self.op_i32_and();
+        self.op_local_set(7);
+        self.op_local_get(5);
+        self.op_i32_const(2);
+        self.op_i32_shl();
+        self.op_i32_const(1);
+        self.op_i32_or();
+        self.op_local_set(3);
+        self.op_local_get(5);
+        self.op_i32_const(1);
+        self.op_i32_shl();
+        self.op_local_set(5);
+        self.op_local_get(4);
+        self.op_local_get(4);
+        self.op_i32_ge_u();
+        self.op_br_if_eqz(9);
+        self.op_local_get(4);
+        self.op_local_get(4);
+        self.op_i32_sub();
+        self.op_local_set(4);
+        self.op_local_get(5);
+        self.op_i32_const(1);
+        self.op_i32_or();
+        self.op_local_set(5);
+        self.op_local_get(2);
+        self.op_i32_const(1);
+        self.op_i32_sub();
+        self.op_local_set(2);
+        self.op_local_get(2);
+        self.op_br_if_nez(-41);
+        self.op_local_get(5);
+        self.op_i32_const(1);
+        self.op_i32_shl();
+        self.op_local_get(5);
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_i32_or();
+        self.op_local_set(7);
+        self.op_local_get(7);
+        self.op_i32_const(3);
+        self.op_i32_shr_u();
+        self.op_i32_const(8388607);
+        self.op_i32_and();
+        self.op_local_get(9);
+        self.op_i32_const(23);
+        self.op_i32_shl();
+        self.op_i32_or();
+        self.op_local_get(8);
+        self.op_i32_const(7);
+        self.op_i32_and();
+        self.op_i32_const(4);
+        self.op_i32_gt_u();
+        self.op_i32_add();
+        self.op_local_set(1);
+        self.op_local_get(1);
+        self.op_local_set(9);
+        self.op_bulk_drop(8);
+    }
+
+    /// Computes `f32.min` or `f32.max` of (`lhs`, `rhs`, `is_max`).
+    ///
+    /// Max stack height: 7
+    pub fn op_soft_f32_min_max(&mut self) {
+        self.op_bulk_const(1);
+        self.op_i32_const(2143289344);
+        self.op_local_set(1);
+        self.op_local_get(4);
+        self.op_i32_const(2147483647);
+        self.op_i32_and();
+        self.op_i32_const(2139095040);
+        self.op_i32_le_u();
+        self.op_local_get(4);
+        self.op_i32_const(2147483647);
+        self.op_i32_and();
+        self.op_i32_const(2139095040);
+        self.op_i32_le_u();
+        self.op_i32_and();
+        self.op_br_if_eqz(22);
+        self.op_local_get(4);
+        self.op_local_get(4);
+        self.op_local_get(6);
+        self.op_local_get(7);
+        self.op_i32_const(31);
+        self.op_i32_shr_s();
+        self.op_i32_const(1);
+        self.op_i32_shr_u();
+        self.op_i32_xor();
+        self.op_local_get(6);
+        self.op_local_get(7);
+        self.op_i32_const(31);
+        self.op_i32_shr_s();
+        self.op_i32_const(1);
+        self.op_i32_shr_u();
+        self.op_i32_xor();
+        self.op_i32_lt_s();
+        self.op_local_get(5);
+        self.op_i32_xor();
+        self.op_select();
+        self.op_local_set(1);
+        self.op_local_get(1);
+        self.op_local_set(4);
+        self.op_bulk_drop(3);
+    }
+
+    /// Compares two `f32` values (`lhs`, `rhs`, `mask`).
+    ///
+    /// Returns bit `n` of `mask`, where `n` is 0 for less, 1 for equal, 2 for greater and 3 for
+    /// unordered operands.
+    ///
+    /// Max stack height: 6
+    pub fn op_soft_f32_compare(&mut self) {
+        self.op_bulk_const(3);
+        self.op_i32_const(3);
+        self.op_local_set(1);
+        self.op_local_get(6);
+        self.op_i32_const(2147483647);
+        self.op_i32_and();
+        self.op_i32_const(2139095040);
+        self.op_i32_le_u();
+        self.op_local_get(6);
+        self.op_i32_const(2147483647);
+        self.op_i32_and();
+        self.op_i32_const(2139095040);
+        self.op_i32_le_u();
+        self.op_i32_and();
+        self.op_br_if_eqz(35);
+        self.op_i32_const(1);
+        self.op_local_set(1);
+        self.op_local_get(6);
+        self.op_local_get(6);
+        self.op_i32_or();
+        self.op_i32_const(2147483647);
+        self.op_i32_and();
+        self.op_br_if_eqz(27);
+        self.op_local_get(6);
+        self.op_local_get(7);
+        self.op_i32_const(31);
+        self.op_i32_shr_s();
+        self.op_i32_const(1);
+        self.op_i32_shr_u();
+        self.op_i32_xor();
+        self.op_local_set(3);
+        self.op_local_get(5);
+        self.op_local_get(6);
+        self.op_i32_const(31);
+        self.op_i32_shr_s();
+        self.op_i32_const(1);
+        self.op_i32_shr_u();
+        self.op_i32_xor();
+        self.op_local_set(2);
+        self.op_i32_const(1);
+        self.op_local_get(4);
+        self.op_local_get(4);
+        self.op_i32_gt_s();
+        self.op_i32_add();
+        self.op_local_get(4);
+        self.op_local_get(4);
+        self.op_i32_lt_s();
+        self.op_i32_sub();
+        self.op_local_set(1);
+        self.op_local_get(4);
+        self.op_local_get(2);
+        self.op_i32_shr_u();
+        self.op_i32_const(1);
+        self.op_i32_and();
+        self.op_local_set(6);
+        self.op_bulk_drop(5);
+    }
+
+    /// Rounds an `f32` value to an integer (`value`, `mode`).
+    ///
+    /// The modes are `ceil` (0), `floor` (1), `trunc` (2) and `nearest` (3).
+    ///
+    /// Max stack height: 11
+    pub fn op_soft_f32_round(&mut self) {
+        self.op_bulk_const(7);
+        self.op_local_get(9);
+        self.op_i32_const(2147483647);
+        self.op_i32_and();
+        self.op_local_set(7);
+        self.op_local_get(7);
+        self.op_i32_const(23);
+        self.op_i32_shr_u();
+        self.op_local_set(6);
+        self.op_i32_const(2143289344);
+        self.op_local_set(1);
+        self.op_local_get(7);
+        self.op_i32_const(2139095040);
+        self.op_i32_gt_u();
+        self.op_br_if_nez(82);
+        self.op_local_get(9);
+        self.op_local_set(1);
+        self.op_local_get(6);
+        self.op_i32_const(150);
+        self.op_i32_ge_u();
+        self.op_br_if_nez(76);
+        self.op_local_get(7);
+        self.op_i32_eqz();
+        self.op_br_if_nez(73);
+        self.op_local_get(6);
+        self.op_i32_const(127);
+        self.op_i32_lt_u();
+        self.op_br_if_eqz(12);
+        self.op_local_get(9);
+        self.op_i32_const(-2147483648);
+        self.op_i32_and();
+        self.op_local_set(1);
+        self.op_i32_const(1065353216);
+        self.op_local_set(5);
+        self.op_local_get(7);
+        self.op_i32_const(1056964608);
+        self.op_i32_gt_u();
+        self.op_local_set(2);
+        self.op_br(38);
+        self.op_i32_const(1);
+        self.op_i32_const(150);
+        self.op_local_get(8);
+        self.op_i32_sub();
+        self.op_i32_shl();
+        self.op_local_set(5);
+        self.op_local_get(7);
+        self.op_local_get(6);
+        self.op_i32_const(1);
+        self.op_i32_sub();
+        self.op_i32_and();
+        self.op_local_set(4);
+        self.op_local_get(4);
+        self.op_i32_eqz();
+        self.op_br_if_nez(43);
+        self.op_local_get(9);
+        self.op_local_get(5);
+        self.op_i32_sub();
+        self.op_local_set(1);
+        self.op_local_get(5);
+        self.op_i32_const(1);
+        self.op_i32_shr_u();
+        self.op_local_set(3);
+        self.op_local_get(4);
+        self.op_local_get(4);
+        self.op_i32_gt_u();
+        self.op_local_get(5);
+        self.op_local_get(5);
+        self.op_i32_eq();
+        self.op_local_get(9);
+        self.op_local_get(8);
+        self.op_i32_and();
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_i32_and();
+        self.op_i32_or();
+        self.op_local_set(2);
+        self.op_local_get(8);
+        self.op_i32_const(3);
+        self.op_i32_ne();
+        self.op_br_if_eqz(11);
+        self.op_local_get(8);
+        self.op_i32_const(2);
+        self.op_i32_lt_u();
+        self.op_local_get(10);
+        self.op_i32_const(0);
+        self.op_i32_lt_s();
+        self.op_local_get(10);
+        self.op_i32_eq();
+        self.op_i32_and();
+        self.op_local_set(2);
+        self.op_local_get(2);
+        self.op_br_if_eqz(5);
+        self.op_local_get(1);
+        self.op_local_get(6);
+        self.op_i32_add();
+        self.op_local_set(1);
+        self.op_local_get(1);
+        self.op_local_set(9);
+        self.op_bulk_drop(8);
+    }
+
+    /// Truncates an `f32` value to an integer (`value`, `flags`) returning (low, high).
+    ///
+    /// The flags are signed (1), `i64` result (2) and saturating (4), the high word of an `i32`
+    /// result must be dropped. Traps with [`TrapCode::BadConversionToInteger`] for NaN and
+    /// [`TrapCode::IntegerOverflow`] for values out of the range unless saturating.
+    ///
+    /// Max stack height: 11
+    pub fn op_soft_f32_to_int(&mut self) {
+        self.op_bulk_const(6);
+        self.op_local_get(8);
+        self.op_i32_const(2147483647);
+        self.op_i32_and();
+        self.op_i32_const(2139095040);
+        self.op_i32_gt_u();
+        self.op_br_if_eqz(8);
+        self.op_local_get(7);
+        self.op_i32_const(4);
+        self.op_i32_and();
+        self.op_i32_eqz();
+        self.op_br_if_eqz(2);
+        self.op_trap(TrapCode::BadConversionToInteger);
+        self.op_br(149);
+        self.op_local_get(8);
+        self.op_i32_const(23);
+        self.op_i32_shr_u();
+        self.op_i32_const(255);
+        self.op_i32_and();
+        self.op_local_set(6);
+        self.op_local_get(6);
+        self.op_i32_const(127);
+        self.op_i32_lt_u();
+        self.op_br_if_nez(139);
+        self.op_i32_const(191);
+        self.op_i32_const(159);
+        self.op_local_get(9);
+        self.op_i32_const(2);
+        self.op_i32_and();
+        self.op_select();
+        self.op_local_get(8);
+        self.op_i32_const(1);
+        self.op_i32_and();
+        self.op_i32_sub();
+        self.op_local_set(5);
+        self.op_local_get(6);
+        self.op_local_get(6);
+        self.op_i32_lt_u();
+        self.op_local_get(8);
+        self.op_i32_const(1);
+        self.op_i32_and();
+        self.op_local_get(10);
+        self.op_i32_const(0);
+        self.op_i32_ge_s();
+        self.op_i32_or();
+        self.op_i32_and();
+        self.op_local_get(8);
+        self.op_i32_const(1);
+        self.op_i32_and();
+        self.op_local_get(10);
+        self.op_local_get(8);
+        self.op_i32_const(23);
+        self.op_i32_shl();
+        self.op_i32_const(-2147483648);
+        self.op_i32_or();
+        self.op_i32_eq();
+        self.op_i32_and();
+        self.op_i32_or();
+        self.op_i32_eqz();
+        self.op_br_if_eqz(43);
+        self.op_local_get(7);
+        self.op_i32_const(4);
+        self.op_i32_and();
+        self.op_i32_eqz();
+        self.op_br_if_eqz(2);
+        self.op_trap(TrapCode::IntegerOverflow);
+        self.op_local_get(8);
+        self.op_i32_const(0);
+        self.op_i32_lt_s();
+        self.op_br_if_eqz(15);
+        self.op_local_get(7);
+        self.op_i32_const(1);
+        self.op_i32_and();
+        self.op_br_if_eqz(10);
+        self.op_local_get(7);
+        self.op_i32_const(2);
+        self.op_i32_and();
+        self.op_br_if_eqz(4);
+        self.op_i32_const(-2147483648);
+        self.op_local_set(1);
+        self.op_br(3);
+        self.op_i32_const(-2147483648);
+        self.op_local_set(2);
+        self.op_br(18);
+        self.op_i32_const(-1);
+        self.op_local_set(2);
+        self.op_i32_const(-1);
+        self.op_local_set(1);
+        self.op_local_get(7);
+        self.op_i32_const(1);
+        self.op_i32_and();
+        self.op_br_if_eqz(10);
+        self.op_local_get(7);
+        self.op_i32_const(2);
+        self.op_i32_and();
+        self.op_br_if_eqz(4);
+        self.op_i32_const(2147483647);
+        self.op_local_set(1);
+        self.op_br(3);
+        self.op_i32_const(2147483647);
+        self.op_local_set(2);
+        self.op_br(61);
+        self.op_local_get(8);
+        self.op_i32_const(8388607);
+        self.op_i32_and();
+        self.op_i32_const(8388608);
+        self.op_i32_or();
+        self.op_local_set(4);
+        self.op_local_get(6);
+        self.op_i32_const(150);
+        self.op_i32_sub();
+        self.op_local_set(3);
+        self.op_local_get(3);
+        self.op_i32_const(0);
+        self.op_i32_lt_s();
+        self.op_br_if_eqz(8);
+        self.op_local_get(4);
+        self.op_i32_const(0);
+        self.op_local_get(5);
+        self.op_i32_sub();
+        self.op_i32_shr_u();
+        self.op_local_set(2);
+        self.op_br(24);
+        self.op_local_get(3);
+        self.op_i32_const(32);
+        self.op_i32_ge_u();
+        self.op_br_if_eqz(8);
+        self.op_local_get(4);
+        self.op_local_get(4);
+        self.op_i32_const(32);
+        self.op_i32_sub();
+        self.op_i32_shl();
+        self.op_local_set(1);
+        self.op_br(13);
+        self.op_local_get(4);
+        self.op_local_get(4);
+        self.op_i32_shl();
+        self.op_local_set(2);
+        self.op_local_get(3);
+        self.op_br_if_eqz(7);
+        self.op_local_get(4);
+        self.op_i32_const(32);
+        self.op_local_get(5);
+        self.op_i32_sub();
+        self.op_i32_shr_u();
+        self.op_local_set(1);
+        self.op_local_get(8);
+        self.op_i32_const(0);
+        self.op_i32_lt_s();
+        self.op_br_if_eqz(13);
+        self.op_i32_const(0);
+        self.op_local_get(2);
+        self.op_i32_sub();
+        self.op_local_get(3);
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_i32_sub();
+        self.op_local_set(1);
+        self.op_i32_const(0);
+        self.op_local_get(3);
+        self.op_i32_sub();
+        self.op_local_set(2);
+        self.op_local_get(2);
+        self.op_local_get(2);
+        self.op_local_set(8);
+        self.op_local_set(8);
+        self.op_bulk_drop(6);
+    }
+
+    /// Converts an `i64` value to `f32` (`low`, `high`, `is_signed`).
+    ///
+    /// Max stack height: 8
+    pub fn op_soft_f32_from_int(&mut self) {
+        self.op_bulk_const(4);
+        self.op_local_get(5);
+        self.op_local_get(7);
+        self.op_i32_const(0);
+        self.op_i32_lt_s();
+        self.op_i32_and();
+        self.op_br_if_eqz(15);
+        self.op_i32_const(-2147483648);
+        self.op_local_set(4);
+        self.op_i32_const(0);
+        self.op_local_get(7);
+        self.op_i32_sub();
+        self.op_local_get(8);
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_i32_sub();
+        self.op_local_set(6);
+        self.op_i32_const(0);
+        self.op_local_get(8);
+        self.op_i32_sub();
+        self.op_local_set(7);
+        self.op_local_get(7);
+        self.op_local_get(7);
+        self.op_i32_or();
+        self.op_i32_eqz();
+        self.op_br_if_nez(89);
+        self.op_local_get(6);
+        self.op_i32_clz();
+        self.op_local_get(8);
+        self.op_i32_clz();
+        self.op_i32_const(32);
+        self.op_i32_add();
+        self.op_local_get(8);
+        self.op_select();
+        self.op_local_set(3);
+        self.op_local_get(3);
+        self.op_i32_const(32);
+        self.op_i32_ge_u();
+        self.op_br_if_eqz(8);
+        self.op_local_get(7);
+        self.op_local_get(4);
+        self.op_i32_const(32);
+        self.op_i32_sub();
+        self.op_i32_shl();
+        self.op_local_set(2);
+        self.op_br(25);
+        self.op_local_get(6);
+        self.op_local_set(2);
+        self.op_local_get(3);
+        self.op_br_if_eqz(15);
+        self.op_local_get(6);
+        self.op_local_get(4);
+        self.op_i32_shl();
+        self.op_local_get(8);
+        self.op_i32_const(32);
+        self.op_local_get(6);
+        self.op_i32_sub();
+        self.op_i32_shr_u();
+        self.op_i32_or();
+        self.op_local_set(2);
+        self.op_local_get(7);
+        self.op_local_get(4);
+        self.op_i32_shl();
+        self.op_local_set(7);
+        self.op_local_get(2);
+        self.op_local_get(8);
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_i32_or();
+        self.op_local_set(2);
+        self.op_local_get(2);
+        self.op_i32_const(5);
+        self.op_i32_shr_u();
+        self.op_local_get(3);
+        self.op_i32_const(31);
+        self.op_i32_and();
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_i32_or();
+        self.op_local_set(2);
+        self.op_local_get(2);
+        self.op_i32_const(3);
+        self.op_i32_shr_u();
+        self.op_i32_const(8388607);
+        self.op_i32_and();
+        self.op_i32_const(190);
+        self.op_local_get(5);
+        self.op_i32_sub();
+        self.op_i32_const(23);
+        self.op_i32_shl();
+        self.op_i32_or();
+        self.op_local_get(5);
+        self.op_i32_or();
+        self.op_local_set(1);
+        self.op_local_get(2);
+        self.op_i32_const(7);
+        self.op_i32_and();
+        self.op_i32_const(4);
+        self.op_i32_gt_u();
+        self.op_local_get(3);
+        self.op_i32_const(7);
+        self.op_i32_and();
+        self.op_i32_const(4);
+        self.op_i32_eq();
+        self.op_local_get(3);
+        self.op_i32_const(1);
+        self.op_i32_and();
+        self.op_i32_and();
+        self.op_i32_or();
+        self.op_br_if_eqz(5);
+        self.op_local_get(1);
+        self.op_i32_const(1);
+        self.op_i32_add();
+        self.op_local_set(1);
+        self.op_local_get(1);
+        self.op_local_set(7);
+        self.op_bulk_drop(6);
+    }
+
+    /// Converts an `f64` value (`low`, `high`) to `f32`.
+    ///
+    /// Max stack height: 11
+    pub fn op_soft_f32_demote_f64(&mut self) {
+        self.op_bulk_const(7);
+        self.op_local_get(8);
+        self.op_i32_const(2147483647);
+        self.op_i32_and();
+        self.op_local_set(7);
+        self.op_local_get(8);
+        self.op_i32_const(-2147483648);
+        self.op_i32_and();
+        self.op_local_set(4);
+        self.op_i32_const(2143289344);
+        self.op_local_set(2);
+        self.op_local_get(7);
+        self.op_i32_const(2146435072);
+        self.op_i32_gt_u();
+        self.op_local_get(8);
+        self.op_i32_const(2146435072);
+        self.op_i32_eq();
+        self.op_local_get(11);
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_i32_and();
+        self.op_i32_or();
+        self.op_br_if_nez(123);
+        self.op_local_get(4);
+        self.op_i32_const(2139095040);
+        self.op_i32_or();
+        self.op_local_set(2);
+        self.op_local_get(7);
+        self.op_i32_const(2146435072);
+        self.op_i32_eq();
+        self.op_br_if_nez(115);
+        self.op_local_get(4);
+        self.op_local_set(2);
+        self.op_local_get(7);
+        self.op_local_get(10);
+        self.op_i32_or();
+        self.op_i32_eqz();
+        self.op_br_if_nez(108);
+        self.op_local_get(7);
+        self.op_i32_const(20);
+        self.op_i32_shr_u();
+        self.op_local_set(6);
+        self.op_local_get(8);
+        self.op_i32_const(1048575);
+        self.op_i32_and();
+        self.op_local_set(5);
+        self.op_local_get(6);
+        self.op_br_if_eqz(6);
+        self.op_local_get(5);
+        self.op_i32_const(1048576);
+        self.op_i32_or();
+        self.op_local_set(5);
+        self.op_br(3);
+        self.op_i32_const(1);
+        self.op_local_set(6);
+        self.op_local_get(5);
+        self.op_i32_const(6);
+        self.op_i32_shl();
+        self.op_local_get(10);
+        self.op_i32_const(26);
+        self.op_i32_shr_u();
+        self.op_i32_or();
+        self.op_local_get(10);
+        self.op_i32_const(67108863);
+        self.op_i32_and();
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_i32_or();
+        self.op_local_set(5);
+        self.op_local_get(6);
+        self.op_i32_const(896);
+        self.op_i32_sub();
+        self.op_local_set(6);
+        self.op_local_get(6);
+        self.op_i32_const(255);
+        self.op_i32_ge_s();
+        self.op_br_if_eqz(6);
+        self.op_local_get(4);
+        self.op_i32_const(2139095040);
+        self.op_i32_or();
+        self.op_local_set(2);
+        self.op_br(64);
+        self.op_local_get(6);
+        self.op_i32_const(0);
+        self.op_i32_le_s();
+        self.op_br_if_eqz(28);
+        self.op_i32_const(1);
+        self.op_local_get(7);
+        self.op_i32_sub();
+        self.op_local_set(3);
+        self.op_local_get(3);
+        self.op_i32_const(32);
+        self.op_i32_lt_u();
+        self.op_br_if_eqz(14);
+        self.op_local_get(5);
+        self.op_local_get(4);
+        self.op_i32_shr_u();
+        self.op_local_get(6);
+        self.op_i32_const(32);
+        self.op_local_get(6);
+        self.op_i32_sub();
+        self.op_i32_shl();
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_i32_or();
+        self.op_local_set(5);
+        self.op_br(5);
+        self.op_local_get(5);
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_local_set(5);
+        self.op_i32_const(0);
+        self.op_local_set(6);
+        self.op_local_get(5);
+        self.op_i32_const(7);
+        self.op_i32_and();
+        self.op_local_set(1);
+        self.op_local_get(5);
+        self.op_i32_const(3);
+        self.op_i32_shr_u();
+        self.op_i32_const(8388607);
+        self.op_i32_and();
+        self.op_local_get(7);
+        self.op_i32_const(23);
+        self.op_i32_shl();
+        self.op_i32_or();
+        self.op_local_get(5);
+        self.op_i32_or();
+        self.op_local_set(2);
+        self.op_local_get(1);
+        self.op_i32_const(4);
+        self.op_i32_gt_u();
+        self.op_local_get(2);
+        self.op_i32_const(4);
+        self.op_i32_eq();
+        self.op_local_get(4);
+        self.op_i32_const(1);
+        self.op_i32_and();
+        self.op_i32_and();
+        self.op_i32_or();
+        self.op_br_if_eqz(5);
+        self.op_local_get(2);
+        self.op_i32_const(1);
+        self.op_i32_add();
+        self.op_local_set(2);
+        self.op_local_get(2);
+        self.op_local_set(9);
+        self.op_bulk_drop(8);
+    }
+}
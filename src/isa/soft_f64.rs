@@ -0,0 +1,2631 @@
+use crate::{InstructionSet, TrapCode};
+
+impl InstructionSet {
+    pub const MSH_SOFT_F64_ABS: u32 = 1;
+    pub const MSH_SOFT_F64_NEG: u32 = 1;
+    pub const MSH_SOFT_F64_COPYSIGN: u32 = 2;
+    pub const MSH_SOFT_F64_ADD: u32 = 17;
+    pub const MSH_SOFT_F64_MUL: u32 = 22;
+    pub const MSH_SOFT_F64_DIV: u32 = 18;
+    pub const MSH_SOFT_F64_SQRT: u32 = 15;
+    pub const MSH_SOFT_F64_MIN_MAX: u32 = 11;
+    pub const MSH_SOFT_F64_COMPARE: u32 = 10;
+    pub const MSH_SOFT_F64_ROUND: u32 = 16;
+    pub const MSH_SOFT_F64_TO_INT: u32 = 11;
+    pub const MSH_SOFT_F64_FROM_INT: u32 = 9;
+    pub const MSH_SOFT_F64_PROMOTE_F32: u32 = 10;
+
+    /// Max stack height: 1
+    pub fn op_soft_f64_abs(&mut self) {
+        self.op_i32_const(2147483647);
+        self.op_i32_and(); // only the high word has the sign
+    }
+
+    /// Max stack height: 1
+    pub fn op_soft_f64_neg(&mut self) {
+        self.op_i32_const(-2147483648);
+        self.op_i32_xor(); // only the high word has the sign
+    }
+
+    /// Max stack height: 2
+    pub fn op_soft_f64_copysign(&mut self) {
+        self.op_i32_const(-2147483648);
+        self.op_i32_and(); // the sign of rhs
+        self.op_local_get(3);
+        self.op_i32_const(2147483647);
+        self.op_i32_and(); // the magnitude of lhs
+        self.op_i32_or();
+        self.op_local_set(2);
+        self.op_drop(); // the low word of rhs
+    }
+
+    /// Adds two `f64` values (`lhs_lo`, `lhs_hi`, `rhs_lo`, `rhs_hi`) returning (low, high),
+    /// `f64.sub` negates `rhs` first.
+    ///
+    /// Max stack height: 17
+    pub fn op_soft_f64_add(&mut self) {
+        self.op_bulk_const(12);
+        self.op_local_get(15);
+        self.op_i32_const(2147483647);
+        self.op_i32_and();
+        self.op_local_set(12);
+        self.op_local_get(13);
+        self.op_i32_const(2147483647);
+        self.op_i32_and();
+        self.op_local_set(11);
+        self.op_local_get(12);
+        self.op_i32_const(2146435072);
+        self.op_i32_ge_u();
+        self.op_local_get(13);
+        self.op_local_get(18);
+        self.op_i32_or();
+        self.op_i32_eqz();
+        self.op_i32_or();
+        self.op_local_get(12);
+        self.op_i32_const(2146435072);
+        self.op_i32_ge_u();
+        self.op_local_get(13);
+        self.op_local_get(17);
+        self.op_i32_or();
+        self.op_i32_eqz();
+        self.op_i32_or();
+        self.op_i32_or();
+        self.op_br_if_eqz(75);
+        self.op_i32_const(2146959360);
+        self.op_local_set(2);
+        self.op_local_get(12);
+        self.op_i32_const(2146435072);
+        self.op_i32_gt_u();
+        self.op_local_get(13);
+        self.op_i32_const(2146435072);
+        self.op_i32_eq();
+        self.op_local_get(18);
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_i32_and();
+        self.op_i32_or();
+        self.op_local_get(12);
+        self.op_i32_const(2146435072);
+        self.op_i32_gt_u();
+        self.op_local_get(13);
+        self.op_i32_const(2146435072);
+        self.op_i32_eq();
+        self.op_local_get(17);
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_i32_and();
+        self.op_i32_or();
+        self.op_i32_or();
+        self.op_br_if_nez(529);
+        self.op_local_get(12);
+        self.op_i32_const(2146435072);
+        self.op_i32_eq();
+        self.op_br_if_eqz(14);
+        self.op_local_get(11);
+        self.op_i32_const(2146435072);
+        self.op_i32_eq();
+        self.op_local_get(16);
+        self.op_local_get(15);
+        self.op_i32_xor();
+        self.op_i32_const(0);
+        self.op_i32_lt_s();
+        self.op_i32_and();
+        self.op_br_if_nez(515);
+        self.op_local_get(15);
+        self.op_local_set(2);
+        self.op_br(512);
+        self.op_local_get(11);
+        self.op_i32_const(2146435072);
+        self.op_i32_eq();
+        self.op_br_if_eqz(4);
+        self.op_local_get(13);
+        self.op_local_set(2);
+        self.op_br(505);
+        self.op_local_get(12);
+        self.op_local_get(17);
+        self.op_i32_or();
+        self.op_i32_eqz();
+        self.op_br_if_eqz(15);
+        self.op_local_get(14);
+        self.op_local_set(3);
+        self.op_local_get(13);
+        self.op_local_set(2);
+        self.op_local_get(11);
+        self.op_local_get(15);
+        self.op_i32_or();
+        self.op_i32_eqz();
+        self.op_br_if_eqz(5);
+        self.op_local_get(15);
+        self.op_local_get(14);
+        self.op_i32_and();
+        self.op_local_set(2);
+        self.op_br(486);
+        self.op_local_get(16);
+        self.op_local_set(3);
+        self.op_local_get(15);
+        self.op_local_set(2);
+        self.op_br(481);
+        self.op_local_get(11);
+        self.op_local_get(13);
+        self.op_i32_gt_u();
+        self.op_local_get(12);
+        self.op_local_get(14);
+        self.op_i32_eq();
+        self.op_local_get(16);
+        self.op_local_get(19);
+        self.op_i32_gt_u();
+        self.op_i32_and();
+        self.op_i32_or();
+        self.op_br_if_eqz(13);
+        self.op_local_get(16);
+        self.op_local_set(10);
+        self.op_local_get(14);
+        self.op_local_set(16);
+        self.op_local_get(10);
+        self.op_local_set(14);
+        self.op_local_get(15);
+        self.op_local_set(10);
+        self.op_local_get(13);
+        self.op_local_set(15);
+        self.op_local_get(10);
+        self.op_local_set(13);
+        self.op_local_get(15);
+        self.op_i32_const(20);
+        self.op_i32_shr_u();
+        self.op_i32_const(2047);
+        self.op_i32_and();
+        self.op_local_set(9);
+        self.op_local_get(13);
+        self.op_i32_const(20);
+        self.op_i32_shr_u();
+        self.op_i32_const(2047);
+        self.op_i32_and();
+        self.op_local_set(8);
+        self.op_local_get(15);
+        self.op_i32_const(1048575);
+        self.op_i32_and();
+        self.op_local_set(7);
+        self.op_local_get(13);
+        self.op_i32_const(1048575);
+        self.op_i32_and();
+        self.op_local_set(6);
+        self.op_local_get(9);
+        self.op_i32_eqz();
+        self.op_br_if_eqz(43);
+        self.op_local_get(7);
+        self.op_i32_clz();
+        self.op_local_get(17);
+        self.op_i32_clz();
+        self.op_i32_const(32);
+        self.op_i32_add();
+        self.op_local_get(9);
+        self.op_select();
+        self.op_i32_const(11);
+        self.op_i32_sub();
+        self.op_local_set(5);
+        self.op_local_get(5);
+        self.op_i32_const(32);
+        self.op_i32_ge_u();
+        self.op_br_if_eqz(10);
+        self.op_local_get(16);
+        self.op_local_get(6);
+        self.op_i32_const(32);
+        self.op_i32_sub();
+        self.op_i32_shl();
+        self.op_local_set(7);
+        self.op_i32_const(0);
+        self.op_local_set(16);
+        self.op_br(15);
+        self.op_local_get(7);
+        self.op_local_get(6);
+        self.op_i32_shl();
+        self.op_local_get(17);
+        self.op_i32_const(32);
+        self.op_local_get(8);
+        self.op_i32_sub();
+        self.op_i32_shr_u();
+        self.op_i32_or();
+        self.op_local_set(7);
+        self.op_local_get(16);
+        self.op_local_get(6);
+        self.op_i32_shl();
+        self.op_local_set(16);
+        self.op_i32_const(1);
+        self.op_local_get(6);
+        self.op_i32_sub();
+        self.op_local_set(9);
+        self.op_local_get(8);
+        self.op_i32_eqz();
+        self.op_br_if_eqz(43);
+        self.op_local_get(6);
+        self.op_i32_clz();
+        self.op_local_get(15);
+        self.op_i32_clz();
+        self.op_i32_const(32);
+        self.op_i32_add();
+        self.op_local_get(8);
+        self.op_select();
+        self.op_i32_const(11);
+        self.op_i32_sub();
+        self.op_local_set(5);
+        self.op_local_get(5);
+        self.op_i32_const(32);
+        self.op_i32_ge_u();
+        self.op_br_if_eqz(10);
+        self.op_local_get(14);
+        self.op_local_get(6);
+        self.op_i32_const(32);
+        self.op_i32_sub();
+        self.op_i32_shl();
+        self.op_local_set(6);
+        self.op_i32_const(0);
+        self.op_local_set(14);
+        self.op_br(15);
+        self.op_local_get(6);
+        self.op_local_get(6);
+        self.op_i32_shl();
+        self.op_local_get(15);
+        self.op_i32_const(32);
+        self.op_local_get(8);
+        self.op_i32_sub();
+        self.op_i32_shr_u();
+        self.op_i32_or();
+        self.op_local_set(6);
+        self.op_local_get(14);
+        self.op_local_get(6);
+        self.op_i32_shl();
+        self.op_local_set(14);
+        self.op_i32_const(1);
+        self.op_local_get(6);
+        self.op_i32_sub();
+        self.op_local_set(8);
+        self.op_local_get(7);
+        self.op_i32_const(1048576);
+        self.op_i32_or();
+        self.op_i32_const(3);
+        self.op_i32_shl();
+        self.op_local_get(17);
+        self.op_i32_const(29);
+        self.op_i32_shr_u();
+        self.op_i32_or();
+        self.op_local_set(7);
+        self.op_local_get(16);
+        self.op_i32_const(3);
+        self.op_i32_shl();
+        self.op_local_set(16);
+        self.op_local_get(6);
+        self.op_i32_const(1048576);
+        self.op_i32_or();
+        self.op_i32_const(3);
+        self.op_i32_shl();
+        self.op_local_get(15);
+        self.op_i32_const(29);
+        self.op_i32_shr_u();
+        self.op_i32_or();
+        self.op_local_set(6);
+        self.op_local_get(14);
+        self.op_i32_const(3);
+        self.op_i32_shl();
+        self.op_local_set(14);
+        self.op_local_get(9);
+        self.op_local_get(9);
+        self.op_i32_sub();
+        self.op_local_set(5);
+        self.op_local_get(5);
+        self.op_br_if_eqz(67);
+        self.op_local_get(5);
+        self.op_i32_const(64);
+        self.op_i32_ge_u();
+        self.op_br_if_eqz(6);
+        self.op_i32_const(0);
+        self.op_local_set(6);
+        self.op_i32_const(1);
+        self.op_local_set(14);
+        self.op_br(58);
+        self.op_local_get(5);
+        self.op_i32_const(32);
+        self.op_i32_lt_u();
+        self.op_br_if_eqz(26);
+        self.op_local_get(14);
+        self.op_i32_const(32);
+        self.op_local_get(7);
+        self.op_i32_sub();
+        self.op_i32_shl();
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_local_set(4);
+        self.op_local_get(14);
+        self.op_local_get(6);
+        self.op_i32_shr_u();
+        self.op_local_get(7);
+        self.op_i32_const(32);
+        self.op_local_get(8);
+        self.op_i32_sub();
+        self.op_i32_shl();
+        self.op_i32_or();
+        self.op_local_get(5);
+        self.op_i32_or();
+        self.op_local_set(14);
+        self.op_local_get(6);
+        self.op_local_get(6);
+        self.op_i32_shr_u();
+        self.op_local_set(6);
+        self.op_br(29);
+        self.op_local_get(5);
+        self.op_i32_const(32);
+        self.op_i32_sub();
+        self.op_local_set(5);
+        self.op_local_get(14);
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_local_get(6);
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_local_get(8);
+        self.op_i32_const(32);
+        self.op_local_get(9);
+        self.op_i32_sub();
+        self.op_i32_shl();
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_i32_and();
+        self.op_i32_or();
+        self.op_local_set(4);
+        self.op_local_get(6);
+        self.op_local_get(6);
+        self.op_i32_shr_u();
+        self.op_local_get(5);
+        self.op_i32_or();
+        self.op_local_set(14);
+        self.op_i32_const(0);
+        self.op_local_set(6);
+        self.op_local_get(15);
+        self.op_local_get(14);
+        self.op_i32_xor();
+        self.op_i32_const(0);
+        self.op_i32_lt_s();
+        self.op_br_if_eqz(67);
+        self.op_local_get(16);
+        self.op_local_set(10);
+        self.op_local_get(16);
+        self.op_local_get(15);
+        self.op_i32_sub();
+        self.op_local_set(16);
+        self.op_local_get(7);
+        self.op_local_get(7);
+        self.op_i32_sub();
+        self.op_local_get(11);
+        self.op_local_get(16);
+        self.op_i32_lt_u();
+        self.op_i32_sub();
+        self.op_local_set(7);
+        self.op_local_get(7);
+        self.op_local_get(17);
+        self.op_i32_or();
+        self.op_i32_eqz();
+        self.op_br_if_nez(222);
+        self.op_local_get(7);
+        self.op_i32_const(8388608);
+        self.op_i32_lt_u();
+        self.op_br_if_eqz(43);
+        self.op_local_get(7);
+        self.op_i32_clz();
+        self.op_local_get(17);
+        self.op_i32_clz();
+        self.op_i32_const(32);
+        self.op_i32_add();
+        self.op_local_get(9);
+        self.op_select();
+        self.op_i32_const(8);
+        self.op_i32_sub();
+        self.op_local_set(5);
+        self.op_local_get(5);
+        self.op_i32_const(32);
+        self.op_i32_ge_u();
+        self.op_br_if_eqz(10);
+        self.op_local_get(16);
+        self.op_local_get(6);
+        self.op_i32_const(32);
+        self.op_i32_sub();
+        self.op_i32_shl();
+        self.op_local_set(7);
+        self.op_i32_const(0);
+        self.op_local_set(16);
+        self.op_br(15);
+        self.op_local_get(7);
+        self.op_local_get(6);
+        self.op_i32_shl();
+        self.op_local_get(17);
+        self.op_i32_const(32);
+        self.op_local_get(8);
+        self.op_i32_sub();
+        self.op_i32_shr_u();
+        self.op_i32_or();
+        self.op_local_set(7);
+        self.op_local_get(16);
+        self.op_local_get(6);
+        self.op_i32_shl();
+        self.op_local_set(16);
+        self.op_local_get(9);
+        self.op_local_get(6);
+        self.op_i32_sub();
+        self.op_local_set(9);
+        self.op_br(37);
+        self.op_local_get(16);
+        self.op_local_get(15);
+        self.op_i32_add();
+        self.op_local_set(16);
+        self.op_local_get(7);
+        self.op_local_get(7);
+        self.op_i32_add();
+        self.op_local_get(17);
+        self.op_local_get(16);
+        self.op_i32_lt_u();
+        self.op_i32_add();
+        self.op_local_set(7);
+        self.op_local_get(7);
+        self.op_i32_const(16777216);
+        self.op_i32_and();
+        self.op_br_if_eqz(21);
+        self.op_local_get(16);
+        self.op_i32_const(1);
+        self.op_i32_shr_u();
+        self.op_local_get(8);
+        self.op_i32_const(31);
+        self.op_i32_shl();
+        self.op_i32_or();
+        self.op_local_get(17);
+        self.op_i32_const(1);
+        self.op_i32_and();
+        self.op_i32_or();
+        self.op_local_set(16);
+        self.op_local_get(7);
+        self.op_i32_const(1);
+        self.op_i32_shr_u();
+        self.op_local_set(7);
+        self.op_local_get(9);
+        self.op_i32_const(1);
+        self.op_i32_add();
+        self.op_local_set(9);
+        self.op_local_get(15);
+        self.op_i32_const(-2147483648);
+        self.op_i32_and();
+        self.op_local_set(15);
+        self.op_local_get(9);
+        self.op_i32_const(2047);
+        self.op_i32_ge_s();
+        self.op_br_if_eqz(6);
+        self.op_local_get(15);
+        self.op_i32_const(2146435072);
+        self.op_i32_or();
+        self.op_local_set(2);
+        self.op_br(126);
+        self.op_local_get(9);
+        self.op_i32_const(0);
+        self.op_i32_le_s();
+        self.op_br_if_eqz(77);
+        self.op_i32_const(1);
+        self.op_local_get(10);
+        self.op_i32_sub();
+        self.op_local_set(5);
+        self.op_local_get(5);
+        self.op_i32_const(64);
+        self.op_i32_ge_u();
+        self.op_br_if_eqz(10);
+        self.op_local_get(7);
+        self.op_local_get(17);
+        self.op_i32_or();
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_local_set(16);
+        self.op_i32_const(0);
+        self.op_local_set(7);
+        self.op_br(58);
+        self.op_local_get(5);
+        self.op_i32_const(32);
+        self.op_i32_lt_u();
+        self.op_br_if_eqz(26);
+        self.op_local_get(16);
+        self.op_i32_const(32);
+        self.op_local_get(7);
+        self.op_i32_sub();
+        self.op_i32_shl();
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_local_set(4);
+        self.op_local_get(16);
+        self.op_local_get(6);
+        self.op_i32_shr_u();
+        self.op_local_get(8);
+        self.op_i32_const(32);
+        self.op_local_get(8);
+        self.op_i32_sub();
+        self.op_i32_shl();
+        self.op_i32_or();
+        self.op_local_get(5);
+        self.op_i32_or();
+        self.op_local_set(16);
+        self.op_local_get(7);
+        self.op_local_get(6);
+        self.op_i32_shr_u();
+        self.op_local_set(7);
+        self.op_br(29);
+        self.op_local_get(5);
+        self.op_i32_const(32);
+        self.op_i32_sub();
+        self.op_local_set(5);
+        self.op_local_get(16);
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_local_get(6);
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_local_get(9);
+        self.op_i32_const(32);
+        self.op_local_get(9);
+        self.op_i32_sub();
+        self.op_i32_shl();
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_i32_and();
+        self.op_i32_or();
+        self.op_local_set(4);
+        self.op_local_get(7);
+        self.op_local_get(6);
+        self.op_i32_shr_u();
+        self.op_local_get(5);
+        self.op_i32_or();
+        self.op_local_set(16);
+        self.op_i32_const(0);
+        self.op_local_set(7);
+        self.op_i32_const(0);
+        self.op_local_set(9);
+        self.op_local_get(16);
+        self.op_i32_const(7);
+        self.op_i32_and();
+        self.op_local_set(1);
+        self.op_local_get(16);
+        self.op_i32_const(3);
+        self.op_i32_shr_u();
+        self.op_local_get(8);
+        self.op_i32_const(29);
+        self.op_i32_shl();
+        self.op_i32_or();
+        self.op_local_set(3);
+        self.op_local_get(7);
+        self.op_i32_const(3);
+        self.op_i32_shr_u();
+        self.op_i32_const(1048575);
+        self.op_i32_and();
+        self.op_local_get(10);
+        self.op_i32_const(20);
+        self.op_i32_shl();
+        self.op_i32_or();
+        self.op_local_get(16);
+        self.op_i32_or();
+        self.op_local_set(2);
+        self.op_local_get(1);
+        self.op_i32_const(4);
+        self.op_i32_gt_u();
+        self.op_local_get(2);
+        self.op_i32_const(4);
+        self.op_i32_eq();
+        self.op_local_get(5);
+        self.op_i32_const(1);
+        self.op_i32_and();
+        self.op_i32_and();
+        self.op_i32_or();
+        self.op_br_if_eqz(10);
+        self.op_local_get(3);
+        self.op_i32_const(1);
+        self.op_i32_add();
+        self.op_local_set(3);
+        self.op_local_get(2);
+        self.op_local_get(4);
+        self.op_i32_eqz();
+        self.op_i32_add();
+        self.op_local_set(2);
+        self.op_local_get(3);
+        self.op_local_get(3);
+        self.op_local_set(16);
+        self.op_local_set(16);
+        self.op_bulk_drop(14);
+    }
+
+    /// Multiplies two `f64` values (`lhs_lo`, `lhs_hi`, `rhs_lo`, `rhs_hi`) returning
+    /// (low, high).
+    ///
+    /// The significands are multiplied with four `I32Mul64` instructions.
+    ///
+    /// Max stack height: 22
+    pub fn op_soft_f64_mul(&mut self) {
+        self.op_bulk_const(17);
+        self.op_local_get(20);
+        self.op_i32_const(2147483647);
+        self.op_i32_and();
+        self.op_local_set(17);
+        self.op_local_get(18);
+        self.op_i32_const(2147483647);
+        self.op_i32_and();
+        self.op_local_set(16);
+        self.op_local_get(20);
+        self.op_local_get(19);
+        self.op_i32_xor();
+        self.op_i32_const(-2147483648);
+        self.op_i32_and();
+        self.op_local_set(15);
+        self.op_i32_const(2146959360);
+        self.op_local_set(2);
+        self.op_local_get(17);
+        self.op_i32_const(2146435072);
+        self.op_i32_gt_u();
+        self.op_local_get(18);
+        self.op_i32_const(2146435072);
+        self.op_i32_eq();
+        self.op_local_get(23);
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_i32_and();
+        self.op_i32_or();
+        self.op_local_get(17);
+        self.op_i32_const(2146435072);
+        self.op_i32_gt_u();
+        self.op_local_get(18);
+        self.op_i32_const(2146435072);
+        self.op_i32_eq();
+        self.op_local_get(22);
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_i32_and();
+        self.op_i32_or();
+        self.op_i32_or();
+        self.op_br_if_nez(404);
+        self.op_local_get(17);
+        self.op_i32_const(2146435072);
+        self.op_i32_eq();
+        self.op_local_get(17);
+        self.op_i32_const(2146435072);
+        self.op_i32_eq();
+        self.op_i32_or();
+        self.op_br_if_eqz(16);
+        self.op_local_get(17);
+        self.op_local_get(22);
+        self.op_i32_or();
+        self.op_i32_eqz();
+        self.op_local_get(17);
+        self.op_local_get(21);
+        self.op_i32_or();
+        self.op_i32_eqz();
+        self.op_i32_or();
+        self.op_br_if_nez(386);
+        self.op_local_get(15);
+        self.op_i32_const(2146435072);
+        self.op_i32_or();
+        self.op_local_set(2);
+        self.op_br(381);
+        self.op_local_get(15);
+        self.op_local_set(2);
+        self.op_local_get(17);
+        self.op_local_get(22);
+        self.op_i32_or();
+        self.op_i32_eqz();
+        self.op_local_get(17);
+        self.op_local_get(21);
+        self.op_i32_or();
+        self.op_i32_eqz();
+        self.op_i32_or();
+        self.op_br_if_nez(369);
+        self.op_local_get(17);
+        self.op_i32_const(20);
+        self.op_i32_shr_u();
+        self.op_local_set(14);
+        self.op_local_get(16);
+        self.op_i32_const(20);
+        self.op_i32_shr_u();
+        self.op_local_set(13);
+        self.op_local_get(20);
+        self.op_i32_const(1048575);
+        self.op_i32_and();
+        self.op_local_set(20);
+        self.op_local_get(18);
+        self.op_i32_const(1048575);
+        self.op_i32_and();
+        self.op_local_set(18);
+        self.op_local_get(14);
+        self.op_i32_eqz();
+        self.op_br_if_eqz(43);
+        self.op_local_get(20);
+        self.op_i32_clz();
+        self.op_local_get(22);
+        self.op_i32_clz();
+        self.op_i32_const(32);
+        self.op_i32_add();
+        self.op_local_get(22);
+        self.op_select();
+        self.op_i32_const(11);
+        self.op_i32_sub();
+        self.op_local_set(12);
+        self.op_local_get(12);
+        self.op_i32_const(32);
+        self.op_i32_ge_u();
+        self.op_br_if_eqz(10);
+        self.op_local_get(21);
+        self.op_local_get(13);
+        self.op_i32_const(32);
+        self.op_i32_sub();
+        self.op_i32_shl();
+        self.op_local_set(20);
+        self.op_i32_const(0);
+        self.op_local_set(21);
+        self.op_br(15);
+        self.op_local_get(20);
+        self.op_local_get(13);
+        self.op_i32_shl();
+        self.op_local_get(22);
+        self.op_i32_const(32);
+        self.op_local_get(15);
+        self.op_i32_sub();
+        self.op_i32_shr_u();
+        self.op_i32_or();
+        self.op_local_set(20);
+        self.op_local_get(21);
+        self.op_local_get(13);
+        self.op_i32_shl();
+        self.op_local_set(21);
+        self.op_i32_const(1);
+        self.op_local_get(13);
+        self.op_i32_sub();
+        self.op_local_set(14);
+        self.op_local_get(13);
+        self.op_i32_eqz();
+        self.op_br_if_eqz(43);
+        self.op_local_get(18);
+        self.op_i32_clz();
+        self.op_local_get(20);
+        self.op_i32_clz();
+        self.op_i32_const(32);
+        self.op_i32_add();
+        self.op_local_get(20);
+        self.op_select();
+        self.op_i32_const(11);
+        self.op_i32_sub();
+        self.op_local_set(12);
+        self.op_local_get(12);
+        self.op_i32_const(32);
+        self.op_i32_ge_u();
+        self.op_br_if_eqz(10);
+        self.op_local_get(19);
+        self.op_local_get(13);
+        self.op_i32_const(32);
+        self.op_i32_sub();
+        self.op_i32_shl();
+        self.op_local_set(18);
+        self.op_i32_const(0);
+        self.op_local_set(19);
+        self.op_br(15);
+        self.op_local_get(18);
+        self.op_local_get(13);
+        self.op_i32_shl();
+        self.op_local_get(20);
+        self.op_i32_const(32);
+        self.op_local_get(15);
+        self.op_i32_sub();
+        self.op_i32_shr_u();
+        self.op_i32_or();
+        self.op_local_set(18);
+        self.op_local_get(19);
+        self.op_local_get(13);
+        self.op_i32_shl();
+        self.op_local_set(19);
+        self.op_i32_const(1);
+        self.op_local_get(13);
+        self.op_i32_sub();
+        self.op_local_set(13);
+        self.op_local_get(20);
+        self.op_i32_const(1048576);
+        self.op_i32_or();
+        self.op_local_set(20);
+        self.op_local_get(18);
+        self.op_i32_const(1048576);
+        self.op_i32_or();
+        self.op_local_set(18);
+        self.op_local_get(21);
+        self.op_local_get(20);
+        self.op_i32_mul64();
+        self.op_local_set(10);
+        self.op_local_set(10);
+        self.op_local_get(21);
+        self.op_local_get(19);
+        self.op_i32_mul64();
+        self.op_local_set(6);
+        self.op_local_set(6);
+        self.op_local_get(9);
+        self.op_local_get(7);
+        self.op_i32_add();
+        self.op_local_set(9);
+        self.op_local_get(9);
+        self.op_local_get(7);
+        self.op_i32_lt_u();
+        self.op_local_set(4);
+        self.op_local_get(5);
+        self.op_local_get(5);
+        self.op_i32_add();
+        self.op_local_set(8);
+        self.op_local_get(20);
+        self.op_local_get(20);
+        self.op_i32_mul64();
+        self.op_local_set(6);
+        self.op_local_set(6);
+        self.op_local_get(9);
+        self.op_local_get(7);
+        self.op_i32_add();
+        self.op_local_set(9);
+        self.op_local_get(9);
+        self.op_local_get(7);
+        self.op_i32_lt_u();
+        self.op_local_set(4);
+        self.op_local_get(5);
+        self.op_local_get(5);
+        self.op_i32_add();
+        self.op_local_set(5);
+        self.op_local_get(8);
+        self.op_local_get(6);
+        self.op_i32_add();
+        self.op_local_set(8);
+        self.op_local_get(8);
+        self.op_local_get(6);
+        self.op_i32_lt_u();
+        self.op_local_set(7);
+        self.op_local_get(20);
+        self.op_local_get(19);
+        self.op_i32_mul64();
+        self.op_local_set(6);
+        self.op_local_set(6);
+        self.op_local_get(8);
+        self.op_local_get(7);
+        self.op_i32_add();
+        self.op_local_set(8);
+        self.op_local_get(8);
+        self.op_local_get(7);
+        self.op_i32_lt_u();
+        self.op_local_set(4);
+        self.op_local_get(7);
+        self.op_local_get(6);
+        self.op_i32_add();
+        self.op_local_get(5);
+        self.op_i32_add();
+        self.op_local_set(7);
+        self.op_local_get(9);
+        self.op_i32_const(17);
+        self.op_i32_shr_u();
+        self.op_local_get(9);
+        self.op_i32_const(15);
+        self.op_i32_shl();
+        self.op_i32_or();
+        self.op_local_get(11);
+        self.op_local_get(11);
+        self.op_i32_const(131071);
+        self.op_i32_and();
+        self.op_i32_or();
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_i32_or();
+        self.op_local_set(21);
+        self.op_local_get(8);
+        self.op_i32_const(17);
+        self.op_i32_shr_u();
+        self.op_local_get(8);
+        self.op_i32_const(15);
+        self.op_i32_shl();
+        self.op_i32_or();
+        self.op_local_set(20);
+        self.op_local_get(14);
+        self.op_local_get(14);
+        self.op_i32_add();
+        self.op_i32_const(1023);
+        self.op_i32_sub();
+        self.op_local_set(14);
+        self.op_local_get(20);
+        self.op_i32_const(16777216);
+        self.op_i32_and();
+        self.op_br_if_eqz(21);
+        self.op_local_get(21);
+        self.op_i32_const(1);
+        self.op_i32_shr_u();
+        self.op_local_get(21);
+        self.op_i32_const(31);
+        self.op_i32_shl();
+        self.op_i32_or();
+        self.op_local_get(22);
+        self.op_i32_const(1);
+        self.op_i32_and();
+        self.op_i32_or();
+        self.op_local_set(21);
+        self.op_local_get(20);
+        self.op_i32_const(1);
+        self.op_i32_shr_u();
+        self.op_local_set(20);
+        self.op_local_get(14);
+        self.op_i32_const(1);
+        self.op_i32_add();
+        self.op_local_set(14);
+        self.op_local_get(14);
+        self.op_i32_const(2047);
+        self.op_i32_ge_s();
+        self.op_br_if_eqz(6);
+        self.op_local_get(15);
+        self.op_i32_const(2146435072);
+        self.op_i32_or();
+        self.op_local_set(2);
+        self.op_br(126);
+        self.op_local_get(14);
+        self.op_i32_const(0);
+        self.op_i32_le_s();
+        self.op_br_if_eqz(77);
+        self.op_i32_const(1);
+        self.op_local_get(15);
+        self.op_i32_sub();
+        self.op_local_set(12);
+        self.op_local_get(12);
+        self.op_i32_const(64);
+        self.op_i32_ge_u();
+        self.op_br_if_eqz(10);
+        self.op_local_get(20);
+        self.op_local_get(22);
+        self.op_i32_or();
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_local_set(21);
+        self.op_i32_const(0);
+        self.op_local_set(20);
+        self.op_br(58);
+        self.op_local_get(12);
+        self.op_i32_const(32);
+        self.op_i32_lt_u();
+        self.op_br_if_eqz(26);
+        self.op_local_get(21);
+        self.op_i32_const(32);
+        self.op_local_get(14);
+        self.op_i32_sub();
+        self.op_i32_shl();
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_local_set(11);
+        self.op_local_get(21);
+        self.op_local_get(13);
+        self.op_i32_shr_u();
+        self.op_local_get(21);
+        self.op_i32_const(32);
+        self.op_local_get(15);
+        self.op_i32_sub();
+        self.op_i32_shl();
+        self.op_i32_or();
+        self.op_local_get(12);
+        self.op_i32_or();
+        self.op_local_set(21);
+        self.op_local_get(20);
+        self.op_local_get(13);
+        self.op_i32_shr_u();
+        self.op_local_set(20);
+        self.op_br(29);
+        self.op_local_get(12);
+        self.op_i32_const(32);
+        self.op_i32_sub();
+        self.op_local_set(12);
+        self.op_local_get(21);
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_local_get(13);
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_local_get(22);
+        self.op_i32_const(32);
+        self.op_local_get(16);
+        self.op_i32_sub();
+        self.op_i32_shl();
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_i32_and();
+        self.op_i32_or();
+        self.op_local_set(11);
+        self.op_local_get(20);
+        self.op_local_get(13);
+        self.op_i32_shr_u();
+        self.op_local_get(12);
+        self.op_i32_or();
+        self.op_local_set(21);
+        self.op_i32_const(0);
+        self.op_local_set(20);
+        self.op_i32_const(0);
+        self.op_local_set(14);
+        self.op_local_get(21);
+        self.op_i32_const(7);
+        self.op_i32_and();
+        self.op_local_set(1);
+        self.op_local_get(21);
+        self.op_i32_const(3);
+        self.op_i32_shr_u();
+        self.op_local_get(21);
+        self.op_i32_const(29);
+        self.op_i32_shl();
+        self.op_i32_or();
+        self.op_local_set(3);
+        self.op_local_get(20);
+        self.op_i32_const(3);
+        self.op_i32_shr_u();
+        self.op_i32_const(1048575);
+        self.op_i32_and();
+        self.op_local_get(15);
+        self.op_i32_const(20);
+        self.op_i32_shl();
+        self.op_i32_or();
+        self.op_local_get(16);
+        self.op_i32_or();
+        self.op_local_set(2);
+        self.op_local_get(1);
+        self.op_i32_const(4);
+        self.op_i32_gt_u();
+        self.op_local_get(2);
+        self.op_i32_const(4);
+        self.op_i32_eq();
+        self.op_local_get(5);
+        self.op_i32_const(1);
+        self.op_i32_and();
+        self.op_i32_and();
+        self.op_i32_or();
+        self.op_br_if_eqz(10);
+        self.op_local_get(3);
+        self.op_i32_const(1);
+        self.op_i32_add();
+        self.op_local_set(3);
+        self.op_local_get(2);
+        self.op_local_get(4);
+        self.op_i32_eqz();
+        self.op_i32_add();
+        self.op_local_set(2);
+        self.op_local_get(3);
+        self.op_local_get(3);
+        self.op_local_set(21);
+        self.op_local_set(21);
+        self.op_bulk_drop(19);
+    }
+
+    /// Divides two `f64` values (`lhs_lo`, `lhs_hi`, `rhs_lo`, `rhs_hi`) returning (low, high).
+    ///
+    /// The quotient is computed by a long division with a fixed number of iterations.
+    ///
+    /// Max stack height: 18
+    pub fn op_soft_f64_div(&mut self) {
+        self.op_bulk_const(13);
+        self.op_local_get(16);
+        self.op_i32_const(2147483647);
+        self.op_i32_and();
+        self.op_local_set(13);
+        self.op_local_get(14);
+        self.op_i32_const(2147483647);
+        self.op_i32_and();
+        self.op_local_set(12);
+        self.op_local_get(16);
+        self.op_local_get(15);
+        self.op_i32_xor();
+        self.op_i32_const(-2147483648);
+        self.op_i32_and();
+        self.op_local_set(11);
+        self.op_i32_const(2146959360);
+        self.op_local_set(2);
+        self.op_local_get(13);
+        self.op_i32_const(2146435072);
+        self.op_i32_gt_u();
+        self.op_local_get(14);
+        self.op_i32_const(2146435072);
+        self.op_i32_eq();
+        self.op_local_get(19);
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_i32_and();
+        self.op_i32_or();
+        self.op_local_get(13);
+        self.op_i32_const(2146435072);
+        self.op_i32_gt_u();
+        self.op_local_get(14);
+        self.op_i32_const(2146435072);
+        self.op_i32_eq();
+        self.op_local_get(18);
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_i32_and();
+        self.op_i32_or();
+        self.op_i32_or();
+        self.op_br_if_nez(395);
+        self.op_local_get(13);
+        self.op_i32_const(2146435072);
+        self.op_i32_eq();
+        self.op_br_if_eqz(10);
+        self.op_local_get(12);
+        self.op_i32_const(2146435072);
+        self.op_i32_eq();
+        self.op_br_if_nez(387);
+        self.op_local_get(11);
+        self.op_i32_const(2146435072);
+        self.op_i32_or();
+        self.op_local_set(2);
+        self.op_br(382);
+        self.op_local_get(13);
+        self.op_local_get(18);
+        self.op_i32_or();
+        self.op_i32_eqz();
+        self.op_br_if_eqz(9);
+        self.op_local_get(12);
+        self.op_local_get(16);
+        self.op_i32_or();
+        self.op_i32_eqz();
+        self.op_br_if_nez(372);
+        self.op_local_get(11);
+        self.op_local_set(2);
+        self.op_br(369);
+        self.op_local_get(11);
+        self.op_local_set(2);
+        self.op_local_get(12);
+        self.op_i32_const(2146435072);
+        self.op_i32_eq();
+        self.op_br_if_nez(363);
+        self.op_local_get(12);
+        self.op_local_get(16);
+        self.op_i32_or();
+        self.op_i32_eqz();
+        self.op_br_if_eqz(6);
+        self.op_local_get(11);
+        self.op_i32_const(2146435072);
+        self.op_i32_or();
+        self.op_local_set(2);
+        self.op_br(353);
+        self.op_local_get(13);
+        self.op_i32_const(20);
+        self.op_i32_shr_u();
+        self.op_local_set(10);
+        self.op_local_get(12);
+        self.op_i32_const(20);
+        self.op_i32_shr_u();
+        self.op_local_set(9);
+        self.op_local_get(16);
+        self.op_i32_const(1048575);
+        self.op_i32_and();
+        self.op_local_set(16);
+        self.op_local_get(14);
+        self.op_i32_const(1048575);
+        self.op_i32_and();
+        self.op_local_set(14);
+        self.op_local_get(10);
+        self.op_i32_eqz();
+        self.op_br_if_eqz(43);
+        self.op_local_get(16);
+        self.op_i32_clz();
+        self.op_local_get(18);
+        self.op_i32_clz();
+        self.op_i32_const(32);
+        self.op_i32_add();
+        self.op_local_get(18);
+        self.op_select();
+        self.op_i32_const(11);
+        self.op_i32_sub();
+        self.op_local_set(8);
+        self.op_local_get(8);
+        self.op_i32_const(32);
+        self.op_i32_ge_u();
+        self.op_br_if_eqz(10);
+        self.op_local_get(17);
+        self.op_local_get(9);
+        self.op_i32_const(32);
+        self.op_i32_sub();
+        self.op_i32_shl();
+        self.op_local_set(16);
+        self.op_i32_const(0);
+        self.op_local_set(17);
+        self.op_br(15);
+        self.op_local_get(16);
+        self.op_local_get(9);
+        self.op_i32_shl();
+        self.op_local_get(18);
+        self.op_i32_const(32);
+        self.op_local_get(11);
+        self.op_i32_sub();
+        self.op_i32_shr_u();
+        self.op_i32_or();
+        self.op_local_set(16);
+        self.op_local_get(17);
+        self.op_local_get(9);
+        self.op_i32_shl();
+        self.op_local_set(17);
+        self.op_i32_const(1);
+        self.op_local_get(9);
+        self.op_i32_sub();
+        self.op_local_set(10);
+        self.op_local_get(9);
+        self.op_i32_eqz();
+        self.op_br_if_eqz(43);
+        self.op_local_get(14);
+        self.op_i32_clz();
+        self.op_local_get(16);
+        self.op_i32_clz();
+        self.op_i32_const(32);
+        self.op_i32_add();
+        self.op_local_get(16);
+        self.op_select();
+        self.op_i32_const(11);
+        self.op_i32_sub();
+        self.op_local_set(8);
+        self.op_local_get(8);
+        self.op_i32_const(32);
+        self.op_i32_ge_u();
+        self.op_br_if_eqz(10);
+        self.op_local_get(15);
+        self.op_local_get(9);
+        self.op_i32_const(32);
+        self.op_i32_sub();
+        self.op_i32_shl();
+        self.op_local_set(14);
+        self.op_i32_const(0);
+        self.op_local_set(15);
+        self.op_br(15);
+        self.op_local_get(14);
+        self.op_local_get(9);
+        self.op_i32_shl();
+        self.op_local_get(16);
+        self.op_i32_const(32);
+        self.op_local_get(11);
+        self.op_i32_sub();
+        self.op_i32_shr_u();
+        self.op_i32_or();
+        self.op_local_set(14);
+        self.op_local_get(15);
+        self.op_local_get(9);
+        self.op_i32_shl();
+        self.op_local_set(15);
+        self.op_i32_const(1);
+        self.op_local_get(9);
+        self.op_i32_sub();
+        self.op_local_set(9);
+        self.op_local_get(16);
+        self.op_i32_const(1048576);
+        self.op_i32_or();
+        self.op_local_set(16);
+        self.op_local_get(14);
+        self.op_i32_const(1048576);
+        self.op_i32_or();
+        self.op_local_set(14);
+        self.op_local_get(10);
+        self.op_local_get(10);
+        self.op_i32_sub();
+        self.op_i32_const(1023);
+        self.op_i32_add();
+        self.op_local_set(10);
+        self.op_local_get(16);
+        self.op_local_get(15);
+        self.op_i32_lt_u();
+        self.op_local_get(17);
+        self.op_local_get(16);
+        self.op_i32_eq();
+        self.op_local_get(19);
+        self.op_local_get(18);
+        self.op_i32_lt_u();
+        self.op_i32_and();
+        self.op_i32_or();
+        self.op_br_if_eqz(17);
+        self.op_local_get(16);
+        self.op_i32_const(1);
+        self.op_i32_shl();
+        self.op_local_get(18);
+        self.op_i32_const(31);
+        self.op_i32_shr_u();
+        self.op_i32_or();
+        self.op_local_set(16);
+        self.op_local_get(17);
+        self.op_i32_const(1);
+        self.op_i32_shl();
+        self.op_local_set(17);
+        self.op_local_get(10);
+        self.op_i32_const(1);
+        self.op_i32_sub();
+        self.op_local_set(10);
+        self.op_i32_const(56);
+        self.op_local_set(4);
+        self.op_local_get(5);
+        self.op_i32_const(1);
+        self.op_i32_shl();
+        self.op_local_get(7);
+        self.op_i32_const(31);
+        self.op_i32_shr_u();
+        self.op_i32_or();
+        self.op_local_set(5);
+        self.op_local_get(6);
+        self.op_i32_const(1);
+        self.op_i32_shl();
+        self.op_local_set(6);
+        self.op_local_get(16);
+        self.op_local_get(15);
+        self.op_i32_gt_u();
+        self.op_local_get(17);
+        self.op_local_get(16);
+        self.op_i32_eq();
+        self.op_local_get(19);
+        self.op_local_get(18);
+        self.op_i32_ge_u();
+        self.op_i32_and();
+        self.op_i32_or();
+        self.op_br_if_eqz(17);
+        self.op_local_get(16);
+        self.op_local_get(15);
+        self.op_i32_sub();
+        self.op_local_get(18);
+        self.op_local_get(17);
+        self.op_i32_lt_u();
+        self.op_i32_sub();
+        self.op_local_set(16);
+        self.op_local_get(17);
+        self.op_local_get(16);
+        self.op_i32_sub();
+        self.op_local_set(17);
+        self.op_local_get(6);
+        self.op_i32_const(1);
+        self.op_i32_or();
+        self.op_local_set(6);
+        self.op_local_get(16);
+        self.op_i32_const(1);
+        self.op_i32_shl();
+        self.op_local_get(18);
+        self.op_i32_const(31);
+        self.op_i32_shr_u();
+        self.op_i32_or();
+        self.op_local_set(16);
+        self.op_local_get(17);
+        self.op_i32_const(1);
+        self.op_i32_shl();
+        self.op_local_set(17);
+        self.op_local_get(4);
+        self.op_i32_const(1);
+        self.op_i32_sub();
+        self.op_local_set(4);
+        self.op_local_get(4);
+        self.op_br_if_nez(-57);
+        self.op_local_get(6);
+        self.op_local_get(17);
+        self.op_local_get(19);
+        self.op_i32_or();
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_i32_or();
+        self.op_local_set(17);
+        self.op_local_get(5);
+        self.op_local_set(16);
+        self.op_local_get(10);
+        self.op_i32_const(2047);
+        self.op_i32_ge_s();
+        self.op_br_if_eqz(6);
+        self.op_local_get(11);
+        self.op_i32_const(2146435072);
+        self.op_i32_or();
+        self.op_local_set(2);
+        self.op_br(126);
+        self.op_local_get(10);
+        self.op_i32_const(0);
+        self.op_i32_le_s();
+        self.op_br_if_eqz(77);
+        self.op_i32_const(1);
+        self.op_local_get(11);
+        self.op_i32_sub();
+        self.op_local_set(8);
+        self.op_local_get(8);
+        self.op_i32_const(64);
+        self.op_i32_ge_u();
+        self.op_br_if_eqz(10);
+        self.op_local_get(16);
+        self.op_local_get(18);
+        self.op_i32_or();
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_local_set(17);
+        self.op_i32_const(0);
+        self.op_local_set(16);
+        self.op_br(58);
+        self.op_local_get(8);
+        self.op_i32_const(32);
+        self.op_i32_lt_u();
+        self.op_br_if_eqz(26);
+        self.op_local_get(17);
+        self.op_i32_const(32);
+        self.op_local_get(10);
+        self.op_i32_sub();
+        self.op_i32_shl();
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_local_set(7);
+        self.op_local_get(17);
+        self.op_local_get(9);
+        self.op_i32_shr_u();
+        self.op_local_get(17);
+        self.op_i32_const(32);
+        self.op_local_get(11);
+        self.op_i32_sub();
+        self.op_i32_shl();
+        self.op_i32_or();
+        self.op_local_get(8);
+        self.op_i32_or();
+        self.op_local_set(17);
+        self.op_local_get(16);
+        self.op_local_get(9);
+        self.op_i32_shr_u();
+        self.op_local_set(16);
+        self.op_br(29);
+        self.op_local_get(8);
+        self.op_i32_const(32);
+        self.op_i32_sub();
+        self.op_local_set(8);
+        self.op_local_get(17);
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_local_get(9);
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_local_get(18);
+        self.op_i32_const(32);
+        self.op_local_get(12);
+        self.op_i32_sub();
+        self.op_i32_shl();
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_i32_and();
+        self.op_i32_or();
+        self.op_local_set(7);
+        self.op_local_get(16);
+        self.op_local_get(9);
+        self.op_i32_shr_u();
+        self.op_local_get(8);
+        self.op_i32_or();
+        self.op_local_set(17);
+        self.op_i32_const(0);
+        self.op_local_set(16);
+        self.op_i32_const(0);
+        self.op_local_set(10);
+        self.op_local_get(17);
+        self.op_i32_const(7);
+        self.op_i32_and();
+        self.op_local_set(1);
+        self.op_local_get(17);
+        self.op_i32_const(3);
+        self.op_i32_shr_u();
+        self.op_local_get(17);
+        self.op_i32_const(29);
+        self.op_i32_shl();
+        self.op_i32_or();
+        self.op_local_set(3);
+        self.op_local_get(16);
+        self.op_i32_const(3);
+        self.op_i32_shr_u();
+        self.op_i32_const(1048575);
+        self.op_i32_and();
+        self.op_local_get(11);
+        self.op_i32_const(20);
+        self.op_i32_shl();
+        self.op_i32_or();
+        self.op_local_get(12);
+        self.op_i32_or();
+        self.op_local_set(2);
+        self.op_local_get(1);
+        self.op_i32_const(4);
+        self.op_i32_gt_u();
+        self.op_local_get(2);
+        self.op_i32_const(4);
+        self.op_i32_eq();
+        self.op_local_get(5);
+        self.op_i32_const(1);
+        self.op_i32_and();
+        self.op_i32_and();
+        self.op_i32_or();
+        self.op_br_if_eqz(10);
+        self.op_local_get(3);
+        self.op_i32_const(1);
+        self.op_i32_add();
+        self.op_local_set(3);
+        self.op_local_get(2);
+        self.op_local_get(4);
+        self.op_i32_eqz();
+        self.op_i32_add();
+        self.op_local_set(2);
+        self.op_local_get(3);
+        self.op_local_get(3);
+        self.op_local_set(17);
+        self.op_local_set(17);
+        self.op_bulk_drop(15);
+    }
+
+    /// Computes the square root of an `f64` value (`low`, `high`) returning (low, high).
+    ///
+    /// The root is computed bit by bit with a fixed number of iterations.
+    ///
+    /// Max stack height: 15
+    pub fn op_soft_f64_sqrt(&mut self) {
+        self.op_bulk_const(11);
+        self.op_local_get(13);
+        self.op_local_set(2);
+        self.op_local_get(12);
+        self.op_local_set(1);
+        self.op_local_get(12);
+        self.op_i32_const(2147483647);
+        self.op_i32_and();
+        self.op_local_get(14);
+        self.op_i32_or();
+        self.op_i32_eqz();
+        self.op_br_if_nez(232);
+        self.op_local_get(12);
+        self.op_i32_const(2146435072);
+        self.op_i32_eq();
+        self.op_local_get(14);
+        self.op_i32_eqz();
+        self.op_i32_and();
+        self.op_br_if_nez(225);
+        self.op_i32_const(0);
+        self.op_local_set(2);
+        self.op_i32_const(2146959360);
+        self.op_local_set(1);
+        self.op_local_get(12);
+        self.op_i32_const(2146435072);
+        self.op_i32_ge_u();
+        self.op_br_if_nez(217);
+        self.op_local_get(12);
+        self.op_i32_const(20);
+        self.op_i32_shr_u();
+        self.op_local_set(11);
+        self.op_local_get(12);
+        self.op_i32_const(1048575);
+        self.op_i32_and();
+        self.op_local_set(12);
+        self.op_local_get(11);
+        self.op_i32_eqz();
+        self.op_br_if_eqz(43);
+        self.op_local_get(12);
+        self.op_i32_clz();
+        self.op_local_get(14);
+        self.op_i32_clz();
+        self.op_i32_const(32);
+        self.op_i32_add();
+        self.op_local_get(14);
+        self.op_select();
+        self.op_i32_const(11);
+        self.op_i32_sub();
+        self.op_local_set(10);
+        self.op_local_get(10);
+        self.op_i32_const(32);
+        self.op_i32_ge_u();
+        self.op_br_if_eqz(10);
+        self.op_local_get(13);
+        self.op_local_get(11);
+        self.op_i32_const(32);
+        self.op_i32_sub();
+        self.op_i32_shl();
+        self.op_local_set(12);
+        self.op_i32_const(0);
+        self.op_local_set(13);
+        self.op_br(15);
+        self.op_local_get(12);
+        self.op_local_get(11);
+        self.op_i32_shl();
+        self.op_local_get(14);
+        self.op_i32_const(32);
+        self.op_local_get(13);
+        self.op_i32_sub();
+        self.op_i32_shr_u();
+        self.op_i32_or();
+        self.op_local_set(12);
+        self.op_local_get(13);
+        self.op_local_get(11);
+        self.op_i32_shl();
+        self.op_local_set(13);
+        self.op_i32_const(1);
+        self.op_local_get(11);
+        self.op_i32_sub();
+        self.op_local_set(11);
+        self.op_local_get(12);
+        self.op_i32_const(1048576);
+        self.op_i32_or();
+        self.op_local_set(12);
+        self.op_local_get(11);
+        self.op_i32_const(1023);
+        self.op_i32_sub();
+        self.op_local_set(11);
+        self.op_local_get(11);
+        self.op_i32_const(1);
+        self.op_i32_and();
+        self.op_br_if_eqz(13);
+        self.op_local_get(12);
+        self.op_i32_const(1);
+        self.op_i32_shl();
+        self.op_local_get(14);
+        self.op_i32_const(31);
+        self.op_i32_shr_u();
+        self.op_i32_or();
+        self.op_local_set(12);
+        self.op_local_get(13);
+        self.op_i32_const(1);
+        self.op_i32_shl();
+        self.op_local_set(13);
+        self.op_local_get(11);
+        self.op_i32_const(1);
+        self.op_i32_shr_s();
+        self.op_i32_const(1023);
+        self.op_i32_add();
+        self.op_local_set(11);
+        self.op_i32_const(56);
+        self.op_local_set(3);
+        self.op_local_get(6);
+        self.op_i32_const(2);
+        self.op_i32_shl();
+        self.op_local_get(8);
+        self.op_i32_const(30);
+        self.op_i32_shr_u();
+        self.op_i32_or();
+        self.op_local_set(6);
+        self.op_local_get(7);
+        self.op_i32_const(2);
+        self.op_i32_shl();
+        self.op_local_get(13);
+        self.op_i32_const(20);
+        self.op_i32_shr_u();
+        self.op_i32_or();
+        self.op_local_set(7);
+        self.op_local_get(12);
+        self.op_i32_const(2);
+        self.op_i32_shl();
+        self.op_local_get(14);
+        self.op_i32_const(30);
+        self.op_i32_shr_u();
+        self.op_i32_or();
+        self.op_i32_const(4194303);
+        self.op_i32_and();
+        self.op_local_set(12);
+        self.op_local_get(13);
+        self.op_i32_const(2);
+        self.op_i32_shl();
+        self.op_local_set(13);
+        self.op_local_get(8);
+        self.op_i32_const(2);
+        self.op_i32_shl();
+        self.op_local_get(10);
+        self.op_i32_const(30);
+        self.op_i32_shr_u();
+        self.op_i32_or();
+        self.op_local_set(4);
+        self.op_local_get(9);
+        self.op_i32_const(2);
+        self.op_i32_shl();
+        self.op_i32_const(1);
+        self.op_i32_or();
+        self.op_local_set(5);
+        self.op_local_get(8);
+        self.op_i32_const(1);
+        self.op_i32_shl();
+        self.op_local_get(10);
+        self.op_i32_const(31);
+        self.op_i32_shr_u();
+        self.op_i32_or();
+        self.op_local_set(8);
+        self.op_local_get(9);
+        self.op_i32_const(1);
+        self.op_i32_shl();
+        self.op_local_set(9);
+        self.op_local_get(6);
+        self.op_local_get(5);
+        self.op_i32_gt_u();
+        self.op_local_get(7);
+        self.op_local_get(6);
+        self.op_i32_eq();
+        self.op_local_get(9);
+        self.op_local_get(8);
+        self.op_i32_ge_u();
+        self.op_i32_and();
+        self.op_i32_or();
+        self.op_br_if_eqz(17);
+        self.op_local_get(6);
+        self.op_local_get(5);
+        self.op_i32_sub();
+        self.op_local_get(8);
+        self.op_local_get(7);
+        self.op_i32_lt_u();
+        self.op_i32_sub();
+        self.op_local_set(6);
+        self.op_local_get(7);
+        self.op_local_get(6);
+        self.op_i32_sub();
+        self.op_local_set(7);
+        self.op_local_get(9);
+        self.op_i32_const(1);
+        self.op_i32_or();
+        self.op_local_set(9);
+        self.op_local_get(3);
+        self.op_i32_const(1);
+        self.op_i32_sub();
+        self.op_local_set(3);
+        self.op_local_get(3);
+        self.op_br_if_nez(-89);
+        self.op_local_get(9);
+        self.op_local_get(7);
+        self.op_local_get(9);
+        self.op_i32_or();
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_i32_or();
+        self.op_local_set(9);
+        self.op_local_get(9);
+        self.op_i32_const(3);
+        self.op_i32_shr_u();
+        self.op_local_get(9);
+        self.op_i32_const(29);
+        self.op_i32_shl();
+        self.op_i32_or();
+        self.op_local_set(2);
+        self.op_local_get(8);
+        self.op_i32_const(3);
+        self.op_i32_shr_u();
+        self.op_i32_const(1048575);
+        self.op_i32_and();
+        self.op_local_get(12);
+        self.op_i32_const(20);
+        self.op_i32_shl();
+        self.op_i32_or();
+        self.op_local_set(1);
+        self.op_local_get(9);
+        self.op_i32_const(7);
+        self.op_i32_and();
+        self.op_i32_const(4);
+        self.op_i32_gt_u();
+        self.op_br_if_eqz(10);
+        self.op_local_get(2);
+        self.op_i32_const(1);
+        self.op_i32_add();
+        self.op_local_set(2);
+        self.op_local_get(1);
+        self.op_local_get(3);
+        self.op_i32_eqz();
+        self.op_i32_add();
+        self.op_local_set(1);
+        self.op_local_get(2);
+        self.op_local_get(2);
+        self.op_local_set(13);
+        self.op_local_set(13);
+        self.op_bulk_drop(11);
+    }
+
+    /// Computes `f64.min` or `f64.max` of (`lhs_lo`, `lhs_hi`, `rhs_lo`, `rhs_hi`, `is_max`)
+    /// returning (low, high).
+    ///
+    /// Max stack height: 11
+    pub fn op_soft_f64_min_max(&mut self) {
+        self.op_bulk_const(5);
+        self.op_i32_const(2146959360);
+        self.op_local_set(1);
+        self.op_local_get(9);
+        self.op_i32_const(2147483647);
+        self.op_i32_and();
+        self.op_i32_const(2146435072);
+        self.op_i32_gt_u();
+        self.op_local_get(10);
+        self.op_i32_const(2147483647);
+        self.op_i32_and();
+        self.op_i32_const(2146435072);
+        self.op_i32_eq();
+        self.op_local_get(12);
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_i32_and();
+        self.op_i32_or();
+        self.op_local_get(8);
+        self.op_i32_const(2147483647);
+        self.op_i32_and();
+        self.op_i32_const(2146435072);
+        self.op_i32_gt_u();
+        self.op_local_get(9);
+        self.op_i32_const(2147483647);
+        self.op_i32_and();
+        self.op_i32_const(2146435072);
+        self.op_i32_eq();
+        self.op_local_get(11);
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_i32_and();
+        self.op_i32_or();
+        self.op_i32_or();
+        self.op_i32_eqz();
+        self.op_br_if_eqz(49);
+        self.op_local_get(9);
+        self.op_local_get(10);
+        self.op_i32_const(31);
+        self.op_i32_shr_s();
+        self.op_i32_const(1);
+        self.op_i32_shr_u();
+        self.op_i32_xor();
+        self.op_local_set(5);
+        self.op_local_get(7);
+        self.op_local_get(8);
+        self.op_i32_const(31);
+        self.op_i32_shr_s();
+        self.op_i32_const(1);
+        self.op_i32_shr_u();
+        self.op_i32_xor();
+        self.op_local_set(4);
+        self.op_local_get(5);
+        self.op_local_get(5);
+        self.op_i32_lt_s();
+        self.op_local_get(6);
+        self.op_local_get(6);
+        self.op_i32_eq();
+        self.op_local_get(12);
+        self.op_local_get(12);
+        self.op_i32_const(31);
+        self.op_i32_shr_s();
+        self.op_i32_xor();
+        self.op_local_get(11);
+        self.op_local_get(11);
+        self.op_i32_const(31);
+        self.op_i32_shr_s();
+        self.op_i32_xor();
+        self.op_i32_lt_u();
+        self.op_i32_and();
+        self.op_i32_or();
+        self.op_local_get(7);
+        self.op_i32_xor();
+        self.op_local_set(3);
+        self.op_local_get(10);
+        self.op_local_get(9);
+        self.op_local_get(5);
+        self.op_select();
+        self.op_local_set(2);
+        self.op_local_get(9);
+        self.op_local_get(8);
+        self.op_local_get(5);
+        self.op_select();
+        self.op_local_set(1);
+        self.op_local_get(2);
+        self.op_local_get(2);
+        self.op_local_set(10);
+        self.op_local_set(10);
+        self.op_bulk_drop(8);
+    }
+
+    /// Compares two `f64` values (`lhs_lo`, `lhs_hi`, `rhs_lo`, `rhs_hi`, `mask`).
+    ///
+    /// Returns bit `n` of `mask`, where `n` is 0 for less, 1 for equal, 2 for greater and 3 for
+    /// unordered operands.
+    ///
+    /// Max stack height: 10
+    pub fn op_soft_f64_compare(&mut self) {
+        self.op_bulk_const(5);
+        self.op_i32_const(3);
+        self.op_local_set(1);
+        self.op_local_get(9);
+        self.op_i32_const(2147483647);
+        self.op_i32_and();
+        self.op_i32_const(2146435072);
+        self.op_i32_gt_u();
+        self.op_local_get(10);
+        self.op_i32_const(2147483647);
+        self.op_i32_and();
+        self.op_i32_const(2146435072);
+        self.op_i32_eq();
+        self.op_local_get(12);
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_i32_and();
+        self.op_i32_or();
+        self.op_local_get(8);
+        self.op_i32_const(2147483647);
+        self.op_i32_and();
+        self.op_i32_const(2146435072);
+        self.op_i32_gt_u();
+        self.op_local_get(9);
+        self.op_i32_const(2147483647);
+        self.op_i32_and();
+        self.op_i32_const(2146435072);
+        self.op_i32_eq();
+        self.op_local_get(11);
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_i32_and();
+        self.op_i32_or();
+        self.op_i32_or();
+        self.op_i32_eqz();
+        self.op_br_if_eqz(67);
+        self.op_i32_const(1);
+        self.op_local_set(1);
+        self.op_local_get(9);
+        self.op_local_get(8);
+        self.op_i32_or();
+        self.op_i32_const(2147483647);
+        self.op_i32_and();
+        self.op_local_get(11);
+        self.op_local_get(10);
+        self.op_i32_or();
+        self.op_i32_or();
+        self.op_br_if_eqz(55);
+        self.op_local_get(9);
+        self.op_local_get(10);
+        self.op_i32_const(31);
+        self.op_i32_shr_s();
+        self.op_i32_const(1);
+        self.op_i32_shr_u();
+        self.op_i32_xor();
+        self.op_local_set(5);
+        self.op_local_get(7);
+        self.op_local_get(8);
+        self.op_i32_const(31);
+        self.op_i32_shr_s();
+        self.op_i32_const(1);
+        self.op_i32_shr_u();
+        self.op_i32_xor();
+        self.op_local_set(4);
+        self.op_local_get(10);
+        self.op_local_get(10);
+        self.op_i32_const(31);
+        self.op_i32_shr_s();
+        self.op_i32_xor();
+        self.op_local_set(3);
+        self.op_local_get(8);
+        self.op_local_get(8);
+        self.op_i32_const(31);
+        self.op_i32_shr_s();
+        self.op_i32_xor();
+        self.op_local_set(2);
+        self.op_i32_const(1);
+        self.op_local_get(6);
+        self.op_local_get(6);
+        self.op_i32_gt_s();
+        self.op_local_get(7);
+        self.op_local_get(7);
+        self.op_i32_eq();
+        self.op_local_get(6);
+        self.op_local_get(6);
+        self.op_i32_gt_u();
+        self.op_i32_and();
+        self.op_i32_or();
+        self.op_i32_add();
+        self.op_local_get(6);
+        self.op_local_get(6);
+        self.op_i32_lt_s();
+        self.op_local_get(7);
+        self.op_local_get(7);
+        self.op_i32_eq();
+        self.op_local_get(6);
+        self.op_local_get(6);
+        self.op_i32_lt_u();
+        self.op_i32_and();
+        self.op_i32_or();
+        self.op_i32_sub();
+        self.op_local_set(1);
+        self.op_local_get(6);
+        self.op_local_get(2);
+        self.op_i32_shr_u();
+        self.op_i32_const(1);
+        self.op_i32_and();
+        self.op_local_set(10);
+        self.op_bulk_drop(9);
+    }
+
+    /// Rounds an `f64` value to an integer (`low`, `high`, `mode`) returning (low, high).
+    ///
+    /// The modes are `ceil` (0), `floor` (1), `trunc` (2) and `nearest` (3).
+    ///
+    /// Max stack height: 16
+    pub fn op_soft_f64_round(&mut self) {
+        self.op_bulk_const(11);
+        self.op_local_get(13);
+        self.op_i32_const(2147483647);
+        self.op_i32_and();
+        self.op_local_set(11);
+        self.op_local_get(11);
+        self.op_i32_const(20);
+        self.op_i32_shr_u();
+        self.op_local_set(10);
+        self.op_i32_const(2146959360);
+        self.op_local_set(1);
+        self.op_local_get(11);
+        self.op_i32_const(2146435072);
+        self.op_i32_gt_u();
+        self.op_local_get(12);
+        self.op_i32_const(2146435072);
+        self.op_i32_eq();
+        self.op_local_get(16);
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_i32_and();
+        self.op_i32_or();
+        self.op_br_if_nez(155);
+        self.op_local_get(14);
+        self.op_local_set(2);
+        self.op_local_get(13);
+        self.op_local_set(1);
+        self.op_local_get(10);
+        self.op_i32_const(1075);
+        self.op_i32_ge_u();
+        self.op_br_if_nez(147);
+        self.op_local_get(11);
+        self.op_local_get(15);
+        self.op_i32_or();
+        self.op_i32_eqz();
+        self.op_br_if_nez(142);
+        self.op_local_get(10);
+        self.op_i32_const(1023);
+        self.op_i32_lt_u();
+        self.op_br_if_eqz(22);
+        self.op_i32_const(0);
+        self.op_local_set(2);
+        self.op_local_get(13);
+        self.op_i32_const(-2147483648);
+        self.op_i32_and();
+        self.op_local_set(1);
+        self.op_i32_const(1072693248);
+        self.op_local_set(8);
+        self.op_local_get(11);
+        self.op_i32_const(1071644672);
+        self.op_i32_gt_u();
+        self.op_local_get(12);
+        self.op_i32_const(1071644672);
+        self.op_i32_eq();
+        self.op_local_get(16);
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_i32_and();
+        self.op_i32_or();
+        self.op_local_set(3);
+        self.op_br(89);
+        self.op_i32_const(1075);
+        self.op_local_get(11);
+        self.op_i32_sub();
+        self.op_local_set(10);
+        self.op_local_get(10);
+        self.op_i32_const(32);
+        self.op_i32_lt_u();
+        self.op_br_if_eqz(6);
+        self.op_i32_const(1);
+        self.op_local_get(11);
+        self.op_i32_shl();
+        self.op_local_set(9);
+        self.op_br(7);
+        self.op_i32_const(1);
+        self.op_local_get(11);
+        self.op_i32_const(32);
+        self.op_i32_sub();
+        self.op_i32_shl();
+        self.op_local_set(8);
+        self.op_local_get(14);
+        self.op_local_get(10);
+        self.op_i32_const(1);
+        self.op_i32_sub();
+        self.op_i32_and();
+        self.op_local_set(7);
+        self.op_local_get(13);
+        self.op_local_get(9);
+        self.op_local_get(10);
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_i32_sub();
+        self.op_i32_and();
+        self.op_local_set(6);
+        self.op_local_get(7);
+        self.op_local_get(7);
+        self.op_i32_or();
+        self.op_i32_eqz();
+        self.op_br_if_nez(79);
+        self.op_local_get(14);
+        self.op_local_get(8);
+        self.op_i32_xor();
+        self.op_local_set(2);
+        self.op_local_get(13);
+        self.op_local_get(7);
+        self.op_i32_xor();
+        self.op_local_set(1);
+        self.op_local_get(9);
+        self.op_i32_const(1);
+        self.op_i32_shr_u();
+        self.op_local_get(9);
+        self.op_i32_const(31);
+        self.op_i32_shl();
+        self.op_i32_or();
+        self.op_local_set(5);
+        self.op_local_get(8);
+        self.op_i32_const(1);
+        self.op_i32_shr_u();
+        self.op_local_set(4);
+        self.op_local_get(6);
+        self.op_local_get(5);
+        self.op_i32_gt_u();
+        self.op_local_get(7);
+        self.op_local_get(6);
+        self.op_i32_eq();
+        self.op_local_get(9);
+        self.op_local_get(8);
+        self.op_i32_gt_u();
+        self.op_i32_and();
+        self.op_i32_or();
+        self.op_local_get(7);
+        self.op_local_get(6);
+        self.op_i32_eq();
+        self.op_local_get(9);
+        self.op_local_get(8);
+        self.op_i32_eq();
+        self.op_i32_and();
+        self.op_local_get(16);
+        self.op_local_get(12);
+        self.op_i32_and();
+        self.op_local_get(16);
+        self.op_local_get(12);
+        self.op_i32_and();
+        self.op_i32_or();
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_i32_and();
+        self.op_i32_or();
+        self.op_local_set(3);
+        self.op_local_get(12);
+        self.op_i32_const(3);
+        self.op_i32_ne();
+        self.op_br_if_eqz(11);
+        self.op_local_get(12);
+        self.op_i32_const(2);
+        self.op_i32_lt_u();
+        self.op_local_get(14);
+        self.op_i32_const(0);
+        self.op_i32_lt_s();
+        self.op_local_get(14);
+        self.op_i32_eq();
+        self.op_i32_and();
+        self.op_local_set(3);
+        self.op_local_get(3);
+        self.op_br_if_eqz(13);
+        self.op_local_get(2);
+        self.op_local_get(10);
+        self.op_i32_add();
+        self.op_local_set(2);
+        self.op_local_get(1);
+        self.op_local_get(9);
+        self.op_i32_add();
+        self.op_local_get(3);
+        self.op_local_get(11);
+        self.op_i32_lt_u();
+        self.op_i32_add();
+        self.op_local_set(1);
+        self.op_local_get(2);
+        self.op_local_get(2);
+        self.op_local_set(14);
+        self.op_local_set(14);
+        self.op_bulk_drop(12);
+    }
+
+    /// Truncates an `f64` value to an integer (`low`, `high`, `flags`) returning (low, high).
+    ///
+    /// The flags are signed (1), `i64` result (2) and saturating (4), the high word of an `i32`
+    /// result must be dropped. Traps with [`TrapCode::BadConversionToInteger`] for NaN and
+    /// [`TrapCode::IntegerOverflow`] for values out of the range unless saturating.
+    ///
+    /// Max stack height: 11
+    pub fn op_soft_f64_to_int(&mut self) {
+        self.op_bulk_const(6);
+        self.op_local_get(8);
+        self.op_i32_const(2147483647);
+        self.op_i32_and();
+        self.op_i32_const(2146435072);
+        self.op_i32_gt_u();
+        self.op_local_get(9);
+        self.op_i32_const(2147483647);
+        self.op_i32_and();
+        self.op_i32_const(2146435072);
+        self.op_i32_eq();
+        self.op_local_get(11);
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_i32_and();
+        self.op_i32_or();
+        self.op_br_if_eqz(8);
+        self.op_local_get(7);
+        self.op_i32_const(4);
+        self.op_i32_and();
+        self.op_i32_eqz();
+        self.op_br_if_eqz(2);
+        self.op_trap(TrapCode::BadConversionToInteger);
+        self.op_br(172);
+        self.op_local_get(8);
+        self.op_i32_const(20);
+        self.op_i32_shr_u();
+        self.op_i32_const(2047);
+        self.op_i32_and();
+        self.op_local_set(6);
+        self.op_local_get(6);
+        self.op_i32_const(1023);
+        self.op_i32_lt_u();
+        self.op_br_if_nez(162);
+        self.op_i32_const(1087);
+        self.op_i32_const(1055);
+        self.op_local_get(9);
+        self.op_i32_const(2);
+        self.op_i32_and();
+        self.op_select();
+        self.op_local_get(8);
+        self.op_i32_const(1);
+        self.op_i32_and();
+        self.op_i32_sub();
+        self.op_local_set(5);
+        self.op_local_get(6);
+        self.op_local_get(6);
+        self.op_i32_lt_u();
+        self.op_local_get(8);
+        self.op_i32_const(1);
+        self.op_i32_and();
+        self.op_local_get(10);
+        self.op_i32_const(0);
+        self.op_i32_ge_s();
+        self.op_i32_or();
+        self.op_i32_and();
+        self.op_local_get(8);
+        self.op_i32_const(1);
+        self.op_i32_and();
+        self.op_local_get(11);
+        self.op_i32_eqz();
+        self.op_i32_and();
+        self.op_local_get(10);
+        self.op_local_get(8);
+        self.op_i32_const(20);
+        self.op_i32_shl();
+        self.op_i32_const(-2147483648);
+        self.op_i32_or();
+        self.op_i32_eq();
+        self.op_i32_and();
+        self.op_i32_or();
+        self.op_i32_eqz();
+        self.op_br_if_eqz(43);
+        self.op_local_get(7);
+        self.op_i32_const(4);
+        self.op_i32_and();
+        self.op_i32_eqz();
+        self.op_br_if_eqz(2);
+        self.op_trap(TrapCode::IntegerOverflow);
+        self.op_local_get(8);
+        self.op_i32_const(0);
+        self.op_i32_lt_s();
+        self.op_br_if_eqz(15);
+        self.op_local_get(7);
+        self.op_i32_const(1);
+        self.op_i32_and();
+        self.op_br_if_eqz(10);
+        self.op_local_get(7);
+        self.op_i32_const(2);
+        self.op_i32_and();
+        self.op_br_if_eqz(4);
+        self.op_i32_const(-2147483648);
+        self.op_local_set(1);
+        self.op_br(3);
+        self.op_i32_const(-2147483648);
+        self.op_local_set(2);
+        self.op_br(18);
+        self.op_i32_const(-1);
+        self.op_local_set(2);
+        self.op_i32_const(-1);
+        self.op_local_set(1);
+        self.op_local_get(7);
+        self.op_i32_const(1);
+        self.op_i32_and();
+        self.op_br_if_eqz(10);
+        self.op_local_get(7);
+        self.op_i32_const(2);
+        self.op_i32_and();
+        self.op_br_if_eqz(4);
+        self.op_i32_const(2147483647);
+        self.op_local_set(1);
+        self.op_br(3);
+        self.op_i32_const(2147483647);
+        self.op_local_set(2);
+        self.op_br(81);
+        self.op_local_get(8);
+        self.op_i32_const(1048575);
+        self.op_i32_and();
+        self.op_i32_const(1048576);
+        self.op_i32_or();
+        self.op_local_set(4);
+        self.op_local_get(6);
+        self.op_i32_const(1075);
+        self.op_i32_sub();
+        self.op_local_set(3);
+        self.op_local_get(3);
+        self.op_i32_const(0);
+        self.op_i32_lt_s();
+        self.op_br_if_eqz(31);
+        self.op_i32_const(0);
+        self.op_local_get(4);
+        self.op_i32_sub();
+        self.op_local_set(3);
+        self.op_local_get(3);
+        self.op_i32_const(32);
+        self.op_i32_ge_u();
+        self.op_br_if_eqz(8);
+        self.op_local_get(4);
+        self.op_local_get(4);
+        self.op_i32_const(32);
+        self.op_i32_sub();
+        self.op_i32_shr_u();
+        self.op_local_set(2);
+        self.op_br(15);
+        self.op_local_get(9);
+        self.op_local_get(4);
+        self.op_i32_shr_u();
+        self.op_local_get(5);
+        self.op_i32_const(32);
+        self.op_local_get(6);
+        self.op_i32_sub();
+        self.op_i32_shl();
+        self.op_i32_or();
+        self.op_local_set(2);
+        self.op_local_get(4);
+        self.op_local_get(4);
+        self.op_i32_shr_u();
+        self.op_local_set(1);
+        self.op_br(21);
+        self.op_local_get(9);
+        self.op_local_set(2);
+        self.op_local_get(4);
+        self.op_local_set(1);
+        self.op_local_get(3);
+        self.op_br_if_eqz(15);
+        self.op_local_get(4);
+        self.op_local_get(4);
+        self.op_i32_shl();
+        self.op_local_get(10);
+        self.op_i32_const(32);
+        self.op_local_get(6);
+        self.op_i32_sub();
+        self.op_i32_shr_u();
+        self.op_i32_or();
+        self.op_local_set(1);
+        self.op_local_get(9);
+        self.op_local_get(4);
+        self.op_i32_shl();
+        self.op_local_set(2);
+        self.op_local_get(8);
+        self.op_i32_const(0);
+        self.op_i32_lt_s();
+        self.op_br_if_eqz(13);
+        self.op_i32_const(0);
+        self.op_local_get(2);
+        self.op_i32_sub();
+        self.op_local_get(3);
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_i32_sub();
+        self.op_local_set(1);
+        self.op_i32_const(0);
+        self.op_local_get(3);
+        self.op_i32_sub();
+        self.op_local_set(2);
+        self.op_local_get(2);
+        self.op_local_get(2);
+        self.op_local_set(9);
+        self.op_local_set(9);
+        self.op_bulk_drop(7);
+    }
+
+    /// Converts an `i64` value to `f64` (`low`, `high`, `is_signed`) returning (low, high).
+    ///
+    /// Max stack height: 9
+    pub fn op_soft_f64_from_int(&mut self) {
+        self.op_bulk_const(5);
+        self.op_local_get(6);
+        self.op_local_get(8);
+        self.op_i32_const(0);
+        self.op_i32_lt_s();
+        self.op_i32_and();
+        self.op_br_if_eqz(15);
+        self.op_i32_const(-2147483648);
+        self.op_local_set(5);
+        self.op_i32_const(0);
+        self.op_local_get(8);
+        self.op_i32_sub();
+        self.op_local_get(9);
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_i32_sub();
+        self.op_local_set(7);
+        self.op_i32_const(0);
+        self.op_local_get(9);
+        self.op_i32_sub();
+        self.op_local_set(8);
+        self.op_local_get(8);
+        self.op_local_get(8);
+        self.op_i32_or();
+        self.op_i32_eqz();
+        self.op_br_if_nez(104);
+        self.op_local_get(7);
+        self.op_i32_clz();
+        self.op_local_get(9);
+        self.op_i32_clz();
+        self.op_i32_const(32);
+        self.op_i32_add();
+        self.op_local_get(9);
+        self.op_select();
+        self.op_local_set(4);
+        self.op_local_get(4);
+        self.op_i32_const(32);
+        self.op_i32_ge_u();
+        self.op_br_if_eqz(10);
+        self.op_local_get(8);
+        self.op_local_get(5);
+        self.op_i32_const(32);
+        self.op_i32_sub();
+        self.op_i32_shl();
+        self.op_local_set(7);
+        self.op_i32_const(0);
+        self.op_local_set(8);
+        self.op_br(17);
+        self.op_local_get(4);
+        self.op_br_if_eqz(15);
+        self.op_local_get(7);
+        self.op_local_get(5);
+        self.op_i32_shl();
+        self.op_local_get(9);
+        self.op_i32_const(32);
+        self.op_local_get(7);
+        self.op_i32_sub();
+        self.op_i32_shr_u();
+        self.op_i32_or();
+        self.op_local_set(7);
+        self.op_local_get(8);
+        self.op_local_get(5);
+        self.op_i32_shl();
+        self.op_local_set(8);
+        self.op_local_get(8);
+        self.op_i32_const(8);
+        self.op_i32_shr_u();
+        self.op_local_get(8);
+        self.op_i32_const(24);
+        self.op_i32_shl();
+        self.op_i32_or();
+        self.op_local_get(9);
+        self.op_i32_const(255);
+        self.op_i32_and();
+        self.op_i32_const(0);
+        self.op_i32_ne();
+        self.op_i32_or();
+        self.op_local_set(8);
+        self.op_local_get(7);
+        self.op_i32_const(8);
+        self.op_i32_shr_u();
+        self.op_local_set(7);
+        self.op_local_get(8);
+        self.op_i32_const(7);
+        self.op_i32_and();
+        self.op_local_set(3);
+        self.op_local_get(8);
+        self.op_i32_const(3);
+        self.op_i32_shr_u();
+        self.op_local_get(8);
+        self.op_i32_const(29);
+        self.op_i32_shl();
+        self.op_i32_or();
+        self.op_local_set(2);
+        self.op_local_get(7);
+        self.op_i32_const(3);
+        self.op_i32_shr_u();
+        self.op_i32_const(1048575);
+        self.op_i32_and();
+        self.op_i32_const(1086);
+        self.op_local_get(6);
+        self.op_i32_sub();
+        self.op_i32_const(20);
+        self.op_i32_shl();
+        self.op_i32_or();
+        self.op_local_get(6);
+        self.op_i32_or();
+        self.op_local_set(1);
+        self.op_local_get(3);
+        self.op_i32_const(4);
+        self.op_i32_gt_u();
+        self.op_local_get(4);
+        self.op_i32_const(4);
+        self.op_i32_eq();
+        self.op_local_get(4);
+        self.op_i32_const(1);
+        self.op_i32_and();
+        self.op_i32_and();
+        self.op_i32_or();
+        self.op_br_if_eqz(10);
+        self.op_local_get(2);
+        self.op_i32_const(1);
+        self.op_i32_add();
+        self.op_local_set(2);
+        self.op_local_get(1);
+        self.op_local_get(3);
+        self.op_i32_eqz();
+        self.op_i32_add();
+        self.op_local_set(1);
+        self.op_local_get(2);
+        self.op_local_get(2);
+        self.op_local_set(8);
+        self.op_local_set(8);
+        self.op_bulk_drop(6);
+    }
+
+    /// Converts an `f32` value to `f64` returning (low, high).
+    ///
+    /// Max stack height: 10
+    pub fn op_soft_f64_promote_f32(&mut self) {
+        self.op_bulk_const(7);
+        self.op_local_get(8);
+        self.op_i32_const(2147483647);
+        self.op_i32_and();
+        self.op_local_set(7);
+        self.op_local_get(8);
+        self.op_i32_const(-2147483648);
+        self.op_i32_and();
+        self.op_local_set(6);
+        self.op_i32_const(2146959360);
+        self.op_local_set(1);
+        self.op_local_get(7);
+        self.op_i32_const(2139095040);
+        self.op_i32_gt_u();
+        self.op_br_if_nez(56);
+        self.op_local_get(6);
+        self.op_i32_const(2146435072);
+        self.op_i32_or();
+        self.op_local_set(1);
+        self.op_local_get(7);
+        self.op_i32_const(2139095040);
+        self.op_i32_eq();
+        self.op_br_if_nez(48);
+        self.op_local_get(6);
+        self.op_local_set(1);
+        self.op_local_get(7);
+        self.op_i32_eqz();
+        self.op_br_if_nez(43);
+        self.op_local_get(7);
+        self.op_i32_const(23);
+        self.op_i32_shr_u();
+        self.op_local_set(5);
+        self.op_local_get(8);
+        self.op_i32_const(8388607);
+        self.op_i32_and();
+        self.op_local_set(4);
+        self.op_local_get(5);
+        self.op_i32_eqz();
+        self.op_br_if_eqz(14);
+        self.op_local_get(4);
+        self.op_i32_clz();
+        self.op_i32_const(8);
+        self.op_i32_sub();
+        self.op_local_set(3);
+        self.op_local_get(4);
+        self.op_local_get(4);
+        self.op_i32_shl();
+        self.op_local_set(4);
+        self.op_i32_const(1);
+        self.op_local_get(4);
+        self.op_i32_sub();
+        self.op_local_set(5);
+        self.op_local_get(4);
+        self.op_i32_const(29);
+        self.op_i32_shl();
+        self.op_local_set(2);
+        self.op_local_get(4);
+        self.op_i32_const(3);
+        self.op_i32_shr_u();
+        self.op_i32_const(1048575);
+        self.op_i32_and();
+        self.op_local_get(6);
+        self.op_i32_const(896);
+        self.op_i32_add();
+        self.op_i32_const(20);
+        self.op_i32_shl();
+        self.op_i32_or();
+        self.op_local_get(7);
+        self.op_i32_or();
+        self.op_local_set(1);
+        self.op_local_get(2);
+        self.op_local_get(2);
+        self.op_local_set(8);
+        self.op_local_set(8);
+        self.op_bulk_drop(6);
+    }
+}
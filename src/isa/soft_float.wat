@@ -0,0 +1,1337 @@
+;; Software floating-point for rWasm builds without the `fpu` feature.
+;;
+;; The functions use only 32-bit integer instructions, an f64 is passed as a pair of i32 words
+;; (low, high). Calls of the `rwasm` intrinsics are replaced with a single instruction:
+;; `i32_mul64` with `I32Mul64`, the traps with `Trap` of the corresponding code.
+;; Every function has exactly one exit, loops have a fixed number of iterations.
+;;
+;; The `op_soft_*` emitters in `soft_f32.rs` and `soft_f64.rs` are the compiled bodies of these
+;; functions (without fuel metering), `tests/soft_float.rs` checks that they are in sync.
+(module
+  (import "rwasm" "i32_mul64" (func $mul64 (param i32 i32) (result i32 i32)))
+  (import "rwasm" "trap_integer_overflow" (func $trap_overflow))
+  (import "rwasm" "trap_bad_conversion" (func $trap_conversion))
+
+  (func (export "main"))
+
+  ;; f32.add (f32.sub negates the rhs)
+  (func $f32_add (param $a i32) (param $b i32) (result i32)
+    (local $aAbs i32) (local $bAbs i32) (local $t i32) (local $aExp i32) (local $bExp i32)
+    (local $aSig i32) (local $bSig i32) (local $shift i32) (local $r i32) (local $rgs i32)
+    (local.set $aAbs (i32.and (local.get $a) (i32.const 0x7fffffff)))
+    (local.set $bAbs (i32.and (local.get $b) (i32.const 0x7fffffff)))
+    (block $exit
+      ;; zeros, infinities and NaNs
+      (if (i32.or
+            (i32.ge_u (i32.sub (local.get $aAbs) (i32.const 1)) (i32.const 0x7f7fffff))
+            (i32.ge_u (i32.sub (local.get $bAbs) (i32.const 1)) (i32.const 0x7f7fffff)))
+        (then
+          (if (i32.or (i32.gt_u (local.get $aAbs) (i32.const 0x7f800000))
+                      (i32.gt_u (local.get $bAbs) (i32.const 0x7f800000)))
+            (then (local.set $r (i32.const 0x7fc00000)) (br $exit)))
+          (if (i32.eq (local.get $aAbs) (i32.const 0x7f800000))
+            (then
+              (local.set $r (select (i32.const 0x7fc00000) (local.get $a)
+                (i32.eq (i32.xor (local.get $a) (local.get $b)) (i32.const 0x80000000))))
+              (br $exit)))
+          (if (i32.eq (local.get $bAbs) (i32.const 0x7f800000))
+            (then (local.set $r (local.get $b)) (br $exit)))
+          (if (i32.eqz (local.get $aAbs))
+            (then
+              (local.set $r (select (i32.and (local.get $a) (local.get $b)) (local.get $b)
+                (i32.eqz (local.get $bAbs))))
+              (br $exit)))
+          (if (i32.eqz (local.get $bAbs))
+            (then (local.set $r (local.get $a)) (br $exit)))))
+      ;; make |a| >= |b|
+      (if (i32.gt_u (local.get $bAbs) (local.get $aAbs))
+        (then
+          (local.set $t (local.get $a))
+          (local.set $a (local.get $b))
+          (local.set $b (local.get $t))))
+      (local.set $aExp (i32.and (i32.shr_u (local.get $a) (i32.const 23)) (i32.const 0xff)))
+      (local.set $bExp (i32.and (i32.shr_u (local.get $b) (i32.const 23)) (i32.const 0xff)))
+      (local.set $aSig (i32.and (local.get $a) (i32.const 0x7fffff)))
+      (local.set $bSig (i32.and (local.get $b) (i32.const 0x7fffff)))
+      (if (i32.eqz (local.get $aExp))
+        (then
+          (local.set $shift (i32.sub (i32.clz (local.get $aSig)) (i32.const 8)))
+          (local.set $aSig (i32.shl (local.get $aSig) (local.get $shift)))
+          (local.set $aExp (i32.sub (i32.const 1) (local.get $shift)))))
+      (if (i32.eqz (local.get $bExp))
+        (then
+          (local.set $shift (i32.sub (i32.clz (local.get $bSig)) (i32.const 8)))
+          (local.set $bSig (i32.shl (local.get $bSig) (local.get $shift)))
+          (local.set $bExp (i32.sub (i32.const 1) (local.get $shift)))))
+      ;; the implicit bit and 3 bits for rounding
+      (local.set $aSig (i32.shl (i32.or (local.get $aSig) (i32.const 0x800000)) (i32.const 3)))
+      (local.set $bSig (i32.shl (i32.or (local.get $bSig) (i32.const 0x800000)) (i32.const 3)))
+      (local.set $shift (i32.sub (local.get $aExp) (local.get $bExp)))
+      (if (local.get $shift)
+        (then
+          (if (i32.lt_u (local.get $shift) (i32.const 32))
+            (then
+              (local.set $bSig (i32.or
+                (i32.shr_u (local.get $bSig) (local.get $shift))
+                (i32.ne (i32.shl (local.get $bSig) (i32.sub (i32.const 32) (local.get $shift)))
+                        (i32.const 0)))))
+            (else (local.set $bSig (i32.const 1))))))
+      (if (i32.lt_s (i32.xor (local.get $a) (local.get $b)) (i32.const 0))
+        (then
+          (local.set $aSig (i32.sub (local.get $aSig) (local.get $bSig)))
+          (if (i32.eqz (local.get $aSig))
+            (then (local.set $r (i32.const 0)) (br $exit)))
+          (if (i32.lt_u (local.get $aSig) (i32.const 0x4000000))
+            (then
+              (local.set $shift (i32.sub (i32.clz (local.get $aSig)) (i32.const 5)))
+              (local.set $aSig (i32.shl (local.get $aSig) (local.get $shift)))
+              (local.set $aExp (i32.sub (local.get $aExp) (local.get $shift))))))
+        (else
+          (local.set $aSig (i32.add (local.get $aSig) (local.get $bSig)))
+          (if (i32.and (local.get $aSig) (i32.const 0x8000000))
+            (then
+              (local.set $aSig (i32.or (i32.shr_u (local.get $aSig) (i32.const 1))
+                                       (i32.and (local.get $aSig) (i32.const 1))))
+              (local.set $aExp (i32.add (local.get $aExp) (i32.const 1)))))))
+      ;; round and pack
+      (local.set $a (i32.and (local.get $a) (i32.const 0x80000000)))
+      (if (i32.ge_s (local.get $aExp) (i32.const 0xff))
+        (then (local.set $r (i32.or (local.get $a) (i32.const 0x7f800000))) (br $exit)))
+      (if (i32.le_s (local.get $aExp) (i32.const 0))
+        (then
+          (local.set $shift (i32.sub (i32.const 1) (local.get $aExp)))
+          (if (i32.lt_u (local.get $shift) (i32.const 32))
+            (then
+              (local.set $aSig (i32.or
+                (i32.shr_u (local.get $aSig) (local.get $shift))
+                (i32.ne (i32.shl (local.get $aSig) (i32.sub (i32.const 32) (local.get $shift)))
+                        (i32.const 0)))))
+            (else (local.set $aSig (i32.ne (local.get $aSig) (i32.const 0)))))
+          (local.set $aExp (i32.const 0))))
+      (local.set $rgs (i32.and (local.get $aSig) (i32.const 7)))
+      (local.set $r (i32.or
+        (i32.or (i32.and (i32.shr_u (local.get $aSig) (i32.const 3)) (i32.const 0x7fffff))
+                (i32.shl (local.get $aExp) (i32.const 23)))
+        (local.get $a)))
+      (if (i32.or (i32.gt_u (local.get $rgs) (i32.const 4))
+                  (i32.and (i32.eq (local.get $rgs) (i32.const 4)) (i32.and (local.get $r) (i32.const 1))))
+        (then (local.set $r (i32.add (local.get $r) (i32.const 1))))))
+    (local.get $r))
+
+  ;; f32.mul
+  (func $f32_mul (param $a i32) (param $b i32) (result i32)
+    (local $aAbs i32) (local $bAbs i32) (local $aExp i32) (local $bExp i32) (local $aSig i32)
+    (local $bSig i32) (local $sign i32) (local $shift i32) (local $lo i32) (local $r i32) (local $rgs i32)
+    (local.set $aAbs (i32.and (local.get $a) (i32.const 0x7fffffff)))
+    (local.set $bAbs (i32.and (local.get $b) (i32.const 0x7fffffff)))
+    (local.set $sign (i32.and (i32.xor (local.get $a) (local.get $b)) (i32.const 0x80000000)))
+    (block $exit
+      (if (i32.or (i32.ge_u (local.get $aAbs) (i32.const 0x7f800000))
+                  (i32.ge_u (local.get $bAbs) (i32.const 0x7f800000)))
+        (then
+          (local.set $r (i32.const 0x7fc00000))
+          (br_if $exit (i32.or (i32.gt_u (local.get $aAbs) (i32.const 0x7f800000))
+                               (i32.gt_u (local.get $bAbs) (i32.const 0x7f800000))))
+          (br_if $exit (i32.or (i32.eqz (local.get $aAbs)) (i32.eqz (local.get $bAbs))))
+          (local.set $r (i32.or (local.get $sign) (i32.const 0x7f800000)))
+          (br $exit)))
+      (local.set $r (local.get $sign))
+      (br_if $exit (i32.or (i32.eqz (local.get $aAbs)) (i32.eqz (local.get $bAbs))))
+      (local.set $aExp (i32.shr_u (local.get $aAbs) (i32.const 23)))
+      (local.set $bExp (i32.shr_u (local.get $bAbs) (i32.const 23)))
+      (local.set $aSig (i32.and (local.get $a) (i32.const 0x7fffff)))
+      (local.set $bSig (i32.and (local.get $b) (i32.const 0x7fffff)))
+      (if (i32.eqz (local.get $aExp))
+        (then
+          (local.set $shift (i32.sub (i32.clz (local.get $aSig)) (i32.const 8)))
+          (local.set $aSig (i32.shl (local.get $aSig) (local.get $shift)))
+          (local.set $aExp (i32.sub (i32.const 1) (local.get $shift)))))
+      (if (i32.eqz (local.get $bExp))
+        (then
+          (local.set $shift (i32.sub (i32.clz (local.get $bSig)) (i32.const 8)))
+          (local.set $bSig (i32.shl (local.get $bSig) (local.get $shift)))
+          (local.set $bExp (i32.sub (i32.const 1) (local.get $shift)))))
+      ;; the 48-bit product has the implicit bit at 46 or 47, keep 27 bits with a sticky bit
+      (call $mul64 (i32.or (local.get $aSig) (i32.const 0x800000))
+                   (i32.or (local.get $bSig) (i32.const 0x800000)))
+      (local.set $aSig)
+      (local.set $lo)
+      (local.set $aSig (i32.or
+        (i32.or (i32.shl (local.get $aSig) (i32.const 12)) (i32.shr_u (local.get $lo) (i32.const 20)))
+        (i32.ne (i32.and (local.get $lo) (i32.const 0xfffff)) (i32.const 0))))
+      (local.set $aExp (i32.sub (i32.add (local.get $aExp) (local.get $bExp)) (i32.const 127)))
+      (if (i32.and (local.get $aSig) (i32.const 0x8000000))
+        (then
+          (local.set $aSig (i32.or (i32.shr_u (local.get $aSig) (i32.const 1))
+                                   (i32.and (local.get $aSig) (i32.const 1))))
+          (local.set $aExp (i32.add (local.get $aExp) (i32.const 1)))))
+      ;; round and pack
+      (if (i32.ge_s (local.get $aExp) (i32.const 0xff))
+        (then (local.set $r (i32.or (local.get $sign) (i32.const 0x7f800000))) (br $exit)))
+      (if (i32.le_s (local.get $aExp) (i32.const 0))
+        (then
+          (local.set $shift (i32.sub (i32.const 1) (local.get $aExp)))
+          (if (i32.lt_u (local.get $shift) (i32.const 32))
+            (then
+              (local.set $aSig (i32.or
+                (i32.shr_u (local.get $aSig) (local.get $shift))
+                (i32.ne (i32.shl (local.get $aSig) (i32.sub (i32.const 32) (local.get $shift)))
+                        (i32.const 0)))))
+            (else (local.set $aSig (i32.ne (local.get $aSig) (i32.const 0)))))
+          (local.set $aExp (i32.const 0))))
+      (local.set $rgs (i32.and (local.get $aSig) (i32.const 7)))
+      (local.set $r (i32.or
+        (i32.or (i32.and (i32.shr_u (local.get $aSig) (i32.const 3)) (i32.const 0x7fffff))
+                (i32.shl (local.get $aExp) (i32.const 23)))
+        (local.get $sign)))
+      (if (i32.or (i32.gt_u (local.get $rgs) (i32.const 4))
+                  (i32.and (i32.eq (local.get $rgs) (i32.const 4)) (i32.and (local.get $r) (i32.const 1))))
+        (then (local.set $r (i32.add (local.get $r) (i32.const 1))))))
+    (local.get $r))
+
+  ;; f32.div
+  (func $f32_div (param $a i32) (param $b i32) (result i32)
+    (local $aAbs i32) (local $bAbs i32) (local $aExp i32) (local $bExp i32) (local $aSig i32)
+    (local $bSig i32) (local $sign i32) (local $shift i32) (local $q i32) (local $i i32) (local $r i32)
+    (local $rgs i32)
+    (local.set $aAbs (i32.and (local.get $a) (i32.const 0x7fffffff)))
+    (local.set $bAbs (i32.and (local.get $b) (i32.const 0x7fffffff)))
+    (local.set $sign (i32.and (i32.xor (local.get $a) (local.get $b)) (i32.const 0x80000000)))
+    (block $exit
+      (local.set $r (i32.const 0x7fc00000))
+      (br_if $exit (i32.or (i32.gt_u (local.get $aAbs) (i32.const 0x7f800000))
+                           (i32.gt_u (local.get $bAbs) (i32.const 0x7f800000))))
+      (if (i32.eq (local.get $aAbs) (i32.const 0x7f800000))
+        (then
+          (br_if $exit (i32.eq (local.get $bAbs) (i32.const 0x7f800000)))
+          (local.set $r (i32.or (local.get $sign) (i32.const 0x7f800000)))
+          (br $exit)))
+      (if (i32.eqz (local.get $aAbs))
+        (then
+          (br_if $exit (i32.eqz (local.get $bAbs)))
+          (local.set $r (local.get $sign))
+          (br $exit)))
+      (local.set $r (local.get $sign))
+      (br_if $exit (i32.eq (local.get $bAbs) (i32.const 0x7f800000)))
+      (if (i32.eqz (local.get $bAbs))
+        (then (local.set $r (i32.or (local.get $sign) (i32.const 0x7f800000))) (br $exit)))
+      (local.set $aExp (i32.shr_u (local.get $aAbs) (i32.const 23)))
+      (local.set $bExp (i32.shr_u (local.get $bAbs) (i32.const 23)))
+      (local.set $aSig (i32.and (local.get $a) (i32.const 0x7fffff)))
+      (local.set $bSig (i32.and (local.get $b) (i32.const 0x7fffff)))
+      (if (i32.eqz (local.get $aExp))
+        (then
+          (local.set $shift (i32.sub (i32.clz (local.get $aSig)) (i32.const 8)))
+          (local.set $aSig (i32.shl (local.get $aSig) (local.get $shift)))
+          (local.set $aExp (i32.sub (i32.const 1) (local.get $shift)))))
+      (if (i32.eqz (local.get $bExp))
+        (then
+          (local.set $shift (i32.sub (i32.clz (local.get $bSig)) (i32.const 8)))
+          (local.set $bSig (i32.shl (local.get $bSig) (local.get $shift)))
+          (local.set $bExp (i32.sub (i32.const 1) (local.get $shift)))))
+      (local.set $aSig (i32.or (local.get $aSig) (i32.const 0x800000)))
+      (local.set $bSig (i32.or (local.get $bSig) (i32.const 0x800000)))
+      (local.set $aExp (i32.add (i32.sub (local.get $aExp) (local.get $bExp)) (i32.const 127)))
+      (if (i32.lt_u (local.get $aSig) (local.get $bSig))
+        (then
+          (local.set $aSig (i32.shl (local.get $aSig) (i32.const 1)))
+          (local.set $aExp (i32.sub (local.get $aExp) (i32.const 1)))))
+      ;; 27 quotient bits by long division, the remainder is the sticky bit
+      (local.set $i (i32.const 27))
+      (loop $continue
+        (local.set $q (i32.shl (local.get $q) (i32.const 1)))
+        (if (i32.ge_u (local.get $aSig) (local.get $bSig))
+          (then
+            (local.set $aSig (i32.sub (local.get $aSig) (local.get $bSig)))
+            (local.set $q (i32.or (local.get $q) (i32.const 1)))))
+        (local.set $aSig (i32.shl (local.get $aSig) (i32.const 1)))
+        (local.set $i (i32.sub (local.get $i) (i32.const 1)))
+        (br_if $continue (local.get $i)))
+      (local.set $aSig (i32.or (local.get $q) (i32.ne (local.get $aSig) (i32.const 0))))
+      ;; round and pack
+      (if (i32.ge_s (local.get $aExp) (i32.const 0xff))
+        (then (local.set $r (i32.or (local.get $sign) (i32.const 0x7f800000))) (br $exit)))
+      (if (i32.le_s (local.get $aExp) (i32.const 0))
+        (then
+          (local.set $shift (i32.sub (i32.const 1) (local.get $aExp)))
+          (if (i32.lt_u (local.get $shift) (i32.const 32))
+            (then
+              (local.set $aSig (i32.or
+                (i32.shr_u (local.get $aSig) (local.get $shift))
+                (i32.ne (i32.shl (local.get $aSig) (i32.sub (i32.const 32) (local.get $shift)))
+                        (i32.const 0)))))
+            (else (local.set $aSig (i32.ne (local.get $aSig) (i32.const 0)))))
+          (local.set $aExp (i32.const 0))))
+      (local.set $rgs (i32.and (local.get $aSig) (i32.const 7)))
+      (local.set $r (i32.or
+        (i32.or (i32.and (i32.shr_u (local.get $aSig) (i32.const 3)) (i32.const 0x7fffff))
+                (i32.shl (local.get $aExp) (i32.const 23)))
+        (local.get $sign)))
+      (if (i32.or (i32.gt_u (local.get $rgs) (i32.const 4))
+                  (i32.and (i32.eq (local.get $rgs) (i32.const 4)) (i32.and (local.get $r) (i32.const 1))))
+        (then (local.set $r (i32.add (local.get $r) (i32.const 1))))))
+    (local.get $r))
+
+  ;; f32.sqrt
+  (func $f32_sqrt (param $a i32) (result i32)
+    (local $exp i32) (local $sig i32) (local $shift i32) (local $q i32) (local $rem i32) (local $t i32)
+    (local $i i32) (local $r i32)
+    (block $exit
+      (local.set $r (local.get $a))
+      ;; +-0 and +inf are returned as is
+      (br_if $exit (i32.eqz (i32.and (local.get $a) (i32.const 0x7fffffff))))
+      (br_if $exit (i32.eq (local.get $a) (i32.const 0x7f800000)))
+      (local.set $r (i32.const 0x7fc00000))
+      (br_if $exit (i32.gt_u (local.get $a) (i32.const 0x7f800000)))
+      (local.set $exp (i32.shr_u (local.get $a) (i32.const 23)))
+      (local.set $sig (i32.and (local.get $a) (i32.const 0x7fffff)))
+      (if (i32.eqz (local.get $exp))
+        (then
+          (local.set $shift (i32.sub (i32.clz (local.get $sig)) (i32.const 8)))
+          (local.set $sig (i32.shl (local.get $sig) (local.get $shift)))
+          (local.set $exp (i32.sub (i32.const 1) (local.get $shift)))))
+      ;; the unbiased exponent must be even, the significand is in [2^24, 2^26)
+      (local.set $exp (i32.sub (local.get $exp) (i32.const 127)))
+      (local.set $sig (i32.shl (i32.or (local.get $sig) (i32.const 0x800000))
+                               (i32.add (i32.and (local.get $exp) (i32.const 1)) (i32.const 1))))
+      (local.set $exp (i32.add (i32.shr_s (local.get $exp) (i32.const 1)) (i32.const 127)))
+      ;; 26 root bits, two bits of the significand per iteration
+      (local.set $i (i32.const 26))
+      (loop $continue
+        (local.set $rem (i32.or (i32.shl (local.get $rem) (i32.const 2))
+                                (i32.shr_u (local.get $sig) (i32.const 24))))
+        (local.set $sig (i32.and (i32.shl (local.get $sig) (i32.const 2)) (i32.const 0x3ffffff)))
+        (local.set $t (i32.or (i32.shl (local.get $q) (i32.const 2)) (i32.const 1)))
+        (local.set $q (i32.shl (local.get $q) (i32.const 1)))
+        (if (i32.ge_u (local.get $rem) (local.get $t))
+          (then
+            (local.set $rem (i32.sub (local.get $rem) (local.get $t)))
+            (local.set $q (i32.or (local.get $q) (i32.const 1)))))
+        (local.set $i (i32.sub (local.get $i) (i32.const 1)))
+        (br_if $continue (local.get $i)))
+      (local.set $sig (i32.or (i32.shl (local.get $q) (i32.const 1))
+                              (i32.ne (local.get $rem) (i32.const 0))))
+      ;; a square root is never a tie
+      (local.set $r (i32.add
+        (i32.or (i32.and (i32.shr_u (local.get $sig) (i32.const 3)) (i32.const 0x7fffff))
+                (i32.shl (local.get $exp) (i32.const 23)))
+        (i32.gt_u (i32.and (local.get $sig) (i32.const 7)) (i32.const 4)))))
+    (local.get $r))
+
+  ;; f32.min and f32.max
+  (func $f32_min_max (param $a i32) (param $b i32) (param $max i32) (result i32)
+    (local $r i32)
+    (local.set $r (i32.const 0x7fc00000))
+    (if (i32.and (i32.le_u (i32.and (local.get $a) (i32.const 0x7fffffff)) (i32.const 0x7f800000))
+                 (i32.le_u (i32.and (local.get $b) (i32.const 0x7fffffff)) (i32.const 0x7f800000)))
+      (then
+        ;; the total order keys make -0 less than +0
+        (local.set $r (select (local.get $a) (local.get $b)
+          (i32.xor
+            (i32.lt_s
+              (i32.xor (local.get $a) (i32.shr_u (i32.shr_s (local.get $a) (i32.const 31)) (i32.const 1)))
+              (i32.xor (local.get $b) (i32.shr_u (i32.shr_s (local.get $b) (i32.const 31)) (i32.const 1))))
+            (local.get $max))))))
+    (local.get $r))
+
+  ;; f32 comparisons, bit `n` of the mask is the result for less (0), equal (1), greater (2) and
+  ;; unordered (3) operands
+  (func $f32_compare (param $a i32) (param $b i32) (param $mask i32) (result i32)
+    (local $aKey i32) (local $bKey i32) (local $c i32)
+    (local.set $c (i32.const 3))
+    (if (i32.and (i32.le_u (i32.and (local.get $a) (i32.const 0x7fffffff)) (i32.const 0x7f800000))
+                 (i32.le_u (i32.and (local.get $b) (i32.const 0x7fffffff)) (i32.const 0x7f800000)))
+      (then
+        (local.set $c (i32.const 1))
+        (if (i32.and (i32.or (local.get $a) (local.get $b)) (i32.const 0x7fffffff))
+          (then
+            (local.set $aKey
+              (i32.xor (local.get $a) (i32.shr_u (i32.shr_s (local.get $a) (i32.const 31)) (i32.const 1))))
+            (local.set $bKey
+              (i32.xor (local.get $b) (i32.shr_u (i32.shr_s (local.get $b) (i32.const 31)) (i32.const 1))))
+            (local.set $c (i32.sub
+              (i32.add (i32.const 1) (i32.gt_s (local.get $aKey) (local.get $bKey)))
+              (i32.lt_s (local.get $aKey) (local.get $bKey))))))))
+    (i32.and (i32.shr_u (local.get $mask) (local.get $c)) (i32.const 1)))
+
+  ;; f32.ceil (0), f32.floor (1), f32.trunc (2) and f32.nearest (3)
+  (func $f32_round (param $a i32) (param $mode i32) (result i32)
+    (local $aAbs i32) (local $exp i32) (local $one i32) (local $rem i32) (local $half i32)
+    (local $up i32) (local $r i32)
+    (local.set $aAbs (i32.and (local.get $a) (i32.const 0x7fffffff)))
+    (local.set $exp (i32.shr_u (local.get $aAbs) (i32.const 23)))
+    (block $exit
+      (local.set $r (i32.const 0x7fc00000))
+      (br_if $exit (i32.gt_u (local.get $aAbs) (i32.const 0x7f800000)))
+      ;; integers, infinities and zeros
+      (local.set $r (local.get $a))
+      (br_if $exit (i32.ge_u (local.get $exp) (i32.const 150)))
+      (br_if $exit (i32.eqz (local.get $aAbs)))
+      (if (i32.lt_u (local.get $exp) (i32.const 127))
+        (then
+          (local.set $r (i32.and (local.get $a) (i32.const 0x80000000)))
+          (local.set $one (i32.const 0x3f800000))
+          (local.set $up (i32.gt_u (local.get $aAbs) (i32.const 0x3f000000))))
+        (else
+          (local.set $one (i32.shl (i32.const 1) (i32.sub (i32.const 150) (local.get $exp))))
+          (local.set $rem (i32.and (local.get $aAbs) (i32.sub (local.get $one) (i32.const 1))))
+          (br_if $exit (i32.eqz (local.get $rem)))
+          (local.set $r (i32.sub (local.get $a) (local.get $rem)))
+          (local.set $half (i32.shr_u (local.get $one) (i32.const 1)))
+          (local.set $up (i32.or
+            (i32.gt_u (local.get $rem) (local.get $half))
+            (i32.and (i32.eq (local.get $rem) (local.get $half))
+                     (i32.ne (i32.and (local.get $aAbs) (local.get $one)) (i32.const 0)))))))
+      (if (i32.ne (local.get $mode) (i32.const 3))
+        (then
+          (local.set $up (i32.and
+            (i32.lt_u (local.get $mode) (i32.const 2))
+            (i32.eq (i32.lt_s (local.get $a) (i32.const 0)) (local.get $mode))))))
+      (if (local.get $up)
+        (then (local.set $r (i32.add (local.get $r) (local.get $one))))))
+    (local.get $r))
+
+  ;; i32 and i64 truncations of f32, the flags are signed (1), i64 (2) and saturating (4)
+  (func $f32_to_int (param $a i32) (param $flags i32) (result i32 i32)
+    (local $exp i32) (local $max i32) (local $sig i32) (local $shift i32) (local $lo i32) (local $hi i32)
+    (block $exit
+      (if (i32.gt_u (i32.and (local.get $a) (i32.const 0x7fffffff)) (i32.const 0x7f800000))
+        (then
+          (if (i32.eqz (i32.and (local.get $flags) (i32.const 4)))
+            (then (call $trap_conversion)))
+          (br $exit)))
+      (local.set $exp (i32.and (i32.shr_u (local.get $a) (i32.const 23)) (i32.const 0xff)))
+      (br_if $exit (i32.lt_u (local.get $exp) (i32.const 127)))
+      ;; the smallest exponent out of the range
+      (local.set $max (i32.sub
+        (select (i32.const 191) (i32.const 159) (i32.and (local.get $flags) (i32.const 2)))
+        (i32.and (local.get $flags) (i32.const 1))))
+      (if (i32.eqz (i32.or
+            (i32.and (i32.lt_u (local.get $exp) (local.get $max))
+                     (i32.or (i32.and (local.get $flags) (i32.const 1))
+                             (i32.ge_s (local.get $a) (i32.const 0))))
+            (i32.and (i32.and (local.get $flags) (i32.const 1))
+                     (i32.eq (local.get $a)
+                             (i32.or (i32.shl (local.get $max) (i32.const 23)) (i32.const 0x80000000))))))
+        (then
+          (if (i32.eqz (i32.and (local.get $flags) (i32.const 4)))
+            (then (call $trap_overflow)))
+          (if (i32.lt_s (local.get $a) (i32.const 0))
+            (then
+              (if (i32.and (local.get $flags) (i32.const 1))
+                (then
+                  (if (i32.and (local.get $flags) (i32.const 2))
+                    (then (local.set $hi (i32.const 0x80000000)))
+                    (else (local.set $lo (i32.const 0x80000000)))))))
+            (else
+              (local.set $lo (i32.const -1))
+              (local.set $hi (i32.const -1))
+              (if (i32.and (local.get $flags) (i32.const 1))
+                (then
+                  (if (i32.and (local.get $flags) (i32.const 2))
+                    (then (local.set $hi (i32.const 0x7fffffff)))
+                    (else (local.set $lo (i32.const 0x7fffffff))))))))
+          (br $exit)))
+      (local.set $sig (i32.or (i32.and (local.get $a) (i32.const 0x7fffff)) (i32.const 0x800000)))
+      (local.set $shift (i32.sub (local.get $exp) (i32.const 150)))
+      (if (i32.lt_s (local.get $shift) (i32.const 0))
+        (then (local.set $lo (i32.shr_u (local.get $sig) (i32.sub (i32.const 0) (local.get $shift)))))
+        (else
+          (if (i32.ge_u (local.get $shift) (i32.const 32))
+            (then (local.set $hi (i32.shl (local.get $sig) (i32.sub (local.get $shift) (i32.const 32)))))
+            (else
+              (local.set $lo (i32.shl (local.get $sig) (local.get $shift)))
+              (if (local.get $shift)
+                (then
+                  (local.set $hi (i32.shr_u (local.get $sig) (i32.sub (i32.const 32) (local.get $shift))))))))))
+      (if (i32.lt_s (local.get $a) (i32.const 0))
+        (then
+          (local.set $hi (i32.sub (i32.sub (i32.const 0) (local.get $hi)) (i32.ne (local.get $lo) (i32.const 0))))
+          (local.set $lo (i32.sub (i32.const 0) (local.get $lo))))))
+    (local.get $lo)
+    (local.get $hi))
+
+  ;; f32 conversions of i64, i32 operands are extended first
+  (func $f32_from_int (param $lo i32) (param $hi i32) (param $signed i32) (result i32)
+    (local $sign i32) (local $n i32) (local $sig i32) (local $r i32)
+    (block $exit
+      (if (i32.and (local.get $signed) (i32.lt_s (local.get $hi) (i32.const 0)))
+        (then
+          (local.set $sign (i32.const 0x80000000))
+          (local.set $hi (i32.sub (i32.sub (i32.const 0) (local.get $hi)) (i32.ne (local.get $lo) (i32.const 0))))
+          (local.set $lo (i32.sub (i32.const 0) (local.get $lo)))))
+      (br_if $exit (i32.eqz (i32.or (local.get $lo) (local.get $hi))))
+      (local.set $n (select (i32.clz (local.get $hi)) (i32.add (i32.clz (local.get $lo)) (i32.const 32))
+                            (local.get $hi)))
+      ;; the 32 most significant bits with a sticky bit
+      (if (i32.ge_u (local.get $n) (i32.const 32))
+        (then (local.set $sig (i32.shl (local.get $lo) (i32.sub (local.get $n) (i32.const 32)))))
+        (else
+          (local.set $sig (local.get $hi))
+          (if (local.get $n)
+            (then
+              (local.set $sig (i32.or (i32.shl (local.get $hi) (local.get $n))
+                                      (i32.shr_u (local.get $lo) (i32.sub (i32.const 32) (local.get $n)))))
+              (local.set $lo (i32.shl (local.get $lo) (local.get $n)))))
+          (local.set $sig (i32.or (local.get $sig) (i32.ne (local.get $lo) (i32.const 0))))))
+      (local.set $sig (i32.or (i32.shr_u (local.get $sig) (i32.const 5))
+                              (i32.ne (i32.and (local.get $sig) (i32.const 31)) (i32.const 0))))
+      (local.set $r (i32.or
+        (i32.or (i32.and (i32.shr_u (local.get $sig) (i32.const 3)) (i32.const 0x7fffff))
+                (i32.shl (i32.sub (i32.const 190) (local.get $n)) (i32.const 23)))
+        (local.get $sign)))
+      (if (i32.or (i32.gt_u (i32.and (local.get $sig) (i32.const 7)) (i32.const 4))
+                  (i32.and (i32.eq (i32.and (local.get $sig) (i32.const 7)) (i32.const 4))
+                           (i32.and (local.get $r) (i32.const 1))))
+        (then (local.set $r (i32.add (local.get $r) (i32.const 1))))))
+    (local.get $r))
+
+  ;; f32.demote_f64
+  (func $f32_demote_f64 (param $lo i32) (param $hi i32) (result i32)
+    (local $abs i32) (local $exp i32) (local $sig i32) (local $sign i32) (local $shift i32) (local $r i32)
+    (local $rgs i32)
+    (local.set $abs (i32.and (local.get $hi) (i32.const 0x7fffffff)))
+    (local.set $sign (i32.and (local.get $hi) (i32.const 0x80000000)))
+    (block $exit
+      (local.set $r (i32.const 0x7fc00000))
+      (br_if $exit (i32.or (i32.gt_u (local.get $abs) (i32.const 0x7ff00000))
+                           (i32.and (i32.eq (local.get $abs) (i32.const 0x7ff00000))
+                                    (i32.ne (local.get $lo) (i32.const 0)))))
+      (local.set $r (i32.or (local.get $sign) (i32.const 0x7f800000)))
+      (br_if $exit (i32.eq (local.get $abs) (i32.const 0x7ff00000)))
+      (local.set $r (local.get $sign))
+      (br_if $exit (i32.eqz (i32.or (local.get $abs) (local.get $lo))))
+      ;; subnormals are far below the f32 range, they only need a sticky bit
+      (local.set $exp (i32.shr_u (local.get $abs) (i32.const 20)))
+      (local.set $sig (i32.and (local.get $hi) (i32.const 0xfffff)))
+      (if (local.get $exp)
+        (then (local.set $sig (i32.or (local.get $sig) (i32.const 0x100000))))
+        (else (local.set $exp (i32.const 1))))
+      (local.set $sig (i32.or
+        (i32.or (i32.shl (local.get $sig) (i32.const 6)) (i32.shr_u (local.get $lo) (i32.const 26)))
+        (i32.ne (i32.and (local.get $lo) (i32.const 0x3ffffff)) (i32.const 0))))
+      (local.set $exp (i32.sub (local.get $exp) (i32.const 896)))
+      ;; round and pack
+      (if (i32.ge_s (local.get $exp) (i32.const 0xff))
+        (then (local.set $r (i32.or (local.get $sign) (i32.const 0x7f800000))) (br $exit)))
+      (if (i32.le_s (local.get $exp) (i32.const 0))
+        (then
+          (local.set $shift (i32.sub (i32.const 1) (local.get $exp)))
+          (if (i32.lt_u (local.get $shift) (i32.const 32))
+            (then
+              (local.set $sig (i32.or
+                (i32.shr_u (local.get $sig) (local.get $shift))
+                (i32.ne (i32.shl (local.get $sig) (i32.sub (i32.const 32) (local.get $shift)))
+                        (i32.const 0)))))
+            (else (local.set $sig (i32.ne (local.get $sig) (i32.const 0)))))
+          (local.set $exp (i32.const 0))))
+      (local.set $rgs (i32.and (local.get $sig) (i32.const 7)))
+      (local.set $r (i32.or
+        (i32.or (i32.and (i32.shr_u (local.get $sig) (i32.const 3)) (i32.const 0x7fffff))
+                (i32.shl (local.get $exp) (i32.const 23)))
+        (local.get $sign)))
+      (if (i32.or (i32.gt_u (local.get $rgs) (i32.const 4))
+                  (i32.and (i32.eq (local.get $rgs) (i32.const 4)) (i32.and (local.get $r) (i32.const 1))))
+        (then (local.set $r (i32.add (local.get $r) (i32.const 1))))))
+    (local.get $r))
+
+  ;; f64.promote_f32
+  (func $f64_promote_f32 (param $a i32) (result i32 i32)
+    (local $abs i32) (local $sign i32) (local $exp i32) (local $sig i32) (local $shift i32)
+    (local $lo i32) (local $hi i32)
+    (local.set $abs (i32.and (local.get $a) (i32.const 0x7fffffff)))
+    (local.set $sign (i32.and (local.get $a) (i32.const 0x80000000)))
+    (block $exit
+      (local.set $hi (i32.const 0x7ff80000))
+      (br_if $exit (i32.gt_u (local.get $abs) (i32.const 0x7f800000)))
+      (local.set $hi (i32.or (local.get $sign) (i32.const 0x7ff00000)))
+      (br_if $exit (i32.eq (local.get $abs) (i32.const 0x7f800000)))
+      (local.set $hi (local.get $sign))
+      (br_if $exit (i32.eqz (local.get $abs)))
+      (local.set $exp (i32.shr_u (local.get $abs) (i32.const 23)))
+      (local.set $sig (i32.and (local.get $a) (i32.const 0x7fffff)))
+      (if (i32.eqz (local.get $exp))
+        (then
+          (local.set $shift (i32.sub (i32.clz (local.get $sig)) (i32.const 8)))
+          (local.set $sig (i32.shl (local.get $sig) (local.get $shift)))
+          (local.set $exp (i32.sub (i32.const 1) (local.get $shift)))))
+      (local.set $lo (i32.shl (local.get $sig) (i32.const 29)))
+      (local.set $hi (i32.or
+        (i32.or (i32.and (i32.shr_u (local.get $sig) (i32.const 3)) (i32.const 0xfffff))
+                (i32.shl (i32.add (local.get $exp) (i32.const 896)) (i32.const 20)))
+        (local.get $sign))))
+    (local.get $lo)
+    (local.get $hi))
+
+  ;; f64.add (f64.sub negates the rhs)
+  (func $f64_add (param $alo i32) (param $ahi i32) (param $blo i32) (param $bhi i32) (result i32 i32)
+    (local $aAbs i32) (local $bAbs i32) (local $t i32) (local $aExp i32) (local $bExp i32)
+    (local $aSigHi i32) (local $bSigHi i32) (local $shift i32) (local $sticky i32)
+    (local $rlo i32) (local $rhi i32) (local $rgs i32)
+    (local.set $aAbs (i32.and (local.get $ahi) (i32.const 0x7fffffff)))
+    (local.set $bAbs (i32.and (local.get $bhi) (i32.const 0x7fffffff)))
+    (block $exit
+      ;; zeros, infinities and NaNs
+      (if (i32.or
+            (i32.or (i32.ge_u (local.get $aAbs) (i32.const 0x7ff00000))
+                    (i32.eqz (i32.or (local.get $aAbs) (local.get $alo))))
+            (i32.or (i32.ge_u (local.get $bAbs) (i32.const 0x7ff00000))
+                    (i32.eqz (i32.or (local.get $bAbs) (local.get $blo)))))
+        (then
+          (local.set $rhi (i32.const 0x7ff80000))
+          (br_if $exit (i32.or
+            (i32.or (i32.gt_u (local.get $aAbs) (i32.const 0x7ff00000))
+                    (i32.and (i32.eq (local.get $aAbs) (i32.const 0x7ff00000)) (i32.ne (local.get $alo) (i32.const 0))))
+            (i32.or (i32.gt_u (local.get $bAbs) (i32.const 0x7ff00000))
+                    (i32.and (i32.eq (local.get $bAbs) (i32.const 0x7ff00000)) (i32.ne (local.get $blo) (i32.const 0))))))
+          (if (i32.eq (local.get $aAbs) (i32.const 0x7ff00000))
+            (then
+              (br_if $exit (i32.and (i32.eq (local.get $bAbs) (i32.const 0x7ff00000))
+                                    (i32.lt_s (i32.xor (local.get $ahi) (local.get $bhi)) (i32.const 0))))
+              (local.set $rhi (local.get $ahi))
+              (br $exit)))
+          (if (i32.eq (local.get $bAbs) (i32.const 0x7ff00000))
+            (then (local.set $rhi (local.get $bhi)) (br $exit)))
+          (if (i32.eqz (i32.or (local.get $aAbs) (local.get $alo)))
+            (then
+              (local.set $rlo (local.get $blo))
+              (local.set $rhi (local.get $bhi))
+              (if (i32.eqz (i32.or (local.get $bAbs) (local.get $blo)))
+                (then (local.set $rhi (i32.and (local.get $ahi) (local.get $bhi)))))
+              (br $exit)))
+          (local.set $rlo (local.get $alo))
+          (local.set $rhi (local.get $ahi))
+          (br $exit)))
+      ;; make |a| >= |b|
+      (if (i32.or (i32.gt_u (local.get $bAbs) (local.get $aAbs))
+                  (i32.and (i32.eq (local.get $bAbs) (local.get $aAbs))
+                           (i32.gt_u (local.get $blo) (local.get $alo))))
+        (then
+          (local.set $t (local.get $alo))
+          (local.set $alo (local.get $blo))
+          (local.set $blo (local.get $t))
+          (local.set $t (local.get $ahi))
+          (local.set $ahi (local.get $bhi))
+          (local.set $bhi (local.get $t))))
+      (local.set $aExp (i32.and (i32.shr_u (local.get $ahi) (i32.const 20)) (i32.const 0x7ff)))
+      (local.set $bExp (i32.and (i32.shr_u (local.get $bhi) (i32.const 20)) (i32.const 0x7ff)))
+      (local.set $aSigHi (i32.and (local.get $ahi) (i32.const 0xfffff)))
+      (local.set $bSigHi (i32.and (local.get $bhi) (i32.const 0xfffff)))
+      (if (i32.eqz (local.get $aExp))
+        (then
+          (local.set $shift (i32.sub
+            (select (i32.clz (local.get $aSigHi)) (i32.add (i32.clz (local.get $alo)) (i32.const 32))
+                    (local.get $aSigHi))
+            (i32.const 11)))
+          (if (i32.ge_u (local.get $shift) (i32.const 32))
+            (then
+              (local.set $aSigHi (i32.shl (local.get $alo) (i32.sub (local.get $shift) (i32.const 32))))
+              (local.set $alo (i32.const 0)))
+            (else
+              (local.set $aSigHi (i32.or (i32.shl (local.get $aSigHi) (local.get $shift))
+                                         (i32.shr_u (local.get $alo) (i32.sub (i32.const 32) (local.get $shift)))))
+              (local.set $alo (i32.shl (local.get $alo) (local.get $shift)))))
+          (local.set $aExp (i32.sub (i32.const 1) (local.get $shift)))))
+      (if (i32.eqz (local.get $bExp))
+        (then
+          (local.set $shift (i32.sub
+            (select (i32.clz (local.get $bSigHi)) (i32.add (i32.clz (local.get $blo)) (i32.const 32))
+                    (local.get $bSigHi))
+            (i32.const 11)))
+          (if (i32.ge_u (local.get $shift) (i32.const 32))
+            (then
+              (local.set $bSigHi (i32.shl (local.get $blo) (i32.sub (local.get $shift) (i32.const 32))))
+              (local.set $blo (i32.const 0)))
+            (else
+              (local.set $bSigHi (i32.or (i32.shl (local.get $bSigHi) (local.get $shift))
+                                         (i32.shr_u (local.get $blo) (i32.sub (i32.const 32) (local.get $shift)))))
+              (local.set $blo (i32.shl (local.get $blo) (local.get $shift)))))
+          (local.set $bExp (i32.sub (i32.const 1) (local.get $shift)))))
+      ;; the implicit bit and 3 bits for rounding
+      (local.set $aSigHi (i32.or (i32.shl (i32.or (local.get $aSigHi) (i32.const 0x100000)) (i32.const 3))
+                                 (i32.shr_u (local.get $alo) (i32.const 29))))
+      (local.set $alo (i32.shl (local.get $alo) (i32.const 3)))
+      (local.set $bSigHi (i32.or (i32.shl (i32.or (local.get $bSigHi) (i32.const 0x100000)) (i32.const 3))
+                                 (i32.shr_u (local.get $blo) (i32.const 29))))
+      (local.set $blo (i32.shl (local.get $blo) (i32.const 3)))
+      (local.set $shift (i32.sub (local.get $aExp) (local.get $bExp)))
+      (if (local.get $shift)
+        (then
+          (if (i32.ge_u (local.get $shift) (i32.const 64))
+            (then
+              (local.set $bSigHi (i32.const 0))
+              (local.set $blo (i32.const 1)))
+            (else
+              (if (i32.lt_u (local.get $shift) (i32.const 32))
+                (then
+                  (local.set $sticky (i32.ne (i32.shl (local.get $blo) (i32.sub (i32.const 32) (local.get $shift)))
+                                             (i32.const 0)))
+                  (local.set $blo (i32.or
+                    (i32.or (i32.shr_u (local.get $blo) (local.get $shift))
+                            (i32.shl (local.get $bSigHi) (i32.sub (i32.const 32) (local.get $shift))))
+                    (local.get $sticky)))
+                  (local.set $bSigHi (i32.shr_u (local.get $bSigHi) (local.get $shift))))
+                (else
+                  (local.set $shift (i32.sub (local.get $shift) (i32.const 32)))
+                  (local.set $sticky (i32.or
+                    (i32.ne (local.get $blo) (i32.const 0))
+                    (i32.and (i32.ne (local.get $shift) (i32.const 0))
+                             (i32.ne (i32.shl (local.get $bSigHi) (i32.sub (i32.const 32) (local.get $shift)))
+                                     (i32.const 0)))))
+                  (local.set $blo (i32.or (i32.shr_u (local.get $bSigHi) (local.get $shift)) (local.get $sticky)))
+                  (local.set $bSigHi (i32.const 0))))))))
+      (if (i32.lt_s (i32.xor (local.get $ahi) (local.get $bhi)) (i32.const 0))
+        (then
+          (local.set $t (local.get $alo))
+          (local.set $alo (i32.sub (local.get $alo) (local.get $blo)))
+          (local.set $aSigHi (i32.sub (i32.sub (local.get $aSigHi) (local.get $bSigHi))
+                                      (i32.lt_u (local.get $t) (local.get $blo))))
+          (br_if $exit (i32.eqz (i32.or (local.get $aSigHi) (local.get $alo))))
+          (if (i32.lt_u (local.get $aSigHi) (i32.const 0x800000))
+            (then
+              (local.set $shift (i32.sub
+                (select (i32.clz (local.get $aSigHi)) (i32.add (i32.clz (local.get $alo)) (i32.const 32))
+                        (local.get $aSigHi))
+                (i32.const 8)))
+              (if (i32.ge_u (local.get $shift) (i32.const 32))
+                (then
+                  (local.set $aSigHi (i32.shl (local.get $alo) (i32.sub (local.get $shift) (i32.const 32))))
+                  (local.set $alo (i32.const 0)))
+                (else
+                  (local.set $aSigHi (i32.or (i32.shl (local.get $aSigHi) (local.get $shift))
+                                             (i32.shr_u (local.get $alo) (i32.sub (i32.const 32) (local.get $shift)))))
+                  (local.set $alo (i32.shl (local.get $alo) (local.get $shift)))))
+              (local.set $aExp (i32.sub (local.get $aExp) (local.get $shift))))))
+        (else
+          (local.set $alo (i32.add (local.get $alo) (local.get $blo)))
+          (local.set $aSigHi (i32.add (i32.add (local.get $aSigHi) (local.get $bSigHi))
+                                      (i32.lt_u (local.get $alo) (local.get $blo))))
+          (if (i32.and (local.get $aSigHi) (i32.const 0x1000000))
+            (then
+              (local.set $alo (i32.or
+                (i32.or (i32.shr_u (local.get $alo) (i32.const 1)) (i32.shl (local.get $aSigHi) (i32.const 31)))
+                (i32.and (local.get $alo) (i32.const 1))))
+              (local.set $aSigHi (i32.shr_u (local.get $aSigHi) (i32.const 1)))
+              (local.set $aExp (i32.add (local.get $aExp) (i32.const 1)))))))
+      ;; round and pack
+      (local.set $ahi (i32.and (local.get $ahi) (i32.const 0x80000000)))
+      (if (i32.ge_s (local.get $aExp) (i32.const 0x7ff))
+        (then (local.set $rhi (i32.or (local.get $ahi) (i32.const 0x7ff00000))) (br $exit)))
+      (if (i32.le_s (local.get $aExp) (i32.const 0))
+        (then
+          (local.set $shift (i32.sub (i32.const 1) (local.get $aExp)))
+          (if (i32.ge_u (local.get $shift) (i32.const 64))
+            (then
+              (local.set $alo (i32.ne (i32.or (local.get $aSigHi) (local.get $alo)) (i32.const 0)))
+              (local.set $aSigHi (i32.const 0)))
+            (else
+              (if (i32.lt_u (local.get $shift) (i32.const 32))
+                (then
+                  (local.set $sticky (i32.ne (i32.shl (local.get $alo) (i32.sub (i32.const 32) (local.get $shift)))
+                                             (i32.const 0)))
+                  (local.set $alo (i32.or
+                    (i32.or (i32.shr_u (local.get $alo) (local.get $shift))
+                            (i32.shl (local.get $aSigHi) (i32.sub (i32.const 32) (local.get $shift))))
+                    (local.get $sticky)))
+                  (local.set $aSigHi (i32.shr_u (local.get $aSigHi) (local.get $shift))))
+                (else
+                  (local.set $shift (i32.sub (local.get $shift) (i32.const 32)))
+                  (local.set $sticky (i32.or
+                    (i32.ne (local.get $alo) (i32.const 0))
+                    (i32.and (i32.ne (local.get $shift) (i32.const 0))
+                             (i32.ne (i32.shl (local.get $aSigHi) (i32.sub (i32.const 32) (local.get $shift)))
+                                     (i32.const 0)))))
+                  (local.set $alo (i32.or (i32.shr_u (local.get $aSigHi) (local.get $shift)) (local.get $sticky)))
+                  (local.set $aSigHi (i32.const 0))))))
+          (local.set $aExp (i32.const 0))))
+      (local.set $rgs (i32.and (local.get $alo) (i32.const 7)))
+      (local.set $rlo (i32.or (i32.shr_u (local.get $alo) (i32.const 3)) (i32.shl (local.get $aSigHi) (i32.const 29))))
+      (local.set $rhi (i32.or
+        (i32.or (i32.and (i32.shr_u (local.get $aSigHi) (i32.const 3)) (i32.const 0xfffff))
+                (i32.shl (local.get $aExp) (i32.const 20)))
+        (local.get $ahi)))
+      (if (i32.or (i32.gt_u (local.get $rgs) (i32.const 4))
+                  (i32.and (i32.eq (local.get $rgs) (i32.const 4)) (i32.and (local.get $rlo) (i32.const 1))))
+        (then
+          (local.set $rlo (i32.add (local.get $rlo) (i32.const 1)))
+          (local.set $rhi (i32.add (local.get $rhi) (i32.eqz (local.get $rlo)))))))
+    (local.get $rlo)
+    (local.get $rhi))
+
+  ;; f64.mul
+  (func $f64_mul (param $alo i32) (param $ahi i32) (param $blo i32) (param $bhi i32) (result i32 i32)
+    (local $aAbs i32) (local $bAbs i32) (local $sign i32) (local $aExp i32) (local $bExp i32)
+    (local $shift i32) (local $sticky i32) (local $w0 i32) (local $w1 i32) (local $w2 i32) (local $w3 i32)
+    (local $plo i32) (local $phi i32) (local $c i32) (local $rlo i32) (local $rhi i32) (local $rgs i32)
+    (local.set $aAbs (i32.and (local.get $ahi) (i32.const 0x7fffffff)))
+    (local.set $bAbs (i32.and (local.get $bhi) (i32.const 0x7fffffff)))
+    (local.set $sign (i32.and (i32.xor (local.get $ahi) (local.get $bhi)) (i32.const 0x80000000)))
+    (block $exit
+      (local.set $rhi (i32.const 0x7ff80000))
+      (br_if $exit (i32.or
+        (i32.or (i32.gt_u (local.get $aAbs) (i32.const 0x7ff00000))
+                (i32.and (i32.eq (local.get $aAbs) (i32.const 0x7ff00000)) (i32.ne (local.get $alo) (i32.const 0))))
+        (i32.or (i32.gt_u (local.get $bAbs) (i32.const 0x7ff00000))
+                (i32.and (i32.eq (local.get $bAbs) (i32.const 0x7ff00000)) (i32.ne (local.get $blo) (i32.const 0))))))
+      (if (i32.or (i32.eq (local.get $aAbs) (i32.const 0x7ff00000))
+                  (i32.eq (local.get $bAbs) (i32.const 0x7ff00000)))
+        (then
+          (br_if $exit (i32.or (i32.eqz (i32.or (local.get $aAbs) (local.get $alo)))
+                               (i32.eqz (i32.or (local.get $bAbs) (local.get $blo)))))
+          (local.set $rhi (i32.or (local.get $sign) (i32.const 0x7ff00000)))
+          (br $exit)))
+      (local.set $rhi (local.get $sign))
+      (br_if $exit (i32.or (i32.eqz (i32.or (local.get $aAbs) (local.get $alo)))
+                           (i32.eqz (i32.or (local.get $bAbs) (local.get $blo)))))
+      (local.set $aExp (i32.shr_u (local.get $aAbs) (i32.const 20)))
+      (local.set $bExp (i32.shr_u (local.get $bAbs) (i32.const 20)))
+      (local.set $ahi (i32.and (local.get $ahi) (i32.const 0xfffff)))
+      (local.set $bhi (i32.and (local.get $bhi) (i32.const 0xfffff)))
+      (if (i32.eqz (local.get $aExp))
+        (then
+          (local.set $shift (i32.sub
+            (select (i32.clz (local.get $ahi)) (i32.add (i32.clz (local.get $alo)) (i32.const 32))
+                    (local.get $ahi))
+            (i32.const 11)))
+          (if (i32.ge_u (local.get $shift) (i32.const 32))
+            (then
+              (local.set $ahi (i32.shl (local.get $alo) (i32.sub (local.get $shift) (i32.const 32))))
+              (local.set $alo (i32.const 0)))
+            (else
+              (local.set $ahi (i32.or (i32.shl (local.get $ahi) (local.get $shift))
+                                      (i32.shr_u (local.get $alo) (i32.sub (i32.const 32) (local.get $shift)))))
+              (local.set $alo (i32.shl (local.get $alo) (local.get $shift)))))
+          (local.set $aExp (i32.sub (i32.const 1) (local.get $shift)))))
+      (if (i32.eqz (local.get $bExp))
+        (then
+          (local.set $shift (i32.sub
+            (select (i32.clz (local.get $bhi)) (i32.add (i32.clz (local.get $blo)) (i32.const 32))
+                    (local.get $bhi))
+            (i32.const 11)))
+          (if (i32.ge_u (local.get $shift) (i32.const 32))
+            (then
+              (local.set $bhi (i32.shl (local.get $blo) (i32.sub (local.get $shift) (i32.const 32))))
+              (local.set $blo (i32.const 0)))
+            (else
+              (local.set $bhi (i32.or (i32.shl (local.get $bhi) (local.get $shift))
+                                      (i32.shr_u (local.get $blo) (i32.sub (i32.const 32) (local.get $shift)))))
+              (local.set $blo (i32.shl (local.get $blo) (local.get $shift)))))
+          (local.set $bExp (i32.sub (i32.const 1) (local.get $shift)))))
+      (local.set $ahi (i32.or (local.get $ahi) (i32.const 0x100000)))
+      (local.set $bhi (i32.or (local.get $bhi) (i32.const 0x100000)))
+      ;; the 106-bit product in four words
+      (call $mul64 (local.get $alo) (local.get $blo))
+      (local.set $w1)
+      (local.set $w0)
+      (call $mul64 (local.get $alo) (local.get $bhi))
+      (local.set $phi)
+      (local.set $plo)
+      (local.set $w1 (i32.add (local.get $w1) (local.get $plo)))
+      (local.set $c (i32.lt_u (local.get $w1) (local.get $plo)))
+      (local.set $w2 (i32.add (local.get $phi) (local.get $c)))
+      (call $mul64 (local.get $ahi) (local.get $blo))
+      (local.set $phi)
+      (local.set $plo)
+      (local.set $w1 (i32.add (local.get $w1) (local.get $plo)))
+      (local.set $c (i32.lt_u (local.get $w1) (local.get $plo)))
+      (local.set $phi (i32.add (local.get $phi) (local.get $c)))
+      (local.set $w2 (i32.add (local.get $w2) (local.get $phi)))
+      (local.set $w3 (i32.lt_u (local.get $w2) (local.get $phi)))
+      (call $mul64 (local.get $ahi) (local.get $bhi))
+      (local.set $phi)
+      (local.set $plo)
+      (local.set $w2 (i32.add (local.get $w2) (local.get $plo)))
+      (local.set $c (i32.lt_u (local.get $w2) (local.get $plo)))
+      (local.set $w3 (i32.add (i32.add (local.get $w3) (local.get $phi)) (local.get $c)))
+      ;; keep 57 bits with a sticky bit
+      (local.set $alo (i32.or
+        (i32.or (i32.shr_u (local.get $w1) (i32.const 17)) (i32.shl (local.get $w2) (i32.const 15)))
+        (i32.ne (i32.or (local.get $w0) (i32.and (local.get $w1) (i32.const 0x1ffff))) (i32.const 0))))
+      (local.set $ahi (i32.or (i32.shr_u (local.get $w2) (i32.const 17)) (i32.shl (local.get $w3) (i32.const 15))))
+      (local.set $aExp (i32.sub (i32.add (local.get $aExp) (local.get $bExp)) (i32.const 1023)))
+      (if (i32.and (local.get $ahi) (i32.const 0x1000000))
+        (then
+          (local.set $alo (i32.or
+            (i32.or (i32.shr_u (local.get $alo) (i32.const 1)) (i32.shl (local.get $ahi) (i32.const 31)))
+            (i32.and (local.get $alo) (i32.const 1))))
+          (local.set $ahi (i32.shr_u (local.get $ahi) (i32.const 1)))
+          (local.set $aExp (i32.add (local.get $aExp) (i32.const 1)))))
+      ;; round and pack
+      (if (i32.ge_s (local.get $aExp) (i32.const 0x7ff))
+        (then (local.set $rhi (i32.or (local.get $sign) (i32.const 0x7ff00000))) (br $exit)))
+      (if (i32.le_s (local.get $aExp) (i32.const 0))
+        (then
+          (local.set $shift (i32.sub (i32.const 1) (local.get $aExp)))
+          (if (i32.ge_u (local.get $shift) (i32.const 64))
+            (then
+              (local.set $alo (i32.ne (i32.or (local.get $ahi) (local.get $alo)) (i32.const 0)))
+              (local.set $ahi (i32.const 0)))
+            (else
+              (if (i32.lt_u (local.get $shift) (i32.const 32))
+                (then
+                  (local.set $sticky (i32.ne (i32.shl (local.get $alo) (i32.sub (i32.const 32) (local.get $shift)))
+                                             (i32.const 0)))
+                  (local.set $alo (i32.or
+                    (i32.or (i32.shr_u (local.get $alo) (local.get $shift))
+                            (i32.shl (local.get $ahi) (i32.sub (i32.const 32) (local.get $shift))))
+                    (local.get $sticky)))
+                  (local.set $ahi (i32.shr_u (local.get $ahi) (local.get $shift))))
+                (else
+                  (local.set $shift (i32.sub (local.get $shift) (i32.const 32)))
+                  (local.set $sticky (i32.or
+                    (i32.ne (local.get $alo) (i32.const 0))
+                    (i32.and (i32.ne (local.get $shift) (i32.const 0))
+                             (i32.ne (i32.shl (local.get $ahi) (i32.sub (i32.const 32) (local.get $shift)))
+                                     (i32.const 0)))))
+                  (local.set $alo (i32.or (i32.shr_u (local.get $ahi) (local.get $shift)) (local.get $sticky)))
+                  (local.set $ahi (i32.const 0))))))
+          (local.set $aExp (i32.const 0))))
+      (local.set $rgs (i32.and (local.get $alo) (i32.const 7)))
+      (local.set $rlo (i32.or (i32.shr_u (local.get $alo) (i32.const 3)) (i32.shl (local.get $ahi) (i32.const 29))))
+      (local.set $rhi (i32.or
+        (i32.or (i32.and (i32.shr_u (local.get $ahi) (i32.const 3)) (i32.const 0xfffff))
+                (i32.shl (local.get $aExp) (i32.const 20)))
+        (local.get $sign)))
+      (if (i32.or (i32.gt_u (local.get $rgs) (i32.const 4))
+                  (i32.and (i32.eq (local.get $rgs) (i32.const 4)) (i32.and (local.get $rlo) (i32.const 1))))
+        (then
+          (local.set $rlo (i32.add (local.get $rlo) (i32.const 1)))
+          (local.set $rhi (i32.add (local.get $rhi) (i32.eqz (local.get $rlo)))))))
+    (local.get $rlo)
+    (local.get $rhi))
+
+  ;; f64.div
+  (func $f64_div (param $alo i32) (param $ahi i32) (param $blo i32) (param $bhi i32) (result i32 i32)
+    (local $aAbs i32) (local $bAbs i32) (local $sign i32) (local $aExp i32) (local $bExp i32)
+    (local $shift i32) (local $sticky i32) (local $qlo i32) (local $qhi i32) (local $i i32)
+    (local $rlo i32) (local $rhi i32) (local $rgs i32)
+    (local.set $aAbs (i32.and (local.get $ahi) (i32.const 0x7fffffff)))
+    (local.set $bAbs (i32.and (local.get $bhi) (i32.const 0x7fffffff)))
+    (local.set $sign (i32.and (i32.xor (local.get $ahi) (local.get $bhi)) (i32.const 0x80000000)))
+    (block $exit
+      (local.set $rhi (i32.const 0x7ff80000))
+      (br_if $exit (i32.or
+        (i32.or (i32.gt_u (local.get $aAbs) (i32.const 0x7ff00000))
+                (i32.and (i32.eq (local.get $aAbs) (i32.const 0x7ff00000)) (i32.ne (local.get $alo) (i32.const 0))))
+        (i32.or (i32.gt_u (local.get $bAbs) (i32.const 0x7ff00000))
+                (i32.and (i32.eq (local.get $bAbs) (i32.const 0x7ff00000)) (i32.ne (local.get $blo) (i32.const 0))))))
+      (if (i32.eq (local.get $aAbs) (i32.const 0x7ff00000))
+        (then
+          (br_if $exit (i32.eq (local.get $bAbs) (i32.const 0x7ff00000)))
+          (local.set $rhi (i32.or (local.get $sign) (i32.const 0x7ff00000)))
+          (br $exit)))
+      (if (i32.eqz (i32.or (local.get $aAbs) (local.get $alo)))
+        (then
+          (br_if $exit (i32.eqz (i32.or (local.get $bAbs) (local.get $blo))))
+          (local.set $rhi (local.get $sign))
+          (br $exit)))
+      (local.set $rhi (local.get $sign))
+      (br_if $exit (i32.eq (local.get $bAbs) (i32.const 0x7ff00000)))
+      (if (i32.eqz (i32.or (local.get $bAbs) (local.get $blo)))
+        (then (local.set $rhi (i32.or (local.get $sign) (i32.const 0x7ff00000))) (br $exit)))
+      (local.set $aExp (i32.shr_u (local.get $aAbs) (i32.const 20)))
+      (local.set $bExp (i32.shr_u (local.get $bAbs) (i32.const 20)))
+      (local.set $ahi (i32.and (local.get $ahi) (i32.const 0xfffff)))
+      (local.set $bhi (i32.and (local.get $bhi) (i32.const 0xfffff)))
+      (if (i32.eqz (local.get $aExp))
+        (then
+          (local.set $shift (i32.sub
+            (select (i32.clz (local.get $ahi)) (i32.add (i32.clz (local.get $alo)) (i32.const 32))
+                    (local.get $ahi))
+            (i32.const 11)))
+          (if (i32.ge_u (local.get $shift) (i32.const 32))
+            (then
+              (local.set $ahi (i32.shl (local.get $alo) (i32.sub (local.get $shift) (i32.const 32))))
+              (local.set $alo (i32.const 0)))
+            (else
+              (local.set $ahi (i32.or (i32.shl (local.get $ahi) (local.get $shift))
+                                      (i32.shr_u (local.get $alo) (i32.sub (i32.const 32) (local.get $shift)))))
+              (local.set $alo (i32.shl (local.get $alo) (local.get $shift)))))
+          (local.set $aExp (i32.sub (i32.const 1) (local.get $shift)))))
+      (if (i32.eqz (local.get $bExp))
+        (then
+          (local.set $shift (i32.sub
+            (select (i32.clz (local.get $bhi)) (i32.add (i32.clz (local.get $blo)) (i32.const 32))
+                    (local.get $bhi))
+            (i32.const 11)))
+          (if (i32.ge_u (local.get $shift) (i32.const 32))
+            (then
+              (local.set $bhi (i32.shl (local.get $blo) (i32.sub (local.get $shift) (i32.const 32))))
+              (local.set $blo (i32.const 0)))
+            (else
+              (local.set $bhi (i32.or (i32.shl (local.get $bhi) (local.get $shift))
+                                      (i32.shr_u (local.get $blo) (i32.sub (i32.const 32) (local.get $shift)))))
+              (local.set $blo (i32.shl (local.get $blo) (local.get $shift)))))
+          (local.set $bExp (i32.sub (i32.const 1) (local.get $shift)))))
+      (local.set $ahi (i32.or (local.get $ahi) (i32.const 0x100000)))
+      (local.set $bhi (i32.or (local.get $bhi) (i32.const 0x100000)))
+      (local.set $aExp (i32.add (i32.sub (local.get $aExp) (local.get $bExp)) (i32.const 1023)))
+      (if (i32.or (i32.lt_u (local.get $ahi) (local.get $bhi))
+                  (i32.and (i32.eq (local.get $ahi) (local.get $bhi)) (i32.lt_u (local.get $alo) (local.get $blo))))
+        (then
+          (local.set $ahi (i32.or (i32.shl (local.get $ahi) (i32.const 1)) (i32.shr_u (local.get $alo) (i32.const 31))))
+          (local.set $alo (i32.shl (local.get $alo) (i32.const 1)))
+          (local.set $aExp (i32.sub (local.get $aExp) (i32.const 1)))))
+      ;; 56 quotient bits by long division, the remainder is the sticky bit
+      (local.set $i (i32.const 56))
+      (loop $continue
+        (local.set $qhi (i32.or (i32.shl (local.get $qhi) (i32.const 1)) (i32.shr_u (local.get $qlo) (i32.const 31))))
+        (local.set $qlo (i32.shl (local.get $qlo) (i32.const 1)))
+        (if (i32.or (i32.gt_u (local.get $ahi) (local.get $bhi))
+                    (i32.and (i32.eq (local.get $ahi) (local.get $bhi)) (i32.ge_u (local.get $alo) (local.get $blo))))
+          (then
+            (local.set $ahi (i32.sub (i32.sub (local.get $ahi) (local.get $bhi))
+                                     (i32.lt_u (local.get $alo) (local.get $blo))))
+            (local.set $alo (i32.sub (local.get $alo) (local.get $blo)))
+            (local.set $qlo (i32.or (local.get $qlo) (i32.const 1)))))
+        (local.set $ahi (i32.or (i32.shl (local.get $ahi) (i32.const 1)) (i32.shr_u (local.get $alo) (i32.const 31))))
+        (local.set $alo (i32.shl (local.get $alo) (i32.const 1)))
+        (local.set $i (i32.sub (local.get $i) (i32.const 1)))
+        (br_if $continue (local.get $i)))
+      (local.set $alo (i32.or (local.get $qlo) (i32.ne (i32.or (local.get $ahi) (local.get $alo)) (i32.const 0))))
+      (local.set $ahi (local.get $qhi))
+      ;; round and pack
+      (if (i32.ge_s (local.get $aExp) (i32.const 0x7ff))
+        (then (local.set $rhi (i32.or (local.get $sign) (i32.const 0x7ff00000))) (br $exit)))
+      (if (i32.le_s (local.get $aExp) (i32.const 0))
+        (then
+          (local.set $shift (i32.sub (i32.const 1) (local.get $aExp)))
+          (if (i32.ge_u (local.get $shift) (i32.const 64))
+            (then
+              (local.set $alo (i32.ne (i32.or (local.get $ahi) (local.get $alo)) (i32.const 0)))
+              (local.set $ahi (i32.const 0)))
+            (else
+              (if (i32.lt_u (local.get $shift) (i32.const 32))
+                (then
+                  (local.set $sticky (i32.ne (i32.shl (local.get $alo) (i32.sub (i32.const 32) (local.get $shift)))
+                                             (i32.const 0)))
+                  (local.set $alo (i32.or
+                    (i32.or (i32.shr_u (local.get $alo) (local.get $shift))
+                            (i32.shl (local.get $ahi) (i32.sub (i32.const 32) (local.get $shift))))
+                    (local.get $sticky)))
+                  (local.set $ahi (i32.shr_u (local.get $ahi) (local.get $shift))))
+                (else
+                  (local.set $shift (i32.sub (local.get $shift) (i32.const 32)))
+                  (local.set $sticky (i32.or
+                    (i32.ne (local.get $alo) (i32.const 0))
+                    (i32.and (i32.ne (local.get $shift) (i32.const 0))
+                             (i32.ne (i32.shl (local.get $ahi) (i32.sub (i32.const 32) (local.get $shift)))
+                                     (i32.const 0)))))
+                  (local.set $alo (i32.or (i32.shr_u (local.get $ahi) (local.get $shift)) (local.get $sticky)))
+                  (local.set $ahi (i32.const 0))))))
+          (local.set $aExp (i32.const 0))))
+      (local.set $rgs (i32.and (local.get $alo) (i32.const 7)))
+      (local.set $rlo (i32.or (i32.shr_u (local.get $alo) (i32.const 3)) (i32.shl (local.get $ahi) (i32.const 29))))
+      (local.set $rhi (i32.or
+        (i32.or (i32.and (i32.shr_u (local.get $ahi) (i32.const 3)) (i32.const 0xfffff))
+                (i32.shl (local.get $aExp) (i32.const 20)))
+        (local.get $sign)))
+      (if (i32.or (i32.gt_u (local.get $rgs) (i32.const 4))
+                  (i32.and (i32.eq (local.get $rgs) (i32.const 4)) (i32.and (local.get $rlo) (i32.const 1))))
+        (then
+          (local.set $rlo (i32.add (local.get $rlo) (i32.const 1)))
+          (local.set $rhi (i32.add (local.get $rhi) (i32.eqz (local.get $rlo)))))))
+    (local.get $rlo)
+    (local.get $rhi))
+
+  ;; f64.sqrt
+  (func $f64_sqrt (param $lo i32) (param $hi i32) (result i32 i32)
+    (local $exp i32) (local $shift i32) (local $qlo i32) (local $qhi i32) (local $remLo i32) (local $remHi i32)
+    (local $tlo i32) (local $thi i32) (local $i i32) (local $rlo i32) (local $rhi i32)
+    (block $exit
+      (local.set $rlo (local.get $lo))
+      (local.set $rhi (local.get $hi))
+      ;; +-0 and +inf are returned as is
+      (br_if $exit (i32.eqz (i32.or (i32.and (local.get $hi) (i32.const 0x7fffffff)) (local.get $lo))))
+      (br_if $exit (i32.and (i32.eq (local.get $hi) (i32.const 0x7ff00000)) (i32.eqz (local.get $lo))))
+      (local.set $rlo (i32.const 0))
+      (local.set $rhi (i32.const 0x7ff80000))
+      (br_if $exit (i32.ge_u (local.get $hi) (i32.const 0x7ff00000)))
+      (local.set $exp (i32.shr_u (local.get $hi) (i32.const 20)))
+      (local.set $hi (i32.and (local.get $hi) (i32.const 0xfffff)))
+      (if (i32.eqz (local.get $exp))
+        (then
+          (local.set $shift (i32.sub
+            (select (i32.clz (local.get $hi)) (i32.add (i32.clz (local.get $lo)) (i32.const 32))
+                    (local.get $hi))
+            (i32.const 11)))
+          (if (i32.ge_u (local.get $shift) (i32.const 32))
+            (then
+              (local.set $hi (i32.shl (local.get $lo) (i32.sub (local.get $shift) (i32.const 32))))
+              (local.set $lo (i32.const 0)))
+            (else
+              (local.set $hi (i32.or (i32.shl (local.get $hi) (local.get $shift))
+                                     (i32.shr_u (local.get $lo) (i32.sub (i32.const 32) (local.get $shift)))))
+              (local.set $lo (i32.shl (local.get $lo) (local.get $shift)))))
+          (local.set $exp (i32.sub (i32.const 1) (local.get $shift)))))
+      (local.set $hi (i32.or (local.get $hi) (i32.const 0x100000)))
+      ;; the unbiased exponent must be even, the significand is in [2^52, 2^54)
+      (local.set $exp (i32.sub (local.get $exp) (i32.const 1023)))
+      (if (i32.and (local.get $exp) (i32.const 1))
+        (then
+          (local.set $hi (i32.or (i32.shl (local.get $hi) (i32.const 1)) (i32.shr_u (local.get $lo) (i32.const 31))))
+          (local.set $lo (i32.shl (local.get $lo) (i32.const 1)))))
+      (local.set $exp (i32.add (i32.shr_s (local.get $exp) (i32.const 1)) (i32.const 1023)))
+      ;; 56 root bits, two bits of the significand per iteration
+      (local.set $i (i32.const 56))
+      (loop $continue
+        (local.set $remHi (i32.or (i32.shl (local.get $remHi) (i32.const 2)) (i32.shr_u (local.get $remLo) (i32.const 30))))
+        (local.set $remLo (i32.or (i32.shl (local.get $remLo) (i32.const 2))
+                                  (i32.shr_u (local.get $hi) (i32.const 20))))
+        (local.set $hi (i32.and
+          (i32.or (i32.shl (local.get $hi) (i32.const 2)) (i32.shr_u (local.get $lo) (i32.const 30)))
+          (i32.const 0x3fffff)))
+        (local.set $lo (i32.shl (local.get $lo) (i32.const 2)))
+        (local.set $thi (i32.or (i32.shl (local.get $qhi) (i32.const 2)) (i32.shr_u (local.get $qlo) (i32.const 30))))
+        (local.set $tlo (i32.or (i32.shl (local.get $qlo) (i32.const 2)) (i32.const 1)))
+        (local.set $qhi (i32.or (i32.shl (local.get $qhi) (i32.const 1)) (i32.shr_u (local.get $qlo) (i32.const 31))))
+        (local.set $qlo (i32.shl (local.get $qlo) (i32.const 1)))
+        (if (i32.or (i32.gt_u (local.get $remHi) (local.get $thi))
+                    (i32.and (i32.eq (local.get $remHi) (local.get $thi))
+                             (i32.ge_u (local.get $remLo) (local.get $tlo))))
+          (then
+            (local.set $remHi (i32.sub (i32.sub (local.get $remHi) (local.get $thi))
+                                       (i32.lt_u (local.get $remLo) (local.get $tlo))))
+            (local.set $remLo (i32.sub (local.get $remLo) (local.get $tlo)))
+            (local.set $qlo (i32.or (local.get $qlo) (i32.const 1)))))
+        (local.set $i (i32.sub (local.get $i) (i32.const 1)))
+        (br_if $continue (local.get $i)))
+      (local.set $qlo (i32.or (local.get $qlo) (i32.ne (i32.or (local.get $remHi) (local.get $remLo)) (i32.const 0))))
+      ;; a square root is never a tie
+      (local.set $rlo (i32.or (i32.shr_u (local.get $qlo) (i32.const 3)) (i32.shl (local.get $qhi) (i32.const 29))))
+      (local.set $rhi (i32.or (i32.and (i32.shr_u (local.get $qhi) (i32.const 3)) (i32.const 0xfffff))
+                              (i32.shl (local.get $exp) (i32.const 20))))
+      (if (i32.gt_u (i32.and (local.get $qlo) (i32.const 7)) (i32.const 4))
+        (then
+          (local.set $rlo (i32.add (local.get $rlo) (i32.const 1)))
+          (local.set $rhi (i32.add (local.get $rhi) (i32.eqz (local.get $rlo)))))))
+    (local.get $rlo)
+    (local.get $rhi))
+
+  ;; f64.min and f64.max
+  (func $f64_min_max (param $alo i32) (param $ahi i32) (param $blo i32) (param $bhi i32) (param $max i32)
+    (result i32 i32)
+    (local $aKey i32) (local $bKey i32) (local $lt i32) (local $rlo i32) (local $rhi i32)
+    (local.set $rhi (i32.const 0x7ff80000))
+    (if (i32.eqz (i32.or
+          (i32.or (i32.gt_u (i32.and (local.get $ahi) (i32.const 0x7fffffff)) (i32.const 0x7ff00000))
+                  (i32.and (i32.eq (i32.and (local.get $ahi) (i32.const 0x7fffffff)) (i32.const 0x7ff00000))
+                           (i32.ne (local.get $alo) (i32.const 0))))
+          (i32.or (i32.gt_u (i32.and (local.get $bhi) (i32.const 0x7fffffff)) (i32.const 0x7ff00000))
+                  (i32.and (i32.eq (i32.and (local.get $bhi) (i32.const 0x7fffffff)) (i32.const 0x7ff00000))
+                           (i32.ne (local.get $blo) (i32.const 0))))))
+      (then
+        ;; the total order keys make -0 less than +0
+        (local.set $aKey (i32.xor (local.get $ahi) (i32.shr_u (i32.shr_s (local.get $ahi) (i32.const 31)) (i32.const 1))))
+        (local.set $bKey (i32.xor (local.get $bhi) (i32.shr_u (i32.shr_s (local.get $bhi) (i32.const 31)) (i32.const 1))))
+        (local.set $lt (i32.xor
+          (i32.or (i32.lt_s (local.get $aKey) (local.get $bKey))
+                  (i32.and (i32.eq (local.get $aKey) (local.get $bKey))
+                           (i32.lt_u (i32.xor (local.get $alo) (i32.shr_s (local.get $ahi) (i32.const 31)))
+                                     (i32.xor (local.get $blo) (i32.shr_s (local.get $bhi) (i32.const 31))))))
+          (local.get $max)))
+        (local.set $rlo (select (local.get $alo) (local.get $blo) (local.get $lt)))
+        (local.set $rhi (select (local.get $ahi) (local.get $bhi) (local.get $lt)))))
+    (local.get $rlo)
+    (local.get $rhi))
+
+  ;; f64 comparisons, bit `n` of the mask is the result for less (0), equal (1), greater (2) and
+  ;; unordered (3) operands
+  (func $f64_compare (param $alo i32) (param $ahi i32) (param $blo i32) (param $bhi i32) (param $mask i32)
+    (result i32)
+    (local $aKey i32) (local $bKey i32) (local $aLoKey i32) (local $bLoKey i32) (local $c i32)
+    (local.set $c (i32.const 3))
+    (if (i32.eqz (i32.or
+          (i32.or (i32.gt_u (i32.and (local.get $ahi) (i32.const 0x7fffffff)) (i32.const 0x7ff00000))
+                  (i32.and (i32.eq (i32.and (local.get $ahi) (i32.const 0x7fffffff)) (i32.const 0x7ff00000))
+                           (i32.ne (local.get $alo) (i32.const 0))))
+          (i32.or (i32.gt_u (i32.and (local.get $bhi) (i32.const 0x7fffffff)) (i32.const 0x7ff00000))
+                  (i32.and (i32.eq (i32.and (local.get $bhi) (i32.const 0x7fffffff)) (i32.const 0x7ff00000))
+                           (i32.ne (local.get $blo) (i32.const 0))))))
+      (then
+        (local.set $c (i32.const 1))
+        (if (i32.or (i32.and (i32.or (local.get $ahi) (local.get $bhi)) (i32.const 0x7fffffff))
+                    (i32.or (local.get $alo) (local.get $blo)))
+          (then
+            (local.set $aKey (i32.xor (local.get $ahi) (i32.shr_u (i32.shr_s (local.get $ahi) (i32.const 31)) (i32.const 1))))
+            (local.set $bKey (i32.xor (local.get $bhi) (i32.shr_u (i32.shr_s (local.get $bhi) (i32.const 31)) (i32.const 1))))
+            (local.set $aLoKey (i32.xor (local.get $alo) (i32.shr_s (local.get $ahi) (i32.const 31))))
+            (local.set $bLoKey (i32.xor (local.get $blo) (i32.shr_s (local.get $bhi) (i32.const 31))))
+            (local.set $c (i32.sub
+              (i32.add (i32.const 1)
+                (i32.or (i32.gt_s (local.get $aKey) (local.get $bKey))
+                        (i32.and (i32.eq (local.get $aKey) (local.get $bKey))
+                                 (i32.gt_u (local.get $aLoKey) (local.get $bLoKey)))))
+              (i32.or (i32.lt_s (local.get $aKey) (local.get $bKey))
+                      (i32.and (i32.eq (local.get $aKey) (local.get $bKey))
+                               (i32.lt_u (local.get $aLoKey) (local.get $bLoKey))))))))))
+    (i32.and (i32.shr_u (local.get $mask) (local.get $c)) (i32.const 1)))
+
+  ;; f64.ceil (0), f64.floor (1), f64.trunc (2) and f64.nearest (3)
+  (func $f64_round (param $lo i32) (param $hi i32) (param $mode i32) (result i32 i32)
+    (local $abs i32) (local $exp i32) (local $oneLo i32) (local $oneHi i32) (local $remLo i32)
+    (local $remHi i32) (local $halfLo i32) (local $halfHi i32) (local $up i32) (local $rlo i32) (local $rhi i32)
+    (local.set $abs (i32.and (local.get $hi) (i32.const 0x7fffffff)))
+    (local.set $exp (i32.shr_u (local.get $abs) (i32.const 20)))
+    (block $exit
+      (local.set $rhi (i32.const 0x7ff80000))
+      (br_if $exit (i32.or (i32.gt_u (local.get $abs) (i32.const 0x7ff00000))
+                           (i32.and (i32.eq (local.get $abs) (i32.const 0x7ff00000))
+                                    (i32.ne (local.get $lo) (i32.const 0)))))
+      ;; integers, infinities and zeros
+      (local.set $rlo (local.get $lo))
+      (local.set $rhi (local.get $hi))
+      (br_if $exit (i32.ge_u (local.get $exp) (i32.const 1075)))
+      (br_if $exit (i32.eqz (i32.or (local.get $abs) (local.get $lo))))
+      (if (i32.lt_u (local.get $exp) (i32.const 1023))
+        (then
+          (local.set $rlo (i32.const 0))
+          (local.set $rhi (i32.and (local.get $hi) (i32.const 0x80000000)))
+          (local.set $oneHi (i32.const 0x3ff00000))
+          (local.set $up (i32.or (i32.gt_u (local.get $abs) (i32.const 0x3fe00000))
+                                 (i32.and (i32.eq (local.get $abs) (i32.const 0x3fe00000))
+                                          (i32.ne (local.get $lo) (i32.const 0))))))
+        (else
+          (local.set $exp (i32.sub (i32.const 1075) (local.get $exp)))
+          (if (i32.lt_u (local.get $exp) (i32.const 32))
+            (then (local.set $oneLo (i32.shl (i32.const 1) (local.get $exp))))
+            (else (local.set $oneHi (i32.shl (i32.const 1) (i32.sub (local.get $exp) (i32.const 32))))))
+          (local.set $remLo (i32.and (local.get $lo) (i32.sub (local.get $oneLo) (i32.const 1))))
+          (local.set $remHi (i32.and (local.get $hi)
+            (i32.sub (local.get $oneHi) (i32.ne (local.get $oneHi) (i32.const 0)))))
+          (br_if $exit (i32.eqz (i32.or (local.get $remLo) (local.get $remHi))))
+          (local.set $rlo (i32.xor (local.get $lo) (local.get $remLo)))
+          (local.set $rhi (i32.xor (local.get $hi) (local.get $remHi)))
+          (local.set $halfLo (i32.or (i32.shr_u (local.get $oneLo) (i32.const 1)) (i32.shl (local.get $oneHi) (i32.const 31))))
+          (local.set $halfHi (i32.shr_u (local.get $oneHi) (i32.const 1)))
+          (local.set $up (i32.or
+            (i32.or (i32.gt_u (local.get $remHi) (local.get $halfHi))
+                    (i32.and (i32.eq (local.get $remHi) (local.get $halfHi))
+                             (i32.gt_u (local.get $remLo) (local.get $halfLo))))
+            (i32.and
+              (i32.and (i32.eq (local.get $remHi) (local.get $halfHi)) (i32.eq (local.get $remLo) (local.get $halfLo)))
+              (i32.ne (i32.or (i32.and (local.get $lo) (local.get $oneLo)) (i32.and (local.get $hi) (local.get $oneHi)))
+                      (i32.const 0)))))))
+      (if (i32.ne (local.get $mode) (i32.const 3))
+        (then
+          (local.set $up (i32.and
+            (i32.lt_u (local.get $mode) (i32.const 2))
+            (i32.eq (i32.lt_s (local.get $hi) (i32.const 0)) (local.get $mode))))))
+      (if (local.get $up)
+        (then
+          (local.set $rlo (i32.add (local.get $rlo) (local.get $oneLo)))
+          (local.set $rhi (i32.add (i32.add (local.get $rhi) (local.get $oneHi))
+                                   (i32.lt_u (local.get $rlo) (local.get $oneLo)))))))
+    (local.get $rlo)
+    (local.get $rhi))
+
+  ;; i32 and i64 truncations of f64, the flags are signed (1), i64 (2) and saturating (4)
+  (func $f64_to_int (param $alo i32) (param $ahi i32) (param $flags i32) (result i32 i32)
+    (local $exp i32) (local $max i32) (local $sigHi i32) (local $shift i32) (local $lo i32) (local $hi i32)
+    (block $exit
+      (if (i32.or (i32.gt_u (i32.and (local.get $ahi) (i32.const 0x7fffffff)) (i32.const 0x7ff00000))
+                  (i32.and (i32.eq (i32.and (local.get $ahi) (i32.const 0x7fffffff)) (i32.const 0x7ff00000))
+                           (i32.ne (local.get $alo) (i32.const 0))))
+        (then
+          (if (i32.eqz (i32.and (local.get $flags) (i32.const 4)))
+            (then (call $trap_conversion)))
+          (br $exit)))
+      (local.set $exp (i32.and (i32.shr_u (local.get $ahi) (i32.const 20)) (i32.const 0x7ff)))
+      (br_if $exit (i32.lt_u (local.get $exp) (i32.const 1023)))
+      ;; the smallest exponent out of the range
+      (local.set $max (i32.sub
+        (select (i32.const 1087) (i32.const 1055) (i32.and (local.get $flags) (i32.const 2)))
+        (i32.and (local.get $flags) (i32.const 1))))
+      (if (i32.eqz (i32.or
+            (i32.and (i32.lt_u (local.get $exp) (local.get $max))
+                     (i32.or (i32.and (local.get $flags) (i32.const 1))
+                             (i32.ge_s (local.get $ahi) (i32.const 0))))
+            (i32.and (i32.and (i32.and (local.get $flags) (i32.const 1)) (i32.eqz (local.get $alo)))
+                     (i32.eq (local.get $ahi)
+                             (i32.or (i32.shl (local.get $max) (i32.const 20)) (i32.const 0x80000000))))))
+        (then
+          (if (i32.eqz (i32.and (local.get $flags) (i32.const 4)))
+            (then (call $trap_overflow)))
+          (if (i32.lt_s (local.get $ahi) (i32.const 0))
+            (then
+              (if (i32.and (local.get $flags) (i32.const 1))
+                (then
+                  (if (i32.and (local.get $flags) (i32.const 2))
+                    (then (local.set $hi (i32.const 0x80000000)))
+                    (else (local.set $lo (i32.const 0x80000000)))))))
+            (else
+              (local.set $lo (i32.const -1))
+              (local.set $hi (i32.const -1))
+              (if (i32.and (local.get $flags) (i32.const 1))
+                (then
+                  (if (i32.and (local.get $flags) (i32.const 2))
+                    (then (local.set $hi (i32.const 0x7fffffff)))
+                    (else (local.set $lo (i32.const 0x7fffffff))))))))
+          (br $exit)))
+      (local.set $sigHi (i32.or (i32.and (local.get $ahi) (i32.const 0xfffff)) (i32.const 0x100000)))
+      (local.set $shift (i32.sub (local.get $exp) (i32.const 1075)))
+      (if (i32.lt_s (local.get $shift) (i32.const 0))
+        (then
+          (local.set $shift (i32.sub (i32.const 0) (local.get $shift)))
+          (if (i32.ge_u (local.get $shift) (i32.const 32))
+            (then (local.set $lo (i32.shr_u (local.get $sigHi) (i32.sub (local.get $shift) (i32.const 32)))))
+            (else
+              (local.set $lo (i32.or (i32.shr_u (local.get $alo) (local.get $shift))
+                                     (i32.shl (local.get $sigHi) (i32.sub (i32.const 32) (local.get $shift)))))
+              (local.set $hi (i32.shr_u (local.get $sigHi) (local.get $shift))))))
+        (else
+          (local.set $lo (local.get $alo))
+          (local.set $hi (local.get $sigHi))
+          (if (local.get $shift)
+            (then
+              (local.set $hi (i32.or (i32.shl (local.get $sigHi) (local.get $shift))
+                                     (i32.shr_u (local.get $alo) (i32.sub (i32.const 32) (local.get $shift)))))
+              (local.set $lo (i32.shl (local.get $alo) (local.get $shift)))))))
+      (if (i32.lt_s (local.get $ahi) (i32.const 0))
+        (then
+          (local.set $hi (i32.sub (i32.sub (i32.const 0) (local.get $hi)) (i32.ne (local.get $lo) (i32.const 0))))
+          (local.set $lo (i32.sub (i32.const 0) (local.get $lo))))))
+    (local.get $lo)
+    (local.get $hi))
+
+  ;; f64 conversions of i64, i32 operands are extended first
+  (func $f64_from_int (param $lo i32) (param $hi i32) (param $signed i32) (result i32 i32)
+    (local $sign i32) (local $n i32) (local $rgs i32) (local $rlo i32) (local $rhi i32)
+    (block $exit
+      (if (i32.and (local.get $signed) (i32.lt_s (local.get $hi) (i32.const 0)))
+        (then
+          (local.set $sign (i32.const 0x80000000))
+          (local.set $hi (i32.sub (i32.sub (i32.const 0) (local.get $hi)) (i32.ne (local.get $lo) (i32.const 0))))
+          (local.set $lo (i32.sub (i32.const 0) (local.get $lo)))))
+      (br_if $exit (i32.eqz (i32.or (local.get $lo) (local.get $hi))))
+      (local.set $n (select (i32.clz (local.get $hi)) (i32.add (i32.clz (local.get $lo)) (i32.const 32))
+                            (local.get $hi)))
+      (if (i32.ge_u (local.get $n) (i32.const 32))
+        (then
+          (local.set $hi (i32.shl (local.get $lo) (i32.sub (local.get $n) (i32.const 32))))
+          (local.set $lo (i32.const 0)))
+        (else
+          (if (local.get $n)
+            (then
+              (local.set $hi (i32.or (i32.shl (local.get $hi) (local.get $n))
+                                     (i32.shr_u (local.get $lo) (i32.sub (i32.const 32) (local.get $n)))))
+              (local.set $lo (i32.shl (local.get $lo) (local.get $n)))))))
+      ;; 56 bits with a sticky bit
+      (local.set $lo (i32.or
+        (i32.or (i32.shr_u (local.get $lo) (i32.const 8)) (i32.shl (local.get $hi) (i32.const 24)))
+        (i32.ne (i32.and (local.get $lo) (i32.const 0xff)) (i32.const 0))))
+      (local.set $hi (i32.shr_u (local.get $hi) (i32.const 8)))
+      (local.set $rgs (i32.and (local.get $lo) (i32.const 7)))
+      (local.set $rlo (i32.or (i32.shr_u (local.get $lo) (i32.const 3)) (i32.shl (local.get $hi) (i32.const 29))))
+      (local.set $rhi (i32.or
+        (i32.or (i32.and (i32.shr_u (local.get $hi) (i32.const 3)) (i32.const 0xfffff))
+                (i32.shl (i32.sub (i32.const 1086) (local.get $n)) (i32.const 20)))
+        (local.get $sign)))
+      (if (i32.or (i32.gt_u (local.get $rgs) (i32.const 4))
+                  (i32.and (i32.eq (local.get $rgs) (i32.const 4)) (i32.and (local.get $rlo) (i32.const 1))))
+        (then
+          (local.set $rlo (i32.add (local.get $rlo) (i32.const 1)))
+          (local.set $rhi (i32.add (local.get $rhi) (i32.eqz (local.get $rlo)))))))
+    (local.get $rlo)
+    (local.get $rhi))
+)
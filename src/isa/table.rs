@@ -1,5 +1,5 @@
-use crate::{ElementSegmentIdx, InstructionSet, TableIdx, TrapCode};
-use rwasm_fuel_policy::{TABLE_ELEMS_PER_FUEL, TABLE_ELEMS_PER_FUEL_LOG2};
+use super::default_fuel_cost_model;
+use crate::{ElementSegmentIdx, FuelCostModel, InstructionSet, TableIdx, TrapCode};
 
 impl InstructionSet {
     pub const MSH_TABLE_INIT_CHECKED: u32 = 2;
@@ -16,6 +16,28 @@ impl InstructionSet {
         offset: u32,
         inject_fuel_check: bool,
     ) {
+        self.op_table_init_checked_with_model(
+            segment_index,
+            table_index,
+            length,
+            offset,
+            default_fuel_cost_model(inject_fuel_check),
+        );
+    }
+
+    /// Charges one fuel per `2^log2` elements (rounded up) if the fuel cost model is set, see
+    /// [`FuelCostModel::table_elems_per_fuel_log2`].
+    ///
+    /// Max stack height: 2
+    pub fn op_table_init_checked_with_model(
+        &mut self,
+        segment_index: ElementSegmentIdx,
+        table_index: TableIdx,
+        length: u32,
+        offset: u32,
+        fuel_cost_model: Option<&dyn FuelCostModel>,
+    ) {
+        let elems_per_fuel_log2 = fuel_cost_model.map(|model| model.table_elems_per_fuel_log2());
         // [d, s, n]
         self.op_local_get(1); // n
         self.op_local_get(3); // s
@@ -32,12 +54,12 @@ impl InstructionSet {
             self.op_local_set(2);
         }
         // charge fuel for this call after all checks
-        if inject_fuel_check {
+        if let Some(elems_per_fuel_log2) = elems_per_fuel_log2 {
             self.op_local_get(1); // n
-            self.op_i32_const(TABLE_ELEMS_PER_FUEL - 1); // upper round
+            self.op_i32_const((1u32 << elems_per_fuel_log2) - 1); // upper round
             self.op_i32_add();
-            self.op_i32_const(TABLE_ELEMS_PER_FUEL_LOG2); // 2^4=16
-            self.op_i32_shr_u(); // n/16
+            self.op_i32_const(elems_per_fuel_log2);
+            self.op_i32_shr_u(); // n/2^elems_per_fuel_log2
             self.op_consume_fuel_stack();
         }
         self.op_table_init(segment_index);
@@ -51,6 +73,24 @@ impl InstructionSet {
         limit_check: Option<u32>,
         inject_fuel_check: bool,
     ) {
+        self.op_table_grow_checked_with_model(
+            table_idx,
+            limit_check,
+            default_fuel_cost_model(inject_fuel_check),
+        );
+    }
+
+    /// Charges one fuel per `2^log2` elements (rounded up) if the fuel cost model is set, see
+    /// [`FuelCostModel::table_elems_per_fuel_log2`].
+    ///
+    /// Max stack height: 2
+    pub fn op_table_grow_checked_with_model(
+        &mut self,
+        table_idx: TableIdx,
+        limit_check: Option<u32>,
+        fuel_cost_model: Option<&dyn FuelCostModel>,
+    ) {
+        let elems_per_fuel_log2 = fuel_cost_model.map(|model| model.table_elems_per_fuel_log2());
         // [init, delta]
         if let Some(limit) = limit_check {
             self.op_local_get(1); // n
@@ -64,14 +104,14 @@ impl InstructionSet {
             // we don't trap here, because, according to a wasm standard, we should put u32::MAX on
             // the top of the stack in case of overflow
             self.op_i32_const(u32::MAX);
-            self.op_br(if inject_fuel_check { 8 } else { 2 });
+            self.op_br(if elems_per_fuel_log2.is_some() { 8 } else { 2 });
         }
-        if inject_fuel_check {
+        if let Some(elems_per_fuel_log2) = elems_per_fuel_log2 {
             self.op_local_get(1); // n
-            self.op_i32_const(TABLE_ELEMS_PER_FUEL - 1); // upper round
+            self.op_i32_const((1u32 << elems_per_fuel_log2) - 1); // upper round
             self.op_i32_add();
-            self.op_i32_const(TABLE_ELEMS_PER_FUEL_LOG2); // 2^4=16
-            self.op_i32_shr_u(); // n/16
+            self.op_i32_const(elems_per_fuel_log2);
+            self.op_i32_shr_u(); // n/2^elems_per_fuel_log2
             self.op_consume_fuel_stack();
         }
         self.op_table_grow(table_idx);
@@ -79,12 +119,28 @@ impl InstructionSet {
 
     /// Max stack height: 2
     pub fn op_table_fill_checked(&mut self, table_idx: TableIdx, inject_fuel_check: bool) {
-        if inject_fuel_check {
+        self.op_table_fill_checked_with_model(
+            table_idx,
+            default_fuel_cost_model(inject_fuel_check),
+        );
+    }
+
+    /// Charges one fuel per `2^log2` elements (rounded up) if the fuel cost model is set, see
+    /// [`FuelCostModel::table_elems_per_fuel_log2`].
+    ///
+    /// Max stack height: 2
+    pub fn op_table_fill_checked_with_model(
+        &mut self,
+        table_idx: TableIdx,
+        fuel_cost_model: Option<&dyn FuelCostModel>,
+    ) {
+        let elems_per_fuel_log2 = fuel_cost_model.map(|model| model.table_elems_per_fuel_log2());
+        if let Some(elems_per_fuel_log2) = elems_per_fuel_log2 {
             self.op_local_get(1); // n
-            self.op_i32_const(TABLE_ELEMS_PER_FUEL - 1); // upper round
+            self.op_i32_const((1u32 << elems_per_fuel_log2) - 1); // upper round
             self.op_i32_add();
-            self.op_i32_const(TABLE_ELEMS_PER_FUEL_LOG2); // 2^4=16
-            self.op_i32_shr_u(); // n/16
+            self.op_i32_const(elems_per_fuel_log2);
+            self.op_i32_shr_u(); // n/2^elems_per_fuel_log2
             self.op_consume_fuel_stack();
         }
         self.op_table_fill(table_idx);
@@ -97,12 +153,30 @@ impl InstructionSet {
         src_table_idx: TableIdx,
         inject_fuel_check: bool,
     ) {
-        if inject_fuel_check {
+        self.op_table_copy_checked_with_model(
+            dst_table_idx,
+            src_table_idx,
+            default_fuel_cost_model(inject_fuel_check),
+        );
+    }
+
+    /// Charges one fuel per `2^log2` elements (rounded up) if the fuel cost model is set, see
+    /// [`FuelCostModel::table_elems_per_fuel_log2`].
+    ///
+    /// Max stack height: 2
+    pub fn op_table_copy_checked_with_model(
+        &mut self,
+        dst_table_idx: TableIdx,
+        src_table_idx: TableIdx,
+        fuel_cost_model: Option<&dyn FuelCostModel>,
+    ) {
+        let elems_per_fuel_log2 = fuel_cost_model.map(|model| model.table_elems_per_fuel_log2());
+        if let Some(elems_per_fuel_log2) = elems_per_fuel_log2 {
             self.op_local_get(1); // n
-            self.op_i32_const(TABLE_ELEMS_PER_FUEL - 1); // upper round
+            self.op_i32_const((1u32 << elems_per_fuel_log2) - 1); // upper round
             self.op_i32_add();
-            self.op_i32_const(TABLE_ELEMS_PER_FUEL_LOG2); // 2^4=16
-            self.op_i32_shr_u(); // n/16
+            self.op_i32_const(elems_per_fuel_log2);
+            self.op_i32_shr_u(); // n/2^elems_per_fuel_log2
             self.op_consume_fuel_stack();
         }
         self.op_table_copy(dst_table_idx, src_table_idx);
@@ -1,7 +1,7 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 #![warn(unused_crate_dependencies)]
 #![allow(unused_variables, dead_code)]
-#![recursion_limit = "750"]
+#![recursion_limit = "2048"]
 
 extern crate alloc;
 extern crate core;
@@ -27,7 +27,7 @@ pub use rwasm_fuel_policy::*;
 pub use strategy::*;
 pub use types::*;
 pub use vm::*;
-pub use wasmparser::{FuncType, ValType};
+pub use wasmparser::{FuncType, GlobalType, MemoryType, TableType, ValType};
 #[cfg(test)]
 use wat as _;
 #[cfg(test)]
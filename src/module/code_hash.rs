@@ -0,0 +1,72 @@
+use crate::RwasmModuleInner;
+use tiny_keccak::{Hasher, Keccak};
+
+/// A domain separator of the canonical module encoding, it's changed with the encoding.
+const CODE_HASH_DOMAIN: &[u8] = b"rwasm-code-hash-v1";
+
+/// Computes keccak256 over the canonical encoding of the executable parts of the module.
+///
+/// The encoding doesn't depend on the binary version the module is decoded from:
+/// - the domain separator
+/// - the code section: a `u32` number of opcodes followed by the aligned (V3) opcode records
+/// - the data section: a `u32` length followed by the bytes
+/// - the elem section: a `u32` number of entries followed by `u32` entries
+/// - `source_pc`
+/// - the export section: a `u32` number of exports followed by the name (a `u32` length and the
+///   bytes), `func_offset` and the param and result types (a `u32` length and one byte per type)
+/// - the tag section (only if it's not empty, so the hash of modules w/o tags stays the same): a
+///   `u32` number of tags followed by the param types of every tag
+/// - the hint section (only if `include_hint_section` is set): a `u32` length followed by the
+///   bytes
+///
+/// All numbers are little-endian, the debug and metadata sections are never included: they don't
+/// change what the code does, the metadata records the settings the code is compiled with, and
+/// their effect (e.g., the fuel instrumentation) is a part of the code section already.
+pub(super) fn code_hash(module: &RwasmModuleInner, include_hint_section: bool) -> [u8; 32] {
+    let mut hasher = Keccak::v256();
+    let write_len = |hasher: &mut Keccak, len: usize| {
+        let len =
+            u32::try_from(len).unwrap_or_else(|_| unreachable!("rwasm: section is too large"));
+        hasher.update(&len.to_le_bytes());
+    };
+    hasher.update(CODE_HASH_DOMAIN);
+    write_len(&mut hasher, module.code_section.len());
+    for opcode in module.code_section.iter() {
+        hasher.update(&opcode.to_aligned_record());
+    }
+    write_len(&mut hasher, module.data_section.len());
+    hasher.update(&module.data_section);
+    write_len(&mut hasher, module.elem_section.len());
+    for elem in module.elem_section.iter() {
+        hasher.update(&elem.to_le_bytes());
+    }
+    hasher.update(&module.source_pc.to_le_bytes());
+    write_len(&mut hasher, module.export_section.len());
+    for export in module.export_section.iter() {
+        write_len(&mut hasher, export.name.len());
+        hasher.update(export.name.as_bytes());
+        hasher.update(&export.func_offset.to_le_bytes());
+        for types in [&export.params, &export.results] {
+            write_len(&mut hasher, types.len());
+            for ty in types.iter() {
+                hasher.update(&[*ty as u8]);
+            }
+        }
+    }
+    if !module.tag_section.is_empty() {
+        write_len(&mut hasher, module.tag_section.len());
+        for tag in module.tag_section.iter() {
+            write_len(&mut hasher, tag.params.len());
+            for ty in tag.params.iter() {
+                hasher.update(&[*ty as u8]);
+            }
+        }
+    }
+    if include_hint_section {
+        write_len(&mut hasher, module.hint_section.len());
+        hasher.update(&module.hint_section);
+    }
+    let mut hash = [0u8; 32];
+    hasher.finalize(&mut hash);
+    hash
+}
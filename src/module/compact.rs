@@ -0,0 +1,305 @@
+use crate::{
+    module::limits::{self, CodeLimiter},
+    types::{read_byte, read_uleb128, write_uleb128},
+    InstructionSet, Opcode, RwasmDebugSection, RwasmExport, RwasmFuncName, RwasmModuleInner,
+    RwasmModuleLimits, RwasmSourceMapEntry, RwasmTag, RwasmValType, RWASM_MAGIC_BYTE_0,
+    RWASM_MAGIC_BYTE_1, RWASM_VERSION_V4,
+};
+use alloc::{boxed::Box, string::String, vec, vec::Vec};
+use bincode::{de::read::Reader, error::DecodeError};
+
+/// The maximum length of the encoded metadata section.
+const MAX_METADATA_LEN: usize = 128;
+
+/// Encodes the module using the compact (V4) encoding.
+///
+/// Every section is prefixed with its LEB128 encoded length, opcodes are encoded with
+/// [`Opcode::write_compact`], and elem entries, source pc, export offsets and debug entries are
+/// LEB128 numbers.
+/// The tag section goes last, so binaries encoded before it was introduced are still valid.
+pub(crate) fn encode_compact(module: &RwasmModuleInner) -> Vec<u8> {
+    let mut sink = vec![RWASM_MAGIC_BYTE_0, RWASM_MAGIC_BYTE_1, RWASM_VERSION_V4];
+    write_len(&mut sink, module.code_section.len());
+    for opcode in module.code_section.iter() {
+        opcode.write_compact(&mut sink);
+    }
+    write_bytes(&mut sink, &module.data_section);
+    write_len(&mut sink, module.elem_section.len());
+    for elem in module.elem_section.iter() {
+        write_uleb128(&mut sink, *elem);
+    }
+    write_bytes(&mut sink, &module.hint_section);
+    write_uleb128(&mut sink, module.source_pc);
+    write_len(&mut sink, module.export_section.len());
+    for export in module.export_section.iter() {
+        write_bytes(&mut sink, export.name.as_bytes());
+        write_uleb128(&mut sink, export.func_offset);
+        for types in [&export.params, &export.results] {
+            write_len(&mut sink, types.len());
+            sink.extend(types.iter().map(|ty| *ty as u8));
+        }
+    }
+    let debug_section = &module.debug_section;
+    write_len(&mut sink, debug_section.source_map.len());
+    for entry in debug_section.source_map.iter() {
+        write_uleb128(&mut sink, entry.start_pc);
+        write_uleb128(&mut sink, entry.end_pc);
+        write_uleb128(&mut sink, entry.func_idx);
+        write_uleb128(&mut sink, entry.wasm_offset);
+    }
+    write_len(&mut sink, debug_section.func_names.len());
+    for func_name in debug_section.func_names.iter() {
+        write_uleb128(&mut sink, func_name.func_idx);
+        write_bytes(&mut sink, func_name.name.as_bytes());
+    }
+    let metadata_section = module
+        .metadata_section
+        .map(|metadata_section| {
+            bincode::encode_to_vec(metadata_section, bincode::config::legacy())
+                .unwrap_or_else(|_| unreachable!("rwasm: failed to serialize metadata section"))
+        })
+        .unwrap_or_default();
+    write_bytes(&mut sink, &metadata_section);
+    write_len(&mut sink, module.tag_section.len());
+    for tag in module.tag_section.iter() {
+        write_len(&mut sink, tag.params.len());
+        sink.extend(tag.params.iter().map(|ty| *ty as u8));
+    }
+    sink
+}
+
+/// Decodes the module body (everything after the version byte) using the compact (V4) encoding.
+///
+/// Every section length is checked against the limits before the section is read.
+pub(crate) fn decode_compact<R: Reader>(
+    reader: &mut R,
+    limits: &RwasmModuleLimits,
+) -> Result<RwasmModuleInner, DecodeError> {
+    let code_len = read_uleb128(reader)? as usize;
+    let mut code_limiter = CodeLimiter::new(limits, code_len)?;
+    let mut code_section = InstructionSet::new();
+    for _ in 0..code_len {
+        let opcode = Opcode::read_compact(reader)?;
+        code_limiter.check(&opcode)?;
+        code_section.push(opcode);
+    }
+    let data_section = read_bytes(
+        reader,
+        limits.max_data_bytes,
+        "rwasm: data section exceeds the limit",
+    )?;
+    let elem_len = read_len(
+        reader,
+        limits.max_elem_entries,
+        "rwasm: elem section exceeds the limit",
+    )?;
+    let mut elem_section = Vec::new();
+    for _ in 0..elem_len {
+        elem_section.push(read_uleb128(reader)?);
+    }
+    let hint_section = read_bytes(
+        reader,
+        limits.max_hint_bytes,
+        "rwasm: hint section exceeds the limit",
+    )?;
+    let source_pc = read_uleb128(reader)?;
+    let export_len = read_len(
+        reader,
+        limits.max_functions,
+        "rwasm: export section exceeds the limit",
+    )?;
+    let mut export_section = Vec::new();
+    for _ in 0..export_len {
+        let name = read_string(reader)?;
+        let func_offset = read_uleb128(reader)?;
+        let params = read_val_types(reader)?;
+        let results = read_val_types(reader)?;
+        export_section.push(RwasmExport {
+            name,
+            func_offset,
+            params,
+            results,
+        });
+    }
+    let source_map_len = read_len(
+        reader,
+        limits.max_opcodes,
+        "rwasm: source map exceeds the limit",
+    )?;
+    let mut source_map = Vec::new();
+    for _ in 0..source_map_len {
+        source_map.push(RwasmSourceMapEntry {
+            start_pc: read_uleb128(reader)?,
+            end_pc: read_uleb128(reader)?,
+            func_idx: read_uleb128(reader)?,
+            wasm_offset: read_uleb128(reader)?,
+        });
+    }
+    let func_names_len = read_len(
+        reader,
+        limits.max_functions,
+        "rwasm: function names exceed the limit",
+    )?;
+    let mut func_names = Vec::new();
+    for _ in 0..func_names_len {
+        func_names.push(RwasmFuncName {
+            func_idx: read_uleb128(reader)?,
+            name: read_string(reader)?,
+        });
+    }
+    let metadata_len = match read_len(
+        reader,
+        MAX_METADATA_LEN,
+        "rwasm: metadata section is too large",
+    ) {
+        Ok(metadata_len) => metadata_len,
+        // binaries encoded before the metadata section was introduced end here
+        Err(DecodeError::UnexpectedEnd { additional: 1 }) => 0,
+        Err(err) => return Err(err),
+    };
+    let metadata_section = if metadata_len > 0 {
+        let mut buf = [0u8; MAX_METADATA_LEN];
+        reader.read(&mut buf[..metadata_len])?;
+        let (metadata_section, bytes_read) =
+            bincode::decode_from_slice(&buf[..metadata_len], bincode::config::legacy())?;
+        if bytes_read != metadata_len {
+            return Err(DecodeError::Other("rwasm: trailing bytes after section"));
+        }
+        Some(metadata_section)
+    } else {
+        None
+    };
+    let tag_len = match read_len(
+        reader,
+        limits.max_functions,
+        "rwasm: tag section exceeds the limit",
+    ) {
+        Ok(tag_len) => tag_len,
+        // binaries encoded before the tag section was introduced end here
+        Err(DecodeError::UnexpectedEnd { additional: 1 }) => 0,
+        Err(err) => return Err(err),
+    };
+    let mut tag_section = Vec::new();
+    for _ in 0..tag_len {
+        tag_section.push(RwasmTag {
+            params: read_val_types(reader)?,
+        });
+    }
+    Ok(RwasmModuleInner {
+        code_section,
+        data_section,
+        elem_section,
+        hint_section,
+        source_pc,
+        export_section,
+        debug_section: RwasmDebugSection {
+            source_map,
+            func_names,
+        },
+        metadata_section,
+        tag_section,
+    })
+}
+
+fn write_len(sink: &mut Vec<u8>, len: usize) {
+    let len = u32::try_from(len).unwrap_or_else(|_| unreachable!("rwasm: section is too large"));
+    write_uleb128(sink, len);
+}
+
+fn write_bytes(sink: &mut Vec<u8>, bytes: &[u8]) {
+    write_len(sink, bytes.len());
+    sink.extend_from_slice(bytes);
+}
+
+fn read_len<R: Reader>(
+    reader: &mut R,
+    max_len: usize,
+    error: &'static str,
+) -> Result<usize, DecodeError> {
+    limits::check_len(read_uleb128(reader)? as usize, max_len, error)
+}
+
+fn read_bytes<R: Reader>(
+    reader: &mut R,
+    max_len: usize,
+    error: &'static str,
+) -> Result<Vec<u8>, DecodeError> {
+    let len = read_len(reader, max_len, error)?;
+    limits::read_bytes_by_chunks(len, |buf| reader.read(buf))
+}
+
+fn read_string<R: Reader>(reader: &mut R) -> Result<Box<str>, DecodeError> {
+    let string = String::from_utf8(read_bytes(reader, usize::MAX, "")?)
+        .map_err(|_| DecodeError::Other("rwasm: name is not a valid utf-8 string"))?;
+    Ok(Box::from(string))
+}
+
+fn read_val_types<R: Reader>(reader: &mut R) -> Result<Vec<RwasmValType>, DecodeError> {
+    let len = read_uleb128(reader)?;
+    let mut types = Vec::new();
+    for _ in 0..len {
+        let ty = match read_byte(reader)? {
+            0 => RwasmValType::I32,
+            1 => RwasmValType::I64,
+            2 => RwasmValType::F32,
+            3 => RwasmValType::F64,
+            4 => RwasmValType::V128,
+            5 => RwasmValType::FuncRef,
+            6 => RwasmValType::ExternRef,
+            _ => return Err(DecodeError::Other("rwasm: invalid value type")),
+        };
+        types.push(ty);
+    }
+    Ok(types)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        instruction_set, RwasmExport, RwasmModule, RwasmModuleBuilder, RwasmValType, TrapCode,
+        RWASM_VERSION_V4,
+    };
+
+    #[test]
+    fn test_compact_module_encoding() {
+        let module = RwasmModuleBuilder::new(instruction_set! {
+            SignatureCheck(0)
+            StackCheck(3)
+            I32Const(100)
+            I32Const(-20)
+            I32Add
+            BrIfNez(-2)
+            Trap(TrapCode::UnreachableCodeReached)
+            .op_table_copy(0, 1)
+            Drop
+            Return
+        })
+        .with_data_section(&[1, 2, 3])
+        .with_elem_section(&[0, 300, u32::MAX])
+        .with_hint_section(&[0x00, 0x61, 0x73, 0x6d])
+        .with_source_pc(2)
+        .with_export(RwasmExport {
+            name: "main".into(),
+            func_offset: 0,
+            params: vec![RwasmValType::I64, RwasmValType::ExternRef],
+            results: vec![RwasmValType::F32],
+        })
+        .build();
+        let encoded = module.serialize_compact();
+        assert_eq!(encoded[2], RWASM_VERSION_V4);
+        assert!(encoded.len() < module.serialize().len() / 2);
+        assert_eq!(RwasmModule::new_checked_exact(&encoded).unwrap(), module);
+        // any truncation must be detected, except binaries w/o the metadata and tag section
+        // lengths, those were encoded before these sections were introduced
+        assert!(encoded.ends_with(&[0, 0]));
+        for legacy_len in [encoded.len() - 2, encoded.len() - 1] {
+            assert_eq!(
+                RwasmModule::new_checked_exact(&encoded[..legacy_len]).unwrap(),
+                module
+            );
+        }
+        for len in 0..encoded.len() - 2 {
+            assert!(RwasmModule::new_checked(&encoded[..len]).is_err());
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use alloc::{boxed::Box, vec::Vec};
+use bincode::{Decode, Encode};
+
+/// A range of the code section that is compiled from one Wasm operator.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Default, Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd, Encode, Decode)]
+pub struct RwasmSourceMapEntry {
+    /// The first program counter of the range.
+    pub start_pc: u32,
+    /// The program counter right after the range (exclusive).
+    pub end_pc: u32,
+    /// An index of the original Wasm function (imported functions are included).
+    pub func_idx: u32,
+    /// A byte offset of the operator inside the original Wasm binary (the hint section).
+    pub wasm_offset: u32,
+}
+
+/// A function name from the Wasm `name` custom section.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Default, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd, Encode, Decode)]
+pub struct RwasmFuncName {
+    pub func_idx: u32,
+    pub name: Box<str>,
+}
+
+/// Debug information that maps the compiled code back to the original Wasm binary.
+///
+/// Emitted only with [`crate::CompilationConfig::emit_debug_section`].
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Default, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd, Encode, Decode)]
+pub struct RwasmDebugSection {
+    /// Non-overlapping PC ranges sorted by the program counter.
+    ///
+    /// Code that isn't compiled from Wasm functions (like the entrypoint or code snippets) is not
+    /// covered.
+    pub source_map: Vec<RwasmSourceMapEntry>,
+    /// Function names sorted by the function index.
+    pub func_names: Vec<RwasmFuncName>,
+}
+
+/// A location inside the original Wasm binary that a program counter is compiled from.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct RwasmSourceLocation<'a> {
+    pub func_idx: u32,
+    pub wasm_offset: u32,
+    pub func_name: Option<&'a str>,
+}
+
+impl RwasmDebugSection {
+    pub fn is_empty(&self) -> bool {
+        self.source_map.is_empty() && self.func_names.is_empty()
+    }
+
+    /// Finds a name of the Wasm function.
+    pub fn func_name(&self, func_idx: u32) -> Option<&str> {
+        self.func_names
+            .binary_search_by_key(&func_idx, |func_name| func_name.func_idx)
+            .ok()
+            .map(|index| self.func_names[index].name.as_ref())
+    }
+
+    /// Finds the source location of the program counter.
+    pub fn resolve(&self, pc: u32) -> Option<RwasmSourceLocation<'_>> {
+        let index = self
+            .source_map
+            .partition_point(|entry| entry.start_pc <= pc)
+            .checked_sub(1)?;
+        let entry = &self.source_map[index];
+        if pc >= entry.end_pc {
+            return None;
+        }
+        Some(RwasmSourceLocation {
+            func_idx: entry.func_idx,
+            wasm_offset: entry.wasm_offset,
+            func_name: self.func_name(entry.func_idx),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn test_resolve_source_location() {
+        let debug_section = RwasmDebugSection {
+            source_map: vec![
+                RwasmSourceMapEntry {
+                    start_pc: 2,
+                    end_pc: 5,
+                    func_idx: 0,
+                    wasm_offset: 100,
+                },
+                RwasmSourceMapEntry {
+                    start_pc: 5,
+                    end_pc: 6,
+                    func_idx: 0,
+                    wasm_offset: 102,
+                },
+                RwasmSourceMapEntry {
+                    start_pc: 8,
+                    end_pc: 10,
+                    func_idx: 1,
+                    wasm_offset: 110,
+                },
+            ],
+            func_names: vec![RwasmFuncName {
+                func_idx: 1,
+                name: "foo".into(),
+            }],
+        };
+        let location = |func_idx, wasm_offset, func_name| RwasmSourceLocation {
+            func_idx,
+            wasm_offset,
+            func_name,
+        };
+        assert_eq!(debug_section.resolve(0), None);
+        assert_eq!(debug_section.resolve(2), Some(location(0, 100, None)));
+        assert_eq!(debug_section.resolve(4), Some(location(0, 100, None)));
+        assert_eq!(debug_section.resolve(5), Some(location(0, 102, None)));
+        assert_eq!(debug_section.resolve(6), None);
+        assert_eq!(
+            debug_section.resolve(9),
+            Some(location(1, 110, Some("foo")))
+        );
+        assert_eq!(debug_section.resolve(10), None);
+    }
+}
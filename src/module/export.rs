@@ -0,0 +1,88 @@
+use alloc::{boxed::Box, vec::Vec};
+use bincode::{Decode, Encode};
+use wasmparser::{FuncType, ValType};
+
+/// A value type stored inside the export section.
+///
+/// It mirrors [`ValType`] but has a stable wire representation and can be ordered and hashed,
+/// what is required by [`crate::RwasmModuleInner`].
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd, Encode, Decode)]
+pub enum RwasmValType {
+    I32,
+    I64,
+    F32,
+    F64,
+    V128,
+    FuncRef,
+    ExternRef,
+}
+
+impl From<ValType> for RwasmValType {
+    fn from(value: ValType) -> Self {
+        match value {
+            ValType::I32 => Self::I32,
+            ValType::I64 => Self::I64,
+            ValType::F32 => Self::F32,
+            ValType::F64 => Self::F64,
+            ValType::V128 => Self::V128,
+            ValType::FuncRef => Self::FuncRef,
+            ValType::ExternRef => Self::ExternRef,
+        }
+    }
+}
+
+impl From<RwasmValType> for ValType {
+    fn from(value: RwasmValType) -> Self {
+        match value {
+            RwasmValType::I32 => Self::I32,
+            RwasmValType::I64 => Self::I64,
+            RwasmValType::F32 => Self::F32,
+            RwasmValType::F64 => Self::F64,
+            RwasmValType::V128 => Self::V128,
+            RwasmValType::FuncRef => Self::FuncRef,
+            RwasmValType::ExternRef => Self::ExternRef,
+        }
+    }
+}
+
+/// An exported function of the rWasm module.
+///
+/// Maps an export name from the original Wasm binary to the compiled function offset inside
+/// the code section, together with the original (not lowered) function signature.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Default, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd, Encode, Decode)]
+pub struct RwasmExport {
+    /// The name of the exported function.
+    pub name: Box<str>,
+    /// An offset of the compiled function inside the code section (points to `SignatureCheck`).
+    pub func_offset: u32,
+    /// Original parameter types of the function.
+    pub params: Vec<RwasmValType>,
+    /// Original result types of the function.
+    pub results: Vec<RwasmValType>,
+}
+
+impl RwasmExport {
+    pub fn new(name: Box<str>, func_offset: u32, func_type: &FuncType) -> Self {
+        Self {
+            name,
+            func_offset,
+            params: func_type.params().iter().copied().map(Into::into).collect(),
+            results: func_type
+                .results()
+                .iter()
+                .copied()
+                .map(Into::into)
+                .collect(),
+        }
+    }
+
+    /// Returns the original Wasm function type of the export.
+    pub fn func_type(&self) -> FuncType {
+        FuncType::new(
+            self.params.iter().copied().map(ValType::from),
+            self.results.iter().copied().map(ValType::from),
+        )
+    }
+}
@@ -0,0 +1,448 @@
+use super::{RwasmModuleInner, RwasmModuleVerificationError};
+use crate::{InstructionSet, Opcode, SignatureIdx, N_BYTES_PER_MEMORY_PAGE};
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    vec,
+    vec::Vec,
+};
+use rwasm_fuel_policy::{MEMORY_BYTES_PER_FUEL_LOG2, TABLE_ELEMS_PER_FUEL_LOG2};
+
+/// The length of the dynamic fuel check that goes right before a bulk operation.
+const BULK_FUEL_CHECK_LEN: usize = 6;
+
+/// A jump source of a function entry, it's outside any code range.
+const FUNC_ENTRY: usize = usize::MAX;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+struct FuelState {
+    /// The entry of the function that owns the instruction.
+    func: u32,
+    /// `ConsumeFuel` is executed on every path from the function entry to the instruction.
+    instrumented: bool,
+    /// `ConsumeFuel` with a non-zero amount is executed on every path to the instruction.
+    charged: bool,
+}
+
+/// Proves that the fuel instrumentation of the module can't be bypassed.
+///
+/// The analysis checks that:
+/// - every function that starts with `SignatureCheck` (compiled Wasm functions and import
+///   trampolines) executes `ConsumeFuel` before anything else, so it dominates every basic block
+///   of the function,
+/// - every internal or indirect call is dominated by a non-zero `ConsumeFuel`, the only
+///   exception is code outside of Wasm functions (the entrypoint, the state router and code
+///   snippets) calling functions that start with `SignatureCheck`,
+/// - every cycle of the control flow (including recursive calls) passes through a non-zero
+///   `ConsumeFuel`, except loops emitted by the i64 division and remainder snippets and the
+///   software float division and square root, they are bounded by construction and accepted only
+///   if the whole emitted sequence matches and can't be entered in the middle,
+/// - every bulk memory/table operation is preceded by the dynamic fuel check emitted by
+///   [`InstructionSet::op_memory_fill_checked`] and similar. Unmetered code can only initialize
+///   segments and grow memory or tables, like the entrypoint does. The check uses the bulk fuel
+///   shifts recorded in the metadata section (or the canonical ones if there is no metadata), so
+///   pinning the metadata pins the shifts as well.
+///
+/// The charged amounts aren't checked, they are defined by the compiler. The module must pass the
+/// structure and stack verification first.
+pub(super) fn verify_fuel(module: &RwasmModuleInner) -> Result<(), RwasmModuleVerificationError> {
+    let code = &module.code_section;
+    let mut funcs = BTreeSet::from([0, module.source_pc]);
+    funcs.extend(
+        module
+            .export_section
+            .iter()
+            .map(|export| export.func_offset),
+    );
+    let mut indirect_targets = module
+        .elem_section
+        .iter()
+        .copied()
+        .filter(|target| *target != 0)
+        .collect::<BTreeSet<_>>();
+    for opcode in code.iter() {
+        match *opcode {
+            Opcode::CallInternal(target) | Opcode::ReturnCallInternal(target) => {
+                funcs.insert(target);
+            }
+            Opcode::RefFunc(target) if target != 0 => {
+                indirect_targets.insert(target);
+            }
+            _ => {}
+        }
+    }
+    let mut signatures = BTreeMap::<SignatureIdx, Vec<u32>>::new();
+    for target in indirect_targets {
+        // the stack verification ensures that indirect targets start with `SignatureCheck`
+        if let Some(Opcode::SignatureCheck(signature)) = code.get(target as usize) {
+            signatures.entry(*signature).or_default().push(target);
+        }
+        funcs.insert(target);
+    }
+    let bulk_fuel_shifts = module.metadata_section.map_or(
+        (MEMORY_BYTES_PER_FUEL_LOG2, TABLE_ELEMS_PER_FUEL_LOG2),
+        |metadata| {
+            (
+                metadata.memory_bytes_per_fuel_log2,
+                metadata.table_elems_per_fuel_log2,
+            )
+        },
+    );
+    for log2 in [bulk_fuel_shifts.0, bulk_fuel_shifts.1] {
+        if log2 >= u32::BITS {
+            return Err(RwasmModuleVerificationError::InvalidBulkFuelShift { log2 });
+        }
+    }
+    let mut analysis = FuelAnalysis::new(code, signatures, bulk_fuel_shifts);
+    for func in funcs {
+        analysis.enter(func)?;
+    }
+    analysis.run()?;
+    analysis.jumps.sort_unstable();
+    analysis.verify_cycles()?;
+    analysis.verify_bulk_ops()
+}
+
+struct FuelAnalysis<'a> {
+    code: &'a InstructionSet,
+    /// Indirect call targets grouped by the signature of their `SignatureCheck`.
+    signatures: BTreeMap<SignatureIdx, Vec<u32>>,
+    states: Vec<Option<FuelState>>,
+    queue: Vec<usize>,
+    funcs: Vec<u32>,
+    /// All `(target, source)` pairs of branches and function entries.
+    jumps: Vec<(usize, usize)>,
+    /// Code emitted by the snippets with loops that don't consume fuel.
+    bounded_loops: [InstructionSet; 8],
+    /// The memory bytes and table elements per fuel shifts of the bulk fuel checks.
+    bulk_fuel_shifts: (u32, u32),
+}
+
+impl<'a> FuelAnalysis<'a> {
+    fn new(
+        code: &'a InstructionSet,
+        signatures: BTreeMap<SignatureIdx, Vec<u32>>,
+        bulk_fuel_shifts: (u32, u32),
+    ) -> Self {
+        Self {
+            code,
+            signatures,
+            states: vec![None; code.len()],
+            queue: Vec::new(),
+            funcs: Vec::new(),
+            jumps: Vec::new(),
+            bounded_loops: InstructionSet::bounded_loop_snippets(),
+            bulk_fuel_shifts,
+        }
+    }
+
+    fn enter(&mut self, func: u32) -> Result<(), RwasmModuleVerificationError> {
+        self.funcs.push(func);
+        self.jumps.push((func as usize, FUNC_ENTRY));
+        let state = FuelState {
+            func,
+            instrumented: false,
+            charged: false,
+        };
+        self.propagate(func as usize, state)
+    }
+
+    fn run(&mut self) -> Result<(), RwasmModuleVerificationError> {
+        while let Some(pc) = self.queue.pop() {
+            let state = self.states[pc].unwrap_or_else(|| unreachable!("rwasm: unvisited pc"));
+            self.step(pc, state)?;
+        }
+        Ok(())
+    }
+
+    fn step(&mut self, pc: usize, state: FuelState) -> Result<(), RwasmModuleVerificationError> {
+        let opcode = self.code[pc];
+        let wasm_func = self.starts_with_signature_check(state.func);
+        let entry_check = pc == state.func as usize && matches!(opcode, Opcode::SignatureCheck(_));
+        if wasm_func
+            && !state.instrumented
+            && !entry_check
+            && !matches!(opcode, Opcode::ConsumeFuel(_))
+        {
+            return Err(RwasmModuleVerificationError::UnmeteredFunctionEntry {
+                func: state.func,
+                pc,
+            });
+        }
+        if !state.charged {
+            let allowed = match opcode {
+                Opcode::CallInternal(target) | Opcode::ReturnCallInternal(target) => {
+                    !wasm_func && self.starts_with_signature_check(target)
+                }
+                Opcode::CallIndirect(_) | Opcode::ReturnCallIndirect(_) => !wasm_func,
+                _ => true,
+            };
+            if !allowed {
+                return Err(RwasmModuleVerificationError::UnmeteredCall { pc });
+            }
+        }
+        let next = FuelState {
+            instrumented: state.instrumented || matches!(opcode, Opcode::ConsumeFuel(_)),
+            charged: state.charged || is_fuel_charging(opcode),
+            ..state
+        };
+        let branches = branch_targets(opcode, pc);
+        self.jumps
+            .extend(branches.iter().map(|target| (*target, pc)));
+        for target in fallthrough(opcode, pc).into_iter().chain(branches) {
+            if target >= self.code.len() {
+                return Err(RwasmModuleVerificationError::FallthroughOutOfBounds {
+                    pc,
+                    code_len: self.code.len(),
+                });
+            }
+            self.propagate(target, next)?;
+        }
+        Ok(())
+    }
+
+    fn propagate(
+        &mut self,
+        pc: usize,
+        state: FuelState,
+    ) -> Result<(), RwasmModuleVerificationError> {
+        match &mut self.states[pc] {
+            Some(prev) if prev.func != state.func => {
+                Err(RwasmModuleVerificationError::OverlappingFunctions {
+                    pc,
+                    func: state.func,
+                    other_func: prev.func,
+                })
+            }
+            Some(prev) => {
+                let joined = FuelState {
+                    func: prev.func,
+                    instrumented: prev.instrumented && state.instrumented,
+                    charged: prev.charged && state.charged,
+                };
+                if joined != *prev {
+                    *prev = joined;
+                    self.queue.push(pc);
+                }
+                Ok(())
+            }
+            prev @ None => {
+                *prev = Some(state);
+                self.queue.push(pc);
+                Ok(())
+            }
+        }
+    }
+
+    fn starts_with_signature_check(&self, func: u32) -> bool {
+        matches!(
+            self.code.get(func as usize),
+            Some(Opcode::SignatureCheck(_))
+        )
+    }
+
+    /// Returns `true` if some code outside `start..end` can jump inside the range (except the
+    /// `start` itself).
+    fn entered_from_outside(&self, start: usize, end: usize) -> bool {
+        let from = self.jumps.partition_point(|(target, _)| *target <= start);
+        self.jumps[from..]
+            .iter()
+            .take_while(|(target, _)| *target < end)
+            .any(|(_, source)| !(start..end).contains(source))
+    }
+
+    /// Searches for a cycle of branches and calls that doesn't pass through a fuel charging.
+    fn verify_cycles(&self) -> Result<(), RwasmModuleVerificationError> {
+        const UNVISITED: u8 = 0;
+        const IN_PROGRESS: u8 = 1;
+        const DONE: u8 = 2;
+        let mut marks = vec![UNVISITED; self.code.len()];
+        // every fuel charging breaks cycles, so the search restarts from its successors
+        let mut roots = self
+            .funcs
+            .iter()
+            .map(|func| *func as usize)
+            .collect::<Vec<_>>();
+        while let Some(root) = roots.pop() {
+            if marks[root] != UNVISITED {
+                continue;
+            }
+            marks[root] = IN_PROGRESS;
+            let mut stack = vec![(root, self.cycle_successors(root), 0)];
+            while let Some((pc, targets, next)) = stack.last_mut() {
+                let pc = *pc;
+                let Some(target) = targets.get(*next).copied() else {
+                    marks[pc] = DONE;
+                    stack.pop();
+                    continue;
+                };
+                *next += 1;
+                if is_fuel_charging(self.code[target]) {
+                    roots.push(target);
+                    continue;
+                }
+                match marks[target] {
+                    UNVISITED => {
+                        marks[target] = IN_PROGRESS;
+                        stack.push((target, self.cycle_successors(target), 0));
+                    }
+                    IN_PROGRESS if !self.is_bounded_loop(pc, target) => {
+                        return Err(RwasmModuleVerificationError::UnmeteredLoop { pc, target });
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Successors of the instruction including the entries of callees.
+    fn cycle_successors(&self, pc: usize) -> Vec<usize> {
+        let opcode = self.code[pc];
+        let mut targets = fallthrough(opcode, pc)
+            .into_iter()
+            .chain(branch_targets(opcode, pc))
+            .collect::<Vec<_>>();
+        match opcode {
+            Opcode::CallInternal(target) | Opcode::ReturnCallInternal(target) => {
+                targets.push(target as usize);
+            }
+            Opcode::CallIndirect(signature) | Opcode::ReturnCallIndirect(signature) => {
+                if let Some(funcs) = self.signatures.get(&signature) {
+                    targets.extend(funcs.iter().map(|func| *func as usize));
+                }
+            }
+            _ => {}
+        }
+        targets
+    }
+
+    /// Checks that the back-edge belongs to the code emitted by a snippet with bounded loops.
+    fn is_bounded_loop(&self, pc: usize, target: usize) -> bool {
+        if target > pc || !matches!(self.code[pc], Opcode::BrIfEqz(_) | Opcode::BrIfNez(_)) {
+            return false;
+        }
+        self.bounded_loops.iter().any(|body| {
+            body.iter()
+                .enumerate()
+                .filter(|(_, opcode)| **opcode == self.code[pc])
+                .any(|(index, _)| {
+                    let Some(start) = pc.checked_sub(index) else {
+                        return false;
+                    };
+                    let end = start + body.len();
+                    end <= self.code.len()
+                        && self.code[start..end] == body[..]
+                        && !self.entered_from_outside(start, end)
+                })
+        })
+    }
+
+    fn verify_bulk_ops(&self) -> Result<(), RwasmModuleVerificationError> {
+        for (pc, state) in self.states.iter().enumerate() {
+            let Some(state) = state else {
+                continue;
+            };
+            let opcode = self.code[pc];
+            let Some(fuel_check) = bulk_fuel_check(opcode, self.bulk_fuel_shifts) else {
+                continue;
+            };
+            let initializer = matches!(
+                opcode,
+                Opcode::MemoryInit(_)
+                    | Opcode::MemoryInitAt(_, _)
+                    | Opcode::TableInit(_)
+                    | Opcode::MemoryGrow
+                    | Opcode::MemoryGrowAt(_)
+                    | Opcode::TableGrow(_)
+            );
+            if initializer && !self.starts_with_signature_check(state.func) {
+                continue;
+            }
+            let checked = pc >= BULK_FUEL_CHECK_LEN
+                && self.code[pc - BULK_FUEL_CHECK_LEN..pc] == fuel_check[..]
+                && !self.entered_from_outside(pc - BULK_FUEL_CHECK_LEN, pc + 1);
+            if !checked {
+                return Err(RwasmModuleVerificationError::MissingBulkFuelCheck { pc });
+            }
+        }
+        Ok(())
+    }
+}
+
+fn is_fuel_charging(opcode: Opcode) -> bool {
+    matches!(opcode, Opcode::ConsumeFuel(fuel) if fuel > 0)
+}
+
+/// The next instruction if the execution can continue after the instruction.
+///
+/// Calls are assumed to return, the callee can't change the fuel state of the caller.
+fn fallthrough(opcode: Opcode, pc: usize) -> Option<usize> {
+    match opcode {
+        Opcode::Unreachable
+        | Opcode::Trap(_)
+        | Opcode::Return
+        | Opcode::ReturnCall(_)
+        | Opcode::ReturnCallInternal(_)
+        | Opcode::ReturnCallIndirect(_)
+        | Opcode::Br(_)
+        | Opcode::BrTable(_)
+        | Opcode::Throw(_)
+        | Opcode::Rethrow(_)
+        | Opcode::Delegate(_) => None,
+        // skip the table index payload
+        Opcode::CallIndirect(_) | Opcode::TableInit(_) => Some(pc + 2),
+        _ => Some(pc + 1),
+    }
+}
+
+/// Branch targets are checked by [`super::verification`] before the fuel analysis.
+fn branch_targets(opcode: Opcode, pc: usize) -> Vec<usize> {
+    let target = |offset: i32| (pc as i64 + offset as i64) as usize;
+    match opcode {
+        Opcode::Br(offset)
+        | Opcode::BrIfEqz(offset)
+        | Opcode::BrIfNez(offset)
+        | Opcode::TryBegin(offset) => vec![target(offset.to_i32())],
+        Opcode::BrTable(targets) => (0..targets as usize).map(|i| pc + 2 * i + 1).collect(),
+        _ => vec![],
+    }
+}
+
+/// The dynamic fuel check that must precede the bulk operation, it charges fuel for the number
+/// of bytes (or table elements) on top of the stack.
+fn bulk_fuel_check(
+    opcode: Opcode,
+    (memory_bytes_per_fuel_log2, table_elems_per_fuel_log2): (u32, u32),
+) -> Option<[Opcode; BULK_FUEL_CHECK_LEN]> {
+    let per_units = |units_per_fuel_log2: u32| {
+        [
+            Opcode::LocalGet(1),
+            Opcode::I32Const(((1u32 << units_per_fuel_log2) - 1).into()),
+            Opcode::I32Add,
+            Opcode::I32Const(units_per_fuel_log2.into()),
+            Opcode::I32ShrU,
+            Opcode::ConsumeFuelStack,
+        ]
+    };
+    match opcode {
+        Opcode::MemoryGrow | Opcode::MemoryGrowAt(_) => Some([
+            Opcode::LocalGet(1),
+            Opcode::I32Const(N_BYTES_PER_MEMORY_PAGE.into()),
+            Opcode::I32Mul,
+            Opcode::I32Const(memory_bytes_per_fuel_log2.into()),
+            Opcode::I32ShrU,
+            Opcode::ConsumeFuelStack,
+        ]),
+        Opcode::MemoryFill
+        | Opcode::MemoryFillAt(_)
+        | Opcode::MemoryCopy
+        | Opcode::MemoryCopyAt(_, _)
+        | Opcode::MemoryInit(_)
+        | Opcode::MemoryInitAt(_, _) => Some(per_units(memory_bytes_per_fuel_log2)),
+        Opcode::TableGrow(_)
+        | Opcode::TableFill(_)
+        | Opcode::TableCopy(_, _)
+        | Opcode::TableInit(_) => Some(per_units(table_elems_per_fuel_log2)),
+        _ => None,
+    }
+}
@@ -0,0 +1,411 @@
+use crate::{
+    Opcode, RwasmDebugSection, RwasmExport, RwasmFuncName, RwasmSourceMapEntry, RwasmTag,
+    RwasmValType, N_BYTES_PER_MEMORY_PAGE, N_DEFAULT_MAX_MEMORY_PAGES, N_MAX_TABLES,
+    N_MAX_TABLE_SIZE,
+};
+use alloc::{boxed::Box, string::String, vec::Vec};
+use bincode::{
+    de::{read::Reader, Decoder},
+    error::DecodeError,
+    Decode,
+};
+
+/// Resource limits that are enforced while an rWasm module is decoded.
+///
+/// Every length is checked before anything is allocated for the section, and sections are read
+/// incrementally, so a small binary can't declare a huge section to exhaust the memory.
+/// The limits are applied by [`crate::RwasmModule::new_checked_with_limits`], and
+/// [`crate::RwasmModule::new_verified`] uses the default ones.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct RwasmModuleLimits {
+    /// The maximum number of opcodes in the code section (source map entries are limited too).
+    pub max_opcodes: usize,
+    /// The maximum length of the data section in bytes.
+    pub max_data_bytes: usize,
+    /// The maximum number of entries in the elem section.
+    pub max_elem_entries: usize,
+    /// The maximum number of targets of one `BrTable`.
+    pub max_branch_table_size: u32,
+    /// The maximum number of functions, they are counted by `SignatureCheck` opcodes (exports,
+    /// function names and tags are limited too).
+    pub max_functions: usize,
+    /// The maximum length of the hint section in bytes.
+    pub max_hint_bytes: usize,
+}
+
+impl Default for RwasmModuleLimits {
+    fn default() -> Self {
+        Self {
+            max_opcodes: 1 << 23,
+            // the data section can't exceed the default memory limit (64mB)
+            max_data_bytes: N_DEFAULT_MAX_MEMORY_PAGES as usize * N_BYTES_PER_MEMORY_PAGE as usize,
+            max_elem_entries: N_MAX_TABLES as usize * N_MAX_TABLE_SIZE as usize,
+            // the same limit as `wasmparser` uses for `br_table`
+            max_branch_table_size: 65_520,
+            max_functions: 1_000_000,
+            max_hint_bytes: N_DEFAULT_MAX_MEMORY_PAGES as usize * N_BYTES_PER_MEMORY_PAGE as usize,
+        }
+    }
+}
+
+impl RwasmModuleLimits {
+    /// Limits that accept any module, used by [`crate::RwasmModule::new_checked`].
+    pub const fn unlimited() -> Self {
+        Self {
+            max_opcodes: usize::MAX,
+            max_data_bytes: usize::MAX,
+            max_elem_entries: usize::MAX,
+            max_branch_table_size: u32::MAX,
+            max_functions: usize::MAX,
+            max_hint_bytes: usize::MAX,
+        }
+    }
+
+    pub fn with_max_opcodes(mut self, max_opcodes: usize) -> Self {
+        self.max_opcodes = max_opcodes;
+        self
+    }
+
+    pub fn with_max_data_bytes(mut self, max_data_bytes: usize) -> Self {
+        self.max_data_bytes = max_data_bytes;
+        self
+    }
+
+    pub fn with_max_elem_entries(mut self, max_elem_entries: usize) -> Self {
+        self.max_elem_entries = max_elem_entries;
+        self
+    }
+
+    pub fn with_max_branch_table_size(mut self, max_branch_table_size: u32) -> Self {
+        self.max_branch_table_size = max_branch_table_size;
+        self
+    }
+
+    pub fn with_max_functions(mut self, max_functions: usize) -> Self {
+        self.max_functions = max_functions;
+        self
+    }
+
+    pub fn with_max_hint_bytes(mut self, max_hint_bytes: usize) -> Self {
+        self.max_hint_bytes = max_hint_bytes;
+        self
+    }
+}
+
+/// The size of a chunk that byte sections are read by.
+const READ_CHUNK_SIZE: usize = 64 * 1024;
+
+pub(crate) fn check_len(
+    len: usize,
+    max_len: usize,
+    error: &'static str,
+) -> Result<usize, DecodeError> {
+    if len > max_len {
+        return Err(DecodeError::Other(error));
+    }
+    Ok(len)
+}
+
+/// Checks opcodes of the code section while it's decoded.
+pub(crate) struct CodeLimiter<'a> {
+    limits: &'a RwasmModuleLimits,
+    functions: usize,
+}
+
+impl<'a> CodeLimiter<'a> {
+    pub(crate) fn new(limits: &'a RwasmModuleLimits, code_len: usize) -> Result<Self, DecodeError> {
+        check_len(
+            code_len,
+            limits.max_opcodes,
+            "rwasm: code section exceeds the limit",
+        )?;
+        Ok(Self {
+            limits,
+            functions: 0,
+        })
+    }
+
+    pub(crate) fn check(&mut self, opcode: &Opcode) -> Result<(), DecodeError> {
+        match *opcode {
+            Opcode::SignatureCheck(_) => {
+                self.functions += 1;
+                check_len(
+                    self.functions,
+                    self.limits.max_functions,
+                    "rwasm: number of functions exceeds the limit",
+                )?;
+            }
+            Opcode::BrTable(targets) if targets > self.limits.max_branch_table_size => {
+                return Err(DecodeError::Other("rwasm: branch table exceeds the limit"));
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Reads bytes by chunks, so the buffer grows only as the data is actually read.
+///
+/// The `read` function must fill the entire buffer or fail.
+pub(crate) fn read_bytes_by_chunks<F>(len: usize, mut read: F) -> Result<Vec<u8>, DecodeError>
+where
+    F: FnMut(&mut [u8]) -> Result<(), DecodeError>,
+{
+    let mut bytes = Vec::new();
+    while bytes.len() < len {
+        let offset = bytes.len();
+        let chunk_len = (len - offset).min(READ_CHUNK_SIZE);
+        bytes.resize(offset + chunk_len, 0);
+        read(&mut bytes[offset..])?;
+    }
+    Ok(bytes)
+}
+
+/// Decodes a length prefix of the bincode legacy encoding.
+pub(crate) fn decode_len<D: Decoder>(decoder: &mut D) -> Result<usize, DecodeError> {
+    let len: u64 = Decode::decode(decoder)?;
+    usize::try_from(len).map_err(|_| DecodeError::OutsideUsizeRange(len))
+}
+
+pub(crate) fn decode_bytes<D: Decoder>(
+    decoder: &mut D,
+    max_len: usize,
+    error: &'static str,
+) -> Result<Vec<u8>, DecodeError> {
+    let len = check_len(decode_len(decoder)?, max_len, error)?;
+    read_bytes_by_chunks(len, |buf| decoder.reader().read(buf))
+}
+
+pub(crate) fn decode_vec<D: Decoder, T>(
+    decoder: &mut D,
+    max_len: usize,
+    error: &'static str,
+    mut decode: impl FnMut(&mut D) -> Result<T, DecodeError>,
+) -> Result<Vec<T>, DecodeError> {
+    let len = check_len(decode_len(decoder)?, max_len, error)?;
+    (0..len).map(|_| decode(decoder)).collect()
+}
+
+fn decode_name<D: Decoder>(decoder: &mut D) -> Result<Box<str>, DecodeError> {
+    let bytes = decode_bytes(decoder, usize::MAX, "")?;
+    let name = String::from_utf8(bytes).map_err(|err| DecodeError::Utf8 {
+        inner: err.utf8_error(),
+    })?;
+    Ok(name.into_boxed_str())
+}
+
+/// A section that is decoded from the bincode legacy encoding with the module limits applied.
+///
+/// It produces the same result as [`Decode`], but it never preallocates memory from the declared
+/// lengths.
+pub(crate) trait DecodeWithLimits: Sized {
+    fn decode_with_limits<D: Decoder>(
+        decoder: &mut D,
+        limits: &RwasmModuleLimits,
+    ) -> Result<Self, DecodeError>;
+}
+
+impl DecodeWithLimits for Vec<RwasmExport> {
+    fn decode_with_limits<D: Decoder>(
+        decoder: &mut D,
+        limits: &RwasmModuleLimits,
+    ) -> Result<Self, DecodeError> {
+        let decode_types = |decoder: &mut D| {
+            decode_vec(decoder, usize::MAX, "", |decoder| {
+                <RwasmValType as Decode<D::Context>>::decode(decoder)
+            })
+        };
+        decode_vec(
+            decoder,
+            limits.max_functions,
+            "rwasm: export section exceeds the limit",
+            |decoder| {
+                Ok(RwasmExport {
+                    name: decode_name(decoder)?,
+                    func_offset: Decode::decode(decoder)?,
+                    params: decode_types(decoder)?,
+                    results: decode_types(decoder)?,
+                })
+            },
+        )
+    }
+}
+
+impl DecodeWithLimits for Vec<RwasmTag> {
+    fn decode_with_limits<D: Decoder>(
+        decoder: &mut D,
+        limits: &RwasmModuleLimits,
+    ) -> Result<Self, DecodeError> {
+        decode_vec(
+            decoder,
+            limits.max_functions,
+            "rwasm: tag section exceeds the limit",
+            |decoder| {
+                Ok(RwasmTag {
+                    params: decode_vec(decoder, usize::MAX, "", |decoder| {
+                        <RwasmValType as Decode<D::Context>>::decode(decoder)
+                    })?,
+                })
+            },
+        )
+    }
+}
+
+impl DecodeWithLimits for RwasmDebugSection {
+    fn decode_with_limits<D: Decoder>(
+        decoder: &mut D,
+        limits: &RwasmModuleLimits,
+    ) -> Result<Self, DecodeError> {
+        let source_map = decode_vec(
+            decoder,
+            limits.max_opcodes,
+            "rwasm: source map exceeds the limit",
+            |decoder| <RwasmSourceMapEntry as Decode<D::Context>>::decode(decoder),
+        )?;
+        let func_names = decode_vec(
+            decoder,
+            limits.max_functions,
+            "rwasm: function names exceed the limit",
+            |decoder| {
+                Ok(RwasmFuncName {
+                    func_idx: Decode::decode(decoder)?,
+                    name: decode_name(decoder)?,
+                })
+            },
+        )?;
+        Ok(Self {
+            source_map,
+            func_names,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        instruction_set, RwasmDebugSection, RwasmExport, RwasmFuncName, RwasmModule,
+        RwasmModuleBuilder, RwasmModuleError, RwasmModuleLimits, RwasmSourceMapEntry,
+        RWASM_MAGIC_BYTE_0, RWASM_MAGIC_BYTE_1, RWASM_VERSION_V1, RWASM_VERSION_V3,
+        RWASM_VERSION_V4,
+    };
+    use alloc::vec::Vec;
+    use bincode::error::DecodeError;
+
+    fn test_module() -> RwasmModule {
+        RwasmModuleBuilder::new(instruction_set! {
+            SignatureCheck(0)
+            StackCheck(1)
+            I32Const(0)
+            BrTable(2)
+            Br(2)
+            Br(1)
+            Return
+        })
+        .with_data_section(&[1, 2, 3, 4])
+        .with_elem_section(&[0, 0, 0])
+        .with_hint_section(&[0x00, 0x61, 0x73, 0x6d, 0x01])
+        .with_export(RwasmExport {
+            name: "main".into(),
+            func_offset: 0,
+            params: vec![],
+            results: vec![],
+        })
+        .with_debug_section(RwasmDebugSection {
+            source_map: vec![RwasmSourceMapEntry {
+                start_pc: 0,
+                end_pc: 7,
+                func_idx: 0,
+                wasm_offset: 0,
+            }],
+            func_names: vec![RwasmFuncName {
+                func_idx: 0,
+                name: "main".into(),
+            }],
+        })
+        .build()
+    }
+
+    fn all_encodings(module: &RwasmModule) -> [Vec<u8>; 3] {
+        [
+            module.serialize(),
+            module.serialize_aligned(),
+            module.serialize_compact(),
+        ]
+    }
+
+    #[test]
+    fn test_limits_accept_module_within_limits() {
+        let module = test_module();
+        let limits = RwasmModuleLimits::default()
+            .with_max_opcodes(7)
+            .with_max_data_bytes(4)
+            .with_max_elem_entries(3)
+            .with_max_branch_table_size(2)
+            .with_max_functions(1)
+            .with_max_hint_bytes(5);
+        for encoded in all_encodings(&module) {
+            let decoded = RwasmModule::new_checked_exact_with_limits(&encoded, &limits).unwrap();
+            assert_eq!(decoded, module);
+        }
+    }
+
+    #[test]
+    fn test_limits_reject_oversized_sections() {
+        let module = test_module();
+        let exceeded_limits = [
+            RwasmModuleLimits::default().with_max_opcodes(6),
+            RwasmModuleLimits::default().with_max_data_bytes(3),
+            RwasmModuleLimits::default().with_max_elem_entries(2),
+            RwasmModuleLimits::default().with_max_branch_table_size(1),
+            RwasmModuleLimits::default().with_max_functions(0),
+            RwasmModuleLimits::default().with_max_hint_bytes(4),
+        ];
+        for encoded in all_encodings(&module) {
+            for limits in exceeded_limits.iter() {
+                let err = RwasmModule::new_checked_with_limits(&encoded, limits)
+                    .expect_err("oversized section must be rejected");
+                assert!(matches!(err, DecodeError::Other(_)), "{limits:?}: {err:?}");
+                assert!(RwasmModule::new_verified_with_limits(&encoded, limits).is_err());
+            }
+            // limits don't affect decoding without them
+            assert_eq!(RwasmModule::new_checked_exact(&encoded).unwrap(), module);
+        }
+    }
+
+    #[test]
+    fn test_huge_declared_length_is_not_preallocated() {
+        let huge_len = 1u64 << 40;
+        // legacy encoding with an empty code section and a huge data section
+        let mut legacy = vec![RWASM_MAGIC_BYTE_0, RWASM_MAGIC_BYTE_1, RWASM_VERSION_V1];
+        legacy.extend_from_slice(&0u64.to_le_bytes());
+        legacy.extend_from_slice(&huge_len.to_le_bytes());
+        legacy.extend_from_slice(&[0; 16]);
+        // compact encoding with a huge code section
+        let compact = vec![
+            RWASM_MAGIC_BYTE_0,
+            RWASM_MAGIC_BYTE_1,
+            RWASM_VERSION_V4,
+            0xff,
+            0xff,
+            0xff,
+            0xff,
+            0x0f,
+        ];
+        // aligned encoding with a huge hint section
+        let mut aligned = vec![RWASM_MAGIC_BYTE_0, RWASM_MAGIC_BYTE_1, RWASM_VERSION_V3, 0];
+        aligned.extend_from_slice(&[0; 12]);
+        aligned.extend_from_slice(&u32::MAX.to_le_bytes());
+        aligned.extend_from_slice(&[0; 16]);
+        for encoded in [legacy, compact, aligned] {
+            // without limits the binary is read until the end of the input
+            let err = RwasmModule::new_checked(&encoded).unwrap_err();
+            assert!(matches!(err, DecodeError::UnexpectedEnd { .. }), "{err:?}");
+            // with the default limits it's rejected before reading the section
+            let err = RwasmModule::new_verified(&encoded).unwrap_err();
+            assert!(
+                matches!(err, RwasmModuleError::Decode(DecodeError::Other(_))),
+                "{err:?}"
+            );
+        }
+    }
+}
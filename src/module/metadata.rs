@@ -0,0 +1,77 @@
+use crate::{CompilationConfig, FuelMeteringMode, InstructionSet};
+use bincode::{Decode, Encode};
+use tiny_keccak::{Hasher, Keccak};
+
+/// Compilation settings that affect the executable surface of a module.
+///
+/// Modules compiled with different fuel settings, fuel cost models, metering modes or with fpu
+/// opcodes look the same on the wire, so a node can pin the expected metadata with
+/// [`crate::ExecutionEngine::with_required_metadata`] and reject everything else.
+///
+/// Emitted only with [`CompilationConfig::emit_metadata_section`].
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Default, Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd, Encode, Decode)]
+pub struct RwasmMetadata {
+    /// See [`CompilationConfig::consume_fuel`].
+    pub consume_fuel: bool,
+    /// See [`CompilationConfig::consume_fuel_for_bulk_ops`].
+    pub consume_fuel_for_bulk_ops: bool,
+    /// See [`CompilationConfig::consume_fuel_for_params_and_locals`].
+    pub consume_fuel_for_params_and_locals: bool,
+    /// See [`CompilationConfig::builtins_consume_fuel`].
+    pub builtins_consume_fuel: bool,
+    /// See [`CompilationConfig::code_snippets`].
+    pub code_snippets: bool,
+    /// See [`CompilationConfig::max_allowed_memory_pages`].
+    pub max_allowed_memory_pages: u32,
+    /// Whether the code section contains fpu opcodes, they can be executed only with the `fpu`
+    /// feature.
+    pub uses_fpu: bool,
+    /// A fingerprint of the import linker (see [`crate::ImportLinker::fingerprint`]), if any.
+    pub import_linker_hash: Option<[u8; 32]>,
+    /// A fingerprint of [`CompilationConfig::fuel_cost_model`] (see
+    /// [`crate::FuelCostModel::fingerprint`]).
+    pub fuel_cost_model_hash: [u8; 32],
+    /// See [`crate::FuelCostModel::memory_bytes_per_fuel_log2`].
+    pub memory_bytes_per_fuel_log2: u32,
+    /// See [`crate::FuelCostModel::table_elems_per_fuel_log2`].
+    pub table_elems_per_fuel_log2: u32,
+    /// See [`CompilationConfig::fuel_metering_mode`].
+    pub fuel_metering_mode: FuelMeteringMode,
+}
+
+impl RwasmMetadata {
+    /// Collects the metadata of a module compiled with the config.
+    pub fn new(config: &CompilationConfig, code_section: &InstructionSet) -> Self {
+        Self {
+            consume_fuel: config.consume_fuel,
+            consume_fuel_for_bulk_ops: config.consume_fuel_for_bulk_ops,
+            consume_fuel_for_params_and_locals: config.consume_fuel_for_params_and_locals,
+            builtins_consume_fuel: config.builtins_consume_fuel,
+            code_snippets: config.code_snippets,
+            max_allowed_memory_pages: config.max_allowed_memory_pages,
+            uses_fpu: code_section
+                .iter()
+                .any(|opcode| opcode.is_fpu_instruction()),
+            import_linker_hash: config
+                .import_linker
+                .as_ref()
+                .map(|import_linker| import_linker.fingerprint()),
+            fuel_cost_model_hash: config.fuel_cost_model.fingerprint(),
+            memory_bytes_per_fuel_log2: config.fuel_cost_model.memory_bytes_per_fuel_log2(),
+            table_elems_per_fuel_log2: config.fuel_cost_model.table_elems_per_fuel_log2(),
+            fuel_metering_mode: config.fuel_metering_mode,
+        }
+    }
+
+    /// Returns a keccak256 hash of the metadata encoding.
+    pub fn fingerprint(&self) -> [u8; 32] {
+        let encoded = bincode::encode_to_vec(self, bincode::config::legacy())
+            .unwrap_or_else(|_| unreachable!("rwasm: failed to serialize metadata"));
+        let mut hasher = Keccak::v256();
+        hasher.update(&encoded);
+        let mut hash = [0u8; 32];
+        hasher.finalize(&mut hash);
+        hash
+    }
+}
@@ -1,18 +1,37 @@
 use crate::{
-    CompilationConfig, CompilationError, ConstructorParams, HintType, InstructionSet, ModuleParser,
-    Opcode,
+    CompilationConfig, CompilationError, CompilationReport, ConstructorParams, HintType,
+    ImportLinker, InstructionSet, ModuleParser, Opcode, TagIdx,
 };
 use alloc::{sync::Arc, vec, vec::Vec};
 use bincode::{
-    de::Decoder,
+    de::{read::Reader, Decoder},
     enc::Encoder,
     error::{DecodeError, EncodeError},
     Decode, Encode,
 };
 use core::ops::Deref;
-
+use limits::{CodeLimiter, DecodeWithLimits};
+
+mod code_hash;
+mod compact;
+mod debug;
+mod export;
+mod fuel_analysis;
+mod limits;
+mod metadata;
+mod stack_analysis;
+mod tag;
+mod text;
 mod verification;
-pub use verification::{RwasmModuleError, RwasmModuleVerificationError};
+mod view;
+pub use debug::{RwasmDebugSection, RwasmFuncName, RwasmSourceLocation, RwasmSourceMapEntry};
+pub use export::{RwasmExport, RwasmValType};
+pub use limits::RwasmModuleLimits;
+pub use metadata::RwasmMetadata;
+pub use tag::RwasmTag;
+pub use text::{RwasmTextError, RwasmTextErrorKind};
+pub use verification::{FuelPolicy, RwasmModuleError, RwasmModuleVerificationError};
+pub use view::RwasmModuleView;
 
 /// Represents a compiled rWasm module.
 ///
@@ -50,6 +69,27 @@ impl RwasmModule {
         Ok(result)
     }
 
+    /// Compiles the module like [`Self::compile`] and reports the statistics of the compiled
+    /// code, see [`CompilationReport`].
+    pub fn compile_with_report(
+        config: CompilationConfig,
+        wasm_binary: &[u8],
+    ) -> Result<(Self, ConstructorParams, CompilationReport), CompilationError> {
+        let mut parser = ModuleParser::new(config);
+        parser.parse(wasm_binary)?;
+        parser.finalize_with_report(wasm_binary)
+    }
+
+    /// Assembles a module from the rWasm text format (`.rwat`).
+    ///
+    /// It accepts the output of the [`core::fmt::Display`] implementation, so a disassembled module
+    /// can be assembled back (the hint and debug sections aren't part of the text format).
+    /// Hand-written modules can use labels for branches and symbolic function names, see
+    /// `docs/rwat.md` for the syntax.
+    pub fn from_text(text: &str) -> Result<Self, RwasmTextError> {
+        text::parse_text(text)
+    }
+
     pub fn empty() -> Self {
         RwasmModuleInner {
             code_section: InstructionSet::default(),
@@ -57,6 +97,10 @@ impl RwasmModule {
             elem_section: vec![],
             hint_section: vec![],
             source_pc: 0,
+            export_section: vec![],
+            debug_section: RwasmDebugSection::default(),
+            metadata_section: None,
+            tag_section: vec![],
         }
         .into()
     }
@@ -71,37 +115,165 @@ impl RwasmModule {
         Ok((inner.into(), bytes_read))
     }
 
+    /// Decodes one rWasm module, rejecting sections that exceed the limits.
+    ///
+    /// The limits are checked before anything is allocated, so it's safe to call for untrusted
+    /// binaries.
+    pub fn new_checked_with_limits(
+        sink: &[u8],
+        limits: &RwasmModuleLimits,
+    ) -> Result<(Self, usize), DecodeError> {
+        let (LimitedRwasmModule(inner), bytes_read) =
+            bincode::decode_from_slice_with_context(sink, bincode::config::legacy(), *limits)?;
+        Ok((inner.into(), bytes_read))
+    }
+
     /// Decodes exactly one rWasm module and rejects trailing bytes.
     pub fn new_checked_exact(sink: &[u8]) -> Result<Self, DecodeError> {
-        let (module, bytes_read) = Self::new_checked(sink)?;
+        Self::new_checked_exact_with_limits(sink, &RwasmModuleLimits::unlimited())
+    }
+
+    /// Decodes exactly one rWasm module with the limits applied, rejecting trailing bytes.
+    pub fn new_checked_exact_with_limits(
+        sink: &[u8],
+        limits: &RwasmModuleLimits,
+    ) -> Result<Self, DecodeError> {
+        let (module, bytes_read) = Self::new_checked_with_limits(sink, limits)?;
         if bytes_read != sink.len() {
             return Err(DecodeError::Other("rwasm: trailing bytes after module"));
         }
         Ok(module)
     }
 
-    /// Decodes and explicitly verifies one rWasm module.
+    /// Decodes and explicitly verifies one rWasm module with the default limits.
+    ///
+    /// Modules with system calls are rejected, see [`Self::new_verified_with_import_linker`].
     pub fn new_verified(sink: &[u8]) -> Result<(Self, usize), RwasmModuleError> {
-        let (module, bytes_read) = Self::new_checked(sink)?;
+        Self::new_verified_with_limits(sink, &RwasmModuleLimits::default())
+    }
+
+    /// Decodes and explicitly verifies one rWasm module with the given limits.
+    pub fn new_verified_with_limits(
+        sink: &[u8],
+        limits: &RwasmModuleLimits,
+    ) -> Result<(Self, usize), RwasmModuleError> {
+        let (module, bytes_read) = Self::new_checked_with_limits(sink, limits)?;
         module.verify()?;
         Ok((module, bytes_read))
     }
 
-    /// Decodes and explicitly verifies exactly one rWasm module, rejecting trailing bytes.
+    /// Decodes and explicitly verifies exactly one rWasm module with the default limits,
+    /// rejecting trailing bytes.
     pub fn new_verified_exact(sink: &[u8]) -> Result<Self, RwasmModuleError> {
-        let module = Self::new_checked_exact(sink)?;
+        Self::new_verified_exact_with_limits(sink, &RwasmModuleLimits::default())
+    }
+
+    /// Decodes and explicitly verifies exactly one rWasm module with the given limits, rejecting
+    /// trailing bytes.
+    pub fn new_verified_exact_with_limits(
+        sink: &[u8],
+        limits: &RwasmModuleLimits,
+    ) -> Result<Self, RwasmModuleError> {
+        let module = Self::new_checked_exact_with_limits(sink, limits)?;
         module.verify()?;
         Ok(module)
     }
 
+    /// Decodes and explicitly verifies one rWasm module with the given limits, the stack effects
+    /// of system calls are resolved with the import linker.
+    pub fn new_verified_with_import_linker(
+        sink: &[u8],
+        limits: &RwasmModuleLimits,
+        import_linker: &ImportLinker,
+    ) -> Result<(Self, usize), RwasmModuleError> {
+        let (module, bytes_read) = Self::new_checked_with_limits(sink, limits)?;
+        module.verify_with_import_linker(import_linker)?;
+        Ok((module, bytes_read))
+    }
+
+    /// Decodes and explicitly verifies exactly one rWasm module like
+    /// [`Self::new_verified_with_import_linker`], rejecting trailing bytes.
+    pub fn new_verified_exact_with_import_linker(
+        sink: &[u8],
+        limits: &RwasmModuleLimits,
+        import_linker: &ImportLinker,
+    ) -> Result<Self, RwasmModuleError> {
+        let module = Self::new_checked_exact_with_limits(sink, limits)?;
+        module.verify_with_import_linker(import_linker)?;
+        Ok(module)
+    }
+
     pub fn serialize(&self) -> Vec<u8> {
         bincode::encode_to_vec(&*self.inner, bincode::config::legacy())
             .unwrap_or_else(|_| unreachable!("rwasm: failed to serialize module"))
     }
 
+    /// Serializes the module using the aligned (V3) encoding, where every opcode is stored as a
+    /// fixed-width record.
+    ///
+    /// Such binaries can be loaded without decoding using [`RwasmModuleView`].
+    pub fn serialize_aligned(&self) -> Vec<u8> {
+        view::encode_aligned(&self.inner)
+    }
+
+    /// Serializes the module using the compact (V4) encoding with one-byte opcodes and LEB128
+    /// immediates.
+    ///
+    /// It decodes to exactly the same module, but the binary is much smaller.
+    pub fn serialize_compact(&self) -> Vec<u8> {
+        compact::encode_compact(&self.inner)
+    }
+
+    /// Returns a borrowed view over the module.
+    pub fn as_view(&self) -> RwasmModuleView<'_> {
+        RwasmModuleView::from(self)
+    }
+
+    /// Returns a keccak256 hash of the code, data, elem, export and tag sections and the source
+    /// PC.
+    ///
+    /// The hash identifies the executable content of the module: it's the same for all binary
+    /// encodings and doesn't depend on the hint, debug and metadata sections. The metadata only
+    /// records the settings the code is compiled with, the fuel instrumentation they produce is a
+    /// part of the code already, so modules with the same hash execute the same way. Use
+    /// [`crate::ExecutionEngine::with_required_metadata`] to pin the metadata itself.
+    pub fn code_hash(&self) -> [u8; 32] {
+        code_hash::code_hash(&self.inner, false)
+    }
+
+    /// Returns the same hash as [`Self::code_hash`], but the hint section is included as well.
+    ///
+    /// The hint section stores the original Wasm binary, so the hash, combined with a fingerprint
+    /// of the compilation settings, can be used as a caching key for anything compiled from it,
+    /// see [`crate::StrategyDefinition::from_rwasm_module_as_wasmtime`].
+    pub fn code_hash_with_hint_section(&self) -> [u8; 32] {
+        code_hash::code_hash(&self.inner, true)
+    }
+
     pub fn hint_type(&self) -> HintType {
         HintType::from_ref(&self.hint_section)
     }
+
+    /// Finds an exported function by its name.
+    pub fn resolve_export(&self, name: &str) -> Option<&RwasmExport> {
+        self.export_section
+            .iter()
+            .find(|export| export.name.as_ref() == name)
+    }
+
+    /// Returns the exception tag with the given index.
+    pub fn resolve_tag(&self, tag: TagIdx) -> Option<&RwasmTag> {
+        self.tag_section.get(tag as usize)
+    }
+
+    /// Maps a program counter inside the code section back to the original Wasm function and
+    /// operator offset.
+    ///
+    /// Returns `None` if the module has no debug section or the PC isn't compiled from a Wasm
+    /// function.
+    pub fn resolve_source_location(&self, pc: u32) -> Option<RwasmSourceLocation<'_>> {
+        self.debug_section.resolve(pc)
+    }
 }
 
 impl From<RwasmModuleInner> for RwasmModule {
@@ -147,6 +319,26 @@ pub struct RwasmModuleInner {
     ///
     /// Note: For old binaries this is always 0.
     pub source_pc: u32,
+
+    /// Exported functions that can be called by name, sorted by the export name.
+    ///
+    /// Note: Presented only in V2 and V5 binaries, for V1 binaries it's always empty.
+    pub export_section: Vec<RwasmExport>,
+
+    /// Debug information that maps program counters to the original Wasm binary.
+    ///
+    /// Note: Presented only in V5 and V6 binaries (and optionally in V3/V4), otherwise it's empty.
+    pub debug_section: RwasmDebugSection,
+
+    /// Compilation settings the module is compiled with.
+    ///
+    /// Note: Presented only in V6 binaries (and optionally in V3/V4), otherwise it's `None`.
+    pub metadata_section: Option<RwasmMetadata>,
+
+    /// Exception tags that can be thrown and caught inside the module.
+    ///
+    /// Note: Presented only in V7 binaries (and optionally in V3/V4), otherwise it's empty.
+    pub tag_section: Vec<RwasmTag>,
 }
 
 /// Rwasm magic bytes 0xef52 (0x52 stands for 'R' in ASCII)
@@ -156,38 +348,142 @@ pub const RWASM_MAGIC_BYTE_1: u8 = 0x52;
 /// Rwasm binary version
 pub const RWASM_VERSION_V1: u8 = 0x01;
 
+/// Rwasm binary version with an export section
+pub const RWASM_VERSION_V2: u8 = 0x02;
+
+/// Rwasm binary version with fixed-width aligned opcodes (see [`RwasmModuleView`])
+pub const RWASM_VERSION_V3: u8 = 0x03;
+
+/// Rwasm binary version with compact opcodes (see [`RwasmModule::serialize_compact`])
+pub const RWASM_VERSION_V4: u8 = 0x04;
+
+/// Rwasm binary version with an export and a debug section
+pub const RWASM_VERSION_V5: u8 = 0x05;
+
+/// Rwasm binary version with an export, a debug and a metadata section
+pub const RWASM_VERSION_V6: u8 = 0x06;
+
+/// Rwasm binary version with an export, a debug, an optional metadata and a tag section
+pub const RWASM_VERSION_V7: u8 = 0x07;
+
 impl Encode for RwasmModuleInner {
     fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
         Encode::encode(&RWASM_MAGIC_BYTE_0, encoder)?;
         Encode::encode(&RWASM_MAGIC_BYTE_1, encoder)?;
-        Encode::encode(&RWASM_VERSION_V1, encoder)?;
+        // we keep V1 for modules w/o exports to stay binary compatible with old binaries
+        let version = if !self.tag_section.is_empty() {
+            RWASM_VERSION_V7
+        } else if self.metadata_section.is_some() {
+            RWASM_VERSION_V6
+        } else if !self.debug_section.is_empty() {
+            RWASM_VERSION_V5
+        } else if !self.export_section.is_empty() {
+            RWASM_VERSION_V2
+        } else {
+            RWASM_VERSION_V1
+        };
+        Encode::encode(&version, encoder)?;
         Encode::encode(&self.code_section, encoder)?;
         Encode::encode(&self.data_section, encoder)?;
         Encode::encode(&self.elem_section, encoder)?;
         Encode::encode(&self.hint_section, encoder)?;
         Encode::encode(&self.source_pc, encoder)?;
+        if version != RWASM_VERSION_V1 {
+            Encode::encode(&self.export_section, encoder)?;
+        }
+        if matches!(
+            version,
+            RWASM_VERSION_V5 | RWASM_VERSION_V6 | RWASM_VERSION_V7
+        ) {
+            Encode::encode(&self.debug_section, encoder)?;
+        }
+        if version == RWASM_VERSION_V7 {
+            Encode::encode(&self.metadata_section, encoder)?;
+            Encode::encode(&self.tag_section, encoder)?;
+        } else if let Some(metadata_section) = &self.metadata_section {
+            Encode::encode(metadata_section, encoder)?;
+        }
         Ok(())
     }
 }
 
 impl<Context> Decode<Context> for RwasmModuleInner {
     fn decode<D: Decoder<Context = Context>>(decoder: &mut D) -> Result<Self, DecodeError> {
+        Self::decode_with_limits(decoder, &RwasmModuleLimits::unlimited())
+    }
+}
+
+/// A module that is decoded with the limits passed as a decoder context.
+struct LimitedRwasmModule(RwasmModuleInner);
+
+impl Decode<RwasmModuleLimits> for LimitedRwasmModule {
+    fn decode<D: Decoder<Context = RwasmModuleLimits>>(
+        decoder: &mut D,
+    ) -> Result<Self, DecodeError> {
+        let limits = *decoder.context();
+        RwasmModuleInner::decode_with_limits(decoder, &limits).map(Self)
+    }
+}
+
+impl DecodeWithLimits for RwasmModuleInner {
+    fn decode_with_limits<D: Decoder>(
+        decoder: &mut D,
+        limits: &RwasmModuleLimits,
+    ) -> Result<Self, DecodeError> {
         let sig0: u8 = Decode::decode(decoder)?;
         let sig1: u8 = Decode::decode(decoder)?;
         if sig0 != RWASM_MAGIC_BYTE_0 || sig1 != RWASM_MAGIC_BYTE_1 {
             return Err(DecodeError::Other("rwasm: invalid magic bytes"));
         }
         let version: u8 = Decode::decode(decoder)?;
-        if version != RWASM_VERSION_V1 {
+        if version == RWASM_VERSION_V3 {
+            // the aligned layout doesn't rely on bincode, so we read raw bytes
+            let mut header = [0u8; view::ALIGNED_HEADER_SIZE];
+            header[..3].copy_from_slice(&[sig0, sig1, version]);
+            decoder.reader().read(&mut header[3..])?;
+            return view::decode_aligned(&header, limits, |buf| decoder.reader().read(buf));
+        }
+        if version == RWASM_VERSION_V4 {
+            return compact::decode_compact(decoder.reader(), limits);
+        }
+        if !matches!(
+            version,
+            RWASM_VERSION_V1
+                | RWASM_VERSION_V2
+                | RWASM_VERSION_V5
+                | RWASM_VERSION_V6
+                | RWASM_VERSION_V7
+        ) {
             return Err(DecodeError::Other("rwasm: not supported version"));
         }
-        let code_section: InstructionSet = Decode::decode(decoder)?;
-        let data_section: Vec<u8> = Decode::decode(decoder)?;
-        let elem_section: Vec<u32> = Decode::decode(decoder)?;
-        let wasm_section: Vec<u8> = Decode::decode(decoder)?;
+        // the same as `InstructionSet::decode`, but the length is checked before reading opcodes
+        let code_len = limits::decode_len(decoder)?;
+        let mut code_limiter = CodeLimiter::new(limits, code_len)?;
+        let mut code_section = InstructionSet::new();
+        for _ in 0..code_len {
+            let opcode: Opcode = Decode::decode(decoder)?;
+            code_limiter.check(&opcode)?;
+            code_section.push(opcode);
+        }
+        let data_section = limits::decode_bytes(
+            decoder,
+            limits.max_data_bytes,
+            "rwasm: data section exceeds the limit",
+        )?;
+        let elem_section: Vec<u32> = limits::decode_vec(
+            decoder,
+            limits.max_elem_entries,
+            "rwasm: elem section exceeds the limit",
+            |decoder| Decode::decode(decoder),
+        )?;
+        let wasm_section = limits::decode_bytes(
+            decoder,
+            limits.max_hint_bytes,
+            "rwasm: hint section exceeds the limit",
+        )?;
         let source_pc: u32 = match Decode::decode(decoder) {
             Ok(source_pc) => source_pc,
-            Err(DecodeError::UnexpectedEnd { additional }) => {
+            Err(DecodeError::UnexpectedEnd { additional }) if version == RWASM_VERSION_V1 => {
                 if additional != size_of::<u32>() {
                     return Err(DecodeError::UnexpectedEnd { additional });
                 }
@@ -196,12 +492,39 @@ impl<Context> Decode<Context> for RwasmModuleInner {
             }
             Err(err) => return Err(err),
         };
+        let export_section = if version != RWASM_VERSION_V1 {
+            Vec::<RwasmExport>::decode_with_limits(decoder, limits)?
+        } else {
+            vec![]
+        };
+        let debug_section = if matches!(
+            version,
+            RWASM_VERSION_V5 | RWASM_VERSION_V6 | RWASM_VERSION_V7
+        ) {
+            RwasmDebugSection::decode_with_limits(decoder, limits)?
+        } else {
+            RwasmDebugSection::default()
+        };
+        let metadata_section = match version {
+            RWASM_VERSION_V6 => Some(Decode::decode(decoder)?),
+            RWASM_VERSION_V7 => Decode::decode(decoder)?,
+            _ => None,
+        };
+        let tag_section = if version == RWASM_VERSION_V7 {
+            Vec::<RwasmTag>::decode_with_limits(decoder, limits)?
+        } else {
+            vec![]
+        };
         Ok(Self {
             code_section,
             data_section,
             elem_section,
             hint_section: wasm_section,
             source_pc,
+            export_section,
+            debug_section,
+            metadata_section,
+            tag_section,
         })
     }
 }
@@ -227,6 +550,16 @@ impl core::fmt::Display for RwasmModule {
         writeln!(f, " .ro_data: {:x?},", self.data_section.as_slice())?;
         writeln!(f, " .ro_elem: {:?},", self.elem_section.as_slice())?;
         writeln!(f, " .source_pc: {:?},", self.source_pc)?;
+        for export in self.export_section.iter() {
+            writeln!(
+                f,
+                " .export: {:?} -> {} {:?} -> {:?},",
+                export.name, export.func_offset, export.params, export.results
+            )?;
+        }
+        for tag in self.tag_section.iter() {
+            writeln!(f, " .tag: {:?},", tag.params)?;
+        }
         writeln!(f, "}}")?;
         Ok(())
     }
@@ -239,6 +572,10 @@ pub struct RwasmModuleBuilder {
     elem_section: Vec<u32>,
     hint_section: Vec<u8>,
     source_pc: u32,
+    export_section: Vec<RwasmExport>,
+    debug_section: RwasmDebugSection,
+    metadata_section: Option<RwasmMetadata>,
+    tag_section: Vec<RwasmTag>,
 }
 
 impl RwasmModuleBuilder {
@@ -269,13 +606,38 @@ impl RwasmModuleBuilder {
         self
     }
 
-    pub fn build(self) -> RwasmModule {
+    pub fn with_export(mut self, export: RwasmExport) -> Self {
+        self.export_section.push(export);
+        self
+    }
+
+    pub fn with_debug_section(mut self, debug_section: RwasmDebugSection) -> Self {
+        self.debug_section = debug_section;
+        self
+    }
+
+    pub fn with_metadata_section(mut self, metadata_section: RwasmMetadata) -> Self {
+        self.metadata_section = Some(metadata_section);
+        self
+    }
+
+    pub fn with_tag(mut self, tag: RwasmTag) -> Self {
+        self.tag_section.push(tag);
+        self
+    }
+
+    pub fn build(mut self) -> RwasmModule {
+        self.export_section.sort_by(|a, b| a.name.cmp(&b.name));
         RwasmModuleInner {
             code_section: self.code_section,
             data_section: self.data_section,
             elem_section: self.elem_section,
             hint_section: self.hint_section,
             source_pc: self.source_pc,
+            export_section: self.export_section,
+            debug_section: self.debug_section,
+            metadata_section: self.metadata_section,
+            tag_section: self.tag_section,
         }
         .into()
     }
@@ -289,9 +651,14 @@ impl From<RwasmModuleBuilder> for RwasmModule {
 
 #[cfg(test)]
 mod tests {
-    use crate::{instruction_set, RwasmModule, RwasmModuleInner};
+    use crate::{
+        instruction_set, Opcode, RwasmDebugSection, RwasmExport, RwasmFuncName, RwasmMetadata,
+        RwasmModule, RwasmModuleInner, RwasmSourceMapEntry, RwasmValType, RWASM_VERSION_V1,
+        RWASM_VERSION_V2, RWASM_VERSION_V5, RWASM_VERSION_V6,
+    };
     use bincode::error::DecodeError;
     use hex_literal::hex;
+    use wasmparser::ValType;
 
     fn test_module() -> RwasmModuleInner {
         RwasmModuleInner {
@@ -307,6 +674,10 @@ mod tests {
             elem_section: vec![],
             hint_section: vec![],
             source_pc: 0,
+            export_section: vec![],
+            debug_section: Default::default(),
+            metadata_section: None,
+            tag_section: vec![],
         }
     }
 
@@ -322,6 +693,144 @@ mod tests {
         assert_eq!(encoded_module, RwasmModule::from(module2).serialize());
     }
 
+    #[test]
+    fn test_module_with_exports_encoding() {
+        let mut module = test_module();
+        let encoded_v1 = bincode::encode_to_vec(&module, bincode::config::legacy()).unwrap();
+        assert_eq!(encoded_v1[2], RWASM_VERSION_V1);
+        module.export_section.push(RwasmExport {
+            name: "main".into(),
+            func_offset: 0,
+            params: vec![RwasmValType::I32, RwasmValType::I64],
+            results: vec![RwasmValType::I32],
+        });
+        let encoded_v2 = bincode::encode_to_vec(&module, bincode::config::legacy()).unwrap();
+        assert_eq!(encoded_v2[2], RWASM_VERSION_V2);
+        let module2 = RwasmModule::new_checked_exact(&encoded_v2).unwrap();
+        assert_eq!(*module2, module);
+        let export = module2.resolve_export("main").unwrap();
+        assert_eq!(export.func_type().params(), &[ValType::I32, ValType::I64]);
+        assert_eq!(export.func_type().results(), &[ValType::I32]);
+        // V2 binaries must always have a source pc followed by the export section
+        let err = RwasmModule::new_checked(&encoded_v2[..encoded_v1.len() - 4]).unwrap_err();
+        assert!(matches!(err, DecodeError::UnexpectedEnd { .. }));
+    }
+
+    #[test]
+    fn test_module_with_debug_section_encoding() {
+        let mut module = test_module();
+        module.debug_section = RwasmDebugSection {
+            source_map: vec![RwasmSourceMapEntry {
+                start_pc: 1,
+                end_pc: 3,
+                func_idx: 0,
+                wasm_offset: 42,
+            }],
+            func_names: vec![RwasmFuncName {
+                func_idx: 0,
+                name: "main".into(),
+            }],
+        };
+        let encoded_v5 = bincode::encode_to_vec(&module, bincode::config::legacy()).unwrap();
+        assert_eq!(encoded_v5[2], RWASM_VERSION_V5);
+        let module2 = RwasmModule::from(module.clone());
+        for encoded in [
+            encoded_v5,
+            module2.serialize_aligned(),
+            module2.serialize_compact(),
+        ] {
+            let module3 = RwasmModule::new_checked_exact(&encoded).unwrap();
+            assert_eq!(*module3, module);
+        }
+        let location = module2.resolve_source_location(2).unwrap();
+        assert_eq!(location.wasm_offset, 42);
+        assert_eq!(location.func_name, Some("main"));
+        assert_eq!(module2.resolve_source_location(3), None);
+    }
+
+    #[test]
+    fn test_module_with_metadata_section_encoding() {
+        let mut module = test_module();
+        module.metadata_section = Some(RwasmMetadata {
+            consume_fuel: true,
+            max_allowed_memory_pages: 16,
+            import_linker_hash: Some([7; 32]),
+            ..Default::default()
+        });
+        let encoded_v6 = bincode::encode_to_vec(&module, bincode::config::legacy()).unwrap();
+        assert_eq!(encoded_v6[2], RWASM_VERSION_V6);
+        let module2 = RwasmModule::from(module.clone());
+        for encoded in [
+            encoded_v6,
+            module2.serialize_aligned(),
+            module2.serialize_compact(),
+        ] {
+            let module3 = RwasmModule::new_checked_exact(&encoded).unwrap();
+            assert_eq!(*module3, module);
+        }
+    }
+
+    #[test]
+    fn test_code_hash() {
+        let module = RwasmModule::from(test_module());
+        let code_hash = module.code_hash();
+        // the hash is a part of the public API, so the canonical encoding must stay the same
+        assert_eq!(
+            hex::encode(code_hash),
+            "b4e81903ff5153bfb4971e71ed4bab38a11ed808adfc21533b9147666c9601f5"
+        );
+        // the hash doesn't depend on the encoding, and the debug and metadata sections
+        let mut module2 = test_module();
+        module2.debug_section.func_names.push(RwasmFuncName {
+            func_idx: 0,
+            name: "main".into(),
+        });
+        module2.metadata_section = Some(RwasmMetadata::default());
+        let module2 = RwasmModule::from(module2);
+        for encoded in [
+            module2.serialize(),
+            module2.serialize_aligned(),
+            module2.serialize_compact(),
+        ] {
+            let module3 = RwasmModule::new_checked_exact(&encoded).unwrap();
+            assert_eq!(module3.code_hash(), code_hash);
+            assert_eq!(
+                module3.code_hash_with_hint_section(),
+                module.code_hash_with_hint_section()
+            );
+        }
+        // but any executable part or the hint section changes it
+        let mutations: [fn(&mut RwasmModuleInner); 5] = [
+            |module: &mut RwasmModuleInner| {
+                module.code_section.push(Opcode::Drop);
+            },
+            |module: &mut RwasmModuleInner| module.data_section.push(0),
+            |module: &mut RwasmModuleInner| module.elem_section.push(0),
+            |module: &mut RwasmModuleInner| module.source_pc = 1,
+            |module: &mut RwasmModuleInner| {
+                module.export_section.push(RwasmExport {
+                    name: "main".into(),
+                    func_offset: 0,
+                    params: vec![],
+                    results: vec![],
+                })
+            },
+        ];
+        for mutate in mutations {
+            let mut module2 = test_module();
+            mutate(&mut module2);
+            assert_ne!(RwasmModule::from(module2).code_hash(), code_hash);
+        }
+        let mut module2 = test_module();
+        module2.hint_section = vec![0x00, 0x61, 0x73, 0x6d];
+        let module2 = RwasmModule::from(module2);
+        assert_eq!(module2.code_hash(), code_hash);
+        assert_ne!(
+            module2.code_hash_with_hint_section(),
+            module.code_hash_with_hint_section()
+        );
+    }
+
     #[test]
     fn test_decode_module_wo_source_pc() {
         const LEGACY_MODULE: &[u8] = &hex!("ef52010600000000000000150000006400000015000000140000003e00000015000000030000003e000000160000000000000000000000050000000000000005000000060000000700000008000000090000000000000000000000");
@@ -0,0 +1,689 @@
+use super::{RwasmModuleInner, RwasmModuleVerificationError};
+use crate::{
+    ImportLinker, InstructionSet, Opcode, RwasmTag, SignatureIdx, TagIdx, N_MAX_STACK_SIZE,
+};
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    vec,
+    vec::Vec,
+};
+use wasmparser::ValType;
+
+/// A stack summary of a function, all heights are relative to the function entry.
+#[derive(Default, Copy, Clone, Debug, Eq, PartialEq)]
+struct FuncSummary {
+    /// The stack height at `Return`, `None` while no reachable return is found.
+    delta: Option<i64>,
+    /// The number of values under the entry that the function (or its callees) reads or drops.
+    inputs: i64,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct StackState {
+    height: i64,
+    /// The stack height that is guaranteed to be allocated on every path to the instruction.
+    reserved: i64,
+}
+
+/// A stack effect of a callee, `delta` is `None` if the callee never returns.
+#[derive(Copy, Clone, Debug)]
+struct CallEffect {
+    inputs: i64,
+    delta: Option<i64>,
+}
+
+/// Proves the value stack consistency of every function in the module.
+///
+/// A function is any code offset that can be called or executed by the host. The analyzer
+/// simulates the stack height of each function relative to its entry and checks that:
+/// - every path to an instruction has the same stack height and all returns agree,
+/// - every push is covered by `StackCheck` or by the values left by a callee,
+/// - local depths and callee inputs stay inside the values provided by the caller,
+/// - functions don't share code, so a function summary doesn't depend on the entry.
+///
+/// The stack effect of a system call isn't stored in the module, so system calls are rejected
+/// unless the import linker is provided.
+pub(super) fn verify_stack(
+    module: &RwasmModuleInner,
+    import_linker: Option<&ImportLinker>,
+) -> Result<(), RwasmModuleVerificationError> {
+    let mut analyzer = StackAnalyzer::new(module, import_linker)?;
+    analyzer.run()?;
+    analyzer.verify_exports(module)
+}
+
+struct StackAnalyzer<'a> {
+    code: &'a InstructionSet,
+    tags: &'a [RwasmTag],
+    import_linker: Option<&'a ImportLinker>,
+    summaries: BTreeMap<u32, FuncSummary>,
+    /// Indirect call targets grouped by the signature of their `SignatureCheck`.
+    signatures: BTreeMap<SignatureIdx, Vec<u32>>,
+    /// Functions that must be analyzed again once the summary of the callee changes.
+    callers: BTreeMap<u32, BTreeSet<u32>>,
+    /// A function that owns the instruction (plus one), zero for unvisited instructions.
+    owners: Vec<u32>,
+}
+
+impl<'a> StackAnalyzer<'a> {
+    fn new(
+        module: &'a RwasmModuleInner,
+        import_linker: Option<&'a ImportLinker>,
+    ) -> Result<Self, RwasmModuleVerificationError> {
+        let code = &module.code_section;
+        let mut funcs = BTreeSet::from([0, module.source_pc]);
+        funcs.extend(
+            module
+                .export_section
+                .iter()
+                .map(|export| export.func_offset),
+        );
+        let mut indirect_targets = BTreeSet::new();
+        indirect_targets.extend(
+            module
+                .elem_section
+                .iter()
+                .copied()
+                .filter(|target| *target != 0),
+        );
+        for opcode in code.iter() {
+            match *opcode {
+                Opcode::CallInternal(target) | Opcode::ReturnCallInternal(target) => {
+                    funcs.insert(target);
+                }
+                Opcode::RefFunc(target) if target != 0 => {
+                    indirect_targets.insert(target);
+                }
+                _ => {}
+            }
+        }
+        // an indirect call can reach only functions with the same `SignatureCheck`, it's the only
+        // way to know the stack effect of an indirect call
+        let mut signatures = BTreeMap::<SignatureIdx, Vec<u32>>::new();
+        for target in indirect_targets {
+            let Some(Opcode::SignatureCheck(signature)) = code.get(target as usize) else {
+                return Err(RwasmModuleVerificationError::MissingSignatureCheck { target });
+            };
+            signatures.entry(*signature).or_default().push(target);
+            funcs.insert(target);
+        }
+        Ok(Self {
+            code,
+            tags: &module.tag_section,
+            import_linker,
+            summaries: funcs
+                .into_iter()
+                .map(|func| (func, FuncSummary::default()))
+                .collect(),
+            signatures,
+            callers: BTreeMap::new(),
+            owners: vec![0; code.len()],
+        })
+    }
+
+    fn run(&mut self) -> Result<(), RwasmModuleVerificationError> {
+        // analyzing callees first makes every function (except recursive ones) analyzed once
+        let ranks = self
+            .analysis_order()
+            .into_iter()
+            .enumerate()
+            .map(|(rank, func)| (func, rank))
+            .collect::<BTreeMap<_, _>>();
+        let mut queue = ranks
+            .iter()
+            .map(|(func, rank)| (*rank, *func))
+            .collect::<BTreeSet<_>>();
+        while let Some((_, func)) = queue.pop_first() {
+            let (summary, callees) = FuncAnalysis::new(self, func).run()?;
+            if summary.inputs > N_MAX_STACK_SIZE as i64 {
+                return Err(RwasmModuleVerificationError::StackUnderflow {
+                    pc: func as usize,
+                    required: summary.inputs,
+                    available: N_MAX_STACK_SIZE as i64,
+                });
+            }
+            for callee in callees {
+                self.callers.entry(callee).or_default().insert(func);
+            }
+            if self.summaries.insert(func, summary) != Some(summary) {
+                if let Some(callers) = self.callers.get(&func) {
+                    queue.extend(callers.iter().map(|caller| (ranks[caller], *caller)));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Orders functions so that callees go before their callers.
+    ///
+    /// The order only affects the performance, so callees are found by scanning the code between
+    /// function entries without following the control flow.
+    fn analysis_order(&self) -> Vec<u32> {
+        let funcs = self.summaries.keys().copied().collect::<Vec<_>>();
+        let callees = |index: usize| {
+            let start = funcs[index] as usize;
+            let end = funcs
+                .get(index + 1)
+                .map_or(self.code.len(), |next| *next as usize);
+            self.code[start..end]
+                .iter()
+                .flat_map(|opcode| match *opcode {
+                    Opcode::CallInternal(target) | Opcode::ReturnCallInternal(target) => {
+                        vec![target]
+                    }
+                    Opcode::CallIndirect(signature) | Opcode::ReturnCallIndirect(signature) => {
+                        self.signatures.get(&signature).cloned().unwrap_or_default()
+                    }
+                    _ => vec![],
+                })
+                .filter_map(|target| funcs.binary_search(&target).ok())
+                .collect::<Vec<_>>()
+        };
+        let mut order = Vec::with_capacity(funcs.len());
+        let mut visited = vec![false; funcs.len()];
+        for root in 0..funcs.len() {
+            if visited[root] {
+                continue;
+            }
+            visited[root] = true;
+            let mut stack = vec![(root, callees(root), 0)];
+            while let Some((func, edges, next)) = stack.last_mut() {
+                match edges.get(*next).copied() {
+                    Some(callee) => {
+                        *next += 1;
+                        if !visited[callee] {
+                            visited[callee] = true;
+                            stack.push((callee, callees(callee), 0));
+                        }
+                    }
+                    None => {
+                        order.push(funcs[*func]);
+                        stack.pop();
+                    }
+                }
+            }
+        }
+        order
+    }
+
+    fn verify_exports(
+        &self,
+        module: &RwasmModuleInner,
+    ) -> Result<(), RwasmModuleVerificationError> {
+        for (index, export) in module.export_section.iter().enumerate() {
+            let summary = self.summaries[&export.func_offset];
+            let params = export
+                .params
+                .iter()
+                .map(|ty| value_slots((*ty).into()))
+                .sum();
+            let results = export
+                .results
+                .iter()
+                .map(|ty| value_slots((*ty).into()))
+                .sum::<i64>();
+            if summary.inputs > params {
+                return Err(RwasmModuleVerificationError::StackUnderflow {
+                    pc: export.func_offset as usize,
+                    required: summary.inputs,
+                    available: params,
+                });
+            }
+            match summary.delta {
+                Some(delta) if delta != results - params => {
+                    return Err(RwasmModuleVerificationError::ExportStackEffectMismatch {
+                        index,
+                        expected: results - params,
+                        actual: delta,
+                    });
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn internal_call_effect(&self, target: u32) -> CallEffect {
+        let summary = self.summaries[&target];
+        CallEffect {
+            inputs: summary.inputs,
+            delta: summary.delta,
+        }
+    }
+
+    /// Returns `None` if no function can pass the signature check, so the call always traps.
+    fn indirect_call_effect(
+        &self,
+        signature: SignatureIdx,
+    ) -> Result<Option<CallEffect>, RwasmModuleVerificationError> {
+        let Some(targets) = self.signatures.get(&signature) else {
+            return Ok(None);
+        };
+        let mut effect = CallEffect {
+            inputs: 0,
+            delta: None,
+        };
+        for target in targets.iter().copied() {
+            let summary = self.summaries[&target];
+            effect.inputs = effect.inputs.max(summary.inputs);
+            match (effect.delta, summary.delta) {
+                (Some(delta), Some(target_delta)) if delta != target_delta => {
+                    return Err(RwasmModuleVerificationError::SignatureStackEffectMismatch {
+                        signature,
+                        target,
+                    });
+                }
+                (None, delta) => effect.delta = delta,
+                _ => {}
+            }
+        }
+        Ok(Some(effect))
+    }
+
+    /// Returns the number of stack slots of the exception payload.
+    fn tag_slots(&self, pc: usize, tag: TagIdx) -> Result<i64, RwasmModuleVerificationError> {
+        self.tags
+            .get(tag as usize)
+            .map(|tag| tag.payload_slots() as i64)
+            .ok_or(RwasmModuleVerificationError::TagIndexOutOfBounds { pc, tag })
+    }
+
+    fn syscall_effect(
+        &self,
+        pc: usize,
+        sys_func_idx: u32,
+    ) -> Result<CallEffect, RwasmModuleVerificationError> {
+        let Some(import_linker) = self.import_linker else {
+            return Err(RwasmModuleVerificationError::UnknownSyscallEffect { pc, sys_func_idx });
+        };
+        let entity = import_linker
+            .resolve_by_func_idx(sys_func_idx)
+            .ok_or(RwasmModuleVerificationError::UnresolvedSyscall { pc, sys_func_idx })?;
+        let params = entity.params.iter().copied().map(value_slots).sum::<i64>();
+        let results = entity.result.iter().copied().map(value_slots).sum::<i64>();
+        Ok(CallEffect {
+            inputs: params,
+            delta: Some(results - params),
+        })
+    }
+}
+
+/// The number of stack slots occupied by a value of the given type.
+fn value_slots(ty: ValType) -> i64 {
+    match ty {
+        ValType::I64 | ValType::F64 => 2,
+        ValType::V128 => 4,
+        _ => 1,
+    }
+}
+
+struct FuncAnalysis<'s, 'a> {
+    analyzer: &'s mut StackAnalyzer<'a>,
+    func: u32,
+    states: BTreeMap<u32, StackState>,
+    queue: Vec<u32>,
+    summary: FuncSummary,
+    callees: BTreeSet<u32>,
+}
+
+impl<'s, 'a> FuncAnalysis<'s, 'a> {
+    fn new(analyzer: &'s mut StackAnalyzer<'a>, func: u32) -> Self {
+        let entry = StackState {
+            height: 0,
+            reserved: 0,
+        };
+        Self {
+            analyzer,
+            func,
+            states: BTreeMap::from([(func, entry)]),
+            queue: vec![func],
+            summary: FuncSummary::default(),
+            callees: BTreeSet::new(),
+        }
+    }
+
+    fn run(mut self) -> Result<(FuncSummary, BTreeSet<u32>), RwasmModuleVerificationError> {
+        while let Some(pc) = self.queue.pop() {
+            let owner = &mut self.analyzer.owners[pc as usize];
+            if *owner != 0 && *owner != self.func + 1 {
+                return Err(RwasmModuleVerificationError::OverlappingFunctions {
+                    pc: pc as usize,
+                    func: self.func,
+                    other_func: *owner - 1,
+                });
+            }
+            *owner = self.func + 1;
+            let state = self.states[&pc];
+            self.step(pc as usize, state)?;
+        }
+        Ok((self.summary, self.callees))
+    }
+
+    fn step(
+        &mut self,
+        pc: usize,
+        mut state: StackState,
+    ) -> Result<(), RwasmModuleVerificationError> {
+        let opcode = self.analyzer.code[pc];
+        let mut next = pc + 1;
+        match opcode {
+            Opcode::Unreachable | Opcode::Trap(_) => return Ok(()),
+            Opcode::LocalGet(depth) => {
+                self.touch(state.height - depth as i64);
+                self.push(pc, &mut state, 1)?;
+            }
+            Opcode::LocalSet(depth) => {
+                self.pop(&mut state, 1);
+                self.touch(state.height - depth as i64);
+            }
+            Opcode::LocalTee(depth) => {
+                self.touch(state.height - 1);
+                self.touch(state.height - depth as i64);
+            }
+            Opcode::LocalGetLocalGet(first, second) => {
+                self.touch(state.height - first as i64);
+                self.push(pc, &mut state, 1)?;
+                self.touch(state.height - second as i64);
+                self.push(pc, &mut state, 1)?;
+            }
+            Opcode::LocalGetI32Const(depth, _) => {
+                self.touch(state.height - depth as i64);
+                self.push(pc, &mut state, 2)?;
+            }
+            Opcode::LocalSetLocalGet(first, second) => {
+                self.pop(&mut state, 1);
+                self.touch(state.height - first as i64);
+                self.touch(state.height - second as i64);
+                self.push(pc, &mut state, 1)?;
+            }
+            Opcode::LocalGetI32Load(depth, _) => {
+                self.touch(state.height - depth as i64);
+                self.push(pc, &mut state, 1)?;
+            }
+            Opcode::LocalSetLocalSet(first, second) => {
+                self.pop(&mut state, 1);
+                self.touch(state.height - first as i64);
+                self.pop(&mut state, 1);
+                self.touch(state.height - second as i64);
+            }
+            Opcode::Br(offset) => {
+                return self.jump(branch_target(pc, offset.to_i32()), state);
+            }
+            Opcode::BrIfEqz(offset) | Opcode::BrIfNez(offset) => {
+                self.pop(&mut state, 1);
+                self.jump(branch_target(pc, offset.to_i32()), state)?;
+            }
+            Opcode::BrTable(targets) => {
+                self.pop(&mut state, 1);
+                for index in 0..targets as usize {
+                    self.jump(pc + 2 * index + 1, state)?;
+                }
+                return Ok(());
+            }
+            // the landing pad starts with the stack height of `TryBegin`
+            Opcode::TryBegin(offset) => self.jump(branch_target(pc, offset.to_i32()), state)?,
+            Opcode::Throw(tag) => {
+                let slots = self.analyzer.tag_slots(pc, tag)?;
+                self.pop(&mut state, slots);
+                return Ok(());
+            }
+            Opcode::Rethrow(_) | Opcode::Delegate(_) => return Ok(()),
+            Opcode::ExceptionPayload(tag) => {
+                let slots = self.analyzer.tag_slots(pc, tag)?;
+                self.push(pc, &mut state, slots)?;
+            }
+            Opcode::Return => return self.ret(pc, state.height),
+            Opcode::ReturnCallInternal(target) => {
+                let effect = self.internal_call_effect(target);
+                if let Some(height) = self.call(&mut state, Some(effect)) {
+                    self.ret(pc, height)?;
+                }
+                return Ok(());
+            }
+            Opcode::ReturnCall(sys_func_idx) => {
+                let effect = self.analyzer.syscall_effect(pc, sys_func_idx)?;
+                if let Some(height) = self.call(&mut state, Some(effect)) {
+                    self.ret(pc, height)?;
+                }
+                return Ok(());
+            }
+            Opcode::ReturnCallIndirect(signature) => {
+                self.pop(&mut state, 1);
+                let effect = self.indirect_call_effect(signature)?;
+                if let Some(height) = self.call(&mut state, effect) {
+                    self.ret(pc, height)?;
+                }
+                return Ok(());
+            }
+            Opcode::CallInternal(target) => {
+                let effect = self.internal_call_effect(target);
+                if self.call(&mut state, Some(effect)).is_none() {
+                    return Ok(());
+                }
+            }
+            Opcode::Call(sys_func_idx) => {
+                let effect = self.analyzer.syscall_effect(pc, sys_func_idx)?;
+                if self.call(&mut state, Some(effect)).is_none() {
+                    return Ok(());
+                }
+            }
+            Opcode::CallIndirect(signature) => {
+                self.pop(&mut state, 1);
+                let effect = self.indirect_call_effect(signature)?;
+                if self.call(&mut state, effect).is_none() {
+                    return Ok(());
+                }
+                // skip the table index payload
+                next = pc + 2;
+            }
+            Opcode::StackCheck(max_stack_height) => {
+                state.reserved = state
+                    .reserved
+                    .max(state.height.saturating_add(max_stack_height as i64));
+            }
+            Opcode::TableInit(_) => {
+                self.pop(&mut state, 3);
+                // skip the table index payload
+                next = pc + 2;
+            }
+            Opcode::BulkConst(count) => self.push(pc, &mut state, count as i64)?,
+            Opcode::BulkDrop(count) => self.pop(&mut state, count as i64),
+            opcode => {
+                let (pops, pushes) = plain_stack_effect(opcode);
+                self.pop(&mut state, pops);
+                self.push(pc, &mut state, pushes)?;
+            }
+        }
+        if next >= self.analyzer.code.len() {
+            return Err(RwasmModuleVerificationError::FallthroughOutOfBounds {
+                pc,
+                code_len: self.analyzer.code.len(),
+            });
+        }
+        self.jump(next, state)
+    }
+
+    /// Marks the stack slot as used, negative indices belong to the caller.
+    fn touch(&mut self, index: i64) {
+        self.summary.inputs = self.summary.inputs.max(-index);
+    }
+
+    fn pop(&mut self, state: &mut StackState, count: i64) {
+        state.height = state.height.saturating_sub(count);
+        self.touch(state.height);
+    }
+
+    fn push(
+        &mut self,
+        pc: usize,
+        state: &mut StackState,
+        count: i64,
+    ) -> Result<(), RwasmModuleVerificationError> {
+        state.height = state.height.saturating_add(count);
+        if count > 0 && state.height > state.reserved {
+            return Err(RwasmModuleVerificationError::StackHeightNotReserved {
+                pc,
+                height: state.height,
+                reserved: state.reserved,
+            });
+        }
+        Ok(())
+    }
+
+    /// Applies the callee effect and returns the new height, `None` if the callee doesn't return
+    /// (or its effect is unknown yet).
+    fn call(&mut self, state: &mut StackState, effect: Option<CallEffect>) -> Option<i64> {
+        let effect = effect?;
+        self.touch(state.height.saturating_sub(effect.inputs));
+        state.height = state.height.saturating_add(effect.delta?);
+        // the values left by the callee are allocated by the callee
+        state.reserved = state.reserved.max(state.height);
+        Some(state.height)
+    }
+
+    fn internal_call_effect(&mut self, target: u32) -> CallEffect {
+        self.callees.insert(target);
+        self.analyzer.internal_call_effect(target)
+    }
+
+    fn indirect_call_effect(
+        &mut self,
+        signature: SignatureIdx,
+    ) -> Result<Option<CallEffect>, RwasmModuleVerificationError> {
+        if let Some(targets) = self.analyzer.signatures.get(&signature) {
+            self.callees.extend(targets.iter().copied());
+        }
+        self.analyzer.indirect_call_effect(signature)
+    }
+
+    fn jump(
+        &mut self,
+        target: usize,
+        state: StackState,
+    ) -> Result<(), RwasmModuleVerificationError> {
+        let target = target as u32;
+        match self.states.get_mut(&target) {
+            Some(prev) if prev.height != state.height => {
+                Err(RwasmModuleVerificationError::StackHeightMismatch {
+                    pc: target as usize,
+                    expected: prev.height,
+                    actual: state.height,
+                })
+            }
+            Some(prev) => {
+                if state.reserved < prev.reserved {
+                    prev.reserved = state.reserved;
+                    self.queue.push(target);
+                }
+                Ok(())
+            }
+            None => {
+                self.states.insert(target, state);
+                self.queue.push(target);
+                Ok(())
+            }
+        }
+    }
+
+    fn ret(&mut self, pc: usize, height: i64) -> Result<(), RwasmModuleVerificationError> {
+        match self.summary.delta {
+            Some(delta) if delta != height => {
+                Err(RwasmModuleVerificationError::StackHeightMismatch {
+                    pc,
+                    expected: delta,
+                    actual: height,
+                })
+            }
+            _ => {
+                self.summary.delta = Some(height);
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Branch targets are checked by [`super::verification`] before the stack analysis.
+fn branch_target(pc: usize, offset: i32) -> usize {
+    (pc as i64 + offset as i64) as usize
+}
+
+/// The number of values popped and pushed by an opcode without special control flow.
+fn plain_stack_effect(opcode: Opcode) -> (i64, i64) {
+    use Opcode::*;
+    match opcode {
+        ConsumeFuel(_) | SignatureCheck(_) | DataDrop(_) | ElemDrop(_) | TryEnd => (0, 0),
+        RefFunc(_) | I32Const(_) | GlobalGet(_) | MemorySize | MemorySizeAt(_) | TableSize(_) => {
+            (0, 1)
+        }
+        ExceptionTag => (0, 1),
+        Drop | GlobalSet(_) | ConsumeFuelStack => (1, 0),
+        Select => (3, 1),
+        I32Load(_) | I32Load8S(_) | I32Load8U(_) | I32Load16S(_) | I32Load16U(_) => (1, 1),
+        I32Store(_) | I32Store8(_) | I32Store16(_) => (2, 0),
+        I32LoadAt(..) | I32Load8SAt(..) | I32Load8UAt(..) | I32Load16SAt(..) | I32Load16UAt(..) => {
+            (1, 1)
+        }
+        I32StoreAt(..) | I32Store8At(..) | I32Store16At(..) => (2, 0),
+        MemoryGrow | MemoryGrowAt(_) | TableGet(_) => (1, 1),
+        MemoryFill | MemoryCopy | MemoryInit(_) | TableFill(_) | TableCopy(_, _) => (3, 0),
+        MemoryFillAt(_) | MemoryCopyAt(_, _) | MemoryInitAt(_, _) => (3, 0),
+        TableGrow(_) => (2, 1),
+        TableSet(_) => (2, 0),
+        I32Eqz | I32Clz | I32Ctz | I32Popcnt | I32WrapI64 | I32Extend8S | I32Extend16S => (1, 1),
+        I32Eq | I32Ne | I32LtS | I32LtU | I32GtS | I32GtU | I32LeS | I32LeU | I32GeS | I32GeU
+        | I32Add | I32Sub | I32Mul | I32DivS | I32DivU | I32RemS | I32RemU | I32And | I32Or
+        | I32Xor | I32Shl | I32ShrS | I32ShrU | I32Rotl | I32Rotr => (2, 1),
+        I32Mul64 | I32Add64 => (2, 2),
+        I32ConstI32Add(_) => (1, 1),
+        F32Load(_) => (1, 1),
+        F64Load(_) => (1, 2),
+        F32Store(_) => (2, 0),
+        F64Store(_) => (3, 0),
+        F32Eq | F32Ne | F32Lt | F32Gt | F32Le | F32Ge => (2, 1),
+        F64Eq | F64Ne | F64Lt | F64Gt | F64Le | F64Ge => (4, 1),
+        F32Abs | F32Neg | F32Ceil | F32Floor | F32Trunc | F32Nearest | F32Sqrt => (1, 1),
+        F32Add | F32Sub | F32Mul | F32Div | F32Min | F32Max | F32Copysign => (2, 1),
+        F64Abs | F64Neg | F64Ceil | F64Floor | F64Trunc | F64Nearest | F64Sqrt => (2, 2),
+        F64Add | F64Sub | F64Mul | F64Div | F64Min | F64Max | F64Copysign => (4, 2),
+        I32TruncF32S | I32TruncF32U | I32TruncSatF32S | I32TruncSatF32U => (1, 1),
+        I32TruncF64S | I32TruncF64U | I32TruncSatF64S | I32TruncSatF64U => (2, 1),
+        I64TruncF32S | I64TruncF32U | I64TruncSatF32S | I64TruncSatF32U => (1, 2),
+        I64TruncF64S | I64TruncF64U | I64TruncSatF64S | I64TruncSatF64U => (2, 2),
+        F32ConvertI32S | F32ConvertI32U => (1, 1),
+        F32ConvertI64S | F32ConvertI64U | F32DemoteF64 => (2, 1),
+        F64ConvertI32S | F64ConvertI32U | F64PromoteF32 => (1, 2),
+        F64ConvertI64S | F64ConvertI64U => (2, 2),
+        Unreachable
+        | Trap(_)
+        | LocalGet(_)
+        | LocalSet(_)
+        | LocalTee(_)
+        | Br(_)
+        | BrIfEqz(_)
+        | BrIfNez(_)
+        | BrTable(_)
+        | Return
+        | ReturnCallInternal(_)
+        | ReturnCall(_)
+        | ReturnCallIndirect(_)
+        | CallInternal(_)
+        | Call(_)
+        | CallIndirect(_)
+        | StackCheck(_)
+        | TableInit(_)
+        | BulkConst(_)
+        | BulkDrop(_)
+        | LocalGetLocalGet(_, _)
+        | LocalGetI32Const(_, _)
+        | LocalSetLocalGet(_, _)
+        | LocalGetI32Load(_, _)
+        | LocalSetLocalSet(_, _)
+        | TryBegin(_)
+        | Throw(_)
+        | Rethrow(_)
+        | Delegate(_)
+        | ExceptionPayload(_) => {
+            unreachable!("rwasm: opcode with special stack effect: {:?}", opcode)
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use crate::RwasmValType;
+use alloc::vec::Vec;
+use bincode::{Decode, Encode};
+use wasmparser::{FuncType, ValType};
+
+/// An exception tag of the rWasm module.
+///
+/// Tags come from the Wasm exception handling proposal, a thrown exception carries the tag index
+/// and the payload values of the tag parameter types.
+/// The tag index is the index inside the tag section.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Default, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd, Encode, Decode)]
+pub struct RwasmTag {
+    /// Original types of the exception payload.
+    pub params: Vec<RwasmValType>,
+}
+
+impl RwasmTag {
+    pub fn new(func_type: &FuncType) -> Self {
+        Self {
+            params: func_type.params().iter().copied().map(Into::into).collect(),
+        }
+    }
+
+    /// Returns the original Wasm type of the tag (tags never have results).
+    pub fn func_type(&self) -> FuncType {
+        FuncType::new(self.params.iter().copied().map(ValType::from), [])
+    }
+
+    /// Returns the number of 32-bit stack slots the payload takes.
+    pub fn payload_slots(&self) -> u32 {
+        self.params
+            .iter()
+            .map(|param| match param {
+                RwasmValType::I64 | RwasmValType::F64 => 2,
+                RwasmValType::V128 => 4,
+                _ => 1,
+            })
+            .sum()
+    }
+}
@@ -0,0 +1,636 @@
+use crate::{
+    InstructionSet, Opcode, RwasmExport, RwasmModule, RwasmModuleBuilder, RwasmTag, RwasmValType,
+    TextOperand,
+};
+use alloc::{string::String, vec, vec::Vec};
+use core::fmt::Formatter;
+use hashbrown::HashMap;
+
+/// An error of the rWasm text format (`.rwat`).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RwasmTextError {
+    /// A line where the error happened, starting from 1.
+    pub line: usize,
+    pub kind: RwasmTextErrorKind,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum RwasmTextErrorKind {
+    MalformedLine,
+    MissingClosingBrace,
+    UnknownDirective(String),
+    UnknownOpcode(String),
+    InvalidOperands,
+    InvalidNumber,
+    InvalidString,
+    InvalidValType,
+    UnresolvedSymbol(String),
+    DuplicateSymbol(String),
+    PcMismatch { expected: u32, actual: u32 },
+    SourcePcMismatch,
+}
+
+impl core::error::Error for RwasmTextError {}
+
+impl core::fmt::Display for RwasmTextError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.kind)
+    }
+}
+
+impl core::fmt::Display for RwasmTextErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            RwasmTextErrorKind::MalformedLine => write!(f, "malformed line"),
+            RwasmTextErrorKind::MissingClosingBrace => write!(f, "missing closing brace"),
+            RwasmTextErrorKind::UnknownDirective(name) => write!(f, "unknown directive .{}", name),
+            RwasmTextErrorKind::UnknownOpcode(name) => write!(f, "unknown opcode {}", name),
+            RwasmTextErrorKind::InvalidOperands => write!(f, "invalid opcode operands"),
+            RwasmTextErrorKind::InvalidNumber => write!(f, "invalid number"),
+            RwasmTextErrorKind::InvalidString => write!(f, "invalid string literal"),
+            RwasmTextErrorKind::InvalidValType => write!(f, "invalid value type"),
+            RwasmTextErrorKind::UnresolvedSymbol(name) => write!(f, "unresolved symbol {}", name),
+            RwasmTextErrorKind::DuplicateSymbol(name) => write!(f, "duplicate symbol {}", name),
+            RwasmTextErrorKind::PcMismatch { expected, actual } => {
+                write!(f, "expected pc {}, but actual pc is {}", expected, actual)
+            }
+            RwasmTextErrorKind::SourcePcMismatch => {
+                write!(f, "source pc doesn't match the source marker")
+            }
+        }
+    }
+}
+
+/// An operand before symbols are resolved.
+#[derive(Debug, Copy, Clone)]
+enum Operand<'a> {
+    Int(i64),
+    Ident(&'a str),
+    /// A label (`@name`) or a function (`$name`) including its sigil.
+    Symbol(&'a str),
+}
+
+struct Instruction<'a> {
+    line: usize,
+    name: &'a str,
+    operands: Vec<Operand<'a>>,
+}
+
+struct Export<'a> {
+    line: usize,
+    name: String,
+    target: Operand<'a>,
+    params: Vec<RwasmValType>,
+    results: Vec<RwasmValType>,
+}
+
+/// A two-pass assembler: the first pass collects instructions and symbols, the second one
+/// resolves symbols and builds the module.
+#[derive(Default)]
+struct Assembler<'a> {
+    instructions: Vec<Instruction<'a>>,
+    symbols: HashMap<&'a str, u32>,
+    data_section: Vec<u8>,
+    elem_section: Vec<(usize, Operand<'a>)>,
+    source_pc: Option<(usize, Operand<'a>)>,
+    source_marker: Option<u32>,
+    exports: Vec<Export<'a>>,
+    tags: Vec<RwasmTag>,
+}
+
+/// Parses a module in the text format, see `docs/rwat.md` for the syntax.
+pub(crate) fn parse_text(text: &str) -> Result<RwasmModule, RwasmTextError> {
+    let mut assembler = Assembler::default();
+    let (mut is_wrapped, mut is_closed, mut is_first) = (false, false, true);
+    let mut last_line = 0;
+    for (index, line) in text.lines().enumerate() {
+        let line_number = index + 1;
+        let error = |kind| RwasmTextError {
+            line: line_number,
+            kind,
+        };
+        let line = strip_comment(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+        last_line = line_number;
+        if is_closed {
+            return Err(error(RwasmTextErrorKind::MalformedLine));
+        }
+        if line == "RwasmModule {" && is_first {
+            is_wrapped = true;
+        } else if line == "}" && is_wrapped {
+            is_closed = true;
+        } else {
+            assembler.parse_line(line_number, line).map_err(error)?;
+        }
+        is_first = false;
+    }
+    if is_wrapped && !is_closed {
+        return Err(RwasmTextError {
+            line: last_line,
+            kind: RwasmTextErrorKind::MissingClosingBrace,
+        });
+    }
+    assembler.finish()
+}
+
+impl<'a> Assembler<'a> {
+    fn parse_line(&mut self, line: usize, text: &'a str) -> Result<(), RwasmTextErrorKind> {
+        if let Some(directive) = text.strip_prefix('.') {
+            self.parse_directive(line, directive)
+        } else if text.starts_with('@') {
+            let label = text
+                .strip_suffix(':')
+                .ok_or(RwasmTextErrorKind::MalformedLine)?;
+            self.define_symbol(label)
+        } else {
+            self.parse_instruction(line, text)
+        }
+    }
+
+    fn define_symbol(&mut self, symbol: &'a str) -> Result<(), RwasmTextErrorKind> {
+        if symbol.len() < 2 || !symbol[1..].bytes().all(is_symbol_char) {
+            return Err(RwasmTextErrorKind::MalformedLine);
+        }
+        let pc = self.instructions.len() as u32;
+        if self.symbols.insert(symbol, pc).is_some() {
+            return Err(RwasmTextErrorKind::DuplicateSymbol(symbol.into()));
+        }
+        Ok(())
+    }
+
+    fn parse_directive(
+        &mut self,
+        line: usize,
+        directive: &'a str,
+    ) -> Result<(), RwasmTextErrorKind> {
+        if let Some(rest) = directive.strip_prefix("function_begin") {
+            return self.parse_function_begin(rest);
+        } else if directive == "function_end" {
+            return Ok(());
+        }
+        let (name, value) = directive
+            .split_once(':')
+            .ok_or_else(|| RwasmTextErrorKind::UnknownDirective(directive.into()))?;
+        let value = value.trim();
+        let value = value.strip_suffix(',').unwrap_or(value).trim_end();
+        match name {
+            "ro_data" => {
+                for byte in parse_list(value)? {
+                    let byte = u8::from_str_radix(byte, 16)
+                        .map_err(|_| RwasmTextErrorKind::InvalidNumber)?;
+                    self.data_section.push(byte);
+                }
+            }
+            "ro_elem" => {
+                for elem in parse_list(value)? {
+                    self.elem_section.push((line, parse_operand(elem)?));
+                }
+            }
+            "source_pc" => self.source_pc = Some((line, parse_operand(value)?)),
+            "export" => self.parse_export(line, value)?,
+            "tag" => self.tags.push(RwasmTag {
+                params: parse_val_types(value)?,
+            }),
+            _ => return Err(RwasmTextErrorKind::UnknownDirective(name.into())),
+        }
+        Ok(())
+    }
+
+    /// Parses `.function_begin_<pc> (#<num>)` produced by the disassembler or
+    /// `.function_begin $name` that defines a function symbol.
+    fn parse_function_begin(&mut self, rest: &'a str) -> Result<(), RwasmTextErrorKind> {
+        if let Some(rest) = rest.strip_prefix('_') {
+            let (pc, func_num) = rest.split_once(' ').unwrap_or((rest, ""));
+            let expected = pc
+                .parse::<u32>()
+                .map_err(|_| RwasmTextErrorKind::InvalidNumber)?;
+            let actual = self.instructions.len() as u32;
+            if expected != actual {
+                return Err(RwasmTextErrorKind::PcMismatch { expected, actual });
+            }
+            let func_num = func_num.trim();
+            let is_valid = func_num.is_empty()
+                || func_num
+                    .strip_prefix("(#")
+                    .and_then(|func_num| func_num.strip_suffix(')'))
+                    .is_some_and(|func_num| func_num.parse::<u32>().is_ok());
+            if !is_valid {
+                return Err(RwasmTextErrorKind::MalformedLine);
+            }
+            return Ok(());
+        }
+        let rest = rest.trim();
+        if rest.is_empty() {
+            return Ok(());
+        } else if !rest.starts_with('$') {
+            return Err(RwasmTextErrorKind::MalformedLine);
+        }
+        self.define_symbol(rest)
+    }
+
+    /// Parses `"name" -> <target> [params] -> [results]`, where the types are optional.
+    fn parse_export(&mut self, line: usize, value: &'a str) -> Result<(), RwasmTextErrorKind> {
+        let (name, rest) = parse_string(value)?;
+        let rest = rest
+            .trim_start()
+            .strip_prefix("->")
+            .ok_or(RwasmTextErrorKind::MalformedLine)?
+            .trim_start();
+        let (target, types) = rest.split_once(' ').unwrap_or((rest, ""));
+        let (params, results) = match types.trim() {
+            "" => (vec![], vec![]),
+            types => {
+                let (params, results) = types
+                    .split_once("->")
+                    .ok_or(RwasmTextErrorKind::MalformedLine)?;
+                (parse_val_types(params)?, parse_val_types(results)?)
+            }
+        };
+        self.exports.push(Export {
+            line,
+            name,
+            target: parse_operand(target)?,
+            params,
+            results,
+        });
+        Ok(())
+    }
+
+    /// Parses `[<pc>:] Name[(operands)] [<- SOURCE]`.
+    fn parse_instruction(&mut self, line: usize, text: &'a str) -> Result<(), RwasmTextErrorKind> {
+        let pc = self.instructions.len() as u32;
+        let mut text = text;
+        if let Some((prefix, rest)) = text.split_once(':') {
+            let expected = prefix
+                .parse::<u32>()
+                .map_err(|_| RwasmTextErrorKind::MalformedLine)?;
+            if expected != pc {
+                return Err(RwasmTextErrorKind::PcMismatch {
+                    expected,
+                    actual: pc,
+                });
+            }
+            text = rest.trim_start();
+        }
+        if let Some(rest) = text.strip_suffix("<- SOURCE") {
+            self.source_marker = Some(pc);
+            text = rest.trim_end();
+        }
+        let (name, operands) = match text.split_once('(') {
+            Some((name, operands)) => {
+                let operands = operands
+                    .strip_suffix(')')
+                    .ok_or(RwasmTextErrorKind::MalformedLine)?;
+                let operands = operands
+                    .split(',')
+                    .map(parse_operand)
+                    .collect::<Result<Vec<_>, _>>()?;
+                (name.trim_end(), operands)
+            }
+            None => (text, vec![]),
+        };
+        if name.is_empty() || !name.bytes().all(|b| b.is_ascii_alphanumeric()) {
+            return Err(RwasmTextErrorKind::MalformedLine);
+        }
+        self.instructions.push(Instruction {
+            line,
+            name,
+            operands,
+        });
+        Ok(())
+    }
+
+    fn resolve_symbol(&self, symbol: &str) -> Result<u32, RwasmTextErrorKind> {
+        self.symbols
+            .get(symbol)
+            .copied()
+            .ok_or_else(|| RwasmTextErrorKind::UnresolvedSymbol(symbol.into()))
+    }
+
+    /// Resolves an operand that is a program counter (a number or a symbol).
+    fn resolve_pc(&self, operand: Operand) -> Result<u32, RwasmTextErrorKind> {
+        match operand {
+            Operand::Int(value) => {
+                u32::try_from(value).map_err(|_| RwasmTextErrorKind::InvalidNumber)
+            }
+            Operand::Symbol(symbol) => self.resolve_symbol(symbol),
+            Operand::Ident(_) => Err(RwasmTextErrorKind::MalformedLine),
+        }
+    }
+
+    fn finish(self) -> Result<RwasmModule, RwasmTextError> {
+        let error = |line: usize| move |kind| RwasmTextError { line, kind };
+        let mut code_section = InstructionSet::new();
+        for (pc, instruction) in self.instructions.iter().enumerate() {
+            // branches use offsets relative to the current pc, other opcodes use absolute pcs
+            let is_branch = matches!(instruction.name, "Br" | "BrIfEqz" | "BrIfNez" | "TryBegin");
+            let operands = instruction
+                .operands
+                .iter()
+                .map(|operand| match *operand {
+                    Operand::Int(value) => Ok(TextOperand::Int(value)),
+                    Operand::Ident(ident) => Ok(TextOperand::Ident(ident)),
+                    Operand::Symbol(symbol) => {
+                        let target = self.resolve_symbol(symbol)? as i64;
+                        let base = if is_branch { pc as i64 } else { 0 };
+                        Ok(TextOperand::Int(target - base))
+                    }
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(error(instruction.line))?;
+            let opcode =
+                Opcode::from_text(instruction.name, &operands).map_err(error(instruction.line))?;
+            code_section.push(opcode);
+        }
+        let mut elem_section = Vec::with_capacity(self.elem_section.len());
+        for (line, elem) in self.elem_section.iter() {
+            elem_section.push(self.resolve_pc(*elem).map_err(error(*line))?);
+        }
+        let source_pc = match self.source_pc {
+            Some((line, source_pc)) => {
+                let source_pc = self.resolve_pc(source_pc).map_err(error(line))?;
+                if self.source_marker.is_some_and(|marker| marker != source_pc) {
+                    return Err(error(line)(RwasmTextErrorKind::SourcePcMismatch));
+                }
+                source_pc
+            }
+            None => self.source_marker.unwrap_or_default(),
+        };
+        let mut builder = RwasmModuleBuilder::new(code_section)
+            .with_data_section(&self.data_section)
+            .with_elem_section(&elem_section)
+            .with_source_pc(source_pc);
+        for export in self.exports.iter() {
+            let func_offset = self.resolve_pc(export.target).map_err(error(export.line))?;
+            builder = builder.with_export(RwasmExport {
+                name: export.name.as_str().into(),
+                func_offset,
+                params: export.params.clone(),
+                results: export.results.clone(),
+            });
+        }
+        for tag in self.tags {
+            builder = builder.with_tag(tag);
+        }
+        Ok(builder.build())
+    }
+}
+
+fn is_symbol_char(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_' || b == b'.'
+}
+
+/// Removes a `;;` comment from the line, comments inside string literals are ignored.
+fn strip_comment(line: &str) -> &str {
+    let (mut in_string, mut escaped) = (false, false);
+    for (index, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            ';' if !in_string && line[index..].starts_with(";;") => return &line[..index],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn parse_operand(text: &str) -> Result<Operand<'_>, RwasmTextErrorKind> {
+    let text = text.trim();
+    match text.as_bytes().first() {
+        Some(b'@' | b'$') if text.len() > 1 && text.bytes().skip(1).all(is_symbol_char) => {
+            Ok(Operand::Symbol(text))
+        }
+        Some(b'-' | b'0'..=b'9') => parse_int(text).map(Operand::Int),
+        Some(c) if c.is_ascii_alphabetic() && text.bytes().all(is_symbol_char) => {
+            Ok(Operand::Ident(text))
+        }
+        _ => Err(RwasmTextErrorKind::MalformedLine),
+    }
+}
+
+/// Parses a decimal or a hexadecimal (`0x` prefixed) integer, optionally negative.
+fn parse_int(text: &str) -> Result<i64, RwasmTextErrorKind> {
+    let (is_negative, digits) = match text.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, text),
+    };
+    let value = match digits.strip_prefix("0x") {
+        Some(hex) => i64::from_str_radix(hex, 16),
+        None if digits.starts_with('+') => return Err(RwasmTextErrorKind::InvalidNumber),
+        None => digits.parse::<i64>(),
+    }
+    .map_err(|_| RwasmTextErrorKind::InvalidNumber)?;
+    Ok(if is_negative { -value } else { value })
+}
+
+/// Parses a list of comma-separated items inside square brackets.
+fn parse_list(text: &str) -> Result<Vec<&str>, RwasmTextErrorKind> {
+    let items = text
+        .trim()
+        .strip_prefix('[')
+        .and_then(|items| items.strip_suffix(']'))
+        .ok_or(RwasmTextErrorKind::MalformedLine)?;
+    if items.trim().is_empty() {
+        return Ok(vec![]);
+    }
+    items
+        .split(',')
+        .map(|item| match item.trim() {
+            "" => Err(RwasmTextErrorKind::MalformedLine),
+            item => Ok(item),
+        })
+        .collect()
+}
+
+fn parse_val_types(text: &str) -> Result<Vec<RwasmValType>, RwasmTextErrorKind> {
+    parse_list(text)?
+        .into_iter()
+        .map(|ty| match ty {
+            "I32" => Ok(RwasmValType::I32),
+            "I64" => Ok(RwasmValType::I64),
+            "F32" => Ok(RwasmValType::F32),
+            "F64" => Ok(RwasmValType::F64),
+            "V128" => Ok(RwasmValType::V128),
+            "FuncRef" => Ok(RwasmValType::FuncRef),
+            "ExternRef" => Ok(RwasmValType::ExternRef),
+            _ => Err(RwasmTextErrorKind::InvalidValType),
+        })
+        .collect()
+}
+
+/// Parses a string literal in the Rust debug format and returns the remaining text.
+fn parse_string(text: &str) -> Result<(String, &str), RwasmTextErrorKind> {
+    let mut chars = text
+        .strip_prefix('"')
+        .ok_or(RwasmTextErrorKind::InvalidString)?
+        .char_indices();
+    let mut result = String::new();
+    while let Some((index, c)) = chars.next() {
+        let c = match c {
+            '"' => return Ok((result, &text[index + 2..])),
+            '\\' => match chars.next().map(|(_, c)| c) {
+                Some('n') => '\n',
+                Some('r') => '\r',
+                Some('t') => '\t',
+                Some('0') => '\0',
+                Some(c @ ('\\' | '"' | '\'')) => c,
+                Some('u') => {
+                    let rest = chars.as_str();
+                    let (code, _) = rest
+                        .strip_prefix('{')
+                        .and_then(|rest| rest.split_once('}'))
+                        .ok_or(RwasmTextErrorKind::InvalidString)?;
+                    let c = u32::from_str_radix(code, 16)
+                        .ok()
+                        .and_then(char::from_u32)
+                        .ok_or(RwasmTextErrorKind::InvalidString)?;
+                    // skip the escaped code with braces
+                    chars.nth(code.len() + 1);
+                    c
+                }
+                _ => return Err(RwasmTextErrorKind::InvalidString),
+            },
+            c => c,
+        };
+        result.push(c);
+    }
+    Err(RwasmTextErrorKind::InvalidString)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{instruction_set, TrapCode};
+    use alloc::string::ToString;
+
+    const TEXT: &str = r#"
+;; a hand-written module with labels, function symbols and data directives
+.function_begin $main
+  StackCheck(3)
+  I32Const(0)                 ;; sum
+  LocalGet(2)                 ;; counter
+@loop:
+  LocalTee(1)
+  BrIfEqz(@exit)
+  LocalTee(1)
+  LocalGet(3)
+  I32Add
+  LocalSet(2)
+  I32Const(-1)
+  I32Add
+  Br(@loop)
+@exit:
+  Drop
+  CallInternal($trap)
+  Return
+.function_end
+
+.function_begin $trap
+  Trap(UnreachableCodeReached)
+.function_end
+
+.ro_data: [1, 2a, ff],
+.ro_elem: [$trap, 0],
+.source_pc: $main,
+.export: "main" -> $main [I32] -> [I32],
+.export: "say \"hi\"\n\u{1f600}" -> 0,
+.tag: [I32, I64],
+"#;
+
+    #[test]
+    fn test_assemble_text_with_symbols() {
+        let module = RwasmModule::from_text(TEXT).unwrap();
+        assert_eq!(
+            module.code_section,
+            instruction_set! {
+                StackCheck(3)
+                I32Const(0)
+                LocalGet(2)
+                LocalTee(1)
+                BrIfEqz(8)
+                LocalTee(1)
+                LocalGet(3)
+                I32Add
+                LocalSet(2)
+                I32Const(-1)
+                I32Add
+                Br(-8)
+                Drop
+                CallInternal(15)
+                Return
+                Trap(TrapCode::UnreachableCodeReached)
+            }
+        );
+        assert_eq!(module.data_section, vec![0x01, 0x2a, 0xff]);
+        assert_eq!(module.elem_section, vec![15, 0]);
+        assert_eq!(module.source_pc, 0);
+        let export = module.resolve_export("main").unwrap();
+        assert_eq!(export.params, vec![RwasmValType::I32]);
+        assert_eq!(export.results, vec![RwasmValType::I32]);
+        assert!(module.resolve_export("say \"hi\"\n\u{1f600}").is_some());
+        assert_eq!(
+            module.resolve_tag(0).unwrap().params,
+            vec![RwasmValType::I32, RwasmValType::I64]
+        );
+        // the disassembled module must be assembled into the same module and text
+        let text = module.to_string();
+        let module2 = RwasmModule::from_text(&text).unwrap();
+        assert_eq!(module2, module);
+        assert_eq!(module2.to_string(), text);
+    }
+
+    #[test]
+    fn test_assemble_text_errors() {
+        let error = |text: &str| RwasmModule::from_text(text).unwrap_err();
+        assert_eq!(
+            error("I32Const(1)\nI32Add(1)"),
+            RwasmTextError {
+                line: 2,
+                kind: RwasmTextErrorKind::InvalidOperands,
+            }
+        );
+        assert_eq!(
+            error("I32Foo").kind,
+            RwasmTextErrorKind::UnknownOpcode("I32Foo".into())
+        );
+        assert_eq!(
+            error("Br(@missing)").kind,
+            RwasmTextErrorKind::UnresolvedSymbol("@missing".into())
+        );
+        assert_eq!(
+            error("@a:\n@a:").kind,
+            RwasmTextErrorKind::DuplicateSymbol("@a".into())
+        );
+        assert_eq!(
+            error("0001: Return").kind,
+            RwasmTextErrorKind::PcMismatch {
+                expected: 1,
+                actual: 0,
+            }
+        );
+        assert_eq!(
+            error("Return  <- SOURCE\nReturn\n.source_pc: 1").kind,
+            RwasmTextErrorKind::SourcePcMismatch
+        );
+        assert_eq!(
+            error("RwasmModule {\nReturn").kind,
+            RwasmTextErrorKind::MissingClosingBrace
+        );
+        assert_eq!(error("Trap(Foo)").kind, RwasmTextErrorKind::InvalidOperands);
+        assert_eq!(
+            error("LocalGet(4294967296)").kind,
+            RwasmTextErrorKind::InvalidOperands
+        );
+        assert_eq!(
+            error(".ro_data: [100]").kind,
+            RwasmTextErrorKind::InvalidNumber
+        );
+        assert_eq!(
+            error(".export: \"main -> 0").kind,
+            RwasmTextErrorKind::InvalidString
+        );
+    }
+}
@@ -1,5 +1,10 @@
-use super::{RwasmModule, RwasmModuleInner};
-use crate::{InstructionSet, Opcode, N_MAX_DATA_SEGMENTS, N_MAX_ELEM_SEGMENTS, N_MAX_TABLES};
+use super::{
+    fuel_analysis::verify_fuel, stack_analysis::verify_stack, RwasmModule, RwasmModuleInner,
+};
+use crate::{
+    ImportLinker, InstructionSet, MemoryIdx, Opcode, TagIdx, N_MAX_DATA_SEGMENTS,
+    N_MAX_ELEM_SEGMENTS, N_MAX_MEMORIES, N_MAX_TABLES,
+};
 use bincode::error::DecodeError;
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -32,6 +37,16 @@ pub enum RwasmModuleVerificationError {
         target: u32,
         code_len: usize,
     },
+    ExportTargetOutOfBounds {
+        index: usize,
+        target: u32,
+        code_len: usize,
+    },
+    InvalidSourceMapEntry {
+        index: usize,
+        start_pc: u32,
+        end_pc: u32,
+    },
     LocalDepthOutOfBounds {
         pc: usize,
         depth: u32,
@@ -51,9 +66,107 @@ pub enum RwasmModuleVerificationError {
     MissingTableIndexPayload {
         pc: usize,
     },
+    MemoryIndexOutOfBounds {
+        pc: usize,
+        memory: MemoryIdx,
+    },
+    /// The exception opcode refers to a tag outside the tag section.
+    TagIndexOutOfBounds {
+        pc: usize,
+        tag: TagIdx,
+    },
     InvalidTableIndexPayload {
         pc: usize,
     },
+    /// An indirect call target (an element or `RefFunc`) doesn't start with `SignatureCheck`.
+    MissingSignatureCheck {
+        target: u32,
+    },
+    /// The instruction is reachable from two different functions.
+    OverlappingFunctions {
+        pc: usize,
+        func: u32,
+        other_func: u32,
+    },
+    /// The execution can run past the end of the code section.
+    FallthroughOutOfBounds {
+        pc: usize,
+        code_len: usize,
+    },
+    /// Paths to the instruction (or returns of a function) have different stack heights.
+    ///
+    /// Heights are relative to the function entry.
+    StackHeightMismatch {
+        pc: usize,
+        expected: i64,
+        actual: i64,
+    },
+    /// The instruction pushes more values than reserved by `StackCheck`.
+    StackHeightNotReserved {
+        pc: usize,
+        height: i64,
+        reserved: i64,
+    },
+    /// The function at `pc` reads more values under its entry than available.
+    StackUnderflow {
+        pc: usize,
+        required: i64,
+        available: i64,
+    },
+    /// The stack effect of an exported function doesn't match its signature.
+    ExportStackEffectMismatch {
+        index: usize,
+        expected: i64,
+        actual: i64,
+    },
+    /// Functions with the same `SignatureCheck` have different stack effects.
+    SignatureStackEffectMismatch {
+        signature: u32,
+        target: u32,
+    },
+    UnresolvedSyscall {
+        pc: usize,
+        sys_func_idx: u32,
+    },
+    /// The module makes a system call, but its stack effect is unknown without the import
+    /// linker.
+    UnknownSyscallEffect {
+        pc: usize,
+        sys_func_idx: u32,
+    },
+    /// The function starting with `SignatureCheck` executes the instruction before `ConsumeFuel`.
+    UnmeteredFunctionEntry {
+        func: u32,
+        pc: usize,
+    },
+    /// The call can be executed without charging fuel.
+    UnmeteredCall {
+        pc: usize,
+    },
+    /// The branch (or call) closes a cycle that doesn't charge fuel.
+    UnmeteredLoop {
+        pc: usize,
+        target: usize,
+    },
+    /// The bulk operation isn't preceded by the dynamic fuel check.
+    MissingBulkFuelCheck {
+        pc: usize,
+    },
+    /// The metadata section records a bulk fuel shift that isn't less than 32.
+    InvalidBulkFuelShift {
+        log2: u32,
+    },
+}
+
+/// Defines what fuel instrumentation a verified module must carry.
+#[derive(Default, Copy, Clone, Debug, Eq, PartialEq)]
+pub enum FuelPolicy {
+    /// The fuel instrumentation isn't checked.
+    #[default]
+    Optional,
+    /// Every loop and function must consume fuel, and bulk operations must charge fuel for
+    /// their size (see [`crate::CompilationConfig::with_consume_fuel_for_bulk_ops`]).
+    Required,
 }
 
 #[derive(Debug)]
@@ -75,8 +188,44 @@ impl From<RwasmModuleVerificationError> for RwasmModuleError {
 }
 
 impl RwasmModuleInner {
+    /// Verifies the module structure and the value stack consistency of every function.
+    ///
+    /// The stack effect of system calls isn't known without the import linker, so modules with
+    /// system calls are rejected with [`RwasmModuleVerificationError::UnknownSyscallEffect`], use
+    /// [`Self::verify_with_import_linker`] for them.
     pub fn verify(&self) -> Result<(), RwasmModuleVerificationError> {
-        verify_module(self)
+        verify_module(self, None)
+    }
+
+    /// Verifies the module like [`Self::verify`], system calls are resolved with the import linker.
+    pub fn verify_with_import_linker(
+        &self,
+        import_linker: &ImportLinker,
+    ) -> Result<(), RwasmModuleVerificationError> {
+        verify_module(self, Some(import_linker))
+    }
+
+    /// Verifies the module like [`Self::verify`] and checks its fuel instrumentation.
+    ///
+    /// Use [`FuelPolicy::Required`] for precompiled modules that must be metered, otherwise the
+    /// `ConsumeFuel` instructions can be stripped to run unmetered loops.
+    pub fn verify_with_policy(
+        &self,
+        policy: FuelPolicy,
+    ) -> Result<(), RwasmModuleVerificationError> {
+        verify_module(self, None)?;
+        verify_fuel_policy(self, policy)
+    }
+
+    /// Verifies the module like [`Self::verify_with_import_linker`] and checks its fuel
+    /// instrumentation like [`Self::verify_with_policy`].
+    pub fn verify_with_import_linker_and_policy(
+        &self,
+        import_linker: &ImportLinker,
+        policy: FuelPolicy,
+    ) -> Result<(), RwasmModuleVerificationError> {
+        verify_module(self, Some(import_linker))?;
+        verify_fuel_policy(self, policy)
     }
 }
 
@@ -84,9 +233,45 @@ impl RwasmModule {
     pub fn verify(&self) -> Result<(), RwasmModuleVerificationError> {
         self.inner.verify()
     }
+
+    pub fn verify_with_import_linker(
+        &self,
+        import_linker: &ImportLinker,
+    ) -> Result<(), RwasmModuleVerificationError> {
+        self.inner.verify_with_import_linker(import_linker)
+    }
+
+    pub fn verify_with_policy(
+        &self,
+        policy: FuelPolicy,
+    ) -> Result<(), RwasmModuleVerificationError> {
+        self.inner.verify_with_policy(policy)
+    }
+
+    pub fn verify_with_import_linker_and_policy(
+        &self,
+        import_linker: &ImportLinker,
+        policy: FuelPolicy,
+    ) -> Result<(), RwasmModuleVerificationError> {
+        self.inner
+            .verify_with_import_linker_and_policy(import_linker, policy)
+    }
 }
 
-fn verify_module(module: &RwasmModuleInner) -> Result<(), RwasmModuleVerificationError> {
+fn verify_fuel_policy(
+    module: &RwasmModuleInner,
+    policy: FuelPolicy,
+) -> Result<(), RwasmModuleVerificationError> {
+    match policy {
+        FuelPolicy::Optional => Ok(()),
+        FuelPolicy::Required => verify_fuel(module),
+    }
+}
+
+fn verify_module(
+    module: &RwasmModuleInner,
+    import_linker: Option<&ImportLinker>,
+) -> Result<(), RwasmModuleVerificationError> {
     let code = &module.code_section;
     let code_len = code.len();
     if code_len == 0 {
@@ -107,20 +292,51 @@ fn verify_module(module: &RwasmModuleInner) -> Result<(), RwasmModuleVerificatio
             });
         }
     }
+    for (index, export) in module.export_section.iter().enumerate() {
+        if export.func_offset as usize >= code_len {
+            return Err(RwasmModuleVerificationError::ExportTargetOutOfBounds {
+                index,
+                target: export.func_offset,
+                code_len,
+            });
+        }
+    }
+    let mut prev_end_pc = 0;
+    for (index, entry) in module.debug_section.source_map.iter().enumerate() {
+        if entry.start_pc < prev_end_pc
+            || entry.start_pc >= entry.end_pc
+            || entry.end_pc as usize > code_len
+        {
+            return Err(RwasmModuleVerificationError::InvalidSourceMapEntry {
+                index,
+                start_pc: entry.start_pc,
+                end_pc: entry.end_pc,
+            });
+        }
+        prev_end_pc = entry.end_pc;
+    }
     for (pc, opcode) in code.iter().copied().enumerate() {
-        verify_opcode(code, pc, opcode)?;
+        verify_opcode(code, module.tag_section.len(), pc, opcode)?;
     }
-    Ok(())
+    verify_stack(module, import_linker)
 }
 
 fn verify_opcode(
     code: &InstructionSet,
+    tag_count: usize,
     pc: usize,
     opcode: Opcode,
 ) -> Result<(), RwasmModuleVerificationError> {
     match opcode {
-        Opcode::Br(offset) | Opcode::BrIfEqz(offset) | Opcode::BrIfNez(offset) => {
-            verify_branch_target(code.len(), pc, offset.to_i32())
+        Opcode::Br(offset)
+        | Opcode::BrIfEqz(offset)
+        | Opcode::BrIfNez(offset)
+        | Opcode::TryBegin(offset) => verify_branch_target(code.len(), pc, offset.to_i32()),
+        Opcode::Throw(tag) | Opcode::ExceptionPayload(tag) => {
+            if tag as usize >= tag_count {
+                return Err(RwasmModuleVerificationError::TagIndexOutOfBounds { pc, tag });
+            }
+            Ok(())
         }
         Opcode::BrTable(targets) => verify_branch_table(code.len(), pc, targets),
         Opcode::CallInternal(target) | Opcode::ReturnCallInternal(target) => {
@@ -142,12 +358,55 @@ fn verify_opcode(
             }
             Ok(())
         }
+        Opcode::LocalGetLocalGet(first, second)
+        | Opcode::LocalSetLocalGet(first, second)
+        | Opcode::LocalSetLocalSet(first, second) => {
+            for depth in [first, second] {
+                if depth == 0 {
+                    return Err(RwasmModuleVerificationError::LocalDepthOutOfBounds {
+                        pc,
+                        depth: depth as u32,
+                    });
+                }
+            }
+            Ok(())
+        }
+        Opcode::LocalGetI32Const(depth, _) | Opcode::LocalGetI32Load(depth, _) => {
+            if depth == 0 {
+                return Err(RwasmModuleVerificationError::LocalDepthOutOfBounds {
+                    pc,
+                    depth: depth as u32,
+                });
+            }
+            Ok(())
+        }
         Opcode::MemoryInit(segment) | Opcode::DataDrop(segment) => {
             if segment as usize >= N_MAX_DATA_SEGMENTS {
                 return Err(RwasmModuleVerificationError::DataSegmentOutOfBounds { pc, segment });
             }
             Ok(())
         }
+        Opcode::MemoryInitAt(memory, segment) => {
+            if segment as usize >= N_MAX_DATA_SEGMENTS {
+                return Err(RwasmModuleVerificationError::DataSegmentOutOfBounds { pc, segment });
+            }
+            verify_memory_index(pc, memory)
+        }
+        Opcode::I32LoadAt(memory, _)
+        | Opcode::I32Load8SAt(memory, _)
+        | Opcode::I32Load8UAt(memory, _)
+        | Opcode::I32Load16SAt(memory, _)
+        | Opcode::I32Load16UAt(memory, _)
+        | Opcode::I32StoreAt(memory, _)
+        | Opcode::I32Store8At(memory, _)
+        | Opcode::I32Store16At(memory, _)
+        | Opcode::MemorySizeAt(memory)
+        | Opcode::MemoryGrowAt(memory)
+        | Opcode::MemoryFillAt(memory) => verify_memory_index(pc, memory),
+        Opcode::MemoryCopyAt(dst, src) => {
+            verify_memory_index(pc, dst)?;
+            verify_memory_index(pc, src)
+        }
         Opcode::TableInit(segment) => {
             if segment as usize >= N_MAX_ELEM_SEGMENTS {
                 return Err(RwasmModuleVerificationError::ElementSegmentOutOfBounds {
@@ -281,10 +540,20 @@ fn verify_table_index(pc: usize, table: u16) -> Result<(), RwasmModuleVerificati
     Ok(())
 }
 
+fn verify_memory_index(pc: usize, memory: MemoryIdx) -> Result<(), RwasmModuleVerificationError> {
+    if u32::from(memory) >= N_MAX_MEMORIES {
+        return Err(RwasmModuleVerificationError::MemoryIndexOutOfBounds { pc, memory });
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{instruction_set, InstructionSet, RwasmModuleBuilder};
+    use crate::{
+        instruction_set, ImportName, InstructionSet, RwasmExport, RwasmMetadata,
+        RwasmModuleBuilder, RwasmSourceMapEntry, RwasmTag, ValType,
+    };
 
     fn module_with_code(code_section: InstructionSet) -> RwasmModuleInner {
         RwasmModuleInner {
@@ -293,6 +562,10 @@ mod tests {
             elem_section: vec![],
             hint_section: vec![],
             source_pc: 0,
+            export_section: vec![],
+            debug_section: Default::default(),
+            metadata_section: None,
+            tag_section: vec![],
         }
     }
 
@@ -375,6 +648,44 @@ mod tests {
         );
     }
 
+    #[test]
+    fn rejects_export_target_outside_code_section() {
+        let mut module = module_with_code(instruction_set! { Return });
+        module.export_section.push(RwasmExport {
+            name: "main".into(),
+            func_offset: 7,
+            ..Default::default()
+        });
+        assert_eq!(
+            verification_error(module),
+            RwasmModuleVerificationError::ExportTargetOutOfBounds {
+                index: 0,
+                target: 7,
+                code_len: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_overlapping_source_map_entries() {
+        let mut module = module_with_code(instruction_set! { I32Const(1) Drop Return });
+        for (start_pc, end_pc) in [(0, 2), (1, 3)] {
+            module.debug_section.source_map.push(RwasmSourceMapEntry {
+                start_pc,
+                end_pc,
+                ..Default::default()
+            });
+        }
+        assert_eq!(
+            verification_error(module),
+            RwasmModuleVerificationError::InvalidSourceMapEntry {
+                index: 1,
+                start_pc: 1,
+                end_pc: 3,
+            }
+        );
+    }
+
     #[test]
     fn rejects_zero_local_depth() {
         let depth = 0;
@@ -405,10 +716,394 @@ mod tests {
         );
     }
 
+    #[test]
+    fn rejects_memory_index_outside_limits() {
+        let memory = N_MAX_MEMORIES as MemoryIdx;
+        assert_eq!(
+            verification_error(module_with_code(
+                instruction_set! { MemorySizeAt(memory) Return }
+            )),
+            RwasmModuleVerificationError::MemoryIndexOutOfBounds { pc: 0, memory }
+        );
+    }
+
+    #[test]
+    fn rejects_tag_index_outside_tag_section() {
+        let mut module = module_with_code(instruction_set! { Throw(1u32) });
+        module.tag_section = vec![RwasmTag::default()];
+        assert_eq!(
+            verification_error(module),
+            RwasmModuleVerificationError::TagIndexOutOfBounds { pc: 0, tag: 1 }
+        );
+    }
+
     #[test]
     fn accepts_verified_encoded_module() {
-        let module = module_with_code(instruction_set! { I32Const(1) Return });
+        let module = module_with_code(instruction_set! { StackCheck(1) I32Const(1) Return });
         let encoded = bincode::encode_to_vec(module, bincode::config::legacy()).unwrap();
         RwasmModule::new_verified(&encoded).unwrap();
     }
+
+    #[test]
+    fn rejects_unreserved_stack_height() {
+        assert_eq!(
+            verification_error(module_with_code(
+                instruction_set! { I32Const(1) Drop Return }
+            )),
+            RwasmModuleVerificationError::StackHeightNotReserved {
+                pc: 0,
+                height: 1,
+                reserved: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_stack_height_mismatch_at_join() {
+        assert_eq!(
+            verification_error(module_with_code(instruction_set! {
+                StackCheck(2)
+                I32Const(0)
+                BrIfNez(3)
+                I32Const(1)
+                Br(1)
+                Return
+            })),
+            RwasmModuleVerificationError::StackHeightMismatch {
+                pc: 5,
+                expected: 0,
+                actual: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_fallthrough_past_code_section() {
+        assert_eq!(
+            verification_error(module_with_code(instruction_set! { ConsumeFuel(1) })),
+            RwasmModuleVerificationError::FallthroughOutOfBounds { pc: 0, code_len: 1 }
+        );
+    }
+
+    #[test]
+    fn rejects_shared_function_code() {
+        assert_eq!(
+            verification_error(module_with_code(instruction_set! {
+                CallInternal(2)
+                Br(1)
+                Return
+            })),
+            RwasmModuleVerificationError::OverlappingFunctions {
+                pc: 2,
+                func: 0,
+                other_func: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_callee_inputs_exceeding_export_params() {
+        let mut module = module_with_code(instruction_set! {
+            StackCheck(1)
+            I32Const(7)
+            CallInternal(4)
+            Return
+            Drop
+            Drop
+            Return
+        });
+        module.export_section.push(RwasmExport {
+            name: "main".into(),
+            func_offset: 0,
+            ..Default::default()
+        });
+        assert_eq!(
+            verification_error(module),
+            RwasmModuleVerificationError::StackUnderflow {
+                pc: 0,
+                required: 1,
+                available: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_export_results_mismatch() {
+        let mut module = module_with_code(instruction_set! { StackCheck(1) I32Const(1) Return });
+        module.export_section.push(RwasmExport {
+            name: "main".into(),
+            func_offset: 0,
+            ..Default::default()
+        });
+        assert_eq!(
+            verification_error(module),
+            RwasmModuleVerificationError::ExportStackEffectMismatch {
+                index: 0,
+                expected: 0,
+                actual: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_indirect_target_without_signature_check() {
+        let mut module = module_with_code(instruction_set! { Return Return });
+        module.elem_section.push(1);
+        assert_eq!(
+            verification_error(module),
+            RwasmModuleVerificationError::MissingSignatureCheck { target: 1 }
+        );
+    }
+
+    #[test]
+    fn fuel_policy_rejects_loop_without_fuel_charging() {
+        let module = module_with_code(instruction_set! {
+            SignatureCheck(0)
+            ConsumeFuel(1)
+            StackCheck(1)
+            ConsumeFuel(0)
+            I32Const(1)
+            BrIfNez(-2)
+            Return
+        });
+        module.verify_with_policy(FuelPolicy::Optional).unwrap();
+        assert_eq!(
+            module.verify_with_policy(FuelPolicy::Required),
+            Err(RwasmModuleVerificationError::UnmeteredLoop { pc: 5, target: 3 })
+        );
+        let mut module = module;
+        module.code_section[3] = Opcode::ConsumeFuel(1);
+        module.verify_with_policy(FuelPolicy::Required).unwrap();
+    }
+
+    #[test]
+    fn fuel_policy_rejects_stripped_function_fuel() {
+        let module = module_with_code(instruction_set! {
+            SignatureCheck(0)
+            StackCheck(1)
+            I32Const(1)
+            Drop
+            Return
+        });
+        assert_eq!(
+            module.verify_with_policy(FuelPolicy::Required),
+            Err(RwasmModuleVerificationError::UnmeteredFunctionEntry { func: 0, pc: 1 })
+        );
+    }
+
+    #[test]
+    fn fuel_policy_rejects_call_of_unmetered_function() {
+        let module = module_with_code(instruction_set! {
+            CallInternal(2)
+            Return
+            Return
+        });
+        assert_eq!(
+            module.verify_with_policy(FuelPolicy::Required),
+            Err(RwasmModuleVerificationError::UnmeteredCall { pc: 0 })
+        );
+    }
+
+    #[test]
+    fn fuel_policy_requires_bulk_fuel_check() {
+        let mut code = instruction_set! {
+            SignatureCheck(0)
+            ConsumeFuel(1)
+            StackCheck(5)
+            I32Const(0)
+            I32Const(0)
+            I32Const(0)
+        };
+        let mut unchecked = code.clone();
+        unchecked.op_memory_fill_checked(false);
+        unchecked.op_return();
+        assert_eq!(
+            module_with_code(unchecked).verify_with_policy(FuelPolicy::Required),
+            Err(RwasmModuleVerificationError::MissingBulkFuelCheck { pc: 6 })
+        );
+        code.op_memory_fill_checked(true);
+        code.op_return();
+        module_with_code(code)
+            .verify_with_policy(FuelPolicy::Required)
+            .unwrap();
+    }
+
+    /// Charges one fuel per `2^shift` bytes filled by `memory.fill`.
+    fn module_with_bulk_fuel_rate(round: u32, shift: u32) -> RwasmModuleInner {
+        module_with_code(instruction_set! {
+            SignatureCheck(0)
+            ConsumeFuel(1)
+            StackCheck(5)
+            I32Const(0)
+            I32Const(0)
+            I32Const(0)
+            LocalGet(1)
+            I32Const(round)
+            I32Add
+            I32Const(shift)
+            I32ShrU
+            ConsumeFuelStack
+            MemoryFill
+            Return
+        })
+    }
+
+    #[test]
+    fn fuel_policy_requires_canonical_bulk_fuel_rate() {
+        // w/o the metadata section only the canonical shift is accepted
+        for (round, shift) in [(0, 0), (31, 5), (63, 5), (1023, 10), (u32::MAX, 32)] {
+            assert_eq!(
+                module_with_bulk_fuel_rate(round, shift).verify_with_policy(FuelPolicy::Required),
+                Err(RwasmModuleVerificationError::MissingBulkFuelCheck { pc: 12 })
+            );
+        }
+    }
+
+    #[test]
+    fn fuel_policy_requires_bulk_fuel_rate_of_metadata() {
+        let with_shift = |module: RwasmModuleInner, log2: u32| RwasmModuleInner {
+            metadata_section: Some(RwasmMetadata {
+                memory_bytes_per_fuel_log2: log2,
+                ..Default::default()
+            }),
+            ..module
+        };
+        for (round, shift) in [(0, 0), (1023, 10)] {
+            let module = module_with_bulk_fuel_rate(round, shift);
+            with_shift(module.clone(), shift)
+                .verify_with_policy(FuelPolicy::Required)
+                .unwrap();
+            assert_eq!(
+                with_shift(module, 6).verify_with_policy(FuelPolicy::Required),
+                Err(RwasmModuleVerificationError::MissingBulkFuelCheck { pc: 12 })
+            );
+        }
+        assert_eq!(
+            with_shift(module_with_bulk_fuel_rate(u32::MAX, 32), 32)
+                .verify_with_policy(FuelPolicy::Required),
+            Err(RwasmModuleVerificationError::InvalidBulkFuelShift { log2: 32 })
+        );
+    }
+
+    #[test]
+    fn fuel_policy_accepts_only_exact_snippet_loops() {
+        let mut code = instruction_set! { StackCheck(InstructionSet::MSH_I64_DIV_U) };
+        code.op_i64_div_u();
+        code.op_return();
+        let mut module = module_with_code(code);
+        module.verify_with_policy(FuelPolicy::Required).unwrap();
+        // the loops are accepted only as a part of the unmodified snippet
+        module.code_section[1] = Opcode::I32Const(1.into());
+        assert!(matches!(
+            module.verify_with_policy(FuelPolicy::Required),
+            Err(RwasmModuleVerificationError::UnmeteredLoop { .. })
+        ));
+    }
+
+    #[test]
+    fn verifies_syscall_effects_with_import_linker() {
+        let mut module = module_with_code(instruction_set! { StackCheck(1) Call(5) Return });
+        module.export_section.push(RwasmExport {
+            name: "main".into(),
+            func_offset: 0,
+            ..Default::default()
+        });
+        // without the import linker, the syscall stack effect is unknown
+        assert_eq!(
+            module.verify(),
+            Err(RwasmModuleVerificationError::UnknownSyscallEffect {
+                pc: 1,
+                sys_func_idx: 5,
+            })
+        );
+        let mut import_linker = ImportLinker::default();
+        import_linker.insert_function(
+            ImportName::new("env", "f"),
+            5,
+            Default::default(),
+            &[],
+            &[ValType::I32],
+        );
+        assert_eq!(
+            module.verify_with_import_linker(&import_linker),
+            Err(RwasmModuleVerificationError::ExportStackEffectMismatch {
+                index: 0,
+                expected: 0,
+                actual: 1,
+            })
+        );
+        assert_eq!(
+            module.verify_with_import_linker(&ImportLinker::default()),
+            Err(RwasmModuleVerificationError::UnresolvedSyscall {
+                pc: 1,
+                sys_func_idx: 5,
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_stack_underflow_after_syscall() {
+        let mut module = module_with_code(instruction_set! {
+            StackCheck(1)
+            I32Const(0)
+            Call(5)
+            Drop
+            Drop
+            Return
+        });
+        module.export_section.push(RwasmExport {
+            name: "main".into(),
+            func_offset: 0,
+            ..Default::default()
+        });
+        let mut import_linker = ImportLinker::default();
+        import_linker.insert_function(
+            ImportName::new("env", "f"),
+            5,
+            Default::default(),
+            &[ValType::I32],
+            &[],
+        );
+        // the code after the syscall can't be checked without the import linker
+        assert_eq!(
+            module.verify(),
+            Err(RwasmModuleVerificationError::UnknownSyscallEffect {
+                pc: 2,
+                sys_func_idx: 5,
+            })
+        );
+        assert_eq!(
+            module.verify_with_policy(FuelPolicy::Optional),
+            Err(RwasmModuleVerificationError::UnknownSyscallEffect {
+                pc: 2,
+                sys_func_idx: 5,
+            })
+        );
+        assert_eq!(
+            module.verify_with_import_linker(&import_linker),
+            Err(RwasmModuleVerificationError::StackUnderflow {
+                pc: 0,
+                required: 2,
+                available: 0,
+            })
+        );
+        // the verified constructors either reject the syscall or check it with the linker
+        let binary = RwasmModule::from(module).serialize();
+        assert!(matches!(
+            RwasmModule::new_verified_exact(&binary),
+            Err(RwasmModuleError::Verification(
+                RwasmModuleVerificationError::UnknownSyscallEffect { .. }
+            ))
+        ));
+        assert!(matches!(
+            RwasmModule::new_verified_exact_with_import_linker(
+                &binary,
+                &Default::default(),
+                &import_linker
+            ),
+            Err(RwasmModuleError::Verification(
+                RwasmModuleVerificationError::StackUnderflow { .. }
+            ))
+        ));
+    }
 }
@@ -0,0 +1,628 @@
+use crate::{
+    module::limits::{self, CodeLimiter, DecodeWithLimits},
+    InstructionSet, Opcode, RwasmDebugSection, RwasmExport, RwasmMetadata, RwasmModule,
+    RwasmModuleInner, RwasmModuleLimits, RwasmTag, TagIdx, OPCODE_RECORD_SIZE, RWASM_MAGIC_BYTE_0,
+    RWASM_MAGIC_BYTE_1, RWASM_VERSION_V3,
+};
+use alloc::vec::Vec;
+use bincode::{
+    de::{
+        read::{Reader, SliceReader},
+        Decoder, DecoderImpl,
+    },
+    error::DecodeError,
+    Decode,
+};
+
+/// A size of the aligned (V3) module header.
+///
+/// The header layout is:
+/// - `0..4`: magic bytes, version and one flags byte (see [`ALIGNED_FLAG_METADATA`] and
+///   [`ALIGNED_FLAG_TAGS`], other bits must be zero)
+/// - `4..8`: number of opcodes in the code section
+/// - `8..12`: number of entries in the elem section
+/// - `12..16`: length of the data section
+/// - `16..20`: length of the hint section
+/// - `20..24`: source pc
+/// - `24..28`: length of the encoded export section
+/// - `28..32`: length of the encoded debug section
+///
+/// The header is followed by the code section (fixed-width opcode records), the elem section
+/// (u32 entries), the data section, the hint section, the export section and the debug section
+/// (both are bincode legacy, empty sections are omitted).
+/// All numbers are little-endian.
+pub(crate) const ALIGNED_HEADER_SIZE: usize = 32;
+
+/// A header flag that means that the debug section is followed by the bincode legacy encoded
+/// metadata section.
+const ALIGNED_FLAG_METADATA: u8 = 0x01;
+
+/// A header flag that means that the module ends with the bincode legacy encoded tag section
+/// (right after the metadata section, if any).
+const ALIGNED_FLAG_TAGS: u8 = 0x02;
+
+struct AlignedHeader {
+    code_len: usize,
+    elem_len: usize,
+    data_len: usize,
+    hint_len: usize,
+    source_pc: u32,
+    export_len: usize,
+    debug_len: usize,
+    has_metadata: bool,
+    has_tags: bool,
+}
+
+impl AlignedHeader {
+    fn read(header: &[u8; ALIGNED_HEADER_SIZE]) -> Result<Self, DecodeError> {
+        if header[0] != RWASM_MAGIC_BYTE_0 || header[1] != RWASM_MAGIC_BYTE_1 {
+            return Err(DecodeError::Other("rwasm: invalid magic bytes"));
+        }
+        if header[2] != RWASM_VERSION_V3 {
+            return Err(DecodeError::Other("rwasm: not supported version"));
+        }
+        let read_u32 = |offset: usize| {
+            u32::from_le_bytes(header[offset..offset + 4].try_into().unwrap_or_else(|_| {
+                unreachable!("rwasm: header field is out of bounds");
+            }))
+        };
+        if header[3] & !(ALIGNED_FLAG_METADATA | ALIGNED_FLAG_TAGS) != 0 {
+            return Err(DecodeError::Other("rwasm: non-zero reserved header byte"));
+        }
+        Ok(Self {
+            code_len: read_u32(4) as usize,
+            elem_len: read_u32(8) as usize,
+            data_len: read_u32(12) as usize,
+            hint_len: read_u32(16) as usize,
+            source_pc: read_u32(20),
+            export_len: read_u32(24) as usize,
+            debug_len: read_u32(28) as usize,
+            has_metadata: header[3] & ALIGNED_FLAG_METADATA != 0,
+            has_tags: header[3] & ALIGNED_FLAG_TAGS != 0,
+        })
+    }
+
+    /// Returns the total length of the module in bytes, including the header (but not the
+    /// metadata and tag sections).
+    fn module_len(&self) -> Option<usize> {
+        self.code_len
+            .checked_mul(OPCODE_RECORD_SIZE)?
+            .checked_add(self.elem_len.checked_mul(size_of::<u32>())?)?
+            .checked_add(self.data_len)?
+            .checked_add(self.hint_len)?
+            .checked_add(self.export_len)?
+            .checked_add(self.debug_len)?
+            .checked_add(ALIGNED_HEADER_SIZE)
+    }
+}
+
+/// Encodes the module using the aligned (V3) layout.
+pub(crate) fn encode_aligned(module: &RwasmModuleInner) -> Vec<u8> {
+    let export_section = if module.export_section.is_empty() {
+        Vec::new()
+    } else {
+        bincode::encode_to_vec(&module.export_section, bincode::config::legacy())
+            .unwrap_or_else(|_| unreachable!("rwasm: failed to serialize export section"))
+    };
+    let debug_section = if module.debug_section.is_empty() {
+        Vec::new()
+    } else {
+        bincode::encode_to_vec(&module.debug_section, bincode::config::legacy())
+            .unwrap_or_else(|_| unreachable!("rwasm: failed to serialize debug section"))
+    };
+    let metadata_section = module.metadata_section.map(|metadata_section| {
+        bincode::encode_to_vec(metadata_section, bincode::config::legacy())
+            .unwrap_or_else(|_| unreachable!("rwasm: failed to serialize metadata section"))
+    });
+    let tag_section = (!module.tag_section.is_empty()).then(|| {
+        bincode::encode_to_vec(&module.tag_section, bincode::config::legacy())
+            .unwrap_or_else(|_| unreachable!("rwasm: failed to serialize tag section"))
+    });
+    let mut flags = 0;
+    if metadata_section.is_some() {
+        flags |= ALIGNED_FLAG_METADATA;
+    }
+    if tag_section.is_some() {
+        flags |= ALIGNED_FLAG_TAGS;
+    }
+    let len_u32 = |len: usize| -> [u8; 4] {
+        u32::try_from(len)
+            .unwrap_or_else(|_| unreachable!("rwasm: section is too large"))
+            .to_le_bytes()
+    };
+    let mut sink = Vec::with_capacity(
+        ALIGNED_HEADER_SIZE
+            + module.code_section.len() * OPCODE_RECORD_SIZE
+            + module.elem_section.len() * size_of::<u32>()
+            + module.data_section.len()
+            + module.hint_section.len()
+            + export_section.len()
+            + debug_section.len()
+            + metadata_section.as_ref().map_or(0, Vec::len)
+            + tag_section.as_ref().map_or(0, Vec::len),
+    );
+    sink.extend_from_slice(&[
+        RWASM_MAGIC_BYTE_0,
+        RWASM_MAGIC_BYTE_1,
+        RWASM_VERSION_V3,
+        flags,
+    ]);
+    sink.extend_from_slice(&len_u32(module.code_section.len()));
+    sink.extend_from_slice(&len_u32(module.elem_section.len()));
+    sink.extend_from_slice(&len_u32(module.data_section.len()));
+    sink.extend_from_slice(&len_u32(module.hint_section.len()));
+    sink.extend_from_slice(&module.source_pc.to_le_bytes());
+    sink.extend_from_slice(&len_u32(export_section.len()));
+    sink.extend_from_slice(&len_u32(debug_section.len()));
+    for opcode in module.code_section.iter() {
+        sink.extend_from_slice(&opcode.to_aligned_record());
+    }
+    for elem in module.elem_section.iter() {
+        sink.extend_from_slice(&elem.to_le_bytes());
+    }
+    sink.extend_from_slice(&module.data_section);
+    sink.extend_from_slice(&module.hint_section);
+    sink.extend_from_slice(&export_section);
+    sink.extend_from_slice(&debug_section);
+    if let Some(metadata_section) = metadata_section {
+        sink.extend_from_slice(&metadata_section);
+    }
+    if let Some(tag_section) = tag_section {
+        sink.extend_from_slice(&tag_section);
+    }
+    sink
+}
+
+/// Decodes the module body using the aligned (V3) layout by copying all sections.
+///
+/// The `read` function must fill the entire buffer or fail, it's used to support any input
+/// source, not only slices.
+/// All lengths from the header are checked against the limits before reading the sections.
+pub(crate) fn decode_aligned<F>(
+    header: &[u8; ALIGNED_HEADER_SIZE],
+    limits: &RwasmModuleLimits,
+    mut read: F,
+) -> Result<RwasmModuleInner, DecodeError>
+where
+    F: FnMut(&mut [u8]) -> Result<(), DecodeError>,
+{
+    let header = AlignedHeader::read(header)?;
+    header
+        .module_len()
+        .ok_or(DecodeError::Other("rwasm: module is too large"))?;
+    limits::check_len(
+        header.data_len,
+        limits.max_data_bytes,
+        "rwasm: data section exceeds the limit",
+    )?;
+    limits::check_len(
+        header.elem_len,
+        limits.max_elem_entries,
+        "rwasm: elem section exceeds the limit",
+    )?;
+    limits::check_len(
+        header.hint_len,
+        limits.max_hint_bytes,
+        "rwasm: hint section exceeds the limit",
+    )?;
+    let mut code_limiter = CodeLimiter::new(limits, header.code_len)?;
+    let mut code_section = InstructionSet::new();
+    let mut record = [0u8; OPCODE_RECORD_SIZE];
+    for _ in 0..header.code_len {
+        read(&mut record)?;
+        let opcode = Opcode::from_aligned_record(&record)
+            .ok_or(DecodeError::Other("rwasm: invalid opcode"))?;
+        code_limiter.check(&opcode)?;
+        code_section.push(opcode);
+    }
+    let mut elem_section = Vec::new();
+    let mut elem = [0u8; size_of::<u32>()];
+    for _ in 0..header.elem_len {
+        read(&mut elem)?;
+        elem_section.push(u32::from_le_bytes(elem));
+    }
+    let mut read_vec = |len: usize| limits::read_bytes_by_chunks(len, &mut read);
+    let data_section = read_vec(header.data_len)?;
+    let hint_section = read_vec(header.hint_len)?;
+    let export_section = decode_section(&read_vec(header.export_len)?, limits)?;
+    let debug_section = decode_section(&read_vec(header.debug_len)?, limits)?;
+    let mut decoder = DecoderImpl::new(FnReader(read), bincode::config::legacy(), ());
+    let metadata_section = if header.has_metadata {
+        Some(Decode::decode(&mut decoder)?)
+    } else {
+        None
+    };
+    let tag_section = if header.has_tags {
+        Vec::<RwasmTag>::decode_with_limits(&mut decoder, limits)?
+    } else {
+        Vec::new()
+    };
+    Ok(RwasmModuleInner {
+        code_section,
+        data_section,
+        elem_section,
+        hint_section,
+        source_pc: header.source_pc,
+        export_section,
+        debug_section,
+        metadata_section,
+        tag_section,
+    })
+}
+
+/// Adapts the `read` function of [`decode_aligned`] to the bincode reader.
+struct FnReader<F>(F);
+
+impl<F> Reader for FnReader<F>
+where
+    F: FnMut(&mut [u8]) -> Result<(), DecodeError>,
+{
+    fn read(&mut self, bytes: &mut [u8]) -> Result<(), DecodeError> {
+        (self.0)(bytes)
+    }
+}
+
+/// Decodes a bincode encoded section, where an empty sink stands for an empty section.
+fn decode_section<T: DecodeWithLimits + Default>(
+    sink: &[u8],
+    limits: &RwasmModuleLimits,
+) -> Result<T, DecodeError> {
+    if sink.is_empty() {
+        return Ok(T::default());
+    }
+    let mut decoder = DecoderImpl::new(SliceReader::new(sink), bincode::config::legacy(), ());
+    let section = T::decode_with_limits(&mut decoder, limits)?;
+    if decoder.reader().peek_read(1).is_some() {
+        return Err(DecodeError::Other("rwasm: trailing bytes after section"));
+    }
+    Ok(section)
+}
+
+#[derive(Debug, Copy, Clone)]
+enum ExportSectionRef<'a> {
+    Encoded(&'a [u8]),
+    Decoded(&'a [RwasmExport]),
+}
+
+#[derive(Debug, Copy, Clone)]
+enum DebugSectionRef<'a> {
+    Encoded(&'a [u8]),
+    Decoded(&'a RwasmDebugSection),
+}
+
+#[derive(Debug, Copy, Clone)]
+enum TagSectionRef<'a> {
+    Encoded(&'a [u8]),
+    Decoded(&'a [RwasmTag]),
+}
+
+/// A borrowed view over an rWasm module.
+///
+/// The view can be created over a byte slice (or a memory-mapped file) with the aligned (V3)
+/// module encoding, in this case the code and elem sections are used in place without any
+/// decoding or copying.
+/// The executor runs directly from the view.
+///
+/// Use [`RwasmModule::serialize_aligned`] to produce such binaries.
+#[derive(Debug, Copy, Clone)]
+pub struct RwasmModuleView<'a> {
+    pub code_section: &'a [Opcode],
+    pub data_section: &'a [u8],
+    pub elem_section: &'a [u32],
+    pub hint_section: &'a [u8],
+    pub source_pc: u32,
+    pub metadata_section: Option<RwasmMetadata>,
+    export_section: ExportSectionRef<'a>,
+    debug_section: DebugSectionRef<'a>,
+    tag_section: TagSectionRef<'a>,
+}
+
+impl<'a> RwasmModuleView<'a> {
+    /// Creates a view over an aligned (V3) binary and returns the number of bytes read.
+    ///
+    /// Every opcode record is validated, but nothing is decoded or copied.
+    /// The binary must be aligned to at least 4 bytes, and it's only supported for little-endian
+    /// targets, use [`RwasmModule::new_checked`] otherwise.
+    pub fn new(sink: &'a [u8]) -> Result<(Self, usize), DecodeError> {
+        if cfg!(target_endian = "big") {
+            return Err(DecodeError::Other(
+                "rwasm: zero-copy view is not supported on big-endian targets",
+            ));
+        }
+        if sink.as_ptr().align_offset(align_of::<Opcode>()) != 0 {
+            return Err(DecodeError::Other("rwasm: unaligned module binary"));
+        }
+        let header: &[u8; ALIGNED_HEADER_SIZE] = sink
+            .get(..ALIGNED_HEADER_SIZE)
+            .and_then(|header| header.try_into().ok())
+            .ok_or(DecodeError::UnexpectedEnd {
+                additional: ALIGNED_HEADER_SIZE.saturating_sub(sink.len()),
+            })?;
+        let header = AlignedHeader::read(header)?;
+        let module_len = header
+            .module_len()
+            .ok_or(DecodeError::Other("rwasm: module is too large"))?;
+        if sink.len() < module_len {
+            return Err(DecodeError::UnexpectedEnd {
+                additional: module_len - sink.len(),
+            });
+        }
+
+        let (code_bytes, rest) =
+            sink[ALIGNED_HEADER_SIZE..].split_at(header.code_len * OPCODE_RECORD_SIZE);
+        for record in code_bytes.chunks_exact(OPCODE_RECORD_SIZE) {
+            let record: &[u8; OPCODE_RECORD_SIZE] = record
+                .try_into()
+                .unwrap_or_else(|_| unreachable!("rwasm: malformed opcode record"));
+            if Opcode::from_aligned_record(record).is_none() {
+                return Err(DecodeError::Other("rwasm: invalid opcode"));
+            }
+        }
+        // SAFETY: the buffer is aligned for `Opcode`, its length is a multiple of the opcode
+        //  size, and every record is validated above.
+        //  An opcode record has the same layout as `Opcode` in memory on little-endian targets,
+        //  because `Opcode` is `repr(u16)` with discriminants equal to opcode codes.
+        let code_section = unsafe {
+            core::slice::from_raw_parts(code_bytes.as_ptr() as *const Opcode, header.code_len)
+        };
+        let (elem_bytes, rest) = rest.split_at(header.elem_len * size_of::<u32>());
+        // SAFETY: the code section size is a multiple of 8, so elem section is aligned to 4,
+        //  and any bit pattern is a valid `u32`
+        let elem_section = unsafe {
+            core::slice::from_raw_parts(elem_bytes.as_ptr() as *const u32, header.elem_len)
+        };
+        let (data_section, rest) = rest.split_at(header.data_len);
+        let (hint_section, rest) = rest.split_at(header.hint_len);
+        let (export_section, rest) = rest.split_at(header.export_len);
+        let (debug_section, rest) = rest.split_at(header.debug_len);
+        // make sure the export and debug sections are well-formed, so we can decode them later
+        decode_section::<Vec<RwasmExport>>(export_section, &RwasmModuleLimits::unlimited())?;
+        decode_section::<RwasmDebugSection>(debug_section, &RwasmModuleLimits::unlimited())?;
+        let (metadata_section, metadata_len) = if header.has_metadata {
+            let (metadata_section, metadata_len) =
+                bincode::decode_from_slice(rest, bincode::config::legacy())?;
+            (Some(metadata_section), metadata_len)
+        } else {
+            (None, 0)
+        };
+        let rest = &rest[metadata_len..];
+        let tag_len = if header.has_tags {
+            bincode::decode_from_slice::<Vec<RwasmTag>, _>(rest, bincode::config::legacy())?.1
+        } else {
+            0
+        };
+        let module_len = module_len + metadata_len + tag_len;
+
+        let view = Self {
+            code_section,
+            data_section,
+            elem_section,
+            hint_section,
+            source_pc: header.source_pc,
+            metadata_section,
+            export_section: ExportSectionRef::Encoded(export_section),
+            debug_section: DebugSectionRef::Encoded(debug_section),
+            tag_section: TagSectionRef::Encoded(&rest[..tag_len]),
+        };
+        Ok((view, module_len))
+    }
+
+    /// Creates a view over an aligned (V3) binary and rejects trailing bytes.
+    pub fn new_exact(sink: &'a [u8]) -> Result<Self, DecodeError> {
+        let (view, bytes_read) = Self::new(sink)?;
+        if bytes_read != sink.len() {
+            return Err(DecodeError::Other("rwasm: trailing bytes after module"));
+        }
+        Ok(view)
+    }
+
+    /// Returns the export section of the module.
+    pub fn export_section(&self) -> Vec<RwasmExport> {
+        match self.export_section {
+            ExportSectionRef::Encoded(sink) => {
+                { decode_section(sink, &RwasmModuleLimits::unlimited()) }
+                    .unwrap_or_else(|_| unreachable!("rwasm: malformed export section"))
+            }
+            ExportSectionRef::Decoded(exports) => exports.to_vec(),
+        }
+    }
+
+    /// Finds an exported function by its name.
+    pub fn resolve_export(&self, name: &str) -> Option<RwasmExport> {
+        match self.export_section {
+            ExportSectionRef::Encoded(_) => self
+                .export_section()
+                .into_iter()
+                .find(|export| export.name.as_ref() == name),
+            ExportSectionRef::Decoded(exports) => exports
+                .iter()
+                .find(|export| export.name.as_ref() == name)
+                .cloned(),
+        }
+    }
+
+    /// Returns the debug section of the module.
+    pub fn debug_section(&self) -> RwasmDebugSection {
+        match self.debug_section {
+            DebugSectionRef::Encoded(sink) => {
+                { decode_section(sink, &RwasmModuleLimits::unlimited()) }
+                    .unwrap_or_else(|_| unreachable!("rwasm: malformed debug section"))
+            }
+            DebugSectionRef::Decoded(debug_section) => debug_section.clone(),
+        }
+    }
+
+    /// Returns the tag section of the module.
+    pub fn tag_section(&self) -> Vec<RwasmTag> {
+        match self.tag_section {
+            TagSectionRef::Encoded(sink) => {
+                { decode_section(sink, &RwasmModuleLimits::unlimited()) }
+                    .unwrap_or_else(|_| unreachable!("rwasm: malformed tag section"))
+            }
+            TagSectionRef::Decoded(tags) => tags.to_vec(),
+        }
+    }
+
+    /// Returns the exception tag with the given index.
+    pub fn resolve_tag(&self, tag: TagIdx) -> Option<RwasmTag> {
+        match self.tag_section {
+            TagSectionRef::Encoded(_) => self.tag_section().into_iter().nth(tag as usize),
+            TagSectionRef::Decoded(tags) => tags.get(tag as usize).cloned(),
+        }
+    }
+
+    /// Copies all sections into an owned module.
+    pub fn to_module(&self) -> RwasmModule {
+        RwasmModuleInner {
+            code_section: InstructionSet {
+                instr: self.code_section.to_vec(),
+            },
+            data_section: self.data_section.to_vec(),
+            elem_section: self.elem_section.to_vec(),
+            hint_section: self.hint_section.to_vec(),
+            source_pc: self.source_pc,
+            export_section: self.export_section(),
+            debug_section: self.debug_section(),
+            metadata_section: self.metadata_section,
+            tag_section: self.tag_section(),
+        }
+        .into()
+    }
+}
+
+impl<'a> From<&'a RwasmModule> for RwasmModuleView<'a> {
+    fn from(module: &'a RwasmModule) -> Self {
+        Self {
+            code_section: &module.code_section,
+            data_section: &module.data_section,
+            elem_section: &module.elem_section,
+            hint_section: &module.hint_section,
+            source_pc: module.source_pc,
+            metadata_section: module.metadata_section,
+            export_section: ExportSectionRef::Decoded(&module.export_section),
+            debug_section: DebugSectionRef::Decoded(&module.debug_section),
+            tag_section: TagSectionRef::Decoded(&module.tag_section),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        instruction_set, Opcode, RwasmExport, RwasmModule, RwasmModuleBuilder, RwasmModuleView,
+        TrapCode, RWASM_VERSION_V3,
+    };
+    use alloc::{vec, vec::Vec};
+
+    fn test_module() -> RwasmModule {
+        RwasmModuleBuilder::new(instruction_set! {
+            SignatureCheck(0)
+            StackCheck(3)
+            I32Const(-7)
+            Br(-1)
+            BrIfEqz(2)
+            Trap(TrapCode::BadSignature)
+            .op_table_copy(3, 7)
+            TableGet(5)
+            F64Load(9)
+            Return
+        })
+        .with_data_section(&[1, 2, 3])
+        .with_elem_section(&[1, 0, 5])
+        .with_hint_section(&[0x00, 0x61, 0x73, 0x6d])
+        .with_source_pc(2)
+        .with_export(RwasmExport {
+            name: "main".into(),
+            func_offset: 0,
+            params: vec![],
+            results: vec![],
+        })
+        .build()
+    }
+
+    /// Copies the binary into a buffer that is aligned to 8 bytes.
+    fn aligned_copy(sink: &[u8]) -> Vec<u64> {
+        let mut buffer = vec![0u64; sink.len().div_ceil(8)];
+        let bytes = unsafe {
+            core::slice::from_raw_parts_mut(buffer.as_mut_ptr() as *mut u8, buffer.len() * 8)
+        };
+        bytes[..sink.len()].copy_from_slice(sink);
+        buffer
+    }
+
+    #[test]
+    fn test_aligned_module_view() {
+        let module = test_module();
+        let encoded = module.serialize_aligned();
+        assert_eq!(encoded[2], RWASM_VERSION_V3);
+        let buffer = aligned_copy(&encoded);
+        let sink =
+            unsafe { core::slice::from_raw_parts(buffer.as_ptr() as *const u8, encoded.len()) };
+        let view = RwasmModuleView::new_exact(sink).unwrap();
+        // the view must point inside the original buffer
+        assert_eq!(
+            view.code_section.as_ptr() as usize,
+            sink.as_ptr() as usize + 32
+        );
+        assert_eq!(view.code_section, module.code_section.as_slice());
+        assert_eq!(view.elem_section, module.elem_section.as_slice());
+        assert_eq!(view.data_section, module.data_section.as_slice());
+        assert_eq!(view.hint_section, module.hint_section.as_slice());
+        assert_eq!(view.source_pc, module.source_pc);
+        assert_eq!(
+            view.resolve_export("main"),
+            module.resolve_export("main").cloned()
+        );
+        assert_eq!(view.to_module(), module);
+        // owned decoding must produce the same module
+        assert_eq!(RwasmModule::new_checked_exact(&encoded).unwrap(), module);
+    }
+
+    #[test]
+    fn test_aligned_module_rejects_malformed_binaries() {
+        let module = test_module();
+        let encoded = module.serialize_aligned();
+        let buffer = aligned_copy(&encoded);
+        let sink =
+            unsafe { core::slice::from_raw_parts(buffer.as_ptr() as *const u8, encoded.len()) };
+        // truncated binary
+        assert!(RwasmModuleView::new(&sink[..sink.len() - 1]).is_err());
+        assert!(RwasmModule::new_checked(&encoded[..encoded.len() - 1]).is_err());
+        // unaligned binary
+        let buffer = aligned_copy(&[&[0u8][..], &encoded].concat());
+        let sink =
+            unsafe { core::slice::from_raw_parts(buffer.as_ptr() as *const u8, encoded.len() + 1) };
+        assert!(RwasmModuleView::new(&sink[1..]).is_err());
+        // invalid opcode and trap code
+        for (offset, value) in [(32, 0xff), (32 + 5 * 8 + 2, 0xfe)] {
+            let mut malformed = encoded.clone();
+            malformed[offset] = value;
+            let buffer = aligned_copy(&malformed);
+            let sink = unsafe {
+                core::slice::from_raw_parts(buffer.as_ptr() as *const u8, malformed.len())
+            };
+            assert!(RwasmModuleView::new(sink).is_err());
+            assert!(RwasmModule::new_checked(&malformed).is_err());
+        }
+    }
+
+    #[test]
+    fn test_aligned_record_matches_memory_layout() {
+        let opcodes = [
+            Opcode::Trap(TrapCode::OutOfFuel),
+            Opcode::LocalGet(7),
+            Opcode::Br((-3).into()),
+            Opcode::TableCopy(0x1234, 0x5678),
+            Opcode::TableSize(0xabcd),
+            Opcode::F32Add,
+        ];
+        for opcode in opcodes {
+            let record = opcode.to_aligned_record();
+            assert_eq!(&record[..2], &(opcode.code() as u16).to_le_bytes());
+            assert_eq!(Opcode::from_aligned_record(&record), Some(opcode));
+            let buffer = aligned_copy(&record);
+            let view = unsafe { *(buffer.as_ptr() as *const Opcode) };
+            assert_eq!(view, opcode);
+        }
+    }
+}
@@ -1,9 +1,11 @@
 use crate::{
     always_failing_syscall_handler, CompilationConfig, CompilationError, ExecutionEngine,
-    ImportLinker, RwasmInstance, RwasmModule, RwasmStore, StoreTr, StrategyError, SyscallHandler,
-    TrapCode, Value,
+    ImportLinker, MemoryIdx, RwasmInstance, RwasmModule, RwasmStore, StoreTr, StrategyError,
+    SyscallHandler, TrapCode, Value,
 };
 use alloc::{sync::Arc, vec::Vec};
+#[cfg(feature = "wasmtime")]
+use tiny_keccak::{Hasher, Keccak};
 
 #[derive(Clone)]
 pub enum StrategyDefinition {
@@ -41,21 +43,91 @@ impl StrategyDefinition {
         })
     }
 
+    /// Creates a definition from an already compiled rWasm module, with the `wasmtime` feature
+    /// it's [`Self::from_rwasm_module_as_wasmtime`], otherwise
+    /// [`Self::from_rwasm_module_as_rwasm`].
+    ///
+    /// Note: with the `wasmtime` feature the rWasm code of the module isn't executed, and it isn't
+    /// checked against the hint section either, the original Wasm binary is compiled with the
+    /// config instead. So the module must be compiled from its hint section with the same config
+    /// (e.g. come from a trusted compiler), otherwise the result diverges from the rWasm
+    /// execution. Use [`Self::from_rwasm_module_as_rwasm`] to execute the rWasm code itself.
+    pub fn from_rwasm_module(
+        #[allow(unused_variables)] compilation_config: CompilationConfig,
+        module: RwasmModule,
+    ) -> Result<Self, CompilationError> {
+        #[cfg(feature = "wasmtime")]
+        return Self::from_rwasm_module_as_wasmtime(compilation_config, &module);
+        #[cfg(not(feature = "wasmtime"))]
+        return Ok(Self::from_rwasm_module_as_rwasm(module));
+    }
+
+    /// Creates a definition that executes the rWasm code of the module.
+    pub fn from_rwasm_module_as_rwasm(module: RwasmModule) -> Self {
+        Self::Rwasm {
+            module,
+            engine: ExecutionEngine::new(),
+        }
+    }
+
+    /// Creates a definition that executes the original Wasm binary from the hint section of the
+    /// module with wasmtime, so the rWasm code of the module isn't used at all.
+    ///
+    /// The caching key is a keccak256 hash of [`RwasmModule::code_hash_with_hint_section`] and the
+    /// fingerprint of the metadata the config produces ([`crate::RwasmMetadata::fingerprint`]), so
+    /// the same module compiled with different fuel settings or import linkers isn't shared.
+    ///
+    /// # Errors
+    ///
+    /// If the hint section doesn't contain a Wasm binary, e.g., the module isn't compiled from Wasm.
+    #[cfg(feature = "wasmtime")]
+    pub fn from_rwasm_module_as_wasmtime(
+        compilation_config: CompilationConfig,
+        module: &RwasmModule,
+    ) -> Result<Self, CompilationError> {
+        if module.hint_type() != crate::HintType::WASM {
+            return Err(CompilationError::MissingWasmHint);
+        }
+        let metadata = crate::RwasmMetadata::new(&compilation_config, &module.code_section);
+        let mut hasher = Keccak::v256();
+        hasher.update(&module.code_hash_with_hint_section());
+        hasher.update(&metadata.fingerprint());
+        let mut module_caching_key = [0u8; 32];
+        hasher.finalize(&mut module_caching_key);
+        Self::new_as_wasmtime(
+            compilation_config,
+            &module.hint_section,
+            Some(module_caching_key),
+        )
+    }
+
+    /// Compiles the Wasm binary with wasmtime.
+    ///
+    /// # Errors
+    ///
+    /// If the config has a custom [`crate::FuelCostModel`], wasmtime can't charge its costs, or
+    /// if wasmtime fails to compile the binary.
     #[cfg(feature = "wasmtime")]
     pub fn new_as_wasmtime(
         compilation_config: CompilationConfig,
         wasm_binary: impl AsRef<[u8]>,
         module_caching_key: Option<[u8; 32]>,
     ) -> Result<Self, CompilationError> {
-        use crate::wasmtime::{compile_wasmtime_module, compile_wasmtime_module_cached};
+        use crate::{
+            wasmtime::{compile_wasmtime_module, compile_wasmtime_module_cached},
+            DefaultFuelCostModel, FuelCostModel,
+        };
+        use alloc::string::ToString;
+        if compilation_config.fuel_cost_model.fingerprint() != DefaultFuelCostModel.fingerprint() {
+            return Err(CompilationError::NotSupportedFuelCostModel);
+        }
         let module = if let Some(binary_caching_key) = module_caching_key {
             compile_wasmtime_module_cached(compilation_config, wasm_binary, binary_caching_key)
         } else {
             compile_wasmtime_module(compilation_config, wasm_binary)
         };
-        let module = module.expect(
-            "rwasm: compilation of wasmtime module can't fail since it's followed by rwasm validation rules, or it's a bug (the binary follows rwasm rules?)",
-        );
+        let module = module
+            .map_err(|err| CompilationError::WasmtimeCompilationFailed(err.to_string().into()))?;
         Ok(Self::Wasmtime { module })
     }
 
@@ -146,6 +218,36 @@ impl<T: 'static> StoreTr<T> for StrategyExecutor<T> {
         }
     }
 
+    fn memory_read_at(
+        &mut self,
+        memory: MemoryIdx,
+        offset: usize,
+        buffer: &mut [u8],
+    ) -> Result<(), TrapCode> {
+        match self {
+            StrategyExecutor::Rwasm { store, .. } => store.memory_read_at(memory, offset, buffer),
+            #[cfg(feature = "wasmtime")]
+            StrategyExecutor::Wasmtime { executor } => {
+                executor.memory_read_at(memory, offset, buffer)
+            }
+        }
+    }
+
+    fn memory_write_at(
+        &mut self,
+        memory: MemoryIdx,
+        offset: usize,
+        buffer: &[u8],
+    ) -> Result<(), TrapCode> {
+        match self {
+            StrategyExecutor::Rwasm { store, .. } => store.memory_write_at(memory, offset, buffer),
+            #[cfg(feature = "wasmtime")]
+            StrategyExecutor::Wasmtime { executor } => {
+                executor.memory_write_at(memory, offset, buffer)
+            }
+        }
+    }
+
     fn data_mut(&mut self) -> &mut T {
         match self {
             StrategyExecutor::Rwasm { store, .. } => store.data_mut(),
@@ -216,7 +318,14 @@ impl<T: 'static> StrategyExecutor<T> {
         result: &mut [Value],
     ) -> Result<(), TrapCode> {
         match self {
-            StrategyExecutor::Rwasm { store, instance } => instance.execute(store, params, result),
+            StrategyExecutor::Rwasm { store, instance } => {
+                // modules compiled w/o an export section can only run an entrypoint
+                if instance.module().export_section.is_empty() {
+                    instance.execute(store, params, result)
+                } else {
+                    instance.call_export(store, func_name, params, result)
+                }
+            }
             #[cfg(feature = "wasmtime")]
             StrategyExecutor::Wasmtime { executor } => executor.execute(func_name, params, result),
         }
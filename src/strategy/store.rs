@@ -1,4 +1,4 @@
-use crate::{CallerTr, RwasmCaller, RwasmStore, StoreTr, TrapCode};
+use crate::{CallerTr, MemoryIdx, RwasmCaller, RwasmStore, StoreTr, TagIdx, TrapCode, Value};
 use alloc::vec::Vec;
 
 pub enum TypedCaller<'a, T: 'static> {
@@ -82,6 +82,32 @@ impl<'a, T> StoreTr<T> for TypedCaller<'a, T> {
         }
     }
 
+    fn memory_read_at(
+        &mut self,
+        memory: MemoryIdx,
+        offset: usize,
+        buffer: &mut [u8],
+    ) -> Result<(), TrapCode> {
+        match self {
+            TypedCaller::Rwasm(store) => store.memory_read_at(memory, offset, buffer),
+            #[cfg(feature = "wasmtime")]
+            TypedCaller::Wasmtime(store) => store.memory_read_at(memory, offset, buffer),
+        }
+    }
+
+    fn memory_write_at(
+        &mut self,
+        memory: MemoryIdx,
+        offset: usize,
+        buffer: &[u8],
+    ) -> Result<(), TrapCode> {
+        match self {
+            TypedCaller::Rwasm(store) => store.memory_write_at(memory, offset, buffer),
+            #[cfg(feature = "wasmtime")]
+            TypedCaller::Wasmtime(store) => store.memory_write_at(memory, offset, buffer),
+        }
+    }
+
     fn data_mut(&mut self) -> &mut T {
         match self {
             TypedCaller::Rwasm(store) => store.data_mut(),
@@ -123,7 +149,15 @@ impl<'a, T> StoreTr<T> for TypedCaller<'a, T> {
     }
 }
 
-impl<'a, T> CallerTr<T> for TypedCaller<'a, T> {}
+impl<'a, T> CallerTr<T> for TypedCaller<'a, T> {
+    fn throw_exception(&mut self, tag: TagIdx, payload: &[Value]) -> TrapCode {
+        match self {
+            TypedCaller::Rwasm(store) => store.throw_exception(tag, payload),
+            #[cfg(feature = "wasmtime")]
+            TypedCaller::Wasmtime(store) => store.throw_exception(tag, payload),
+        }
+    }
+}
 
 #[allow(clippy::large_enum_variant)]
 pub enum TypedStore<T: 'static> {
@@ -157,6 +191,32 @@ impl<T> StoreTr<T> for TypedStore<T> {
         }
     }
 
+    fn memory_read_at(
+        &mut self,
+        memory: MemoryIdx,
+        offset: usize,
+        buffer: &mut [u8],
+    ) -> Result<(), TrapCode> {
+        match self {
+            TypedStore::Rwasm(store) => store.memory_read_at(memory, offset, buffer),
+            #[cfg(feature = "wasmtime")]
+            TypedStore::Wasmtime(store) => store.memory_read_at(memory, offset, buffer),
+        }
+    }
+
+    fn memory_write_at(
+        &mut self,
+        memory: MemoryIdx,
+        offset: usize,
+        buffer: &[u8],
+    ) -> Result<(), TrapCode> {
+        match self {
+            TypedStore::Rwasm(store) => store.memory_write_at(memory, offset, buffer),
+            #[cfg(feature = "wasmtime")]
+            TypedStore::Wasmtime(store) => store.memory_write_at(memory, offset, buffer),
+        }
+    }
+
     fn data_mut(&mut self) -> &mut T {
         match self {
             TypedStore::Rwasm(store) => store.data_mut(),
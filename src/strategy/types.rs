@@ -1,4 +1,4 @@
-use crate::{CompilationError, TrapCode};
+use crate::{CompilationError, MemoryIdx, TagIdx, TrapCode, Value};
 use alloc::vec::Vec;
 
 pub fn checked_memory_range_end(offset: usize, length: usize) -> Result<usize, TrapCode> {
@@ -14,6 +14,22 @@ pub trait StoreTr<T> {
 
     fn memory_write(&mut self, offset: usize, buffer: &[u8]) -> Result<(), TrapCode>;
 
+    /// Reads from the linear memory with the given index (multi-memory).
+    fn memory_read_at(
+        &mut self,
+        memory: MemoryIdx,
+        offset: usize,
+        buffer: &mut [u8],
+    ) -> Result<(), TrapCode>;
+
+    /// Writes into the linear memory with the given index (multi-memory).
+    fn memory_write_at(
+        &mut self,
+        memory: MemoryIdx,
+        offset: usize,
+        buffer: &[u8],
+    ) -> Result<(), TrapCode>;
+
     fn data_mut(&mut self) -> &mut T;
 
     fn data(&self) -> &T;
@@ -25,7 +41,14 @@ pub trait StoreTr<T> {
     fn reset_fuel(&mut self, new_fuel_limit: u64);
 }
 
-pub trait CallerTr<T>: StoreTr<T> {}
+pub trait CallerTr<T>: StoreTr<T> {
+    /// Raises a guest exception (the exception handling proposal) from a host function.
+    ///
+    /// The host function must return the returned trap code, then the exception is thrown at the
+    /// call site and can be caught by a `try` block of the guest. If the payload doesn't match the
+    /// tag parameters, the execution traps with [`TrapCode::BadSignature`] instead.
+    fn throw_exception(&mut self, tag: TagIdx, payload: &[Value]) -> TrapCode;
+}
 
 #[derive(Debug)]
 pub enum StrategyError {
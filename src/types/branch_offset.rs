@@ -6,6 +6,7 @@ use bincode::{Decode, Encode};
 /// upon taking the respective branch.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Hash, PartialOrd, Ord, Encode, Decode)]
+#[repr(transparent)]
 pub struct BranchOffset(i32);
 
 impl From<i32> for BranchOffset {
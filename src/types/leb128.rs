@@ -0,0 +1,94 @@
+use alloc::vec::Vec;
+use bincode::{de::read::Reader, error::DecodeError};
+
+/// Writes a `u32` number using the unsigned LEB128 encoding.
+pub(crate) fn write_uleb128(sink: &mut Vec<u8>, mut value: u32) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            sink.push(byte);
+            return;
+        }
+        sink.push(byte | 0x80);
+    }
+}
+
+/// Reads an unsigned LEB128 number that fits into `u32`.
+///
+/// Only the canonical (the shortest) encoding is accepted, so every number has exactly one
+/// binary representation.
+pub(crate) fn read_uleb128<R: Reader>(reader: &mut R) -> Result<u32, DecodeError> {
+    let mut result = 0u32;
+    for i in 0..5 {
+        let byte = read_byte(reader)?;
+        // the fifth byte can only carry the 4 most significant bits
+        if i == 4 && byte > 0x0f {
+            return Err(DecodeError::Other("rwasm: leb128 number overflows u32"));
+        }
+        result |= ((byte & 0x7f) as u32) << (i * 7);
+        if byte & 0x80 == 0 {
+            if byte == 0 && i > 0 {
+                return Err(DecodeError::Other("rwasm: non-canonical leb128 number"));
+            }
+            return Ok(result);
+        }
+    }
+    unreachable!("rwasm: leb128 number is longer than 5 bytes")
+}
+
+pub(crate) fn read_byte<R: Reader>(reader: &mut R) -> Result<u8, DecodeError> {
+    let mut byte = [0u8; 1];
+    reader.read(&mut byte)?;
+    Ok(byte[0])
+}
+
+/// Maps signed numbers to unsigned ones, so numbers with a small absolute value stay small.
+pub(crate) fn zigzag_encode(value: i32) -> u32 {
+    ((value << 1) ^ (value >> 31)) as u32
+}
+
+pub(crate) fn zigzag_decode(value: u32) -> i32 {
+    ((value >> 1) as i32) ^ -((value & 1) as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bincode::de::read::SliceReader;
+
+    #[test]
+    fn test_uleb128_round_trip() {
+        for value in [0, 1, 0x7f, 0x80, 0x3fff, 0x4000, u32::MAX - 1, u32::MAX] {
+            let mut sink = Vec::new();
+            write_uleb128(&mut sink, value);
+            let mut reader = SliceReader::new(&sink);
+            assert_eq!(read_uleb128(&mut reader).unwrap(), value);
+        }
+        let mut sink = Vec::new();
+        write_uleb128(&mut sink, u32::MAX);
+        assert_eq!(sink.len(), 5);
+    }
+
+    #[test]
+    fn test_uleb128_rejects_malformed_numbers() {
+        for sink in [
+            &[0x80, 0x00][..],
+            &[0xff, 0xff, 0xff, 0xff, 0x1f],
+            &[0xff, 0xff, 0xff, 0xff, 0x8f, 0x00],
+            &[0x80],
+        ] {
+            let mut reader = SliceReader::new(sink);
+            assert!(read_uleb128(&mut reader).is_err(), "{sink:x?}");
+        }
+    }
+
+    #[test]
+    fn test_zigzag() {
+        for (value, expected) in [(0, 0), (-1, 1), (1, 2), (-2, 3), (i32::MAX, u32::MAX - 1)] {
+            assert_eq!(zigzag_encode(value), expected);
+            assert_eq!(zigzag_decode(expected), value);
+        }
+        assert_eq!(zigzag_decode(zigzag_encode(i32::MIN)), i32::MIN);
+    }
+}
@@ -6,6 +6,7 @@ mod global_variable;
 mod hint_type;
 mod host_error;
 mod import_name;
+mod leb128;
 mod nan_preserving_float;
 mod opcode;
 mod trap_code;
@@ -21,6 +22,7 @@ pub use global_variable::*;
 pub use hint_type::*;
 pub use host_error::*;
 pub use import_name::*;
+pub(crate) use leb128::*;
 pub use nan_preserving_float::*;
 pub use opcode::*;
 pub use trap_code::*;
@@ -50,8 +52,8 @@ pub const N_DEFAULT_MAX_MEMORY_PAGES: u32 = 1024;
 pub const N_MAX_ALLOWED_MEMORY_PAGES: u32 = 32768;
 
 /// A default memory index in a Wasm binary.
-/// According to Wasm validation rules, this value is always 0,
-/// since Wasm doesn't support multiple memory segments yet
+/// Memory instructions of the default memory are compiled to opcodes w/o a memory index,
+/// other memories (the multi-memory proposal) use the `*At` opcodes.
 pub const DEFAULT_MEMORY_INDEX: u32 = 0;
 
 pub const N_MAX_DATA_SEGMENTS: usize = 100_000;
@@ -74,6 +76,9 @@ pub const SNIPPET_FUNC_IDX_UNRESOLVED: u32 = u32::MAX;
 /// That maximum possible number of tables allowed, the limited is driven from Wasm standards
 pub const N_MAX_TABLES: u32 = 100;
 
+/// That maximum possible number of memories allowed, the limit is driven from Wasm standards
+pub const N_MAX_MEMORIES: u32 = 100;
+
 /// The maximum limit of elements in total can be fit into one table.
 /// It means in total you can have `100*1024=102_400` elements.
 ///
@@ -81,11 +86,19 @@ pub const N_MAX_TABLES: u32 = 100;
 /// inside.
 pub const N_MAX_TABLE_SIZE: u32 = 1024;
 
+/// The first global word of the host globals declared by the import linker, the words of the
+/// module globals are below it.
+pub const N_HOST_GLOBAL_WORDS_OFFSET: u32 = 1 << 31;
+
+/// The maximum number of host globals, every host global takes two global words.
+pub const N_MAX_HOST_GLOBALS: u32 = 1 << 30;
+
 pub type InstrLoc = u32;
 pub type LabelRef = u32;
 pub type FuncTypeIdx = u32;
 pub type SignatureIdx = u32;
-pub type MemoryIdx = u32;
+/// Max number of memories can't exceed 100, so it fits into u16 next to an address offset
+pub type MemoryIdx = u16;
 pub type GlobalIdx = u32;
 /// Max table size can't exceed 100 elements, so it easily fits into u16
 pub type TableIdx = u16;
@@ -94,9 +107,12 @@ pub type DataSegmentIdx = u32;
 pub type ElementSegmentIdx = u32;
 pub type CompiledFunc = u32;
 pub type LocalDepth = u32;
+/// Fused opcodes keep two immediates in one 8-byte record, so their local depths are u16
+pub type ShortLocalDepth = u16;
 pub type BranchTableTargets = u32;
 pub type MaxStackHeight = u32;
 pub type SysFuncIdx = u32;
 pub type AddressOffset = u32;
 pub type BlockFuel = u32;
 pub type NumLocals = u32;
+pub type TagIdx = u32;
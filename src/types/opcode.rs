@@ -1,15 +1,209 @@
 use crate::{
     types::{
-        AddressOffset, BlockFuel, BranchOffset, BranchTableTargets, CompiledFunc, DataSegmentIdx,
-        ElementSegmentIdx, GlobalIdx, LocalDepth, SignatureIdx, TableIdx, UntypedValue,
+        read_byte, read_uleb128, write_uleb128, zigzag_decode, zigzag_encode, AddressOffset,
+        BlockFuel, BranchOffset, BranchTableTargets, CompiledFunc, DataSegmentIdx,
+        ElementSegmentIdx, GlobalIdx, LocalDepth, MemoryIdx, ShortLocalDepth, SignatureIdx,
+        TableIdx, TagIdx, UntypedValue,
     },
-    MaxStackHeight, NumLocals, SysFuncIdx, TrapCode,
+    MaxStackHeight, NumLocals, RwasmTextErrorKind, SysFuncIdx, TrapCode,
 };
 use alloc::{format, vec::Vec};
-use bincode::{Decode, Encode};
+use bincode::{de::read::Reader, error::DecodeError, Decode, Encode};
+use num_traits::FromPrimitive;
 
 const FPU_OPCODE_OFFSET: u32 = 1000;
 
+/// A compact opcode code offset for fpu opcodes, it keeps all compact codes within one byte.
+const FPU_COMPACT_OPCODE_OFFSET: u32 = 0x80;
+
+/// A size of one opcode record inside the aligned (zero-copy) code section.
+///
+/// The record has exactly the same layout as [`Opcode`] has in memory on little-endian targets:
+/// a `u16` opcode code followed by the fields placed according to the `repr(C)` rules.
+pub const OPCODE_RECORD_SIZE: usize = size_of::<Opcode>();
+
+const _: () = assert!(OPCODE_RECORD_SIZE == 8);
+
+/// A field of the opcode that can be stored inside the aligned opcode record.
+trait AlignedField: Sized {
+    fn write_le(&self, buf: &mut [u8]);
+
+    fn read_le(buf: &[u8]) -> Option<Self>;
+}
+
+impl AlignedField for u16 {
+    fn write_le(&self, buf: &mut [u8]) {
+        buf[..2].copy_from_slice(&self.to_le_bytes());
+    }
+
+    fn read_le(buf: &[u8]) -> Option<Self> {
+        Some(Self::from_le_bytes(buf.get(..2)?.try_into().ok()?))
+    }
+}
+
+impl AlignedField for u32 {
+    fn write_le(&self, buf: &mut [u8]) {
+        buf[..4].copy_from_slice(&self.to_le_bytes());
+    }
+
+    fn read_le(buf: &[u8]) -> Option<Self> {
+        Some(Self::from_le_bytes(buf.get(..4)?.try_into().ok()?))
+    }
+}
+
+impl AlignedField for BranchOffset {
+    fn write_le(&self, buf: &mut [u8]) {
+        buf[..4].copy_from_slice(&self.to_i32().to_le_bytes());
+    }
+
+    fn read_le(buf: &[u8]) -> Option<Self> {
+        Some(i32::from_le_bytes(buf.get(..4)?.try_into().ok()?).into())
+    }
+}
+
+impl AlignedField for UntypedValue {
+    fn write_le(&self, buf: &mut [u8]) {
+        self.to_bits().write_le(buf)
+    }
+
+    fn read_le(buf: &[u8]) -> Option<Self> {
+        u32::read_le(buf).map(UntypedValue::from_bits)
+    }
+}
+
+impl AlignedField for TrapCode {
+    fn write_le(&self, buf: &mut [u8]) {
+        buf[0] = *self as u8;
+    }
+
+    fn read_le(buf: &[u8]) -> Option<Self> {
+        TrapCode::from_u8(*buf.first()?)
+    }
+}
+
+/// A field of the opcode that can be stored using the compact encoding.
+trait CompactField: Sized {
+    fn write_compact(&self, sink: &mut Vec<u8>);
+
+    fn read_compact<R: Reader>(reader: &mut R) -> Result<Self, DecodeError>;
+}
+
+impl CompactField for u16 {
+    fn write_compact(&self, sink: &mut Vec<u8>) {
+        write_uleb128(sink, *self as u32);
+    }
+
+    fn read_compact<R: Reader>(reader: &mut R) -> Result<Self, DecodeError> {
+        u16::try_from(read_uleb128(reader)?)
+            .map_err(|_| DecodeError::Other("rwasm: leb128 number overflows u16"))
+    }
+}
+
+impl CompactField for u32 {
+    fn write_compact(&self, sink: &mut Vec<u8>) {
+        write_uleb128(sink, *self);
+    }
+
+    fn read_compact<R: Reader>(reader: &mut R) -> Result<Self, DecodeError> {
+        read_uleb128(reader)
+    }
+}
+
+impl CompactField for BranchOffset {
+    fn write_compact(&self, sink: &mut Vec<u8>) {
+        write_uleb128(sink, zigzag_encode(self.to_i32()));
+    }
+
+    fn read_compact<R: Reader>(reader: &mut R) -> Result<Self, DecodeError> {
+        Ok(zigzag_decode(read_uleb128(reader)?).into())
+    }
+}
+
+impl CompactField for UntypedValue {
+    fn write_compact(&self, sink: &mut Vec<u8>) {
+        // constants are often small negative numbers, that is why we use zigzag here
+        write_uleb128(sink, zigzag_encode(self.to_bits() as i32));
+    }
+
+    fn read_compact<R: Reader>(reader: &mut R) -> Result<Self, DecodeError> {
+        Ok(UntypedValue::from_bits(
+            zigzag_decode(read_uleb128(reader)?) as u32,
+        ))
+    }
+}
+
+impl CompactField for TrapCode {
+    fn write_compact(&self, sink: &mut Vec<u8>) {
+        sink.push(*self as u8);
+    }
+
+    fn read_compact<R: Reader>(reader: &mut R) -> Result<Self, DecodeError> {
+        TrapCode::from_u8(read_byte(reader)?).ok_or(DecodeError::Other("rwasm: invalid trap code"))
+    }
+}
+
+/// An opcode operand of the text format, symbols are already resolved by the assembler.
+#[derive(Debug, Copy, Clone)]
+pub(crate) enum TextOperand<'a> {
+    Int(i64),
+    Ident(&'a str),
+}
+
+/// A field of the opcode that can be parsed from the text format.
+trait TextField: Sized {
+    fn from_text(operand: TextOperand) -> Option<Self>;
+}
+
+impl TextField for u16 {
+    fn from_text(operand: TextOperand) -> Option<Self> {
+        match operand {
+            TextOperand::Int(value) => u16::try_from(value).ok(),
+            TextOperand::Ident(_) => None,
+        }
+    }
+}
+
+impl TextField for u32 {
+    fn from_text(operand: TextOperand) -> Option<Self> {
+        match operand {
+            TextOperand::Int(value) => u32::try_from(value).ok(),
+            TextOperand::Ident(_) => None,
+        }
+    }
+}
+
+impl TextField for BranchOffset {
+    fn from_text(operand: TextOperand) -> Option<Self> {
+        match operand {
+            TextOperand::Int(value) => i32::try_from(value).ok().map(Into::into),
+            TextOperand::Ident(_) => None,
+        }
+    }
+}
+
+impl TextField for UntypedValue {
+    fn from_text(operand: TextOperand) -> Option<Self> {
+        // constants are printed as unsigned bits, but negative numbers are allowed as well
+        let bits = match operand {
+            TextOperand::Int(value) if value < 0 => i32::try_from(value).ok()? as u32,
+            TextOperand::Int(value) => u32::try_from(value).ok()?,
+            TextOperand::Ident(_) => return None,
+        };
+        Some(UntypedValue::from_bits(bits))
+    }
+}
+
+impl TextField for TrapCode {
+    fn from_text(operand: TextOperand) -> Option<Self> {
+        let TextOperand::Ident(name) = operand else {
+            return None;
+        };
+        (0..=u8::MAX)
+            .filter_map(TrapCode::from_u8)
+            .find(|trap_code| format!("{:?}", trap_code) == name)
+    }
+}
+
 macro_rules! define_opcode_enum {
     (
         $(
@@ -24,7 +218,7 @@ macro_rules! define_opcode_enum {
         pub enum Opcode {
             $(
                 $(#[$meta])*
-                $variant $(($($field_ty),+))?,
+                $variant $(($($field_ty),+))? = define_opcode_enum!(@code $($kind)? $code) as u16,
             )*
         }
 
@@ -79,6 +273,123 @@ macro_rules! define_opcode_enum {
                 }
             }
         }
+
+        impl Opcode {
+            /// Encodes the opcode into a fixed-width record of the aligned code section.
+            ///
+            /// The padding bytes are always zeroed, so the encoding is deterministic.
+            pub fn to_aligned_record(&self) -> [u8; OPCODE_RECORD_SIZE] {
+                let mut record = [0u8; OPCODE_RECORD_SIZE];
+                match self {
+                    $(
+                        $(#[$meta])*
+                        Self::$variant $( ( $($field),+ ) )? => {
+                            let code = define_opcode_enum!(@code $($kind)? $code) as u16;
+                            code.write_le(&mut record[..]);
+                            $(
+                                #[allow(dead_code)]
+                                #[repr(C)]
+                                struct Layout { code: u16, $($field: $field_ty),+ }
+                                $(
+                                    $field.write_le(&mut record[core::mem::offset_of!(Layout, $field)..]);
+                                )+
+                            )?
+                        }
+                    )*
+                }
+                record
+            }
+
+            /// Decodes an opcode from a fixed-width record of the aligned code section.
+            ///
+            /// Returns `None` if the opcode code is unknown or one of the fields has an invalid
+            /// value, padding bytes are ignored.
+            pub fn from_aligned_record(record: &[u8; OPCODE_RECORD_SIZE]) -> Option<Self> {
+                let code = u16::read_le(&record[..])? as u32;
+                match code {
+                    $(
+                        $(#[$meta])*
+                        code if code == define_opcode_enum!(@code $($kind)? $code) => {
+                            $(
+                                #[allow(dead_code)]
+                                #[repr(C)]
+                                struct Layout { code: u16, $($field: $field_ty),+ }
+                            )?
+                            Some(Self::$variant $( ( $(
+                                <$field_ty as AlignedField>::read_le(
+                                    &record[core::mem::offset_of!(Layout, $field)..],
+                                )?
+                            ),+ ) )?)
+                        }
+                    )*
+                    _ => None,
+                }
+            }
+
+            /// Returns a one-byte opcode code used by the compact encoding.
+            pub fn compact_code(&self) -> u8 {
+                match self {
+                    $(
+                        $(#[$meta])*
+                        Self::$variant $( ( $(define_opcode_enum!(@ignore $field)),+ ) )? => {
+                            define_opcode_enum!(@compact_code $($kind)? $code) as u8
+                        }
+                    )*
+                }
+            }
+
+            /// Encodes the opcode using the compact encoding: a one-byte opcode code followed by
+            /// LEB128 immediates (signed immediates are zigzag encoded).
+            pub fn write_compact(&self, sink: &mut Vec<u8>) {
+                sink.push(self.compact_code());
+                match self {
+                    $(
+                        $(#[$meta])*
+                        Self::$variant $( ( $($field),+ ) )? => {
+                            $(
+                                $(
+                                    $field.write_compact(sink);
+                                )+
+                            )?
+                        }
+                    )*
+                }
+            }
+
+            /// Creates an opcode from its name and operands used by the text format.
+            pub(crate) fn from_text(
+                name: &str,
+                operands: &[TextOperand<'_>],
+            ) -> Result<Self, RwasmTextErrorKind> {
+                match name {
+                    $(
+                        $(#[$meta])*
+                        stringify!($variant) => match operands {
+                            [$($($field),+)?] => Ok(Self::$variant $( ( $(
+                                <$field_ty as TextField>::from_text(*$field)
+                                    .ok_or(RwasmTextErrorKind::InvalidOperands)?
+                            ),+ ) )?),
+                            _ => Err(RwasmTextErrorKind::InvalidOperands),
+                        },
+                    )*
+                    _ => Err(RwasmTextErrorKind::UnknownOpcode(name.into())),
+                }
+            }
+
+            /// Decodes an opcode encoded with [`Opcode::write_compact`].
+            pub fn read_compact<R: Reader>(reader: &mut R) -> Result<Self, DecodeError> {
+                let code = read_byte(reader)? as u32;
+                match code {
+                    $(
+                        $(#[$meta])*
+                        code if code == define_opcode_enum!(@compact_code $($kind)? $code) => {
+                            Ok(Self::$variant $( ( $(<$field_ty as CompactField>::read_compact(reader)?),+ ) )?)
+                        }
+                    )*
+                    _ => Err(DecodeError::Other("rwasm: invalid opcode")),
+                }
+            }
+        }
     };
     (@ignore $field:ident) => {
         _
@@ -89,6 +400,12 @@ macro_rules! define_opcode_enum {
     (@code $code:expr) => {
         $code
     };
+    (@compact_code fpu $code:expr) => {
+        FPU_COMPACT_OPCODE_OFFSET + $code
+    };
+    (@compact_code $code:expr) => {
+        $code
+    };
 }
 
 define_opcode_enum! {
@@ -184,6 +501,38 @@ define_opcode_enum! {
     BulkConst(locals: NumLocals) => 82u32,
     BulkDrop(locals: NumLocals) => 83u32,
 
+    // fused
+    LocalGetLocalGet(first: ShortLocalDepth, second: ShortLocalDepth) => 84u32,
+    LocalGetI32Const(depth: ShortLocalDepth, value: UntypedValue) => 85u32,
+    LocalSetLocalGet(first: ShortLocalDepth, second: ShortLocalDepth) => 86u32,
+    LocalGetI32Load(depth: ShortLocalDepth, offset: AddressOffset) => 87u32,
+    LocalSetLocalSet(first: ShortLocalDepth, second: ShortLocalDepth) => 88u32,
+    I32ConstI32Add(value: UntypedValue) => 89u32,
+
+    // multi-memory
+    I32LoadAt(memory: MemoryIdx, offset: AddressOffset) => 90u32,
+    I32Load8SAt(memory: MemoryIdx, offset: AddressOffset) => 91u32,
+    I32Load8UAt(memory: MemoryIdx, offset: AddressOffset) => 92u32,
+    I32Load16SAt(memory: MemoryIdx, offset: AddressOffset) => 93u32,
+    I32Load16UAt(memory: MemoryIdx, offset: AddressOffset) => 94u32,
+    I32StoreAt(memory: MemoryIdx, offset: AddressOffset) => 95u32,
+    I32Store8At(memory: MemoryIdx, offset: AddressOffset) => 96u32,
+    I32Store16At(memory: MemoryIdx, offset: AddressOffset) => 97u32,
+    MemorySizeAt(memory: MemoryIdx) => 98u32,
+    MemoryGrowAt(memory: MemoryIdx) => 99u32,
+    MemoryFillAt(memory: MemoryIdx) => 100u32,
+    MemoryCopyAt(dst: MemoryIdx, src: MemoryIdx) => 101u32,
+    MemoryInitAt(memory: MemoryIdx, segment: DataSegmentIdx) => 102u32,
+
+    // exceptions
+    TryBegin(offset: BranchOffset) => 103u32,
+    TryEnd => 104u32,
+    Throw(tag: TagIdx) => 105u32,
+    Rethrow(depth: u32) => 106u32,
+    Delegate(depth: u32) => 107u32,
+    ExceptionTag => 108u32,
+    ExceptionPayload(tag: TagIdx) => 109u32,
+
     // fpu
     @fpu F32Load(offset: AddressOffset) => 0u32,
     @fpu F64Load(offset: AddressOffset) => 1u32,
@@ -270,6 +619,11 @@ impl core::fmt::Display for Opcode {
                 Opcode::Br(value) => write!(f, "Br({})", value.to_i32()),
                 Opcode::BrIfEqz(value) => write!(f, "BrIfEqz({})", value.to_i32()),
                 Opcode::BrIfNez(value) => write!(f, "BrIfNez({})", value.to_i32()),
+                Opcode::TryBegin(value) => write!(f, "TryBegin({})", value.to_i32()),
+                Opcode::LocalGetI32Const(depth, value) => {
+                    write!(f, "LocalGetI32Const({}, {})", depth, value)
+                }
+                Opcode::I32ConstI32Add(value) => write!(f, "I32ConstI32Add({})", value),
                 _ => write!(f, "{:?}", self),
             }
         }
@@ -279,7 +633,10 @@ impl core::fmt::Display for Opcode {
 impl Opcode {
     pub fn update_branch_offset<I: Into<BranchOffset>>(&mut self, new_offset: I) {
         match self {
-            Opcode::Br(offset) | Opcode::BrIfEqz(offset) | Opcode::BrIfNez(offset) => {
+            Opcode::Br(offset)
+            | Opcode::BrIfEqz(offset)
+            | Opcode::BrIfNez(offset)
+            | Opcode::TryBegin(offset) => {
                 *offset = new_offset.into();
             }
             _ => unreachable!(),
@@ -322,17 +679,7 @@ impl Opcode {
     }
 
     pub fn is_memory_instruction(self) -> bool {
-        matches!(
-            self,
-            Opcode::I32Load8S(_)
-                | Opcode::I32Load8U(_)
-                | Opcode::I32Load16S(_)
-                | Opcode::I32Load16U(_)
-                | Opcode::I32Load(_)
-                | Opcode::I32Store8(_)
-                | Opcode::I32Store16(_)
-                | Opcode::I32Store(_)
-        )
+        self.is_memory_load_instruction() || self.is_memory_store_instruction()
     }
 
     pub fn is_memory_load_instruction(self) -> bool {
@@ -343,16 +690,53 @@ impl Opcode {
                 | Opcode::I32Load16S(_)
                 | Opcode::I32Load16U(_)
                 | Opcode::I32Load(_)
+                | Opcode::I32Load8SAt(..)
+                | Opcode::I32Load8UAt(..)
+                | Opcode::I32Load16SAt(..)
+                | Opcode::I32Load16UAt(..)
+                | Opcode::I32LoadAt(..)
         )
     }
 
     pub fn is_memory_store_instruction(self) -> bool {
         matches!(
             self,
-            Opcode::I32Store8(_) | Opcode::I32Store16(_) | Opcode::I32Store(_)
+            Opcode::I32Store8(_)
+                | Opcode::I32Store16(_)
+                | Opcode::I32Store(_)
+                | Opcode::I32Store8At(..)
+                | Opcode::I32Store16At(..)
+                | Opcode::I32StoreAt(..)
         )
     }
 
+    /// Returns the opcode that does the same for the memory with the given index, the default
+    /// memory keeps the original opcodes.
+    ///
+    /// Opcodes that don't access memory are returned as is, `MemoryCopy` is mapped to a copy
+    /// within the given memory.
+    pub fn with_memory_index(self, memory: MemoryIdx) -> Self {
+        if memory == 0 {
+            return self;
+        }
+        match self {
+            Opcode::I32Load(offset) => Opcode::I32LoadAt(memory, offset),
+            Opcode::I32Load8S(offset) => Opcode::I32Load8SAt(memory, offset),
+            Opcode::I32Load8U(offset) => Opcode::I32Load8UAt(memory, offset),
+            Opcode::I32Load16S(offset) => Opcode::I32Load16SAt(memory, offset),
+            Opcode::I32Load16U(offset) => Opcode::I32Load16UAt(memory, offset),
+            Opcode::I32Store(offset) => Opcode::I32StoreAt(memory, offset),
+            Opcode::I32Store8(offset) => Opcode::I32Store8At(memory, offset),
+            Opcode::I32Store16(offset) => Opcode::I32Store16At(memory, offset),
+            Opcode::MemorySize => Opcode::MemorySizeAt(memory),
+            Opcode::MemoryGrow => Opcode::MemoryGrowAt(memory),
+            Opcode::MemoryFill => Opcode::MemoryFillAt(memory),
+            Opcode::MemoryCopy => Opcode::MemoryCopyAt(memory, memory),
+            Opcode::MemoryInit(segment) => Opcode::MemoryInitAt(memory, segment),
+            opcode => opcode,
+        }
+    }
+
     pub fn is_ecall_instruction(self) -> bool {
         matches!(self, Opcode::Call(_) | Opcode::ReturnCall(_))
     }
@@ -372,6 +756,11 @@ impl Opcode {
         false
     }
 
+    /// Returns `true` for floating-point opcodes that are supported only with the `fpu` feature.
+    pub fn is_fpu_instruction(self) -> bool {
+        self.code() >= FPU_OPCODE_OFFSET
+    }
+
     pub fn is_unary_instruction(self) -> bool {
         matches!(
             self,
@@ -446,6 +835,7 @@ impl Opcode {
             Opcode::Br(branch_offset) => branch_offset.to_i32() as u32,
             Opcode::BrIfEqz(branch_offset) => branch_offset.to_i32() as u32,
             Opcode::BrIfNez(branch_offset) => branch_offset.to_i32() as u32,
+            Opcode::TryBegin(branch_offset) => branch_offset.to_i32() as u32,
             Opcode::BrTable(target) => *target,
             Opcode::ConsumeFuel(block_fuel) => *block_fuel,
             Opcode::ReturnCallInternal(func) => *func,
@@ -470,6 +860,21 @@ impl Opcode {
             Opcode::I32Store16(offset) => *offset,
             Opcode::MemoryInit(seg_id) => *seg_id,
             Opcode::DataDrop(seg_id) => *seg_id,
+            Opcode::I32LoadAt(_, offset)
+            | Opcode::I32Load8SAt(_, offset)
+            | Opcode::I32Load8UAt(_, offset)
+            | Opcode::I32Load16SAt(_, offset)
+            | Opcode::I32Load16UAt(_, offset)
+            | Opcode::I32StoreAt(_, offset)
+            | Opcode::I32Store8At(_, offset)
+            | Opcode::I32Store16At(_, offset) => *offset,
+            Opcode::MemorySizeAt(memory) => *memory as u32,
+            Opcode::MemoryGrowAt(memory) => *memory as u32,
+            Opcode::MemoryFillAt(memory) => *memory as u32,
+            Opcode::MemoryCopyAt(dst_memory, src_memory) => {
+                (*dst_memory as u32) << 16 | (*src_memory as u32)
+            }
+            Opcode::MemoryInitAt(_, seg_id) => *seg_id,
             Opcode::TableSize(table_id) => *table_id as u32,
             Opcode::TableGrow(table_id) => *table_id as u32,
             Opcode::TableFill(table_id) => *table_id as u32,
@@ -480,6 +885,8 @@ impl Opcode {
             }
             Opcode::TableInit(ele_seg_id) => *ele_seg_id,
             Opcode::ElemDrop(ele_seg_id) => *ele_seg_id,
+            Opcode::Throw(tag) | Opcode::ExceptionPayload(tag) => *tag,
+            Opcode::Rethrow(depth) | Opcode::Delegate(depth) => *depth,
             _ => 0,
         }
     }
@@ -533,6 +940,56 @@ mod tests {
         assert_eq!(decoded_len, data.len());
     }
 
+    #[test]
+    fn test_compact_opcode_encoding() {
+        let opcodes = [
+            (Opcode::Drop, 1),
+            (Opcode::I32Const(1.into()), 2),
+            (Opcode::I32Const((-1).into()), 2),
+            (Opcode::I32Const(u32::MAX.into()), 2),
+            (Opcode::I32Const(i32::MIN.into()), 6),
+            (Opcode::Br((-3).into()), 2),
+            (Opcode::LocalGet(300), 3),
+            (Opcode::Trap(TrapCode::OutOfFuel), 2),
+            (Opcode::TableCopy(1, u16::MAX), 5),
+            (Opcode::I32LoadAt(1, 300), 4),
+            (Opcode::MemoryInitAt(2, 0), 3),
+            (Opcode::TryBegin(12.into()), 2),
+            (Opcode::Throw(3), 2),
+            (Opcode::F64Load(7), 2),
+            (Opcode::I64TruncSatF64U, 1),
+        ];
+        for (opcode, expected_len) in opcodes {
+            let mut data = Vec::new();
+            opcode.write_compact(&mut data);
+            assert_eq!(data.len(), expected_len, "{opcode:?}");
+            let mut reader = bincode::de::read::SliceReader::new(&data);
+            assert_eq!(Opcode::read_compact(&mut reader).unwrap(), opcode);
+        }
+        // every opcode code must fit into one byte w/o collisions
+        assert_eq!(Opcode::ExceptionPayload(0).compact_code(), 109);
+        assert_eq!(Opcode::F32Load(0).compact_code(), 0x80);
+        assert_eq!(Opcode::I64TruncSatF64U.compact_code(), 0x80 + 69);
+        // u16 immediates must be in range
+        let mut reader = bincode::de::read::SliceReader::new(&[45, 0x80, 0x80, 0x04, 0]);
+        assert!(Opcode::read_compact(&mut reader).is_err());
+    }
+
+    #[test]
+    fn test_opcode_with_memory_index() {
+        assert_eq!(Opcode::I32Load(7).with_memory_index(0), Opcode::I32Load(7));
+        assert_eq!(
+            Opcode::I32Load(7).with_memory_index(2),
+            Opcode::I32LoadAt(2, 7)
+        );
+        assert_eq!(
+            Opcode::MemoryCopy.with_memory_index(1),
+            Opcode::MemoryCopyAt(1, 1)
+        );
+        assert_eq!(Opcode::I32Add.with_memory_index(1), Opcode::I32Add);
+        assert!(Opcode::I32Store16At(1, 0).is_memory_store_instruction());
+    }
+
     #[test]
     fn test_opcode_size() {
         assert_eq!(size_of::<Opcode>(), 8);
@@ -625,6 +1082,32 @@ mod tests {
             Opcode::I32Add64,
             Opcode::BulkConst(3),
             Opcode::BulkDrop(3),
+            Opcode::LocalGetLocalGet(1, 2),
+            Opcode::LocalGetI32Const(1, 42.into()),
+            Opcode::LocalSetLocalGet(1, 2),
+            Opcode::LocalGetI32Load(1, 0),
+            Opcode::LocalSetLocalSet(1, 2),
+            Opcode::I32ConstI32Add(42.into()),
+            Opcode::I32LoadAt(1, 0),
+            Opcode::I32Load8SAt(1, 0),
+            Opcode::I32Load8UAt(1, 0),
+            Opcode::I32Load16SAt(1, 0),
+            Opcode::I32Load16UAt(1, 0),
+            Opcode::I32StoreAt(1, 0),
+            Opcode::I32Store8At(1, 0),
+            Opcode::I32Store16At(1, 0),
+            Opcode::MemorySizeAt(1),
+            Opcode::MemoryGrowAt(1),
+            Opcode::MemoryFillAt(1),
+            Opcode::MemoryCopyAt(1, 0),
+            Opcode::MemoryInitAt(1, 0),
+            Opcode::TryBegin(0.into()),
+            Opcode::TryEnd,
+            Opcode::Throw(0),
+            Opcode::Rethrow(0),
+            Opcode::Delegate(0),
+            Opcode::ExceptionTag,
+            Opcode::ExceptionPayload(0),
         ];
         for (expected, opcode) in opcodes.iter().enumerate() {
             assert_eq!(opcode.code(), expected as u32, "{opcode:#}");
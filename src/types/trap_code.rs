@@ -23,6 +23,12 @@ pub enum TrapCode {
     // a special trap code for interrupting an execution,
     // it saves the latest registers for IP and SP in the call stack
     InterruptionCalled = 0x0c,
+    // the module's metadata doesn't match the metadata required by the execution engine
+    IncompatibleModule = 0x0d,
+    // an exception was thrown, but no handler of the current execution caught it
+    UncaughtException = 0x0e,
+    // an atomic memory access with an address that isn't aligned to its width
+    UnalignedAtomic = 0x0f,
     // this trap code is only used for external calls to terminate the execution,
     // but this error can't be returned from an execution cycle
     ExecutionHalted = 0xff,
@@ -47,6 +53,9 @@ impl core::fmt::Display for TrapCode {
             TrapCode::UnknownExternalFunction => write!(f, "unknown external function"),
             TrapCode::IllegalOpcode => write!(f, "illegal opcode"),
             TrapCode::InterruptionCalled => write!(f, "interruption called"),
+            TrapCode::IncompatibleModule => write!(f, "incompatible module"),
+            TrapCode::UncaughtException => write!(f, "uncaught exception"),
+            TrapCode::UnalignedAtomic => write!(f, "unaligned atomic"),
             TrapCode::ExecutionHalted => write!(f, "execution halted"),
         }
     }
@@ -1,4 +1,5 @@
-use crate::InstructionPtr;
+use crate::{InstructionPtr, Opcode, TagIdx, UntypedValue};
+use alloc::vec::Vec;
 use smallvec::SmallVec;
 
 #[derive(Default, Clone)]
@@ -8,6 +9,34 @@ use smallvec::SmallVec;
 pub struct CallStack {
     /// Return address stack backing storage; holds instruction pointers for nested calls.
     buf: SmallVec<[InstructionPtr; 16]>,
+    /// Exception handlers of the active `try` blocks across all calls (innermost last).
+    handlers: Vec<HandlerFrame>,
+}
+
+/// A thrown exception: the tag index and the payload in 32-bit stack slots.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Exception {
+    pub tag: TagIdx,
+    pub payload: Vec<UntypedValue>,
+}
+
+/// An entry of the exception handler stack.
+///
+/// Every `try` block owns exactly one entry: the handler while the body runs, and the caught
+/// exception while one of its `catch` clauses runs.
+#[derive(Debug, Clone)]
+pub(crate) enum HandlerFrame {
+    /// The body of a `try` block is running.
+    Try {
+        /// The landing pad of the `try` block.
+        handler: InstructionPtr,
+        /// The call stack length upon entering the `try` block.
+        call_depth: usize,
+        /// The value stack length upon entering the `try` block.
+        stack_len: usize,
+    },
+    /// A `catch` clause of a `try` block is running.
+    Caught(Exception),
 }
 
 impl CallStack {
@@ -27,9 +56,44 @@ impl CallStack {
         self.buf.len()
     }
 
+    /// Drops return addresses of the calls above the given length.
+    pub(crate) fn truncate(&mut self, len: usize) {
+        self.buf.truncate(len);
+    }
+
+    pub(crate) fn push_handler(&mut self, handler: HandlerFrame) {
+        self.handlers.push(handler);
+    }
+
+    pub(crate) fn pop_handler(&mut self) -> Option<HandlerFrame> {
+        self.handlers.pop()
+    }
+
+    /// Returns the handler entry at the given depth, a `depth` of 0 is the innermost one.
+    pub(crate) fn handler(&self, depth: u32) -> Option<&HandlerFrame> {
+        self.handlers.iter().nth_back(depth as usize)
+    }
+
+    /// Moves all return addresses from one code section to another one with the same content.
+    ///
+    /// # Safety
+    ///
+    /// All return addresses must point inside the code section that starts at `from`.
+    pub(crate) unsafe fn rebase(&mut self, from: *const Opcode, to: *const Opcode) {
+        for ip in self.buf.iter_mut() {
+            unsafe { ip.rebase(from, to) };
+        }
+        for handler in self.handlers.iter_mut() {
+            if let HandlerFrame::Try { handler, .. } = handler {
+                unsafe { handler.rebase(from, to) };
+            }
+        }
+    }
+
     pub fn reset(&mut self) {
         unsafe {
             self.buf.set_len(0);
         }
+        self.handlers.clear();
     }
 }
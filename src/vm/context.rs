@@ -1,4 +1,4 @@
-use crate::{types::TrapCode, CallerTr, RwasmStore, StoreTr};
+use crate::{types::TrapCode, CallerTr, MemoryIdx, RwasmStore, StoreTr, TagIdx, Value};
 use alloc::vec::Vec;
 
 pub struct RwasmCaller<'a, T: 'static> {
@@ -29,6 +29,24 @@ impl<'a, T: 'static> StoreTr<T> for RwasmCaller<'a, T> {
         Ok(())
     }
 
+    fn memory_read_at(
+        &mut self,
+        memory: MemoryIdx,
+        offset: usize,
+        buffer: &mut [u8],
+    ) -> Result<(), TrapCode> {
+        self.store.memory_read_at(memory, offset, buffer)
+    }
+
+    fn memory_write_at(
+        &mut self,
+        memory: MemoryIdx,
+        offset: usize,
+        buffer: &[u8],
+    ) -> Result<(), TrapCode> {
+        self.store.memory_write_at(memory, offset, buffer)
+    }
+
     fn data_mut(&mut self) -> &mut T {
         &mut self.store.data
     }
@@ -50,4 +68,9 @@ impl<'a, T: 'static> StoreTr<T> for RwasmCaller<'a, T> {
     }
 }
 
-impl<'a, T: 'static> CallerTr<T> for RwasmCaller<'a, T> {}
+impl<'a, T: 'static> CallerTr<T> for RwasmCaller<'a, T> {
+    fn throw_exception(&mut self, tag: TagIdx, payload: &[Value]) -> TrapCode {
+        self.store.pending_exception = Some((tag, payload.to_vec()));
+        TrapCode::UncaughtException
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
-    CallStack, InstructionPtr, ReusableContext, RwasmExecutor, RwasmModule, RwasmStore, TrapCode,
-    Value, ValueStack,
+    CallStack, InstructionPtr, ReusableContext, RwasmExecutor, RwasmMetadata, RwasmModule,
+    RwasmModuleView, RwasmStore, TrapCode, Value, ValueStack,
 };
 use alloc::sync::Arc;
 use core::mem::take;
@@ -11,6 +11,7 @@ use spin::Mutex;
 #[derive(Default, Clone)]
 pub struct ExecutionEngine {
     inner: Arc<Mutex<ExecutionEngineInner>>,
+    required_metadata: Option<RwasmMetadata>,
 }
 
 impl ExecutionEngine {
@@ -18,14 +19,48 @@ impl ExecutionEngine {
         Self::default()
     }
 
+    /// Accepts only modules with exactly this metadata section, so a node can pin the fuel
+    /// settings, the fuel cost model, the fuel metering mode, fpu usage and the import linker its
+    /// modules are compiled with.
+    ///
+    /// Modules without a metadata section and modules with a different one are rejected with
+    /// [`TrapCode::IncompatibleModule`].
+    pub fn with_required_metadata(mut self, required_metadata: RwasmMetadata) -> Self {
+        self.required_metadata = Some(required_metadata);
+        self
+    }
+
+    /// Checks the module's metadata section against the metadata required by the engine.
+    pub fn check_metadata(&self, metadata: Option<&RwasmMetadata>) -> Result<(), TrapCode> {
+        match &self.required_metadata {
+            Some(required_metadata) if metadata != Some(required_metadata) => {
+                Err(TrapCode::IncompatibleModule)
+            }
+            _ => Ok(()),
+        }
+    }
+
     #[inline(always)]
     pub fn entrypoint<T>(
         &self,
         store: &mut RwasmStore<T>,
         module: &RwasmModule,
     ) -> Result<(), TrapCode> {
+        self.check_metadata(module.metadata_section.as_ref())?;
+        let mut ctx = self.inner.lock();
+        ctx.entrypoint(store, module.into(), Some(module))
+    }
+
+    /// Runs the entrypoint of a borrowed rWasm module, see [`Self::execute_view`].
+    #[inline(always)]
+    pub fn entrypoint_view<T>(
+        &self,
+        store: &mut RwasmStore<T>,
+        module: RwasmModuleView<'_>,
+    ) -> Result<(), TrapCode> {
+        self.check_metadata(module.metadata_section.as_ref())?;
         let mut ctx = self.inner.lock();
-        ctx.entrypoint(store, module)
+        ctx.entrypoint(store, module, None)
     }
 
     #[inline(always)]
@@ -36,8 +71,64 @@ impl ExecutionEngine {
         params: &[Value],
         result: &mut [Value],
     ) -> Result<(), TrapCode> {
+        self.check_metadata(module.metadata_section.as_ref())?;
+        let mut ctx = self.inner.lock();
+        ctx.execute(
+            store,
+            module.into(),
+            Some(module),
+            module.source_pc,
+            params,
+            result,
+        )
+    }
+
+    /// Executes a rWasm module starting from the given program counter.
+    ///
+    /// The PC must point to the beginning of a compiled function, for example, an export offset.
+    #[inline(always)]
+    pub fn execute_at<T>(
+        &self,
+        store: &mut RwasmStore<T>,
+        module: &RwasmModule,
+        pc: u32,
+        params: &[Value],
+        result: &mut [Value],
+    ) -> Result<(), TrapCode> {
+        self.check_metadata(module.metadata_section.as_ref())?;
+        let mut ctx = self.inner.lock();
+        ctx.execute(store, module.into(), Some(module), pc, params, result)
+    }
+
+    /// Executes a borrowed rWasm module, the code is executed in place without copying.
+    ///
+    /// If the execution is interrupted, then the module is copied into the resumable context.
+    #[inline(always)]
+    pub fn execute_view<T>(
+        &self,
+        store: &mut RwasmStore<T>,
+        module: RwasmModuleView<'_>,
+        params: &[Value],
+        result: &mut [Value],
+    ) -> Result<(), TrapCode> {
+        self.check_metadata(module.metadata_section.as_ref())?;
         let mut ctx = self.inner.lock();
-        ctx.execute(store, module, params, result)
+        ctx.execute(store, module, None, module.source_pc, params, result)
+    }
+
+    /// Executes a borrowed rWasm module starting from the given program counter.
+    #[inline(always)]
+    pub fn execute_view_at<T>(
+        &self,
+        store: &mut RwasmStore<T>,
+        module: RwasmModuleView<'_>,
+        pc: u32,
+        params: &[Value],
+        result: &mut [Value],
+    ) -> Result<(), TrapCode> {
+        self.check_metadata(module.metadata_section.as_ref())?;
+        let mut ctx = self.inner.lock();
+        ctx.execute(store, module, None, pc, params, result)
     }
 
     #[inline(always)]
@@ -61,7 +152,8 @@ impl ExecutionEngineInner {
     pub(crate) fn entrypoint<T>(
         &mut self,
         store: &mut RwasmStore<T>,
-        module: &RwasmModule,
+        module: RwasmModuleView<'_>,
+        owner: Option<&RwasmModule>,
     ) -> Result<(), TrapCode> {
         let mut value_stack = ValueStack::default();
         let mut call_stack = CallStack::default();
@@ -73,19 +165,27 @@ impl ExecutionEngineInner {
             RwasmExecutor::entrypoint(module, &mut value_stack, &mut call_stack, store);
         match executor.run(&[], &mut []) {
             Err(TrapCode::InterruptionCalled) => {
-                let (ip, sp) = (executor.ip, executor.sp);
+                let (mut ip, sp) = (executor.ip, executor.sp);
                 value_stack.sync_stack_ptr(sp);
-                self.remember_context(module.clone(), store, value_stack, call_stack, ip)
+                let module = match owner {
+                    Some(module) => module.clone(),
+                    None => Self::detach_view(module, &mut ip, &mut call_stack),
+                };
+                self.remember_context(module, store, value_stack, call_stack, ip)
             }
             res => res,
         }
     }
 
     /// Executes a rWasm module's function with the given parameters and stores the result.
+    ///
+    /// The owner is used to remember the context on interruption, for borrowed modules it's `None`.
     pub(crate) fn execute<T>(
         &mut self,
         store: &mut RwasmStore<T>,
-        module: &RwasmModule,
+        module: RwasmModuleView<'_>,
+        owner: Option<&RwasmModule>,
+        pc: u32,
         params: &[Value],
         result: &mut [Value],
     ) -> Result<(), TrapCode> {
@@ -97,20 +197,40 @@ impl ExecutionEngineInner {
         );
         let sp = value_stack.stack_ptr();
         let mut ip = InstructionPtr::new(module.code_section.as_ptr());
-        debug_assert!(module.source_pc < module.code_section.len() as u32);
-        ip.offset(module.source_pc as isize);
+        debug_assert!(pc < module.code_section.len() as u32);
+        ip.offset(pc as isize);
         let mut executor =
             RwasmExecutor::new(module, &mut value_stack, sp, &mut call_stack, ip, store);
         match executor.run(params, result) {
             Err(TrapCode::InterruptionCalled) => {
-                let (ip, sp) = (executor.ip, executor.sp);
+                let (mut ip, sp) = (executor.ip, executor.sp);
                 value_stack.sync_stack_ptr(sp);
-                self.remember_context(module.clone(), store, value_stack, call_stack, ip)
+                let module = match owner {
+                    Some(module) => module.clone(),
+                    None => Self::detach_view(module, &mut ip, &mut call_stack),
+                };
+                self.remember_context(module, store, value_stack, call_stack, ip)
             }
             res => res,
         }
     }
 
+    /// Copies a borrowed module to make it possible to resume the execution after the view is gone.
+    fn detach_view(
+        module: RwasmModuleView<'_>,
+        ip: &mut InstructionPtr,
+        call_stack: &mut CallStack,
+    ) -> RwasmModule {
+        let owned = module.to_module();
+        let (from, to) = (module.code_section.as_ptr(), owned.code_section.as_ptr());
+        // SAFETY: the IP and all return addresses point inside the view's code section
+        unsafe {
+            ip.rebase(from, to);
+            call_stack.rebase(from, to);
+        }
+        owned
+    }
+
     /// Resumes the execution of a WASM (WebAssembly) function that was previously interrupted.
     pub(crate) fn resume<T>(
         &mut self,
@@ -1,5 +1,6 @@
 mod alu;
 mod control_flow;
+mod exception;
 #[cfg(feature = "fpu")]
 mod fpu;
 mod memory;
@@ -9,8 +10,8 @@ mod table;
 
 use crate::{
     types::{AddressOffset, TableIdx, UntypedValue},
-    CallStack, InstructionPtr, Opcode, RwasmCaller, RwasmModule, RwasmStore, SysFuncIdx, TrapCode,
-    TypedCaller, Value, ValueStack, ValueStackPtr,
+    CallStack, InstructionPtr, MemoryIdx, Opcode, RwasmCaller, RwasmModuleView, RwasmStore,
+    SysFuncIdx, TrapCode, TypedCaller, Value, ValueStack, ValueStackPtr,
 };
 use smallvec::SmallVec;
 
@@ -18,7 +19,7 @@ use smallvec::SmallVec;
 /// in the `rwasm` runtime environment. It acts as the primary execution object, coordinating
 /// the state and execution flow of a WebAssembly module.
 pub struct RwasmExecutor<'a, T: 'static> {
-    pub(crate) module: &'a RwasmModule,
+    pub(crate) module: RwasmModuleView<'a>,
     pub(crate) value_stack: &'a mut ValueStack,
     pub(crate) sp: ValueStackPtr,
     pub(crate) call_stack: &'a mut CallStack,
@@ -28,18 +29,19 @@ pub struct RwasmExecutor<'a, T: 'static> {
 
 impl<'a, T> RwasmExecutor<'a, T> {
     pub fn entrypoint(
-        module: &'a RwasmModule,
+        module: impl Into<RwasmModuleView<'a>>,
         value_stack: &'a mut ValueStack,
         call_stack: &'a mut CallStack,
         store: &'a mut RwasmStore<T>,
     ) -> Self {
+        let module = module.into();
         let sp = value_stack.stack_ptr();
         let ip = InstructionPtr::new(module.code_section.as_ptr());
         Self::new(module, value_stack, sp, call_stack, ip, store)
     }
 
     pub fn new(
-        module: &'a RwasmModule,
+        module: impl Into<RwasmModuleView<'a>>,
         value_stack: &'a mut ValueStack,
         sp: ValueStackPtr,
         call_stack: &'a mut CallStack,
@@ -47,7 +49,7 @@ impl<'a, T> RwasmExecutor<'a, T> {
         store: &'a mut RwasmStore<T>,
     ) -> Self {
         Self {
-            module,
+            module: module.into(),
             value_stack,
             sp,
             call_stack,
@@ -260,6 +262,13 @@ impl<'a, T> RwasmExecutor<'a, T> {
             BulkConst(imm) => self.visit_bulk_const(imm),
             BulkDrop(imm) => self.visit_bulk_drop(imm),
 
+            LocalGetLocalGet(first, second) => self.visit_local_get_local_get(first, second),
+            LocalGetI32Const(depth, imm) => self.visit_local_get_i32_const(depth, imm),
+            LocalSetLocalGet(first, second) => self.visit_local_set_local_get(first, second),
+            LocalGetI32Load(depth, imm) => self.visit_local_get_i32_load(depth, imm)?,
+            LocalSetLocalSet(first, second) => self.visit_local_set_local_set(first, second),
+            I32ConstI32Add(imm) => self.visit_i32_const_i32_add(imm),
+
             MemorySize => self.visit_memory_size(),
             MemoryGrow => self.visit_memory_grow()?,
             MemoryFill => self.visit_memory_fill()?,
@@ -275,6 +284,20 @@ impl<'a, T> RwasmExecutor<'a, T> {
             I32Store8(imm) => self.visit_i32_store_8(imm)?,
             I32Store16(imm) => self.visit_i32_store_16(imm)?,
 
+            MemorySizeAt(memory) => self.visit_memory_size_at(memory),
+            MemoryGrowAt(memory) => self.visit_memory_grow_at(memory)?,
+            MemoryFillAt(memory) => self.visit_memory_fill_at(memory)?,
+            MemoryCopyAt(dst, src) => self.visit_memory_copy_at(dst, src)?,
+            MemoryInitAt(memory, imm) => self.visit_memory_init_at(memory, imm)?,
+            I32LoadAt(memory, imm) => self.visit_i32_load_at(memory, imm)?,
+            I32Load8SAt(memory, imm) => self.visit_i32_load_i8_s_at(memory, imm)?,
+            I32Load8UAt(memory, imm) => self.visit_i32_load_i8_u_at(memory, imm)?,
+            I32Load16SAt(memory, imm) => self.visit_i32_load_i16_s_at(memory, imm)?,
+            I32Load16UAt(memory, imm) => self.visit_i32_load_i16_u_at(memory, imm)?,
+            I32StoreAt(memory, imm) => self.visit_i32_store_at(memory, imm)?,
+            I32Store8At(memory, imm) => self.visit_i32_store_8_at(memory, imm)?,
+            I32Store16At(memory, imm) => self.visit_i32_store_16_at(memory, imm)?,
+
             TableSize(imm) => self.visit_table_size(imm),
             TableGrow(imm) => self.visit_table_grow(imm)?,
             TableFill(imm) => self.visit_table_fill(imm)?,
@@ -284,6 +307,14 @@ impl<'a, T> RwasmExecutor<'a, T> {
             TableInit(imm) => self.visit_table_init(imm)?,
             ElemDrop(imm) => self.visit_element_drop(imm),
 
+            TryBegin(imm) => self.visit_try_begin(imm),
+            TryEnd => self.visit_try_end(),
+            Throw(tag) => self.visit_throw(tag)?,
+            Rethrow(depth) => self.visit_rethrow(depth)?,
+            Delegate(depth) => self.visit_delegate(depth)?,
+            ExceptionTag => self.visit_exception_tag()?,
+            ExceptionPayload(tag) => self.visit_exception_payload(tag)?,
+
             #[cfg(feature = "fpu")]
             opcode => self.exec_fpu_opcode(opcode)?,
             #[cfg(not(feature = "fpu"))]
@@ -382,6 +413,43 @@ impl<'a, T> RwasmExecutor<'a, T> {
         Ok(())
     }
 
+    #[inline(always)]
+    pub(crate) fn execute_load_extend_at(
+        &mut self,
+        memory: MemoryIdx,
+        offset: AddressOffset,
+        load_extend: fn(
+            memory: &[u8],
+            address: UntypedValue,
+            offset: u32,
+        ) -> Result<UntypedValue, TrapCode>,
+    ) -> Result<(), TrapCode> {
+        let memory = self.store.memory_data(memory);
+        self.sp
+            .try_eval_top(|address| load_extend(memory, address, offset))?;
+        self.ip.add(1);
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub(crate) fn execute_store_wrap_at(
+        &mut self,
+        memory: MemoryIdx,
+        offset: AddressOffset,
+        store_wrap: fn(
+            memory: &mut [u8],
+            address: UntypedValue,
+            offset: u32,
+            value: UntypedValue,
+        ) -> Result<(), TrapCode>,
+    ) -> Result<(), TrapCode> {
+        let (address, value) = self.sp.pop2();
+        let memory = self.store.memory_mut(memory).data_mut();
+        store_wrap(memory, address, offset, value)?;
+        self.ip.add(1);
+        Ok(())
+    }
+
     #[inline(always)]
     pub(crate) fn execute_store_wrap(
         &mut self,
@@ -461,6 +529,13 @@ impl<'a, T> RwasmExecutor<'a, T> {
                 // terminate an execution
                 Err(TrapCode::InterruptionCalled)
             }
+            Err(TrapCode::UncaughtException) => {
+                // the host raised a guest exception, it can be caught by a `try` block
+                match self.store.pending_exception.take() {
+                    Some((tag, values)) => self.throw_host_exception(tag, &values),
+                    None => Err(TrapCode::UncaughtException),
+                }
+            }
             Err(err) => Err(err),
         }
     }
@@ -101,3 +101,11 @@ impl<'a, T> RwasmExecutor<'a, T> {
         fn visit_i32_rem_u(i32_rem_u);
     }
 }
+
+impl<'a, T> RwasmExecutor<'a, T> {
+    #[inline(always)]
+    pub(crate) fn visit_i32_const_i32_add(&mut self, rhs: UntypedValue) {
+        self.sp.eval_top(|lhs| lhs.i32_add(rhs));
+        self.ip.add(1);
+    }
+}
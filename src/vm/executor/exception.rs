@@ -0,0 +1,144 @@
+use crate::{BranchOffset, Exception, HandlerFrame, RwasmExecutor, TagIdx, TrapCode, Value};
+use alloc::vec::Vec;
+use wasmparser::ValType;
+
+impl<'a, T> RwasmExecutor<'a, T> {
+    #[inline(always)]
+    pub(crate) fn visit_try_begin(&mut self, branch_offset: BranchOffset) {
+        let mut handler = self.ip;
+        handler.offset(branch_offset.to_i32() as isize);
+        // we store the stack length instead of a pointer since the stack can be reallocated
+        let stack_len = self.value_stack.stack_len(self.sp);
+        self.call_stack.push_handler(HandlerFrame::Try {
+            handler,
+            call_depth: self.call_stack.len(),
+            stack_len,
+        });
+        self.ip.add(1);
+    }
+
+    #[inline(always)]
+    pub(crate) fn visit_try_end(&mut self) {
+        self.call_stack.pop_handler();
+        self.ip.add(1);
+    }
+
+    #[inline(always)]
+    pub(crate) fn visit_throw(&mut self, tag: TagIdx) -> Result<(), TrapCode> {
+        let payload_slots = self
+            .module
+            .resolve_tag(tag)
+            .expect("rwasm: unresolved tag index")
+            .payload_slots();
+        let mut payload = (0..payload_slots)
+            .map(|_| self.sp.pop())
+            .collect::<Vec<_>>();
+        payload.reverse();
+        self.throw_exception(Exception { tag, payload })
+    }
+
+    #[inline(always)]
+    pub(crate) fn visit_rethrow(&mut self, depth: u32) -> Result<(), TrapCode> {
+        let exception = self.caught_exception(depth)?.clone();
+        self.throw_exception(exception)
+    }
+
+    #[inline(always)]
+    pub(crate) fn visit_delegate(&mut self, depth: u32) -> Result<(), TrapCode> {
+        let exception = self.caught_exception(0)?.clone();
+        // drop the caught exception and the handlers between the `try` and the target label
+        for _ in 0..=depth {
+            self.call_stack.pop_handler();
+        }
+        self.throw_exception(exception)
+    }
+
+    #[inline(always)]
+    pub(crate) fn visit_exception_tag(&mut self) -> Result<(), TrapCode> {
+        let tag = self.caught_exception(0)?.tag;
+        self.sp.push_as(tag);
+        self.ip.add(1);
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub(crate) fn visit_exception_payload(&mut self, tag: TagIdx) -> Result<(), TrapCode> {
+        // borrow the call stack directly to push the payload while the exception is borrowed
+        let Some(HandlerFrame::Caught(exception)) = self.call_stack.handler(0) else {
+            return Err(TrapCode::IllegalOpcode);
+        };
+        if exception.tag != tag {
+            return Err(TrapCode::BadSignature);
+        }
+        for value in exception.payload.iter() {
+            self.sp.push(*value);
+        }
+        self.ip.add(1);
+        Ok(())
+    }
+
+    /// Returns the exception caught by the handler entry at the given depth.
+    fn caught_exception(&self, depth: u32) -> Result<&Exception, TrapCode> {
+        match self.call_stack.handler(depth) {
+            Some(HandlerFrame::Caught(exception)) => Ok(exception),
+            // the compiler never emits exception opcodes outside `catch` clauses
+            _ => Err(TrapCode::IllegalOpcode),
+        }
+    }
+
+    /// Unwinds to the innermost `try` block and jumps to its landing pad.
+    ///
+    /// The calls above the `try` block are discarded and the value stack is restored to the
+    /// height of `TryBegin`, the exception stays active until the end of the `catch` clause.
+    /// Traps with [`TrapCode::UncaughtException`] if there is no `try` block left.
+    pub(crate) fn throw_exception(&mut self, exception: Exception) -> Result<(), TrapCode> {
+        while let Some(handler) = self.call_stack.pop_handler() {
+            let HandlerFrame::Try {
+                handler,
+                call_depth,
+                stack_len,
+            } = handler
+            else {
+                continue;
+            };
+            self.call_stack.truncate(call_depth);
+            self.sp = self.value_stack.stack_ptr_at(stack_len);
+            self.value_stack.sync_stack_ptr(self.sp);
+            self.call_stack
+                .push_handler(HandlerFrame::Caught(exception));
+            self.ip = handler;
+            return Ok(());
+        }
+        Err(TrapCode::UncaughtException)
+    }
+
+    /// Throws the exception raised by a host function inside the guest.
+    ///
+    /// Traps with [`TrapCode::BadSignature`] if the values don't match the tag parameters.
+    pub(crate) fn throw_host_exception(
+        &mut self,
+        tag: TagIdx,
+        values: &[Value],
+    ) -> Result<(), TrapCode> {
+        let Some(rwasm_tag) = self.module.resolve_tag(tag) else {
+            return Err(TrapCode::BadSignature);
+        };
+        let matches_params = rwasm_tag.params.len() == values.len()
+            && rwasm_tag
+                .params
+                .iter()
+                .zip(values)
+                .all(|(param, value)| ValType::from(*param) == value.ty());
+        if !matches_params {
+            return Err(TrapCode::BadSignature);
+        }
+        self.value_stack.sync_stack_ptr(self.sp);
+        self.value_stack
+            .reserve(rwasm_tag.payload_slots() as usize)?;
+        self.sp = self.value_stack.stack_ptr();
+        for value in values {
+            self.sp.push_value(value);
+        }
+        self.visit_throw(tag)
+    }
+}
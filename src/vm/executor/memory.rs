@@ -1,4 +1,7 @@
-use crate::{AddressOffset, DataSegmentIdx, Pages, RwasmExecutor, TrapCode, UntypedValue};
+use crate::{
+    types::ShortLocalDepth, AddressOffset, DataSegmentIdx, MemoryIdx, Pages, RwasmExecutor,
+    TrapCode, UntypedValue,
+};
 
 macro_rules! impl_visit_load {
     ( $( fn $visit_ident:ident($untyped_ident:ident); )* ) => {
@@ -22,6 +25,28 @@ macro_rules! impl_visit_store {
     }
 }
 
+macro_rules! impl_visit_load_at {
+    ( $( fn $visit_ident:ident($untyped_ident:ident); )* ) => {
+        $(
+            #[inline(always)]
+            pub(crate) fn $visit_ident(&mut self, memory: MemoryIdx, address_offset: AddressOffset) -> Result<(), TrapCode> {
+                self.execute_load_extend_at(memory, address_offset, UntypedValue::$untyped_ident)
+            }
+        )*
+    }
+}
+
+macro_rules! impl_visit_store_at {
+    ( $( fn $visit_ident:ident($untyped_ident:ident); )* ) => {
+        $(
+            #[inline(always)]
+            pub(crate) fn $visit_ident(&mut self, memory: MemoryIdx, address_offset: AddressOffset) -> Result<(), TrapCode> {
+                self.execute_store_wrap_at(memory, address_offset, UntypedValue::$untyped_ident)
+            }
+        )*
+    }
+}
+
 impl<'a, T> RwasmExecutor<'a, T> {
     impl_visit_load! {
         fn visit_i32_load(i32_load);
@@ -38,6 +63,32 @@ impl<'a, T> RwasmExecutor<'a, T> {
         fn visit_i32_store_16(i32_store16, 2);
     }
 
+    impl_visit_load_at! {
+        fn visit_i32_load_at(i32_load);
+
+        fn visit_i32_load_i8_s_at(i32_load8_s);
+        fn visit_i32_load_i8_u_at(i32_load8_u);
+        fn visit_i32_load_i16_s_at(i32_load16_s);
+        fn visit_i32_load_i16_u_at(i32_load16_u);
+    }
+
+    impl_visit_store_at! {
+        fn visit_i32_store_at(i32_store);
+        fn visit_i32_store_8_at(i32_store8);
+        fn visit_i32_store_16_at(i32_store16);
+    }
+
+    #[inline(always)]
+    pub(crate) fn visit_local_get_i32_load(
+        &mut self,
+        local_depth: ShortLocalDepth,
+        address_offset: AddressOffset,
+    ) -> Result<(), TrapCode> {
+        let address = self.sp.nth_back(local_depth as usize);
+        self.sp.push(address);
+        self.execute_load_extend(address_offset, UntypedValue::i32_load)
+    }
+
     #[inline(always)]
     pub(crate) fn visit_memory_size(&mut self) {
         let result: u32 = self.store.global_memory.current_pages().into();
@@ -58,8 +109,7 @@ impl<'a, T> RwasmExecutor<'a, T> {
         };
         let new_pages = self
             .store
-            .global_memory
-            .grow(delta)
+            .try_grow_memory(0, delta)
             .map(u32::from)
             .unwrap_or(u32::MAX);
         self.sp.push_as(new_pages);
@@ -137,7 +187,7 @@ impl<'a, T> RwasmExecutor<'a, T> {
             .get_mut(dst_offset..)
             .and_then(|memory| memory.get_mut(..n))
             .ok_or(TrapCode::MemoryOutOfBounds)?;
-        let mut memory_section = self.module.data_section.as_slice();
+        let mut memory_section = self.module.data_section;
         if is_empty_data_segment {
             memory_section = &[];
         }
@@ -154,6 +204,121 @@ impl<'a, T> RwasmExecutor<'a, T> {
         Ok(())
     }
 
+    #[inline(always)]
+    pub(crate) fn visit_memory_size_at(&mut self, memory: MemoryIdx) {
+        let result = self
+            .store
+            .memory(memory)
+            .map_or(0, |memory| memory.current_pages().into());
+        self.sp.push_as(result);
+        self.ip.add(1);
+    }
+
+    #[inline(always)]
+    pub(crate) fn visit_memory_grow_at(&mut self, memory: MemoryIdx) -> Result<(), TrapCode> {
+        let delta: u32 = self.sp.pop_as();
+        let new_pages = Pages::new(delta)
+            .and_then(|delta| self.store.try_grow_memory(memory, delta))
+            .map(u32::from)
+            .unwrap_or(u32::MAX);
+        self.sp.push_as(new_pages);
+        self.ip.add(1);
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub(crate) fn visit_memory_fill_at(&mut self, memory: MemoryIdx) -> Result<(), TrapCode> {
+        let (d, val, n) = self.sp.pop3();
+        let n = i32::from(n) as usize;
+        let offset = i32::from(d) as usize;
+        self.store
+            .memory_mut(memory)
+            .data_mut()
+            .get_mut(offset..)
+            .and_then(|memory| memory.get_mut(..n))
+            .ok_or(TrapCode::MemoryOutOfBounds)?
+            .fill(u8::from(val));
+        self.ip.add(1);
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub(crate) fn visit_memory_copy_at(
+        &mut self,
+        dst_memory: MemoryIdx,
+        src_memory: MemoryIdx,
+    ) -> Result<(), TrapCode> {
+        let (d, s, n) = self.sp.pop3();
+        let n = i32::from(n) as usize;
+        let src_offset = i32::from(s) as usize;
+        let dst_offset = i32::from(d) as usize;
+        if dst_memory == src_memory {
+            let data = self.store.memory_mut(dst_memory).data_mut();
+            data.get(src_offset..)
+                .and_then(|memory| memory.get(..n))
+                .ok_or(TrapCode::MemoryOutOfBounds)?;
+            data.get(dst_offset..)
+                .and_then(|memory| memory.get(..n))
+                .ok_or(TrapCode::MemoryOutOfBounds)?;
+            data.copy_within(src_offset..src_offset.wrapping_add(n), dst_offset);
+        } else {
+            // different memories can't be borrowed at the same time, so copy through a buffer
+            let buffer = self
+                .store
+                .memory_data(src_memory)
+                .get(src_offset..)
+                .and_then(|memory| memory.get(..n))
+                .ok_or(TrapCode::MemoryOutOfBounds)?
+                .to_vec();
+            self.store
+                .memory_mut(dst_memory)
+                .data_mut()
+                .get_mut(dst_offset..)
+                .and_then(|memory| memory.get_mut(..n))
+                .ok_or(TrapCode::MemoryOutOfBounds)?
+                .copy_from_slice(&buffer);
+        }
+        self.ip.add(1);
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub(crate) fn visit_memory_init_at(
+        &mut self,
+        memory: MemoryIdx,
+        data_segment_idx: DataSegmentIdx,
+    ) -> Result<(), TrapCode> {
+        let is_empty_data_segment = self
+            .store
+            .empty_data_segments
+            .get(data_segment_idx as usize)
+            .as_deref()
+            .copied()
+            .unwrap_or(false);
+        let (d, s, n) = self.sp.pop3();
+        let n = i32::from(n) as usize;
+        let src_offset = i32::from(s) as usize;
+        let dst_offset = i32::from(d) as usize;
+        let memory = self
+            .store
+            .memory_mut(memory)
+            .data_mut()
+            .get_mut(dst_offset..)
+            .and_then(|memory| memory.get_mut(..n))
+            .ok_or(TrapCode::MemoryOutOfBounds)?;
+        let mut memory_section = self.module.data_section;
+        if is_empty_data_segment {
+            memory_section = &[];
+        }
+        let data = memory_section
+            .get(src_offset..)
+            .and_then(|data| data.get(..n))
+            .ok_or(TrapCode::MemoryOutOfBounds)?;
+        memory.copy_from_slice(data);
+        self.ip.add(1);
+        Ok(())
+    }
+
     #[inline(always)]
     pub(crate) fn visit_data_drop(&mut self, data_segment_idx: DataSegmentIdx) {
         let empty_data_segments = &mut self.store.empty_data_segments;
@@ -1,4 +1,6 @@
-use crate::{CompiledFunc, LocalDepth, NumLocals, RwasmExecutor, UntypedValue};
+use crate::{
+    types::ShortLocalDepth, CompiledFunc, LocalDepth, NumLocals, RwasmExecutor, UntypedValue,
+};
 
 impl<'a, T> RwasmExecutor<'a, T> {
     #[inline(always)]
@@ -67,4 +69,55 @@ impl<'a, T> RwasmExecutor<'a, T> {
         self.sp.drop_n(imm as usize);
         self.ip.add(1);
     }
+
+    #[inline(always)]
+    pub(crate) fn visit_local_get_local_get(
+        &mut self,
+        first: ShortLocalDepth,
+        second: ShortLocalDepth,
+    ) {
+        let value = self.sp.nth_back(first as usize);
+        self.sp.push(value);
+        let value = self.sp.nth_back(second as usize);
+        self.sp.push(value);
+        self.ip.add(1);
+    }
+
+    #[inline(always)]
+    pub(crate) fn visit_local_get_i32_const(
+        &mut self,
+        local_depth: ShortLocalDepth,
+        untyped_value: UntypedValue,
+    ) {
+        let value = self.sp.nth_back(local_depth as usize);
+        self.sp.push(value);
+        self.sp.push(untyped_value);
+        self.ip.add(1);
+    }
+
+    #[inline(always)]
+    pub(crate) fn visit_local_set_local_get(
+        &mut self,
+        first: ShortLocalDepth,
+        second: ShortLocalDepth,
+    ) {
+        let new_value = self.sp.pop();
+        self.sp.set_nth_back(first as usize, new_value);
+        let value = self.sp.nth_back(second as usize);
+        self.sp.push(value);
+        self.ip.add(1);
+    }
+
+    #[inline(always)]
+    pub(crate) fn visit_local_set_local_set(
+        &mut self,
+        first: ShortLocalDepth,
+        second: ShortLocalDepth,
+    ) {
+        let new_value = self.sp.pop();
+        self.sp.set_nth_back(first as usize, new_value);
+        let new_value = self.sp.pop();
+        self.sp.set_nth_back(second as usize, new_value);
+        self.ip.add(1);
+    }
 }
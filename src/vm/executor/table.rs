@@ -3,12 +3,12 @@ use crate::{ElementSegmentIdx, RwasmExecutor, TableEntity, TableIdx, TrapCode};
 impl<'a, T> RwasmExecutor<'a, T> {
     #[inline(always)]
     pub(crate) fn visit_table_size(&mut self, table_idx: TableIdx) {
+        // imported tables that the host hasn't grown don't exist in the store yet
         let table_size = self
             .store
             .tables
             .get(&table_idx)
-            .expect("rwasm: unresolved table segment")
-            .size();
+            .map_or(0, TableEntity::size);
         self.sp.push_as(table_size);
         self.ip.add(1);
     }
@@ -131,7 +131,7 @@ impl<'a, T> RwasmExecutor<'a, T> {
             .copied()
             .unwrap_or(false);
 
-        let mut module_elements_section = &self.module.elem_section[..];
+        let mut module_elements_section = self.module.elem_section;
         if is_empty_segment {
             module_elements_section = &[];
         }
@@ -1,17 +1,22 @@
 use crate::{
-    intrinsic::Intrinsic, vm::instance::RwasmInstance, ExecutionEngine, ImportName, RwasmModule,
-    RwasmStore, TrapCode,
+    intrinsic::Intrinsic, vm::instance::RwasmInstance, ExecutionEngine, GlobalIdx, ImportName,
+    RwasmModule, RwasmStore, RwasmValType, TrapCode, N_HOST_GLOBAL_WORDS_OFFSET,
+    N_MAX_HOST_GLOBALS,
 };
 use alloc::vec::Vec;
 use hashbrown::HashMap;
 use rwasm_fuel_policy::SyscallFuelParams;
-use wasmparser::{FuncType, ValType};
+use tiny_keccak::{Hasher, Keccak};
+use wasmparser::{FuncType, GlobalType, MemoryType, TableType, ValType};
 
 #[derive(Debug, Default, Clone)]
 pub struct ImportLinker {
     entities: Vec<ImportLinkerEntity>,
     name_to_entity: HashMap<ImportName, usize>,
     idx_to_entity: HashMap<u32, usize>,
+    globals: HashMap<ImportName, ImportLinkerGlobal>,
+    memories: HashMap<ImportName, MemoryType>,
+    tables: HashMap<ImportName, TableType>,
 }
 
 #[derive(Debug, Clone)]
@@ -23,6 +28,35 @@ pub struct ImportLinkerEntity {
     pub intrinsic: Option<Intrinsic>,
 }
 
+/// A global declared by the import linker, modules import it by its name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImportLinkerGlobal {
+    /// The type and the mutability the imported global must be declared with.
+    pub global_type: GlobalType,
+    pub value: GlobalValueProvider,
+}
+
+/// Provides the value of a global declared by the import linker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GlobalValueProvider {
+    /// The value is baked into the compiled module, so it can be used in constant expressions.
+    /// Writes to a mutable global aren't visible to the host.
+    Const(i64),
+    /// The value lives in the store under the given host global index, the host sets it before
+    /// an execution and reads the written value back (see [`RwasmStore::set_global`]).
+    Host(u32),
+}
+
+impl ImportLinkerGlobal {
+    /// Returns the first global word of a host global, `i64` and `f64` globals take two words.
+    pub fn global_word(&self) -> Option<GlobalIdx> {
+        match self.value {
+            GlobalValueProvider::Const(_) => None,
+            GlobalValueProvider::Host(index) => Some(N_HOST_GLOBAL_WORDS_OFFSET + index * 2),
+        }
+    }
+}
+
 impl<const N: usize> From<[(ImportName, ImportLinkerEntity); N]> for ImportLinker {
     fn from(arr: [(ImportName, ImportLinkerEntity); N]) -> Self {
         let mut result = Self::default();
@@ -126,6 +160,60 @@ impl ImportLinker {
         );
     }
 
+    /// Declares a global that modules can import by `import_name`.
+    ///
+    /// Note: imported globals aren't supported by wasmtime.
+    pub fn insert_global(
+        &mut self,
+        import_name: ImportName,
+        global_type: GlobalType,
+        value: GlobalValueProvider,
+    ) {
+        if self.contains_name(&import_name) {
+            panic!("import linker name collision: {}", import_name)
+        }
+        if let GlobalValueProvider::Host(index) = value {
+            if index >= N_MAX_HOST_GLOBALS {
+                panic!("import linker host global index overflow: {}", import_name)
+            } else if self.globals.values().any(|global| global.value == value) {
+                panic!("import linker host global index collision: {}", import_name)
+            }
+        }
+        self.globals
+            .insert(import_name, ImportLinkerGlobal { global_type, value });
+    }
+
+    /// Declares a linear memory that modules can import by `import_name`, the memory is
+    /// imported with the declared limits, and the host allocates its initial pages (see
+    /// [`RwasmStore::grow_memory`]).
+    ///
+    /// Note: imported memories aren't supported by wasmtime.
+    pub fn insert_memory(&mut self, import_name: ImportName, memory_type: MemoryType) {
+        if self.contains_name(&import_name) {
+            panic!("import linker name collision: {}", import_name)
+        }
+        self.memories.insert(import_name, memory_type);
+    }
+
+    /// Declares a table that modules can import by `import_name`, e.g.,
+    /// `__indirect_function_table`. The host allocates its initial elements (see
+    /// [`RwasmStore::grow_table`]).
+    ///
+    /// Note: imported tables aren't supported by wasmtime.
+    pub fn insert_table(&mut self, import_name: ImportName, table_type: TableType) {
+        if self.contains_name(&import_name) {
+            panic!("import linker name collision: {}", import_name)
+        }
+        self.tables.insert(import_name, table_type);
+    }
+
+    fn contains_name(&self, import_name: &ImportName) -> bool {
+        self.name_to_entity.contains_key(import_name)
+            || self.globals.contains_key(import_name)
+            || self.memories.contains_key(import_name)
+            || self.tables.contains_key(import_name)
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = (ImportName, ImportLinkerEntity)> + use<'_> {
         ImportLinkerIter {
             items: self.name_to_entity.iter(),
@@ -135,7 +223,7 @@ impl ImportLinker {
 
     pub fn insert_entity(&mut self, import_name: ImportName, entity: ImportLinkerEntity) {
         let sys_func_idx = entity.sys_func_idx;
-        if self.name_to_entity.contains_key(&import_name) {
+        if self.contains_name(&import_name) {
             panic!("import linker name collision: {}", import_name)
         } else if self.idx_to_entity.contains_key(&sys_func_idx) {
             panic!("import linker `sys_func_idx` collision: {}", import_name)
@@ -157,8 +245,142 @@ impl ImportLinker {
         self.entities.get(index)
     }
 
+    pub fn resolve_global(&self, import_name: &ImportName) -> Option<&ImportLinkerGlobal> {
+        self.globals.get(import_name)
+    }
+
+    pub fn resolve_memory(&self, import_name: &ImportName) -> Option<&MemoryType> {
+        self.memories.get(import_name)
+    }
+
+    pub fn resolve_table(&self, import_name: &ImportName) -> Option<&TableType> {
+        self.tables.get(import_name)
+    }
+
     pub fn resolve_by_func_idx(&self, sys_func_idx: u32) -> Option<&ImportLinkerEntity> {
         let index = self.idx_to_entity.get(&sys_func_idx).copied()?;
         self.entities.get(index)
     }
+
+    /// Returns a keccak256 hash of all entities sorted by the import name.
+    ///
+    /// Any change of a syscall index, signature, fuel params, intrinsic, global, memory or table
+    /// changes the compiled code, so the hash is stored inside [`crate::RwasmMetadata`] to identify the linker a module
+    /// is compiled with.
+    pub fn fingerprint(&self) -> [u8; 32] {
+        let mut hasher = Keccak::v256();
+        let write_u32 = |hasher: &mut Keccak, value: u32| hasher.update(&value.to_le_bytes());
+        for import_name in self.find_symbols() {
+            let entity = self
+                .resolve_by_import_name(&import_name)
+                .unwrap_or_else(|| unreachable!("rwasm: missing import linker entity"));
+            for name in [import_name.module(), import_name.name()] {
+                write_u32(&mut hasher, name.len() as u32);
+                hasher.update(name.as_bytes());
+            }
+            write_u32(&mut hasher, entity.sys_func_idx);
+            let fuel_params: (u8, [u64; 4]) = match &entity.syscall_fuel_param {
+                SyscallFuelParams::None => (0, [0; 4]),
+                SyscallFuelParams::Const(base) => (1, [*base, 0, 0, 0]),
+                SyscallFuelParams::LinearFuel(params) => (
+                    2,
+                    [
+                        params.base_fuel as u64,
+                        params.param_index as u64,
+                        params.word_cost as u64,
+                        0,
+                    ],
+                ),
+                SyscallFuelParams::QuadraticFuel(params) => (
+                    3,
+                    [
+                        params.local_depth as u64,
+                        params.word_cost as u64,
+                        params.divisor as u64,
+                        params.fuel_denom_rate as u64,
+                    ],
+                ),
+            };
+            hasher.update(&[fuel_params.0]);
+            for value in fuel_params.1 {
+                hasher.update(&value.to_le_bytes());
+            }
+            for types in [entity.params, entity.result] {
+                write_u32(&mut hasher, types.len() as u32);
+                for ty in types {
+                    hasher.update(&[RwasmValType::from(*ty) as u8]);
+                }
+            }
+            match &entity.intrinsic {
+                None => hasher.update(&[0]),
+                Some(Intrinsic::Remove) => hasher.update(&[1]),
+                Some(Intrinsic::Replace(opcodes)) => {
+                    hasher.update(&[2]);
+                    write_u32(&mut hasher, opcodes.len() as u32);
+                    for opcode in opcodes {
+                        hasher.update(&opcode.to_aligned_record());
+                    }
+                }
+            }
+        }
+        let mut globals: Vec<_> = self.globals.iter().collect();
+        globals.sort_by_key(|(import_name, _)| *import_name);
+        for (import_name, global) in globals {
+            for name in [import_name.module(), import_name.name()] {
+                write_u32(&mut hasher, name.len() as u32);
+                hasher.update(name.as_bytes());
+            }
+            hasher.update(&[
+                RwasmValType::from(global.global_type.content_type) as u8,
+                global.global_type.mutable as u8,
+            ]);
+            match global.value {
+                GlobalValueProvider::Const(value) => {
+                    hasher.update(&[0]);
+                    hasher.update(&value.to_le_bytes());
+                }
+                GlobalValueProvider::Host(index) => {
+                    hasher.update(&[1]);
+                    write_u32(&mut hasher, index);
+                }
+            }
+        }
+        let write_limits = |hasher: &mut Keccak, initial: u64, maximum: Option<u64>| {
+            hasher.update(&initial.to_le_bytes());
+            match maximum {
+                None => hasher.update(&[0]),
+                Some(maximum) => {
+                    hasher.update(&[1]);
+                    hasher.update(&maximum.to_le_bytes());
+                }
+            }
+        };
+        let mut memories: Vec<_> = self.memories.iter().collect();
+        memories.sort_by_key(|(import_name, _)| *import_name);
+        for (import_name, memory_type) in memories {
+            for name in [import_name.module(), import_name.name()] {
+                write_u32(&mut hasher, name.len() as u32);
+                hasher.update(name.as_bytes());
+            }
+            hasher.update(&[memory_type.memory64 as u8, memory_type.shared as u8]);
+            write_limits(&mut hasher, memory_type.initial, memory_type.maximum);
+        }
+        let mut tables: Vec<_> = self.tables.iter().collect();
+        tables.sort_by_key(|(import_name, _)| *import_name);
+        for (import_name, table_type) in tables {
+            for name in [import_name.module(), import_name.name()] {
+                write_u32(&mut hasher, name.len() as u32);
+                hasher.update(name.as_bytes());
+            }
+            hasher.update(&[RwasmValType::from(table_type.element_type) as u8]);
+            write_limits(
+                &mut hasher,
+                table_type.initial as u64,
+                table_type.maximum.map(u64::from),
+            );
+        }
+        let mut hash = [0u8; 32];
+        hasher.finalize(&mut hash);
+        hash
+    }
 }
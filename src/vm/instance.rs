@@ -11,6 +11,8 @@ impl RwasmInstance {
         engine: ExecutionEngine,
         module: RwasmModule,
     ) -> Result<Self, TrapCode> {
+        // Reject modules compiled with settings that the engine doesn't accept.
+        engine.check_metadata(module.metadata_section.as_ref())?;
         // Invoke an entrypoint before (it triggers first init for memory, data, tables, etc. and also calls a start section).
         // We call entrypoint only if source PC is greater than 0, it means that the module has a start section and it's not legacy module.
         if module.source_pc > 0 {
@@ -28,6 +30,40 @@ impl RwasmInstance {
         self.engine.execute(store, &self.module, params, result)
     }
 
+    /// Calls an exported function by its name.
+    ///
+    /// The module must be compiled with an export section; params must match the original
+    /// function signature.
+    pub fn call_export<T>(
+        &self,
+        store: &mut RwasmStore<T>,
+        name: &str,
+        params: &[Value],
+        result: &mut [Value],
+    ) -> Result<(), TrapCode> {
+        let export = self
+            .module
+            .resolve_export(name)
+            .ok_or(TrapCode::UnknownExternalFunction)?;
+        let func_type = export.func_type();
+        if func_type.params().len() != params.len()
+            || func_type.results().len() != result.len()
+            || func_type
+                .params()
+                .iter()
+                .zip(params.iter())
+                .any(|(ty, param)| *ty != param.ty())
+        {
+            return Err(TrapCode::BadSignature);
+        }
+        self.engine
+            .execute_at(store, &self.module, export.func_offset, params, result)
+    }
+
+    pub fn module(&self) -> &RwasmModule {
+        &self.module
+    }
+
     pub fn resume<T>(
         &self,
         store: &mut RwasmStore<T>,
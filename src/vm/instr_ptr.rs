@@ -63,6 +63,17 @@ impl InstructionPtr {
         unsafe { *self.ptr }
     }
 
+    /// Moves the pointer from one code section to another one with the same content.
+    ///
+    /// # Safety
+    ///
+    /// The pointer must point inside the code section that starts at `from`.
+    #[inline]
+    pub(crate) unsafe fn rebase(&mut self, from: *const Opcode, to: *const Opcode) {
+        let pc = unsafe { self.ptr.offset_from(from) };
+        self.ptr = unsafe { to.offset(pc) };
+    }
+
     #[cfg(feature = "tracing")]
     pub fn is_valid(self, max: u64) -> bool {
         self.ptr as u64 <= max
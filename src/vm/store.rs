@@ -1,11 +1,13 @@
 use crate::{
-    CallStack, GlobalIdx, GlobalMemory, ImportLinker, InstructionPtr, Pages, RwasmModule,
-    SignatureIdx, StoreTr, SyscallHandler, TableEntity, TableIdx, TrapCode, UntypedValue,
-    ValueStack, N_DEFAULT_MAX_MEMORY_PAGES, N_MAX_ALLOWED_MEMORY_PAGES,
+    CallStack, GlobalIdx, GlobalMemory, GlobalVariable, ImportLinker, ImportLinkerGlobal,
+    ImportName, InstructionPtr, MemoryIdx, Pages, RwasmModule, SignatureIdx, StoreTr,
+    SyscallHandler, TableEntity, TableIdx, TagIdx, TrapCode, UntypedValue, Value, ValueStack,
+    NULL_FUNC_IDX, N_DEFAULT_MAX_MEMORY_PAGES, N_MAX_ALLOWED_MEMORY_PAGES,
 };
 use alloc::{sync::Arc, vec::Vec};
 use bitvec::{order::Lsb0, vec::BitVec};
 use hashbrown::HashMap;
+use wasmparser::ValType;
 
 /// Host-side store that holds memory, tables, globals, and host context for a rwasm instance.
 /// It also tracks fuel for metering and provides access to imported functions and syscalls.
@@ -13,8 +15,12 @@ use hashbrown::HashMap;
 pub struct RwasmStore<T: 'static> {
     /// Total amount of fuel consumed by the currently running instance.
     pub(crate) consumed_fuel: u64,
-    /// The linear memory shared by the running module and the host.
+    /// The linear memory shared by the running module and the host, its max number of pages is
+    /// the page budget shared by all memories.
     pub(crate) global_memory: GlobalMemory,
+    /// Non-default linear memories (multi-memory), created with zero pages on the first write or
+    /// grow.
+    pub(crate) memories: HashMap<MemoryIdx, GlobalMemory>,
     /// User-defined context available to host functions and syscalls.
     pub(crate) data: T,
     /// The last used signature index used for validating indirect calls.
@@ -35,6 +41,9 @@ pub struct RwasmStore<T: 'static> {
     pub(crate) resumable_context: Option<ReusableContext>,
     /// A fuel config (None stands for no limit).
     pub(crate) fuel_limit: Option<u64>,
+    /// The exception raised by a host function, thrown inside the guest once the host function
+    /// returns.
+    pub(crate) pending_exception: Option<(TagIdx, Vec<Value>)>,
     /// Execution tracer used when the `tracing` feature is enabled.
     #[cfg(feature = "tracing")]
     pub tracer: crate::Tracer,
@@ -76,6 +85,32 @@ impl<T: 'static> StoreTr<T> for RwasmStore<T> {
         Ok(())
     }
 
+    fn memory_read_at(
+        &mut self,
+        memory: MemoryIdx,
+        offset: usize,
+        buffer: &mut [u8],
+    ) -> Result<(), TrapCode> {
+        match self.memory(memory) {
+            Some(memory) => memory.read(offset, buffer),
+            // a memory that isn't created yet has no pages
+            None if offset == 0 && buffer.is_empty() => Ok(()),
+            None => Err(TrapCode::MemoryOutOfBounds),
+        }
+    }
+
+    fn memory_write_at(
+        &mut self,
+        memory: MemoryIdx,
+        offset: usize,
+        buffer: &[u8],
+    ) -> Result<(), TrapCode> {
+        if memory == 0 {
+            return self.memory_write(offset, buffer);
+        }
+        self.memory_mut(memory).write(offset, buffer)
+    }
+
     fn data_mut(&mut self) -> &mut T {
         &mut self.data
     }
@@ -123,6 +158,7 @@ impl<T: 'static> RwasmStore<T> {
         Self {
             consumed_fuel: 0,
             global_memory,
+            memories: Default::default(),
             data: context,
             #[cfg(feature = "tracing")]
             tracer: crate::Tracer::default(),
@@ -135,6 +171,7 @@ impl<T: 'static> RwasmStore<T> {
             import_linker,
             resumable_context: None,
             fuel_limit,
+            pending_exception: None,
         }
     }
 
@@ -161,15 +198,105 @@ impl<T: 'static> RwasmStore<T> {
         }
         // in case of a trap, we might have this flag remains active
         self.last_signature = None;
+        self.pending_exception = None;
+    }
+
+    /// Returns the linear memory with the given index, `None` if a non-default memory isn't
+    /// created yet. Unlike [`Self::memory_mut`], it never creates a memory.
+    pub(crate) fn memory(&self, memory: MemoryIdx) -> Option<&GlobalMemory> {
+        if memory == 0 {
+            return Some(&self.global_memory);
+        }
+        self.memories.get(&memory)
+    }
+
+    /// Returns the bytes of the linear memory with the given index, a memory that isn't created
+    /// yet is empty.
+    pub(crate) fn memory_data(&self, memory: MemoryIdx) -> &[u8] {
+        self.memory(memory).map_or(&[], GlobalMemory::data)
+    }
+
+    /// Returns the linear memory with the given index, the default memory is always present,
+    /// and other memories are created with zero pages on the first access.
+    ///
+    /// Memories must be grown with [`Self::try_grow_memory`] only, so the page budget is shared.
+    pub(crate) fn memory_mut(&mut self, memory: MemoryIdx) -> &mut GlobalMemory {
+        if memory == 0 {
+            return &mut self.global_memory;
+        }
+        let max_pages = self.global_memory.max_allowed_memory_pages;
+        self.memories
+            .entry(memory)
+            .or_insert_with(|| GlobalMemory::new(Pages::new_unchecked(0), max_pages))
+    }
+
+    /// Grows the linear memory with the given index by `delta` pages and returns the previous
+    /// number of pages.
+    ///
+    /// Memories imported from the import linker aren't allocated by the module, so the host
+    /// grows them to the declared initial size (and writes their content with
+    /// [`StoreTr::memory_write_at`]) before the instantiation, otherwise the entrypoint traps
+    /// with [`TrapCode::MemoryOutOfBounds`].
+    ///
+    /// Fails with [`TrapCode::MemoryOutOfBounds`] if the memory exceeds the max allowed pages,
+    /// all memories share them.
+    pub fn grow_memory(&mut self, memory: MemoryIdx, delta: u32) -> Result<u32, TrapCode> {
+        Pages::new(delta)
+            .and_then(|delta| self.try_grow_memory(memory, delta))
+            .map(u32::from)
+            .ok_or(TrapCode::MemoryOutOfBounds)
+    }
+
+    /// Grows the linear memory with the given index by `delta` pages and returns the previous
+    /// number of pages, `None` if the memory exceeds the max allowed pages.
+    ///
+    /// The max allowed pages of the store are shared by all memories, so a memory can grow only
+    /// by the pages that aren't used by other memories.
+    pub(crate) fn try_grow_memory(&mut self, memory: MemoryIdx, delta: Pages) -> Option<Pages> {
+        if u32::from(delta) > 0 {
+            let used_pages = self
+                .memories
+                .values()
+                .map(|memory| u32::from(memory.current_pages()))
+                .fold(
+                    u32::from(self.global_memory.current_pages()),
+                    u32::saturating_add,
+                );
+            let max_pages = u32::from(self.global_memory.max_allowed_memory_pages);
+            if used_pages.checked_add(delta.into())? > max_pages {
+                return None;
+            }
+        }
+        self.memory_mut(memory).grow(delta)
+    }
+
+    /// Grows the table with the given index by `delta` null elements and returns the previous
+    /// number of elements.
+    ///
+    /// Tables imported from the import linker aren't allocated by the module, so the host grows
+    /// them to the declared initial size before the instantiation, e.g., for the element
+    /// segments of `__indirect_function_table`, otherwise the entrypoint traps with
+    /// [`TrapCode::TableOutOfBounds`].
+    ///
+    /// Fails with [`TrapCode::TableOutOfBounds`] if the table exceeds
+    /// [`crate::N_MAX_TABLE_SIZE`] elements.
+    pub fn grow_table(&mut self, table: TableIdx, delta: u32) -> Result<u32, TrapCode> {
+        let result = self
+            .tables
+            .entry(table)
+            .or_default()
+            .grow_untyped(delta, UntypedValue::from(NULL_FUNC_IDX));
+        if result == u32::MAX {
+            return Err(TrapCode::TableOutOfBounds);
+        }
+        Ok(result)
     }
 
     pub fn fuel_consumed(&self) -> u64 {
         self.consumed_fuel
     }
 
-    /// Returns the current linear memory size in bytes.
-    ///
-    /// Note: rwasm currently supports only the default (index 0) linear memory.
+    /// Returns the current size of the default (index 0) linear memory in bytes.
     pub fn memory_size_bytes(&self) -> usize {
         self.global_memory.data().len()
     }
@@ -235,6 +362,70 @@ impl<T: 'static> RwasmStore<T> {
             .unwrap_or_default()
             .to_bits()
     }
+
+    /// Returns the value of a host global declared by the import linker.
+    ///
+    /// Fails with [`TrapCode::UnknownExternalFunction`] if the linker doesn't declare a host
+    /// global with this name.
+    pub fn global(&self, import_name: &ImportName) -> Result<Value, TrapCode> {
+        let (global, global_word) = self.resolve_host_global(import_name)?;
+        let bits = match global.global_type.content_type {
+            // the high word of a 64-bit global comes first
+            ValType::I64 | ValType::F64 => {
+                (self.global_word_bits(global_word) as u64) << 32
+                    | self.global_word_bits(global_word + 1) as u64
+            }
+            _ => self.global_word_bits(global_word) as u64,
+        };
+        GlobalVariable::new(global.global_type, bits as i64)
+            .value()
+            .ok_or(TrapCode::BadSignature)
+    }
+
+    /// Sets the value of a host global declared by the import linker, e.g., a chain parameter
+    /// before an execution.
+    ///
+    /// Fails with [`TrapCode::BadSignature`] if the value doesn't have the type of the global.
+    pub fn set_global(&mut self, import_name: &ImportName, value: Value) -> Result<(), TrapCode> {
+        let (global, global_word) = self.resolve_host_global(import_name)?;
+        if value.ty() != global.global_type.content_type {
+            return Err(TrapCode::BadSignature);
+        }
+        let bits = match value {
+            Value::I32(value) => value as u32 as u64,
+            Value::I64(value) => value as u64,
+            Value::F32(value) => value.to_bits() as u64,
+            Value::F64(value) => value.to_bits(),
+            Value::FuncRef(_) | Value::ExternRef(_) => return Err(TrapCode::BadSignature),
+        };
+        let mut set_word = |index, bits| {
+            self.global_variables
+                .insert(index, UntypedValue::from_bits(bits));
+        };
+        match value {
+            Value::I64(_) | Value::F64(_) => {
+                set_word(global_word, (bits >> 32) as u32);
+                set_word(global_word + 1, bits as u32);
+            }
+            _ => set_word(global_word, bits as u32),
+        }
+        Ok(())
+    }
+
+    fn resolve_host_global(
+        &self,
+        import_name: &ImportName,
+    ) -> Result<(ImportLinkerGlobal, GlobalIdx), TrapCode> {
+        let global = self
+            .import_linker
+            .resolve_global(import_name)
+            .copied()
+            .ok_or(TrapCode::UnknownExternalFunction)?;
+        let global_word = global
+            .global_word()
+            .ok_or(TrapCode::UnknownExternalFunction)?;
+        Ok((global, global_word))
+    }
 }
 
 #[cfg(test)]
@@ -257,4 +448,17 @@ mod tests {
             N_MAX_ALLOWED_MEMORY_PAGES
         );
     }
+
+    #[test]
+    fn reads_dont_create_memories() {
+        let mut store = RwasmStore::<()>::default();
+        store.memory_read_at(5, 0, &mut []).unwrap();
+        assert_eq!(
+            store.memory_read_at(5, 0, &mut [0u8; 1]),
+            Err(TrapCode::MemoryOutOfBounds)
+        );
+        assert!(store.memories.is_empty());
+        store.grow_memory(5, 1).unwrap();
+        assert_eq!(store.memories.len(), 1);
+    }
 }
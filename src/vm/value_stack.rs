@@ -98,6 +98,11 @@ impl ValueStack {
         sp.offset_from(self.base_ptr()) as usize
     }
 
+    /// Returns the [`ValueStackPtr`] that points after the first `len` values of the stack.
+    pub(crate) fn stack_ptr_at(&mut self, len: usize) -> ValueStackPtr {
+        self.base_ptr().into_add(len)
+    }
+
     /// Checks if the stack has overflowed based on the provided stack pointer.
     pub fn has_stack_overflowed(&mut self, sp: ValueStackPtr) -> bool {
         self.stack_len(sp) > self.maximum_len
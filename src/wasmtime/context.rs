@@ -1,6 +1,6 @@
 use crate::{
-    checked_memory_range_end, CallerTr, StoreTr, SyscallHandler, TrapCode, TypedCaller,
-    N_BYTES_PER_MEMORY_PAGE,
+    checked_memory_range_end, CallerTr, MemoryIdx, StoreTr, SyscallHandler, TagIdx, TrapCode,
+    TypedCaller, Value, N_BYTES_PER_MEMORY_PAGE,
 };
 use wasmtime::{AsContext, AsContextMut, StoreLimits};
 
@@ -60,6 +60,31 @@ impl<'a, T: 'static> StoreTr<T> for WasmtimeCaller<'a, T> {
             .map_err(|_| TrapCode::MemoryOutOfBounds)
     }
 
+    fn memory_read_at(
+        &mut self,
+        memory: MemoryIdx,
+        offset: usize,
+        buffer: &mut [u8],
+    ) -> Result<(), TrapCode> {
+        // only the exported default memory is reachable from the host
+        if memory != 0 {
+            return Err(TrapCode::MemoryOutOfBounds);
+        }
+        self.memory_read(offset, buffer)
+    }
+
+    fn memory_write_at(
+        &mut self,
+        memory: MemoryIdx,
+        offset: usize,
+        buffer: &[u8],
+    ) -> Result<(), TrapCode> {
+        if memory != 0 {
+            return Err(TrapCode::MemoryOutOfBounds);
+        }
+        self.memory_write(offset, buffer)
+    }
+
     fn data_mut(&mut self) -> &mut T {
         &mut self.caller.data_mut().data
     }
@@ -100,4 +125,9 @@ impl<'a, T: 'static> StoreTr<T> for WasmtimeCaller<'a, T> {
     }
 }
 
-impl<'a, T: 'static> CallerTr<T> for WasmtimeCaller<'a, T> {}
+impl<'a, T: 'static> CallerTr<T> for WasmtimeCaller<'a, T> {
+    fn throw_exception(&mut self, _tag: TagIdx, _payload: &[Value]) -> TrapCode {
+        // exceptions are executed by the rwasm VM only, so they can't be caught here
+        TrapCode::UncaughtException
+    }
+}
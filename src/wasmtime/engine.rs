@@ -28,6 +28,7 @@ pub fn wasmtime_engine(compilation_config: &CompilationConfig) -> Engine {
     // cfg.memory_guard_size(...);
 
     cfg.wasm_memory64(false);
+    cfg.wasm_multi_memory(true);
     cfg.memory_init_cow(false);
     cfg.cranelift_opt_level(OptLevel::Speed);
     cfg.parallel_compilation(true);
@@ -237,6 +237,31 @@ impl<T> crate::StoreTr<T> for WasmtimeExecutor<T> {
             .map_err(|_| TrapCode::MemoryOutOfBounds)
     }
 
+    fn memory_read_at(
+        &mut self,
+        memory: crate::MemoryIdx,
+        offset: usize,
+        buffer: &mut [u8],
+    ) -> Result<(), TrapCode> {
+        // only the exported default memory is reachable from the host
+        if memory != 0 {
+            return Err(TrapCode::MemoryOutOfBounds);
+        }
+        self.memory_read(offset, buffer)
+    }
+
+    fn memory_write_at(
+        &mut self,
+        memory: crate::MemoryIdx,
+        offset: usize,
+        buffer: &[u8],
+    ) -> Result<(), TrapCode> {
+        if memory != 0 {
+            return Err(TrapCode::MemoryOutOfBounds);
+        }
+        self.memory_write(offset, buffer)
+    }
+
     fn data_mut(&mut self) -> &mut T {
         &mut self.store.data_mut().data
     }
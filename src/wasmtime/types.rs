@@ -17,7 +17,7 @@ pub(super) fn map_wasmtime_error(err: wasmtime::Error) -> TrapCode {
         match trap {
             Trap::StackOverflow => TrapCode::StackOverflow,
             Trap::MemoryOutOfBounds => TrapCode::MemoryOutOfBounds,
-            Trap::HeapMisaligned => TrapCode::MemoryOutOfBounds,
+            Trap::HeapMisaligned => TrapCode::UnalignedAtomic,
             Trap::TableOutOfBounds => TrapCode::TableOutOfBounds,
             Trap::IndirectCallToNull => TrapCode::IndirectCallToNull,
             Trap::BadSignature => TrapCode::BadSignature,
@@ -0,0 +1,285 @@
+mod common;
+
+use common::{compile_verified, compile_wat, execute_with_fuel};
+use rwasm::{CompilationConfig, RwasmModule, TrapCode};
+
+fn module_wat(body: &str) -> String {
+    format!(
+        r#"
+(module
+  (memory 1 1 shared)
+  (memory $second 1)
+  (data (i32.const 0) "\01\02\03\04\05\06\07\08\f0\f1\f2\f3\f4\f5\f6\f7")
+  (func (export "main") (result i64)
+    {body}))
+"#
+    )
+}
+
+fn execute(module: &RwasmModule) -> Result<i64, TrapCode> {
+    execute_with_fuel(module, &[], 1, 1_000_000).map(|result| result[0])
+}
+
+/// Evaluates the body with and without code snippets.
+fn eval(body: &str) -> Result<i64, TrapCode> {
+    let wat = module_wat(body);
+    let [result, without_snippets] = [true, false].map(|code_snippets| {
+        let config = CompilationConfig::default()
+            .with_code_snippets(code_snippets)
+            .with_lower_atomics(true);
+        execute(&compile_verified(&wat, config))
+    });
+    assert_eq!(result, without_snippets, "{body}");
+    result
+}
+
+#[test]
+fn test_atomics_require_lower_atomics() {
+    let wat = module_wat("(i64.atomic.load (i32.const 0))");
+    assert!(compile_wat(&wat, CompilationConfig::default()).is_err());
+}
+
+#[test]
+fn test_atomic_loads_and_stores() {
+    assert_eq!(
+        eval("(i64.atomic.load (i32.const 8))"),
+        Ok(0xf7f6f5f4f3f2f1f0u64 as i64)
+    );
+    assert_eq!(
+        eval("(i64.extend_i32_u (i32.atomic.load offset=4 (i32.const 0)))"),
+        Ok(0x08070605)
+    );
+    assert_eq!(
+        eval("(i64.extend_i32_u (i32.atomic.load8_u (i32.const 9)))"),
+        Ok(0xf1)
+    );
+    assert_eq!(
+        eval("(i64.extend_i32_u (i32.atomic.load16_u (i32.const 10)))"),
+        Ok(0xf3f2)
+    );
+    assert_eq!(eval("(i64.atomic.load32_u (i32.const 12))"), Ok(0xf7f6f5f4));
+    assert_eq!(
+        eval(
+            "(i64.atomic.store (i32.const 16) (i64.const -2))
+             (i32.atomic.store8 (i32.const 16) (i32.const 0x1234))
+             (i64.atomic.store16 (i32.const 18) (i64.const 0x5678))
+             (i64.atomic.load (i32.const 16))"
+        ),
+        Ok(0xffff_ffff_5678_ff34u64 as i64)
+    );
+    assert_eq!(
+        eval(
+            "(i64.atomic.store32 (i32.const 20) (i64.const 0x1_8000_0000))
+             (i64.atomic.load (i32.const 16))"
+        ),
+        Ok(0x8000_0000_0000_0000u64 as i64)
+    );
+}
+
+#[test]
+fn test_atomic_rmw() {
+    let rmw = |op: &str, value: &str| {
+        eval(&format!(
+            "(i64.add
+               (i64.shl (i64.extend_i32_u (i32.atomic.rmw.{op} (i32.const 0) (i32.const {value}))) (i64.const 32))
+               (i64.extend_i32_u (i32.atomic.load (i32.const 0))))"
+        ))
+        .unwrap()
+    };
+    let old = 0x04030201i64 << 32;
+    assert_eq!(rmw("add", "0x10"), old + 0x04030211);
+    assert_eq!(rmw("sub", "2"), old + 0x040301ff);
+    assert_eq!(rmw("and", "0xff00"), old + 0x0200);
+    assert_eq!(rmw("or", "0x100000"), old + 0x04130201);
+    assert_eq!(rmw("xor", "-1"), old + 0xfbfcfdfe);
+    assert_eq!(rmw("xchg", "7"), old + 7);
+
+    // the narrow accesses wrap and keep the neighbour bytes
+    assert_eq!(
+        eval(
+            "(i64.add
+               (i64.shl (i64.extend_i32_u (i32.atomic.rmw8.add_u (i32.const 9) (i32.const 0x20))) (i64.const 32))
+               (i64.atomic.load (i32.const 8)))"
+        ),
+        Ok(((0xf1i64) << 32).wrapping_add(0xf7f6f5f4f3f211f0u64 as i64))
+    );
+    assert_eq!(
+        eval(
+            "(drop (i32.atomic.rmw16.sub_u (i32.const 2) (i32.const 0x0405)))
+             (i64.atomic.load (i32.const 0))"
+        ),
+        Ok(0x08070605_fffe0201)
+    );
+    assert_eq!(
+        eval(
+            "(drop (i64.atomic.rmw.add (i32.const 8) (i64.const 0x10)))
+             (drop (i64.atomic.rmw.sub (i32.const 8) (i64.const 1)))
+             (i64.atomic.load (i32.const 8))"
+        ),
+        Ok(0xf7f6f5f4f3f2f1ffu64 as i64)
+    );
+    assert_eq!(
+        eval("(i64.atomic.rmw32.xor_u (i32.const 4) (i64.const -1))"),
+        Ok(0x08070605)
+    );
+    assert_eq!(
+        eval(
+            "(drop (i64.atomic.rmw.xchg (i32.const 0) (i64.const -1)))
+             (drop (i64.atomic.rmw8.and_u (i32.const 0) (i64.const 0x0f)))
+             (drop (i64.atomic.rmw16.or_u (i32.const 6) (i64.const 0x10000)))
+             (i64.atomic.load (i32.const 0))"
+        ),
+        Ok(0xffffffff_ffffff0fu64 as i64)
+    );
+}
+
+#[test]
+fn test_atomic_cmpxchg() {
+    assert_eq!(
+        eval(
+            "(i64.extend_i32_u (i32.atomic.rmw.cmpxchg (i32.const 0) (i32.const 0x04030201) (i32.const 7)))
+             (i64.extend_i32_u (i32.atomic.load (i32.const 0)))
+             (i64.add)"
+        ),
+        Ok(0x04030201 + 7)
+    );
+    assert_eq!(
+        eval(
+            "(drop (i32.atomic.rmw.cmpxchg (i32.const 0) (i32.const 0x04030200) (i32.const 7)))
+             (i64.extend_i32_u (i32.atomic.load (i32.const 0)))"
+        ),
+        Ok(0x04030201)
+    );
+    // the expected value is wrapped to the access width
+    assert_eq!(
+        eval(
+            "(drop (i32.atomic.rmw8.cmpxchg_u (i32.const 1) (i32.const 0x102) (i32.const 0x1ff)))
+             (i64.extend_i32_u (i32.atomic.load (i32.const 0)))"
+        ),
+        Ok(0x0403ff01)
+    );
+    assert_eq!(
+        eval(
+            "(drop (i64.atomic.rmw32.cmpxchg_u (i32.const 4) (i64.const 0x1_08070605) (i64.const -1)))
+             (i64.atomic.load (i32.const 0))"
+        ),
+        Ok(0xffffffff_04030201u64 as i64)
+    );
+    // both words of an `i64` are compared
+    assert_eq!(
+        eval(
+            "(drop (i64.atomic.rmw.cmpxchg (i32.const 8) (i64.const 0xf3f2f1f0) (i64.const 0)))
+             (i64.atomic.rmw.cmpxchg (i32.const 8) (i64.const 0xf7f6f5f4f3f2f1f0) (i64.const 5))
+             (i64.atomic.load (i32.const 8))
+             (i64.add)"
+        ),
+        Ok((0xf7f6f5f4f3f2f1f0u64 as i64).wrapping_add(5))
+    );
+    assert_eq!(
+        eval(
+            "(drop (i64.atomic.rmw16.cmpxchg_u (i32.const 8) (i64.const 0xf1f0) (i64.const 0xabcd)))
+             (i64.atomic.load16_u (i32.const 8))"
+        ),
+        Ok(0xabcd)
+    );
+}
+
+#[test]
+fn test_unaligned_atomics_trap() {
+    assert_eq!(
+        eval("(i64.extend_i32_u (i32.atomic.load (i32.const 2)))"),
+        Err(TrapCode::UnalignedAtomic)
+    );
+    assert_eq!(
+        eval("(i64.atomic.load offset=4 (i32.const 0))"),
+        Err(TrapCode::UnalignedAtomic)
+    );
+    assert_eq!(
+        eval("(i64.atomic.store16 offset=1 (i32.const 0) (i64.const 0)) (i64.const 0)"),
+        Err(TrapCode::UnalignedAtomic)
+    );
+    assert_eq!(
+        eval("(i64.atomic.rmw.cmpxchg (i32.const 12) (i64.const 0) (i64.const 0))"),
+        Err(TrapCode::UnalignedAtomic)
+    );
+    assert_eq!(
+        eval("(i64.extend_i32_u (memory.atomic.notify (i32.const 1) (i32.const 1)))"),
+        Err(TrapCode::UnalignedAtomic)
+    );
+    // the effective address is aligned
+    assert_eq!(
+        eval("(i64.extend_i32_u (i32.atomic.load16_u offset=1 (i32.const 1)))"),
+        Ok(0x0403)
+    );
+    assert_eq!(
+        eval("(i64.extend_i32_u (i32.atomic.load8_u offset=3 (i32.const 0)))"),
+        Ok(4)
+    );
+    assert_eq!(
+        eval("(i64.atomic.load (i32.const 65536))"),
+        Err(TrapCode::MemoryOutOfBounds)
+    );
+}
+
+#[test]
+fn test_atomic_wait_and_notify() {
+    assert_eq!(
+        eval(
+            "(i64.extend_i32_u (memory.atomic.wait32 (i32.const 0) (i32.const 0) (i64.const -1)))"
+        ),
+        Ok(1)
+    );
+    assert_eq!(
+        eval(
+            "(i64.extend_i32_u (memory.atomic.wait32 (i32.const 0) (i32.const 0x04030201) (i64.const 100)))"
+        ),
+        Ok(2)
+    );
+    assert_eq!(
+        eval(
+            "(i64.extend_i32_u (memory.atomic.wait64 (i32.const 0) (i64.const 0x0807060504030201) (i64.const 0)))"
+        ),
+        Ok(2)
+    );
+    assert_eq!(
+        eval(
+            "(i64.extend_i32_u (memory.atomic.wait64 (i32.const 0) (i64.const 0x0807060604030201) (i64.const 0)))"
+        ),
+        Ok(1)
+    );
+    // nothing can wake the waiter
+    assert_eq!(
+        eval(
+            "(i64.extend_i32_u (memory.atomic.wait32 (i32.const 0) (i32.const 0x04030201) (i64.const -1)))"
+        ),
+        Err(TrapCode::UnreachableCodeReached)
+    );
+    assert_eq!(
+        eval(
+            "(atomic.fence)
+             (i64.extend_i32_u (memory.atomic.notify (i32.const 4) (i32.const 10)))"
+        ),
+        Ok(0)
+    );
+    assert_eq!(
+        eval("(i64.extend_i32_u (memory.atomic.notify (i32.const 65536) (i32.const 1)))"),
+        Err(TrapCode::MemoryOutOfBounds)
+    );
+}
+
+#[test]
+fn test_atomics_of_other_memories() {
+    assert_eq!(
+        eval(
+            "(drop (i64.atomic.rmw.add $second (i32.const 8) (i64.const 3)))
+             (i64.add
+               (i64.atomic.load $second (i32.const 8))
+               (i64.atomic.load (i32.const 8)))"
+        ),
+        Ok((0xf7f6f5f4f3f2f1f0u64 as i64).wrapping_add(3))
+    );
+    assert_eq!(
+        eval("(i64.extend_i32_u (i32.atomic.load $second (i32.const 2)))"),
+        Err(TrapCode::UnalignedAtomic)
+    );
+}
@@ -1,7 +1,7 @@
 use fib_example::FIB_WASM;
 use rwasm::{
-    for_each_strategy, CompilationConfig, ExecutionEngine, ImportLinker, RwasmModule, RwasmStore,
-    Value,
+    for_each_strategy, CompilationConfig, CompilationError, ExecutionEngine, ImportLinker,
+    RwasmModule, RwasmModuleBuilder, RwasmModuleView, RwasmStore, StrategyDefinition, Value,
 };
 use std::sync::Arc;
 
@@ -238,3 +238,296 @@ fn test_entrypoint_init_fails() {
     engine.entrypoint(&mut store, &module).unwrap();
     engine.execute(&mut store, &module, &[], &mut []).unwrap();
 }
+
+#[test]
+fn test_call_exports() {
+    let wasm_binary = wat::parse_str(
+        r#"
+(module
+  (func (export "add") (param i32 i32) (result i32)
+    local.get 0
+    local.get 1
+    i32.add
+  )
+  (func (export "mul64") (param i64 i64) (result i64)
+    local.get 0
+    local.get 1
+    i64.mul
+  )
+)
+"#,
+    )
+    .unwrap();
+    let config = CompilationConfig::default()
+        .with_entrypoint_name("add".into())
+        .with_allow_malformed_entrypoint_func_type(true)
+        .with_emit_export_section(true);
+    let (rwasm_module, _) = RwasmModule::compile(config.clone(), &wasm_binary).unwrap();
+    assert_eq!(rwasm_module.export_section.len(), 2);
+    // make sure exports survive the encoding
+    let rwasm_module = RwasmModule::new_verified_exact(&rwasm_module.serialize()).unwrap();
+    let mut store = RwasmStore::<()>::default();
+    let instance = ImportLinker::default()
+        .instantiate(&mut store, ExecutionEngine::new(), rwasm_module)
+        .unwrap();
+    let mut result = [Value::I32(0)];
+    instance
+        .call_export(
+            &mut store,
+            "add",
+            &[Value::I32(20), Value::I32(22)],
+            &mut result,
+        )
+        .unwrap();
+    assert_eq!(result[0].i32().unwrap(), 42);
+    let mut result = [Value::I64(0)];
+    instance
+        .call_export(
+            &mut store,
+            "mul64",
+            &[Value::I64(1 << 33), Value::I64(3)],
+            &mut result,
+        )
+        .unwrap();
+    assert_eq!(result[0].i64().unwrap(), 3 << 33);
+    assert_eq!(
+        instance.call_export(&mut store, "missing", &[], &mut []),
+        Err(rwasm::TrapCode::UnknownExternalFunction)
+    );
+    assert_eq!(
+        instance.call_export(&mut store, "add", &[Value::I32(1)], &mut [Value::I32(0)]),
+        Err(rwasm::TrapCode::BadSignature)
+    );
+
+    // the same function names must be callable through every strategy
+    for_each_strategy(
+        |strategy| {
+            let mut executor = strategy
+                .create_executor(
+                    Arc::new(ImportLinker::default()),
+                    (),
+                    rwasm::always_failing_syscall_handler,
+                    Some(1_000_000),
+                    None,
+                )
+                .unwrap();
+            let mut result = [Value::I64(0)];
+            executor.execute("mul64", &[Value::I64(7), Value::I64(6)], &mut result)?;
+            assert_eq!(result[0].i64().unwrap(), 42);
+            Ok(())
+        },
+        config,
+        &wasm_binary,
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_strategy_from_rwasm_module() {
+    let wasm_binary = wat::parse_str(
+        r#"
+(module
+  (func (export "main") (result i32)
+    i32.const 20
+    i32.const 22
+    i32.add
+  )
+)
+"#,
+    )
+    .unwrap();
+    let config = CompilationConfig::default().with_entrypoint_name("main".into());
+    let (module, _) = RwasmModule::compile(config.clone(), &wasm_binary).unwrap();
+    let execute = |strategy: StrategyDefinition| {
+        let mut executor = strategy
+            .create_executor(
+                Arc::new(ImportLinker::default()),
+                (),
+                rwasm::always_failing_syscall_handler,
+                Some(1_000_000),
+                None,
+            )
+            .unwrap();
+        let mut result = [Value::I32(0)];
+        executor.execute("main", &[], &mut result).unwrap();
+        result[0].i32().unwrap()
+    };
+    let strategy = StrategyDefinition::from_rwasm_module_as_rwasm(module.clone());
+    assert_eq!(execute(strategy), 42);
+    #[cfg(feature = "wasmtime")]
+    {
+        let strategy =
+            StrategyDefinition::from_rwasm_module_as_wasmtime(config.clone(), &module).unwrap();
+        assert_eq!(execute(strategy), 42);
+    }
+    // wasmtime compiles the hint section and doesn't use the rWasm code, so only rWasm can execute
+    // a module without the original Wasm binary
+    let module_without_hint = RwasmModuleBuilder::new(module.code_section.clone())
+        .with_source_pc(module.source_pc)
+        .build();
+    let strategy = StrategyDefinition::from_rwasm_module_as_rwasm(module_without_hint.clone());
+    assert_eq!(execute(strategy), 42);
+    #[cfg(feature = "wasmtime")]
+    assert!(matches!(
+        StrategyDefinition::from_rwasm_module_as_wasmtime(config.clone(), &module_without_hint),
+        Err(CompilationError::MissingWasmHint)
+    ));
+    let strategy = StrategyDefinition::from_rwasm_module(config, module).unwrap();
+    assert_eq!(execute(strategy), 42);
+}
+
+#[cfg(feature = "wasmtime")]
+#[test]
+fn test_strategy_from_rwasm_module_caches_by_config() {
+    use rwasm::StoreTr;
+    let wasm_binary = wat::parse_str(
+        r#"
+(module
+  (func (export "main") (result i32)
+    (local $i i32)
+    (loop $next
+      (br_if $next (i32.lt_u (local.tee $i (i32.add (local.get $i) (i32.const 1))) (i32.const 1000)))
+    )
+    local.get $i
+  )
+)
+"#,
+    )
+    .unwrap();
+    let config = CompilationConfig::default().with_entrypoint_name("main".into());
+    let (module, _) = RwasmModule::compile(config.clone(), &wasm_binary).unwrap();
+    // the same module is compiled w/o and with fuel metering, the second compilation must not
+    // reuse the cached module of the first one
+    let fuel_limit = 1_000_000;
+    for consume_fuel in [false, true] {
+        let config = config.clone().with_consume_fuel(consume_fuel);
+        let strategy = StrategyDefinition::from_rwasm_module_as_wasmtime(config, &module).unwrap();
+        let mut executor = strategy
+            .create_executor(
+                Arc::new(ImportLinker::default()),
+                (),
+                rwasm::always_failing_syscall_handler,
+                Some(fuel_limit),
+                None,
+            )
+            .unwrap();
+        let mut result = [Value::I32(0)];
+        executor.execute("main", &[], &mut result).unwrap();
+        assert_eq!(result[0].i32().unwrap(), 1000);
+        let consumed_fuel = fuel_limit - executor.remaining_fuel().unwrap();
+        assert_eq!(consumed_fuel > 0, consume_fuel);
+    }
+}
+
+#[test]
+fn test_execute_aligned_view() {
+    let wasm_binary = wat::parse_str(
+        r#"
+(module
+  (type $t (func (result i32)))
+  (memory 1)
+  (data (i32.const 0) "\07\00\00\00")
+  (table 2 funcref)
+  (elem (i32.const 0) $seven $answer)
+  (func $seven (result i32)
+    i32.const 0
+    i32.load
+  )
+  (func $answer (result i32)
+    i32.const 35
+    i32.const 0
+    call_indirect (type $t)
+    i32.add
+  )
+  (func (export "main") (result i32)
+    i32.const 1
+    call_indirect (type $t)
+  )
+)
+"#,
+    )
+    .unwrap();
+    let config = CompilationConfig::default()
+        .with_entrypoint_name("main".into())
+        .with_allow_malformed_entrypoint_func_type(true);
+    let (rwasm_module, _) = RwasmModule::compile(config, &wasm_binary).unwrap();
+    let encoded = rwasm_module.serialize_aligned();
+    // keep the binary aligned to 8 bytes like it's mapped from a file
+    let mut buffer = vec![0u64; encoded.len().div_ceil(8)];
+    let sink =
+        unsafe { std::slice::from_raw_parts_mut(buffer.as_mut_ptr() as *mut u8, buffer.len() * 8) };
+    sink[..encoded.len()].copy_from_slice(&encoded);
+    let view = RwasmModuleView::new_exact(&sink[..encoded.len()]).unwrap();
+    assert_eq!(view.code_section, rwasm_module.code_section.as_slice());
+
+    let engine = ExecutionEngine::new();
+    let mut store = RwasmStore::<()>::default();
+    engine.entrypoint_view(&mut store, view).unwrap();
+    let mut result = [Value::I32(0)];
+    engine
+        .execute_view(&mut store, view, &[], &mut result)
+        .unwrap();
+    assert_eq!(result[0].i32().unwrap(), 42);
+
+    // the aligned binary can be decoded into an owned module as well
+    let rwasm_module = RwasmModule::new_verified_exact(&encoded).unwrap();
+    let mut store = RwasmStore::<()>::default();
+    engine.entrypoint(&mut store, &rwasm_module).unwrap();
+    let mut result = [Value::I32(0)];
+    engine
+        .execute(&mut store, &rwasm_module, &[], &mut result)
+        .unwrap();
+    assert_eq!(result[0].i32().unwrap(), 42);
+}
+
+#[test]
+fn test_text_format_round_trip() {
+    let wasm_binary = wat::parse_str(
+        r#"
+(module
+  (memory 1)
+  (data (i32.const 0) "\2a\00\00\00")
+  (func $load (result i32)
+    i32.const 0
+    i32.load
+  )
+  (func (export "main") (result i32)
+    (local $i i32)
+    loop $again
+      local.get $i
+      i32.const 1
+      i32.add
+      local.tee $i
+      i32.const 10
+      i32.lt_u
+      br_if $again
+    end
+    call $load
+  )
+)
+"#,
+    )
+    .unwrap();
+    let config = CompilationConfig::default()
+        .with_entrypoint_name("main".into())
+        .with_allow_malformed_entrypoint_func_type(true)
+        .with_emit_export_section(true);
+    let (rwasm_module, _) = RwasmModule::compile(config, &wasm_binary).unwrap();
+    let text = rwasm_module.to_string();
+    let assembled = RwasmModule::from_text(&text).unwrap();
+    assert_eq!(assembled.to_string(), text);
+    assert_eq!(assembled.code_section, rwasm_module.code_section);
+    assert_eq!(assembled.data_section, rwasm_module.data_section);
+    assert_eq!(assembled.elem_section, rwasm_module.elem_section);
+    assert_eq!(assembled.source_pc, rwasm_module.source_pc);
+    assert_eq!(assembled.export_section, rwasm_module.export_section);
+
+    let engine = ExecutionEngine::new();
+    let mut store = RwasmStore::<()>::default();
+    engine.entrypoint(&mut store, &assembled).unwrap();
+    let mut result = [Value::I32(0)];
+    engine
+        .execute(&mut store, &assembled, &[], &mut result)
+        .unwrap();
+    assert_eq!(result[0].i32().unwrap(), 42);
+}
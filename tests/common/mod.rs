@@ -0,0 +1,72 @@
+//! Helpers shared by the integration tests, every test crate uses only some of them.
+#![allow(dead_code)]
+
+use rwasm::{
+    always_failing_syscall_handler, CompilationConfig, CompilationError, ExecutionEngine,
+    FuelPolicy, ImportLinker, RwasmInstance, RwasmModule, RwasmStore, StoreTr, TrapCode, Value,
+};
+use std::sync::Arc;
+
+/// Compiles the WAT module with the `main` export as the entrypoint.
+pub fn compile_wat(wat: &str, config: CompilationConfig) -> Result<RwasmModule, CompilationError> {
+    let wasm = wat::parse_str(wat).unwrap();
+    let config = config.with_entrypoint_name("main".into());
+    RwasmModule::compile(config, &wasm).map(|(module, _)| module)
+}
+
+/// Compiles the WAT module like [`compile_wat`] and checks that it passes the verifier with the
+/// required fuel policy, system calls are resolved with the import linker of the config.
+pub fn compile_verified(wat: &str, config: CompilationConfig) -> RwasmModule {
+    let import_linker = config.import_linker.clone();
+    let module = compile_wat(wat, config).unwrap();
+    match import_linker {
+        Some(import_linker) => module
+            .verify_with_import_linker_and_policy(&import_linker, FuelPolicy::Required)
+            .unwrap(),
+        None => module.verify_with_policy(FuelPolicy::Required).unwrap(),
+    }
+    module
+}
+
+/// Instantiates the module in the store and returns the `i64` results of the entrypoint.
+pub fn execute_in<T>(
+    store: &mut RwasmStore<T>,
+    module: &RwasmModule,
+    params: &[Value],
+    results: usize,
+) -> Result<Vec<i64>, TrapCode> {
+    let instance = RwasmInstance::new(store, ExecutionEngine::new(), module.clone())?;
+    let mut result = vec![Value::I64(0); results];
+    instance.execute(store, params, &mut result)?;
+    Ok(result.iter().map(|value| value.i64().unwrap()).collect())
+}
+
+/// Executes the module like [`execute_in`] in a new store with the given fuel.
+pub fn execute_with_fuel(
+    module: &RwasmModule,
+    params: &[Value],
+    results: usize,
+    fuel: u64,
+) -> Result<Vec<i64>, TrapCode> {
+    let mut store = RwasmStore::<()>::default();
+    store.reset_fuel(fuel);
+    execute_in(&mut store, module, params, results)
+}
+
+/// Returns the config that resolves the imports with the linker, fuel metering is disabled.
+pub fn linker_config(import_linker: Arc<ImportLinker>) -> CompilationConfig {
+    CompilationConfig::default()
+        .with_import_linker(import_linker)
+        .with_consume_fuel(false)
+}
+
+/// Creates a store without a fuel limit that resolves the imports with the linker.
+pub fn linker_store(import_linker: Arc<ImportLinker>) -> RwasmStore<()> {
+    RwasmStore::new(
+        import_linker,
+        (),
+        always_failing_syscall_handler,
+        None,
+        None,
+    )
+}
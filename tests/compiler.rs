@@ -1,4 +1,14 @@
-use rwasm::{CompilationConfig, CompilationError, ConstructorParams, RwasmModule};
+use hex_literal::hex;
+use rwasm::{
+    CompilationConfig, CompilationError, CompiledFuncKind, ConstructorParams, DefaultFuelCostModel,
+    ExecutionEngine, FuelCostModel, FuelMeteringMode, FuelPolicy, ImportLinker, ImportName, Opcode,
+    OptimizationLevel, RwasmInstance, RwasmMetadata, RwasmModule, RwasmModuleVerificationError,
+    RwasmStore, StateRouterConfig, StoreTr, TrapCode, ValType, Value,
+};
+use rwasm_fuel_policy::{
+    LinearFuelParams, SyscallFuelParams, MEMORY_BYTES_PER_FUEL_LOG2, TABLE_ELEMS_PER_FUEL_LOG2,
+};
+use std::sync::Arc;
 
 fn test_compilation(wat_str: &str) -> Result<(RwasmModule, ConstructorParams), CompilationError> {
     let wasm = wat::parse_str(wat_str).expect("valid WAT");
@@ -48,11 +58,50 @@ fn test_memory_init_missing_data_segment_returns_error() {
     let config = CompilationConfig::default().with_entrypoint_name("entry".into());
     let err = RwasmModule::compile(config, WASM).expect_err("module must be rejected");
     assert!(matches!(
-        err,
+        err.without_context(),
         CompilationError::MemoryOutOfBounds | CompilationError::MalformedWasmBinary(_)
     ));
 }
 
+#[test]
+fn test_compilation_error_has_function_context() {
+    let wasm = wat::parse_str(
+        r#"
+        (module
+          (func $ok)
+          (func $bad (export "entry") (result i32)
+            (i32.add (i32.const 1))))
+        "#,
+    )
+    .unwrap();
+    let config = CompilationConfig::default().with_entrypoint_name("entry".into());
+    let err = RwasmModule::compile(config, &wasm).expect_err("module must be rejected");
+    assert!(matches!(
+        err.without_context(),
+        CompilationError::MalformedWasmBinary(_)
+    ));
+    let context = err.context().expect("error must have a context");
+    assert_eq!(context.func_idx, 1);
+    assert_eq!(context.func_name.as_deref(), Some("bad"));
+    assert_eq!(context.operator, Some("I32Add"));
+    // the only `i32.add` opcode of the binary
+    let offset = wasm.iter().position(|byte| *byte == 0x6a).unwrap();
+    assert_eq!(context.offset, Some(offset));
+    let message = err.to_string();
+    assert!(
+        message.ends_with(&format!(
+            "(in function #1 `bad`, I32Add at offset {offset:#x})"
+        )),
+        "{message}"
+    );
+
+    // errors outside of function bodies have no context
+    let wasm = wat::parse_str(r#"(module (func (export "entry")) (start 0))"#).unwrap();
+    let config = CompilationConfig::default().with_entrypoint_name("entry".into());
+    let err = RwasmModule::compile(config, &wasm).expect_err("module must be rejected");
+    assert!(err.context().is_none());
+}
+
 #[test]
 fn test_i64_type_split_into_2_x_i32_bug() {
     const WAT: &str = r#"
@@ -166,3 +215,647 @@ fn test_max_locals_single_func() {
         output_size as f64 / 1_000_000.0
     );
 }
+
+#[test]
+fn test_debug_section_maps_pc_to_wasm_offset() {
+    const WAT: &str = r#"
+        (module
+          (func $fail (param i32)
+            local.get 0
+            if
+              unreachable
+            end)
+          (func $main (export "main")
+            i32.const 1
+            call $fail))
+    "#;
+    let wasm = wat::parse_str(WAT).expect("valid WAT");
+    let config = CompilationConfig::default()
+        .with_entrypoint_name("main".into())
+        .with_emit_debug_section(true);
+    let (module, _) = RwasmModule::compile(config, &wasm).unwrap();
+    module.verify().unwrap();
+    let trap_pc = module
+        .code_section
+        .iter()
+        .position(|opcode| *opcode == Opcode::Unreachable)
+        .unwrap() as u32;
+    let location = module.resolve_source_location(trap_pc).unwrap();
+    assert_eq!(location.func_idx, 0);
+    assert_eq!(location.func_name, Some("fail"));
+    // the offset points to the `unreachable` operator inside the original binary
+    assert_eq!(module.hint_section[location.wasm_offset as usize], 0x00);
+    // the entrypoint isn't compiled from Wasm functions
+    assert_eq!(module.resolve_source_location(0), None);
+    // the debug section survives encoding
+    let module2 = RwasmModule::new_checked_exact(&module.serialize()).unwrap();
+    assert_eq!(module2.resolve_source_location(trap_pc), Some(location));
+
+    // the debug section is disabled by default
+    let config = CompilationConfig::default().with_entrypoint_name("main".into());
+    let (module, _) = RwasmModule::compile(config, &wasm).unwrap();
+    assert!(module.debug_section.is_empty());
+    assert_eq!(module.resolve_source_location(trap_pc), None);
+}
+
+#[test]
+fn test_compiled_module_passes_stack_verification() {
+    const WAT: &str = r#"
+        (module
+          (func $write (import "env" "write") (param i32 i32))
+          (type $binop (func (param i64 i64) (result i64)))
+          (table 2 funcref)
+          (elem (i32.const 0) $shl $add)
+          (memory 1)
+          (func $shl (type $binop)
+            local.get 0
+            local.get 1
+            i64.shl)
+          (func $add (type $binop)
+            local.get 0
+            local.get 1
+            i64.add)
+          (func $main (export "main") (param i32) (result i64)
+            i32.const 0
+            i32.const 32
+            call $write
+            block (result i64)
+              i64.const 3
+              local.get 0
+              br_table 0 0
+            end
+            i64.const 5
+            local.get 0
+            call_indirect (type $binop)))
+    "#;
+    let wasm = wat::parse_str(WAT).expect("valid WAT");
+    let mut import_linker = ImportLinker::default();
+    import_linker.insert_function(
+        ImportName::new("env", "write"),
+        0x01,
+        SyscallFuelParams::LinearFuel(LinearFuelParams {
+            base_fuel: 10,
+            param_index: 1,
+            word_cost: 3,
+        }),
+        &[ValType::I32, ValType::I32],
+        &[],
+    );
+    let import_linker = Arc::new(import_linker);
+    let config = CompilationConfig::default()
+        .with_entrypoint_name("main".into())
+        .with_builtins_consume_fuel(true)
+        .with_import_linker(import_linker.clone());
+    let (module, _) = RwasmModule::compile(config, &wasm).unwrap();
+    assert!(matches!(
+        module.verify(),
+        Err(RwasmModuleVerificationError::UnknownSyscallEffect { .. })
+    ));
+    module.verify_with_import_linker(&import_linker).unwrap();
+}
+
+#[test]
+fn test_stack_checks_reserve_emitted_code_heights() {
+    const WAT: &str = r#"
+        (module
+          (func $write (import "env" "write") (param i32 i32))
+          (func (export "main")
+            i32.const 0
+            i32.const 32
+            call $write)
+          (func (export "shl")
+            i64.const 1
+            i64.const 2
+            i64.shl
+            drop))
+    "#;
+    let wasm = wat::parse_str(WAT).expect("valid WAT");
+    let mut import_linker = ImportLinker::default();
+    import_linker.insert_function(
+        ImportName::new("env", "write"),
+        0x01,
+        SyscallFuelParams::LinearFuel(LinearFuelParams {
+            base_fuel: 10,
+            param_index: 1,
+            word_cost: 3,
+        }),
+        &[ValType::I32, ValType::I32],
+        &[],
+    );
+    let config = CompilationConfig::default()
+        .with_state_router(StateRouterConfig {
+            states: Box::new([("main".into(), 1), ("shl".into(), 2)]),
+            opcode: Some(Opcode::I32Const(1.into())),
+        })
+        .with_builtins_consume_fuel(true)
+        .with_import_linker(Arc::new(import_linker));
+    let (module, _) = RwasmModule::compile(config, &wasm).unwrap();
+    let code = &module.code_section;
+    // returns the height reserved by the last stack check before the first matching opcode
+    let reserved_before = |f: fn(&Opcode) -> bool| {
+        let pos = code.iter().position(f).unwrap();
+        code[..pos]
+            .iter()
+            .rev()
+            .find_map(|opcode| match opcode {
+                Opcode::StackCheck(height) => Some(*height),
+                _ => None,
+            })
+            .unwrap()
+    };
+    // the state router pushes the state, copies it and compares it with a state value
+    assert_eq!(reserved_before(|opcode| *opcode == Opcode::I32Eq), 3);
+    // the linear fuel of a syscall is calculated with two words on top of the syscall params
+    assert_eq!(
+        reserved_before(|opcode| *opcode == Opcode::ConsumeFuelStack),
+        2
+    );
+    // i64.shl copies both halves of the shifted value on top of its scratch words
+    assert_eq!(reserved_before(|opcode| *opcode == Opcode::I32Shl), 10);
+    // the reserved heights are a part of the code, so the hash changes with them, update it only
+    // for an intentional (breaking) change of the compiler output
+    assert_eq!(
+        module.code_hash(),
+        hex!("a6f618485b29f50ce9a40a081431313969c3df67735b727833031175e170a5b5")
+    );
+}
+
+#[test]
+fn test_fuel_policy_requires_compiled_fuel_instrumentation() {
+    const WAT: &str = r#"
+        (module
+          (memory 1)
+          (func $main (export "main") (param i32 i64) (result i64)
+            (loop
+              local.get 0
+              i32.const 1
+              i32.sub
+              local.tee 0
+              br_if 0)
+            i32.const 0
+            i32.const 0
+            local.get 0
+            memory.fill
+            local.get 1
+            i64.const 7
+            i64.rem_u))
+    "#;
+    let wasm = wat::parse_str(WAT).expect("valid WAT");
+    let compile = |config: CompilationConfig| {
+        let (module, _) =
+            RwasmModule::compile(config.with_entrypoint_name("main".into()), &wasm).unwrap();
+        module.verify_with_policy(FuelPolicy::Required)
+    };
+    compile(CompilationConfig::default()).unwrap();
+    compile(CompilationConfig::default().with_code_snippets(false)).unwrap();
+    assert!(matches!(
+        compile(CompilationConfig::default().with_consume_fuel(false)),
+        Err(RwasmModuleVerificationError::UnmeteredFunctionEntry { .. })
+    ));
+    assert!(matches!(
+        compile(CompilationConfig::default().with_consume_fuel_for_bulk_ops(false)),
+        Err(RwasmModuleVerificationError::MissingBulkFuelCheck { .. })
+    ));
+}
+
+#[test]
+fn test_metadata_section_records_compilation_config() {
+    const WAT: &str = r#"
+        (module
+          (func $write (import "env" "write") (param i32))
+          (func $main (export "main") (param i32) (result i32)
+            local.get 0
+            call $write
+            local.get 0
+            i32.const 1
+            i32.add))
+    "#;
+    let wasm = wat::parse_str(WAT).expect("valid WAT");
+    let import_linker = |fuel: SyscallFuelParams| {
+        let mut import_linker = ImportLinker::default();
+        import_linker.insert_function(
+            ImportName::new("env", "write"),
+            0x01,
+            fuel,
+            &[ValType::I32],
+            &[],
+        );
+        Arc::new(import_linker)
+    };
+    let compile = |config: CompilationConfig| {
+        let config = config
+            .with_entrypoint_name("main".into())
+            .with_emit_metadata_section(true)
+            .with_import_linker(import_linker(SyscallFuelParams::Const(10)));
+        RwasmModule::compile(config, &wasm).unwrap().0
+    };
+    let module = compile(CompilationConfig::default());
+    let metadata = module.metadata_section.unwrap();
+    assert_eq!(
+        metadata,
+        RwasmMetadata {
+            consume_fuel: true,
+            consume_fuel_for_bulk_ops: true,
+            consume_fuel_for_params_and_locals: true,
+            builtins_consume_fuel: false,
+            code_snippets: true,
+            max_allowed_memory_pages: CompilationConfig::default().max_allowed_memory_pages,
+            uses_fpu: false,
+            import_linker_hash: Some(import_linker(SyscallFuelParams::Const(10)).fingerprint()),
+            fuel_cost_model_hash: DefaultFuelCostModel.fingerprint(),
+            memory_bytes_per_fuel_log2: MEMORY_BYTES_PER_FUEL_LOG2,
+            table_elems_per_fuel_log2: TABLE_ELEMS_PER_FUEL_LOG2,
+            fuel_metering_mode: FuelMeteringMode::Eager,
+        }
+    );
+    // a different syscall fuel changes the import linker fingerprint
+    assert_ne!(
+        import_linker(SyscallFuelParams::Const(10)).fingerprint(),
+        import_linker(SyscallFuelParams::Const(11)).fingerprint()
+    );
+    // the metadata section survives all encodings
+    for encoded in [
+        module.serialize(),
+        module.serialize_aligned(),
+        module.serialize_compact(),
+    ] {
+        let module2 = RwasmModule::new_checked_exact(&encoded).unwrap();
+        assert_eq!(module2, module);
+    }
+
+    // the engine accepts only modules with the required metadata
+    let engine = ExecutionEngine::new().with_required_metadata(metadata);
+    let mut store = RwasmStore::<()>::default();
+    RwasmInstance::new(&mut store, engine.clone(), module).unwrap();
+    let unmetered_module = compile(CompilationConfig::default().with_consume_fuel(false));
+    assert_ne!(
+        unmetered_module.metadata_section.unwrap().fingerprint(),
+        metadata.fingerprint()
+    );
+    assert_eq!(
+        RwasmInstance::new(&mut store, engine.clone(), unmetered_module).err(),
+        Some(TrapCode::IncompatibleModule)
+    );
+    let legacy_module = RwasmModule::compile(
+        CompilationConfig::default()
+            .with_entrypoint_name("main".into())
+            .with_import_linker(import_linker(SyscallFuelParams::Const(10))),
+        &wasm,
+    )
+    .unwrap()
+    .0;
+    assert_eq!(legacy_module.metadata_section, None);
+    let mut result = [Value::I32(0)];
+    assert_eq!(
+        engine.execute(&mut store, &legacy_module, &[Value::I32(0)], &mut result),
+        Err(TrapCode::IncompatibleModule)
+    );
+}
+
+#[test]
+fn test_optimizer_keeps_results_and_fuel() {
+    const WAT: &str = r#"
+        (module
+          (type $binop (func (param i64 i64) (result i64)))
+          (table 2 funcref)
+          (elem (i32.const 0) $shl $rem)
+          (memory 1)
+          (func $shl (type $binop)
+            local.get 0
+            local.get 1
+            i64.shl)
+          (func $rem (type $binop)
+            local.get 0
+            local.get 1
+            i64.rem_u)
+          (func $main (export "main") (param i32) (result i64) (local i32)
+            i32.const 2
+            i32.const 3
+            i32.add
+            i32.const 4
+            i32.mul
+            local.set 1
+            local.get 1
+            drop
+            i32.const 7
+            drop
+            i32.const 0
+            i32.const 0
+            local.get 1
+            memory.fill
+            block (result i64)
+              i64.const 3
+              local.get 0
+              br_table 0 0
+            end
+            i64.const 100
+            local.get 0
+            i32.const 1
+            i32.and
+            call_indirect (type $binop)
+            i64.const 1
+            i64.add))
+    "#;
+    let wasm = wat::parse_str(WAT).expect("valid WAT");
+    let compile = |config: CompilationConfig| {
+        let config = config
+            .with_entrypoint_name("main".into())
+            .with_emit_export_section(true);
+        RwasmModule::compile(config, &wasm).unwrap().0
+    };
+    let module = compile(CompilationConfig::default());
+    let optimized =
+        compile(CompilationConfig::default().with_optimize(OptimizationLevel::Peephole));
+    assert!(optimized.code_section.len() < module.code_section.len());
+    optimized.verify().unwrap();
+    optimized.verify_with_policy(FuelPolicy::Required).unwrap();
+
+    let run = |module: &RwasmModule, param: i32| {
+        let mut store = RwasmStore::<()>::default();
+        store.reset_fuel(1_000_000);
+        let instance =
+            RwasmInstance::new(&mut store, ExecutionEngine::new(), module.clone()).unwrap();
+        let mut result = [Value::I64(0)];
+        instance
+            .execute(&mut store, &[Value::I32(param)], &mut result)
+            .unwrap();
+        (result[0].i64().unwrap(), store.remaining_fuel())
+    };
+    for param in 0..4 {
+        assert_eq!(run(&optimized, param), run(&module, param));
+    }
+}
+
+#[test]
+fn test_fused_opcodes_keep_results_and_fuel() {
+    const WAT: &str = r#"
+        (module
+          (memory 1)
+          (data (i32.const 0) "\01\00\00\00\02\00\00\00\03\00\00\00\04\00\00\00")
+          (func $main (export "main") (param i32) (result i32) (local i32 i32 i32)
+            block
+              loop
+                local.get 1
+                local.get 0
+                i32.ge_u
+                br_if 1
+                local.get 2
+                local.get 1
+                i32.const 4
+                i32.mul
+                i32.load
+                i32.add
+                local.get 1
+                i32.add
+                i32.const 1
+                i32.add
+                local.set 2
+                local.get 1
+                i32.const 1
+                i32.add
+                local.set 1
+                br 0
+              end
+            end
+            local.get 2
+            local.get 3
+            local.get 3
+            drop
+            drop
+            i32.eqz
+            if (result i32)
+              local.get 2
+            else
+              i32.const -1
+            end))
+    "#;
+    let wasm = wat::parse_str(WAT).expect("valid WAT");
+    let compile = |config: CompilationConfig| {
+        let config = config
+            .with_entrypoint_name("main".into())
+            .with_emit_export_section(true);
+        RwasmModule::compile(config, &wasm).unwrap().0
+    };
+    let module = compile(CompilationConfig::default());
+    let fused = compile(CompilationConfig::default().with_fused_opcodes(true));
+    assert!(fused.code_section.len() < module.code_section.len());
+    assert!(fused
+        .code_section
+        .iter()
+        .any(|opcode| matches!(opcode, Opcode::LocalGetLocalGet(_, _))));
+    fused.verify().unwrap();
+    fused.verify_with_policy(FuelPolicy::Required).unwrap();
+    for bytes in [
+        fused.serialize(),
+        fused.serialize_aligned(),
+        fused.serialize_compact(),
+    ] {
+        assert_eq!(RwasmModule::new(&bytes).0.code_section, fused.code_section);
+    }
+    let assembled = RwasmModule::from_text(&fused.to_string()).unwrap();
+    assert_eq!(assembled.code_section, fused.code_section);
+
+    let run = |module: &RwasmModule, param: i32| {
+        let mut store = RwasmStore::<()>::default();
+        store.reset_fuel(1_000_000);
+        let instance =
+            RwasmInstance::new(&mut store, ExecutionEngine::new(), module.clone()).unwrap();
+        let mut result = [Value::I32(0)];
+        instance
+            .execute(&mut store, &[Value::I32(param)], &mut result)
+            .unwrap();
+        (result[0].i32().unwrap(), store.remaining_fuel())
+    };
+    for param in 0..5 {
+        assert_eq!(run(&fused, param), run(&module, param));
+    }
+}
+
+#[test]
+fn test_dead_functions_are_eliminated() {
+    const WAT: &str = r#"
+        (module
+          (func $write (import "env" "write") (param i32))
+          (type $unop (func (param i64) (result i64)))
+          (table 1 funcref)
+          (elem (i32.const 0) $indirect)
+          (func $unused (param i64) (result i64)
+            local.get 0
+            i64.const 3
+            i64.div_s
+            call $unused_too)
+          (func $unused_too (param i64) (result i64)
+            i32.const 1
+            call $write
+            local.get 0)
+          (func $indirect (type $unop)
+            local.get 0
+            i64.const 2
+            i64.mul)
+          (func $fail (param i32)
+            local.get 0
+            if
+              unreachable
+            end)
+          (func $main (export "main") (param i64) (result i64)
+            i32.const 0
+            call $fail
+            local.get 0
+            i32.const 0
+            call_indirect (type $unop)))
+    "#;
+    let wasm = wat::parse_str(WAT).expect("valid WAT");
+    let mut import_linker = ImportLinker::default();
+    import_linker.insert_function(
+        ImportName::new("env", "write"),
+        0x01,
+        SyscallFuelParams::Const(10),
+        &[ValType::I32],
+        &[],
+    );
+    let compile = |config: CompilationConfig| {
+        let config = config
+            .with_entrypoint_name("main".into())
+            .with_emit_debug_section(true)
+            .with_import_linker(Arc::new(import_linker.clone()));
+        RwasmModule::compile(config, &wasm).unwrap().0
+    };
+    let module = compile(CompilationConfig::default());
+    let eliminated = compile(CompilationConfig::default().with_eliminate_dead_functions(true));
+    assert!(eliminated.code_section.len() < module.code_section.len());
+    // the import trampoline and the division snippet are used only by unused functions
+    assert!(!eliminated
+        .code_section
+        .iter()
+        .any(|opcode| matches!(opcode, Opcode::Call(_))));
+    assert_eq!(
+        eliminated
+            .code_section
+            .iter()
+            .filter(|opcode| matches!(opcode, Opcode::CallInternal(_)))
+            .count(),
+        2
+    );
+    eliminated.verify().unwrap();
+    eliminated.verify_with_policy(FuelPolicy::Required).unwrap();
+    // the source map is relocated and doesn't reference removed functions
+    let trap_pc = eliminated
+        .code_section
+        .iter()
+        .position(|opcode| *opcode == Opcode::Unreachable)
+        .unwrap() as u32;
+    let location = eliminated.resolve_source_location(trap_pc).unwrap();
+    assert_eq!(location.func_name, Some("fail"));
+    assert!(eliminated
+        .debug_section
+        .func_names
+        .iter()
+        .all(|func_name| !func_name.name.starts_with("unused")));
+
+    let run = |module: RwasmModule| {
+        let mut store = RwasmStore::<()>::default();
+        store.reset_fuel(1_000_000);
+        let instance = RwasmInstance::new(&mut store, ExecutionEngine::new(), module).unwrap();
+        let mut result = [Value::I64(0)];
+        instance
+            .execute(&mut store, &[Value::I64(21)], &mut result)
+            .unwrap();
+        (result[0].i64().unwrap(), store.remaining_fuel())
+    };
+    assert_eq!(run(eliminated), run(module));
+}
+
+#[test]
+fn test_compilation_report() {
+    const WAT: &str = r#"
+        (module
+          (memory 1)
+          (data (i32.const 0) "abcd")
+          (table 2 funcref)
+          (elem (i32.const 0) $add)
+          (func $add (param i64 i64) (result i64)
+            (i64.add (local.get 0) (local.get 1)))
+          (func $dead (result i32)
+            (i32.const 7))
+          (func (export "main") (result i64)
+            (i64.add
+              (call $add (i64.const 40) (i64.const 2))
+              (i64.const 0))))
+        "#;
+    let wasm = wat::parse_str(WAT).expect("valid WAT");
+    for config in [
+        CompilationConfig::default(),
+        CompilationConfig::default()
+            .with_optimize(OptimizationLevel::Peephole)
+            .with_fused_opcodes(true),
+    ] {
+        let config = config
+            .with_entrypoint_name("main".into())
+            .with_eliminate_dead_functions(true);
+        let (module, _, report) = RwasmModule::compile_with_report(config.clone(), &wasm).unwrap();
+        assert_eq!(RwasmModule::compile(config, &wasm).unwrap().0, module);
+
+        let kinds = report
+            .funcs
+            .iter()
+            .map(|func| (func.func_idx, func.kind))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                (0, CompiledFuncKind::Wasm),
+                (2, CompiledFuncKind::Wasm),
+                (3, CompiledFuncKind::Snippet),
+            ]
+        );
+        for func in &report.funcs {
+            let code = &module.code_section[func.offset as usize..][..func.stats.len as usize];
+            assert!(matches!(
+                (func.kind, code[0]),
+                (CompiledFuncKind::Wasm, Opcode::SignatureCheck(_))
+                    | (CompiledFuncKind::Snippet, Opcode::StackCheck(_))
+            ));
+        }
+        // both `i64.add` are lowered to calls of the same snippet
+        assert_eq!(report.func(0).unwrap().stats.snippet_calls, 1);
+        assert_eq!(report.func(2).unwrap().stats.snippet_calls, 1);
+        assert_eq!(report.total.snippet_calls, 2);
+        assert_eq!(
+            report
+                .func(2)
+                .unwrap()
+                .stats
+                .opcode_counts
+                .get("CallInternal"),
+            Some(&2)
+        );
+
+        let total_len =
+            report.entrypoint.len + report.funcs.iter().map(|func| func.stats.len).sum::<u32>();
+        assert_eq!(total_len, report.total.len);
+        assert_eq!(report.total.len as usize, module.code_section.len());
+        assert_eq!(
+            report.total.opcode_counts.values().sum::<u32>(),
+            report.total.len
+        );
+        let consume_fuel = module
+            .code_section
+            .iter()
+            .filter_map(|opcode| match opcode {
+                Opcode::ConsumeFuel(fuel) => Some(*fuel as u64),
+                _ => None,
+            })
+            .sum::<u64>();
+        assert!(consume_fuel > 0);
+        assert_eq!(report.total.consume_fuel, consume_fuel);
+        let max_stack_check = report
+            .funcs
+            .iter()
+            .map(|func| func.stats.max_stack_check)
+            .chain([report.entrypoint.max_stack_check])
+            .max();
+        assert_eq!(Some(report.total.max_stack_check), max_stack_check);
+        assert_eq!(report.data_section_len, module.data_section.len());
+        assert_eq!(report.elem_section_len, module.elem_section.len());
+    }
+}
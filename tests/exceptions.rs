@@ -0,0 +1,224 @@
+mod common;
+
+use common::{compile_verified, execute_in, execute_with_fuel};
+use rwasm::{
+    CallerTr, CompilationConfig, ImportLinker, ImportName, OptimizationLevel, RwasmModule,
+    RwasmStore, SyscallFuelParams, TrapCode, TypedCaller, Value,
+};
+use std::sync::Arc;
+use wasmparser::ValType;
+
+fn execute(module: &RwasmModule, param: i32) -> Result<i64, TrapCode> {
+    execute_with_fuel(module, &[Value::I32(param)], 1, 1_000_000).map(|result| result[0])
+}
+
+#[test]
+fn test_try_catch() {
+    let module = compile_verified(
+        r#"
+(module
+  (tag $e32 (param i32))
+  (tag $e64 (param i64 i32))
+  (tag $empty)
+  (func $thrower (param i32)
+    (if (i32.eq (local.get 0) (i32.const 1)) (then (throw $e32 (i32.const 7))))
+    (if (i32.eq (local.get 0) (i32.const 2)) (then (throw $e64 (i64.const 0x1_0000_0000) (i32.const 3))))
+    (if (i32.eq (local.get 0) (i32.const 3)) (then (throw $empty))))
+  (func (export "main") (param i32) (result i64)
+    ;; the values below the `try` block are kept
+    (i64.const 100)
+    try (result i64)
+      (call $thrower (local.get 0))
+      (i64.const -1)
+    catch $e32
+      i64.extend_i32_u
+    catch $e64
+      i64.extend_i32_u
+      i64.add
+    catch_all
+      (i64.const 42)
+    end
+    i64.add))
+"#,
+        CompilationConfig::default(),
+    );
+    assert_eq!(execute(&module, 0), Ok(99));
+    assert_eq!(execute(&module, 1), Ok(107));
+    assert_eq!(execute(&module, 2), Ok(0x1_0000_0067));
+    assert_eq!(execute(&module, 3), Ok(142));
+}
+
+#[test]
+fn test_optimized_try_catch() {
+    const WAT: &str = r#"
+(module
+  (tag $e (param i32))
+  (func (export "main") (param i32) (result i64) (local i32)
+    (local.set 1 (local.get 0))
+    try (result i64)
+      (if (local.get 1) (then (throw $e (i32.add (local.get 1) (i32.const 1)))))
+      (i64.const 0)
+    catch $e
+      (local.set 1)
+      (i64.extend_i32_u (i32.add (local.get 1) (local.get 1)))
+    end))
+"#;
+    let module = compile_verified(WAT, CompilationConfig::default());
+    let optimized = compile_verified(
+        WAT,
+        CompilationConfig::default()
+            .with_optimize(OptimizationLevel::Peephole)
+            .with_fused_opcodes(true),
+    );
+    for param in 0..3 {
+        assert_eq!(execute(&optimized, param), execute(&module, param));
+    }
+    assert_eq!(execute(&optimized, 2), Ok(6));
+}
+
+#[test]
+fn test_rethrow_and_delegate() {
+    let module = compile_verified(
+        r#"
+(module
+  (tag $e (param i32))
+  (func $nested (param i32) (result i32)
+    try (result i32)
+      try (result i32)
+        try
+          (throw $e (local.get 0))
+        ;; skips the handlers of the middle `try` block
+        delegate 1
+        (i32.const 0)
+      catch $e
+        drop
+        (i32.const -1)
+      end
+    catch $e
+      (i32.add (i32.const 10))
+    end)
+  (func (export "main") (param i32) (result i64)
+    try (result i64)
+      try
+        (throw $e (call $nested (local.get 0)))
+      catch $e
+        drop
+        ;; the caught exception is thrown again with the original payload
+        rethrow 0
+      end
+      (i64.const 0)
+    catch $e
+      i64.extend_i32_u
+    end))
+"#,
+        CompilationConfig::default(),
+    );
+    assert_eq!(execute(&module, 5), Ok(15));
+}
+
+#[test]
+fn test_branches_leave_try_blocks() {
+    let module = compile_verified(
+        r#"
+(module
+  (tag $e)
+  (func $leave (param i32) (result i32)
+    (block $out
+      try
+        (loop $loop
+          try
+            (br_if $out (i32.eqz (local.get 0)))
+            (local.set 0 (i32.sub (local.get 0) (i32.const 1)))
+            (br_table $loop $out (local.get 0))
+          end)
+      end)
+    (local.get 0))
+  (func (export "main") (param i32) (result i64)
+    try (result i64)
+      (drop (call $leave (local.get 0)))
+      ;; the `try` blocks left by the branches don't catch this exception anymore
+      (throw $e)
+    catch_all
+      (i64.extend_i32_u (call $leave (local.get 0)))
+    end))
+"#,
+        CompilationConfig::default(),
+    );
+    assert_eq!(execute(&module, 0), Ok(0));
+    assert_eq!(execute(&module, 1), Ok(0));
+    assert_eq!(execute(&module, 3), Ok(2));
+}
+
+#[test]
+fn test_uncaught_exception() {
+    let module = compile_verified(
+        r#"
+(module
+  (tag $e (param i32))
+  (func $thrower (param i32) (result i32)
+    (throw $e (local.get 0)))
+  (func (export "main") (param i32) (result i64)
+    try (result i64)
+      (i64.extend_i32_u (call $thrower (local.get 0)))
+    catch $e
+      drop
+      rethrow 0
+    end))
+"#,
+        CompilationConfig::default(),
+    );
+    assert_eq!(execute(&module, 0), Err(TrapCode::UncaughtException));
+}
+
+#[test]
+fn test_host_exception() {
+    let mut import_linker = ImportLinker::default();
+    import_linker.insert_function(
+        ImportName::new("env", "throw"),
+        0x01,
+        SyscallFuelParams::default(),
+        &[ValType::I32],
+        &[],
+    );
+    let import_linker = Arc::new(import_linker);
+    let module = compile_verified(
+        r#"
+(module
+  (tag $e (param i64))
+  (func $throw (import "env" "throw") (param i32))
+  (func (export "main") (param i32) (result i64)
+    try (result i64)
+      (call $throw (local.get 0))
+      (i64.const 0)
+    catch $e
+    end))
+"#,
+        CompilationConfig::default().with_import_linker(import_linker.clone()),
+    );
+    fn syscall_handler(
+        caller: &mut TypedCaller<'_, ()>,
+        _sys_func_idx: u32,
+        params: &[Value],
+        _result: &mut [Value],
+    ) -> Result<(), TrapCode> {
+        match params[0].i32().unwrap() {
+            0 => Ok(()),
+            1 => Err(caller.throw_exception(0, &[Value::I64(77)])),
+            // the payload doesn't match the tag parameters
+            _ => Err(caller.throw_exception(0, &[Value::I32(77)])),
+        }
+    }
+    let run = |param: i32| {
+        let mut store = RwasmStore::new(
+            import_linker.clone(),
+            (),
+            syscall_handler,
+            Some(1_000_000),
+            None,
+        );
+        execute_in(&mut store, &module, &[Value::I32(param)], 1).map(|result| result[0])
+    };
+    assert_eq!(run(0), Ok(0));
+    assert_eq!(run(1), Ok(77));
+    assert_eq!(run(2), Err(TrapCode::BadSignature));
+}
@@ -2,11 +2,12 @@ use hex_literal::hex;
 use rwasm::{
     for_each_strategy,
     wasmtime::{compile_wasmtime_module, WasmtimeExecutor},
-    CompilationConfig, ExecutionEngine, ImportLinker, ImportName, Opcode, RwasmModule, RwasmStore,
-    StateRouterConfig, StoreTr, StrategyDefinition, TrapCode, TypedCaller, Value,
+    CallStack, CompilationConfig, ExecutionEngine, ImportLinker, ImportName, InstructionPtr,
+    Opcode, RwasmExecutor, RwasmModule, RwasmModuleLimits, RwasmStore, StateRouterConfig, StoreTr,
+    StrategyDefinition, TrapCode, TypedCaller, Value, ValueStack,
 };
 use rwasm_fuel_policy::SyscallFuelParams;
-use std::{str::from_utf8, sync::Arc};
+use std::{collections::HashMap, str::from_utf8, sync::Arc};
 use wasmparser::ValType;
 
 #[derive(Default, Clone)]
@@ -223,15 +224,19 @@ fn test_nitro_verifier_strategy() {
 const STATE_MAIN: u32 = 1;
 const STATE_DEPLOY: u32 = 2;
 
-fn run_fluentbase_binary(wasm_binary: &[u8], host_state: HostState) -> HostState {
-    let import_linker = create_import_linker();
+fn compile_fluentbase_binary(wasm_binary: &[u8]) -> RwasmModule {
     let config = CompilationConfig::default()
         .with_state_router(StateRouterConfig {
             states: Box::new([("deploy".into(), STATE_DEPLOY), ("main".into(), STATE_MAIN)]),
             opcode: Some(Opcode::I32Const(STATE_MAIN.into())),
         })
-        .with_import_linker(import_linker.clone());
-    let (rwasm_module, _) = RwasmModule::compile(config, wasm_binary).unwrap();
+        .with_import_linker(create_import_linker());
+    RwasmModule::compile(config, wasm_binary).unwrap().0
+}
+
+fn run_fluentbase_binary(wasm_binary: &[u8], host_state: HostState) -> HostState {
+    let import_linker = create_import_linker();
+    let rwasm_module = compile_fluentbase_binary(wasm_binary);
     let mut store = RwasmStore::new(
         import_linker.clone(),
         host_state,
@@ -261,14 +266,183 @@ fn test_wasm_panic() {
     )
 }
 
-#[test]
-fn test_wasm_secp256k1() {
-    let wasm_binary = include_bytes!("assets/secp256k1-stack-ub.wasm");
+fn secp256k1_host_state() -> HostState {
     let mut host_state = HostState {
         input: vec![0u8; 1024],
         output: vec![],
         state: STATE_MAIN,
     };
     host_state.input.extend_from_slice(&hex!("a04a451028d0f9284ce82243755e245238ab1e4ecf7b9dd8bf4734d9ecfd0529cf09dd8d0eb3c3968aca8846a249424e5537d3470f979ff902b57914dc77d02316bd29784f668a73cc7a36f4cc5b9ce704481e6cb5b1c2c832af02ca6837ebec044e3b81af9c2234cad09d679ce6035ed1392347ce64ce405f5dcd36228a25de6e47fd35c4215d1edf53e6f83de344615ce719bdb0fd878f6ed76f06dd277956de"));
-    run_fluentbase_binary(wasm_binary, host_state);
+    host_state
+}
+
+#[test]
+fn test_wasm_secp256k1() {
+    let wasm_binary = include_bytes!("assets/secp256k1-stack-ub.wasm");
+    run_fluentbase_binary(wasm_binary, secp256k1_host_state());
+}
+
+#[test]
+fn test_compact_encoding_round_trip() {
+    let assets: [&[u8]; 3] = [
+        include_bytes!("assets/nitro-verifier-stack-ub.wasm"),
+        include_bytes!("assets/panic-stack-ub.wasm"),
+        include_bytes!("assets/secp256k1-stack-ub.wasm"),
+    ];
+    for wasm_binary in assets {
+        let config = CompilationConfig::default()
+            .with_entrypoint_name("main".into())
+            .with_allow_malformed_entrypoint_func_type(true)
+            .with_import_linker(create_import_linker());
+        let (rwasm_module, _) = RwasmModule::compile(config, wasm_binary).unwrap();
+        let legacy = rwasm_module.serialize();
+        let compact = rwasm_module.serialize_compact();
+        assert!(compact.len() < legacy.len());
+        let decoded = RwasmModule::new_verified_exact_with_import_linker(
+            &compact,
+            &RwasmModuleLimits::default(),
+            &create_import_linker(),
+        )
+        .unwrap();
+        assert_eq!(decoded.code_section, rwasm_module.code_section);
+        assert_eq!(decoded, rwasm_module);
+        assert_eq!(decoded.serialize(), legacy);
+    }
+}
+
+/// Counts adjacent instruction pairs of the bundled assets compiled w/o fused opcodes, both in the
+/// code section (all assets) and in the executed code (assets that run in a debug build). Run with
+/// `--nocapture` to print the most frequent pairs, `docs/pipeline.md` keeps the output.
+#[test]
+fn test_fused_opcode_pairs_are_selected_by_profiling() {
+    const TOP_PAIRS: usize = 10;
+    const FUSED_PAIRS: [(&str, &str); 6] = [
+        ("LocalGet", "LocalGet"),
+        ("LocalGet", "I32Const"),
+        ("LocalSet", "LocalGet"),
+        ("LocalGet", "I32Load"),
+        ("LocalSet", "LocalSet"),
+        ("I32Const", "I32Add"),
+    ];
+    // pairs are keyed by opcode codes, the opcodes are kept to print their names
+    type PairCounts = HashMap<(u32, u32), (u64, Opcode, Opcode)>;
+    let count_pair = |counts: &mut PairCounts, lhs: &Opcode, rhs: &Opcode| {
+        counts
+            .entry((lhs.code(), rhs.code()))
+            .or_insert((0, *lhs, *rhs))
+            .0 += 1;
+    };
+
+    let mut static_pairs = PairCounts::new();
+    for wasm_binary in [
+        include_bytes!("assets/nitro-verifier-stack-ub.wasm").as_slice(),
+        include_bytes!("assets/panic-stack-ub.wasm"),
+        include_bytes!("assets/secp256k1-stack-ub.wasm"),
+    ] {
+        let rwasm_module = compile_fluentbase_binary(wasm_binary);
+        for pair in rwasm_module.code_section.windows(2) {
+            count_pair(&mut static_pairs, &pair[0], &pair[1]);
+        }
+    }
+
+    // only pairs executed one after another are counted, branches and calls break a pair
+    let mut executed_pairs = PairCounts::new();
+    for (wasm_binary, host_state) in [
+        (
+            include_bytes!("assets/panic-stack-ub.wasm").as_slice(),
+            HostState {
+                state: STATE_MAIN,
+                ..Default::default()
+            },
+        ),
+        (
+            include_bytes!("assets/secp256k1-stack-ub.wasm"),
+            secp256k1_host_state(),
+        ),
+    ] {
+        let rwasm_module = compile_fluentbase_binary(wasm_binary);
+        let code = &rwasm_module.code_section;
+        let mut store = RwasmStore::new(
+            create_import_linker(),
+            host_state,
+            fluentbase_syscall_handler,
+            None,
+            None,
+        );
+        // the instance runs the entrypoint from the beginning first if the source PC is set
+        let source_pc = rwasm_module.source_pc as usize;
+        let start_pcs = if source_pc > 0 {
+            vec![0, source_pc]
+        } else {
+            vec![0]
+        };
+        for start_pc in start_pcs {
+            let (mut value_stack, mut call_stack) = (ValueStack::default(), CallStack::default());
+            let sp = value_stack.stack_ptr();
+            let mut ip = InstructionPtr::new(code.as_ptr());
+            ip.offset(start_pc as isize);
+            let mut executor = RwasmExecutor::new(
+                &rwasm_module,
+                &mut value_stack,
+                sp,
+                &mut call_stack,
+                ip,
+                &mut store,
+            );
+            let mut prev_pc = None;
+            loop {
+                let pc = executor.program_counter() as usize;
+                if pc > 0 && prev_pc == Some(pc - 1) {
+                    count_pair(&mut executed_pairs, &code[pc - 1], &code[pc]);
+                }
+                prev_pc = Some(pc);
+                match executor.step(code[pc]) {
+                    Ok(false) => {}
+                    Ok(true) | Err(TrapCode::ExecutionHalted) => break,
+                    Err(trap_code) => panic!("unexpected trap: {:?}", trap_code),
+                }
+            }
+        }
+    }
+
+    let rank = |counts: &PairCounts| {
+        let total = counts.values().map(|(count, _, _)| count).sum::<u64>();
+        let mut pairs = counts
+            .values()
+            .map(|(count, lhs, rhs)| (format!("{:#}+{:#}", lhs, rhs), *count, total))
+            .collect::<Vec<_>>();
+        pairs.sort_by(|lhs, rhs| rhs.1.cmp(&lhs.1).then(lhs.0.cmp(&rhs.0)));
+        pairs
+    };
+    let static_ranks = rank(&static_pairs);
+    let executed_ranks = rank(&executed_pairs);
+    for (name, ranks) in [("static", &static_ranks), ("executed", &executed_ranks)] {
+        println!("{} pairs:", name);
+        for (i, (pair, count, total)) in ranks.iter().take(TOP_PAIRS).enumerate() {
+            let share = *count as f64 * 100.0 / *total as f64;
+            println!("{:>4} {:<24} {:>9} {:>6.2}%", i + 1, pair, count, share);
+        }
+    }
+    let position = |ranks: &[(String, u64, u64)], pair: &str| {
+        ranks.iter().position(|(name, _, _)| name == pair)
+    };
+    for (lhs, rhs) in FUSED_PAIRS {
+        let pair = format!("{}+{}", lhs, rhs);
+        let static_rank = position(&static_ranks, &pair);
+        let executed_rank = position(&executed_ranks, &pair);
+        println!(
+            "fused {}: static rank {:?}, executed rank {:?}",
+            pair,
+            static_rank.map(|i| i + 1),
+            executed_rank.map(|i| i + 1),
+        );
+        assert!(
+            [static_rank, executed_rank]
+                .into_iter()
+                .any(|rank| rank.is_some_and(|rank| rank < TOP_PAIRS)),
+            "{} isn't one of the {} most frequent pairs",
+            pair,
+            TOP_PAIRS
+        );
+    }
 }
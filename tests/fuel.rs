@@ -1,5 +1,10 @@
-use rwasm::{CompilationConfig, ExecutionEngine, RwasmModule, RwasmStore, StoreTr, Value};
+use rwasm::{
+    CompilationConfig, DefaultFuelCostModel, ExecutionEngine, FuelCostClass, FuelCostModel,
+    FuelMeteringMode, FuelPolicy, RwasmModule, RwasmModuleVerificationError, RwasmStore, StoreTr,
+    TrapCode, Value,
+};
 use rwasm_fuel_policy::FuelCosts;
+use std::sync::Arc;
 
 #[test]
 fn test_entrypoint_call_consumes_fuel() {
@@ -248,3 +253,398 @@ fn test_table_bulk_ops_compile_with_fuel_metering() {
     let consumed = fuel_limit - store.remaining_fuel().unwrap();
     assert!(consumed > FuelCosts::BASE as u64);
 }
+
+/// Charges every class a different power of ten, so the charged classes can be read from the
+/// consumed fuel.
+#[derive(Debug)]
+struct DecimalFuelCostModel;
+
+impl FuelCostModel for DecimalFuelCostModel {
+    fn opcode_cost(&self, class: FuelCostClass) -> u32 {
+        match class {
+            FuelCostClass::Base => 1,
+            FuelCostClass::Arithmetic => 10,
+            FuelCostClass::Entity => 100,
+            FuelCostClass::Load => 1_000,
+            FuelCostClass::Store => 10_000,
+        }
+    }
+
+    fn call_cost(&self) -> u32 {
+        100_000
+    }
+
+    fn locals_cost(&self, locals: u32) -> u32 {
+        locals.saturating_mul(1_000_000)
+    }
+
+    fn memory_bytes_per_fuel_log2(&self) -> u32 {
+        0
+    }
+}
+
+const PRICED_WAT: &str = r#"
+    (module
+      (memory 1)
+      (global $g (mut i32) (i32.const 5))
+      (func $callee)
+      (func (export "entry") (result i32)
+        (local i32 i32)
+        call $callee
+        i32.const 8
+        global.get $g
+        i32.store
+        i32.const 8
+        i32.load
+        i32.const 3
+        i32.mul
+      )
+    )
+"#;
+
+fn compile_entry(config: CompilationConfig, wat: &str) -> RwasmModule {
+    let wasm_binary = wat::parse_str(wat).unwrap();
+    let config = config
+        .with_entrypoint_name("entry".into())
+        .with_allow_malformed_entrypoint_func_type(true);
+    RwasmModule::compile(config, &wasm_binary).unwrap().0
+}
+
+fn consumed_fuel(config: CompilationConfig, wat: &str, params: &[Value]) -> u64 {
+    let module = compile_entry(config, wat);
+    module.verify_with_policy(FuelPolicy::Required).unwrap();
+    execute_entry(&module, params)
+}
+
+/// Executes the entry of the module and returns the consumed fuel.
+fn execute_entry(module: &RwasmModule, params: &[Value]) -> u64 {
+    let fuel_limit = 100_000_000;
+    let mut store = RwasmStore::<()>::default();
+    let engine = ExecutionEngine::new();
+    engine.entrypoint(&mut store, module).unwrap();
+    store.reset_fuel(fuel_limit);
+    let mut result = [Value::I32(0)];
+    engine
+        .execute(&mut store, module, params, &mut result)
+        .unwrap();
+    fuel_limit - store.remaining_fuel().unwrap()
+}
+
+#[test]
+fn test_default_fuel_cost_model_charges_fuel_costs() {
+    let expected = FuelCosts::BASE // entry
+        + FuelCosts::fuel_for_locals(2)
+        + FuelCosts::CALL
+        + FuelCosts::BASE // callee
+        + FuelCosts::BASE * 3 // constants
+        + FuelCosts::ENTITY
+        + FuelCosts::STORE
+        + FuelCosts::LOAD
+        + FuelCosts::BASE; // mul
+    let default_config = CompilationConfig::default().with_entrypoint_name("entry".into());
+    assert_eq!(
+        consumed_fuel(default_config.clone(), PRICED_WAT, &[]),
+        expected as u64
+    );
+    // the default model emits the same code as before
+    let wasm_binary = wat::parse_str(PRICED_WAT).unwrap();
+    let explicit_config = default_config
+        .clone()
+        .with_fuel_cost_model(Arc::new(DefaultFuelCostModel));
+    assert_eq!(
+        RwasmModule::compile(default_config, &wasm_binary)
+            .unwrap()
+            .0
+            .serialize(),
+        RwasmModule::compile(explicit_config, &wasm_binary)
+            .unwrap()
+            .0
+            .serialize()
+    );
+}
+
+#[test]
+fn test_custom_fuel_cost_model_prices_every_class() {
+    let config = CompilationConfig::default().with_fuel_cost_model(Arc::new(DecimalFuelCostModel));
+    assert_eq!(consumed_fuel(config.clone(), PRICED_WAT, &[]), 2_111_115);
+    // params and locals aren't charged without the flag
+    assert_eq!(
+        consumed_fuel(
+            config.with_consume_fuel_for_params_and_locals(false),
+            PRICED_WAT,
+            &[]
+        ),
+        111_115
+    );
+}
+
+#[test]
+fn test_custom_fuel_cost_model_prices_bulk_bytes() {
+    let wat = r#"
+        (module
+          (memory 1)
+          (func (export "entry") (param i32) (result i32)
+            i32.const 0
+            i32.const 7
+            local.get 0
+            memory.fill
+            i32.const 0
+          )
+        )
+    "#;
+    let charged_for_bytes = |module: &RwasmModule| {
+        execute_entry(module, &[Value::I32(100)]) - execute_entry(module, &[Value::I32(0)])
+    };
+    let module = compile_entry(CompilationConfig::default(), wat);
+    module.verify_with_policy(FuelPolicy::Required).unwrap();
+    assert_eq!(charged_for_bytes(&module), 2);
+    let config = CompilationConfig::default().with_fuel_cost_model(Arc::new(DecimalFuelCostModel));
+    let module = compile_entry(config.clone(), wat);
+    assert_eq!(charged_for_bytes(&module), 100);
+    // w/o the metadata the verifier only accepts the canonical number of bytes per fuel
+    assert!(matches!(
+        module.verify_with_policy(FuelPolicy::Required),
+        Err(RwasmModuleVerificationError::MissingBulkFuelCheck { .. })
+    ));
+    // the metadata carries the shifts of the model
+    let module = compile_entry(config.with_emit_metadata_section(true), wat);
+    module.verify_with_policy(FuelPolicy::Required).unwrap();
+    assert_eq!(charged_for_bytes(&module), 100);
+}
+
+#[cfg(feature = "wasmtime")]
+#[test]
+fn test_custom_fuel_cost_model_is_rejected_by_wasmtime() {
+    use rwasm::{CompilationError, StrategyDefinition};
+    let wasm_binary = wat::parse_str(PRICED_WAT).unwrap();
+    let config = CompilationConfig::default().with_entrypoint_name("entry".into());
+    StrategyDefinition::new_as_wasmtime(config.clone(), &wasm_binary, None).unwrap();
+    StrategyDefinition::new_as_wasmtime(
+        config
+            .clone()
+            .with_fuel_cost_model(Arc::new(DefaultFuelCostModel)),
+        &wasm_binary,
+        None,
+    )
+    .unwrap();
+    assert!(matches!(
+        StrategyDefinition::new_as_wasmtime(
+            config.with_fuel_cost_model(Arc::new(DecimalFuelCostModel)),
+            &wasm_binary,
+            None
+        ),
+        Err(CompilationError::NotSupportedFuelCostModel)
+    ));
+}
+
+#[test]
+fn test_fuel_cost_model_is_pinned_by_metadata() {
+    let compile = |config: CompilationConfig| {
+        compile_entry(config.with_emit_metadata_section(true), PRICED_WAT)
+    };
+    let module = compile(CompilationConfig::default());
+    let metadata = module.metadata_section.unwrap();
+    let engine = ExecutionEngine::new().with_required_metadata(metadata);
+    let mut store = RwasmStore::<()>::default();
+    engine.entrypoint(&mut store, &module).unwrap();
+    // the unit model keeps the default bulk shifts, so only the fingerprint tells it apart
+    for fuel_cost_model in [
+        Arc::new(DecimalFuelCostModel) as Arc<dyn FuelCostModel>,
+        Arc::new(UnitFuelCostModel),
+    ] {
+        let module = compile(CompilationConfig::default().with_fuel_cost_model(fuel_cost_model));
+        let custom_metadata = module.metadata_section.unwrap();
+        assert_ne!(
+            custom_metadata.fuel_cost_model_hash,
+            metadata.fuel_cost_model_hash
+        );
+        assert_eq!(
+            engine.entrypoint(&mut store, &module),
+            Err(TrapCode::IncompatibleModule)
+        );
+    }
+}
+
+#[test]
+fn test_fuel_metering_mode_is_pinned_by_metadata() {
+    let compile = |mode: FuelMeteringMode| {
+        let config = CompilationConfig::default()
+            .with_fuel_metering_mode(mode)
+            .with_emit_metadata_section(true);
+        compile_entry(config, PRICED_WAT)
+    };
+    let eager_module = compile(FuelMeteringMode::Eager);
+    let lazy_module = compile(FuelMeteringMode::Lazy);
+    for (module, other_module) in [(&eager_module, &lazy_module), (&lazy_module, &eager_module)] {
+        let engine =
+            ExecutionEngine::new().with_required_metadata(module.metadata_section.unwrap());
+        let mut store = RwasmStore::<()>::default();
+        engine.entrypoint(&mut store, module).unwrap();
+        assert_eq!(
+            engine.entrypoint(&mut store, other_module),
+            Err(TrapCode::IncompatibleModule)
+        );
+    }
+}
+
+/// Charges one fuel for every operator, so the consumed fuel counts the charged operators.
+#[derive(Debug)]
+struct UnitFuelCostModel;
+
+impl FuelCostModel for UnitFuelCostModel {
+    fn opcode_cost(&self, _class: FuelCostClass) -> u32 {
+        1
+    }
+
+    fn call_cost(&self) -> u32 {
+        1
+    }
+
+    fn locals_cost(&self, _locals: u32) -> u32 {
+        0
+    }
+}
+
+/// Executes the entry with the given metering mode, returns the result and the consumed fuel.
+fn metered_execution(
+    mode: FuelMeteringMode,
+    wat: &str,
+    param: i32,
+    fuel_limit: u64,
+) -> (Result<i32, TrapCode>, u64) {
+    let wasm_binary = wat::parse_str(wat).unwrap();
+    let config = CompilationConfig::default()
+        .with_entrypoint_name("entry".into())
+        .with_allow_malformed_entrypoint_func_type(true)
+        .with_fuel_cost_model(Arc::new(UnitFuelCostModel))
+        .with_fuel_metering_mode(mode);
+    let (module, _) = RwasmModule::compile(config, &wasm_binary).unwrap();
+    module.verify_with_policy(FuelPolicy::Required).unwrap();
+    let mut store = RwasmStore::<()>::default();
+    let engine = ExecutionEngine::new();
+    engine.entrypoint(&mut store, &module).unwrap();
+    store.reset_fuel(fuel_limit);
+    let mut result = [Value::I32(0)];
+    let result = engine
+        .execute(&mut store, &module, &[Value::I32(param)], &mut result)
+        .map(|_| result[0].i32().unwrap());
+    (result, fuel_limit - store.remaining_fuel().unwrap())
+}
+
+/// Executes the entry with both metering modes, returns the results and the consumed fuel of
+/// the eager and the lazy mode.
+fn differential_execution(wat: &str, param: i32) -> (Result<i32, TrapCode>, u64, u64) {
+    let (eager_result, eager_fuel) =
+        metered_execution(FuelMeteringMode::Eager, wat, param, 1_000_000);
+    let (lazy_result, lazy_fuel) = metered_execution(FuelMeteringMode::Lazy, wat, param, 1_000_000);
+    assert_eq!(eager_result, lazy_result, "param {param}");
+    (eager_result, eager_fuel, lazy_fuel)
+}
+
+#[test]
+fn test_lazy_fuel_metering_matches_eager_metering() {
+    let wat = r#"
+        (module
+          (type $unary (func (param i32) (result i32)))
+          (table 2 funcref)
+          (elem (i32.const 0) $double $inc)
+          (func $double (param i32) (result i32)
+            (i32.mul (local.get 0) (i32.const 2)))
+          (func $inc (param i32) (result i32)
+            (i32.add (local.get 0) (i32.const 1)))
+          (func $fib (param i32) (result i32)
+            (if (result i32) (i32.lt_u (local.get 0) (i32.const 2))
+              (then (local.get 0))
+              (else
+                (i32.add
+                  (call $fib (i32.sub (local.get 0) (i32.const 1)))
+                  (call $fib (i32.sub (local.get 0) (i32.const 2)))))))
+          (func (export "entry") (param i32) (result i32)
+            (local $acc i32) (local $i i32)
+            (if (i32.eq (local.get 0) (i32.const 11))
+              (then (return (i32.const -1))))
+            (block $exit
+              (loop $next
+                (br_if $exit (i32.ge_u (local.get $i) (local.get 0)))
+                (block $odd
+                  (block $even
+                    (br_table $even $odd (i32.and (local.get $i) (i32.const 1))))
+                  (local.set $acc
+                    (call_indirect (type $unary) (local.get $acc) (i32.const 0)))
+                  (br $next (local.set $i (i32.add (local.get $i) (i32.const 1)))))
+                (local.set $acc
+                  (call_indirect (type $unary) (local.get $acc) (i32.const 1)))
+                (local.set $i (i32.add (local.get $i) (i32.const 1)))
+                (br $next)))
+            (i32.add (local.get $acc) (call $fib (local.get 0)))))
+    "#;
+    // both modes charge every executed operator once if the execution doesn't trap
+    for param in 0..12 {
+        let (result, eager_fuel, lazy_fuel) = differential_execution(wat, param);
+        assert!(result.is_ok());
+        assert_eq!(eager_fuel, lazy_fuel, "param {param}");
+    }
+}
+
+#[test]
+fn test_lazy_fuel_metering_charges_completed_operators() {
+    let wat = r#"
+        (module
+          (memory 1)
+          (func $checked (param i32) (result i32)
+            (i32.div_u (i32.const 100) (local.get 0)))
+          (func (export "entry") (param i32) (result i32)
+            (i32.add (i32.const 1) (i32.const 2))
+            (call $checked (local.get 0))
+            (i32.add)
+            (i32.load (i32.const 0))
+            (i32.add)))
+    "#;
+    // 10 for the entry (with its prologue) and 4 for the callee
+    assert_eq!(differential_execution(wat, 5), (Ok(23), 14, 14));
+    // the eager mode charges the whole code up front, the lazy mode charges the prologues and
+    // the 6 operators done before the division traps
+    assert_eq!(
+        differential_execution(wat, 0),
+        (Err(TrapCode::IntegerDivisionByZero), 14, 9)
+    );
+}
+
+#[test]
+fn test_lazy_fuel_metering_charges_thrown_exceptions() {
+    let wat = r#"
+        (module
+          (tag $error)
+          (func $fail (param i32)
+            (if (local.get 0) (then (throw $error))))
+          (func (export "entry") (param i32) (result i32)
+            try (result i32)
+              (call $fail (local.get 0))
+              (i32.add (i32.const 1) (i32.const 2))
+            catch $error
+              (i32.const 7)
+            end))
+    "#;
+    let (result, eager_fuel, lazy_fuel) = differential_execution(wat, 0);
+    assert_eq!(result, Ok(3));
+    assert_eq!(eager_fuel, lazy_fuel);
+    // the eager mode charges the 3 operators skipped by the exception too
+    let (result, eager_fuel, lazy_fuel) = differential_execution(wat, 1);
+    assert_eq!(result, Ok(7));
+    assert_eq!(eager_fuel, lazy_fuel + 3);
+}
+
+#[test]
+fn test_lazy_fuel_metering_stops_infinite_loops() {
+    let wat = r#"
+        (module
+          (func (export "entry") (param i32) (result i32)
+            (loop $forever (br $forever))
+            (i32.const 0)))
+    "#;
+    for mode in [FuelMeteringMode::Eager, FuelMeteringMode::Lazy] {
+        let (result, fuel) = metered_execution(mode, wat, 0, 1_000);
+        assert_eq!(result, Err(TrapCode::OutOfFuel));
+        assert!(fuel <= 1_000);
+    }
+}
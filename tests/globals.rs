@@ -0,0 +1,183 @@
+mod common;
+
+use common::{compile_wat, execute_in, linker_config, linker_store};
+use rwasm::{
+    CompilationError, GlobalType, GlobalValueProvider, ImportLinker, ImportName, RwasmModule,
+    RwasmStore, TrapCode, ValType, Value,
+};
+use std::sync::Arc;
+
+const CHAIN_ID: GlobalType = GlobalType {
+    content_type: ValType::I64,
+    mutable: false,
+};
+const BLOCK_NUMBER: GlobalType = GlobalType {
+    content_type: ValType::I64,
+    mutable: false,
+};
+const COUNTER: GlobalType = GlobalType {
+    content_type: ValType::I32,
+    mutable: true,
+};
+
+fn import_linker() -> Arc<ImportLinker> {
+    let mut import_linker = ImportLinker::default();
+    import_linker.insert_global(
+        ImportName::new("env", "chain_id"),
+        CHAIN_ID,
+        GlobalValueProvider::Const(1337),
+    );
+    import_linker.insert_global(
+        ImportName::new("env", "block_number"),
+        BLOCK_NUMBER,
+        GlobalValueProvider::Host(0),
+    );
+    import_linker.insert_global(
+        ImportName::new("env", "counter"),
+        COUNTER,
+        GlobalValueProvider::Host(1),
+    );
+    Arc::new(import_linker)
+}
+
+fn compile(wat: &str) -> Result<RwasmModule, CompilationError> {
+    compile_wat(wat, linker_config(import_linker()))
+}
+
+fn store() -> RwasmStore<()> {
+    linker_store(import_linker())
+}
+
+fn execute(store: &mut RwasmStore<()>, module: &RwasmModule) -> Result<i64, TrapCode> {
+    execute_in(store, module, &[], 1).map(|result| result[0])
+}
+
+#[test]
+fn test_const_imported_global() {
+    let module = compile(
+        r#"
+(module
+  (import "env" "chain_id" (global $chain_id i64))
+  (global $next (mut i64) (global.get $chain_id))
+  (func (export "main") (result i64)
+    (global.set $next (i64.add (global.get $next) (i64.const 1)))
+    (i64.add (global.get $chain_id) (global.get $next))))
+"#,
+    )
+    .unwrap();
+    assert_eq!(execute(&mut store(), &module), Ok(1337 + 1338));
+}
+
+#[test]
+fn test_host_globals() {
+    let module = compile(
+        r#"
+(module
+  (import "env" "block_number" (global $block_number i64))
+  (import "env" "counter" (global $counter (mut i32)))
+  (func (export "main") (result i64)
+    (global.set $counter (i32.add (global.get $counter) (i32.const 1)))
+    (global.get $block_number)))
+"#,
+    )
+    .unwrap();
+    let mut store = store();
+    let block_number = ImportName::new("env", "block_number");
+    let counter = ImportName::new("env", "counter");
+    store
+        .set_global(&block_number, Value::I64(0x1_0000_0002))
+        .unwrap();
+    store.set_global(&counter, Value::I32(41)).unwrap();
+    assert_eq!(execute(&mut store, &module), Ok(0x1_0000_0002));
+    assert_eq!(store.global(&counter), Ok(Value::I32(42)));
+    assert_eq!(store.global(&block_number), Ok(Value::I64(0x1_0000_0002)));
+
+    // the next execution sees the new values
+    store.set_global(&block_number, Value::I64(-7)).unwrap();
+    assert_eq!(execute(&mut store, &module), Ok(-7));
+    assert_eq!(store.global(&counter), Ok(Value::I32(43)));
+
+    assert_eq!(
+        store.set_global(&counter, Value::I64(1)),
+        Err(TrapCode::BadSignature)
+    );
+    // const globals are baked into the module
+    assert_eq!(
+        store.global(&ImportName::new("env", "chain_id")),
+        Err(TrapCode::UnknownExternalFunction)
+    );
+    assert_eq!(
+        store.global(&ImportName::new("env", "unknown")),
+        Err(TrapCode::UnknownExternalFunction)
+    );
+}
+
+#[test]
+fn test_imported_global_errors() {
+    let result =
+        compile(r#"(module (import "env" "counter" (global i32)) (func (export "main")))"#);
+    assert!(matches!(
+        result,
+        Err(CompilationError::MalformedImportGlobalType)
+    ));
+    let result =
+        compile(r#"(module (import "env" "chain_id" (global i32)) (func (export "main")))"#);
+    assert!(matches!(
+        result,
+        Err(CompilationError::MalformedImportGlobalType)
+    ));
+    let result =
+        compile(r#"(module (import "env" "gas_price" (global i64)) (func (export "main")))"#);
+    assert!(matches!(
+        result,
+        Err(CompilationError::UnresolvedImportGlobal)
+    ));
+    // host globals are unknown at compile time
+    let result = compile(
+        r#"
+(module
+  (import "env" "block_number" (global $block_number i64))
+  (global i64 (global.get $block_number))
+  (func (export "main")))
+"#,
+    );
+    assert!(matches!(
+        result,
+        Err(CompilationError::ConstEvaluationFailed)
+    ));
+}
+
+#[test]
+fn test_default_imported_global_value() {
+    let module = compile_wat(
+        r#"
+(module
+  (import "env" "chain_id" (global $chain_id i64))
+  (import "env" "gas_price" (global $gas_price i64))
+  (func (export "main") (result i64)
+    (i64.add (global.get $chain_id) (global.get $gas_price))))
+"#,
+        linker_config(import_linker()).with_default_imported_global_value(100),
+    )
+    .unwrap();
+    assert_eq!(execute(&mut store(), &module), Ok(1337 + 100));
+}
+
+#[test]
+fn test_globals_change_import_linker_fingerprint() {
+    let mut import_linker = ImportLinker::default();
+    let fingerprint = import_linker.fingerprint();
+    import_linker.insert_global(
+        ImportName::new("env", "chain_id"),
+        CHAIN_ID,
+        GlobalValueProvider::Const(1),
+    );
+    assert_ne!(import_linker.fingerprint(), fingerprint);
+    let mut other_linker = ImportLinker::default();
+    other_linker.insert_global(
+        ImportName::new("env", "chain_id"),
+        CHAIN_ID,
+        GlobalValueProvider::Const(2),
+    );
+    assert_ne!(import_linker.fingerprint(), other_linker.fingerprint());
+}
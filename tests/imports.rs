@@ -0,0 +1,460 @@
+mod common;
+
+use common::{compile_wat, execute_in, linker_config, linker_store};
+use rwasm::{
+    CompilationConfig, CompilationError, FuncType, GlobalType, GlobalValueProvider,
+    ImportIssueKind, ImportLinker, ImportName, MemoryType, ModuleParser, RwasmModule, RwasmStore,
+    StoreTr, TableType, TrapCode, ValType, N_MAX_TABLE_SIZE,
+};
+use rwasm_fuel_policy::SyscallFuelParams;
+use std::sync::Arc;
+
+const MEMORY: MemoryType = MemoryType {
+    memory64: false,
+    shared: false,
+    initial: 1,
+    maximum: Some(2),
+};
+const TABLE: TableType = TableType {
+    element_type: ValType::FuncRef,
+    initial: 2,
+    maximum: None,
+};
+
+fn import_linker(memory: MemoryType, table: TableType) -> Arc<ImportLinker> {
+    let mut import_linker = ImportLinker::default();
+    import_linker.insert_memory(ImportName::new("env", "memory"), memory);
+    import_linker.insert_table(ImportName::new("env", "__indirect_function_table"), table);
+    Arc::new(import_linker)
+}
+
+fn compile_with(
+    wat: &str,
+    import_linker: Arc<ImportLinker>,
+) -> Result<RwasmModule, CompilationError> {
+    compile_wat(wat, linker_config(import_linker))
+}
+
+fn compile(wat: &str) -> Result<RwasmModule, CompilationError> {
+    compile_with(wat, import_linker(MEMORY, TABLE))
+}
+
+/// Creates a store with the imported memory and table allocated by the host.
+fn store() -> RwasmStore<()> {
+    let mut store = linker_store(import_linker(MEMORY, TABLE));
+    assert_eq!(store.grow_memory(0, 1), Ok(0));
+    assert_eq!(store.grow_table(0, 2), Ok(0));
+    store
+}
+
+fn execute(store: &mut RwasmStore<()>, module: &RwasmModule) -> Result<i64, TrapCode> {
+    execute_in(store, module, &[], 1).map(|result| result[0])
+}
+
+#[test]
+fn test_imported_memory() {
+    let module = compile(
+        r#"
+(module
+  (import "env" "memory" (memory 1))
+  (data (i32.const 8) "\02")
+  (func (export "main") (result i64)
+    (i64.add
+      (i64.load (i32.const 0))
+      (i64.load8_u (i32.const 8)))))
+"#,
+    )
+    .unwrap();
+    let mut store = store();
+    store.memory_write_at(0, 0, &40i64.to_le_bytes()).unwrap();
+    assert_eq!(execute(&mut store, &module), Ok(42));
+}
+
+#[test]
+fn test_imported_memory_grows_within_declared_limits() {
+    let module = compile(
+        r#"
+(module
+  (import "env" "memory" (memory 1))
+  (func (export "main") (result i64)
+    (i64.extend_i32_s (memory.grow (i32.const 1)))
+    (i64.extend_i32_s (memory.grow (i32.const 1)))
+    (i64.const 32)
+    (i64.shl)
+    (i64.add)))
+"#,
+    )
+    .unwrap();
+    // the second grow exceeds the max of the memory declared by the linker
+    assert_eq!(execute(&mut store(), &module), Ok(1 + (-1 << 32)));
+    let mut store = store();
+    assert_eq!(store.grow_memory(0, 1), Ok(1));
+    assert_eq!(execute(&mut store, &module), Ok(-1 + (-1 << 32)));
+}
+
+#[test]
+fn test_imported_indirect_function_table() {
+    let module = compile(
+        r#"
+(module
+  (import "env" "__indirect_function_table" (table 1 funcref))
+  (table $own 1 funcref)
+  (type $get (func (result i64)))
+  (elem (i32.const 0) $forty $two)
+  (elem (table $own) (i32.const 0) func $two)
+  (func $forty (result i64) (i64.const 40))
+  (func $two (result i64) (i64.const 2))
+  (func (export "main") (result i64)
+    (i64.add
+      (call_indirect (type $get) (i32.const 0))
+      (i64.add
+        (call_indirect (type $get) (i32.const 1))
+        (i64.add
+          (call_indirect $own (type $get) (i32.const 0))
+          (i64.extend_i32_u (table.size $own)))))))
+"#,
+    )
+    .unwrap();
+    assert_eq!(execute(&mut store(), &module), Ok(40 + 2 + 2 + 1));
+}
+
+#[test]
+fn test_imported_memory_and_table_must_be_allocated_by_host() {
+    let module = compile(
+        r#"
+(module
+  (import "env" "memory" (memory 1))
+  (import "env" "__indirect_function_table" (table 1 funcref))
+  (func (export "main") (result i64)
+    (i64.const 42)))
+"#,
+    )
+    .unwrap();
+    let mut store = linker_store(import_linker(MEMORY, TABLE));
+    assert_eq!(
+        execute(&mut store, &module),
+        Err(TrapCode::MemoryOutOfBounds)
+    );
+    assert_eq!(store.grow_memory(0, 1), Ok(0));
+    assert_eq!(
+        execute(&mut store, &module),
+        Err(TrapCode::TableOutOfBounds)
+    );
+    // the table must have the initial elements declared by the linker, not by the module
+    assert_eq!(store.grow_table(0, 1), Ok(0));
+    assert_eq!(
+        execute(&mut store, &module),
+        Err(TrapCode::TableOutOfBounds)
+    );
+    assert_eq!(store.grow_table(0, 1), Ok(1));
+    assert_eq!(execute(&mut store, &module), Ok(42));
+}
+
+#[test]
+fn test_imported_memory_and_table_limits() {
+    // the module can't expect more than the linker declares
+    let result = compile(r#"(module (import "env" "memory" (memory 2)) (func (export "main")))"#);
+    assert!(matches!(
+        result,
+        Err(CompilationError::MalformedImportMemoryType)
+    ));
+    let result = compile(r#"(module (import "env" "memory" (memory 1 1)) (func (export "main")))"#);
+    assert!(matches!(
+        result,
+        Err(CompilationError::MalformedImportMemoryType)
+    ));
+    let result = compile(
+        r#"(module (import "env" "__indirect_function_table" (table 1 8 funcref)) (func (export "main")))"#,
+    );
+    assert!(matches!(
+        result,
+        Err(CompilationError::MalformedImportTableType)
+    ));
+    let result = compile(
+        r#"(module (import "env" "__indirect_function_table" (table 1 externref)) (func (export "main")))"#,
+    );
+    assert!(matches!(
+        result,
+        Err(CompilationError::MalformedImportTableType)
+    ));
+    // the limits declared by the linker must fit the rwasm limits
+    let memory = MemoryType {
+        initial: 4096,
+        maximum: None,
+        ..MEMORY
+    };
+    let result = compile_with(
+        r#"(module (import "env" "memory" (memory 1)) (func (export "main")))"#,
+        import_linker(memory, TABLE),
+    );
+    assert!(matches!(
+        result,
+        Err(CompilationError::MaxMemoryPagesReached)
+    ));
+    let table = TableType {
+        initial: 2048,
+        ..TABLE
+    };
+    let result = compile_with(
+        r#"(module (import "env" "__indirect_function_table" (table 1 funcref)) (func (export "main")))"#,
+        import_linker(MEMORY, table),
+    );
+    assert!(matches!(result, Err(CompilationError::TableOutOfBounds)));
+    // the host can't grow the memory and the table beyond the rwasm limits
+    let mut store = store();
+    assert_eq!(
+        store.grow_memory(0, u32::MAX),
+        Err(TrapCode::MemoryOutOfBounds)
+    );
+    assert_eq!(store.grow_table(0, 2048), Err(TrapCode::TableOutOfBounds));
+}
+
+#[test]
+fn test_undeclared_imported_memory_and_table() {
+    let result = compile(r#"(module (import "env" "heap" (memory 1)) (func (export "main")))"#);
+    assert!(matches!(
+        result,
+        Err(CompilationError::UnresolvedImportMemory)
+    ));
+    let result =
+        compile(r#"(module (import "env" "table" (table 1 funcref)) (func (export "main")))"#);
+    assert!(matches!(
+        result,
+        Err(CompilationError::UnresolvedImportTable)
+    ));
+    let result = compile_with(
+        r#"(module (import "env" "memory" (memory 1)) (func (export "main")))"#,
+        Arc::new(ImportLinker::default()),
+    );
+    assert!(matches!(
+        result,
+        Err(CompilationError::UnresolvedImportMemory)
+    ));
+}
+
+#[test]
+fn test_memories_and_tables_change_import_linker_fingerprint() {
+    let mut import_linker = ImportLinker::default();
+    let fingerprint = import_linker.fingerprint();
+    import_linker.insert_memory(ImportName::new("env", "memory"), MEMORY);
+    let memory_fingerprint = import_linker.fingerprint();
+    assert_ne!(memory_fingerprint, fingerprint);
+    import_linker.insert_table(ImportName::new("env", "__indirect_function_table"), TABLE);
+    assert_ne!(import_linker.fingerprint(), memory_fingerprint);
+    let memory = MemoryType {
+        maximum: None,
+        ..MEMORY
+    };
+    assert_ne!(
+        import_linker.fingerprint(),
+        import_linker_fingerprint(memory, TABLE)
+    );
+    assert_eq!(
+        import_linker.fingerprint(),
+        import_linker_fingerprint(MEMORY, TABLE)
+    );
+}
+
+fn import_linker_fingerprint(memory: MemoryType, table: TableType) -> [u8; 32] {
+    import_linker(memory, table).fingerprint()
+}
+
+#[test]
+fn test_analyze_imports_reports_every_issue() {
+    let mut import_linker = ImportLinker::default();
+    import_linker.insert_function(
+        ImportName::new("env", "add"),
+        1,
+        SyscallFuelParams::default(),
+        &[ValType::I32, ValType::I32],
+        &[ValType::I32],
+    );
+    import_linker.insert_global(
+        ImportName::new("env", "chain_id"),
+        GlobalType {
+            content_type: ValType::I64,
+            mutable: false,
+        },
+        GlobalValueProvider::Const(1),
+    );
+    import_linker.insert_memory(ImportName::new("env", "memory"), MEMORY);
+    import_linker.insert_table(ImportName::new("env", "__indirect_function_table"), TABLE);
+    let wasm = wat::parse_str(
+        r#"
+(module
+  (import "env" "add" (func (param i32 i32) (result i32)))
+  (import "env" "sub" (func (param i32 i32) (result i32)))
+  (import "env" "add" (func $add64 (param i64 i64) (result i64)))
+  (import "env" "ref" (func (param externref)))
+  (import "env" "chain_id" (global i32))
+  (import "env" "gas_price" (global i64))
+  (import "env" "memory" (memory 2))
+  (import "env" "heap" (memory 1))
+  (import "env" "__indirect_function_table" (table 1 funcref))
+  (func (export "main")))
+"#,
+    )
+    .unwrap();
+    let config = CompilationConfig::default()
+        .with_entrypoint_name("main".into())
+        .with_import_linker(Arc::new(import_linker));
+    let report = ModuleParser::analyze_imports(&wasm, &config).unwrap();
+    assert_eq!(report.imports, 9);
+    assert!(!report.is_resolved());
+    let issues = report
+        .issues
+        .iter()
+        .map(|issue| (issue.import_name.to_string(), issue.kind.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        issues,
+        vec![
+            ("env::sub".to_string(), ImportIssueKind::UnresolvedFunction),
+            (
+                "env::add".to_string(),
+                ImportIssueKind::FuncTypeMismatch {
+                    expected: FuncType::new([ValType::I32, ValType::I32], [ValType::I32]),
+                    actual: FuncType::new([ValType::I64, ValType::I64], [ValType::I64]),
+                }
+            ),
+            ("env::ref".to_string(), ImportIssueKind::UnresolvedFunction),
+            (
+                "env::ref".to_string(),
+                ImportIssueKind::RefTypeInFuncType(FuncType::new([ValType::ExternRef], []))
+            ),
+            (
+                "env::chain_id".to_string(),
+                ImportIssueKind::GlobalTypeMismatch {
+                    expected: GlobalType {
+                        content_type: ValType::I64,
+                        mutable: false,
+                    },
+                    actual: GlobalType {
+                        content_type: ValType::I32,
+                        mutable: false,
+                    },
+                }
+            ),
+            (
+                "env::gas_price".to_string(),
+                ImportIssueKind::UnresolvedGlobal
+            ),
+            (
+                "env::memory".to_string(),
+                ImportIssueKind::MemoryTypeMismatch {
+                    expected: MEMORY,
+                    actual: MemoryType {
+                        initial: 2,
+                        maximum: None,
+                        ..MEMORY
+                    },
+                }
+            ),
+            ("env::heap".to_string(), ImportIssueKind::UnresolvedMemory),
+        ]
+    );
+    // the issues are ordered like the imports and the compilation fails at the first one
+    let result = RwasmModule::compile(config.clone(), &wasm);
+    assert!(matches!(
+        (result, report.issues[0].kind.compilation_error()),
+        (
+            Err(CompilationError::UnresolvedImportFunction),
+            CompilationError::UnresolvedImportFunction
+        )
+    ));
+
+    let wasm = wat::parse_str(
+        r#"
+(module
+  (import "env" "add" (func (param i32 i32) (result i32)))
+  (import "env" "memory" (memory 1))
+  (func (export "main")))
+"#,
+    )
+    .unwrap();
+    let report = ModuleParser::analyze_imports(&wasm, &config).unwrap();
+    assert_eq!(report.imports, 2);
+    assert!(report.is_resolved());
+}
+
+#[test]
+fn test_analyze_imports_respects_compilation_config() {
+    const LARGE_TABLE: TableType = TableType {
+        initial: N_MAX_TABLE_SIZE + 1,
+        ..TABLE
+    };
+    let mut import_linker = ImportLinker::default();
+    import_linker.insert_function(
+        ImportName::new("env", "call"),
+        1,
+        SyscallFuelParams::default(),
+        &[ValType::ExternRef],
+        &[],
+    );
+    import_linker.insert_memory(ImportName::new("env", "memory"), MEMORY);
+    import_linker.insert_memory(ImportName::new("env", "heap"), MEMORY);
+    import_linker.insert_table(
+        ImportName::new("env", "__indirect_function_table"),
+        LARGE_TABLE,
+    );
+    let wasm = wat::parse_str(
+        r#"
+(module
+  (import "env" "call" (func (param externref)))
+  (import "env" "chain_id" (global i64))
+  (import "env" "memory" (memory 1))
+  (import "env" "heap" (memory 1))
+  (import "env" "__indirect_function_table" (table 1 funcref))
+  (func (export "main")))
+"#,
+    )
+    .unwrap();
+    let config = CompilationConfig::default()
+        .with_entrypoint_name("main".into())
+        .with_import_linker(Arc::new(import_linker))
+        .with_max_allowed_memory_pages(2);
+    let report = ModuleParser::analyze_imports(&wasm, &config).unwrap();
+    let issues = report
+        .issues
+        .iter()
+        .map(|issue| (issue.import_name.to_string(), issue.kind.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        issues,
+        vec![
+            (
+                "env::call".to_string(),
+                ImportIssueKind::RefTypeInFuncType(FuncType::new([ValType::ExternRef], []))
+            ),
+            (
+                "env::chain_id".to_string(),
+                ImportIssueKind::UnresolvedGlobal
+            ),
+            (
+                "env::heap".to_string(),
+                ImportIssueKind::MemoryPagesLimitReached(MEMORY)
+            ),
+            (
+                "env::__indirect_function_table".to_string(),
+                ImportIssueKind::TableSizeLimitReached(LARGE_TABLE)
+            ),
+        ]
+    );
+    // relaxing the config resolves the issues one by one and the compilation fails at the first
+    // remaining one
+    let func_ref_config = config.clone().with_allow_func_ref_function_types(true);
+    let global_config = func_ref_config
+        .clone()
+        .with_default_imported_global_value(0);
+    let memory_config = global_config.clone().with_max_allowed_memory_pages(3);
+    let configs = [config, func_ref_config, global_config, memory_config];
+    for (config, issue) in configs.into_iter().zip(&report.issues) {
+        let report = ModuleParser::analyze_imports(&wasm, &config).unwrap();
+        assert_eq!(report.issues.first(), Some(issue));
+        let error = RwasmModule::compile(config, &wasm).err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            issue.kind.compilation_error().to_string()
+        );
+    }
+}
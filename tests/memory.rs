@@ -1,6 +1,7 @@
 use rwasm::{
-    always_failing_syscall_handler, instruction_set, ExecutionEngine, ImportLinker, RwasmModule,
-    RwasmModuleBuilder, RwasmStore,
+    always_failing_syscall_handler, instruction_set, CompilationConfig, ExecutionEngine,
+    FuelPolicy, ImportLinker, RwasmInstance, RwasmModule, RwasmModuleBuilder, RwasmStore, StoreTr,
+    TrapCode,
 };
 
 fn execute_module(module: &RwasmModule) -> u64 {
@@ -48,3 +49,126 @@ fn test_memory_fuel_ddos_not_possible() {
     let fuel_consumed = execute_module(&rwasm_module);
     assert_eq!(fuel_consumed, 3);
 }
+
+#[test]
+fn test_multi_memory() {
+    let wasm = wat::parse_str(
+        r#"
+(module
+  (memory $m0 1)
+  (memory $m1 1 2)
+  (data (memory $m1) (i32.const 8) "\01\02\03\04")
+  (data $p "\aa\bb")
+  (func (export "main")
+    ;; loads and stores
+    (i32.store $m1 (i32.const 16) (i32.const 0x11223344))
+    (i32.store $m0 (i32.const 0) (i32.load8_u $m1 (i32.const 16)))
+    (i64.store $m1 (i32.const 24) (i64.const 0x0102030405060708))
+    (i64.store $m0 (i32.const 8) (i64.load $m1 (i32.const 24)))
+    ;; size and grow respect the limits of each memory
+    (i32.store $m0 (i32.const 16) (memory.grow $m1 (i32.const 1)))
+    (i32.store $m0 (i32.const 20) (memory.grow $m1 (i32.const 1)))
+    (i32.store $m0 (i32.const 24) (memory.size $m1))
+    (i32.store $m0 (i32.const 28) (memory.size $m0))
+    ;; bulk operations
+    (memory.fill $m1 (i32.const 100) (i32.const 0x5a) (i32.const 4))
+    (memory.copy $m0 $m1 (i32.const 32) (i32.const 100) (i32.const 4))
+    (memory.init $m1 $p (i32.const 200) (i32.const 0) (i32.const 2))
+    (memory.copy $m1 $m1 (i32.const 202) (i32.const 8) (i32.const 4))
+    ;; floats
+    (f32.store $m1 (i32.const 300) (f32.const 1.5))
+    (i32.store $m0 (i32.const 36) (i32.load $m1 (i32.const 300)))
+  )
+)
+"#,
+    )
+    .unwrap();
+    let config = CompilationConfig::default().with_entrypoint_name("main".into());
+    let (module, _) = RwasmModule::compile(config, &wasm).unwrap();
+    module.verify_with_policy(FuelPolicy::Required).unwrap();
+    let mut store = RwasmStore::<()>::default();
+    // the entrypoint allocates the memories and initializes the active data segment
+    let instance = RwasmInstance::new(&mut store, ExecutionEngine::new(), module).unwrap();
+    instance.execute(&mut store, &[], &mut []).unwrap();
+
+    let mut memory0 = [0u8; 44];
+    store.memory_read_at(0, 0, &mut memory0).unwrap();
+    let word = |offset: usize| u32::from_le_bytes(memory0[offset..offset + 4].try_into().unwrap());
+    assert_eq!(word(0), 0x44);
+    assert_eq!(word(8), 0x05060708);
+    assert_eq!(word(12), 0x01020304);
+    assert_eq!(word(16), 1);
+    assert_eq!(word(20), u32::MAX);
+    assert_eq!(word(24), 2);
+    assert_eq!(word(28), 1);
+    assert_eq!(word(32), 0x5a5a5a5a);
+    assert_eq!(word(36), 1.5f32.to_bits());
+    assert_eq!(word(40), 0);
+
+    let mut memory1 = [0u8; 6];
+    store.memory_read_at(1, 200, &mut memory1).unwrap();
+    assert_eq!(memory1, [0xaa, 0xbb, 0x01, 0x02, 0x03, 0x04]);
+    store.memory_write_at(1, 200, &[0xff]).unwrap();
+    store.memory_read_at(1, 200, &mut memory1[..1]).unwrap();
+    assert_eq!(memory1[0], 0xff);
+    // the default memory isn't affected by writes into the other memory
+    store.memory_read(200, &mut memory1[..1]).unwrap();
+    assert_eq!(memory1[0], 0);
+    // memory 1 has 2 pages, memory 2 doesn't exist
+    let mut buffer = [0u8; 1];
+    assert_eq!(
+        store.memory_read_at(1, 2 * 65536, &mut buffer),
+        Err(TrapCode::MemoryOutOfBounds)
+    );
+    assert_eq!(
+        store.memory_read_at(2, 0, &mut buffer),
+        Err(TrapCode::MemoryOutOfBounds)
+    );
+}
+
+#[test]
+fn test_multi_memory_shares_page_budget() {
+    let wasm = wat::parse_str(
+        r#"
+(module
+  (memory $m0 1)
+  (memory $m1 1)
+  (func (export "main")
+    ;; the budget is 3 pages, both memories already use 2 of them
+    (i32.store $m0 (i32.const 0) (memory.grow $m1 (i32.const 2)))
+    (i32.store $m0 (i32.const 4) (memory.grow $m1 (i32.const 1)))
+    (i32.store $m0 (i32.const 8) (memory.grow $m0 (i32.const 1)))
+    (i32.store $m0 (i32.const 12) (memory.size $m1))
+  )
+)
+"#,
+    )
+    .unwrap();
+    let config = CompilationConfig::default().with_entrypoint_name("main".into());
+    let (module, _) = RwasmModule::compile(config, &wasm).unwrap();
+    let mut store = RwasmStore::new(
+        ImportLinker::default().into(),
+        (),
+        always_failing_syscall_handler,
+        None,
+        Some(3),
+    );
+    let instance = RwasmInstance::new(&mut store, ExecutionEngine::new(), module).unwrap();
+    instance.execute(&mut store, &[], &mut []).unwrap();
+
+    let mut memory0 = [0u8; 16];
+    store.memory_read_at(0, 0, &mut memory0).unwrap();
+    let word = |offset: usize| u32::from_le_bytes(memory0[offset..offset + 4].try_into().unwrap());
+    assert_eq!(word(0), u32::MAX);
+    assert_eq!(word(4), 1);
+    assert_eq!(word(8), u32::MAX);
+    assert_eq!(word(12), 2);
+    // the host can't grow any memory past the budget either
+    for memory in [0, 1, 2] {
+        assert_eq!(
+            store.grow_memory(memory, 1),
+            Err(TrapCode::MemoryOutOfBounds)
+        );
+    }
+    assert_eq!(store.grow_memory(2, 0), Ok(0));
+}
@@ -0,0 +1,862 @@
+mod common;
+
+use common::{compile_verified, execute_with_fuel};
+use rwasm::{CompilationConfig, RwasmModule, TrapCode, Value};
+
+fn execute(module: &RwasmModule, results: usize) -> Result<Vec<i64>, TrapCode> {
+    execute_with_fuel(module, &[], results, 10_000_000)
+}
+
+/// Evaluates both expressions with and without code snippets and compares the vectors.
+fn assert_simd(expr: &str, expected: &str) {
+    let wat = format!(
+        r#"
+(module
+  (memory 1)
+  (data (i32.const 0) "\00\01\02\03\04\05\06\07\08\09\0a\0b\0c\0d\0e\0f\80\81\82\83\84\85\86\87\f8\f9\fa\fb\fc\fd\fe\ff")
+  (func (export "main") (result i64 i64 i64 i64) (local $v v128) (local $e v128)
+    (local.set $v {expr})
+    (local.set $e {expected})
+    (i64x2.extract_lane 0 (local.get $v))
+    (i64x2.extract_lane 1 (local.get $v))
+    (i64x2.extract_lane 0 (local.get $e))
+    (i64x2.extract_lane 1 (local.get $e))))
+"#
+    );
+    for code_snippets in [true, false] {
+        let config = CompilationConfig::default().with_code_snippets(code_snippets);
+        let result = execute(&compile_verified(&wat, config), 4).unwrap();
+        assert_eq!(
+            result[0..2],
+            result[2..4],
+            "{expr} (code snippets: {code_snippets})"
+        );
+    }
+}
+
+/// Evaluates a scalar `i64` expression with and without code snippets.
+fn assert_scalar(expr: &str, expected: i64) {
+    let wat = format!(
+        r#"
+(module
+  (memory 1)
+  (func (export "main") (result i64)
+    {expr}))
+"#
+    );
+    for code_snippets in [true, false] {
+        let config = CompilationConfig::default().with_code_snippets(code_snippets);
+        let result = execute(&compile_verified(&wat, config), 1).unwrap();
+        assert_eq!(
+            result[0], expected,
+            "{expr} (code snippets: {code_snippets})"
+        );
+    }
+}
+
+const A8: &str = "(v128.const i8x16 1 -2 3 -4 5 -6 7 -8 127 -128 100 -100 0 -1 50 -50)";
+const B8: &str = "(v128.const i8x16 2 -2 -3 4 127 -128 -7 8 1 -1 100 100 0 1 -50 50)";
+const A16: &str = "(v128.const i16x8 1 -2 300 -400 32767 -32768 1000 -1)";
+const B16: &str = "(v128.const i16x8 2 -2 -300 400 1 -1 1000 1)";
+const A32: &str = "(v128.const i32x4 1 -2 0x7fffffff -0x80000000)";
+const B32: &str = "(v128.const i32x4 2 -2 1 -1)";
+const A64: &str = "(v128.const i64x2 0x1_0000_0001 -5)";
+const B64: &str = "(v128.const i64x2 0xffff_ffff 3)";
+
+#[test]
+fn test_constants_and_lanes() {
+    assert_simd(
+        "(v128.const i32x4 0x03020100 0x07060504 0x0b0a0908 0x0f0e0d0c)",
+        "(v128.const i8x16 0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15)",
+    );
+    assert_scalar(
+        &format!("(i64.extend_i32_s (i8x16.extract_lane_s 9 {A8}))"),
+        -128,
+    );
+    assert_scalar(
+        &format!("(i64.extend_i32_s (i8x16.extract_lane_u 15 {A8}))"),
+        206,
+    );
+    assert_scalar(
+        &format!("(i64.extend_i32_s (i16x8.extract_lane_s 5 {A16}))"),
+        -32768,
+    );
+    assert_scalar(
+        &format!("(i64.extend_i32_s (i16x8.extract_lane_u 7 {A16}))"),
+        65535,
+    );
+    assert_scalar(
+        &format!("(i64.extend_i32_s (i32x4.extract_lane 3 {A32}))"),
+        i32::MIN as i64,
+    );
+    assert_scalar(&format!("(i64x2.extract_lane 1 {A64})"), -5);
+    assert_scalar(
+        "(i64.reinterpret_f64 (f64x2.extract_lane 1 (v128.const f64x2 1.5 -2.5)))",
+        (-2.5f64).to_bits() as i64,
+    );
+    assert_scalar(
+        "(i64.extend_i32_u (i32.reinterpret_f32 (f32x4.extract_lane 2 (v128.const f32x4 1 2 3 4))))",
+        3f32.to_bits() as i64,
+    );
+    assert_simd(
+        &format!("(i8x16.replace_lane 3 {A8} (i32.const 0x1ff))"),
+        "(v128.const i8x16 1 -2 3 -1 5 -6 7 -8 127 -128 100 -100 0 -1 50 -50)",
+    );
+    assert_simd(
+        &format!("(i16x8.replace_lane 6 {A16} (i32.const 7))"),
+        "(v128.const i16x8 1 -2 300 -400 32767 -32768 7 -1)",
+    );
+    assert_simd(
+        &format!("(i32x4.replace_lane 1 {A32} (i32.const 9))"),
+        "(v128.const i32x4 1 9 0x7fffffff -0x80000000)",
+    );
+    assert_simd(
+        &format!("(i64x2.replace_lane 0 {A64} (i64.const -9))"),
+        "(v128.const i64x2 -9 -5)",
+    );
+    assert_simd(
+        "(f32x4.replace_lane 0 (v128.const f32x4 1 2 3 4) (f32.const 5))",
+        "(v128.const f32x4 5 2 3 4)",
+    );
+    assert_simd(
+        "(f64x2.replace_lane 1 (v128.const f64x2 1 2) (f64.const 5))",
+        "(v128.const f64x2 1 5)",
+    );
+    assert_simd(
+        "(i8x16.splat (i32.const 0x1fe))",
+        "(v128.const i8x16 -2 -2 -2 -2 -2 -2 -2 -2 -2 -2 -2 -2 -2 -2 -2 -2)",
+    );
+    assert_simd(
+        "(i16x8.splat (i32.const -3))",
+        "(v128.const i16x8 -3 -3 -3 -3 -3 -3 -3 -3)",
+    );
+    assert_simd("(i32x4.splat (i32.const 7))", "(v128.const i32x4 7 7 7 7)");
+    assert_simd("(i64x2.splat (i64.const -7))", "(v128.const i64x2 -7 -7)");
+    assert_simd(
+        "(f32x4.splat (f32.const 1.5))",
+        "(v128.const f32x4 1.5 1.5 1.5 1.5)",
+    );
+    assert_simd(
+        "(f64x2.splat (f64.const 1.5))",
+        "(v128.const f64x2 1.5 1.5)",
+    );
+}
+
+#[test]
+fn test_memory() {
+    assert_simd(
+        "(v128.load offset=1 (i32.const 15))",
+        "(v128.const i8x16 0x80 0x81 0x82 0x83 0x84 0x85 0x86 0x87 0xf8 0xf9 0xfa 0xfb 0xfc 0xfd 0xfe 0xff)",
+    );
+    assert_simd(
+        "(v128.load8x8_s (i32.const 14))",
+        "(v128.const i16x8 14 15 -128 -127 -126 -125 -124 -123)",
+    );
+    assert_simd(
+        "(v128.load8x8_u (i32.const 14))",
+        "(v128.const i16x8 14 15 128 129 130 131 132 133)",
+    );
+    assert_simd(
+        "(v128.load16x4_s (i32.const 22))",
+        "(v128.const i32x4 -30842 -1544 -1030 -516)",
+    );
+    assert_simd(
+        "(v128.load16x4_u (i32.const 24))",
+        "(v128.const i32x4 0xf9f8 0xfbfa 0xfdfc 0xfffe)",
+    );
+    assert_simd(
+        "(v128.load32x2_s (i32.const 24))",
+        "(v128.const i64x2 0xfffffffffbfaf9f8 0xfffffffffffefdfc)",
+    );
+    assert_simd(
+        "(v128.load32x2_u (i32.const 24))",
+        "(v128.const i64x2 0xfbfaf9f8 0xfffefdfc)",
+    );
+    assert_simd(
+        "(v128.load8_splat (i32.const 17))",
+        "(v128.const i8x16 0x81 0x81 0x81 0x81 0x81 0x81 0x81 0x81 0x81 0x81 0x81 0x81 0x81 0x81 0x81 0x81)",
+    );
+    assert_simd(
+        "(v128.load16_splat (i32.const 1))",
+        "(v128.const i16x8 0x201 0x201 0x201 0x201 0x201 0x201 0x201 0x201)",
+    );
+    assert_simd(
+        "(v128.load32_splat (i32.const 4))",
+        "(v128.const i32x4 0x07060504 0x07060504 0x07060504 0x07060504)",
+    );
+    assert_simd(
+        "(v128.load64_splat (i32.const 8))",
+        "(v128.const i64x2 0x0f0e0d0c0b0a0908 0x0f0e0d0c0b0a0908)",
+    );
+    assert_simd(
+        "(v128.load32_zero (i32.const 4))",
+        "(v128.const i32x4 0x07060504 0 0 0)",
+    );
+    assert_simd(
+        "(v128.load64_zero (i32.const 8))",
+        "(v128.const i64x2 0x0f0e0d0c0b0a0908 0)",
+    );
+    assert_simd(
+        &format!("(v128.load8_lane 5 (i32.const 31) {A8})"),
+        "(v128.const i8x16 1 -2 3 -4 5 -1 7 -8 127 -128 100 -100 0 -1 50 -50)",
+    );
+    assert_simd(
+        &format!("(v128.load16_lane 7 (i32.const 0) {A16})"),
+        "(v128.const i16x8 1 -2 300 -400 32767 -32768 1000 0x100)",
+    );
+    assert_simd(
+        &format!("(v128.load32_lane 2 (i32.const 4) {A32})"),
+        "(v128.const i32x4 1 -2 0x07060504 -0x80000000)",
+    );
+    assert_simd(
+        &format!("(v128.load64_lane 1 (i32.const 8) {A64})"),
+        "(v128.const i64x2 0x1_0000_0001 0x0f0e0d0c0b0a0908)",
+    );
+    assert_simd(
+        &format!("(v128.store offset=3 (i32.const 97) {A32}) (v128.load (i32.const 100))"),
+        A32,
+    );
+    assert_simd(
+        &format!(
+            "(v128.store8_lane 9 (i32.const 100) {A8})
+             (v128.store16_lane 5 (i32.const 101) {A16})
+             (v128.store32_lane 3 (i32.const 103) {A32})
+             (v128.store64_lane 0 (i32.const 107) {A64})
+             (v128.load (i32.const 100))"
+        ),
+        "(v128.const i8x16 0x80 0x00 0x80 0x00 0x00 0x00 0x80 0x01 0x00 0x00 0x00 0x01 0x00 0x00 0x00 0x00)",
+    );
+    // nothing is written by an out of bounds store
+    let module = compile_verified(
+        r#"
+(module
+  (memory 1)
+  (func (export "main") (result i64)
+    (v128.store (i32.const 65530) (v128.const i64x2 -1 -1))
+    (i64.const 0)))
+"#,
+        CompilationConfig::default(),
+    );
+    assert_eq!(execute(&module, 1), Err(TrapCode::MemoryOutOfBounds));
+}
+
+#[test]
+fn test_bitwise() {
+    assert_simd(
+        &format!("(v128.not {A32})"),
+        "(v128.const i32x4 -2 1 -0x80000000 0x7fffffff)",
+    );
+    assert_simd(
+        "(v128.and (v128.const i32x4 0xff00 0xf0 -1 0) (v128.const i32x4 0x0ff0 0xff 3 -1))",
+        "(v128.const i32x4 0x0f00 0xf0 3 0)",
+    );
+    assert_simd(
+        "(v128.or (v128.const i32x4 0xff00 0xf0 -1 0) (v128.const i32x4 0x0ff0 0xff 3 -1))",
+        "(v128.const i32x4 0xfff0 0xff -1 -1)",
+    );
+    assert_simd(
+        "(v128.xor (v128.const i32x4 0xff00 0xf0 -1 0) (v128.const i32x4 0x0ff0 0xff 3 -1))",
+        "(v128.const i32x4 0xf0f0 0x0f -4 -1)",
+    );
+    assert_simd(
+        "(v128.andnot (v128.const i32x4 0xff00 0xf0 -1 0) (v128.const i32x4 0x0ff0 0xff 3 -1))",
+        "(v128.const i32x4 0xf000 0 -4 0)",
+    );
+    assert_simd(
+        "(v128.bitselect (v128.const i32x4 0xffff 1 2 3) (v128.const i32x4 0xff0000 4 5 6) (v128.const i32x4 0xff00ff -1 0 0))",
+        "(v128.const i32x4 0xff 1 5 6)",
+    );
+    assert_scalar(
+        "(i64.extend_i32_u (v128.any_true (v128.const i32x4 0 0 0 0)))",
+        0,
+    );
+    assert_scalar(
+        "(i64.extend_i32_u (v128.any_true (v128.const i32x4 0 0 0 1)))",
+        1,
+    );
+    assert_simd(
+        &format!("(i8x16.shuffle 31 0 1 16 17 15 2 3 4 5 6 7 8 9 10 11 {A8} {B8})"),
+        "(v128.const i8x16 50 1 -2 2 -2 -50 3 -4 5 -6 7 -8 127 -128 100 -100)",
+    );
+    assert_simd(
+        "(i8x16.swizzle (v128.const i8x16 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25) (v128.const i8x16 0 15 7 8 16 255 3 4 5 6 9 10 11 12 13 14))",
+        "(v128.const i8x16 10 25 17 18 0 0 13 14 15 16 19 20 21 22 23 24)",
+    );
+}
+
+#[test]
+fn test_integer_compare() {
+    assert_simd(
+        &format!("(i8x16.eq {A8} {B8})"),
+        "(v128.const i8x16 0 -1 0 0 0 0 0 0 0 0 -1 0 -1 0 0 0)",
+    );
+    assert_simd(
+        &format!("(i8x16.ne {A8} {B8})"),
+        "(v128.const i8x16 -1 0 -1 -1 -1 -1 -1 -1 -1 -1 0 -1 0 -1 -1 -1)",
+    );
+    assert_simd(
+        &format!("(i8x16.lt_s {A8} {B8})"),
+        "(v128.const i8x16 -1 0 0 -1 -1 0 0 -1 0 -1 0 -1 0 -1 0 -1)",
+    );
+    assert_simd(
+        &format!("(i8x16.lt_u {A8} {B8})"),
+        "(v128.const i8x16 -1 0 -1 0 -1 0 -1 0 0 -1 0 0 0 0 -1 0)",
+    );
+    assert_simd(
+        &format!("(i8x16.ge_s {A8} {B8})"),
+        "(v128.const i8x16 0 -1 -1 0 0 -1 -1 0 -1 0 -1 0 -1 0 -1 0)",
+    );
+    assert_simd(
+        &format!("(i16x8.gt_s {A16} {B16})"),
+        "(v128.const i16x8 0 0 -1 0 -1 0 0 0)",
+    );
+    assert_simd(
+        &format!("(i16x8.gt_u {A16} {B16})"),
+        "(v128.const i16x8 0 0 0 -1 -1 0 0 -1)",
+    );
+    assert_simd(
+        &format!("(i16x8.le_u {A16} {B16})"),
+        "(v128.const i16x8 -1 -1 -1 0 0 -1 -1 0)",
+    );
+    assert_simd(
+        &format!("(i32x4.lt_s {A32} {B32})"),
+        "(v128.const i32x4 -1 0 0 -1)",
+    );
+    assert_simd(
+        &format!("(i32x4.lt_u {A32} {B32})"),
+        "(v128.const i32x4 -1 0 0 -1)",
+    );
+    assert_simd(
+        &format!("(i32x4.ge_u {A32} {B32})"),
+        "(v128.const i32x4 0 -1 -1 0)",
+    );
+    assert_simd(
+        &format!("(i32x4.eq {A32} {B32})"),
+        "(v128.const i32x4 0 -1 0 0)",
+    );
+    assert_simd(&format!("(i64x2.eq {A64} {B64})"), "(v128.const i64x2 0 0)");
+    assert_simd(
+        &format!("(i64x2.ne {A64} {B64})"),
+        "(v128.const i64x2 -1 -1)",
+    );
+    assert_simd(
+        &format!("(i64x2.gt_s {A64} {B64})"),
+        "(v128.const i64x2 -1 0)",
+    );
+    assert_simd(
+        &format!("(i64x2.le_s {A64} {B64})"),
+        "(v128.const i64x2 0 -1)",
+    );
+}
+
+#[test]
+fn test_integer_arithmetic() {
+    assert_simd(
+        &format!("(i8x16.add {A8} {B8})"),
+        "(v128.const i8x16 3 -4 0 0 -124 122 0 0 -128 127 -56 0 0 0 0 0)",
+    );
+    assert_simd(
+        &format!("(i8x16.add_sat_s {A8} {B8})"),
+        "(v128.const i8x16 3 -4 0 0 127 -128 0 0 127 -128 127 0 0 0 0 0)",
+    );
+    assert_simd(
+        &format!("(i8x16.add_sat_u {A8} {B8})"),
+        "(v128.const i8x16 3 -1 -1 -1 -124 -1 -1 -1 -128 -1 -56 -1 0 -1 -1 -1)",
+    );
+    assert_simd(
+        &format!("(i8x16.sub_sat_s {A8} {B8})"),
+        "(v128.const i8x16 -1 0 6 -8 -122 122 14 -16 126 -127 0 -128 0 -2 100 -100)",
+    );
+    assert_simd(
+        &format!("(i8x16.sub_sat_u {A8} {B8})"),
+        "(v128.const i8x16 0 0 0 -8 0 122 0 -16 126 0 0 56 0 -2 0 -100)",
+    );
+    assert_simd(
+        &format!("(i8x16.min_s {A8} {B8})"),
+        "(v128.const i8x16 1 -2 -3 -4 5 -128 -7 -8 1 -128 100 -100 0 -1 -50 -50)",
+    );
+    assert_simd(
+        &format!("(i8x16.max_u {A8} {B8})"),
+        "(v128.const i8x16 2 -2 -3 -4 127 -6 -7 -8 127 -1 100 -100 0 -1 -50 -50)",
+    );
+    assert_simd(
+        &format!("(i8x16.avgr_u {A8} {B8})"),
+        "(v128.const i8x16 2 -2 -128 -128 66 -67 -128 -128 64 -64 100 -128 0 -128 -128 -128)",
+    );
+    assert_simd(
+        &format!("(i8x16.abs {A8})"),
+        "(v128.const i8x16 1 2 3 4 5 6 7 8 127 -128 100 100 0 1 50 50)",
+    );
+    assert_simd(
+        &format!("(i8x16.neg {A8})"),
+        "(v128.const i8x16 -1 2 -3 4 -5 6 -7 8 -127 -128 -100 100 0 1 -50 50)",
+    );
+    assert_simd(
+        "(i8x16.popcnt (v128.const i8x16 0 1 3 7 15 31 63 127 -1 -128 0x55 0xaa 2 4 8 16))",
+        "(v128.const i8x16 0 1 2 3 4 5 6 7 8 1 4 4 1 1 1 1)",
+    );
+    assert_simd(
+        &format!("(i16x8.mul {A16} {B16})"),
+        "(v128.const i16x8 2 4 -24464 -28928 32767 -32768 16960 -1)",
+    );
+    assert_simd(
+        &format!("(i16x8.add_sat_s {A16} {B16})"),
+        "(v128.const i16x8 3 -4 0 0 32767 -32768 2000 0)",
+    );
+    assert_simd(
+        &format!("(i16x8.sub_sat_u {A16} {B16})"),
+        "(v128.const i16x8 0 0 0 -800 32766 0 0 -2)",
+    );
+    assert_simd(
+        "(i16x8.q15mulr_sat_s (v128.const i16x8 -32768 16384 -16384 100 0 32767 1 -1) (v128.const i16x8 -32768 16384 16384 200 5 32767 1 1))",
+        "(v128.const i16x8 32767 8192 -8192 1 0 32766 0 0)",
+    );
+    assert_simd(
+        &format!("(i32x4.add {A32} {B32})"),
+        "(v128.const i32x4 3 -4 -0x80000000 0x7fffffff)",
+    );
+    assert_simd(
+        &format!("(i32x4.sub {A32} {B32})"),
+        "(v128.const i32x4 -1 0 0x7ffffffe 0x80000001)",
+    );
+    assert_simd(
+        &format!("(i32x4.mul {A32} {B32})"),
+        "(v128.const i32x4 2 4 0x7fffffff -0x80000000)",
+    );
+    assert_simd(
+        &format!("(i32x4.min_u {A32} {B32})"),
+        "(v128.const i32x4 1 -2 1 -0x80000000)",
+    );
+    assert_simd(
+        &format!("(i32x4.max_s {A32} {B32})"),
+        "(v128.const i32x4 2 -2 0x7fffffff -1)",
+    );
+    assert_simd(
+        &format!("(i32x4.abs {A32})"),
+        "(v128.const i32x4 1 2 0x7fffffff -0x80000000)",
+    );
+    assert_simd(
+        &format!("(i32x4.neg {A32})"),
+        "(v128.const i32x4 -1 2 -0x7fffffff -0x80000000)",
+    );
+    assert_simd(
+        &format!("(i32x4.dot_i16x8_s {A16} {B16})"),
+        "(v128.const i32x4 6 -250000 65535 999999)",
+    );
+    assert_simd(
+        &format!("(i64x2.add {A64} {B64})"),
+        "(v128.const i64x2 0x2_0000_0000 -2)",
+    );
+    assert_simd(
+        &format!("(i64x2.sub {A64} {B64})"),
+        "(v128.const i64x2 0x2 -8)",
+    );
+    assert_simd(
+        &format!("(i64x2.mul {A64} {B64})"),
+        "(v128.const i64x2 -1 -15)",
+    );
+    assert_simd(
+        &format!("(i64x2.neg {A64})"),
+        "(v128.const i64x2 -0x1_0000_0001 5)",
+    );
+    assert_simd(
+        &format!("(i64x2.abs {A64})"),
+        "(v128.const i64x2 0x1_0000_0001 5)",
+    );
+}
+
+#[test]
+fn test_shifts() {
+    assert_simd(
+        &format!("(i8x16.shl {A8} (i32.const 9))"),
+        "(v128.const i8x16 2 -4 6 -8 10 -12 14 -16 -2 0 -56 56 0 -2 100 -100)",
+    );
+    assert_simd(
+        &format!("(i8x16.shr_s {A8} (i32.const 2))"),
+        "(v128.const i8x16 0 -1 0 -1 1 -2 1 -2 31 -32 25 -25 0 -1 12 -13)",
+    );
+    assert_simd(
+        &format!("(i8x16.shr_u {A8} (i32.const 2))"),
+        "(v128.const i8x16 0 63 0 63 1 62 1 62 31 32 25 39 0 63 12 51)",
+    );
+    assert_simd(
+        &format!("(i16x8.shr_s {A16} (i32.const 17))"),
+        "(v128.const i16x8 0 -1 150 -200 16383 -16384 500 -1)",
+    );
+    assert_simd(
+        &format!("(i16x8.shr_u {A16} (i32.const 1))"),
+        "(v128.const i16x8 0 32767 150 32568 16383 16384 500 32767)",
+    );
+    assert_simd(
+        &format!("(i32x4.shl {A32} (i32.const 33))"),
+        "(v128.const i32x4 2 -4 -2 0)",
+    );
+    assert_simd(
+        &format!("(i32x4.shr_s {A32} (i32.const 1))"),
+        "(v128.const i32x4 0 -1 0x3fffffff -0x40000000)",
+    );
+    assert_simd(
+        &format!("(i32x4.shr_u {A32} (i32.const 1))"),
+        "(v128.const i32x4 0 0x7fffffff 0x3fffffff 0x40000000)",
+    );
+    assert_simd(
+        &format!("(i64x2.shl {A64} (i32.const 65))"),
+        "(v128.const i64x2 0x2_0000_0002 -10)",
+    );
+    assert_simd(
+        &format!("(i64x2.shr_s {A64} (i32.const 1))"),
+        "(v128.const i64x2 0x8000_0000 -3)",
+    );
+    assert_simd(
+        &format!("(i64x2.shr_u {A64} (i32.const 32))"),
+        "(v128.const i64x2 1 0xffff_ffff)",
+    );
+}
+
+#[test]
+fn test_lane_reductions() {
+    assert_scalar(&format!("(i64.extend_i32_u (i8x16.all_true {A8}))"), 0);
+    assert_scalar(&format!("(i64.extend_i32_u (i16x8.all_true {A16}))"), 1);
+    assert_scalar(&format!("(i64.extend_i32_u (i32x4.all_true {A32}))"), 1);
+    assert_scalar(
+        "(i64.extend_i32_u (i32x4.all_true (v128.const i32x4 1 1 0 1)))",
+        0,
+    );
+    assert_scalar(&format!("(i64.extend_i32_u (i64x2.all_true {A64}))"), 1);
+    assert_scalar(
+        "(i64.extend_i32_u (i64x2.all_true (v128.const i64x2 0 1)))",
+        0,
+    );
+    assert_scalar(
+        &format!("(i64.extend_i32_u (i8x16.bitmask {A8}))"),
+        0b1010_1010_1010_1010,
+    );
+    assert_scalar(
+        &format!("(i64.extend_i32_u (i16x8.bitmask {A16}))"),
+        0b1010_1010,
+    );
+    assert_scalar(&format!("(i64.extend_i32_u (i32x4.bitmask {A32}))"), 0b1010);
+    assert_scalar(&format!("(i64.extend_i32_u (i64x2.bitmask {A64}))"), 0b10);
+}
+
+#[test]
+fn test_widening_and_narrowing() {
+    assert_simd(
+        &format!("(i8x16.narrow_i16x8_s {A16} {B16})"),
+        "(v128.const i8x16 1 -2 127 -128 127 -128 127 -1 2 -2 -128 127 1 -1 127 1)",
+    );
+    assert_simd(
+        &format!("(i8x16.narrow_i16x8_u {A16} {B16})"),
+        "(v128.const i8x16 1 0 255 0 255 0 255 0 2 0 0 255 1 0 255 1)",
+    );
+    assert_simd(
+        &format!("(i16x8.narrow_i32x4_s {A32} {B32})"),
+        "(v128.const i16x8 1 -2 32767 -32768 2 -2 1 -1)",
+    );
+    assert_simd(
+        &format!("(i16x8.narrow_i32x4_u {A32} {B32})"),
+        "(v128.const i16x8 1 0 65535 0 2 0 1 0)",
+    );
+    assert_simd(
+        &format!("(i16x8.extend_low_i8x16_s {A8})"),
+        "(v128.const i16x8 1 -2 3 -4 5 -6 7 -8)",
+    );
+    assert_simd(
+        &format!("(i16x8.extend_high_i8x16_u {A8})"),
+        "(v128.const i16x8 127 128 100 156 0 255 50 206)",
+    );
+    assert_simd(
+        &format!("(i32x4.extend_high_i16x8_s {A16})"),
+        "(v128.const i32x4 32767 -32768 1000 -1)",
+    );
+    assert_simd(
+        &format!("(i32x4.extend_low_i16x8_u {A16})"),
+        "(v128.const i32x4 1 65534 300 65136)",
+    );
+    assert_simd(
+        &format!("(i64x2.extend_high_i32x4_s {A32})"),
+        "(v128.const i64x2 0x7fffffff -0x80000000)",
+    );
+    assert_simd(
+        &format!("(i64x2.extend_low_i32x4_u {A32})"),
+        "(v128.const i64x2 1 0xfffffffe)",
+    );
+    assert_simd(
+        &format!("(i16x8.extadd_pairwise_i8x16_s {A8})"),
+        "(v128.const i16x8 -1 -1 -1 -1 -1 0 -1 0)",
+    );
+    assert_simd(
+        &format!("(i16x8.extadd_pairwise_i8x16_u {A8})"),
+        "(v128.const i16x8 255 255 255 255 255 256 255 256)",
+    );
+    assert_simd(
+        &format!("(i32x4.extadd_pairwise_i16x8_s {A16})"),
+        "(v128.const i32x4 -1 -100 -1 999)",
+    );
+    assert_simd(
+        &format!("(i32x4.extadd_pairwise_i16x8_u {A16})"),
+        "(v128.const i32x4 65535 65436 65535 66535)",
+    );
+    assert_simd(
+        &format!("(i16x8.extmul_low_i8x16_s {A8} {B8})"),
+        "(v128.const i16x8 2 4 -9 -16 635 768 -49 -64)",
+    );
+    assert_simd(
+        &format!("(i16x8.extmul_high_i8x16_u {A8} {B8})"),
+        "(v128.const i16x8 127 32640 10000 15600 0 255 10300 10300)",
+    );
+    assert_simd(
+        &format!("(i32x4.extmul_low_i16x8_s {A16} {B16})"),
+        "(v128.const i32x4 2 4 -90000 -160000)",
+    );
+    assert_simd(
+        &format!("(i32x4.extmul_high_i16x8_u {A16} {B16})"),
+        "(v128.const i32x4 32767 2147450880 1000000 65535)",
+    );
+    assert_simd(
+        &format!("(i64x2.extmul_low_i32x4_s {A32} {B32})"),
+        "(v128.const i64x2 2 4)",
+    );
+    assert_simd(
+        &format!("(i64x2.extmul_high_i32x4_s {A32} {B32})"),
+        "(v128.const i64x2 0x7fffffff 0x80000000)",
+    );
+    assert_simd(
+        &format!("(i64x2.extmul_high_i32x4_u {A32} {B32})"),
+        "(v128.const i64x2 0x7fffffff 0x7fffffff_80000000)",
+    );
+    assert_simd(
+        &format!("(i64x2.extmul_low_i32x4_u {A32} {B32})"),
+        "(v128.const i64x2 2 0xfffffffc_00000004)",
+    );
+}
+
+#[test]
+fn test_float() {
+    const F32A: &str = "(v128.const f32x4 1.5 -2.5 nan -0)";
+    const F32B: &str = "(v128.const f32x4 2 -2.5 1 0)";
+    const F64A: &str = "(v128.const f64x2 1.5 -0)";
+    const F64B: &str = "(v128.const f64x2 -2.5 0)";
+    assert_simd(
+        &format!("(f32x4.eq {F32A} {F32B})"),
+        "(v128.const i32x4 0 -1 0 -1)",
+    );
+    assert_simd(
+        &format!("(f32x4.ne {F32A} {F32B})"),
+        "(v128.const i32x4 -1 0 -1 0)",
+    );
+    assert_simd(
+        &format!("(f32x4.lt {F32A} {F32B})"),
+        "(v128.const i32x4 -1 0 0 0)",
+    );
+    assert_simd(
+        &format!("(f32x4.ge {F32A} {F32B})"),
+        "(v128.const i32x4 0 -1 0 -1)",
+    );
+    assert_simd(
+        &format!("(f64x2.gt {F64A} {F64B})"),
+        "(v128.const i64x2 -1 0)",
+    );
+    assert_simd(
+        &format!("(f64x2.le {F64A} {F64B})"),
+        "(v128.const i64x2 0 -1)",
+    );
+    assert_simd(
+        "(f32x4.add (v128.const f32x4 1.5 -2.5 1e10 0.25) (v128.const f32x4 2 -2.5 1 0.5))",
+        "(v128.const f32x4 3.5 -5 1e10 0.75)",
+    );
+    assert_simd(
+        "(f32x4.sub (v128.const f32x4 1.5 -2.5 3 0.25) (v128.const f32x4 2 -2.5 1 0.5))",
+        "(v128.const f32x4 -0.5 0 2 -0.25)",
+    );
+    assert_simd(
+        "(f32x4.mul (v128.const f32x4 1.5 -2.5 3 0.25) (v128.const f32x4 2 -2.5 1 0.5))",
+        "(v128.const f32x4 3 6.25 3 0.125)",
+    );
+    assert_simd(
+        "(f32x4.div (v128.const f32x4 1.5 -2.5 3 0.25) (v128.const f32x4 2 -2.5 1 0.5))",
+        "(v128.const f32x4 0.75 1 3 0.5)",
+    );
+    assert_simd(
+        "(f32x4.sqrt (v128.const f32x4 4 2.25 0 16))",
+        "(v128.const f32x4 2 1.5 0 4)",
+    );
+    assert_simd(
+        "(f32x4.min (v128.const f32x4 1 -0 3 -4) (v128.const f32x4 2 0 -3 4))",
+        "(v128.const f32x4 1 -0 -3 -4)",
+    );
+    assert_simd(
+        "(f32x4.max (v128.const f32x4 1 -0 3 -4) (v128.const f32x4 2 0 -3 4))",
+        "(v128.const f32x4 2 0 3 4)",
+    );
+    assert_simd(
+        "(f32x4.pmin (v128.const f32x4 1 -0 3 -4) (v128.const f32x4 2 0 -3 4))",
+        "(v128.const f32x4 1 -0 -3 -4)",
+    );
+    assert_simd(
+        "(f32x4.pmax (v128.const f32x4 1 -0 3 -4) (v128.const f32x4 2 0 -3 4))",
+        "(v128.const f32x4 2 -0 3 4)",
+    );
+    assert_simd(
+        "(f32x4.abs (v128.const f32x4 -1 2 -0 -3))",
+        "(v128.const f32x4 1 2 0 3)",
+    );
+    assert_simd(
+        "(f32x4.neg (v128.const f32x4 -1 2 -0 0))",
+        "(v128.const f32x4 1 -2 0 -0)",
+    );
+    assert_simd(
+        "(f32x4.ceil (v128.const f32x4 1.5 -1.5 2 -0.5))",
+        "(v128.const f32x4 2 -1 2 -0)",
+    );
+    assert_simd(
+        "(f32x4.floor (v128.const f32x4 1.5 -1.5 2 -0.5))",
+        "(v128.const f32x4 1 -2 2 -1)",
+    );
+    assert_simd(
+        "(f32x4.trunc (v128.const f32x4 1.5 -1.5 2 -0.5))",
+        "(v128.const f32x4 1 -1 2 -0)",
+    );
+    assert_simd(
+        "(f32x4.nearest (v128.const f32x4 1.5 -2.5 2.6 -0.5))",
+        "(v128.const f32x4 2 -2 3 -0)",
+    );
+    assert_simd(
+        "(f64x2.add (v128.const f64x2 1.5 1e300) (v128.const f64x2 -2.5 1e300))",
+        "(v128.const f64x2 -1 2e300)",
+    );
+    assert_simd(
+        "(f64x2.sub (v128.const f64x2 1.5 0.25) (v128.const f64x2 -2.5 1))",
+        "(v128.const f64x2 4 -0.75)",
+    );
+    assert_simd(
+        "(f64x2.mul (v128.const f64x2 1.5 0.25) (v128.const f64x2 -2.5 1))",
+        "(v128.const f64x2 -3.75 0.25)",
+    );
+    assert_simd(
+        "(f64x2.div (v128.const f64x2 1.5 0.25) (v128.const f64x2 -2.5 1))",
+        "(v128.const f64x2 -0.6 0.25)",
+    );
+    assert_simd(
+        "(f64x2.sqrt (v128.const f64x2 2.25 64))",
+        "(v128.const f64x2 1.5 8)",
+    );
+    assert_simd(
+        &format!("(f64x2.min {F64A} {F64B})"),
+        "(v128.const f64x2 -2.5 -0)",
+    );
+    assert_simd(
+        &format!("(f64x2.max {F64A} {F64B})"),
+        "(v128.const f64x2 1.5 0)",
+    );
+    assert_simd(
+        &format!("(f64x2.pmin {F64A} {F64B})"),
+        "(v128.const f64x2 -2.5 -0)",
+    );
+    assert_simd(
+        &format!("(f64x2.pmax {F64A} {F64B})"),
+        "(v128.const f64x2 1.5 -0)",
+    );
+    assert_simd(&format!("(f64x2.abs {F64B})"), "(v128.const f64x2 2.5 0)");
+    assert_simd(&format!("(f64x2.neg {F64B})"), "(v128.const f64x2 2.5 -0)");
+    assert_simd(
+        "(f64x2.ceil (v128.const f64x2 1.5 -1.5))",
+        "(v128.const f64x2 2 -1)",
+    );
+    assert_simd(
+        "(f64x2.floor (v128.const f64x2 1.5 -1.5))",
+        "(v128.const f64x2 1 -2)",
+    );
+    assert_simd(
+        "(f64x2.trunc (v128.const f64x2 1.5 -1.5))",
+        "(v128.const f64x2 1 -1)",
+    );
+    assert_simd(
+        "(f64x2.nearest (v128.const f64x2 2.5 -3.5))",
+        "(v128.const f64x2 2 -4)",
+    );
+}
+
+#[test]
+fn test_float_conversions() {
+    assert_simd(
+        "(i32x4.trunc_sat_f32x4_s (v128.const f32x4 1.5 -2.5 1e10 nan))",
+        "(v128.const i32x4 1 -2 0x7fffffff 0)",
+    );
+    assert_simd(
+        "(i32x4.trunc_sat_f32x4_u (v128.const f32x4 1.5 -2.5 1e10 3e9))",
+        "(v128.const i32x4 1 0 -1 3000000000)",
+    );
+    assert_simd(
+        "(f32x4.convert_i32x4_s (v128.const i32x4 1 -2 0x7fffffff 0))",
+        "(v128.const f32x4 1 -2 2147483648 0)",
+    );
+    assert_simd(
+        "(f32x4.convert_i32x4_u (v128.const i32x4 1 -2 0x7fffffff 0))",
+        "(v128.const f32x4 1 4294967296 2147483648 0)",
+    );
+    assert_simd(
+        "(i32x4.trunc_sat_f64x2_s_zero (v128.const f64x2 -1e20 -3.9))",
+        "(v128.const i32x4 -0x80000000 -3 0 0)",
+    );
+    assert_simd(
+        "(i32x4.trunc_sat_f64x2_u_zero (v128.const f64x2 -1 4e9))",
+        "(v128.const i32x4 0 4000000000 0 0)",
+    );
+    assert_simd(
+        "(f64x2.convert_low_i32x4_s (v128.const i32x4 -1 7 5 5))",
+        "(v128.const f64x2 -1 7)",
+    );
+    assert_simd(
+        "(f64x2.convert_low_i32x4_u (v128.const i32x4 -1 7 5 5))",
+        "(v128.const f64x2 4294967295 7)",
+    );
+    assert_simd(
+        "(f32x4.demote_f64x2_zero (v128.const f64x2 1.5 -1e300))",
+        "(v128.const f32x4 1.5 -inf 0 0)",
+    );
+    assert_simd(
+        "(f64x2.promote_low_f32x4 (v128.const f32x4 1.5 -0.25 7 7))",
+        "(v128.const f64x2 1.5 -0.25)",
+    );
+}
+
+#[test]
+fn test_v128_values() {
+    // `v128` locals, parameters, results, blocks and `select` take four stack slots
+    let module = compile_verified(
+        r#"
+(module
+  (func $swap (param v128 i32 v128) (result v128 v128)
+    (local v128)
+    (local.set 3 (local.get 0))
+    (local.get 2)
+    (local.tee 3 (i32x4.add (local.get 3) (i32x4.splat (local.get 1))))
+    (local.get 3)
+    drop)
+  (func (export "main") (param i32) (result i64 i64)
+    (local $v v128)
+    (call $swap
+      (v128.const i32x4 1 2 3 4)
+      (i32.const 10)
+      (v128.const i32x4 5 6 7 8))
+    (local.set $v)
+    (block (param v128) (result v128)
+      (br_if 0 (local.get 0))
+      (drop)
+      (local.get $v))
+    (select (local.get $v) (local.get 0))
+    (local.set $v)
+    (i64x2.extract_lane 0 (local.get $v))
+    (i64x2.extract_lane 1 (local.get $v))))
+"#,
+        CompilationConfig::default(),
+    );
+    let run = |param: i32| {
+        let result = execute_with_fuel(&module, &[Value::I32(param)], 2, 1_000_000).unwrap();
+        (result[0], result[1])
+    };
+    // `$swap` returns (5 6 7 8) and (11 12 13 14)
+    assert_eq!(run(1), (6 << 32 | 5, 8 << 32 | 7));
+    assert_eq!(run(0), (12 << 32 | 11, 14 << 32 | 13));
+}
@@ -0,0 +1,902 @@
+use rand::Rng;
+use rwasm::{
+    intrinsic::Intrinsic, CallStack, CompilationConfig, ExecutionEngine, FuelPolicy, ImportLinker,
+    ImportName, InstructionSet, Opcode, RwasmExecutor, RwasmModule, RwasmModuleBuilder, RwasmStore,
+    TrapCode, ValType, Value, ValueStack,
+};
+use std::sync::Arc;
+
+const COMPARE_MASKS: [(&str, u32); 6] = [
+    ("eq", 0b0010),
+    ("ne", 0b1101),
+    ("lt", 0b0001),
+    ("gt", 0b0100),
+    ("le", 0b0011),
+    ("ge", 0b0110),
+];
+
+type Emitter = fn(&mut InstructionSet);
+
+fn run_soft(
+    mut is: InstructionSet,
+    inputs: &[u32],
+    msh_allowed: u32,
+) -> Result<Vec<u32>, TrapCode> {
+    is.op_return();
+    let rwasm_module = RwasmModuleBuilder::new(is).build();
+    let mut value_stack = ValueStack::default();
+    value_stack.reserve(32)?;
+    let mut call_stack = CallStack::default();
+    let mut store = RwasmStore::<()>::default();
+    for i in inputs {
+        value_stack.push((*i).into());
+    }
+    let mut executor =
+        RwasmExecutor::entrypoint(&rwasm_module, &mut value_stack, &mut call_stack, &mut store);
+    executor.run_with_stack_check()?;
+    let output = value_stack
+        .as_slice()
+        .iter()
+        .map(|v| v.as_u32())
+        .collect::<Vec<_>>();
+    let msh = (value_stack.max_stack_height() - inputs.len()) as u32;
+    assert!(msh <= msh_allowed, "MSH: {msh} <= {msh_allowed}");
+    Ok(output)
+}
+
+fn emit(emitters: &[Emitter]) -> InstructionSet {
+    let mut is = InstructionSet::new();
+    for emitter in emitters {
+        emitter(&mut is);
+    }
+    is
+}
+
+fn split(v: u64) -> [u32; 2] {
+    [v as u32, (v >> 32) as u32]
+}
+
+fn join(output: &[u32]) -> u64 {
+    assert_eq!(output.len(), 2);
+    (output[1] as u64) << 32 | output[0] as u64
+}
+
+fn assert_f32(op: &str, inputs: &[u32], actual: u32, expected: f32) {
+    if expected.is_nan() {
+        assert!(
+            f32::from_bits(actual).is_nan(),
+            "{op}({inputs:x?}) = {actual:#x}, expected NaN"
+        );
+    } else {
+        assert_eq!(
+            actual,
+            expected.to_bits(),
+            "{op}({inputs:x?}) = {actual:#x}, expected {expected:?}"
+        );
+    }
+}
+
+fn assert_f64(op: &str, inputs: &[u32], actual: u64, expected: f64) {
+    if expected.is_nan() {
+        assert!(
+            f64::from_bits(actual).is_nan(),
+            "{op}({inputs:x?}) = {actual:#x}, expected NaN"
+        );
+    } else {
+        assert_eq!(
+            actual,
+            expected.to_bits(),
+            "{op}({inputs:x?}) = {actual:#x}, expected {expected:?}"
+        );
+    }
+}
+
+/// Wasm `min`/`max` semantics: NaN is propagated (`None`) and `-0 < +0`.
+fn wasm_min_max<T: PartialOrd + Copy>(
+    a: T,
+    b: T,
+    is_max: bool,
+    is_sign_negative: fn(T) -> bool,
+) -> Option<T> {
+    match a.partial_cmp(&b)? {
+        core::cmp::Ordering::Less => Some(if is_max { b } else { a }),
+        core::cmp::Ordering::Greater => Some(if is_max { a } else { b }),
+        // equal values differ only for zeros of different signs
+        core::cmp::Ordering::Equal => Some(if is_max == is_sign_negative(a) { b } else { a }),
+    }
+}
+
+fn compare_bit<T: PartialOrd>(a: T, b: T) -> u32 {
+    match a.partial_cmp(&b) {
+        Some(core::cmp::Ordering::Less) => 0,
+        Some(core::cmp::Ordering::Equal) => 1,
+        Some(core::cmp::Ordering::Greater) => 2,
+        None => 3,
+    }
+}
+
+/// The expected result of a truncation with the given flags, `value` is an exact integer or NaN.
+fn expected_to_int(value: f64, flags: u32) -> Result<u64, TrapCode> {
+    let (is_signed, is_i64, is_saturating) = (flags & 1 != 0, flags & 2 != 0, flags & 4 != 0);
+    let saturated = match (is_signed, is_i64) {
+        (true, true) => value as i64 as u64,
+        (false, true) => value as u64,
+        (true, false) => value as i32 as u32 as u64,
+        (false, false) => value as u32 as u64,
+    };
+    if is_saturating {
+        return Ok(saturated);
+    }
+    if value.is_nan() {
+        return Err(TrapCode::BadConversionToInteger);
+    }
+    let (min, max) = match (is_signed, is_i64) {
+        (true, true) => (-9223372036854775808.0, 9223372036854775808.0),
+        (false, true) => (0.0, 18446744073709551616.0),
+        (true, false) => (-2147483648.0, 2147483648.0),
+        (false, false) => (0.0, 4294967296.0),
+    };
+    if value < min || value >= max {
+        return Err(TrapCode::IntegerOverflow);
+    }
+    Ok(saturated)
+}
+
+fn f32_values() -> Vec<f32> {
+    let mut values = vec![
+        0.0,
+        -0.0,
+        1.0,
+        -1.0,
+        0.5,
+        -0.5,
+        1.5,
+        2.5,
+        -2.5,
+        3.0,
+        0.1,
+        1.0 / 3.0,
+        1e10,
+        -1e-10,
+        2147483648.0,
+        -2147483648.0,
+        4294967296.0,
+        9223372036854775808.0,
+        -9223372036854775808.0,
+        16777216.0,
+        16777217.0,
+        f32::MIN_POSITIVE,
+        -f32::MIN_POSITIVE,
+        f32::from_bits(1),
+        f32::from_bits(0x807fffff),
+        f32::from_bits(0x00400000),
+        f32::MAX,
+        f32::MIN,
+        f32::EPSILON,
+        f32::INFINITY,
+        f32::NEG_INFINITY,
+        f32::NAN,
+        f32::from_bits(0xffc00001),
+        f32::from_bits(0x7f800001),
+    ];
+    let mut rng = rand::rng();
+    for _ in 0..24 {
+        let value = f32::from_bits(rng.random());
+        // a neighbour of the opposite sign for the cancellation in `add`
+        values.push(value);
+        values.push(-f32::from_bits(value.to_bits() ^ 1));
+    }
+    for _ in 0..8 {
+        values.push(rng.random_range(-100.0..100.0));
+    }
+    values
+}
+
+fn f64_values() -> Vec<f64> {
+    let mut values = vec![
+        0.0,
+        -0.0,
+        1.0,
+        -1.0,
+        0.5,
+        -0.5,
+        1.5,
+        2.5,
+        -2.5,
+        3.0,
+        0.1,
+        1.0 / 3.0,
+        1e100,
+        -1e-100,
+        2147483648.0,
+        -2147483649.0,
+        4294967296.0,
+        9223372036854775808.0,
+        -9223372036854775808.0,
+        18446744073709551616.0,
+        9007199254740993.0,
+        f64::MIN_POSITIVE,
+        -f64::MIN_POSITIVE,
+        f64::from_bits(1),
+        f64::from_bits(0x800fffffffffffff),
+        f64::from_bits(0x0008000000000000),
+        f64::MAX,
+        f64::MIN,
+        f64::EPSILON,
+        f64::INFINITY,
+        f64::NEG_INFINITY,
+        f64::NAN,
+        f64::from_bits(0xfff8000000000001),
+        f64::from_bits(0x7ff0000000000001),
+        f32::MAX as f64,
+        f32::MIN_POSITIVE as f64 / 3.0,
+        3.4028235677973366e38,
+    ];
+    let mut rng = rand::rng();
+    for _ in 0..24 {
+        let value = f64::from_bits(rng.random());
+        values.push(value);
+        values.push(-f64::from_bits(value.to_bits() ^ 1));
+    }
+    for _ in 0..8 {
+        values.push(rng.random_range(-100.0..100.0));
+    }
+    values
+}
+
+fn int_values() -> Vec<u64> {
+    let mut values = vec![
+        0,
+        1,
+        u64::MAX,
+        i64::MIN as u64,
+        i64::MAX as u64,
+        i32::MIN as u64,
+        i32::MAX as u64,
+        u32::MAX as u64,
+        (1 << 24) + 1,
+        (1 << 53) + 1,
+        (1 << 63) + (1 << 10),
+        0x0123456789abcdef,
+    ];
+    let mut rng = rand::rng();
+    for shift in 0..64 {
+        values.push(rng.random::<u64>() >> shift);
+    }
+    values
+}
+
+#[test]
+fn test_soft_f32_arithmetic() {
+    let values = f32_values();
+    type Case = (&'static str, Vec<Emitter>, u32, fn(f32, f32) -> f32);
+    let cases: Vec<Case> = vec![
+        (
+            "add",
+            vec![InstructionSet::op_soft_f32_add],
+            InstructionSet::MSH_SOFT_F32_ADD,
+            |a, b| a + b,
+        ),
+        (
+            "sub",
+            vec![
+                InstructionSet::op_soft_f32_neg,
+                InstructionSet::op_soft_f32_add,
+            ],
+            InstructionSet::MSH_SOFT_F32_ADD,
+            |a, b| a - b,
+        ),
+        (
+            "mul",
+            vec![InstructionSet::op_soft_f32_mul],
+            InstructionSet::MSH_SOFT_F32_MUL,
+            |a, b| a * b,
+        ),
+        (
+            "div",
+            vec![InstructionSet::op_soft_f32_div],
+            InstructionSet::MSH_SOFT_F32_DIV,
+            |a, b| a / b,
+        ),
+        (
+            "copysign",
+            vec![InstructionSet::op_soft_f32_copysign],
+            InstructionSet::MSH_SOFT_F32_COPYSIGN,
+            f32::copysign,
+        ),
+    ];
+    for (op, emitters, msh, expected) in cases {
+        let is = emit(&emitters);
+        for a in &values {
+            for b in &values {
+                let inputs = [a.to_bits(), b.to_bits()];
+                let output = run_soft(is.clone(), &inputs, msh).unwrap();
+                assert_eq!(output.len(), 1);
+                assert_f32(op, &inputs, output[0], expected(*a, *b));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_soft_f32_min_max_and_compare() {
+    let values = f32_values();
+    let min_max = emit(&[InstructionSet::op_soft_f32_min_max]);
+    let compare = emit(&[InstructionSet::op_soft_f32_compare]);
+    for a in &values {
+        for b in &values {
+            for is_max in [false, true] {
+                let inputs = [a.to_bits(), b.to_bits(), is_max as u32];
+                let output = run_soft(
+                    min_max.clone(),
+                    &inputs,
+                    InstructionSet::MSH_SOFT_F32_MIN_MAX,
+                )
+                .unwrap();
+                assert_eq!(output.len(), 1);
+                let expected = wasm_min_max(*a, *b, is_max, f32::is_sign_negative);
+                assert_f32("min_max", &inputs, output[0], expected.unwrap_or(f32::NAN));
+            }
+            for (op, mask) in COMPARE_MASKS {
+                let inputs = [a.to_bits(), b.to_bits(), mask];
+                let output = run_soft(
+                    compare.clone(),
+                    &inputs,
+                    InstructionSet::MSH_SOFT_F32_COMPARE,
+                )
+                .unwrap();
+                let expected = (mask >> compare_bit(*a, *b)) & 1;
+                assert_eq!(output, vec![expected], "{op}({a:?}, {b:?})");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_soft_f32_unary() {
+    type Case = (&'static str, Vec<Emitter>, Option<u32>, u32, fn(f32) -> f32);
+    let cases: Vec<Case> = vec![
+        (
+            "abs",
+            vec![InstructionSet::op_soft_f32_abs],
+            None,
+            InstructionSet::MSH_SOFT_F32_ABS,
+            f32::abs,
+        ),
+        (
+            "neg",
+            vec![InstructionSet::op_soft_f32_neg],
+            None,
+            InstructionSet::MSH_SOFT_F32_NEG,
+            |a| -a,
+        ),
+        (
+            "sqrt",
+            vec![InstructionSet::op_soft_f32_sqrt],
+            None,
+            InstructionSet::MSH_SOFT_F32_SQRT,
+            f32::sqrt,
+        ),
+        (
+            "ceil",
+            vec![InstructionSet::op_soft_f32_round],
+            Some(0),
+            InstructionSet::MSH_SOFT_F32_ROUND,
+            f32::ceil,
+        ),
+        (
+            "floor",
+            vec![InstructionSet::op_soft_f32_round],
+            Some(1),
+            InstructionSet::MSH_SOFT_F32_ROUND,
+            f32::floor,
+        ),
+        (
+            "trunc",
+            vec![InstructionSet::op_soft_f32_round],
+            Some(2),
+            InstructionSet::MSH_SOFT_F32_ROUND,
+            f32::trunc,
+        ),
+        (
+            "nearest",
+            vec![InstructionSet::op_soft_f32_round],
+            Some(3),
+            InstructionSet::MSH_SOFT_F32_ROUND,
+            f32::round_ties_even,
+        ),
+    ];
+    let mut values = f32_values();
+    let mut rng = rand::rng();
+    for _ in 0..256 {
+        values.push(f32::from_bits(rng.random()));
+    }
+    for (op, emitters, mode, msh, expected) in cases {
+        let is = emit(&emitters);
+        for a in &values {
+            let inputs: Vec<u32> = [a.to_bits()].into_iter().chain(mode).collect();
+            let output = run_soft(is.clone(), &inputs, msh).unwrap();
+            assert_eq!(output.len(), 1);
+            assert_f32(op, &inputs, output[0], expected(*a));
+        }
+    }
+}
+
+#[test]
+fn test_soft_f32_conversions() {
+    let to_int = emit(&[InstructionSet::op_soft_f32_to_int]);
+    for a in f32_values() {
+        for flags in 0..8 {
+            let inputs = [a.to_bits(), flags];
+            let output = run_soft(to_int.clone(), &inputs, InstructionSet::MSH_SOFT_F32_TO_INT);
+            let expected = expected_to_int((a as f64).trunc(), flags);
+            // the high word of `i32` results is dropped by `i32.wrap_i64`
+            let output = output.map(|output| {
+                if flags & 2 != 0 {
+                    join(&output)
+                } else {
+                    output[0] as u64
+                }
+            });
+            assert_eq!(output, expected, "to_int({a:?}, {flags})");
+        }
+    }
+    let from_int = emit(&[InstructionSet::op_soft_f32_from_int]);
+    for x in int_values() {
+        for is_signed in [false, true] {
+            let inputs = [x as u32, (x >> 32) as u32, is_signed as u32];
+            let output = run_soft(
+                from_int.clone(),
+                &inputs,
+                InstructionSet::MSH_SOFT_F32_FROM_INT,
+            )
+            .unwrap();
+            let expected = if is_signed { x as i64 as f32 } else { x as f32 };
+            assert_f32("from_int", &inputs, output[0], expected);
+        }
+    }
+    let demote = emit(&[InstructionSet::op_soft_f32_demote_f64]);
+    for a in f64_values() {
+        let inputs = split(a.to_bits());
+        let output = run_soft(
+            demote.clone(),
+            &inputs,
+            InstructionSet::MSH_SOFT_F32_DEMOTE_F64,
+        )
+        .unwrap();
+        assert_f32("demote", &inputs, output[0], a as f32);
+    }
+    let promote = emit(&[InstructionSet::op_soft_f64_promote_f32]);
+    for a in f32_values() {
+        let inputs = [a.to_bits()];
+        let output = run_soft(
+            promote.clone(),
+            &inputs,
+            InstructionSet::MSH_SOFT_F64_PROMOTE_F32,
+        )
+        .unwrap();
+        assert_f64("promote", &inputs, join(&output), a as f64);
+    }
+}
+
+#[test]
+fn test_soft_f64_arithmetic() {
+    let values = f64_values();
+    type Case = (&'static str, Vec<Emitter>, u32, fn(f64, f64) -> f64);
+    let cases: Vec<Case> = vec![
+        (
+            "add",
+            vec![InstructionSet::op_soft_f64_add],
+            InstructionSet::MSH_SOFT_F64_ADD,
+            |a, b| a + b,
+        ),
+        (
+            "sub",
+            vec![
+                InstructionSet::op_soft_f64_neg,
+                InstructionSet::op_soft_f64_add,
+            ],
+            InstructionSet::MSH_SOFT_F64_ADD,
+            |a, b| a - b,
+        ),
+        (
+            "mul",
+            vec![InstructionSet::op_soft_f64_mul],
+            InstructionSet::MSH_SOFT_F64_MUL,
+            |a, b| a * b,
+        ),
+        (
+            "div",
+            vec![InstructionSet::op_soft_f64_div],
+            InstructionSet::MSH_SOFT_F64_DIV,
+            |a, b| a / b,
+        ),
+        (
+            "copysign",
+            vec![InstructionSet::op_soft_f64_copysign],
+            InstructionSet::MSH_SOFT_F64_COPYSIGN,
+            f64::copysign,
+        ),
+    ];
+    for (op, emitters, msh, expected) in cases {
+        let is = emit(&emitters);
+        for a in &values {
+            for b in &values {
+                let inputs = [split(a.to_bits()), split(b.to_bits())].concat();
+                let output = run_soft(is.clone(), &inputs, msh).unwrap();
+                assert_f64(op, &inputs, join(&output), expected(*a, *b));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_soft_f64_min_max_and_compare() {
+    let values = f64_values();
+    let min_max = emit(&[InstructionSet::op_soft_f64_min_max]);
+    let compare = emit(&[InstructionSet::op_soft_f64_compare]);
+    for a in &values {
+        for b in &values {
+            for is_max in [false, true] {
+                let inputs = [split(a.to_bits()), split(b.to_bits()), [is_max as u32, 0]].concat();
+                let inputs = &inputs[..5];
+                let output = run_soft(
+                    min_max.clone(),
+                    inputs,
+                    InstructionSet::MSH_SOFT_F64_MIN_MAX,
+                )
+                .unwrap();
+                let output = join(&output);
+                let expected = wasm_min_max(*a, *b, is_max, f64::is_sign_negative);
+                assert_f64("min_max", inputs, output, expected.unwrap_or(f64::NAN));
+            }
+            for (op, mask) in COMPARE_MASKS {
+                let inputs = [split(a.to_bits()), split(b.to_bits()), [mask, 0]].concat();
+                let output = run_soft(
+                    compare.clone(),
+                    &inputs[..5],
+                    InstructionSet::MSH_SOFT_F64_COMPARE,
+                )
+                .unwrap();
+                let expected = (mask >> compare_bit(*a, *b)) & 1;
+                assert_eq!(output, vec![expected], "{op}({a:?}, {b:?})");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_soft_f64_unary() {
+    type Case = (&'static str, Vec<Emitter>, Option<u32>, u32, fn(f64) -> f64);
+    let cases: Vec<Case> = vec![
+        (
+            "abs",
+            vec![InstructionSet::op_soft_f64_abs],
+            None,
+            InstructionSet::MSH_SOFT_F64_ABS,
+            f64::abs,
+        ),
+        (
+            "neg",
+            vec![InstructionSet::op_soft_f64_neg],
+            None,
+            InstructionSet::MSH_SOFT_F64_NEG,
+            |a| -a,
+        ),
+        (
+            "sqrt",
+            vec![InstructionSet::op_soft_f64_sqrt],
+            None,
+            InstructionSet::MSH_SOFT_F64_SQRT,
+            f64::sqrt,
+        ),
+        (
+            "ceil",
+            vec![InstructionSet::op_soft_f64_round],
+            Some(0),
+            InstructionSet::MSH_SOFT_F64_ROUND,
+            f64::ceil,
+        ),
+        (
+            "floor",
+            vec![InstructionSet::op_soft_f64_round],
+            Some(1),
+            InstructionSet::MSH_SOFT_F64_ROUND,
+            f64::floor,
+        ),
+        (
+            "trunc",
+            vec![InstructionSet::op_soft_f64_round],
+            Some(2),
+            InstructionSet::MSH_SOFT_F64_ROUND,
+            f64::trunc,
+        ),
+        (
+            "nearest",
+            vec![InstructionSet::op_soft_f64_round],
+            Some(3),
+            InstructionSet::MSH_SOFT_F64_ROUND,
+            f64::round_ties_even,
+        ),
+    ];
+    let mut values = f64_values();
+    let mut rng = rand::rng();
+    for _ in 0..256 {
+        values.push(f64::from_bits(rng.random()));
+    }
+    for (op, emitters, mode, msh, expected) in cases {
+        let is = emit(&emitters);
+        for a in &values {
+            let inputs: Vec<u32> = split(a.to_bits()).into_iter().chain(mode).collect();
+            let output = run_soft(is.clone(), &inputs, msh).unwrap();
+            assert_f64(op, &inputs, join(&output), expected(*a));
+        }
+    }
+}
+
+#[test]
+fn test_soft_f64_conversions() {
+    let to_int = emit(&[InstructionSet::op_soft_f64_to_int]);
+    for a in f64_values() {
+        for flags in 0..8 {
+            let inputs = [split(a.to_bits()), [flags, 0]].concat();
+            let output = run_soft(
+                to_int.clone(),
+                &inputs[..3],
+                InstructionSet::MSH_SOFT_F64_TO_INT,
+            );
+            let expected = expected_to_int(a.trunc(), flags);
+            let output = output.map(|output| {
+                if flags & 2 != 0 {
+                    join(&output)
+                } else {
+                    output[0] as u64
+                }
+            });
+            assert_eq!(output, expected, "to_int({a:?}, {flags})");
+        }
+    }
+    let from_int = emit(&[InstructionSet::op_soft_f64_from_int]);
+    for x in int_values() {
+        for is_signed in [false, true] {
+            let inputs = [x as u32, (x >> 32) as u32, is_signed as u32];
+            let output = run_soft(
+                from_int.clone(),
+                &inputs,
+                InstructionSet::MSH_SOFT_F64_FROM_INT,
+            )
+            .unwrap();
+            let expected = if is_signed { x as i64 as f64 } else { x as f64 };
+            assert_f64("from_int", &inputs, join(&output), expected);
+        }
+    }
+}
+
+#[test]
+fn test_soft_float_source_in_sync() {
+    let wasm = wat::parse_str(include_str!("../src/isa/soft_float.wat")).unwrap();
+    let mut import_linker = ImportLinker::default();
+    import_linker.insert_intrinsic(
+        ImportName::new("rwasm", "i32_mul64"),
+        1,
+        Intrinsic::Replace(vec![Opcode::I32Mul64]),
+        &[ValType::I32, ValType::I32],
+        &[ValType::I32, ValType::I32],
+    );
+    import_linker.insert_intrinsic(
+        ImportName::new("rwasm", "trap_integer_overflow"),
+        2,
+        Intrinsic::Replace(vec![Opcode::Trap(TrapCode::IntegerOverflow)]),
+        &[],
+        &[],
+    );
+    import_linker.insert_intrinsic(
+        ImportName::new("rwasm", "trap_bad_conversion"),
+        3,
+        Intrinsic::Replace(vec![Opcode::Trap(TrapCode::BadConversionToInteger)]),
+        &[],
+        &[],
+    );
+    let config = CompilationConfig::default()
+        .with_entrypoint_name("main".into())
+        .with_import_linker(Arc::new(import_linker))
+        .with_consume_fuel(false);
+    let (module, _) = RwasmModule::compile(config, &wasm).unwrap();
+    // the functions in the order of the source
+    let emitters: [(Emitter, u32); 20] = [
+        (
+            InstructionSet::op_soft_f32_add,
+            InstructionSet::MSH_SOFT_F32_ADD,
+        ),
+        (
+            InstructionSet::op_soft_f32_mul,
+            InstructionSet::MSH_SOFT_F32_MUL,
+        ),
+        (
+            InstructionSet::op_soft_f32_div,
+            InstructionSet::MSH_SOFT_F32_DIV,
+        ),
+        (
+            InstructionSet::op_soft_f32_sqrt,
+            InstructionSet::MSH_SOFT_F32_SQRT,
+        ),
+        (
+            InstructionSet::op_soft_f32_min_max,
+            InstructionSet::MSH_SOFT_F32_MIN_MAX,
+        ),
+        (
+            InstructionSet::op_soft_f32_compare,
+            InstructionSet::MSH_SOFT_F32_COMPARE,
+        ),
+        (
+            InstructionSet::op_soft_f32_round,
+            InstructionSet::MSH_SOFT_F32_ROUND,
+        ),
+        (
+            InstructionSet::op_soft_f32_to_int,
+            InstructionSet::MSH_SOFT_F32_TO_INT,
+        ),
+        (
+            InstructionSet::op_soft_f32_from_int,
+            InstructionSet::MSH_SOFT_F32_FROM_INT,
+        ),
+        (
+            InstructionSet::op_soft_f32_demote_f64,
+            InstructionSet::MSH_SOFT_F32_DEMOTE_F64,
+        ),
+        (
+            InstructionSet::op_soft_f64_promote_f32,
+            InstructionSet::MSH_SOFT_F64_PROMOTE_F32,
+        ),
+        (
+            InstructionSet::op_soft_f64_add,
+            InstructionSet::MSH_SOFT_F64_ADD,
+        ),
+        (
+            InstructionSet::op_soft_f64_mul,
+            InstructionSet::MSH_SOFT_F64_MUL,
+        ),
+        (
+            InstructionSet::op_soft_f64_div,
+            InstructionSet::MSH_SOFT_F64_DIV,
+        ),
+        (
+            InstructionSet::op_soft_f64_sqrt,
+            InstructionSet::MSH_SOFT_F64_SQRT,
+        ),
+        (
+            InstructionSet::op_soft_f64_min_max,
+            InstructionSet::MSH_SOFT_F64_MIN_MAX,
+        ),
+        (
+            InstructionSet::op_soft_f64_compare,
+            InstructionSet::MSH_SOFT_F64_COMPARE,
+        ),
+        (
+            InstructionSet::op_soft_f64_round,
+            InstructionSet::MSH_SOFT_F64_ROUND,
+        ),
+        (
+            InstructionSet::op_soft_f64_to_int,
+            InstructionSet::MSH_SOFT_F64_TO_INT,
+        ),
+        (
+            InstructionSet::op_soft_f64_from_int,
+            InstructionSet::MSH_SOFT_F64_FROM_INT,
+        ),
+    ];
+    let code = &module.code_section;
+    let mut starts = code
+        .iter()
+        .enumerate()
+        .filter(|(_, opcode)| matches!(opcode, Opcode::SignatureCheck(_)))
+        .map(|(pc, _)| pc)
+        .collect::<Vec<_>>();
+    starts.push(code.len());
+    // skip the import trampolines and the entrypoint
+    let functions = starts.windows(2).skip(4).collect::<Vec<_>>();
+    assert_eq!(functions.len(), emitters.len());
+    for (i, (range, (emitter, msh))) in functions.into_iter().zip(emitters).enumerate() {
+        let (start, end) = (range[0], range[1]);
+        assert_eq!(code[start + 1], Opcode::StackCheck(msh), "function #{i}");
+        assert_eq!(code[end - 1], Opcode::Return);
+        assert_eq!(
+            &code[start + 2..end - 1],
+            &emit(&[emitter])[..],
+            "function #{i}"
+        );
+    }
+}
+
+fn run_float_wat(config: CompilationConfig, body: &str, params: &[Value], result: Value) -> Value {
+    let wat = format!(
+        r#"
+(module
+  (memory 0)
+  (func (export "main") (param i64 i64) (result i64) (local $x f64) (local $n i64)
+    (drop (memory.grow (i32.const 1)))
+    {body}
+  )
+)
+"#
+    );
+    let wasm = wat::parse_str(&wat).unwrap();
+    let config = config
+        .with_entrypoint_name("main".into())
+        .with_allow_malformed_entrypoint_func_type(true);
+    let (module, _) = RwasmModule::compile(config, &wasm).unwrap();
+    module.verify_with_policy(FuelPolicy::Required).unwrap();
+    let mut store = RwasmStore::<()>::default();
+    let mut results = [result];
+    ExecutionEngine::default()
+        .execute(&mut store, &module, params, &mut results)
+        .unwrap();
+    results[0].clone()
+}
+
+#[test]
+fn test_float_instructions_end_to_end() {
+    let f64_binary = [
+        ("f64.add", (|a, b| a + b) as fn(f64, f64) -> f64),
+        ("f64.sub", |a, b| a - b),
+        ("f64.mul", |a, b| a * b),
+        ("f64.div", |a, b| a / b),
+        ("f64.copysign", f64::copysign),
+    ];
+    let f32_binary = [
+        ("f32.add", (|a, b| a + b) as fn(f32, f32) -> f32),
+        ("f32.sub", |a, b| a - b),
+        ("f32.mul", |a, b| a * b),
+        ("f32.div", |a, b| a / b),
+    ];
+    let pairs = [(1.5, -0.25), (1e300, 3.0), (-7.0, 0.1), (2.0, 1e-310)];
+    for code_snippets in [true, false] {
+        let config = CompilationConfig::default().with_code_snippets(code_snippets);
+        for (op, expected) in f64_binary {
+            let body = format!(
+                "local.get 0 f64.reinterpret_i64 local.get 1 f64.reinterpret_i64 {op} i64.reinterpret_f64"
+            );
+            for (a, b) in pairs {
+                let params = [
+                    Value::I64(f64::to_bits(a) as i64),
+                    Value::I64(f64::to_bits(b) as i64),
+                ];
+                let result = run_float_wat(config.clone(), &body, &params, Value::I64(0));
+                assert_eq!(
+                    result,
+                    Value::I64(expected(a, b).to_bits() as i64),
+                    "{op}({a}, {b})"
+                );
+            }
+        }
+        for (op, expected) in f32_binary {
+            // the operands are stored as `f32` and loaded back
+            let body = format!(
+                "i32.const 0 local.get 0 f64.convert_i64_s f32.demote_f64 f32.store
+                 i32.const 0 f32.load local.get 1 f32.convert_i64_u {op}
+                 f32.sqrt f64.promote_f32 f64.nearest i64.trunc_sat_f64_s"
+            );
+            for (a, b) in [(3i64, 7u64), (-1000, 3), (123456789, 1 << 40)] {
+                let params = [Value::I64(a), Value::I64(b as i64)];
+                let result = run_float_wat(config.clone(), &body, &params, Value::I64(0));
+                let expected =
+                    (expected(a as f64 as f32, b as f32).sqrt() as f64).round_ties_even() as i64;
+                assert_eq!(result, Value::I64(expected), "{op}({a}, {b})");
+            }
+        }
+        // a float loop with a comparison: the number of halvings until `x < 1`
+        let body = "(local.set $x (f64.convert_i64_u (local.get 0)))
+            (block $done
+              (loop $continue
+                (br_if $done (f64.lt (local.get $x) (f64.const 1)))
+                (local.set $x (f64.mul (local.get $x) (f64.const 0.5)))
+                (local.set $n (i64.add (local.get $n) (i64.const 1)))
+                (br $continue)))
+            (local.get $n)";
+        for (x, expected) in [(0, 0), (1, 1), (1000, 10), (u64::MAX, 65)] {
+            let params = [Value::I64(x as i64), Value::I64(0)];
+            let result = run_float_wat(config.clone(), body, &params, Value::I64(0));
+            assert_eq!(result, Value::I64(expected), "halvings({x})");
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use fib_example::FIB_WASM;
 use rwasm::{
     always_failing_syscall_handler, wasmtime::compile_wasmtime_module, CompilationConfig,
-    ImportLinker, StrategyDefinition, Value,
+    CompilationError, ImportLinker, StrategyDefinition, Value,
 };
 use std::sync::Arc;
 use wasmtime::{Engine, Instance, Module, Store, TypedFunc};
@@ -57,6 +57,22 @@ fn test_wasmtime_disabled_f64_div() {
     assert_eq!(trap, wasmtime::Trap::DisabledOpcode);
 }
 
+#[test]
+fn test_wasmtime_compilation_error_is_returned() {
+    let malformed_binary = b"\0asm\x01\0\0\0\xff";
+    for module_caching_key in [None, Some([0xff; 32])] {
+        let result = StrategyDefinition::new_as_wasmtime(
+            CompilationConfig::default(),
+            malformed_binary,
+            module_caching_key,
+        );
+        assert!(matches!(
+            result,
+            Err(CompilationError::WasmtimeCompilationFailed(_))
+        ));
+    }
+}
+
 #[test]
 fn test_wasmtime_f32_const() {
     let wat = r#"